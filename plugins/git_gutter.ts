@@ -247,6 +247,7 @@ async function updateGitGutter(bufferId: number): Promise<void> {
       // Clear indicators for non-tracked files
       editor.debug("Git Gutter: file not tracked by git");
       editor.clearLineIndicators(bufferId, NAMESPACE);
+      editor.clearScrollbarMarks(bufferId, NAMESPACE);
       state.hunks = [];
       return;
     }
@@ -264,6 +265,7 @@ async function updateGitGutter(bufferId: number): Promise<void> {
 
     // Clear existing indicators
     editor.clearLineIndicators(bufferId, NAMESPACE);
+    editor.clearScrollbarMarks(bufferId, NAMESPACE);
 
     // Apply new indicators
     for (const hunk of hunks) {
@@ -284,6 +286,16 @@ async function updateGitGutter(bufferId: number): Promise<void> {
           color[2],
           PRIORITY
         );
+        editor.setScrollbarMark(
+          bufferId,
+          line,
+          line,
+          NAMESPACE,
+          color[0],
+          color[1],
+          color[2],
+          PRIORITY
+        );
       } else {
         // Added/modified indicators show on each affected line
         for (let i = 0; i < hunk.lineCount; i++) {
@@ -300,6 +312,19 @@ async function updateGitGutter(bufferId: number): Promise<void> {
             PRIORITY
           );
         }
+        if (hunk.lineCount > 0) {
+          const firstLine = hunk.startLine - 1;
+          editor.setScrollbarMark(
+            bufferId,
+            firstLine,
+            firstLine + hunk.lineCount - 1,
+            NAMESPACE,
+            color[0],
+            color[1],
+            color[2],
+            PRIORITY
+          );
+        }
       }
     }
 
@@ -707,6 +707,26 @@ interface EditorAPI {
    * @returns true if indicators were cleared
    */
   clearLineIndicators(buffer_id: number, namespace: string): boolean;
+  /**
+   * Mark a range of lines on the scrollbar track
+   * @param buffer_id - The buffer ID
+   * @param start_line - First line of the range (0-indexed)
+   * @param end_line - Last line of the range (0-indexed, inclusive)
+   * @param namespace - Namespace for grouping (e.g., "git-gutter")
+   * @param r - Red color component (0-255)
+   * @param g - Green color component (0-255)
+   * @param b - Blue color component (0-255)
+   * @param priority - Priority when several marks share a track row (higher wins)
+   * @returns true if the mark was set
+   */
+  setScrollbarMark(buffer_id: number, start_line: number, end_line: number, namespace: string, r: number, g: number, b: number, priority: number): boolean;
+  /**
+   * Clear all scrollbar marks for a specific namespace
+   * @param buffer_id - The buffer ID
+   * @param namespace - Namespace to clear (e.g., "git-gutter")
+   * @returns true if marks were cleared
+   */
+  clearScrollbarMarks(buffer_id: number, namespace: string): boolean;
   /**
    * Submit a transformed view stream for a viewport
    * @param buffer_id - Buffer to apply the transform to
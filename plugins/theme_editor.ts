@@ -1337,6 +1337,14 @@ function createDefaultTheme(): Record<string, unknown> {
       current_line_bg: [40, 40, 40],
       line_number_fg: [100, 100, 100],
       line_number_bg: [30, 30, 30],
+      ruler_bg: [50, 50, 50],
+      sticky_scroll_bg: [37, 37, 42],
+      indent_guide_fg: [60, 60, 60],
+      indent_guide_active_fg: [120, 120, 120],
+      bracket_pair_1_fg: [255, 215, 0],
+      bracket_pair_2_fg: [218, 112, 214],
+      bracket_pair_3_fg: [23, 159, 255],
+      fold_placeholder_fg: [128, 128, 128],
     },
     ui: {
       tab_active_fg: "Yellow",
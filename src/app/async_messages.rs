@@ -7,18 +7,26 @@
 //! - File explorer events
 //! - Plugin events
 
+use crate::input::keybindings::Action;
 use crate::model::buffer::Buffer;
 use crate::model::event::BufferId;
+use crate::primitives::syntax_selection::SelectionChain;
 use crate::services::async_bridge::{LspMessageType, LspProgressValue, LspServerStatus};
 use crate::services::lsp::manager::detect_language;
 use crate::state::{SemanticTokenSpan, SemanticTokenStore};
+use crate::view::document_symbols::symbols_from_lsp;
 use crate::view::file_tree::{FileTreeView, NodeId};
-use lsp_types::{Diagnostic, InlayHint, SemanticToken, SemanticTokensLegend, SemanticTokensResult};
+use crate::view::folding::FoldRange;
+use lsp_types::{
+    Diagnostic, DocumentSymbolResponse, FoldingRange, InlayHint, SelectionRange, SemanticToken,
+    SemanticTokensLegend, SemanticTokensResult,
+};
 use rust_i18n::t;
 use serde_json::Value;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
+use super::lsp_requests::PendingSelectionRange;
 use super::types::{LspMessageEntry, LspProgressInfo};
 use super::Editor;
 
@@ -268,6 +276,133 @@ impl Editor {
         }
     }
 
+    /// Handle LSP folding range response
+    pub(super) fn handle_lsp_folding_ranges(
+        &mut self,
+        request_id: u64,
+        uri: String,
+        ranges: Vec<FoldingRange>,
+    ) {
+        let Some((buffer_id, target_version)) =
+            self.pending_folding_range_requests.remove(&request_id)
+        else {
+            tracing::debug!(
+                "Folding range response {} for {} without pending entry",
+                request_id,
+                uri
+            );
+            return;
+        };
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        // Ranges for an older version would fold the wrong lines
+        if state.buffer.version() != target_version {
+            return;
+        }
+        let ranges = ranges
+            .iter()
+            .map(|r| FoldRange::new(r.start_line as usize, r.end_line as usize))
+            .filter(|r| r.end_line > r.start_line)
+            .collect();
+        state.folds.set_lsp_ranges(target_version, ranges);
+    }
+
+    /// Handle LSP document symbol response
+    pub(super) fn handle_lsp_document_symbols(
+        &mut self,
+        request_id: u64,
+        uri: String,
+        symbols: Option<DocumentSymbolResponse>,
+    ) {
+        let Some((buffer_id, target_version)) =
+            self.pending_document_symbol_requests.remove(&request_id)
+        else {
+            tracing::debug!(
+                "Document symbol response {} for {} without pending entry",
+                request_id,
+                uri
+            );
+            return;
+        };
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        // Positions of an older version would point at the wrong bytes; the
+        // next render asks again
+        if state.buffer.version() != target_version {
+            return;
+        }
+        let symbols = symbols.map(|symbols| {
+            let buffer = &state.buffer;
+            symbols_from_lsp(symbols, |position| {
+                buffer.lsp_position_to_byte(position.line as usize, position.character as usize)
+            })
+        });
+        state.symbols.set_lsp_symbols(target_version, symbols);
+    }
+
+    /// Handle LSP selection range response and run the expansion it was requested for
+    pub(super) fn handle_lsp_selection_ranges(
+        &mut self,
+        request_id: u64,
+        uri: String,
+        ranges: Vec<SelectionRange>,
+    ) {
+        let Some(PendingSelectionRange {
+            buffer_id,
+            version: target_version,
+            origins,
+            ..
+        }) = self.pending_selection_range_requests.remove(&request_id)
+        else {
+            tracing::debug!(
+                "Selection range response {} for {} without pending entry",
+                request_id,
+                uri
+            );
+            return;
+        };
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        if state.buffer.version() != target_version {
+            return;
+        }
+        // Positions the server did not answer get empty chains so the syntax
+        // tree is used for them instead of asking again
+        let mut responses = ranges.into_iter();
+        let chains = origins
+            .into_iter()
+            .map(|origin| {
+                let mut ranges = Vec::new();
+                let mut next = responses.next().map(Box::new);
+                while let Some(range) = next {
+                    let start = state.buffer.lsp_position_to_byte(
+                        range.range.start.line as usize,
+                        range.range.start.character as usize,
+                    );
+                    let end = state.buffer.lsp_position_to_byte(
+                        range.range.end.line as usize,
+                        range.range.end.character as usize,
+                    );
+                    ranges.push(start..end);
+                    next = range.parent;
+                }
+                SelectionChain { origin, ranges }
+            })
+            .collect();
+        state
+            .selection_expansion
+            .set_lsp_chains(target_version, chains);
+
+        if self.active_buffer() == buffer_id {
+            if let Err(e) = self.apply_action_as_events(Action::ExpandSelection) {
+                tracing::warn!("Failed to expand selection: {}", e);
+            }
+        }
+    }
+
     /// Handle LSP server quiescent notification (rust-analyzer project fully loaded)
     pub(super) fn handle_lsp_server_quiescent(&mut self, language: String) {
         tracing::info!(
@@ -435,6 +570,19 @@ impl Editor {
         // Update warning domain for LSP status indicator
         self.update_lsp_warning_domain();
 
+        // A stopped server never answers its pending selection range requests
+        if matches!(status, LspServerStatus::Error | LspServerStatus::Shutdown) {
+            let request_ids: Vec<u64> = self
+                .pending_selection_range_requests
+                .iter()
+                .filter(|(_, pending)| pending.language == language)
+                .map(|(request_id, _)| *request_id)
+                .collect();
+            for request_id in request_ids {
+                self.drop_selection_range_request(request_id);
+            }
+        }
+
         // Handle server crash - trigger auto-restart
         if status == LspServerStatus::Error {
             let was_running = old_status
@@ -64,8 +64,22 @@ impl Editor {
             .map(|s| s.buffer.is_binary())
             .unwrap_or(false);
 
+        let decompress_error = self
+            .buffers
+            .get_mut(&buffer_id)
+            .and_then(|s| s.buffer.take_decompress_error());
+
         // Show appropriate status message for binary vs regular files
-        if is_binary {
+        if let Some(error) = decompress_error {
+            self.status_message = Some(
+                t!(
+                    "buffer.opened_not_decompressed",
+                    name = display_name,
+                    error = error
+                )
+                .to_string(),
+            );
+        } else if is_binary {
             self.status_message = Some(t!("buffer.opened_binary", name = display_name).to_string());
         } else {
             self.status_message = Some(t!("buffer.opened", name = display_name).to_string());
@@ -170,6 +184,10 @@ impl Editor {
             );
             // Set the file path so saving will create the file
             new_state.buffer.set_file_path(path.to_path_buf());
+            // A new `notes.gz` is written compressed, like an existing one
+            new_state
+                .buffer
+                .set_compression(crate::model::compression::Compression::from_extension(path));
             new_state
         };
         // Note: line_wrap_enabled is set on SplitViewState.viewport when the split is created
@@ -182,19 +200,29 @@ impl Editor {
             tracing::info!("Detected binary file: {}", path.display());
         }
 
-        // Set show_whitespace_tabs, use_tabs, and tab_size based on language config
-        // with fallback to global editor config for tab_size
-        if let Some(language) = detect_language(path, &self.config.languages) {
-            if let Some(lang_config) = self.config.languages.get(&language) {
-                state.show_whitespace_tabs = lang_config.show_whitespace_tabs;
-                state.use_tabs = lang_config.use_tabs;
-                // Use language-specific tab_size if set, otherwise fall back to global
-                state.tab_size = lang_config.tab_size.unwrap_or(self.config.editor.tab_size);
-            } else {
-                state.tab_size = self.config.editor.tab_size;
+        // Set show_whitespace_tabs, use_tabs, tab_size, rulers, indent guides and bracket
+        // colorization based on language config and .editorconfig, with fallback to
+        // global editor config
+        let buffer_config = self.buffer_config_for_path(path);
+        state.show_whitespace_tabs = buffer_config.show_whitespace_tabs;
+        state.use_tabs = buffer_config.use_tabs;
+        state.tab_size = buffer_config.tab_size;
+        state.rulers = buffer_config.rulers.clone();
+        state.indent_guides = buffer_config.indent_guides;
+        state.bracket_pair_colorization = buffer_config.bracket_pair_colorization;
+
+        // A new file gets the configured line ending and encoding
+        if !file_exists {
+            state
+                .buffer
+                .set_default_line_ending(buffer_config.line_ending.to_line_ending());
+            if let Some(encoding) = buffer_config
+                .charset
+                .as_deref()
+                .and_then(crate::model::encoding::Encoding::from_label)
+            {
+                state.buffer.set_default_encoding(encoding);
             }
-        } else {
-            state.tab_size = self.config.editor.tab_size;
         }
 
         // Apply line_numbers default from config
@@ -205,6 +233,7 @@ impl Editor {
         self.buffers.insert(buffer_id, state);
         self.event_logs
             .insert(buffer_id, crate::model::event::EventLog::new());
+        self.restore_undo_history(buffer_id, path);
 
         // Create metadata for this buffer
         let mut metadata =
@@ -249,6 +278,9 @@ impl Editor {
         // Track file for auto-revert and conflict detection
         self.watch_file(path);
 
+        // Scan large files for exact line numbers in the background
+        self.start_line_indexing(buffer_id);
+
         // Fire AfterFileOpen hook for plugins
         self.plugin_manager.run_hook(
             "after_file_open",
@@ -286,6 +318,7 @@ impl Editor {
             editor_state.cursors.primary_mut().position = cursor_pos;
             editor_state.cursors.primary_mut().anchor =
                 file_state.cursor.anchor.map(|a| a.min(max_pos));
+            super::folding::restore_folds(editor_state, &file_state.folds);
         }
 
         // Apply viewport (scroll) state to SplitViewState
@@ -344,6 +377,11 @@ impl Editor {
                 top_view_line_offset: view_state.viewport.top_view_line_offset,
                 left_column: view_state.viewport.left_column,
             },
+            folds: self
+                .buffers
+                .get(&buffer_id)
+                .map(|state| state.folds.collapsed_ranges(&state.buffer))
+                .unwrap_or_default(),
         };
 
         // Save to disk
@@ -416,6 +454,39 @@ impl Editor {
         }
     }
 
+    /// Move the cursor of a split to `position` and scroll it into view,
+    /// recording the jump in the position history
+    pub(super) fn move_cursor_and_reveal(&mut self, split_id: SplitId, position: usize) {
+        let Some(buffer_id) = self.split_manager.get_buffer_id(split_id) else {
+            return;
+        };
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let primary = state.cursors.primary();
+        let event = Event::MoveCursor {
+            cursor_id: state.cursors.primary_id(),
+            old_position: primary.position,
+            new_position: position.min(state.buffer.len()),
+            old_anchor: primary.anchor,
+            new_anchor: None,
+            old_sticky_column: primary.sticky_column,
+            new_sticky_column: 0,
+        };
+        if let Some(event_log) = self.event_logs.get_mut(&buffer_id) {
+            event_log.append(event.clone());
+        }
+        state.apply(&event);
+
+        if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+            view_state.viewport.clear_skip_ensure_visible();
+        }
+        if !self.in_navigation {
+            self.position_history
+                .record_movement(buffer_id, position, None);
+        }
+    }
+
     /// Create a new empty buffer
     pub fn new_buffer(&mut self) -> BufferId {
         // Save current position before switching to new buffer
@@ -441,6 +512,7 @@ impl Editor {
         state
             .margins
             .set_line_numbers(self.config.editor.line_numbers);
+        state.indent_guides = self.config.editor.indent_guides;
         // Set default line ending for new buffers from config
         state
             .buffer
@@ -983,6 +1055,14 @@ impl Editor {
         // Save file state before closing (for per-file session persistence)
         self.save_file_state_on_close(id);
 
+        // Keep the undo history for the next time this file is opened
+        self.persist_undo_history(id);
+        self.on_undo_tree_buffer_closed(id);
+        self.on_hierarchy_buffer_closed(id);
+        self.on_project_search_buffer_closed(id);
+        self.on_file_history_buffer_closed(id);
+        self.followed_buffers.remove(&id);
+
         // If closing a terminal buffer, clean up terminal-related data structures
         if let Some(terminal_id) = self.terminal_buffers.remove(&id) {
             // Close the terminal process
@@ -0,0 +1,554 @@
+//! Code lenses.
+//!
+//! The lenses of the buffers on screen are requested from their language
+//! server whenever a buffer changes (`textDocument/codeLens`) and shown as
+//! virtual lines above the lines they belong to. Lenses the server answers
+//! without a command are resolved once they scroll into view
+//! (`codeLens/resolve`). Clicking a lens, or picking it from the code lens
+//! picker, runs its command: a few well-known commands are handled by the
+//! editor, the others are sent back to the server
+//! (`workspace/executeCommand`).
+
+use lsp_types::{CodeLens, Command, Location, Position};
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use rust_i18n::t;
+
+use super::Editor;
+use crate::input::commands::Suggestion;
+use crate::model::event::{BufferId, SplitId};
+use crate::services::lsp::manager::detect_language;
+use crate::state::EditorState;
+use crate::view::code_lens;
+use crate::view::prompt::{Prompt, PromptType};
+use crate::view::virtual_text::{VirtualTextNamespace, VirtualTextPosition};
+
+/// Client-side commands showing the locations of a lens, with arguments
+/// `[uri, position, locations]`
+const SHOW_REFERENCES_COMMANDS: &[&str] = &[
+    "rust-analyzer.showReferences",
+    "editor.action.showReferences",
+];
+
+/// rust-analyzer command running a test, binary or example in a terminal
+const RUN_SINGLE_COMMAND: &str = "rust-analyzer.runSingle";
+
+/// Client-side commands that need a debugger
+const DEBUG_COMMANDS: &[&str] = &["rust-analyzer.debugSingle"];
+
+/// Replace the virtual lines of a buffer's lenses
+fn apply_code_lenses_to_state(state: &mut EditorState, style: Style) {
+    let namespace = VirtualTextNamespace::from_string(code_lens::NAMESPACE.to_string());
+    state
+        .virtual_texts
+        .clear_namespace(&mut state.marker_list, &namespace);
+
+    for lens_line in state.code_lenses.lines() {
+        let position = state.buffer.lsp_position_to_byte(lens_line.line, 0);
+        // Line the lenses up with the code they belong to
+        let indent: usize = state
+            .buffer
+            .get_line(lens_line.line)
+            .map(|line| {
+                line.iter()
+                    .take_while(|&&b| b == b' ' || b == b'\t')
+                    .map(|&b| if b == b'\t' { state.tab_size } else { 1 })
+                    .sum()
+            })
+            .unwrap_or(0);
+        state.virtual_texts.add_line(
+            &mut state.marker_list,
+            position,
+            format!("{}{}", " ".repeat(indent), lens_line.text),
+            style,
+            VirtualTextPosition::LineAbove,
+            namespace.clone(),
+            0,
+        );
+    }
+}
+
+/// Quote an argument for a POSIX shell command line
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Shell command line of a rust-analyzer runnable
+fn runnable_command_line(runnable: &serde_json::Value) -> Option<String> {
+    let args = runnable.get("args")?;
+    let strings = |key: &str| -> Vec<String> {
+        args.get(key)
+            .and_then(|value| value.as_array())
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|value| value.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut words = match runnable.get("kind").and_then(|kind| kind.as_str()) {
+        Some("cargo") => {
+            let program = args
+                .get("overrideCargo")
+                .and_then(|program| program.as_str())
+                .unwrap_or("cargo");
+            let mut words = vec![program.to_string()];
+            words.extend(strings("cargoArgs"));
+            words.extend(strings("cargoExtraArgs"));
+            let executable_args = strings("executableArgs");
+            if !executable_args.is_empty() {
+                words.push("--".to_string());
+                words.extend(executable_args);
+            }
+            words
+        }
+        Some("shell") => {
+            let mut words = vec![args.get("program")?.as_str()?.to_string()];
+            words.extend(strings("args"));
+            words
+        }
+        _ => return None,
+    };
+    words.iter_mut().for_each(|word| *word = shell_quote(word));
+
+    let command_line = words.join(" ");
+    match args
+        .get("cwd")
+        .or_else(|| args.get("workspaceRoot"))
+        .and_then(|cwd| cwd.as_str())
+    {
+        Some(cwd) => Some(format!("cd {} && {}", shell_quote(cwd), command_line)),
+        None => Some(command_line),
+    }
+}
+
+/// Identifier starting at an LSP position of a buffer
+fn word_at(state: &EditorState, position: Position) -> String {
+    let start = state
+        .buffer
+        .lsp_position_to_byte(position.line as usize, position.character as usize);
+    let line_start = state.buffer.lsp_position_to_byte(position.line as usize, 0);
+    let line = state
+        .buffer
+        .get_line(position.line as usize)
+        .unwrap_or_default();
+    let text = String::from_utf8_lossy(
+        line.get(start.saturating_sub(line_start)..)
+            .unwrap_or_default(),
+    );
+    text.chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect()
+}
+
+impl Editor {
+    /// Request the code lenses of a buffer if its server provides them and
+    /// the current lenses are out of date.
+    pub(crate) fn maybe_request_code_lenses(&mut self, buffer_id: BufferId) {
+        if self
+            .pending_code_lens_requests
+            .values()
+            .any(|(id, _)| *id == buffer_id)
+        {
+            return;
+        }
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        let buffer_version = state.buffer.version();
+        if state.code_lenses.version() == Some(buffer_version) {
+            return;
+        }
+
+        let Some(metadata) = self.buffer_metadata.get(&buffer_id) else {
+            return;
+        };
+        if !metadata.lsp_enabled {
+            return;
+        }
+        let Some(uri) = metadata.file_uri().cloned() else {
+            return;
+        };
+        let Some(path) = metadata.file_path() else {
+            return;
+        };
+        let Some(language) = detect_language(path, &self.config.languages) else {
+            return;
+        };
+
+        // Only ask servers that are already running
+        let Some(lsp) = self.lsp.as_mut() else {
+            return;
+        };
+        if !lsp.code_lens_supported(&language) {
+            return;
+        }
+        let Some(handle) = lsp.get_handle_mut(&language) else {
+            return;
+        };
+
+        let request_id = self.next_lsp_request_id;
+        self.next_lsp_request_id += 1;
+
+        match handle.code_lenses(request_id, uri) {
+            Ok(_) => {
+                self.pending_code_lens_requests
+                    .insert(request_id, (buffer_id, buffer_version));
+            }
+            Err(e) => {
+                tracing::debug!("Failed to request code lenses: {}", e);
+            }
+        }
+    }
+
+    /// Resolve the lenses of a buffer that are within a view, starting at
+    /// `top_byte` and `height` rows high
+    pub(crate) fn maybe_resolve_code_lenses(
+        &mut self,
+        buffer_id: BufferId,
+        top_byte: usize,
+        height: usize,
+    ) {
+        let Some(language) = self
+            .buffer_metadata
+            .get(&buffer_id)
+            .and_then(|metadata| metadata.file_path())
+            .and_then(|path| detect_language(path, &self.config.languages))
+        else {
+            return;
+        };
+        if !self
+            .lsp
+            .as_ref()
+            .is_some_and(|lsp| lsp.code_lens_resolve_supported(&language))
+        {
+            return;
+        }
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let Some(version) = state.code_lenses.version() else {
+            return;
+        };
+        // Collapsed folds show more lines than rows: look a view further
+        let top_line = state.buffer.get_line_number(top_byte);
+        let indices = state
+            .code_lenses
+            .take_unresolved(top_line..top_line + height * 2);
+        if indices.is_empty() {
+            return;
+        }
+        let lenses: Vec<(usize, CodeLens)> = indices
+            .into_iter()
+            .map(|index| (index, state.code_lenses.lenses()[index].clone()))
+            .collect();
+
+        let Some(handle) = self
+            .lsp
+            .as_mut()
+            .and_then(|lsp| lsp.get_handle_mut(&language))
+        else {
+            return;
+        };
+        for (index, lens) in lenses {
+            let request_id = self.next_lsp_request_id;
+            self.next_lsp_request_id += 1;
+            match handle.resolve_code_lens(request_id, lens) {
+                Ok(_) => {
+                    self.pending_code_lens_resolves
+                        .insert(request_id, (buffer_id, version, index));
+                }
+                Err(e) => {
+                    tracing::debug!("Failed to resolve code lens: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Handle LSP code lens response
+    pub(super) fn handle_lsp_code_lenses(
+        &mut self,
+        request_id: u64,
+        uri: String,
+        lenses: Option<Vec<CodeLens>>,
+    ) {
+        let Some((buffer_id, target_version)) = self.pending_code_lens_requests.remove(&request_id)
+        else {
+            tracing::debug!(
+                "Code lens response {} for {} without pending entry",
+                request_id,
+                uri
+            );
+            return;
+        };
+        let style = Style::default().fg(self.theme.line_number_fg);
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        // Lines of an older version would put the lenses above the wrong code
+        if state.buffer.version() != target_version {
+            return;
+        }
+        // Keep showing the previous lenses if the server failed, e.g. because
+        // the document changed while it was computing them
+        let lenses = lenses.unwrap_or_else(|| state.code_lenses.lenses().to_vec());
+        state.code_lenses.set(target_version, lenses);
+        apply_code_lenses_to_state(state, style);
+    }
+
+    /// Handle LSP code lens resolve response
+    pub(super) fn handle_code_lens_resolved(&mut self, request_id: u64, lens: Option<CodeLens>) {
+        let Some((buffer_id, version, index)) = self.pending_code_lens_resolves.remove(&request_id)
+        else {
+            return;
+        };
+        let style = Style::default().fg(self.theme.line_number_fg);
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        if state.code_lenses.resolve(version, index, lens) {
+            apply_code_lenses_to_state(state, style);
+        }
+    }
+
+    /// Request the lenses of a language's buffers again, as its server asked
+    pub(super) fn handle_code_lens_refresh(&mut self, language: &str) {
+        for (buffer_id, metadata) in &self.buffer_metadata {
+            let buffer_language = metadata
+                .file_path()
+                .and_then(|path| detect_language(path, &self.config.languages));
+            if buffer_language.as_deref() != Some(language) {
+                continue;
+            }
+            if let Some(state) = self.buffers.get_mut(buffer_id) {
+                state.code_lenses.invalidate();
+            }
+        }
+    }
+
+    /// Open a picker listing the resolved lenses of the active buffer, with
+    /// the first lens at or below the cursor selected
+    pub fn show_code_lens_picker(&mut self) {
+        let buffer_id = self.active_buffer();
+        let state = self.active_state();
+        let cursor_line = state
+            .buffer
+            .get_line_number(state.cursors.primary().position);
+
+        let mut commands = Vec::new();
+        let mut suggestions = Vec::new();
+        let mut selected = None;
+        for lens in state.code_lenses.lenses() {
+            let Some(command) = &lens.command else {
+                continue;
+            };
+            let line = lens.range.start.line as usize;
+            if selected.is_none() && line >= cursor_line {
+                selected = Some(commands.len());
+            }
+            let code = state
+                .buffer
+                .get_line(line)
+                .map(|code| String::from_utf8_lossy(&code).trim().to_string())
+                .unwrap_or_default();
+            suggestions.push(Suggestion {
+                text: command.title.clone(),
+                description: Some(format!("{}: {}", line + 1, code)),
+                value: Some(commands.len().to_string()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            });
+            commands.push(command.clone());
+        }
+
+        if commands.is_empty() {
+            self.set_status_message(t!("lsp.no_code_lenses").to_string());
+            return;
+        }
+
+        let mut prompt = Prompt::with_suggestions(
+            t!("lsp.pick_code_lens").to_string(),
+            PromptType::CodeLens,
+            suggestions,
+        );
+        prompt.selected_suggestion = Some(selected.unwrap_or(0));
+        self.prompt = Some(prompt);
+        self.code_lens_picker = Some((buffer_id, commands));
+    }
+
+    /// Run the lens picked in the code lens picker
+    pub(crate) fn run_code_lens_from_prompt(&mut self, index: &str) {
+        let Some((buffer_id, commands)) = self.code_lens_picker.take() else {
+            return;
+        };
+        if let Some(command) = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| commands.into_iter().nth(index))
+        {
+            self.run_code_lens_command(buffer_id, command);
+        }
+    }
+
+    /// The command of the lens drawn at a screen position of a split, if any
+    pub(super) fn code_lens_at(
+        &self,
+        split_id: SplitId,
+        buffer_id: BufferId,
+        col: u16,
+        row: u16,
+        content_rect: Rect,
+    ) -> Option<Command> {
+        let mappings = self.cached_layout.view_line_mappings.get(&split_id)?;
+        let visual_row = row.checked_sub(content_rect.y)? as usize;
+        let mapping = mappings.get(visual_row)?;
+        if mapping.char_source_bytes.iter().any(Option::is_some) {
+            return None;
+        }
+
+        // Virtual lines above a line are drawn right before it, in the order
+        // of their anchors and priorities
+        let (rows_above, line_bytes) =
+            mappings[visual_row..]
+                .iter()
+                .enumerate()
+                .find_map(|(offset, mapping)| {
+                    let mut bytes = mapping.char_source_bytes.iter().flatten();
+                    let start = *bytes.next()?;
+                    let end = bytes.last().map_or(start, |end| *end) + 1;
+                    Some((offset, start..end))
+                })?;
+        let state = self.buffers.get(&buffer_id)?;
+        let above: Vec<_> = state
+            .virtual_texts
+            .query_lines_in_range(&state.marker_list, line_bytes.start, line_bytes.end)
+            .into_iter()
+            .filter(|(_, vtext)| vtext.position == VirtualTextPosition::LineAbove)
+            .collect();
+        let (anchor, vtext) = above.get(above.len().checked_sub(rows_above)?)?;
+        if vtext.namespace.as_ref().map(|ns| ns.as_str()) != Some(code_lens::NAMESPACE) {
+            return None;
+        }
+
+        let line = state.buffer.get_line_number(*anchor);
+        let lens_line = state
+            .code_lenses
+            .lines()
+            .into_iter()
+            .find(|lens_line| lens_line.line == line)?;
+        let gutter_width = state.margins.left_total_width() as u16;
+        let text_col = col.checked_sub(content_rect.x)?.checked_sub(gutter_width)? as usize;
+        let char_index = *mapping.visual_to_char.get(text_col)?;
+        let indent = vtext.text.chars().count() - lens_line.text.chars().count();
+        let index = lens_line.lens_at(char_index.checked_sub(indent)?)?;
+        state.code_lenses.command(index).cloned()
+    }
+
+    /// Run the command of a lens of a buffer
+    pub(super) fn run_code_lens_command(&mut self, buffer_id: BufferId, command: Command) {
+        let arguments = command.arguments.clone().unwrap_or_default();
+        let name = command.command.as_str();
+
+        if SHOW_REFERENCES_COMMANDS.contains(&name) {
+            let position = arguments
+                .get(1)
+                .and_then(|position| serde_json::from_value::<Position>(position.clone()).ok());
+            let locations = arguments
+                .get(2)
+                .and_then(|locations| {
+                    serde_json::from_value::<Vec<Location>>(locations.clone()).ok()
+                })
+                .unwrap_or_default();
+            let symbol = match (position, self.buffers.get(&buffer_id)) {
+                (Some(position), Some(state)) => word_at(state, position),
+                _ => String::new(),
+            };
+            if locations.is_empty() {
+                self.set_status_message(t!("lsp.no_references").to_string());
+            } else {
+                self.show_references(symbol, &locations);
+            }
+        } else if name == RUN_SINGLE_COMMAND {
+            match arguments.first().and_then(runnable_command_line) {
+                Some(command_line) => {
+                    self.open_terminal();
+                    self.send_terminal_input(format!("{}\r", command_line).as_bytes());
+                }
+                None => self.set_status_message(
+                    t!("lsp.code_lens_unsupported", command = name).to_string(),
+                ),
+            }
+        } else if DEBUG_COMMANDS.contains(&name) {
+            self.set_status_message(t!("lsp.code_lens_unsupported", command = name).to_string());
+        } else {
+            let request_id = self.next_lsp_request_id;
+            let title = command.title.clone();
+            let sent = self
+                .with_lsp_for_buffer(buffer_id, |handle, _uri, _language| {
+                    handle.execute_command(request_id, command).is_ok()
+                })
+                .unwrap_or(false);
+            if sent {
+                self.next_lsp_request_id += 1;
+                self.pending_execute_commands.insert(request_id, title);
+            }
+        }
+    }
+
+    /// Handle LSP execute command response
+    pub(super) fn handle_execute_command_response(
+        &mut self,
+        request_id: u64,
+        result: Result<(), String>,
+    ) {
+        let Some(title) = self.pending_execute_commands.remove(&request_id) else {
+            return;
+        };
+        if let Err(error) = result {
+            self.set_status_message(
+                t!("lsp.code_lens_failed", title = title, error = error).to_string(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_runnable_command_line() {
+        let runnable = json!({
+            "label": "test tests::it_works",
+            "kind": "cargo",
+            "args": {
+                "cwd": "/work/my crate",
+                "cargoArgs": ["test", "--package", "demo", "--lib"],
+                "executableArgs": ["tests::it_works", "--exact"]
+            }
+        });
+        assert_eq!(
+            runnable_command_line(&runnable).unwrap(),
+            "cd '/work/my crate' && cargo test --package demo --lib -- tests::it_works --exact"
+        );
+
+        let shell = json!({
+            "kind": "shell",
+            "args": { "program": "make", "args": ["it's"] }
+        });
+        assert_eq!(runnable_command_line(&shell).unwrap(), r"make 'it'\''s'");
+        assert!(runnable_command_line(&json!({ "kind": "npm", "args": {} })).is_none());
+    }
+}
@@ -0,0 +1,83 @@
+//! Per-file settings from `.editorconfig` files.
+//!
+//! EditorConfig properties sit between the user and project configuration
+//! layers: they override user settings, but a value set explicitly in
+//! `.fresh/config.json` (or the session) is kept.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::config::BufferConfig;
+use crate::config_io::{ConfigLayer, ConfigResolver};
+use crate::services::editorconfig::EditorConfigProperties;
+use crate::services::lsp::manager::detect_language;
+
+use super::Editor;
+
+impl Editor {
+    /// Effective settings for the file at `path`: its language settings with
+    /// `.editorconfig` properties merged in
+    pub(crate) fn buffer_config_for_path(&self, path: &Path) -> BufferConfig {
+        self.resolve_buffer_config(path).0
+    }
+
+    /// Settings for the file at `path`, with the config paths whose effective
+    /// value comes from `.editorconfig`
+    fn resolve_buffer_config(&self, path: &Path) -> (BufferConfig, Vec<String>) {
+        let language = detect_language(path, &self.config.languages);
+        let mut config = BufferConfig::resolve(&self.config, language.as_deref());
+        let properties = EditorConfigProperties::for_file(path);
+        if properties.is_empty() {
+            return (config, Vec::new());
+        }
+
+        let sources = ConfigResolver::new(self.dir_context.clone(), self.working_dir.clone())
+            .get_layer_sources()
+            .unwrap_or_default();
+        let applied = properties.apply_to(&mut config, |field| {
+            setting_paths(field, language.as_deref())
+                .iter()
+                .any(|p| set_above_editorconfig(&sources, p))
+        });
+        let paths = applied
+            .into_iter()
+            .flat_map(|field| setting_paths(field, language.as_deref()))
+            .collect();
+        (config, paths)
+    }
+
+    /// Mark the settings of the active file that come from `.editorconfig`
+    /// in a map of setting paths to their source layer
+    pub(crate) fn add_editorconfig_sources(&self, sources: &mut HashMap<String, ConfigLayer>) {
+        let Some(path) = self.active_state().buffer.file_path() else {
+            return;
+        };
+        for setting in self.resolve_buffer_config(path).1 {
+            if !set_above_editorconfig(sources, &setting) {
+                sources.insert(setting, ConfigLayer::EditorConfig);
+            }
+        }
+    }
+}
+
+/// Whether a layer that takes precedence over `.editorconfig` sets `path`
+fn set_above_editorconfig(sources: &HashMap<String, ConfigLayer>, path: &str) -> bool {
+    sources
+        .get(path)
+        .is_some_and(|layer| layer.precedence() > ConfigLayer::EditorConfig.precedence())
+}
+
+/// Config paths of the settings a `BufferConfig` field is resolved from
+fn setting_paths(field: &str, language: Option<&str>) -> Vec<String> {
+    let language_setting = |key: &str| language.map(|l| format!("/languages/{}/{}", l, key));
+    match field {
+        "tab_size" => std::iter::once("/editor/tab_size".to_string())
+            .chain(language_setting("tab_size"))
+            .collect(),
+        "use_tabs" | "trim_trailing_whitespace" | "final_newline" => {
+            language_setting(field).into_iter().collect()
+        }
+        "line_ending" => vec!["/editor/default_line_ending".to_string()],
+        _ => Vec::new(),
+    }
+}
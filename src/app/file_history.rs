@@ -0,0 +1,441 @@
+//! Local file history.
+//!
+//! Every save records a snapshot of the file in the project's
+//! [`LocalHistoryStore`]. "File History" lists the snapshots of the active
+//! file and opens the chosen one side by side with the buffer, where the whole
+//! snapshot or the hunk under the cursor can be restored into the buffer.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use rust_i18n::t;
+
+use super::file_open::{format_modified, format_size};
+use super::Editor;
+use crate::model::buffer::TextBuffer;
+use crate::model::composite_buffer::{
+    AlignedRow, CompositeLayout, DiffHunk, LineAlignment, PaneStyle, RowType, SourcePane,
+};
+use crate::model::event::{BufferId, Event};
+use crate::model::line_diff::{line_hunks, minimal_edit, LineHunk};
+use crate::primitives::text_property::TextPropertyEntry;
+use crate::services::local_history::{RetentionLimits, Snapshot};
+use crate::view::prompt::{Prompt, PromptType};
+
+/// Buffer mode of the snapshot comparison view (registered in `ModeRegistry::new`)
+pub(crate) const FILE_HISTORY_MODE: &str = "file-history";
+
+/// An open comparison of a buffer with one of its file's snapshots
+pub(crate) struct FileHistoryView {
+    /// Hidden buffer holding the snapshot content
+    snapshot_buffer: BufferId,
+
+    /// Buffer the snapshot is compared with
+    source: BufferId,
+
+    /// Hunks from the snapshot to the buffer the alignment was built from
+    hunks: Vec<LineHunk>,
+}
+
+impl Editor {
+    /// Record the saved content of a buffer in the local history
+    pub(crate) fn record_local_history(&self, buffer_id: BufferId) {
+        if let Some(state) = self.buffers.get(&buffer_id) {
+            self.record_file_history(&state.buffer);
+        }
+    }
+
+    /// Record the content of a text buffer just saved to its file in the local
+    /// history, whether or not the file is open in the editor
+    pub(crate) fn record_file_history(&self, buffer: &TextBuffer) {
+        if !self.config.editor.local_history {
+            return;
+        }
+        if buffer.is_large_file() || buffer.is_binary() {
+            return;
+        }
+        let Some(path) = buffer.file_path() else {
+            return;
+        };
+        let Some(content) = buffer.to_string() else {
+            return;
+        };
+
+        let limits = RetentionLimits {
+            max_entries: self.config.editor.local_history_max_entries,
+            max_age: Duration::from_secs(
+                self.config.editor.local_history_max_age_days as u64 * 86400,
+            ),
+        };
+        if let Err(e) =
+            self.local_history
+                .record(path, content.as_bytes(), SystemTime::now(), limits)
+        {
+            tracing::warn!("Failed to record local history for {:?}: {}", path, e);
+        }
+    }
+
+    /// List the local history snapshots of the active buffer's file
+    pub fn show_file_history(&mut self) {
+        let buffer_id = self.active_buffer();
+        let Some(path) = self.active_state().buffer.file_path().map(PathBuf::from) else {
+            self.set_status_message(t!("file_history.no_file").to_string());
+            return;
+        };
+        let snapshots = match self.local_history.snapshots(&path) {
+            Ok(snapshots) => snapshots,
+            Err(e) => {
+                self.set_status_message(
+                    t!("file_history.error", error = e.to_string()).to_string(),
+                );
+                return;
+            }
+        };
+        if snapshots.is_empty() {
+            self.set_status_message(t!("file_history.empty").to_string());
+            return;
+        }
+
+        let suggestions = snapshots
+            .iter()
+            .map(|snapshot| crate::input::commands::Suggestion {
+                text: format_saved_at(snapshot, "%Y-%m-%d %H:%M:%S"),
+                description: Some(format!(
+                    "{}, {}",
+                    format_modified(saved_at(snapshot)),
+                    format_size(snapshot.size as u64)
+                )),
+                value: Some(snapshot_key(snapshot)),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+        self.prompt = Some(Prompt::with_suggestions(
+            t!("file_history.prompt").to_string(),
+            PromptType::FileHistory { buffer_id },
+            suggestions,
+        ));
+    }
+
+    /// Open the snapshot picked in the "File History" prompt side by side with its buffer
+    pub(crate) fn open_file_history_snapshot(&mut self, source: BufferId, key: &str) {
+        let Some(path) = self
+            .buffers
+            .get(&source)
+            .and_then(|state| state.buffer.file_path())
+            .map(PathBuf::from)
+        else {
+            return;
+        };
+        let snapshot = self
+            .local_history
+            .snapshots(&path)
+            .ok()
+            .and_then(|snapshots| snapshots.into_iter().find(|s| snapshot_key(s) == key));
+        let Some(snapshot) = snapshot else {
+            self.set_status_message(t!("file_history.not_found").to_string());
+            return;
+        };
+        let content = match self.local_history.read(&path, &snapshot) {
+            Ok(content) => String::from_utf8_lossy(&content).into_owned(),
+            Err(e) => {
+                self.set_status_message(
+                    t!("file_history.error", error = e.to_string()).to_string(),
+                );
+                return;
+            }
+        };
+        let Some(current) = self.buffers.get(&source).and_then(|s| s.buffer.to_string()) else {
+            self.set_status_message(t!("file_history.unavailable").to_string());
+            return;
+        };
+
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let time = format_saved_at(&snapshot, "%H:%M:%S");
+
+        // The name's extension gives the snapshot the file's syntax highlighting
+        let snapshot_buffer = self.create_virtual_buffer(
+            format!("[{time}] {file_name}"),
+            "special".to_string(),
+            true,
+        );
+        if let Some(meta) = self.buffer_metadata.get_mut(&snapshot_buffer) {
+            meta.hidden_from_tabs = true;
+        }
+        if let Err(e) = self.set_virtual_buffer_content(
+            snapshot_buffer,
+            vec![TextPropertyEntry::text(content.clone())],
+        ) {
+            tracing::warn!("Failed to fill file history snapshot buffer: {}", e);
+        }
+
+        let hunks = line_hunks(&content, &current);
+        let composite = self.create_composite_buffer(
+            t!("file_history.tab_name", file = file_name, time = time).to_string(),
+            FILE_HISTORY_MODE.to_string(),
+            CompositeLayout::SideBySide {
+                ratios: vec![0.5, 0.5],
+                show_separator: true,
+            },
+            vec![
+                SourcePane::new(
+                    snapshot_buffer,
+                    t!("file_history.snapshot_label", time = time).to_string(),
+                    false,
+                )
+                .with_style(PaneStyle::old_diff()),
+                SourcePane::new(source, t!("file_history.current_label").to_string(), false)
+                    .with_style(PaneStyle::new_diff()),
+            ],
+        );
+        self.set_composite_alignment(composite, alignment(&hunks, &content, &current));
+        self.file_history_views.insert(
+            composite,
+            FileHistoryView {
+                snapshot_buffer,
+                source,
+                hunks,
+            },
+        );
+        self.set_active_buffer(composite);
+        self.set_status_message(t!("file_history.help").to_string());
+    }
+
+    /// Replace the compared buffer's content with the snapshot
+    pub fn handle_file_history_restore(&mut self) {
+        let Some((view_id, snapshot, current)) = self.active_file_history_texts() else {
+            return;
+        };
+        let source = self.file_history_views[&view_id].source;
+        if snapshot == current {
+            self.set_status_message(t!("file_history.no_changes").to_string());
+            return;
+        }
+        if self.replace_file_history_source(view_id, source, &current, &snapshot) {
+            self.set_status_message(t!("file_history.restored").to_string());
+        }
+    }
+
+    /// Replace the hunk under the cursor in the compared buffer with the snapshot's lines
+    pub fn handle_file_history_restore_hunk(&mut self) {
+        let Some((view_id, snapshot, current)) = self.active_file_history_texts() else {
+            return;
+        };
+        let view = &self.file_history_views[&view_id];
+        let (source, hunks) = (view.source, view.hunks.clone());
+
+        // The buffer may have been edited since the view was built
+        if line_hunks(&snapshot, &current) != hunks {
+            self.refresh_file_history_view(view_id, &snapshot, &current);
+            self.set_status_message(t!("file_history.outdated").to_string());
+            return;
+        }
+
+        let split_id = self.split_manager.active_split();
+        let cursor_row = self
+            .composite_view_states
+            .get(&(split_id, view_id))
+            .map(|vs| vs.cursor_row)
+            .unwrap_or(0);
+        let hunk = self
+            .composite_buffers
+            .get(&view_id)
+            .and_then(|composite| hunk_at_row(&composite.alignment.rows, cursor_row))
+            .and_then(|index| hunks.get(index))
+            .cloned();
+        let Some(hunk) = hunk else {
+            self.set_status_message(t!("file_history.no_hunk").to_string());
+            return;
+        };
+
+        let restored = restore_hunk(&current, &snapshot, &hunk);
+        if self.replace_file_history_source(view_id, source, &current, &restored) {
+            self.set_status_message(t!("file_history.hunk_restored").to_string());
+        }
+    }
+
+    /// Clean up a file history view whose buffer, or compared buffer, is being closed
+    pub(crate) fn on_file_history_buffer_closed(&mut self, buffer_id: BufferId) {
+        if let Some(view) = self.file_history_views.remove(&buffer_id) {
+            self.close_composite_buffer(buffer_id);
+            let _ = self.force_close_buffer(view.snapshot_buffer);
+            return;
+        }
+        let views: Vec<BufferId> = self
+            .file_history_views
+            .iter()
+            .filter(|(_, view)| view.source == buffer_id)
+            .map(|(&id, _)| id)
+            .collect();
+        for view_id in views {
+            let _ = self.force_close_buffer(view_id);
+        }
+    }
+
+    /// The active file history view with its snapshot and compared buffer contents
+    fn active_file_history_texts(&mut self) -> Option<(BufferId, String, String)> {
+        let view_id = self.active_buffer();
+        let view = self.file_history_views.get(&view_id)?;
+        let snapshot = self.buffers.get(&view.snapshot_buffer)?.buffer.to_string();
+        let current = self.buffers.get(&view.source)?.buffer.to_string();
+        match (snapshot, current) {
+            (Some(snapshot), Some(current)) => Some((view_id, snapshot, current)),
+            _ => {
+                self.set_status_message(t!("file_history.unavailable").to_string());
+                None
+            }
+        }
+    }
+
+    /// Change the compared buffer's content from `current` to `new` as one
+    /// undoable edit, then rebuild the view's alignment
+    fn replace_file_history_source(
+        &mut self,
+        view_id: BufferId,
+        source: BufferId,
+        current: &str,
+        new: &str,
+    ) -> bool {
+        let Some(cursor_id) = self
+            .buffers
+            .get(&source)
+            .map(|state| state.cursors.primary_id())
+        else {
+            return false;
+        };
+        let (range, text) = minimal_edit(current, new);
+        let events = vec![
+            Event::Delete {
+                range: range.clone(),
+                deleted_text: current[range.clone()].to_string(),
+                cursor_id,
+            },
+            Event::Insert {
+                position: range.start,
+                text: text.to_string(),
+                cursor_id,
+            },
+        ];
+        if let Err(e) = self.apply_events_to_buffer_as_bulk_edit(
+            source,
+            events,
+            t!("file_history.restore_description").to_string(),
+        ) {
+            self.set_status_message(t!("file_history.error", error = e.to_string()).to_string());
+            return false;
+        }
+        self.invalidate_layouts_for_buffer(source);
+
+        if let Some(snapshot) = self
+            .file_history_views
+            .get(&view_id)
+            .and_then(|view| self.buffers.get(&view.snapshot_buffer))
+            .and_then(|state| state.buffer.to_string())
+        {
+            self.refresh_file_history_view(view_id, &snapshot, new);
+        }
+        true
+    }
+
+    /// Rebuild a view's hunks and alignment from the given contents
+    fn refresh_file_history_view(&mut self, view_id: BufferId, snapshot: &str, current: &str) {
+        let hunks = line_hunks(snapshot, current);
+        self.set_composite_alignment(view_id, alignment(&hunks, snapshot, current));
+        if let Some(view) = self.file_history_views.get_mut(&view_id) {
+            view.hunks = hunks;
+        }
+    }
+}
+
+/// Value identifying a snapshot in the "File History" prompt
+fn snapshot_key(snapshot: &Snapshot) -> String {
+    format!("{}:{}", snapshot.saved_at, snapshot.content_hash)
+}
+
+fn saved_at(snapshot: &Snapshot) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(snapshot.saved_at)
+}
+
+/// Local time a snapshot was saved at, in the given `chrono` format
+fn format_saved_at(snapshot: &Snapshot, format: &str) -> String {
+    let datetime: chrono::DateTime<chrono::Local> = saved_at(snapshot).into();
+    datetime.format(format).to_string()
+}
+
+/// Side-by-side alignment of the snapshot (left) and the buffer (right)
+fn alignment(hunks: &[LineHunk], snapshot: &str, current: &str) -> LineAlignment {
+    let diff_hunks: Vec<DiffHunk> = hunks
+        .iter()
+        .map(|h| DiffHunk::new(h.old.start, h.old.len(), h.new.start, h.new.len()))
+        .collect();
+    LineAlignment::from_hunks(
+        &diff_hunks,
+        snapshot.split('\n').count(),
+        current.split('\n').count(),
+    )
+}
+
+/// Index of the hunk the row at `row` belongs to (its header or changed lines)
+fn hunk_at_row(rows: &[AlignedRow], row: usize) -> Option<usize> {
+    let header = rows
+        .iter()
+        .take(row.saturating_add(1))
+        .rposition(|r| r.row_type == RowType::HunkHeader)?;
+    if rows[header..=row.min(rows.len() - 1)]
+        .iter()
+        .any(|r| r.row_type == RowType::Context)
+    {
+        return None;
+    }
+    Some(
+        rows[..header]
+            .iter()
+            .filter(|r| r.row_type == RowType::HunkHeader)
+            .count(),
+    )
+}
+
+/// `current` with the lines of `hunk` replaced by the snapshot's lines
+fn restore_hunk(current: &str, snapshot: &str, hunk: &LineHunk) -> String {
+    let current_lines: Vec<&str> = current.split('\n').collect();
+    let snapshot_lines: Vec<&str> = snapshot.split('\n').collect();
+    current_lines[..hunk.new.start]
+        .iter()
+        .chain(&snapshot_lines[hunk.old.clone()])
+        .chain(&current_lines[hunk.new.end..])
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_hunk() {
+        let snapshot = "a\nb\nc\nd";
+        let current = "a\nB\nc";
+        let hunks = line_hunks(snapshot, current);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(restore_hunk(current, snapshot, &hunks[0]), "a\nb\nc");
+        assert_eq!(restore_hunk(current, snapshot, &hunks[1]), "a\nB\nc\nd");
+    }
+
+    #[test]
+    fn test_hunk_at_row() {
+        let snapshot = "a\nb\nc\nd\ne";
+        let current = "a\nB\nc\nd\nE";
+        let rows = alignment(&line_hunks(snapshot, current), snapshot, current).rows;
+        // a, header, b/B, c, d, header, e/E
+        assert_eq!(hunk_at_row(&rows, 0), None);
+        assert_eq!(hunk_at_row(&rows, 1), Some(0));
+        assert_eq!(hunk_at_row(&rows, 2), Some(0));
+        assert_eq!(hunk_at_row(&rows, 3), None);
+        assert_eq!(hunk_at_row(&rows, 6), Some(1));
+    }
+}
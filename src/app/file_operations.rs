@@ -8,7 +8,10 @@
 //! - File modification time tracking
 //! - Save conflict detection
 
+use crate::config::{BackupOption, BufferConfig};
 use crate::model::buffer::SudoSaveRequired;
+use crate::model::encoding::Encoding;
+use crate::model::save_options::{BackupPolicy, SaveOptions};
 use crate::view::prompt::PromptType;
 use std::path::{Path, PathBuf};
 
@@ -30,7 +33,11 @@ impl Editor {
             .file_path()
             .map(|p| p.to_path_buf());
 
-        match self.active_state_mut().buffer.save() {
+        if let Some(path) = &path {
+            self.apply_save_transforms(path);
+        }
+        let options = self.active_save_options();
+        match self.active_state_mut().buffer.save_with_options(&options) {
             Ok(()) => self.finalize_save(path),
             Err(e) => {
                 if let Some(sudo_info) = e.downcast_ref::<SudoSaveRequired>() {
@@ -47,6 +54,34 @@ impl Editor {
         }
     }
 
+    /// How the active buffer is written to disk, from the save strategy of its
+    /// language and the backup settings
+    pub(crate) fn active_save_options(&self) -> SaveOptions {
+        self.save_options_for(&self.active_state().language)
+    }
+
+    /// How a buffer of `language` is written to disk, from the language's save
+    /// strategy and the backup settings
+    pub(crate) fn save_options_for(&self, language: &str) -> SaveOptions {
+        let editor = &self.config.editor;
+        let strategy = BufferConfig::resolve(&self.config, Some(language)).save_strategy;
+        let backup = match editor.backup {
+            BackupOption::None => BackupPolicy::None,
+            BackupOption::Adjacent => BackupPolicy::Adjacent,
+            BackupOption::Directory => BackupPolicy::Directory {
+                dir: editor
+                    .backup_directory
+                    .clone()
+                    .unwrap_or_else(|| self.dir_context.backups_dir()),
+                count: editor.backup_count,
+            },
+        };
+        SaveOptions {
+            strategy: strategy.to_save_strategy(),
+            backup,
+        }
+    }
+
     /// Internal helper to finalize save state (mark as saved, notify LSP, etc.)
     pub(crate) fn finalize_save(&mut self, path: Option<PathBuf>) -> anyhow::Result<()> {
         // Auto-detect language if it's currently "text" and we have a path
@@ -75,6 +110,18 @@ impl Editor {
             }
         }
 
+        // Pick up edited snippet files the next time snippets are used
+        if path
+            .as_ref()
+            .is_some_and(|p| p.starts_with(self.dir_context.snippets_dir()))
+        {
+            self.snippet_library = None;
+        }
+
+        // Persist undo history against the saved content
+        self.persist_undo_history(self.active_buffer());
+        self.record_local_history(self.active_buffer());
+
         // Notify LSP of save
         self.notify_lsp_save();
 
@@ -128,6 +175,33 @@ impl Editor {
     /// Revert the active buffer to the last saved version on disk
     /// Returns Ok(true) if reverted, Ok(false) if no file path, Err on failure
     pub fn revert_file(&mut self) -> anyhow::Result<bool> {
+        let reverted = self.reload_active_file(None)?;
+        if reverted {
+            self.status_message = Some(t!("status.reverted").to_string());
+        }
+        Ok(reverted)
+    }
+
+    /// Reload the active buffer from disk, decoding it with the given encoding
+    /// Returns Ok(true) if reopened, Ok(false) if no file path, Err on failure
+    pub fn reopen_with_encoding(&mut self, encoding: Encoding) -> anyhow::Result<bool> {
+        let reopened = self.reload_active_file(Some(encoding))?;
+        if reopened {
+            self.status_message = Some(
+                t!(
+                    "status.reopened_with_encoding",
+                    encoding = encoding.display_name()
+                )
+                .to_string(),
+            );
+        }
+        Ok(reopened)
+    }
+
+    /// Reload the active buffer from disk, preserving viewport and cursors.
+    ///
+    /// If `encoding` is None, the encoding is detected from the file content.
+    fn reload_active_file(&mut self, encoding: Option<Encoding>) -> anyhow::Result<bool> {
         let path = match self.active_state().buffer.file_path() {
             Some(p) => p.to_path_buf(),
             None => {
@@ -152,13 +226,14 @@ impl Editor {
         let old_cursors = self.active_state().cursors.clone();
 
         // Load the file content fresh from disk
-        let mut new_state = EditorState::from_file_with_languages(
+        let mut new_state = EditorState::from_file_with_encoding(
             &path,
             self.terminal_width,
             self.terminal_height,
             self.config.editor.large_file_threshold_bytes as usize,
             &self.grammar_registry,
             &self.config.languages,
+            encoding,
         )?;
 
         // Restore cursor positions (clamped to valid range for new file size)
@@ -192,6 +267,7 @@ impl Editor {
 
         // Clear seen_byte_ranges so plugins get notified of all visible lines
         self.seen_byte_ranges.remove(&buffer_id);
+        self.start_line_indexing(buffer_id);
 
         // Update the file modification time
         if let Ok(metadata) = std::fs::metadata(&path) {
@@ -203,7 +279,6 @@ impl Editor {
         // Notify LSP that the file was changed
         self.notify_lsp_file_changed(&path);
 
-        self.status_message = Some(t!("status.reverted").to_string());
         Ok(true)
     }
 
@@ -243,10 +318,12 @@ impl Editor {
         self.last_auto_revert_poll = self.time_source.now();
 
         // Collect paths of open files that need checking
+        // Followed buffers are kept up to date by poll_followed_files instead
         let files_to_check: Vec<PathBuf> = self
             .buffers
-            .values()
-            .filter_map(|state| state.buffer.file_path().map(PathBuf::from))
+            .iter()
+            .filter(|(id, _)| !self.followed_buffers.contains_key(id))
+            .filter_map(|(_, state)| state.buffer.file_path().map(PathBuf::from))
             .collect();
 
         let mut any_changed = false;
@@ -653,6 +730,7 @@ impl Editor {
 
         // Clear seen_byte_ranges so plugins get notified of all visible lines
         self.seen_byte_ranges.remove(&buffer_id);
+        self.start_line_indexing(buffer_id);
 
         // Update the file modification time
         if let Ok(metadata) = std::fs::metadata(path) {
@@ -689,6 +767,10 @@ impl Editor {
             if self.terminal_buffers.contains_key(&buffer_id) {
                 continue;
             }
+            // Followed buffers are extended in place instead of reverted
+            if self.followed_buffers.contains_key(&buffer_id) {
+                continue;
+            }
 
             let state = match self.buffers.get(&buffer_id) {
                 Some(s) => s,
@@ -0,0 +1,178 @@
+//! Code folding actions
+//!
+//! Fold ranges come from the language server when it provides them, and
+//! otherwise from the tree-sitter syntax tree or indentation (see
+//! `view::folding`). Collapsed folds live on the buffer state; these actions
+//! only decide which ranges to collapse or expand and keep the cursor visible.
+
+use rust_i18n::t;
+
+use crate::state::EditorState;
+use crate::view::folding::{innermost_range, nesting_levels, FoldRange};
+
+use super::Editor;
+
+/// Foldable ranges of a buffer
+fn fold_ranges(state: &mut EditorState) -> Vec<FoldRange> {
+    let language = state.highlighter.language().copied();
+    state
+        .folds
+        .ranges(
+            &state.buffer,
+            &mut state.syntax_tree,
+            language.as_ref(),
+            state.tab_size,
+        )
+        .to_vec()
+}
+
+/// Move cursors out of collapsed folds onto the end of the fold header line
+fn reveal_cursors(state: &mut EditorState) {
+    let regions = state.folds.folded_regions(&state.buffer);
+    if regions.is_empty() {
+        return;
+    }
+    let buffer = &state.buffer;
+    state.cursors.map(|cursor| {
+        let Some(region) = regions.iter().find(|r| r.hidden.contains(&cursor.position)) else {
+            return;
+        };
+        let header = buffer.get_line(region.header_line).unwrap_or_default();
+        let content_len = header
+            .iter()
+            .rposition(|b| *b != b'\n' && *b != b'\r')
+            .map_or(0, |i| i + 1);
+        cursor.position = region.header_start + content_len;
+        cursor.anchor = None;
+        cursor.sticky_column = 0;
+    });
+}
+
+/// Collapse folds saved in a session, skipping ranges the file no longer has
+pub(crate) fn restore_folds(state: &mut EditorState, folds: &[FoldRange]) {
+    let Some(line_count) = state.buffer.line_count() else {
+        return;
+    };
+    for range in folds.iter().filter(|r| r.end_line < line_count) {
+        state.folds.collapse(&state.buffer, *range);
+    }
+}
+
+/// Toggle the fold whose header is `line` (used by gutter clicks)
+///
+/// Returns false if the line does not start a foldable range.
+pub(crate) fn toggle_fold_at_line(state: &mut EditorState, line: usize) -> bool {
+    if state.folds.expand_at_line(&state.buffer, line) {
+        return true;
+    }
+    let ranges = fold_ranges(state);
+    let Some(range) = ranges.iter().find(|r| r.start_line == line) else {
+        return false;
+    };
+    state.folds.collapse(&state.buffer, *range);
+    reveal_cursors(state);
+    true
+}
+
+impl Editor {
+    /// Collapse the innermost fold around the cursor line
+    pub(crate) fn fold_at_cursor(&mut self) {
+        let state = self.active_state_mut();
+        let line = state
+            .buffer
+            .get_line_number(state.cursors.primary().position);
+        let ranges = fold_ranges(state);
+        let range = innermost_range(&ranges, line, |r| {
+            state.folds.is_collapsed(&state.buffer, r.start_line)
+        });
+        match range {
+            Some(range) => {
+                state.folds.collapse(&state.buffer, range);
+                reveal_cursors(state);
+            }
+            None => self.set_status_message(t!("fold.none").to_string()),
+        }
+    }
+
+    /// Expand the fold on the cursor line, or the folds around it
+    pub(crate) fn unfold_at_cursor(&mut self) {
+        let state = self.active_state_mut();
+        let position = state.cursors.primary().position;
+        let line = state.buffer.get_line_number(position);
+        let expanded = state.folds.expand_at_line(&state.buffer, line)
+            || state.folds.expand_containing(&state.buffer, position);
+        if !expanded {
+            self.set_status_message(t!("fold.none_collapsed").to_string());
+        }
+    }
+
+    /// Expand the fold on the cursor line if there is one, otherwise collapse one
+    pub(crate) fn toggle_fold_at_cursor(&mut self) {
+        let state = self.active_state();
+        let line = state
+            .buffer
+            .get_line_number(state.cursors.primary().position);
+        if state.folds.is_collapsed(&state.buffer, line) {
+            self.unfold_at_cursor();
+        } else {
+            self.fold_at_cursor();
+        }
+    }
+
+    /// Collapse every foldable range
+    pub(crate) fn fold_all(&mut self) {
+        let state = self.active_state_mut();
+        let ranges = fold_ranges(state);
+        if ranges.is_empty() {
+            self.set_status_message(t!("fold.none").to_string());
+            return;
+        }
+        for range in ranges {
+            state.folds.collapse(&state.buffer, range);
+        }
+        reveal_cursors(state);
+    }
+
+    /// Expand every fold
+    pub(crate) fn unfold_all(&mut self) {
+        self.active_state_mut().folds.expand_all();
+    }
+
+    /// Start the prompt for folding a nesting level
+    pub(crate) fn start_fold_level_prompt(&mut self) {
+        self.start_prompt(
+            t!("fold.level_prompt").to_string(),
+            crate::view::prompt::PromptType::FoldLevel,
+        );
+    }
+
+    /// Collapse the ranges at nesting level `input`, keeping the cursor line visible
+    pub(crate) fn handle_fold_level(&mut self, input: &str) {
+        let level = match input.trim().parse::<usize>() {
+            Ok(level) if level > 0 => level,
+            _ => {
+                self.set_status_message(t!("fold.invalid_level", input = input).to_string());
+                return;
+            }
+        };
+        let state = self.active_state_mut();
+        let cursor_line = state
+            .buffer
+            .get_line_number(state.cursors.primary().position);
+        let ranges = fold_ranges(state);
+        let levels = nesting_levels(&ranges);
+        let mut folded = 0;
+        for (range, _) in ranges
+            .iter()
+            .zip(levels)
+            .filter(|(_, l)| *l == level)
+            .filter(|(r, _)| !(r.start_line < cursor_line && cursor_line <= r.end_line))
+        {
+            if state.folds.collapse(&state.buffer, *range) {
+                folded += 1;
+            }
+        }
+        reveal_cursors(state);
+        self.set_status_message(t!("fold.level_folded", count = folded, level = level).to_string());
+    }
+}
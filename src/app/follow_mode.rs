@@ -0,0 +1,285 @@
+//! Follow mode for growing files
+//!
+//! A followed buffer watches its file for appended data and extends itself in
+//! place, like `tail -f`, instead of being reverted. Only the new bytes are
+//! read, so this works for multi-gigabyte logs. A view whose cursor sits at the
+//! end of the buffer with the end visible stays pinned to the end; moving the
+//! cursor or scrolling away unpins it. When the file shrinks or is replaced
+//! (a new inode, as with log rotation), the buffer is reopened.
+
+use std::fs::Metadata;
+use std::path::Path;
+
+use rust_i18n::t;
+
+use crate::model::buffer::LineNumber;
+use crate::model::event::{BufferId, SplitId};
+
+use super::Editor;
+
+/// What a followed buffer has read of its file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FollowState {
+    /// Size of the file when it was last read
+    len: u64,
+
+    /// Device and inode of the file, to notice when it is replaced
+    file_id: Option<(u64, u64)>,
+
+    /// Whether following is held off by unsaved changes (reported once)
+    paused: bool,
+}
+
+impl FollowState {
+    fn of(metadata: &Metadata) -> Self {
+        #[cfg(unix)]
+        let file_id = {
+            use std::os::unix::fs::MetadataExt;
+            Some((metadata.dev(), metadata.ino()))
+        };
+        #[cfg(not(unix))]
+        let file_id = None;
+
+        Self {
+            len: metadata.len(),
+            file_id,
+            paused: false,
+        }
+    }
+}
+
+impl Editor {
+    /// Start or stop following the active buffer's file
+    pub(crate) fn toggle_follow(&mut self) {
+        let buffer_id = self.active_buffer();
+        if self.followed_buffers.remove(&buffer_id).is_some() {
+            self.status_message = Some(t!("follow.disabled").to_string());
+            return;
+        }
+
+        let state = self.active_state();
+        let Some(path) = state.buffer.file_path().map(Path::to_path_buf) else {
+            self.status_message = Some(t!("follow.no_file").to_string());
+            return;
+        };
+        if state.buffer.is_modified() {
+            self.status_message = Some(t!("follow.unsaved_changes").to_string());
+            return;
+        }
+        let metadata = match std::fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                self.status_message =
+                    Some(t!("file.error_opening", error = e.to_string()).to_string());
+                return;
+            }
+        };
+
+        // Catch up first if the file changed since it was loaded
+        let stale = metadata.modified().ok() != self.file_mod_times.get(&path).copied();
+        if stale {
+            if let Err(e) = self.revert_buffer_by_id(buffer_id, &path) {
+                self.status_message =
+                    Some(t!("file.error_opening", error = e.to_string()).to_string());
+                return;
+            }
+        }
+
+        self.followed_buffers
+            .insert(buffer_id, FollowState::of(&metadata));
+        let splits = self.split_manager.splits_for_buffer(buffer_id);
+        self.pin_to_end(buffer_id, &splits);
+        self.status_message = Some(
+            t!(
+                "follow.enabled",
+                name = path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string()
+            )
+            .to_string(),
+        );
+    }
+
+    /// Poll followed files for appended data (called from main loop).
+    /// Returns true if any buffer changed (requires re-render).
+    pub fn poll_followed_files(&mut self) -> bool {
+        if self.followed_buffers.is_empty() {
+            return false;
+        }
+        let poll_interval =
+            std::time::Duration::from_millis(self.config.editor.auto_revert_poll_interval_ms);
+        if self.time_source.elapsed_since(self.last_follow_poll) < poll_interval {
+            return false;
+        }
+        self.last_follow_poll = self.time_source.now();
+
+        let buffer_ids: Vec<BufferId> = self.followed_buffers.keys().copied().collect();
+        let mut any_changed = false;
+        for buffer_id in buffer_ids {
+            any_changed |= self.poll_followed_buffer(buffer_id);
+        }
+        any_changed
+    }
+
+    /// Bring a followed buffer up to date with its file.
+    /// Returns true if the buffer or the status changed.
+    fn poll_followed_buffer(&mut self, buffer_id: BufferId) -> bool {
+        let Some(path) = self
+            .buffers
+            .get(&buffer_id)
+            .and_then(|state| state.buffer.file_path())
+            .map(Path::to_path_buf)
+        else {
+            self.followed_buffers.remove(&buffer_id);
+            return false;
+        };
+        // A rotated log may briefly not exist; try again on the next poll
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            return false;
+        };
+        let Some(follow) = self.followed_buffers.get_mut(&buffer_id) else {
+            return false;
+        };
+        let current = FollowState::of(&metadata);
+        let replaced = current.file_id != follow.file_id || current.len < follow.len;
+        if !replaced && current.len == follow.len {
+            return false;
+        }
+
+        // Never drop unsaved edits; wait until they are saved or reverted
+        let modified = self
+            .buffers
+            .get(&buffer_id)
+            .is_some_and(|state| state.buffer.is_modified());
+        if modified {
+            let first_report = !follow.paused;
+            follow.paused = true;
+            if first_report {
+                self.status_message = Some(t!("follow.paused").to_string());
+            }
+            return first_report;
+        }
+        let old_len = follow.len;
+        *follow = current.clone();
+
+        let splits = self.pinned_splits(buffer_id);
+        let appended = !replaced && self.append_to_followed_buffer(buffer_id, &path, old_len);
+        if !appended {
+            if let Err(e) = self.revert_buffer_by_id(buffer_id, &path) {
+                tracing::warn!("Failed to reopen followed file {:?}: {}", path, e);
+                self.followed_buffers.remove(&buffer_id);
+                self.status_message =
+                    Some(t!("file.error_opening", error = e.to_string()).to_string());
+                return true;
+            }
+            if replaced {
+                self.status_message = Some(t!("follow.reopened").to_string());
+            }
+        }
+        if let Ok(mtime) = metadata.modified() {
+            self.file_mod_times.insert(path, mtime);
+        }
+        self.invalidate_layouts_for_buffer(buffer_id);
+        self.pin_to_end(buffer_id, &splits);
+        true
+    }
+
+    /// Append the bytes added to a followed file after `old_len`.
+    /// Returns false if the buffer has to be reopened instead.
+    fn append_to_followed_buffer(
+        &mut self,
+        buffer_id: BufferId,
+        path: &Path,
+        old_len: u64,
+    ) -> bool {
+        let Some(new_len) = self.followed_buffers.get(&buffer_id).map(|f| f.len) else {
+            return false;
+        };
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return false;
+        };
+        // Transcoded or compressed content doesn't map to file offsets
+        if !state.buffer.encoding().is_utf8() || state.buffer.compression().is_some() {
+            return false;
+        }
+
+        let start = state.buffer.len();
+        let bytes = (new_len - old_len) as usize;
+        if let Err(e) = state.buffer.append_from_file(path, old_len as usize, bytes) {
+            tracing::warn!("Failed to append to followed file {:?}: {}", path, e);
+            return false;
+        }
+        state.highlighter.invalidate_range(start..start + bytes);
+        self.notify_lsp_file_changed(path);
+        true
+    }
+
+    /// Splits showing the end of a buffer with their cursor there
+    fn pinned_splits(&mut self, buffer_id: BufferId) -> Vec<SplitId> {
+        let active_split = self.split_manager.active_split();
+        let splits = self.split_manager.splits_for_buffer(buffer_id);
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return Vec::new();
+        };
+        let end = state.buffer.len();
+        splits
+            .into_iter()
+            .filter(|split_id| {
+                let Some(view_state) = self.split_view_states.get(split_id) else {
+                    return false;
+                };
+                let cursor = if *split_id == active_split {
+                    state.cursors.primary().position
+                } else {
+                    view_state.cursors.primary().position
+                };
+                if cursor != end {
+                    return false;
+                }
+                // The end is visible if it is within a screenful of the top
+                let viewport = &view_state.viewport;
+                let mut lines = state.buffer.line_iterator(viewport.top_byte, 80);
+                let mut visible = 0;
+                while lines.next().is_some() {
+                    visible += 1;
+                    if visible > viewport.visible_line_count() {
+                        return false;
+                    }
+                }
+                true
+            })
+            .collect()
+    }
+
+    /// Move the cursor to the end of the buffer in `splits` and scroll it into view
+    fn pin_to_end(&mut self, buffer_id: BufferId, splits: &[SplitId]) {
+        let active_split = self.split_manager.active_split();
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let end = state.buffer.len();
+        for split_id in splits {
+            let Some(view_state) = self.split_view_states.get_mut(split_id) else {
+                continue;
+            };
+            let cursors = if *split_id == active_split {
+                &mut state.cursors
+            } else {
+                &mut view_state.cursors
+            };
+            cursors.primary_mut().position = end;
+            cursors.primary_mut().anchor = None;
+            let cursor = *cursors.primary();
+            view_state
+                .viewport
+                .ensure_visible(&mut state.buffer, &cursor);
+        }
+        if splits.contains(&active_split) {
+            if let Some(position) = state.buffer.offset_to_position(end) {
+                state.primary_cursor_line_number = LineNumber::Absolute(position.line);
+            }
+        }
+    }
+}
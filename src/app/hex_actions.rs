@@ -0,0 +1,542 @@
+//! Hex view actions
+//!
+//! A split in hex view edits its buffer byte by byte (see [`crate::view::hex_view`]).
+//! Cursor movement works on nibbles and rows, typing overwrites bytes in place, and
+//! search takes hex byte patterns. Size-changing edits are not supported, apart from
+//! appending bytes at the end of the buffer.
+//!
+//! Entering hex view reopens a buffer that was decoded from another encoding as raw
+//! UTF-8 bytes, so the bytes shown are the bytes on disk. Editing bytes marks the
+//! buffer to be saved verbatim; a buffer that was only viewed keeps converting its
+//! line endings and encoding on save.
+
+use rust_i18n::t;
+
+use crate::input::keybindings::Action;
+use crate::model::encoding::Encoding;
+use crate::model::event::{CursorId, Event};
+use crate::view::hex_view::{self, HexViewState, BYTES_PER_ROW};
+
+use super::Editor;
+
+/// Cursor target of a hex view movement
+struct HexPosition {
+    offset: usize,
+    low_nibble: bool,
+}
+
+/// Actions handled as usual in hex view: those that leave the buffer content
+/// alone (movement, selection, copy, search, undo and redo, and everything
+/// outside the buffer). Any other action could insert or delete bytes, so it is
+/// refused; hex input overwrites bytes instead.
+fn allowed_in_hex_view(action: &Action) -> bool {
+    matches!(
+        action,
+        // Movement and selection
+        Action::MoveLeft
+            | Action::MoveRight
+            | Action::MoveUp
+            | Action::MoveDown
+            | Action::MoveWordLeft
+            | Action::MoveWordRight
+            | Action::MoveLineStart
+            | Action::MoveLineEnd
+            | Action::MovePageUp
+            | Action::MovePageDown
+            | Action::MoveDocumentStart
+            | Action::MoveDocumentEnd
+            | Action::SelectLeft
+            | Action::SelectRight
+            | Action::SelectUp
+            | Action::SelectDown
+            | Action::SelectWordLeft
+            | Action::SelectWordRight
+            | Action::SelectLineStart
+            | Action::SelectLineEnd
+            | Action::SelectDocumentStart
+            | Action::SelectDocumentEnd
+            | Action::SelectPageUp
+            | Action::SelectPageDown
+            | Action::SelectAll
+            | Action::SelectWord
+            | Action::SelectLine
+            | Action::ExpandSelection
+            | Action::ShrinkSelection
+            | Action::SetMark
+            | Action::RemoveSecondaryCursors
+            | Action::Recenter
+            | Action::ScrollUp
+            | Action::ScrollDown
+            | Action::SmartHome
+            | Action::GotoLine
+            | Action::GoToMatchingBracket
+            | Action::JumpToNextError
+            | Action::JumpToPreviousError
+            | Action::NavigateBack
+            | Action::NavigateForward
+            | Action::SetBookmark(_)
+            | Action::JumpToBookmark(_)
+            | Action::ClearBookmark(_)
+            | Action::ListBookmarks
+            | Action::PromptSetBookmark
+            | Action::PromptJumpToBookmark
+            // Copy
+            | Action::Copy
+            | Action::CopyWithTheme(_)
+            // Undo and redo
+            | Action::Undo
+            | Action::Redo
+            | Action::UndoChronological
+            | Action::RedoChronological
+            | Action::ShowUndoTree
+            // Search
+            | Action::Search
+            | Action::FindInSelection
+            | Action::FindNext
+            | Action::FindPrevious
+            | Action::FindSelectionNext
+            | Action::FindSelectionPrevious
+            | Action::HexSearch
+            | Action::ToggleSearchCaseSensitive
+            | Action::ToggleSearchWholeWord
+            | Action::ToggleSearchRegex
+            | Action::ToggleSearchConfirmEach
+            | Action::ProjectSearch
+            | Action::ProjectSearchOpen
+            | Action::ProjectSearchToggle
+            | Action::ProjectSearchFilter
+            // Files, buffers and splits
+            | Action::Save
+            | Action::SaveAs
+            | Action::SaveWithEncoding
+            | Action::ReopenWithEncoding
+            | Action::Revert
+            | Action::ToggleAutoRevert
+            | Action::ToggleFollow
+            | Action::Open
+            | Action::SwitchProject
+            | Action::New
+            | Action::Close
+            | Action::CloseTab
+            | Action::Quit
+            | Action::FileHistory
+            | Action::StopLineIndexing
+            | Action::NextBuffer
+            | Action::PrevBuffer
+            | Action::SwitchToPreviousTab
+            | Action::SwitchToTabByName
+            | Action::ScrollTabsLeft
+            | Action::ScrollTabsRight
+            | Action::SplitHorizontal
+            | Action::SplitVertical
+            | Action::CloseSplit
+            | Action::NextSplit
+            | Action::PrevSplit
+            | Action::IncreaseSplitSize
+            | Action::DecreaseSplitSize
+            | Action::ToggleMaximizeSplit
+            // Macros (recording only; playing them back could edit)
+            | Action::StartMacroRecording
+            | Action::StopMacroRecording
+            | Action::ToggleMacroRecording(_)
+            | Action::ShowMacro(_)
+            | Action::ListMacros
+            | Action::PromptRecordMacro
+            // Views, panels and settings
+            | Action::ToggleHexView
+            | Action::ToggleLineWrap
+            | Action::ToggleStickyScroll
+            | Action::ToggleComposeMode
+            | Action::SetComposeWidth
+            | Action::ToggleLineNumbers
+            | Action::ToggleInlayHints
+            | Action::ToggleMouseHover
+            | Action::ToggleMouseCapture
+            | Action::ToggleDebugHighlights
+            | Action::ToggleTabIndicators
+            | Action::ToggleMenuBar
+            | Action::ToggleTabBar
+            | Action::ToggleFileExplorer
+            | Action::FocusFileExplorer
+            | Action::FocusEditor
+            | Action::ToggleOutline
+            | Action::FocusOutline
+            | Action::ToggleBreadcrumbs
+            | Action::PickSiblingSymbol
+            | Action::Fold
+            | Action::Unfold
+            | Action::ToggleFold
+            | Action::FoldAll
+            | Action::UnfoldAll
+            | Action::FoldLevel
+            | Action::SelectTheme
+            | Action::SelectKeybindingMap
+            | Action::SelectCursorStyle
+            | Action::SelectLocale
+            | Action::SwitchKeybindingMap(_)
+            | Action::SetBackground
+            | Action::SetBackgroundBlend
+            | Action::SetTabSize
+            | Action::ResetBufferSettings
+            | Action::DumpConfig
+            | Action::OpenSettings
+            | Action::CommandPalette
+            | Action::ShowHelp
+            | Action::ShowKeyboardShortcuts
+            | Action::ShowWarnings
+            | Action::ShowLspStatus
+            | Action::ClearWarnings
+            | Action::MenuOpen(_)
+            | Action::MenuActivate
+            | Action::CalibrateInput
+            // Language server navigation
+            | Action::LspGotoDefinition
+            | Action::LspGotoTypeDefinition
+            | Action::LspGotoImplementation
+            | Action::LspGotoDeclaration
+            | Action::LspReferences
+            | Action::LspWorkspaceSymbol
+            | Action::LspHover
+            | Action::LspSignatureHelp
+            | Action::LspRestart
+            | Action::LspStop
+            | Action::ShowCallHierarchy
+            | Action::ShowTypeHierarchy
+            // Terminals
+            | Action::OpenTerminal
+            | Action::CloseTerminal
+            | Action::FocusTerminal
+            | Action::None
+    )
+}
+
+impl Editor {
+    /// Whether the active split shows the active buffer in hex view
+    pub fn is_hex_view_active(&self) -> bool {
+        self.active_hex_view().is_some()
+    }
+
+    fn active_hex_view(&self) -> Option<&HexViewState> {
+        let buffer_id = self.active_buffer();
+        self.split_view_states
+            .get(&self.split_manager.active_split())
+            .and_then(|vs| vs.hex_view.as_ref())
+            .filter(|hex| hex.buffer_id == buffer_id)
+    }
+
+    fn active_hex_view_mut(&mut self) -> Option<&mut HexViewState> {
+        let buffer_id = self.active_buffer();
+        self.split_view_states
+            .get_mut(&self.split_manager.active_split())
+            .and_then(|vs| vs.hex_view.as_mut())
+            .filter(|hex| hex.buffer_id == buffer_id)
+    }
+
+    /// Toggle hex view for the active buffer in the active split
+    pub fn toggle_hex_view(&mut self) {
+        let buffer_id = self.active_buffer();
+        let split_id = self.split_manager.active_split();
+
+        if self.is_hex_view_active() {
+            if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+                view_state.hex_view = None;
+            }
+            // The cursor may be in the middle of a multi-byte character
+            let state = self.active_state_mut();
+            let position = state.cursors.primary().position;
+            let snapped = state.buffer.snap_to_char_boundary(position);
+            if snapped != position {
+                self.move_hex_cursor(snapped);
+            }
+            self.set_status_message(t!("hex_view.disabled").to_string());
+            return;
+        }
+
+        if self.is_composite_buffer(buffer_id) {
+            self.set_status_message(t!("hex_view.unsupported").to_string());
+            return;
+        }
+
+        // Hex view edits the bytes on disk, so a transcoded buffer is reopened
+        // with its undecoded bytes first
+        if self.active_state().buffer.encoding() != Encoding::Utf8 {
+            if self.active_state().buffer.is_modified() {
+                self.set_status_message(t!("hex_view.reopen_modified").to_string());
+                return;
+            }
+            match self.reopen_with_encoding(Encoding::Utf8) {
+                Ok(true) => {}
+                Ok(false) => return,
+                Err(e) => {
+                    self.set_status_message(
+                        t!("error.reopen_failed", error = e.to_string()).to_string(),
+                    );
+                    return;
+                }
+            }
+        }
+
+        let position = self.active_state().cursors.primary().position;
+        if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+            view_state.hex_view = Some(HexViewState::new(buffer_id));
+        }
+        // Drop any selection, which the hex view does not show
+        self.move_hex_cursor(position);
+        self.set_status_message(t!("hex_view.enabled").to_string());
+    }
+
+    /// Handle an action in hex view.
+    ///
+    /// Returns true if the action was handled (or deliberately blocked) here, false
+    /// if it should be handled as usual.
+    pub(super) fn handle_hex_view_action(&mut self, action: &Action) -> bool {
+        if self.is_prompting() || !self.is_hex_view_active() {
+            return false;
+        }
+
+        let len = self.active_state().buffer.len();
+        let offset = self.active_state().cursors.primary().position.min(len);
+        let Some(view) = self.active_hex_view() else {
+            return false;
+        };
+        let ascii_focus = view.ascii_focus;
+        let low_nibble = view.low_nibble && !ascii_focus;
+        let page = self.active_viewport().height.max(1) as usize * BYTES_PER_ROW;
+        let row_start = offset - offset % BYTES_PER_ROW;
+
+        let byte_start = |offset: usize| HexPosition {
+            offset,
+            low_nibble: false,
+        };
+        let target = match action {
+            Action::MoveRight if ascii_focus => byte_start((offset + 1).min(len)),
+            Action::MoveRight if !low_nibble && offset < len => HexPosition {
+                offset,
+                low_nibble: true,
+            },
+            Action::MoveRight => byte_start((offset + 1).min(len)),
+            Action::MoveLeft if low_nibble => byte_start(offset),
+            Action::MoveLeft if ascii_focus || offset == 0 => byte_start(offset.saturating_sub(1)),
+            Action::MoveLeft => HexPosition {
+                offset: offset - 1,
+                low_nibble: true,
+            },
+            Action::MoveWordRight => byte_start((offset + 1).min(len)),
+            Action::MoveWordLeft => byte_start(offset.saturating_sub(1)),
+            Action::MoveUp if offset >= BYTES_PER_ROW => HexPosition {
+                offset: offset - BYTES_PER_ROW,
+                low_nibble,
+            },
+            Action::MoveUp => return true,
+            Action::MoveDown => HexPosition {
+                offset: (offset + BYTES_PER_ROW).min(len),
+                low_nibble: low_nibble && offset + BYTES_PER_ROW < len,
+            },
+            Action::MoveLineStart => byte_start(row_start),
+            Action::MoveLineEnd => byte_start((row_start + BYTES_PER_ROW - 1).min(len)),
+            Action::MovePageUp => byte_start(offset.saturating_sub(page)),
+            Action::MovePageDown => byte_start((offset + page).min(len)),
+            Action::MoveDocumentStart => byte_start(0),
+            Action::MoveDocumentEnd => byte_start(len),
+            Action::InsertTab => {
+                if let Some(view) = self.active_hex_view_mut() {
+                    view.ascii_focus = !view.ascii_focus;
+                    view.low_nibble = false;
+                }
+                return true;
+            }
+            Action::InsertChar(c) => {
+                self.hex_type_char(*c, offset, ascii_focus, low_nibble);
+                return true;
+            }
+            Action::FindNext => {
+                if let Some(pattern) = self.active_hex_view().and_then(|v| v.last_pattern.clone()) {
+                    self.hex_search(pattern);
+                    return true;
+                }
+                return false;
+            }
+            _ if allowed_in_hex_view(action) => return false,
+            _ => {
+                self.set_status_message(t!("hex_view.overwrite_only").to_string());
+                return true;
+            }
+        };
+
+        if let Some(view) = self.active_hex_view_mut() {
+            view.low_nibble = target.low_nibble;
+        }
+        self.move_hex_cursor(target.offset);
+        true
+    }
+
+    /// Type a character in hex view: a hex digit overwrites the focused nibble,
+    /// and in the ASCII gutter an ASCII character overwrites the byte
+    fn hex_type_char(&mut self, c: char, offset: usize, ascii_focus: bool, low_nibble: bool) {
+        if self.is_editing_disabled() {
+            self.set_status_message(t!("buffer.editing_disabled").to_string());
+            return;
+        }
+
+        let old_byte = if offset < self.active_state().buffer.len() {
+            self.active_state_mut()
+                .buffer
+                .get_text_range_mut(offset, 1)
+                .ok()
+                .and_then(|bytes| bytes.first().copied())
+        } else {
+            None
+        };
+
+        let (byte, next) = if ascii_focus {
+            if !c.is_ascii() || c.is_ascii_control() {
+                self.set_status_message(t!("hex_view.not_ascii").to_string());
+                return;
+            }
+            (
+                c as u8,
+                HexPosition {
+                    offset: offset + 1,
+                    low_nibble: false,
+                },
+            )
+        } else {
+            let Some(digit) = c.to_digit(16) else {
+                self.set_status_message(t!("hex_view.not_hex_digit").to_string());
+                return;
+            };
+            let digit = digit as u8;
+            let old = old_byte.unwrap_or(0);
+            if low_nibble {
+                (
+                    (old & 0xF0) | digit,
+                    HexPosition {
+                        offset: offset + 1,
+                        low_nibble: false,
+                    },
+                )
+            } else {
+                (
+                    (old & 0x0F) | (digit << 4),
+                    HexPosition {
+                        offset,
+                        low_nibble: true,
+                    },
+                )
+            }
+        };
+
+        if old_byte != Some(byte) {
+            self.hex_overwrite_byte(offset, byte, next.offset);
+        } else {
+            self.move_hex_cursor(next.offset);
+        }
+        if let Some(view) = self.active_hex_view_mut() {
+            view.low_nibble = next.low_nibble;
+        }
+    }
+
+    /// Replace the byte at `offset` (or append one at the end of the buffer) as a
+    /// single undoable edit, leaving the cursor at `new_cursor`
+    fn hex_overwrite_byte(&mut self, offset: usize, byte: u8, new_cursor: usize) {
+        let state = self.active_state_mut();
+        let old_cursors: Vec<(CursorId, usize, Option<usize>)> = state
+            .cursors
+            .iter()
+            .map(|(id, c)| (id, c.position, c.anchor))
+            .collect();
+        let old_tree = state.buffer.snapshot_piece_tree();
+        // Edited bytes are saved as they are, even after leaving hex view
+        state.buffer.set_raw_bytes(true);
+
+        if offset < state.buffer.len() {
+            state.buffer.delete_bytes(offset, 1);
+        }
+        state.buffer.insert_bytes(offset, vec![byte]);
+        let new_tree = state.buffer.snapshot_piece_tree();
+
+        let primary_id = state.cursors.primary_id();
+        let new_cursors: Vec<(CursorId, usize, Option<usize>)> = old_cursors
+            .iter()
+            .map(|&(id, position, anchor)| {
+                if id == primary_id {
+                    (id, new_cursor, None)
+                } else {
+                    (id, position, anchor)
+                }
+            })
+            .collect();
+        let primary = state.cursors.primary_mut();
+        primary.position = new_cursor;
+        primary.anchor = None;
+        state.highlighter.invalidate_all();
+
+        let event = Event::BulkEdit {
+            old_tree: Some(old_tree),
+            new_tree: Some(new_tree),
+            old_cursors,
+            new_cursors,
+            description: format!("Overwrite byte at 0x{:X}", offset),
+        };
+
+        self.sync_editor_state_to_split_view_state();
+        self.invalidate_layouts_for_buffer(self.active_buffer());
+        self.adjust_other_split_cursors_for_event(&event);
+        self.active_event_log_mut().append(event);
+    }
+
+    /// Move the primary cursor to `offset`, clearing any selection
+    fn move_hex_cursor(&mut self, offset: usize) {
+        let state = self.active_state();
+        let cursor = state.cursors.primary();
+        let event = Event::MoveCursor {
+            cursor_id: state.cursors.primary_id(),
+            old_position: cursor.position,
+            new_position: offset,
+            old_anchor: cursor.anchor,
+            new_anchor: None,
+            old_sticky_column: cursor.sticky_column,
+            new_sticky_column: 0,
+        };
+        self.active_event_log_mut().append(event.clone());
+        self.apply_event_to_active_buffer(&event);
+    }
+
+    /// Handle HexSearch prompt confirmation
+    pub(super) fn handle_hex_search(&mut self, input: &str) {
+        let Some(pattern) = hex_view::parse_hex_pattern(input) else {
+            self.set_status_message(t!("hex_view.invalid_pattern", input = input).to_string());
+            return;
+        };
+        if let Some(view) = self.active_hex_view_mut() {
+            view.last_pattern = Some(pattern.clone());
+        }
+        self.hex_search(pattern);
+    }
+
+    /// Move the cursor to the next occurrence of `pattern` after the cursor, wrapping
+    /// around at the end of the buffer
+    fn hex_search(&mut self, pattern: Vec<u8>) {
+        let state = self.active_state_mut();
+        let start = state.cursors.primary().position + 1;
+        match state.buffer.find_next_bytes(&pattern, start) {
+            Ok(Some(offset)) => {
+                if let Some(view) = self.active_hex_view_mut() {
+                    view.low_nibble = false;
+                }
+                self.move_hex_cursor(offset);
+                self.set_status_message(
+                    t!("hex_view.found", offset = format!("0x{:X}", offset)).to_string(),
+                );
+            }
+            Ok(None) => {
+                self.set_status_message(t!("hex_view.not_found").to_string());
+            }
+            Err(e) => {
+                self.set_status_message(
+                    t!("hex_view.search_failed", error = e.to_string()).to_string(),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,792 @@
+//! Call hierarchy and type hierarchy panel.
+//!
+//! Shows the callers or callees of the function at the cursor
+//! (`callHierarchy/incomingCalls` and `outgoingCalls`), or the supertypes or
+//! subtypes of the type at the cursor (`typeHierarchy/supertypes` and
+//! `subtypes`), as a tree in a virtual buffer split off next to it. The
+//! children of a node are requested from the language server the first time it
+//! is expanded. The code of the row under the cursor is previewed below the
+//! tree; Enter opens it in the split the hierarchy was opened from, and `t`
+//! switches between the two directions.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use lsp_types::{CallHierarchyItem, Position, Range, SymbolKind, TypeHierarchyItem, Uri};
+use rust_i18n::t;
+
+use super::{uri_to_path, Editor};
+use crate::model::event::{BufferId, SplitDirection, SplitId};
+use crate::primitives::text_property::TextPropertyEntry;
+use crate::services::lsp::manager::detect_language;
+use crate::view::document_symbols::kind_icon;
+use crate::view::split::SplitViewState;
+
+/// Display name of the hierarchy panel buffer
+pub(crate) const HIERARCHY_BUFFER_NAME: &str = "*Hierarchy*";
+
+/// Buffer mode of the hierarchy panel (registered in `ModeRegistry::new`)
+pub(crate) const HIERARCHY_MODE: &str = "hierarchy";
+
+/// Text property holding the node shown on a tree row
+const NODE_PROPERTY: &str = "hierarchy_node";
+
+/// Lines shown before the previewed line
+const PREVIEW_LINES_BEFORE: usize = 2;
+
+/// Lines shown after the previewed line
+const PREVIEW_LINES_AFTER: usize = 5;
+
+/// Which relatives of the symbol the panel shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HierarchyDirection {
+    Callers,
+    Callees,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyDirection {
+    fn is_call(self) -> bool {
+        matches!(self, Self::Callers | Self::Callees)
+    }
+
+    /// The other direction of the same hierarchy
+    fn toggled(self) -> Self {
+        match self {
+            Self::Callers => Self::Callees,
+            Self::Callees => Self::Callers,
+            Self::Supertypes => Self::Subtypes,
+            Self::Subtypes => Self::Supertypes,
+        }
+    }
+
+    fn title(self, name: &str) -> String {
+        match self {
+            Self::Callers => t!("hierarchy.callers_title", name = name),
+            Self::Callees => t!("hierarchy.callees_title", name = name),
+            Self::Supertypes => t!("hierarchy.supertypes_title", name = name),
+            Self::Subtypes => t!("hierarchy.subtypes_title", name = name),
+        }
+        .to_string()
+    }
+}
+
+/// An item of a call or type hierarchy
+#[derive(Debug, Clone)]
+enum HierarchyItem {
+    Call(CallHierarchyItem),
+    Type(TypeHierarchyItem),
+}
+
+impl HierarchyItem {
+    fn name(&self) -> &str {
+        match self {
+            Self::Call(item) => &item.name,
+            Self::Type(item) => &item.name,
+        }
+    }
+
+    fn kind(&self) -> SymbolKind {
+        match self {
+            Self::Call(item) => item.kind,
+            Self::Type(item) => item.kind,
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Call(item) => item.detail.as_deref(),
+            Self::Type(item) => item.detail.as_deref(),
+        }
+    }
+
+    fn uri(&self) -> &Uri {
+        match self {
+            Self::Call(item) => &item.uri,
+            Self::Type(item) => &item.uri,
+        }
+    }
+
+    fn selection_range(&self) -> Range {
+        match self {
+            Self::Call(item) => item.selection_range,
+            Self::Type(item) => item.selection_range,
+        }
+    }
+}
+
+/// A row of the hierarchy tree
+#[derive(Debug, Clone)]
+struct HierarchyNode {
+    item: HierarchyItem,
+    /// Where the row leads: the first call for a caller, otherwise the item's name
+    target: Position,
+    depth: usize,
+    /// Node indices of the children, once the server sent them
+    children: Option<Vec<usize>>,
+    expanded: bool,
+}
+
+impl HierarchyNode {
+    fn new(item: HierarchyItem, target: Option<Position>, depth: usize) -> Self {
+        Self {
+            target: target.unwrap_or(item.selection_range().start),
+            item,
+            depth,
+            children: None,
+            expanded: false,
+        }
+    }
+}
+
+/// A hierarchy requested for the symbol at the cursor
+#[derive(Debug)]
+pub(crate) struct PendingHierarchy {
+    request_id: u64,
+    direction: HierarchyDirection,
+    language: String,
+    source_split: SplitId,
+}
+
+/// State of the open hierarchy panel
+pub(crate) struct HierarchyPanel {
+    /// The panel's virtual buffer
+    buffer_id: BufferId,
+
+    /// Split that items are opened in
+    source_split: SplitId,
+
+    /// Language of the server that answers the panel's requests
+    language: String,
+
+    direction: HierarchyDirection,
+
+    /// Tree nodes; the first `root_count` are the items the hierarchy was
+    /// prepared for
+    nodes: Vec<HierarchyNode>,
+    root_count: usize,
+
+    /// Children requests in flight: request id -> node
+    pending: HashMap<u64, usize>,
+
+    /// Node the panel content previews
+    previewed: Option<usize>,
+}
+
+impl Editor {
+    /// Show the callers of the function at the cursor
+    pub fn show_call_hierarchy(&mut self) {
+        self.request_hierarchy(HierarchyDirection::Callers);
+    }
+
+    /// Show the subtypes of the type at the cursor
+    pub fn show_type_hierarchy(&mut self) {
+        self.request_hierarchy(HierarchyDirection::Subtypes);
+    }
+
+    /// Ask the server to prepare the hierarchy of the symbol at the cursor
+    fn request_hierarchy(&mut self, direction: HierarchyDirection) {
+        let buffer_id = self.active_buffer();
+        let language = self
+            .buffer_metadata
+            .get(&buffer_id)
+            .and_then(|metadata| metadata.file_path())
+            .and_then(|path| detect_language(path, &self.config.languages));
+        let supported = language.as_ref().is_some_and(|language| {
+            self.lsp.as_ref().is_some_and(|lsp| {
+                if direction.is_call() {
+                    lsp.call_hierarchy_supported(language)
+                } else {
+                    lsp.type_hierarchy_supported(language)
+                }
+            })
+        });
+        let Some(language) = language.filter(|_| supported) else {
+            let message = if direction.is_call() {
+                t!("hierarchy.call_unavailable")
+            } else {
+                t!("hierarchy.type_unavailable")
+            };
+            self.set_status_message(message.to_string());
+            return;
+        };
+
+        let state = self.active_state();
+        let (line, character) = state
+            .buffer
+            .position_to_lsp_position(state.cursors.primary().position);
+        let position = Position::new(line as u32, character as u32);
+        let request_id = self.next_lsp_request_id;
+        let sent = self
+            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
+                let result = if direction.is_call() {
+                    handle.prepare_call_hierarchy(request_id, uri.clone(), position)
+                } else {
+                    handle.prepare_type_hierarchy(request_id, uri.clone(), position)
+                };
+                result.is_ok()
+            })
+            .unwrap_or(false);
+        if sent {
+            self.next_lsp_request_id += 1;
+            self.pending_hierarchy = Some(PendingHierarchy {
+                request_id,
+                direction,
+                language,
+                source_split: self.split_manager.active_split(),
+            });
+        }
+    }
+
+    /// Open the panel with the call hierarchy items the server prepared
+    pub(super) fn handle_call_hierarchy_prepared(
+        &mut self,
+        request_id: u64,
+        items: Option<Vec<CallHierarchyItem>>,
+    ) {
+        let items = items.map(|items| items.into_iter().map(HierarchyItem::Call).collect());
+        self.open_hierarchy(request_id, items);
+    }
+
+    /// Add the callers or callees the server sent to the panel
+    pub(super) fn handle_call_hierarchy_calls(
+        &mut self,
+        request_id: u64,
+        calls: Option<Vec<(CallHierarchyItem, Vec<Range>)>>,
+    ) {
+        let children = calls.map(|calls| {
+            calls
+                .into_iter()
+                .map(|(item, ranges)| (HierarchyItem::Call(item), ranges))
+                .collect()
+        });
+        self.set_hierarchy_children(request_id, children);
+    }
+
+    /// Open the panel with the prepared type hierarchy items, or add the
+    /// supertypes or subtypes the server sent to it
+    pub(super) fn handle_type_hierarchy_items(
+        &mut self,
+        request_id: u64,
+        items: Option<Vec<TypeHierarchyItem>>,
+    ) {
+        if self.pending_hierarchy.as_ref().map(|p| p.request_id) == Some(request_id) {
+            let items = items.map(|items| items.into_iter().map(HierarchyItem::Type).collect());
+            self.open_hierarchy(request_id, items);
+        } else {
+            let children = items.map(|items| {
+                items
+                    .into_iter()
+                    .map(|item| (HierarchyItem::Type(item), Vec::new()))
+                    .collect()
+            });
+            self.set_hierarchy_children(request_id, children);
+        }
+    }
+
+    fn open_hierarchy(&mut self, request_id: u64, items: Option<Vec<HierarchyItem>>) {
+        if self.pending_hierarchy.as_ref().map(|p| p.request_id) != Some(request_id) {
+            tracing::debug!("Ignoring outdated hierarchy response {}", request_id);
+            return;
+        }
+        let Some(pending) = self.pending_hierarchy.take() else {
+            return;
+        };
+        let items = match items {
+            None => {
+                self.set_status_message(t!("hierarchy.failed").to_string());
+                return;
+            }
+            Some(items) if items.is_empty() => {
+                self.set_status_message(t!("hierarchy.no_symbol").to_string());
+                return;
+            }
+            Some(items) => items,
+        };
+
+        let existing = self
+            .hierarchy
+            .take()
+            .map(|panel| panel.buffer_id)
+            .filter(|buffer_id| self.buffers.contains_key(buffer_id));
+        let buffer_id = match existing {
+            Some(buffer_id) => buffer_id,
+            None => {
+                let buffer_id = self.create_virtual_buffer(
+                    HIERARCHY_BUFFER_NAME.to_string(),
+                    HIERARCHY_MODE.to_string(),
+                    true,
+                );
+                if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                    state.margins.set_line_numbers(false);
+                    state.editing_disabled = true;
+                }
+                buffer_id
+            }
+        };
+        self.hierarchy = Some(HierarchyPanel {
+            buffer_id,
+            source_split: pending.source_split,
+            language: pending.language,
+            direction: pending.direction,
+            root_count: items.len(),
+            nodes: items
+                .into_iter()
+                .map(|item| HierarchyNode::new(item, None, 0))
+                .collect(),
+            pending: HashMap::new(),
+            previewed: None,
+        });
+        self.expand_hierarchy_roots();
+
+        self.focus_hierarchy_panel(buffer_id);
+        self.render_hierarchy_panel();
+        let first_row = self.hierarchy_row_offset(0);
+        self.set_cursor_in_buffer(buffer_id, first_row);
+    }
+
+    /// Focus the panel, splitting it off next to the active split if it isn't visible
+    fn focus_hierarchy_panel(&mut self, buffer_id: BufferId) {
+        self.save_current_split_view_state();
+        if let Some(&split_id) = self.split_manager.splits_for_buffer(buffer_id).first() {
+            self.split_manager.set_active_split(split_id);
+        } else {
+            match self
+                .split_manager
+                .split_active(SplitDirection::Vertical, buffer_id, 0.5)
+            {
+                Ok(split_id) => {
+                    let mut view_state = SplitViewState::with_buffer(
+                        self.terminal_width,
+                        self.terminal_height,
+                        buffer_id,
+                    );
+                    view_state.viewport.line_wrap_enabled = false;
+                    self.split_view_states.insert(split_id, view_state);
+                    self.split_manager.set_active_split(split_id);
+                }
+                Err(e) => {
+                    self.set_status_message(t!("split.error", error = e).to_string());
+                }
+            }
+        }
+        self.restore_current_split_view_state();
+    }
+
+    /// Expand every root of the panel's tree
+    fn expand_hierarchy_roots(&mut self) {
+        let root_count = self.hierarchy.as_ref().map_or(0, |panel| panel.root_count);
+        for node in 0..root_count {
+            self.expand_hierarchy_node(node);
+        }
+    }
+
+    /// Expand a node, requesting its children if they aren't known yet
+    fn expand_hierarchy_node(&mut self, node: usize) {
+        let request_id = self.next_lsp_request_id;
+        let Some(panel) = &mut self.hierarchy else {
+            return;
+        };
+        let Some(tree_node) = panel.nodes.get_mut(node) else {
+            return;
+        };
+        tree_node.expanded = true;
+        if tree_node.children.is_some() || panel.pending.values().any(|&n| n == node) {
+            return;
+        }
+        let item = tree_node.item.clone();
+        let direction = panel.direction;
+        let handle = self
+            .lsp
+            .as_mut()
+            .and_then(|lsp| lsp.get_handle_mut(&panel.language));
+        let sent = handle.is_some_and(|handle| match item {
+            HierarchyItem::Call(item) => handle
+                .call_hierarchy_calls(request_id, item, direction == HierarchyDirection::Callers)
+                .is_ok(),
+            HierarchyItem::Type(item) => handle
+                .type_hierarchy_relatives(
+                    request_id,
+                    item,
+                    direction == HierarchyDirection::Supertypes,
+                )
+                .is_ok(),
+        });
+        if sent {
+            self.next_lsp_request_id += 1;
+            panel.pending.insert(request_id, node);
+        } else {
+            panel.nodes[node].children = Some(Vec::new());
+        }
+    }
+
+    /// Add the children the server sent for a node
+    fn set_hierarchy_children(
+        &mut self,
+        request_id: u64,
+        children: Option<Vec<(HierarchyItem, Vec<Range>)>>,
+    ) {
+        let Some(panel) = &mut self.hierarchy else {
+            return;
+        };
+        let Some(node) = panel.pending.remove(&request_id) else {
+            tracing::debug!("Ignoring outdated hierarchy children {}", request_id);
+            return;
+        };
+        let failed = children.is_none();
+        let depth = panel.nodes[node].depth + 1;
+        let mut indices = Vec::new();
+        for (item, ranges) in children.unwrap_or_default() {
+            // The ranges of outgoing calls are in the caller, not in the item
+            let target = if panel.direction == HierarchyDirection::Callers {
+                ranges.first().map(|range| range.start)
+            } else {
+                None
+            };
+            indices.push(panel.nodes.len());
+            panel.nodes.push(HierarchyNode::new(item, target, depth));
+        }
+        panel.nodes[node].children = Some(indices);
+        panel.previewed = None;
+
+        if failed {
+            self.set_status_message(t!("hierarchy.failed").to_string());
+        }
+        self.render_hierarchy_panel();
+    }
+
+    /// Expand or collapse the node under the panel's cursor
+    pub(crate) fn hierarchy_toggle_node(&mut self) {
+        let Some(node) = self.hierarchy_selection() else {
+            return;
+        };
+        let expanded = self
+            .hierarchy
+            .as_ref()
+            .is_some_and(|panel| panel.nodes[node].expanded);
+        if expanded {
+            if let Some(panel) = &mut self.hierarchy {
+                panel.nodes[node].expanded = false;
+            }
+        } else {
+            self.expand_hierarchy_node(node);
+        }
+        self.render_hierarchy_panel();
+    }
+
+    /// Switch between callers and callees, or supertypes and subtypes
+    pub(crate) fn hierarchy_toggle_direction(&mut self) {
+        let active_buffer = self.active_buffer();
+        let Some(panel) = &mut self.hierarchy else {
+            return;
+        };
+        if active_buffer != panel.buffer_id {
+            return;
+        }
+        panel.direction = panel.direction.toggled();
+        panel.nodes.truncate(panel.root_count);
+        for node in &mut panel.nodes {
+            node.children = None;
+            node.expanded = false;
+        }
+        panel.pending.clear();
+        panel.previewed = None;
+        let buffer_id = panel.buffer_id;
+        self.expand_hierarchy_roots();
+        self.render_hierarchy_panel();
+        let first_row = self.hierarchy_row_offset(0);
+        self.set_cursor_in_buffer(buffer_id, first_row);
+    }
+
+    /// Open the item under the panel's cursor in the split the hierarchy was
+    /// opened from
+    pub(crate) fn hierarchy_open(&mut self) {
+        let Some(node) = self.hierarchy_selection() else {
+            return;
+        };
+        let Some(panel) = &self.hierarchy else {
+            return;
+        };
+        let tree_node = &panel.nodes[node];
+        let target = tree_node.target;
+        let Ok(path) = uri_to_path(tree_node.item.uri()) else {
+            self.set_status_message(t!("lsp.cannot_open_definition").to_string());
+            return;
+        };
+        let source_split = panel.source_split;
+
+        self.save_current_split_view_state();
+        if self.split_view_states.contains_key(&source_split) {
+            self.split_manager.set_active_split(source_split);
+        }
+        self.restore_current_split_view_state();
+        let buffer_id = match self.open_file(&path) {
+            Ok(buffer_id) => buffer_id,
+            Err(e) => {
+                self.set_status_message(
+                    t!("file.error_opening", error = e.to_string()).to_string(),
+                );
+                return;
+            }
+        };
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        let position = state
+            .buffer
+            .lsp_position_to_byte(target.line as usize, target.character as usize);
+        self.move_cursor_and_reveal(self.split_manager.active_split(), position);
+    }
+
+    /// Update the preview when the row under the panel's cursor changed.
+    /// Cheap when nothing changed; called before every render.
+    pub(crate) fn sync_hierarchy_panel(&mut self) {
+        let Some(panel) = &self.hierarchy else {
+            return;
+        };
+        if !self.buffers.contains_key(&panel.buffer_id) {
+            self.hierarchy = None;
+            return;
+        }
+        let previewed = panel.previewed;
+        let selected = self.hierarchy_selection();
+        if selected.is_some() && selected != previewed {
+            self.render_hierarchy_panel();
+        }
+    }
+
+    /// Forget the hierarchy panel if its buffer is being closed
+    pub(crate) fn on_hierarchy_buffer_closed(&mut self, buffer_id: BufferId) {
+        if self.hierarchy.as_ref().map(|p| p.buffer_id) == Some(buffer_id) {
+            self.hierarchy = None;
+        }
+    }
+
+    /// Node shown on the panel row under the cursor
+    fn hierarchy_selection(&self) -> Option<usize> {
+        let buffer_id = self.hierarchy.as_ref()?.buffer_id;
+        let state = self.buffers.get(&buffer_id)?;
+        // The panel's cursor lives in its split's view state unless the panel is focused
+        let position = if self.active_buffer() == buffer_id {
+            state.cursors.primary().position
+        } else {
+            let split_id = *self.split_manager.splits_for_buffer(buffer_id).first()?;
+            self.split_view_states
+                .get(&split_id)?
+                .cursors
+                .primary()
+                .position
+        };
+        state
+            .text_properties
+            .get_at(position)
+            .iter()
+            .find_map(|p| p.get_as::<usize>(NODE_PROPERTY))
+    }
+
+    /// Byte offset of a node's row in the panel
+    fn hierarchy_row_offset(&self, node: usize) -> usize {
+        self.hierarchy
+            .as_ref()
+            .and_then(|panel| self.buffers.get(&panel.buffer_id))
+            .and_then(|state| {
+                state
+                    .text_properties
+                    .all()
+                    .iter()
+                    .find(|p| p.get_as::<usize>(NODE_PROPERTY) == Some(node))
+                    .map(|p| p.start)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Rebuild the panel content from its state
+    fn render_hierarchy_panel(&mut self) {
+        let selected = self.hierarchy_selection();
+        let Some(panel) = &self.hierarchy else {
+            return;
+        };
+        let buffer_id = panel.buffer_id;
+        let selected = selected.or((panel.root_count > 0).then_some(0));
+        let entries = self.hierarchy_content(panel, selected);
+        if let Err(e) = self.set_virtual_buffer_content(buffer_id, entries) {
+            tracing::error!("Failed to set hierarchy content: {}", e);
+            return;
+        }
+        if let Some(panel) = &mut self.hierarchy {
+            panel.previewed = selected;
+        }
+    }
+
+    /// Build the panel content: a header, the tree, then a preview of the
+    /// code of the `selected` node
+    fn hierarchy_content(
+        &self,
+        panel: &HierarchyPanel,
+        selected: Option<usize>,
+    ) -> Vec<TextPropertyEntry> {
+        let mut entries = Vec::new();
+        let names: Vec<&str> = panel.nodes[..panel.root_count]
+            .iter()
+            .map(|node| node.item.name())
+            .collect();
+        entries.push(TextPropertyEntry::text(format!(
+            "{}\n",
+            panel.direction.title(&names.join(", "))
+        )));
+        entries.push(TextPropertyEntry::text(format!(
+            "{}\n\n",
+            t!("hierarchy.help")
+        )));
+
+        // Tree rows, depth first
+        let mut stack: Vec<usize> = (0..panel.root_count).rev().collect();
+        while let Some(node) = stack.pop() {
+            let tree_node = &panel.nodes[node];
+            let marker = match &tree_node.children {
+                Some(children) if children.is_empty() => ' ',
+                _ if tree_node.expanded => '▾',
+                _ => '▸',
+            };
+            let mut row = format!(
+                "{}{} {} {}",
+                "  ".repeat(tree_node.depth),
+                marker,
+                kind_icon(tree_node.item.kind()),
+                tree_node.item.name()
+            );
+            if let Some(detail) = tree_node.item.detail().filter(|d| !d.is_empty()) {
+                row.push_str(&format!("  {}", detail));
+            }
+            row.push_str(&format!(
+                "  {}",
+                self.hierarchy_location(tree_node.item.uri(), tree_node.target)
+            ));
+            if tree_node.expanded && tree_node.children.is_none() {
+                row.push_str(&format!("  {}", t!("hierarchy.loading")));
+            }
+            entries.push(
+                TextPropertyEntry::text(format!("{row}\n"))
+                    .with_property(NODE_PROPERTY, serde_json::json!(node)),
+            );
+            if tree_node.expanded {
+                if let Some(children) = &tree_node.children {
+                    stack.extend(children.iter().rev());
+                }
+            }
+        }
+
+        // Preview of the selected row's code
+        let Some(tree_node) = selected.and_then(|node| panel.nodes.get(node)) else {
+            return entries;
+        };
+        let uri = tree_node.item.uri();
+        let target = tree_node.target;
+        entries.push(TextPropertyEntry::text(format!(
+            "\n── {} ──\n",
+            self.hierarchy_location(uri, target)
+        )));
+        let target_line = target.line as usize;
+        let first = target_line.saturating_sub(PREVIEW_LINES_BEFORE);
+        let lines = uri_to_path(uri).ok().and_then(|path| {
+            self.file_lines_for_preview(&path, first..target_line + PREVIEW_LINES_AFTER + 1)
+        });
+        let Some(lines) = lines else {
+            entries.push(TextPropertyEntry::text(format!(
+                "{}\n",
+                t!("hierarchy.preview_unavailable")
+            )));
+            return entries;
+        };
+        for (index, line) in (first..).zip(lines) {
+            let marker = if index == target_line { '▶' } else { ' ' };
+            entries.push(TextPropertyEntry::text(format!(
+                "{marker}{:>5} │ {line}\n",
+                index + 1
+            )));
+        }
+        entries
+    }
+
+    /// "path:line" of a position, relative to the working directory
+    fn hierarchy_location(&self, uri: &Uri, position: Position) -> String {
+        match uri_to_path(uri) {
+            Ok(path) => {
+                let path = path.strip_prefix(&self.working_dir).unwrap_or(&path);
+                format!("{}:{}", path.display(), position.line + 1)
+            }
+            Err(_) => format!("{}:{}", uri.as_str(), position.line + 1),
+        }
+    }
+
+    /// The lines of a file in `lines` for previews (fewer at its end): from
+    /// its buffer if it is open, otherwise read from disk up to the last line
+    pub(super) fn file_lines_for_preview(
+        &self,
+        path: &Path,
+        lines: std::ops::Range<usize>,
+    ) -> Option<Vec<String>> {
+        let open_buffer = self.buffer_metadata.iter().find_map(|(id, metadata)| {
+            (metadata.file_path().map(PathBuf::as_path) == Some(path)).then_some(*id)
+        });
+        match open_buffer.and_then(|id| self.buffers.get(&id)) {
+            Some(state) if !state.buffer.is_large_file() => Some(
+                lines
+                    .map_while(|line| state.buffer.get_line(line))
+                    .map(|line| {
+                        String::from_utf8_lossy(&line)
+                            .trim_end_matches(['\n', '\r'])
+                            .to_string()
+                    })
+                    .collect(),
+            ),
+            Some(_) => None,
+            None => {
+                let file = std::fs::File::open(path).ok()?;
+                BufReader::new(file)
+                    .lines()
+                    .skip(lines.start)
+                    .take(lines.len())
+                    .collect::<std::io::Result<_>>()
+                    .ok()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction_toggles_within_hierarchy() {
+        use HierarchyDirection::*;
+        assert_eq!(Callers.toggled(), Callees);
+        assert_eq!(Callees.toggled(), Callers);
+        assert_eq!(Supertypes.toggled(), Subtypes);
+        assert_eq!(Subtypes.toggled(), Supertypes);
+        assert!(Callees.is_call());
+        assert!(!Subtypes.is_call());
+    }
+
+    #[test]
+    fn test_node_targets_item_name_by_default() {
+        let item = CallHierarchyItem {
+            name: "parse".to_string(),
+            kind: SymbolKind::FUNCTION,
+            tags: None,
+            detail: None,
+            uri: "file:///tmp/a.rs".parse().unwrap(),
+            range: Range::new(Position::new(3, 0), Position::new(9, 1)),
+            selection_range: Range::new(Position::new(3, 7), Position::new(3, 12)),
+            data: None,
+        };
+        let node = HierarchyNode::new(HierarchyItem::Call(item.clone()), None, 0);
+        assert_eq!(node.target, Position::new(3, 7));
+        let call_site = Some(Position::new(20, 4));
+        let node = HierarchyNode::new(HierarchyItem::Call(item), call_site, 1);
+        assert_eq!(node.target, Position::new(20, 4));
+    }
+}
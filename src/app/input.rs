@@ -80,6 +80,13 @@ impl Editor {
             return Ok(());
         }
 
+        // The outline panel handles navigation and filter keys itself
+        if context == crate::input::keybindings::KeyContext::Outline
+            && self.handle_outline_key(&key_event)
+        {
+            return Ok(());
+        }
+
         // Only check buffer mode keybindings if we're not in a higher-priority context
         // (Menu, Prompt, Popup should take precedence over mode bindings)
         let should_check_mode_bindings = matches!(
@@ -193,6 +200,9 @@ impl Editor {
         match action {
             Action::LspCompletion
             | Action::LspGotoDefinition
+            | Action::LspGotoTypeDefinition
+            | Action::LspGotoImplementation
+            | Action::LspGotoDeclaration
             | Action::LspReferences
             | Action::LspHover
             | Action::None => {
@@ -217,6 +227,11 @@ impl Editor {
         // Record action to macro if recording
         self.record_macro_action(&action);
 
+        // Splits in hex view move and edit by byte
+        if self.key_context == KeyContext::Normal && self.handle_hex_view_action(&action) {
+            return Ok(());
+        }
+
         match action {
             Action::Quit => self.quit(),
             Action::Save => {
@@ -311,6 +326,9 @@ impl Editor {
             Action::ToggleAutoRevert => {
                 self.toggle_auto_revert();
             }
+            Action::ToggleFollow => {
+                self.toggle_follow();
+            }
             Action::FormatBuffer => {
                 if let Err(e) = self.format_buffer() {
                     self.set_status_message(
@@ -318,6 +336,13 @@ impl Editor {
                     );
                 }
             }
+            Action::FormatSelection => {
+                if let Err(e) = self.format_selection() {
+                    self.set_status_message(
+                        t!("error.format_failed", error = e.to_string()).to_string(),
+                    );
+                }
+            }
             Action::Copy => {
                 // Check if active buffer is a composite buffer
                 let buffer_id = self.active_buffer();
@@ -353,6 +378,27 @@ impl Editor {
             Action::Redo => {
                 self.handle_redo();
             }
+            Action::UndoChronological => {
+                self.handle_undo_chronological();
+            }
+            Action::RedoChronological => {
+                self.handle_redo_chronological();
+            }
+            Action::ShowUndoTree => {
+                self.show_undo_tree();
+            }
+            Action::UndoTreeRestore => {
+                self.handle_undo_tree_restore();
+            }
+            Action::FileHistory => {
+                self.show_file_history();
+            }
+            Action::FileHistoryRestore => {
+                self.handle_file_history_restore();
+            }
+            Action::FileHistoryRestoreHunk => {
+                self.handle_file_history_restore_hunk();
+            }
             Action::ShowHelp => {
                 self.open_help_manual();
             }
@@ -396,6 +442,15 @@ impl Editor {
                     suggestions,
                 );
             }
+            Action::ToggleHexView => {
+                self.toggle_hex_view();
+            }
+            Action::HexSearch => {
+                self.start_prompt(
+                    t!("hex_view.search_prompt").to_string(),
+                    PromptType::HexSearch,
+                );
+            }
             Action::ToggleLineWrap => {
                 self.config.editor.line_wrap = !self.config.editor.line_wrap;
 
@@ -411,6 +466,24 @@ impl Editor {
                 };
                 self.set_status_message(t!("view.line_wrap_state", state = state).to_string());
             }
+            Action::ToggleStickyScroll => {
+                self.config.editor.sticky_scroll = !self.config.editor.sticky_scroll;
+                let state = if self.config.editor.sticky_scroll {
+                    t!("view.state_enabled").to_string()
+                } else {
+                    t!("view.state_disabled").to_string()
+                };
+                self.set_status_message(t!("view.sticky_scroll_state", state = state).to_string());
+            }
+            Action::ToggleBreadcrumbs => {
+                self.config.editor.breadcrumbs = !self.config.editor.breadcrumbs;
+                let state = if self.config.editor.breadcrumbs {
+                    t!("view.state_enabled").to_string()
+                } else {
+                    t!("view.state_disabled").to_string()
+                };
+                self.set_status_message(t!("view.breadcrumbs_state", state = state).to_string());
+            }
             Action::ToggleComposeMode => {
                 self.handle_toggle_compose_mode();
             }
@@ -455,8 +528,20 @@ impl Editor {
             Action::LspCompletion => {
                 self.request_completion()?;
             }
+            Action::InsertSnippet => self.show_insert_snippet(),
+            Action::InsertTab if self.snippet_session_active() => self.snippet_next_tabstop(),
+            Action::DedentSelection if self.snippet_session_active() => self.snippet_prev_tabstop(),
             Action::LspGotoDefinition => {
-                self.request_goto_definition()?;
+                self.request_goto(GotoKind::Definition)?;
+            }
+            Action::LspGotoTypeDefinition => {
+                self.request_goto(GotoKind::TypeDefinition)?;
+            }
+            Action::LspGotoImplementation => {
+                self.request_goto(GotoKind::Implementation)?;
+            }
+            Action::LspGotoDeclaration => {
+                self.request_goto(GotoKind::Declaration)?;
             }
             Action::LspRename => {
                 self.start_rename()?;
@@ -467,6 +552,13 @@ impl Editor {
             Action::LspReferences => {
                 self.request_references()?;
             }
+            Action::LspWorkspaceSymbol => self.show_workspace_symbol_picker(),
+            Action::LspCodeLens => self.show_code_lens_picker(),
+            Action::ShowCallHierarchy => self.show_call_hierarchy(),
+            Action::ShowTypeHierarchy => self.show_type_hierarchy(),
+            Action::HierarchyOpen => self.hierarchy_open(),
+            Action::HierarchyToggleNode => self.hierarchy_toggle_node(),
+            Action::HierarchyToggleDirection => self.hierarchy_toggle_direction(),
             Action::LspSignatureHelp => {
                 self.request_signature_help()?;
             }
@@ -535,6 +627,27 @@ impl Editor {
                     false,
                 );
             }
+            Action::ProjectSearch => {
+                self.start_project_search_prompt(PromptType::ProjectSearch);
+            }
+            Action::ProjectReplace => {
+                self.start_project_replace();
+            }
+            Action::ProjectSearchOpen => {
+                self.project_search_open_match();
+            }
+            Action::ProjectSearchToggle => {
+                self.project_search_toggle_match();
+            }
+            Action::ProjectSearchFilter => {
+                self.start_project_search_filter_prompt();
+            }
+            Action::ProjectSearchApply => {
+                self.apply_project_replace();
+            }
+            Action::UndoProjectReplace => {
+                self.undo_project_replace();
+            }
             Action::FindInSelection => {
                 self.start_search_prompt(
                     t!("file.search_prompt").to_string(),
@@ -600,12 +713,27 @@ impl Editor {
             Action::DecreaseSplitSize => self.adjust_split_size(-0.05),
             Action::ToggleMaximizeSplit => self.toggle_maximize_split(),
             Action::ToggleFileExplorer => self.toggle_file_explorer(),
+            Action::ToggleOutline => self.toggle_outline(),
+            Action::FocusOutline => self.focus_outline(),
+            Action::PickSiblingSymbol => self.pick_sibling_symbol(),
             Action::ToggleMenuBar => self.toggle_menu_bar(),
             Action::ToggleTabBar => self.toggle_tab_bar(),
             Action::ToggleLineNumbers => self.toggle_line_numbers(),
             Action::ToggleMouseCapture => self.toggle_mouse_capture(),
             Action::ToggleMouseHover => self.toggle_mouse_hover(),
             Action::ToggleDebugHighlights => self.toggle_debug_highlights(),
+            Action::ExpandSelection => {
+                // Wait for the language server's selection ranges if it has them
+                if !self.request_selection_ranges() {
+                    self.apply_action_as_events(action)?;
+                }
+            }
+            Action::Fold => self.fold_at_cursor(),
+            Action::Unfold => self.unfold_at_cursor(),
+            Action::ToggleFold => self.toggle_fold_at_cursor(),
+            Action::FoldAll => self.fold_all(),
+            Action::UnfoldAll => self.unfold_all(),
+            Action::FoldLevel => self.start_fold_level_prompt(),
             // Buffer settings
             Action::SetTabSize => {
                 let current = self
@@ -622,6 +750,15 @@ impl Editor {
             Action::SetLineEnding => {
                 self.start_set_line_ending_prompt();
             }
+            Action::ReopenWithEncoding => {
+                self.start_encoding_prompt(PromptType::ReopenWithEncoding);
+            }
+            Action::SaveWithEncoding => {
+                self.start_encoding_prompt(PromptType::SaveWithEncoding);
+            }
+            Action::StopLineIndexing => {
+                self.stop_line_indexing(self.active_buffer());
+            }
             Action::ToggleIndentationStyle => {
                 if let Some(state) = self.buffers.get_mut(&self.active_buffer()) {
                     state.use_tabs = !state.use_tabs;
@@ -662,6 +799,10 @@ impl Editor {
             Action::FileExplorerToggleHidden => self.file_explorer_toggle_hidden(),
             Action::FileExplorerToggleGitignored => self.file_explorer_toggle_gitignored(),
             Action::RemoveSecondaryCursors => {
+                // Escape also cancels a search running in the background
+                // and ends snippet tabstop navigation
+                self.cancel_background_search();
+                self.end_snippet_session();
                 // Convert action to events and apply them
                 if let Some(events) = self.action_to_events(Action::RemoveSecondaryCursors) {
                     // Wrap in batch for atomic undo
@@ -1121,6 +1262,10 @@ impl Editor {
                     self.update_prompt_suggestions();
                 }
             }
+            Action::InsertNewline => {
+                self.apply_action_as_events(action)?;
+                self.maybe_request_on_type_formatting('\n');
+            }
             _ => {
                 // TODO: Why do we have this catch-all? It seems like actions should either:
                 // 1. Be handled explicitly above (like InsertChar, PopupConfirm, etc.)
@@ -1189,6 +1334,19 @@ impl Editor {
         let active_split = self.split_manager.active_split();
         let buffer_id = self.active_buffer();
 
+        // Hex view scrolls by rows of bytes
+        let buffer_len = self.buffers.get(&buffer_id).map(|s| s.buffer.len());
+        if let Some(hex_view) = self
+            .split_view_states
+            .get_mut(&active_split)
+            .and_then(|vs| vs.hex_view.as_mut())
+            .filter(|hex| hex.buffer_id == buffer_id)
+        {
+            let max_row = crate::view::hex_view::row_count(buffer_len.unwrap_or(0)) - 1;
+            hex_view.scroll(delta as isize, max_row);
+            return Ok(());
+        }
+
         // Check if this is a composite buffer - if so, use composite scroll
         if self.is_composite_buffer(buffer_id) {
             let max_row = self
@@ -1218,10 +1376,10 @@ impl Editor {
             .map(|vt| vt.tokens.clone());
 
         // Get mutable references to both buffer and view state
-        let buffer = self.buffers.get_mut(&buffer_id).map(|s| &mut s.buffer);
+        let state = self.buffers.get_mut(&buffer_id);
         let view_state = self.split_view_states.get_mut(&active_split);
 
-        if let (Some(buffer), Some(view_state)) = (buffer, view_state) {
+        if let (Some(state), Some(view_state)) = (state, view_state) {
             let top_byte_before = view_state.viewport.top_byte;
             if let Some(tokens) = view_transform_tokens {
                 // Use view-aware scrolling with the transform's tokens
@@ -1234,15 +1392,13 @@ impl Editor {
                     .scroll_view_lines(&view_lines, delta as isize);
             } else {
                 // No view transform - use traditional buffer-based scrolling
-                if delta < 0 {
-                    // Scroll up
-                    let lines_to_scroll = delta.abs() as usize;
-                    view_state.viewport.scroll_up(buffer, lines_to_scroll);
-                } else {
-                    // Scroll down
-                    let lines_to_scroll = delta as usize;
-                    view_state.viewport.scroll_down(buffer, lines_to_scroll);
-                }
+                let folded = state.folds.folded_regions(&state.buffer);
+                scroll_viewport(
+                    &mut view_state.viewport,
+                    &mut state.buffer,
+                    &folded,
+                    delta as isize,
+                );
             }
             // Skip ensure_visible so the scroll position isn't undone during render
             view_state.viewport.set_skip_ensure_visible();
@@ -1718,6 +1874,12 @@ impl Editor {
             self.key_context = crate::input::keybindings::KeyContext::Normal;
         }
 
+        // Clicking the title of a code lens runs its command
+        if let Some(command) = self.code_lens_at(split_id, buffer_id, col, row, content_rect) {
+            self.run_code_lens_command(buffer_id, command);
+            return Ok(());
+        }
+
         // Get cached view line mappings for this split (before mutable borrow of buffers)
         let cached_mappings = self
             .cached_layout
@@ -1748,6 +1910,15 @@ impl Editor {
                 return Ok(());
             };
 
+            // Clicking the fold marker in the gutter toggles the fold
+            let fold_column = state.margins.left_config.fold_marker_column();
+            if fold_column.is_some_and(|c| col.saturating_sub(content_rect.x) as usize == c) {
+                let line = state.buffer.get_line_number(target_position);
+                if super::folding::toggle_fold_at_line(state, line) {
+                    return Ok(());
+                }
+            }
+
             // Check for onClick text property at this position
             // This enables clickable UI elements in virtual buffers
             let onclick_action = state
@@ -1792,6 +1963,14 @@ impl Editor {
             }
             state.apply(&event);
 
+            // A sticky scroll header maps to a line above the view: scroll to it
+            // even if the view was just scrolled with the mouse wheel
+            if target_position < fallback {
+                if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+                    view_state.viewport.clear_skip_ensure_visible();
+                }
+            }
+
             // Track position history
             if !self.in_navigation {
                 self.position_history
@@ -1914,6 +2093,57 @@ impl Editor {
         }
     }
 
+    /// Start the encoding selection prompt for reopening or saving the buffer
+    fn start_encoding_prompt(&mut self, prompt_type: PromptType) {
+        use crate::model::encoding::Encoding;
+
+        let current_encoding = self.active_state().buffer.encoding();
+
+        let current_index = Encoding::ALL
+            .iter()
+            .position(|enc| *enc == current_encoding)
+            .unwrap_or(0);
+
+        let suggestions: Vec<crate::input::commands::Suggestion> = Encoding::ALL
+            .iter()
+            .map(|enc| {
+                let is_current = *enc == current_encoding;
+                crate::input::commands::Suggestion {
+                    text: format!("{} ({})", enc.display_name(), enc.description()),
+                    description: if is_current {
+                        Some("current".to_string())
+                    } else {
+                        None
+                    },
+                    value: Some(enc.display_name().to_string()),
+                    disabled: false,
+                    keybinding: None,
+                    source: None,
+                }
+            })
+            .collect();
+
+        let message = if matches!(prompt_type, PromptType::ReopenWithEncoding) {
+            t!("prompt.reopen_with_encoding").to_string()
+        } else {
+            t!("prompt.save_with_encoding").to_string()
+        };
+
+        self.prompt = Some(crate::view::prompt::Prompt::with_suggestions(
+            message,
+            prompt_type,
+            suggestions,
+        ));
+
+        if let Some(prompt) = self.prompt.as_mut() {
+            if !prompt.suggestions.is_empty() {
+                prompt.selected_suggestion = Some(current_index);
+                prompt.input = prompt.suggestions[current_index].text.clone();
+                prompt.cursor_pos = prompt.input.len();
+            }
+        }
+    }
+
     /// Start the theme selection prompt with available themes
     fn start_select_theme_prompt(&mut self) {
         let available_themes = crate::view::theme::Theme::available_themes();
@@ -2515,6 +2745,9 @@ impl Editor {
         // Auto-trigger completion on trigger characters
         self.maybe_trigger_completion(c);
 
+        // Let the language server reformat around its trigger characters
+        self.maybe_request_on_type_formatting(c);
+
         Ok(())
     }
 
@@ -2523,7 +2756,7 @@ impl Editor {
     /// This is the catch-all handler for actions that can be converted to buffer events
     /// (cursor movements, text edits, etc.). It handles batching for multi-cursor,
     /// position history tracking, and editing permission checks.
-    fn apply_action_as_events(&mut self, action: Action) -> AnyhowResult<()> {
+    pub(super) fn apply_action_as_events(&mut self, action: Action) -> AnyhowResult<()> {
         // Check if active buffer is a composite buffer - handle scroll/movement specially
         let buffer_id = self.active_buffer();
         if self.is_composite_buffer(buffer_id) {
@@ -0,0 +1,347 @@
+//! Background search of large files
+//!
+//! Confirming a search in a large file starts a [`SearchTask`] over a snapshot
+//! of the buffer instead of loading the whole file. The cursor jumps to the
+//! first match after it as soon as it is found, and the status bar shows how
+//! much of the file has been searched until the total match count is known.
+//! Escape or a new search cancels the task.
+//!
+//! A common pattern can match hundreds of millions of times in a multi-gigabyte
+//! file, so matches are not all turned into overlays: their positions are kept
+//! in sorted vectors (up to `MAX_REPORTED_MATCHES`, later ones are only
+//! counted) and only those inside the viewport are highlighted, as it scrolls.
+
+use std::ops::Range;
+
+use rust_i18n::t;
+
+use crate::model::buffer::LineNumber;
+use crate::model::event::BufferId;
+use crate::services::buffer_search::SearchTask;
+
+use super::types::{SearchState, ViewportMatches};
+use super::Editor;
+
+/// Format a byte count as megabytes with one decimal
+fn megabytes(bytes: usize) -> String {
+    format!("{:.1}", bytes as f64 / (1024.0 * 1024.0))
+}
+
+impl Editor {
+    /// Start searching the active (large) buffer in the background
+    pub(super) fn start_background_search(
+        &mut self,
+        query: &str,
+        regex_pattern: &str,
+        case_sensitive: bool,
+        search_range: Option<Range<usize>>,
+    ) {
+        let regex = match regex::bytes::RegexBuilder::new(regex_pattern)
+            .case_insensitive(!case_sensitive)
+            .build()
+        {
+            Ok(r) => r,
+            Err(e) => {
+                self.search_state = None;
+                self.set_status_message(
+                    t!("error.invalid_regex", error = e.to_string()).to_string(),
+                );
+                return;
+            }
+        };
+
+        let buffer_id = self.active_buffer();
+        let ns = self.search_namespace.clone();
+        let state = self.active_state_mut();
+        state.overlays.clear_namespace(&ns, &mut state.marker_list);
+        let range = search_range.clone().unwrap_or(0..state.buffer.len());
+        let version = state.buffer.version();
+        let snapshot = state.buffer.content_snapshot();
+
+        let (Some(runtime), Some(bridge)) = (&self.tokio_runtime, &self.async_bridge) else {
+            return;
+        };
+        tracing::debug!(
+            "Searching {} bytes of buffer {:?} in the background",
+            range.len(),
+            buffer_id
+        );
+        self.search_task = Some(SearchTask::spawn(
+            runtime,
+            bridge.sender(),
+            buffer_id,
+            version,
+            query.to_string(),
+            snapshot,
+            regex,
+            range,
+        ));
+
+        // Matches are filled in as the search progresses
+        self.search_state = Some(SearchState {
+            query: query.to_string(),
+            matches: Vec::new(),
+            current_match_index: None,
+            wrap_search: search_range.is_none(),
+            search_range,
+            viewport_matches: Some(ViewportMatches {
+                buffer_id,
+                version,
+                lengths: Vec::new(),
+                total: 0,
+                highlighted: None,
+            }),
+        });
+        self.set_search_progress_status();
+    }
+
+    /// Cancel the background search, keeping the matches found so far.
+    ///
+    /// Returns whether a search was running.
+    pub(crate) fn cancel_background_search(&mut self) -> bool {
+        if self.search_task.take().is_none() {
+            return false;
+        }
+        if self
+            .search_state
+            .as_ref()
+            .is_some_and(|search| search.matches.is_empty())
+        {
+            self.search_state = None;
+        }
+        self.set_status_message(t!("search.cancelled").to_string());
+        true
+    }
+
+    /// Record a batch of matches from the background search
+    pub(super) fn handle_search_progress(
+        &mut self,
+        task_id: u64,
+        matches: Vec<(usize, usize)>,
+        match_count: usize,
+        bytes_searched: usize,
+    ) {
+        // Ignore batches of cancelled or replaced searches
+        let Some(task) = self.search_task.as_mut().filter(|task| task.id == task_id) else {
+            return;
+        };
+        task.bytes_searched = bytes_searched;
+        let buffer_id = task.buffer_id;
+        let version = task.version;
+
+        // Match positions are only valid for the content the search started on
+        let still_valid = buffer_id == self.active_buffer()
+            && self
+                .buffers
+                .get(&buffer_id)
+                .is_some_and(|state| state.buffer.version() == version);
+        if !still_valid {
+            self.search_task = None;
+            self.search_state = None;
+            self.clear_search_overlays_in(buffer_id);
+            self.set_status_message(t!("search.interrupted").to_string());
+            return;
+        }
+
+        if match_count > 0 {
+            let cursor_pos = self.active_state().cursors.primary().position;
+            let mut jump_to = None;
+            if let Some(search_state) = self.search_state.as_mut() {
+                let first_new = search_state.matches.len();
+                search_state
+                    .matches
+                    .extend(matches.iter().map(|&(pos, _)| pos));
+                if let Some(viewport_matches) = search_state.viewport_matches.as_mut() {
+                    viewport_matches
+                        .lengths
+                        .extend(matches.iter().map(|&(_, len)| len));
+                    viewport_matches.total += match_count;
+                    // Highlight new matches that are already in view
+                    viewport_matches.highlighted = None;
+                }
+                // Jump to the first match at or after the cursor as soon as it is found
+                if search_state.current_match_index.is_none() {
+                    if let Some(offset) = matches.iter().position(|&(pos, _)| pos >= cursor_pos) {
+                        search_state.current_match_index = Some(first_new + offset);
+                        jump_to = Some(matches[offset].0);
+                    }
+                }
+            }
+            if let Some(pos) = jump_to {
+                self.move_cursor_to_match(pos);
+            }
+        }
+
+        self.set_search_progress_status();
+    }
+
+    /// Report the match count once the background search is done
+    pub(super) fn handle_search_complete(&mut self, task_id: u64) {
+        let Some(task) = self.search_task.take_if(|task| task.id == task_id) else {
+            return;
+        };
+        let Some(search_state) = self.search_state.as_mut() else {
+            return;
+        };
+        let in_selection = search_state.search_range.is_some();
+        let num_matches = Self::search_match_total(search_state);
+        if num_matches == 0 {
+            self.search_state = None;
+            self.set_status_message(Self::search_not_found_message(&task.query, in_selection));
+            return;
+        }
+
+        // No match after the cursor, so wrap around to the first one
+        if search_state.current_match_index.is_none() {
+            search_state.current_match_index = Some(0);
+            let pos = search_state.matches[0];
+            self.move_cursor_to_match(pos);
+        }
+        self.set_status_message(Self::search_found_message(
+            num_matches,
+            &task.query,
+            in_selection,
+        ));
+    }
+
+    /// Report a background search that could not read the buffer
+    pub(super) fn handle_search_failed(&mut self, task_id: u64, error: String) {
+        if self
+            .search_task
+            .take_if(|task| task.id == task_id)
+            .is_some()
+        {
+            tracing::warn!("Background search failed: {}", error);
+            self.set_status_message(t!("search.failed", error = error).to_string());
+        }
+    }
+
+    /// Show how far the background search has got
+    fn set_search_progress_status(&mut self) {
+        let Some(task) = &self.search_task else {
+            return;
+        };
+        let found = self
+            .search_state
+            .as_ref()
+            .map_or(0, Self::search_match_total);
+        let message = t!(
+            "search.progress",
+            query = task.query,
+            searched = megabytes(task.bytes_searched),
+            total = megabytes(task.total_bytes()),
+            matches = found
+        )
+        .to_string();
+        self.set_status_message(message);
+    }
+
+    /// Number of matches found, including those whose positions are not stored
+    fn search_match_total(search_state: &SearchState) -> usize {
+        search_state
+            .viewport_matches
+            .as_ref()
+            .map_or(search_state.matches.len(), |viewport| viewport.total)
+    }
+
+    /// Highlight the background search matches inside the active split's viewport
+    pub(super) fn sync_search_viewport_highlights(&mut self) {
+        let Some(search_state) = self.search_state.as_mut() else {
+            return;
+        };
+        let Some(viewport_matches) = search_state.viewport_matches.as_mut() else {
+            return;
+        };
+        let active_split = self.split_manager.active_split();
+        if self.split_manager.get_buffer_id(active_split) != Some(viewport_matches.buffer_id) {
+            return;
+        }
+        let Some(viewport) = self
+            .split_view_states
+            .get(&active_split)
+            .map(|view_state| &view_state.viewport)
+        else {
+            return;
+        };
+        let Some(state) = self.buffers.get_mut(&viewport_matches.buffer_id) else {
+            return;
+        };
+        let ns = &self.search_namespace;
+
+        // Positions are only valid for the content the search ran on
+        if state.buffer.version() != viewport_matches.version {
+            if viewport_matches.highlighted.take().is_some() {
+                state.overlays.clear_namespace(ns, &mut state.marker_list);
+            }
+            return;
+        }
+
+        let top_byte = viewport.top_byte;
+        let mut visible_end = top_byte;
+        let mut lines = state.buffer.line_iterator(top_byte, 80);
+        for _ in 0..viewport.height {
+            match lines.next() {
+                Some((line_start, line_content)) => {
+                    visible_end = line_start + line_content.len();
+                }
+                None => break,
+            }
+        }
+        let visible = top_byte..visible_end;
+        if viewport_matches.highlighted.as_ref() == Some(&visible) {
+            return;
+        }
+
+        state.overlays.clear_namespace(ns, &mut state.marker_list);
+        let starts = &search_state.matches;
+        let lengths = &viewport_matches.lengths;
+        // Matches don't overlap, so their ends are sorted like their starts
+        let mut first = starts.partition_point(|&pos| pos < visible.start);
+        if first > 0 && starts[first - 1] + lengths[first - 1] > visible.start {
+            first -= 1;
+        }
+        let last = starts.partition_point(|&pos| pos < visible.end);
+        let search_style = ratatui::style::Style::default()
+            .fg(self.theme.search_match_fg)
+            .bg(self.theme.search_match_bg);
+        for (&match_pos, &match_len) in starts[first..last].iter().zip(&lengths[first..last]) {
+            let overlay = crate::view::overlay::Overlay::with_namespace(
+                &mut state.marker_list,
+                match_pos..(match_pos + match_len),
+                crate::view::overlay::OverlayFace::Style {
+                    style: search_style,
+                },
+                ns.clone(),
+            )
+            .with_priority_value(10);
+            state.overlays.add(overlay);
+        }
+        viewport_matches.highlighted = Some(visible);
+    }
+
+    /// Remove search highlights from a buffer that may no longer be active
+    fn clear_search_overlays_in(&mut self, buffer_id: BufferId) {
+        let ns = self.search_namespace.clone();
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.overlays.clear_namespace(&ns, &mut state.marker_list);
+        }
+    }
+
+    /// Move the primary cursor to a match and scroll it into view
+    fn move_cursor_to_match(&mut self, pos: usize) {
+        let active_split = self.split_manager.active_split();
+        let active_buffer = self.active_buffer();
+        let state = self.active_state_mut();
+        state.cursors.primary_mut().position = pos;
+        state.cursors.primary_mut().anchor = None;
+        if let Some(position) = state.buffer.offset_to_position(pos) {
+            state.primary_cursor_line_number = LineNumber::Absolute(position.line);
+        }
+        if let Some(view_state) = self.split_view_states.get_mut(&active_split) {
+            let state = self.buffers.get_mut(&active_buffer).unwrap();
+            view_state
+                .viewport
+                .ensure_visible(&mut state.buffer, state.cursors.primary());
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Background line indexing of large files
+//!
+//! Large files open without line metadata, so line numbers start out estimated.
+//! Opening or reverting one starts a [`LineIndexTask`] that scans the file and
+//! reports line feed counts chunk by chunk. Once every chunk is recorded in the
+//! piece tree, the gutter, Go To Line and the status bar show exact lines.
+
+use rust_i18n::t;
+
+use crate::model::buffer::LineNumber;
+use crate::model::event::BufferId;
+use crate::services::line_indexer::LineIndexTask;
+
+use super::Editor;
+
+impl Editor {
+    /// Start indexing the lines of a large file, replacing any running scan
+    pub(crate) fn start_line_indexing(&mut self, buffer_id: BufferId) {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        state.line_indexing = None;
+        if state.buffer.is_binary() {
+            return;
+        }
+        let Some(source) = state.buffer.line_index_source().cloned() else {
+            return;
+        };
+        if let (Some(runtime), Some(bridge)) = (&self.tokio_runtime, &self.async_bridge) {
+            tracing::debug!(
+                "Indexing lines of {} ({} bytes)",
+                source.path.display(),
+                source.len
+            );
+            state.line_indexing = Some(LineIndexTask::spawn(
+                runtime,
+                bridge.sender(),
+                buffer_id,
+                source,
+            ));
+        }
+    }
+
+    /// Stop indexing the lines of a buffer, keeping the chunks indexed so far
+    pub(crate) fn stop_line_indexing(&mut self, buffer_id: BufferId) {
+        let stopped = self
+            .buffers
+            .get_mut(&buffer_id)
+            .and_then(|state| state.line_indexing.take())
+            .is_some();
+        self.status_message = Some(if stopped {
+            t!("status.line_indexing_stopped").to_string()
+        } else {
+            t!("status.no_line_indexing").to_string()
+        });
+    }
+
+    /// Record a batch of line feed counts from a line indexing task
+    pub(super) fn handle_line_index_progress(
+        &mut self,
+        buffer_id: BufferId,
+        task_id: u64,
+        first_chunk: usize,
+        counts: Vec<usize>,
+        bytes_scanned: usize,
+    ) {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        // Ignore batches of cancelled or replaced tasks
+        let Some(task) = state
+            .line_indexing
+            .as_mut()
+            .filter(|task| task.id == task_id)
+        else {
+            return;
+        };
+        task.bytes_scanned = bytes_scanned;
+        let source = task.source.clone();
+
+        if let Err(e) = state
+            .buffer
+            .apply_line_feed_counts(&source, first_chunk, &counts)
+        {
+            state.line_indexing = None;
+            self.status_message =
+                Some(t!("status.line_index_failed", error = e.to_string()).to_string());
+            return;
+        }
+
+        if state.buffer.line_index_source().is_some() {
+            return;
+        }
+
+        // Every chunk is indexed (or the source went away, e.g. saved over)
+        state.line_indexing = None;
+        if let Some(line_count) = state.buffer.line_count() {
+            let position = state.cursors.primary().position;
+            state.primary_cursor_line_number =
+                LineNumber::Absolute(state.buffer.get_line_number(position));
+            tracing::debug!("Indexed {} lines of buffer {:?}", line_count, buffer_id);
+            if buffer_id == self.active_buffer() {
+                self.status_message =
+                    Some(t!("status.line_index_complete", lines = line_count).to_string());
+            }
+        }
+    }
+
+    /// Report a line indexing task that could not read its file
+    pub(super) fn handle_line_index_failed(
+        &mut self,
+        buffer_id: BufferId,
+        task_id: u64,
+        error: String,
+    ) {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        if state
+            .line_indexing
+            .as_ref()
+            .is_some_and(|task| task.id == task_id)
+        {
+            state.line_indexing = None;
+            tracing::warn!("Line indexing of buffer {:?} failed: {}", buffer_id, error);
+            self.status_message = Some(t!("status.line_index_failed", error = error).to_string());
+        }
+    }
+}
@@ -0,0 +1,337 @@
+//! Formatting through the language server.
+//!
+//! "Format Buffer", format-on-save, "Format Selection" and on-type formatting
+//! send `textDocument/formatting`, `textDocument/rangeFormatting` and
+//! `textDocument/onTypeFormatting`. The returned text edits are turned into the
+//! smallest replacements of the buffer and applied as one undoable batch, so
+//! cursors and marks in unchanged text stay where they are.
+
+use std::ops::Range;
+
+use lsp_types::{FormattingOptions, TextEdit};
+use rust_i18n::t;
+
+use super::Editor;
+use crate::config::FormatterSource;
+use crate::model::buffer::Buffer;
+use crate::model::event::BufferId;
+use crate::model::line_diff::minimal_edits;
+use crate::services::lsp::manager::detect_language;
+
+/// What a formatting request was made for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum FormattingKind {
+    /// "Format Buffer"
+    Document,
+    /// Format on save; the buffer is saved again once formatted
+    OnSave,
+    /// "Format Selection" of the given byte range
+    Selection(Range<usize>),
+    /// A trigger character was typed
+    OnType(char),
+}
+
+impl FormattingKind {
+    /// Description of the undo step
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Document | Self::OnSave => "Format document",
+            Self::Selection(_) => "Format selection",
+            Self::OnType(_) => "Format on type",
+        }
+    }
+}
+
+/// A formatting request waiting for the server's edits
+#[derive(Debug, Clone)]
+pub(super) struct PendingFormatting {
+    buffer_id: BufferId,
+    /// Buffer version the edits apply to
+    version: u64,
+    kind: FormattingKind,
+}
+
+impl Editor {
+    /// Whether files of `language` are formatted by its language server rather
+    /// than by an external formatter
+    pub(crate) fn formats_with_lsp(&self, language: &str) -> bool {
+        let Some(lang_config) = self.config.languages.get(language) else {
+            return false;
+        };
+        match lang_config.formatter_source {
+            FormatterSource::Lsp => true,
+            FormatterSource::External => false,
+            FormatterSource::Auto => {
+                lang_config.formatter.is_none()
+                    && self
+                        .lsp
+                        .as_ref()
+                        .is_some_and(|lsp| lsp.formatting_supported(language))
+            }
+        }
+    }
+
+    /// Format the primary selection through the language server
+    pub fn format_selection(&mut self) -> Result<(), String> {
+        let Some(range) = self.active_state().cursors.primary().selection_range() else {
+            return Err("Select the text to format first".to_string());
+        };
+        self.request_lsp_formatting(FormattingKind::Selection(range))
+    }
+
+    /// Ask the language server to reformat the code around the cursor after
+    /// one of its trigger characters was typed
+    pub(crate) fn maybe_request_on_type_formatting(&mut self, c: char) {
+        let Some(path) = self.active_state().buffer.file_path() else {
+            return;
+        };
+        let Some(language) = detect_language(path, &self.config.languages) else {
+            return;
+        };
+        let enabled = self.config.languages.get(&language).is_some_and(|lc| {
+            lc.format_on_type && lc.formatter_source != FormatterSource::External
+        });
+        let is_trigger = self
+            .lsp
+            .as_ref()
+            .is_some_and(|lsp| lsp.is_on_type_formatting_trigger(c, &language));
+        if enabled && is_trigger {
+            if let Err(e) = self.request_lsp_formatting(FormattingKind::OnType(c)) {
+                tracing::debug!("Failed to request on-type formatting: {}", e);
+            }
+        }
+    }
+
+    /// Send a formatting request for the active buffer
+    pub(super) fn request_lsp_formatting(&mut self, kind: FormattingKind) -> Result<(), String> {
+        let buffer_id = self.active_buffer();
+        let state = self.active_state();
+        let version = state.buffer.version();
+        let options = FormattingOptions {
+            tab_size: state.tab_size as u32,
+            insert_spaces: !state.use_tabs,
+            ..Default::default()
+        };
+        let lsp_position = |offset: usize| {
+            let (line, character) = state.buffer.position_to_lsp_position(offset);
+            lsp_types::Position::new(line as u32, character as u32)
+        };
+        let range = match &kind {
+            FormattingKind::Selection(range) => Some(lsp_types::Range::new(
+                lsp_position(range.start),
+                lsp_position(range.end),
+            )),
+            _ => None,
+        };
+        let position = lsp_position(state.cursors.primary().position);
+
+        let metadata = self
+            .buffer_metadata
+            .get(&buffer_id)
+            .filter(|metadata| metadata.lsp_enabled)
+            .ok_or_else(|| "Language server is disabled for this buffer".to_string())?;
+        let uri = metadata
+            .file_uri()
+            .cloned()
+            .ok_or_else(|| "Cannot format unsaved buffer".to_string())?;
+        let language = metadata
+            .file_path()
+            .and_then(|path| detect_language(path, &self.config.languages))
+            .ok_or_else(|| "No language detected for this file".to_string())?;
+
+        let lsp = self
+            .lsp
+            .as_mut()
+            .ok_or_else(|| format!("No running language server formats {} files", language))?;
+        let supported = match &kind {
+            FormattingKind::Selection(_) => lsp.range_formatting_supported(&language),
+            FormattingKind::OnType(c) => lsp.is_on_type_formatting_trigger(*c, &language),
+            FormattingKind::Document | FormattingKind::OnSave => {
+                lsp.formatting_supported(&language)
+            }
+        };
+        let handle = lsp
+            .get_handle_mut(&language)
+            .filter(|_| supported)
+            .ok_or_else(|| match &kind {
+                FormattingKind::Selection(_) => {
+                    format!("The {} language server cannot format a selection", language)
+                }
+                _ => format!("No running language server formats {} files", language),
+            })?;
+
+        let request_id = self.next_lsp_request_id;
+        self.next_lsp_request_id += 1;
+        match &kind {
+            FormattingKind::Document | FormattingKind::OnSave => {
+                handle.formatting(request_id, uri, options)
+            }
+            FormattingKind::Selection(_) => {
+                handle.range_formatting(request_id, uri, range.unwrap_or_default(), options)
+            }
+            FormattingKind::OnType(c) => {
+                handle.on_type_formatting(request_id, uri, position, c.to_string(), options)
+            }
+        }?;
+        self.pending_formatting_requests.insert(
+            request_id,
+            PendingFormatting {
+                buffer_id,
+                version,
+                kind,
+            },
+        );
+        Ok(())
+    }
+
+    /// Handle the edits of a formatting request
+    pub(super) fn handle_lsp_formatting(
+        &mut self,
+        request_id: u64,
+        uri: String,
+        edits: Option<Vec<TextEdit>>,
+    ) {
+        let Some(pending) = self.pending_formatting_requests.remove(&request_id) else {
+            tracing::debug!(
+                "Formatting response {} for {} without pending entry",
+                request_id,
+                uri
+            );
+            return;
+        };
+        // Typing should not be interrupted by messages about on-type formatting
+        let quiet = matches!(pending.kind, FormattingKind::OnType(_));
+
+        let Some(edits) = edits else {
+            if !quiet {
+                self.set_status_message(t!("format.lsp_failed").to_string());
+            }
+            return;
+        };
+        // The edits are positions in the text the request was made for
+        if self.active_buffer() != pending.buffer_id
+            || self.active_state().buffer.version() != pending.version
+        {
+            tracing::debug!("Dropping formatting edits for {}: buffer changed", uri);
+            if !quiet {
+                self.set_status_message(t!("format.outdated").to_string());
+            }
+            return;
+        }
+        let Some(text) = self.active_state().buffer.to_string() else {
+            return;
+        };
+        let Some(formatted) = apply_text_edits(&self.active_state().buffer, &text, edits) else {
+            tracing::warn!("Ignoring overlapping formatting edits for {}", uri);
+            if !quiet {
+                self.set_status_message(t!("format.lsp_failed").to_string());
+            }
+            return;
+        };
+
+        let changes = minimal_edits(&text, &formatted);
+        let changed = !changes.is_empty();
+        self.apply_edits_to_active_buffer(pending.kind.description(), &text, changes);
+        match pending.kind {
+            FormattingKind::OnSave => {
+                if changed {
+                    if let Err(e) = self.resave_after_format() {
+                        self.set_status_message(e);
+                    }
+                }
+            }
+            FormattingKind::Document | FormattingKind::Selection(_) => {
+                self.set_status_message(t!("format.formatted_with_lsp").to_string());
+            }
+            FormattingKind::OnType(_) => {}
+        }
+    }
+}
+
+/// `text`, the content of `buffer`, with the server's edits applied, or None
+/// if the edits overlap
+fn apply_text_edits(buffer: &Buffer, text: &str, edits: Vec<TextEdit>) -> Option<String> {
+    let mut replacements: Vec<(Range<usize>, String)> = edits
+        .into_iter()
+        .map(|edit| {
+            let start = buffer.lsp_position_to_byte(
+                edit.range.start.line as usize,
+                edit.range.start.character as usize,
+            );
+            let end = buffer.lsp_position_to_byte(
+                edit.range.end.line as usize,
+                edit.range.end.character as usize,
+            );
+            (start..end.max(start), edit.new_text)
+        })
+        .collect();
+    // Inserts at the same position keep the order the server sent them in
+    replacements.sort_by_key(|(range, _)| range.start);
+
+    let mut result = String::with_capacity(text.len());
+    let mut pos = 0;
+    for (range, new_text) in replacements {
+        if range.start < pos
+            || range.end > text.len()
+            || !text.is_char_boundary(range.start)
+            || !text.is_char_boundary(range.end)
+        {
+            return None;
+        }
+        result.push_str(&text[pos..range.start]);
+        result.push_str(&new_text);
+        pos = range.end;
+    }
+    result.push_str(&text[pos..]);
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::Position;
+
+    fn edit(start: (u32, u32), end: (u32, u32), new_text: &str) -> TextEdit {
+        TextEdit {
+            range: lsp_types::Range::new(
+                Position::new(start.0, start.1),
+                Position::new(end.0, end.1),
+            ),
+            new_text: new_text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_text_edits() {
+        let text = "fn main(){\nlet x=1;\n}\n";
+        let buffer = Buffer::from_str_test(text);
+        let edits = vec![
+            edit((1, 0), (1, 0), "    "),
+            edit((0, 9), (0, 9), " "),
+            edit((1, 5), (1, 6), " = "),
+        ];
+        assert_eq!(
+            apply_text_edits(&buffer, text, edits).as_deref(),
+            Some("fn main() {\n    let x = 1;\n}\n")
+        );
+    }
+
+    #[test]
+    fn test_apply_text_edits_keeps_insert_order() {
+        let text = "x";
+        let buffer = Buffer::from_str_test(text);
+        let edits = vec![edit((0, 0), (0, 0), "a"), edit((0, 0), (0, 0), "b")];
+        assert_eq!(
+            apply_text_edits(&buffer, text, edits).as_deref(),
+            Some("abx")
+        );
+    }
+
+    #[test]
+    fn test_apply_text_edits_rejects_overlap() {
+        let text = "abcdef";
+        let buffer = Buffer::from_str_test(text);
+        let edits = vec![edit((0, 0), (0, 4), ""), edit((0, 2), (0, 5), "x")];
+        assert_eq!(apply_text_edits(&buffer, text, edits), None);
+    }
+}
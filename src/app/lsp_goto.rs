@@ -0,0 +1,213 @@
+//! "Go to Definition", "Go to Type Definition", "Go to Implementation" and
+//! "Go to Declaration".
+//!
+//! A single location is jumped to directly; several open a picker showing the
+//! code at each of them. Every jump is recorded in the position history, so
+//! "Navigate Back" returns to where the request was made.
+
+use anyhow::Result as AnyhowResult;
+use lsp_types::Location;
+use rust_i18n::t;
+
+use crate::input::commands::Suggestion;
+use crate::services::lsp::async_handler::GotoKind;
+use crate::view::prompt::{Prompt, PromptType};
+
+use super::{uri_to_path, Editor};
+
+/// Status shown when the server found nothing
+fn not_found_message(kind: GotoKind) -> String {
+    match kind {
+        GotoKind::Definition => t!("lsp.no_definition"),
+        GotoKind::TypeDefinition => t!("lsp.no_type_definition"),
+        GotoKind::Implementation => t!("lsp.no_implementation"),
+        GotoKind::Declaration => t!("lsp.no_declaration"),
+    }
+    .to_string()
+}
+
+/// Status shown after jumping to a location
+fn jumped_message(kind: GotoKind, path: &str, line: usize) -> String {
+    match kind {
+        GotoKind::Definition => t!("lsp.jumped_to_definition", path = path, line = line),
+        GotoKind::TypeDefinition => {
+            t!("lsp.jumped_to_type_definition", path = path, line = line)
+        }
+        GotoKind::Implementation => {
+            t!("lsp.jumped_to_implementation", path = path, line = line)
+        }
+        GotoKind::Declaration => t!("lsp.jumped_to_declaration", path = path, line = line),
+    }
+    .to_string()
+}
+
+/// Prompt of the picker listing several locations
+fn picker_prompt(kind: GotoKind, count: usize) -> String {
+    match kind {
+        GotoKind::Definition => t!("lsp.pick_definition", count = count),
+        GotoKind::TypeDefinition => t!("lsp.pick_type_definition", count = count),
+        GotoKind::Implementation => t!("lsp.pick_implementation", count = count),
+        GotoKind::Declaration => t!("lsp.pick_declaration", count = count),
+    }
+    .to_string()
+}
+
+impl Editor {
+    /// Request the definition, type definition, implementation or declaration
+    /// of the symbol at the cursor
+    pub(crate) fn request_goto(&mut self, kind: GotoKind) -> AnyhowResult<()> {
+        let state = self.active_state();
+        let cursor_pos = state.cursors.primary().position;
+
+        // Convert byte position to LSP position (line, UTF-16 code units)
+        let (line, character) = state.buffer.position_to_lsp_position(cursor_pos);
+        let buffer_id = self.active_buffer();
+        let request_id = self.next_lsp_request_id;
+
+        // Use helper to ensure didOpen is sent before the request
+        let sent = self
+            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
+                let result =
+                    handle.goto(request_id, kind, uri.clone(), line as u32, character as u32);
+                if result.is_ok() {
+                    tracing::info!(
+                        "Requested {} at {}:{}:{}",
+                        kind.method(),
+                        uri.as_str(),
+                        line,
+                        character
+                    );
+                }
+                result.is_ok()
+            })
+            .unwrap_or(false);
+
+        if sent {
+            self.next_lsp_request_id += 1;
+            self.pending_goto_request = Some((request_id, kind));
+        }
+
+        Ok(())
+    }
+
+    /// Jump to the single location the server answered with, or let the user
+    /// pick one of several
+    pub(super) fn handle_goto_response(&mut self, request_id: u64, locations: Vec<Location>) {
+        let kind = match self.pending_goto_request {
+            Some((pending_id, kind)) if pending_id == request_id => kind,
+            _ => {
+                tracing::debug!("Ignoring goto response for outdated request {}", request_id);
+                return;
+            }
+        };
+        self.pending_goto_request = None;
+
+        // Servers may list a location once per matching declaration
+        let mut locations = locations;
+        let mut seen = Vec::new();
+        locations.retain(|location| {
+            let key = (location.uri.clone(), location.range.start);
+            let new = !seen.contains(&key);
+            seen.push(key);
+            new
+        });
+
+        match locations.len() {
+            0 => self.set_status_message(not_found_message(kind)),
+            1 => self.jump_to_location(kind, &locations[0]),
+            _ => self.show_goto_picker(kind, locations),
+        }
+    }
+
+    /// Open a picker listing the code at each location
+    fn show_goto_picker(&mut self, kind: GotoKind, locations: Vec<Location>) {
+        let suggestions = locations
+            .iter()
+            .enumerate()
+            .map(|(index, location)| {
+                let line = location.range.start.line as usize;
+                let path = uri_to_path(&location.uri).ok();
+                let code = path.as_ref().and_then(|path| {
+                    self.file_lines_for_preview(path, line..line + 1)?
+                        .pop()
+                        .map(|code| code.trim().to_string())
+                });
+                let location_text = match &path {
+                    Some(path) => {
+                        let path = path.strip_prefix(&self.working_dir).unwrap_or(path);
+                        format!("{}:{}", path.display(), line + 1)
+                    }
+                    None => format!("{}:{}", location.uri.as_str(), line + 1),
+                };
+                Suggestion {
+                    text: code
+                        .filter(|code| !code.is_empty())
+                        .unwrap_or_else(|| location_text.clone()),
+                    description: Some(location_text),
+                    value: Some(index.to_string()),
+                    disabled: false,
+                    keybinding: None,
+                    source: None,
+                }
+            })
+            .collect();
+        self.prompt = Some(Prompt::with_suggestions(
+            picker_prompt(kind, locations.len()),
+            PromptType::GotoLocation,
+            suggestions,
+        ));
+        self.goto_picker = Some((kind, locations));
+    }
+
+    /// Jump to the location picked in the "go to" picker
+    pub(crate) fn goto_location_from_prompt(&mut self, index: &str) {
+        let Some((kind, locations)) = self.goto_picker.take() else {
+            return;
+        };
+        if let Some(location) = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| locations.get(index))
+        {
+            self.jump_to_location(kind, location);
+        }
+    }
+
+    /// Open a location's file and move the cursor there, recording the
+    /// position the jump started from
+    fn jump_to_location(&mut self, kind: GotoKind, location: &Location) {
+        let Ok(path) = uri_to_path(&location.uri) else {
+            self.set_status_message(t!("lsp.cannot_open_definition").to_string());
+            return;
+        };
+
+        // Start a history entry at the origin, even for a jump within the buffer
+        self.position_history.commit_pending_movement();
+        let origin = self.active_state().cursors.primary();
+        let (position, anchor) = (origin.position, origin.anchor);
+        self.position_history
+            .record_movement(self.active_buffer(), position, anchor);
+        self.position_history.commit_pending_movement();
+
+        let buffer_id = match self.open_file(&path) {
+            Ok(buffer_id) => buffer_id,
+            Err(e) => {
+                self.set_status_message(
+                    t!("file.error_opening", error = e.to_string()).to_string(),
+                );
+                return;
+            }
+        };
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        let line = location.range.start.line as usize;
+        let position = state
+            .buffer
+            .lsp_position_to_byte(line, location.range.start.character as usize);
+        self.move_cursor_and_reveal(self.split_manager.active_split(), position);
+        self.position_history.commit_pending_movement();
+
+        self.set_status_message(jumped_message(kind, &path.display().to_string(), line + 1));
+    }
+}
@@ -13,16 +13,35 @@
 use anyhow::Result as AnyhowResult;
 use rust_i18n::t;
 use std::io;
+use std::time::{Duration, Instant};
 
 use lsp_types::TextDocumentContentChangeEvent;
 
 use crate::model::event::{BufferId, Event};
+use crate::primitives::syntax_selection::SelectionChain;
 use crate::primitives::word_navigation::{find_word_end, find_word_start};
 use crate::services::lsp::manager::detect_language;
 use crate::view::prompt::{Prompt, PromptType};
 
 use super::{uri_to_path, Editor};
 
+/// How long Expand Selection waits for the language server before falling
+/// back to the syntax tree
+const SELECTION_RANGE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A selection range request waiting for the server's answer
+#[derive(Debug, Clone)]
+pub(super) struct PendingSelectionRange {
+    pub(super) buffer_id: BufferId,
+    /// Buffer version the ranges are requested for
+    pub(super) version: u64,
+    /// Byte offsets the ranges are requested for
+    pub(super) origins: Vec<usize>,
+    /// Language of the server, whose requests are dropped when it stops
+    pub(super) language: String,
+    sent: Instant,
+}
+
 impl Editor {
     /// Handle LSP completion response
     pub(crate) fn handle_completion_response(
@@ -42,9 +61,19 @@ impl Editor {
         self.pending_completion_request = None;
         self.lsp_status.clear();
 
+        // Offer the user's snippets alongside the server's items
+        let mut items = items;
+        items.extend(self.snippet_completion_items());
+        self.show_completion_popup(items);
+
+        Ok(())
+    }
+
+    /// Show the completion popup for the items matching the word at the cursor
+    pub(crate) fn show_completion_popup(&mut self, items: Vec<lsp_types::CompletionItem>) {
         if items.is_empty() {
             tracing::debug!("No completion items received");
-            return Ok(());
+            return;
         }
 
         // Get the partial word at cursor to filter completions
@@ -84,7 +113,7 @@ impl Editor {
 
         if filtered_items.is_empty() {
             tracing::debug!("No completion items match prefix '{}'", prefix);
-            return Ok(());
+            return;
         }
 
         // Convert CompletionItem to PopupListItem
@@ -161,86 +190,11 @@ impl Editor {
             "Showing completion popup with {} items",
             self.completion_items.as_ref().map_or(0, |i| i.len())
         );
-
-        Ok(())
-    }
-
-    /// Handle LSP go-to-definition response
-    pub(crate) fn handle_goto_definition_response(
-        &mut self,
-        request_id: u64,
-        locations: Vec<lsp_types::Location>,
-    ) -> AnyhowResult<()> {
-        // Check if this is the pending request
-        if self.pending_goto_definition_request != Some(request_id) {
-            tracing::debug!(
-                "Ignoring go-to-definition response for outdated request {}",
-                request_id
-            );
-            return Ok(());
-        }
-
-        self.pending_goto_definition_request = None;
-
-        if locations.is_empty() {
-            self.status_message = Some(t!("lsp.no_definition").to_string());
-            return Ok(());
-        }
-
-        // For now, just jump to the first location
-        let location = &locations[0];
-
-        // Convert URI to file path
-        if let Ok(path) = uri_to_path(&location.uri) {
-            // Open the file
-            let buffer_id = self.open_file(&path)?;
-
-            // Move cursor to the definition position
-            let line = location.range.start.line as usize;
-            let character = location.range.start.character as usize;
-
-            // Calculate byte position from line and character
-            if let Some(state) = self.buffers.get(&buffer_id) {
-                let position = state.buffer.line_col_to_position(line, character);
-
-                // Move cursor
-                let cursor_id = state.cursors.primary_id();
-                let old_position = state.cursors.primary().position;
-                let old_anchor = state.cursors.primary().anchor;
-                let old_sticky_column = state.cursors.primary().sticky_column;
-                let event = crate::model::event::Event::MoveCursor {
-                    cursor_id,
-                    old_position,
-                    new_position: position,
-                    old_anchor,
-                    new_anchor: None,
-                    old_sticky_column,
-                    new_sticky_column: 0, // Reset sticky column for goto definition
-                };
-
-                if let Some(state) = self.buffers.get_mut(&buffer_id) {
-                    state.apply(&event);
-                }
-            }
-
-            self.status_message = Some(
-                t!(
-                    "lsp.jumped_to_definition",
-                    path = path.display().to_string(),
-                    line = line + 1
-                )
-                .to_string(),
-            );
-        } else {
-            self.status_message = Some(t!("lsp.cannot_open_definition").to_string());
-        }
-
-        Ok(())
     }
 
     /// Check if there are any pending LSP requests
     pub fn has_pending_lsp_requests(&self) -> bool {
-        self.pending_completion_request.is_some() || self.pending_goto_definition_request.is_some()
+        self.pending_completion_request.is_some() || self.pending_goto_request.is_some()
     }
 
     /// Cancel any pending LSP requests
@@ -253,11 +207,8 @@ impl Editor {
             self.send_lsp_cancel_request(request_id);
             self.lsp_status.clear();
         }
-        if let Some(request_id) = self.pending_goto_definition_request.take() {
-            tracing::debug!(
-                "Canceling pending LSP goto-definition request {}",
-                request_id
-            );
+        if let Some((request_id, kind)) = self.pending_goto_request.take() {
+            tracing::debug!("Canceling pending LSP {:?} request {}", kind, request_id);
             // Send cancellation to the LSP server
             self.send_lsp_cancel_request(request_id);
             self.lsp_status.clear();
@@ -393,6 +344,10 @@ impl Editor {
             self.next_lsp_request_id += 1;
             self.pending_completion_request = Some(request_id);
             self.lsp_status = "LSP: completion...".to_string();
+        } else {
+            // Without a language server, complete the user's snippets
+            let items = self.snippet_completion_items();
+            self.show_completion_popup(items);
         }
 
         Ok(())
@@ -447,42 +402,6 @@ impl Editor {
         }
     }
 
-    /// Request LSP go-to-definition at current cursor position
-    pub(crate) fn request_goto_definition(&mut self) -> AnyhowResult<()> {
-        // Get the current buffer and cursor position
-        let state = self.active_state();
-        let cursor_pos = state.cursors.primary().position;
-
-        // Convert byte position to LSP position (line, UTF-16 code units)
-        let (line, character) = state.buffer.position_to_lsp_position(cursor_pos);
-        let buffer_id = self.active_buffer();
-        let request_id = self.next_lsp_request_id;
-
-        // Use helper to ensure didOpen is sent before the request
-        let sent = self
-            .with_lsp_for_buffer(buffer_id, |handle, uri, _language| {
-                let result =
-                    handle.goto_definition(request_id, uri.clone(), line as u32, character as u32);
-                if result.is_ok() {
-                    tracing::info!(
-                        "Requested go-to-definition at {}:{}:{}",
-                        uri.as_str(),
-                        line,
-                        character
-                    );
-                }
-                result.is_ok()
-            })
-            .unwrap_or(false);
-
-        if sent {
-            self.next_lsp_request_id += 1;
-            self.pending_goto_definition_request = Some(request_id);
-        }
-
-        Ok(())
-    }
-
     /// Request LSP hover documentation at current cursor position
     pub(crate) fn request_hover(&mut self) -> AnyhowResult<()> {
         // Get the current buffer and cursor position
@@ -718,8 +637,8 @@ impl Editor {
         use crate::view::virtual_text::VirtualTextPosition;
         use ratatui::style::{Color, Style};
 
-        // Clear existing inlay hints
-        state.virtual_texts.clear(&mut state.marker_list);
+        // Clear existing inlay hints, keeping virtual lines such as code lenses
+        state.virtual_texts.clear_inline(&mut state.marker_list);
 
         if hints.is_empty() {
             return;
@@ -1179,6 +1098,15 @@ impl Editor {
             return Ok(());
         }
 
+        let symbol = std::mem::take(&mut self.pending_references_symbol);
+        self.show_references(symbol, &locations);
+
+        Ok(())
+    }
+
+    /// Report the references found for a symbol and hand them to the plugins
+    /// that display them
+    pub(crate) fn show_references(&mut self, symbol: String, locations: &[lsp_types::Location]) {
         // Convert locations to hook args format
         let lsp_locations: Vec<crate::services::plugins::hooks::LspLocation> = locations
             .iter()
@@ -1200,7 +1128,6 @@ impl Editor {
             .collect();
 
         let count = lsp_locations.len();
-        let symbol = std::mem::take(&mut self.pending_references_symbol);
         self.set_status_message(
             t!("lsp.found_references", count = count, symbol = &symbol).to_string(),
         );
@@ -1219,8 +1146,6 @@ impl Editor {
             count,
             symbol
         );
-
-        Ok(())
     }
 
     /// Apply LSP text edits to a buffer and return the number of changes made.
@@ -1950,6 +1875,248 @@ impl Editor {
             }
         }
     }
+
+    /// Request folding ranges for a buffer if its server provides them and
+    /// the current ranges are out of date.
+    pub(crate) fn maybe_request_folding_ranges(&mut self, buffer_id: BufferId) {
+        if self
+            .pending_folding_range_requests
+            .values()
+            .any(|(id, _)| *id == buffer_id)
+        {
+            return;
+        }
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        let buffer_version = state.buffer.version();
+        if state.folds.lsp_ranges_version() == Some(buffer_version) {
+            return;
+        }
+
+        let Some(metadata) = self.buffer_metadata.get(&buffer_id) else {
+            return;
+        };
+        if !metadata.lsp_enabled {
+            return;
+        }
+        let Some(uri) = metadata.file_uri().cloned() else {
+            return;
+        };
+        let Some(path) = metadata.file_path() else {
+            return;
+        };
+        let Some(language) = detect_language(path, &self.config.languages) else {
+            return;
+        };
+
+        // Only ask servers that are already running; folding falls back to
+        // tree-sitter and indentation otherwise
+        let Some(lsp) = self.lsp.as_mut() else {
+            return;
+        };
+        if !lsp.folding_range_supported(&language) {
+            return;
+        }
+        let Some(handle) = lsp.get_handle_mut(&language) else {
+            return;
+        };
+
+        let request_id = self.next_lsp_request_id;
+        self.next_lsp_request_id += 1;
+
+        match handle.folding_ranges(request_id, uri) {
+            Ok(_) => {
+                self.pending_folding_range_requests
+                    .insert(request_id, (buffer_id, buffer_version));
+            }
+            Err(e) => {
+                tracing::debug!("Failed to request folding ranges: {}", e);
+            }
+        }
+    }
+
+    /// Request the symbols of a buffer if its server provides them and the
+    /// current symbols are out of date.
+    pub(crate) fn maybe_request_document_symbols(&mut self, buffer_id: BufferId) {
+        if self
+            .pending_document_symbol_requests
+            .values()
+            .any(|(id, _)| *id == buffer_id)
+        {
+            return;
+        }
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        let buffer_version = state.buffer.version();
+        if state.symbols.lsp_version() == Some(buffer_version) {
+            return;
+        }
+
+        let Some(metadata) = self.buffer_metadata.get(&buffer_id) else {
+            return;
+        };
+        if !metadata.lsp_enabled {
+            return;
+        }
+        let Some(uri) = metadata.file_uri().cloned() else {
+            return;
+        };
+        let Some(path) = metadata.file_path() else {
+            return;
+        };
+        let Some(language) = detect_language(path, &self.config.languages) else {
+            return;
+        };
+
+        // Only ask servers that are already running; symbols fall back to
+        // tree-sitter tags otherwise
+        let Some(lsp) = self.lsp.as_mut() else {
+            return;
+        };
+        if !lsp.document_symbol_supported(&language) {
+            return;
+        }
+        let Some(handle) = lsp.get_handle_mut(&language) else {
+            return;
+        };
+
+        let request_id = self.next_lsp_request_id;
+        self.next_lsp_request_id += 1;
+
+        match handle.document_symbols(request_id, uri) {
+            Ok(_) => {
+                self.pending_document_symbol_requests
+                    .insert(request_id, (buffer_id, buffer_version));
+            }
+            Err(e) => {
+                tracing::debug!("Failed to request document symbols: {}", e);
+            }
+        }
+    }
+
+    /// Request selection ranges around cursors of the active buffer that
+    /// have none for the current version.
+    ///
+    /// Returns true while a request is in flight; the selection is expanded
+    /// when the response arrives.
+    pub(crate) fn request_selection_ranges(&mut self) -> bool {
+        let buffer_id = self.active_buffer();
+        let pending = self
+            .pending_selection_range_requests
+            .iter()
+            .find(|(_, pending)| pending.buffer_id == buffer_id)
+            .map(|(request_id, pending)| (*request_id, pending.sent));
+        if let Some((request_id, sent)) = pending {
+            if self.time_source.elapsed_since(sent) < SELECTION_RANGE_TIMEOUT {
+                return true;
+            }
+            // The server is not answering: use the syntax tree for these positions
+            tracing::debug!("Selection range request {} timed out", request_id);
+            self.drop_selection_range_request(request_id);
+        }
+        let state = self.active_state();
+        let buffer_version = state.buffer.version();
+        let origins: Vec<usize> = state
+            .cursors
+            .iter()
+            .map(|(_, cursor)| {
+                cursor
+                    .selection_range()
+                    .unwrap_or(cursor.position..cursor.position)
+            })
+            .filter(|selection| {
+                state
+                    .selection_expansion
+                    .lsp_chain(buffer_version, selection.clone())
+                    .is_none()
+            })
+            .map(|selection| selection.start)
+            .collect();
+        if origins.is_empty() {
+            return false;
+        }
+        let positions = origins
+            .iter()
+            .map(|&offset| {
+                let (line, character) = state.buffer.position_to_lsp_position(offset);
+                lsp_types::Position::new(line as u32, character as u32)
+            })
+            .collect();
+
+        let Some(metadata) = self.buffer_metadata.get(&buffer_id) else {
+            return false;
+        };
+        if !metadata.lsp_enabled {
+            return false;
+        }
+        let Some(uri) = metadata.file_uri().cloned() else {
+            return false;
+        };
+        let Some(path) = metadata.file_path() else {
+            return false;
+        };
+        let Some(language) = detect_language(path, &self.config.languages) else {
+            return false;
+        };
+
+        // Without a running server the syntax tree is used right away
+        let Some(lsp) = self.lsp.as_mut() else {
+            return false;
+        };
+        if !lsp.selection_range_supported(&language) {
+            return false;
+        }
+        let Some(handle) = lsp.get_handle_mut(&language) else {
+            return false;
+        };
+
+        let request_id = self.next_lsp_request_id;
+        self.next_lsp_request_id += 1;
+
+        match handle.selection_ranges(request_id, uri, positions) {
+            Ok(_) => {
+                let pending = PendingSelectionRange {
+                    buffer_id,
+                    version: buffer_version,
+                    origins,
+                    language,
+                    sent: self.time_source.now(),
+                };
+                self.pending_selection_range_requests
+                    .insert(request_id, pending);
+                true
+            }
+            Err(e) => {
+                tracing::debug!("Failed to request selection ranges: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Forget a selection range request that will not be answered. Its
+    /// positions get empty chains, so expansion uses the syntax tree instead
+    /// of asking again.
+    pub(super) fn drop_selection_range_request(&mut self, request_id: u64) {
+        let Some(pending) = self.pending_selection_range_requests.remove(&request_id) else {
+            return;
+        };
+        let Some(state) = self.buffers.get_mut(&pending.buffer_id) else {
+            return;
+        };
+        let chains = pending
+            .origins
+            .into_iter()
+            .map(|origin| SelectionChain {
+                origin,
+                ranges: Vec::new(),
+            })
+            .collect();
+        state
+            .selection_expansion
+            .set_lsp_chains(pending.version, chains);
+    }
 }
 
 #[cfg(test)]
@@ -16,9 +16,12 @@ impl Editor {
         // Simple state lookups
         let line_numbers = self.is_line_numbers_visible();
         let line_wrap = self.is_line_wrap_enabled();
+        let sticky_scroll = self.config.editor.sticky_scroll;
+        let breadcrumbs = self.config.editor.breadcrumbs;
         let compose_mode = self.is_compose_mode();
         let file_explorer_visible = self.file_explorer_visible;
         let file_explorer_focused = self.is_file_explorer_focused();
+        let outline = self.outline_visible();
         let mouse_capture = self.mouse_enabled;
         let mouse_hover = self.config.editor.mouse_hover_enabled;
         let inlay_hints = self.config.editor.enable_inlay_hints;
@@ -38,9 +41,12 @@ impl Editor {
             .context
             .set(context_keys::LINE_NUMBERS, line_numbers)
             .set(context_keys::LINE_WRAP, line_wrap)
+            .set(context_keys::STICKY_SCROLL, sticky_scroll)
+            .set(context_keys::BREADCRUMBS, breadcrumbs)
             .set(context_keys::COMPOSE_MODE, compose_mode)
             .set(context_keys::FILE_EXPLORER, file_explorer_visible)
             .set(context_keys::FILE_EXPLORER_FOCUSED, file_explorer_focused)
+            .set(context_keys::OUTLINE, outline)
             .set(context_keys::MOUSE_CAPTURE, mouse_capture)
             .set(context_keys::MOUSE_HOVER, mouse_hover)
             .set(context_keys::INLAY_HINTS, inlay_hints)
@@ -115,18 +121,20 @@ impl Editor {
             .unwrap_or(false)
     }
 
-    /// Check if a formatter is configured for the current buffer's language.
+    /// Check if a formatter is configured, or the language server formats,
+    /// for the current buffer's language.
     fn is_formatter_available(&self) -> bool {
         self.buffer_metadata
             .get(&self.active_buffer())
             .and_then(|metadata| {
                 metadata.file_path().and_then(|path| {
-                    detect_language(path, &self.config.languages).and_then(|language| {
-                        self.config
-                            .languages
-                            .get(&language)
-                            .and_then(|lc| lc.formatter.as_ref())
-                            .map(|_| true)
+                    detect_language(path, &self.config.languages).map(|language| {
+                        self.formats_with_lsp(&language)
+                            || self
+                                .config
+                                .languages
+                                .get(&language)
+                                .is_some_and(|lc| lc.formatter.is_some())
                     })
                 })
             })
@@ -3,28 +3,43 @@ mod buffer_management;
 mod calibration_actions;
 pub mod calibration_wizard;
 mod clipboard;
+mod code_lens;
 mod composite_buffer_actions;
+mod editorconfig;
 mod file_explorer;
+mod file_history;
 pub mod file_open;
 mod file_open_input;
 mod file_operations;
+mod folding;
+mod follow_mode;
 mod help;
+mod hex_actions;
+mod hierarchy;
 mod input;
 mod input_dispatch;
+mod large_file_search;
+mod line_indexing;
 mod lsp_actions;
+mod lsp_formatting;
+mod lsp_goto;
 mod lsp_requests;
 mod menu_actions;
 mod menu_context;
 mod mouse_input;
 mod on_save_actions;
+mod outline;
 mod plugin_commands;
 mod popup_actions;
+mod project_search;
 mod prompt_actions;
 mod recovery_actions;
 mod render;
+mod scrollbar_marks;
 pub mod session;
 mod settings_actions;
 mod shell_command;
+mod snippets;
 mod split_actions;
 mod tab_drag;
 mod terminal;
@@ -32,8 +47,10 @@ mod terminal_input;
 mod toggle_actions;
 pub mod types;
 mod undo_actions;
+mod undo_tree;
 mod view_actions;
 pub mod warning_domains;
+mod workspace_symbols;
 
 use anyhow::Result as AnyhowResult;
 use rust_i18n::t;
@@ -87,21 +104,26 @@ use crate::input::position_history::PositionHistory;
 use crate::model::event::{Event, EventLog, SplitDirection, SplitId};
 use crate::services::async_bridge::{AsyncBridge, AsyncMessage};
 use crate::services::fs::{FsBackend, FsManager, LocalFsBackend};
+use crate::services::local_history::LocalHistoryStore;
+use crate::services::lsp::async_handler::GotoKind;
 use crate::services::lsp::manager::{detect_language, LspManager};
 #[cfg(feature = "plugins")]
 use crate::services::plugins::api::BufferSavedDiff;
 use crate::services::plugins::api::PluginCommand;
 use crate::services::plugins::PluginManager;
 use crate::services::recovery::{RecoveryConfig, RecoveryService};
+use crate::services::snippets::SnippetLibrary;
 use crate::services::time_source::{RealTimeSource, SharedTimeSource};
+use crate::services::undo_history::UndoHistoryStore;
 use crate::state::EditorState;
 use crate::types::LspServerConfig;
 use crate::view::file_tree::{FileTree, FileTreeView};
+use crate::view::folding::scroll_viewport;
 use crate::view::prompt::{Prompt, PromptType};
 use crate::view::scroll_sync::ScrollSyncManager;
 use crate::view::split::{SplitManager, SplitViewState};
 use crate::view::ui::{
-    FileExplorerRenderer, SplitRenderer, StatusBarRenderer, SuggestionsRenderer,
+    FileExplorerRenderer, OutlineRenderer, SplitRenderer, StatusBarRenderer, SuggestionsRenderer,
 };
 use crossterm::event::{KeyCode, KeyModifiers};
 use lsp_types::{Position, Range as LspRange, TextDocumentContentChangeEvent};
@@ -241,6 +263,12 @@ pub struct Editor {
     /// This is the runtime value that can be modified by dragging the border
     file_explorer_width_percent: f32,
 
+    /// Outline panel (only when open)
+    outline: Option<crate::view::outline::OutlinePanel>,
+
+    /// Search of the "Go to Symbol in Workspace" prompt (only while it is open)
+    workspace_symbol_search: Option<workspace_symbols::WorkspaceSymbolSearch>,
+
     /// Whether menu bar is visible
     menu_bar_visible: bool,
 
@@ -290,8 +318,15 @@ pub struct Editor {
     /// Stored when completion popup is shown, used for re-filtering as user types
     completion_items: Option<Vec<lsp_types::CompletionItem>>,
 
-    /// Pending LSP go-to-definition request ID (if any)
-    pending_goto_definition_request: Option<u64>,
+    /// User snippets, loaded on first use and dropped when a snippet file is saved
+    snippet_library: Option<SnippetLibrary>,
+
+    /// Pending LSP go-to-definition, type definition, implementation or
+    /// declaration request (if any)
+    pending_goto_request: Option<(u64, GotoKind)>,
+
+    /// Request kind and locations listed by the "go to" picker (only while it is open)
+    goto_picker: Option<(GotoKind, Vec<lsp_types::Location>)>,
 
     /// Pending LSP hover request ID (if any)
     pending_hover_request: Option<u64>,
@@ -317,6 +352,32 @@ pub struct Editor {
     /// Track semantic token requests per buffer to prevent duplicate inflight requests
     semantic_tokens_in_flight: HashMap<BufferId, (u64, u64)>,
 
+    /// Pending folding range requests keyed by LSP request ID -> (buffer_id, buffer_version)
+    pending_folding_range_requests: HashMap<u64, (BufferId, u64)>,
+
+    /// Pending document symbol requests keyed by LSP request ID -> (buffer_id, buffer_version)
+    pending_document_symbol_requests: HashMap<u64, (BufferId, u64)>,
+
+    /// Pending formatting requests keyed by LSP request ID
+    pending_formatting_requests: HashMap<u64, lsp_formatting::PendingFormatting>,
+
+    /// Pending selection range requests keyed by LSP request ID
+    pending_selection_range_requests: HashMap<u64, lsp_requests::PendingSelectionRange>,
+
+    /// Pending code lens requests keyed by LSP request ID -> (buffer_id, buffer_version)
+    pending_code_lens_requests: HashMap<u64, (BufferId, u64)>,
+
+    /// Pending code lens resolve requests keyed by LSP request ID ->
+    /// (buffer_id, version of the lenses, lens index)
+    pending_code_lens_resolves: HashMap<u64, (BufferId, u64, usize)>,
+
+    /// Titles of the code lens commands being executed by a server, keyed by
+    /// LSP request ID
+    pending_execute_commands: HashMap<u64, String>,
+
+    /// Buffer and commands listed by the code lens picker (only while it is open)
+    code_lens_picker: Option<(BufferId, Vec<lsp_types::Command>)>,
+
     /// Hover symbol range (byte offsets) - for highlighting the symbol under hover
     /// Format: (start_byte_offset, end_byte_offset)
     hover_symbol_range: Option<(usize, usize)>,
@@ -331,6 +392,9 @@ pub struct Editor {
     /// Search state (if search is active)
     search_state: Option<SearchState>,
 
+    /// Search running in the background over a large file
+    search_task: Option<crate::services::buffer_search::SearchTask>,
+
     /// Search highlight namespace (for efficient bulk removal)
     search_namespace: crate::view::overlay::OverlayNamespace,
 
@@ -449,6 +513,12 @@ pub struct Editor {
     /// Last time we polled for directory changes (for file tree refresh)
     last_file_tree_poll: std::time::Instant,
 
+    /// Buffers following their file as it grows (like `tail -f`)
+    followed_buffers: HashMap<BufferId, follow_mode::FollowState>,
+
+    /// Last time we polled followed files for appended data
+    last_follow_poll: std::time::Instant,
+
     /// Last known modification times for open files (for auto-revert)
     /// Maps file path to last known modification time
     file_mod_times: HashMap<PathBuf, std::time::SystemTime>,
@@ -470,6 +540,30 @@ pub struct Editor {
     /// Recovery service for auto-save and crash recovery
     recovery_service: RecoveryService,
 
+    /// Store for undo histories persisted across sessions
+    undo_history: UndoHistoryStore,
+
+    /// Store for snapshots of saved files
+    local_history: LocalHistoryStore,
+
+    /// Open local history snapshot comparisons, by composite buffer
+    file_history_views: HashMap<BufferId, file_history::FileHistoryView>,
+
+    /// Undo tree panel, while open
+    undo_tree: Option<undo_tree::UndoTreePanel>,
+
+    /// Call or type hierarchy panel, while open
+    hierarchy: Option<hierarchy::HierarchyPanel>,
+
+    /// Hierarchy requested for the symbol at the cursor, until the server answers
+    pending_hierarchy: Option<hierarchy::PendingHierarchy>,
+
+    /// Project search results panel, while open
+    project_search: Option<project_search::ProjectSearchPanel>,
+
+    /// The last project-wide replace, until it is undone
+    last_project_replace: Option<project_search::ProjectReplaceRecord>,
+
     /// Request a full terminal clear and redraw on the next frame
     full_redraw_requested: bool,
 
@@ -688,6 +782,9 @@ impl Editor {
 
         let keybindings = KeybindingResolver::new(&config);
 
+        let local_history =
+            LocalHistoryStore::for_project(&dir_context.local_history_dir(), &working_dir);
+
         // Create an empty initial buffer
         let mut buffers = HashMap::new();
         let mut event_logs = HashMap::new();
@@ -880,6 +977,8 @@ impl Editor {
             file_explorer_visible: false,
             file_explorer_sync_in_progress: false,
             file_explorer_width_percent: file_explorer_width,
+            outline: None,
+            workspace_symbol_search: None,
             menu_bar_visible: show_menu_bar,
             menu_bar_auto_shown: false,
             tab_bar_visible: show_tab_bar,
@@ -895,7 +994,9 @@ impl Editor {
             next_lsp_request_id: 0,
             pending_completion_request: None,
             completion_items: None,
-            pending_goto_definition_request: None,
+            snippet_library: None,
+            pending_goto_request: None,
+            goto_picker: None,
             pending_hover_request: None,
             pending_references_request: None,
             pending_references_symbol: String::new(),
@@ -904,10 +1005,19 @@ impl Editor {
             pending_inlay_hints_request: None,
             pending_semantic_token_requests: HashMap::new(),
             semantic_tokens_in_flight: HashMap::new(),
+            pending_folding_range_requests: HashMap::new(),
+            pending_document_symbol_requests: HashMap::new(),
+            pending_formatting_requests: HashMap::new(),
+            pending_selection_range_requests: HashMap::new(),
+            pending_code_lens_requests: HashMap::new(),
+            pending_code_lens_resolves: HashMap::new(),
+            pending_execute_commands: HashMap::new(),
+            code_lens_picker: None,
             hover_symbol_range: None,
             hover_symbol_overlay: None,
             mouse_hover_screen_position: None,
             search_state: None,
+            search_task: None,
             search_namespace: crate::view::overlay::OverlayNamespace::from_string(
                 "search".to_string(),
             ),
@@ -963,6 +1073,8 @@ impl Editor {
             auto_revert_enabled: true,
             last_auto_revert_poll: time_source.now(),
             last_file_tree_poll: time_source.now(),
+            followed_buffers: HashMap::new(),
+            last_follow_poll: time_source.now(),
             file_mod_times: HashMap::new(),
             dir_mod_times: HashMap::new(),
             file_rapid_change_counts: HashMap::new(),
@@ -976,6 +1088,14 @@ impl Editor {
                 };
                 RecoveryService::with_config_and_dir(recovery_config, dir_context.recovery_dir())
             },
+            undo_history: UndoHistoryStore::new(dir_context.undo_dir()),
+            local_history,
+            file_history_views: HashMap::new(),
+            undo_tree: None,
+            hierarchy: None,
+            pending_hierarchy: None,
+            project_search: None,
+            last_project_replace: None,
             full_redraw_requested: false,
             time_source: time_source.clone(),
             last_auto_save: time_source.now(),
@@ -1107,9 +1227,11 @@ impl Editor {
     }
 
     /// Check if editing should be disabled for the active buffer
-    /// This returns true when editing_disabled is true (e.g., for read-only virtual buffers)
+    /// This returns true when editing_disabled is true (e.g., for read-only virtual buffers),
+    /// except for binary files, which are read-only as text but editable in hex view
     pub fn is_editing_disabled(&self) -> bool {
-        self.active_state().editing_disabled
+        let state = self.active_state();
+        state.editing_disabled && !(state.buffer.is_binary() && self.is_hex_view_active())
     }
 
     /// Resolve a keybinding for the current mode
@@ -1782,6 +1904,14 @@ impl Editor {
         // Apply bulk edits
         let _delta = state.buffer.apply_bulk_edits(&edit_refs);
 
+        // Keep snippet tabstops in step (descending order keeps positions valid)
+        if let Some(session) = &mut state.snippet_session {
+            for (pos, del_len, text) in &edits {
+                session.adjust_for_delete(*pos, *del_len);
+                session.adjust_for_insert(*pos, text.len());
+            }
+        }
+
         // Snapshot the tree after edits (for redo) - O(1) Arc clone
         let new_tree = state.buffer.snapshot_piece_tree();
 
@@ -2112,7 +2242,6 @@ impl Editor {
 
             // Get mutable references to both buffer and view state
             if let Some(state) = self.buffers.get_mut(&buffer_id) {
-                let buffer = &mut state.buffer;
                 if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
                     if let Some(tokens) = view_transform_tokens {
                         // Use view-aware scrolling with the transform's tokens
@@ -2123,15 +2252,13 @@ impl Editor {
                             .scroll_view_lines(&view_lines, line_offset);
                     } else {
                         // No view transform - use traditional buffer-based scrolling
-                        if line_offset > 0 {
-                            view_state
-                                .viewport
-                                .scroll_down(buffer, line_offset as usize);
-                        } else {
-                            view_state
-                                .viewport
-                                .scroll_up(buffer, line_offset.unsigned_abs());
-                        }
+                        let folded = state.folds.folded_regions(&state.buffer);
+                        scroll_viewport(
+                            &mut view_state.viewport,
+                            &mut state.buffer,
+                            &folded,
+                            line_offset,
+                        );
                     }
                     // Mark to skip ensure_visible on next render so the scroll isn't undone
                     view_state.viewport.set_skip_ensure_visible();
@@ -2766,6 +2893,14 @@ impl Editor {
                     | PromptType::SelectTheme { .. }
                     | PromptType::SelectLocale
                     | PromptType::SwitchToTab
+                    | PromptType::FileHistory { .. }
+                    | PromptType::InsertSnippet
+                    | PromptType::GotoSymbol
+                    | PromptType::WorkspaceSymbol
+                    | PromptType::GotoLocation
+                    | PromptType::CodeLens
+                    | PromptType::ReopenWithEncoding
+                    | PromptType::SaveWithEncoding
                     | PromptType::Plugin { .. }
             ) {
                 // Use the selected suggestion if any
@@ -2997,12 +3132,20 @@ impl Editor {
                 }
             }
             PromptType::SwitchToTab
+            | PromptType::FileHistory { .. }
+            | PromptType::InsertSnippet
+            | PromptType::GotoSymbol
+            | PromptType::GotoLocation
+            | PromptType::CodeLens
             | PromptType::SelectTheme { .. }
             | PromptType::StopLspServer => {
                 if let Some(prompt) = &mut self.prompt {
                     prompt.filter_suggestions(false);
                 }
             }
+            PromptType::WorkspaceSymbol => {
+                self.workspace_symbol_query_changed();
+            }
             PromptType::SelectLocale => {
                 // Locale selection also matches on description (language names)
                 if let Some(prompt) = &mut self.prompt {
@@ -3038,6 +3181,17 @@ impl Editor {
                     completion_trigger_characters,
                     semantic_tokens_legend,
                     semantic_tokens_full,
+                    folding_range,
+                    selection_range,
+                    document_symbol,
+                    workspace_symbol,
+                    formatting,
+                    range_formatting,
+                    on_type_formatting_triggers,
+                    call_hierarchy,
+                    type_hierarchy,
+                    code_lens,
+                    code_lens_resolve,
                 } => {
                     tracing::info!("LSP server initialized for language: {}", language);
                     tracing::debug!(
@@ -3058,6 +3212,18 @@ impl Editor {
                             semantic_tokens_legend,
                             semantic_tokens_full,
                         );
+                        lsp.set_folding_range_support(&language, folding_range);
+                        lsp.set_selection_range_support(&language, selection_range);
+                        lsp.set_document_symbol_support(&language, document_symbol);
+                        lsp.set_workspace_symbol_support(&language, workspace_symbol);
+                        lsp.set_formatting_support(
+                            &language,
+                            formatting,
+                            range_formatting,
+                            on_type_formatting_triggers,
+                        );
+                        lsp.set_hierarchy_support(&language, call_hierarchy, type_hierarchy);
+                        lsp.set_code_lens_support(&language, code_lens, code_lens_resolve);
                     }
 
                     // Send didOpen for all open buffers of this language
@@ -3136,13 +3302,11 @@ impl Editor {
                         tracing::error!("Error handling completion response: {}", e);
                     }
                 }
-                AsyncMessage::LspGotoDefinition {
+                AsyncMessage::LspGoto {
                     request_id,
                     locations,
                 } => {
-                    if let Err(e) = self.handle_goto_definition_response(request_id, locations) {
-                        tracing::error!("Error handling goto definition response: {}", e);
-                    }
+                    self.handle_goto_response(request_id, locations);
                 }
                 AsyncMessage::LspRename { request_id, result } => {
                     if let Err(e) = self.handle_rename_response(request_id, result) {
@@ -3200,6 +3364,66 @@ impl Editor {
                 } => {
                     self.handle_lsp_semantic_tokens(request_id, uri, result);
                 }
+                AsyncMessage::LspFoldingRanges {
+                    request_id,
+                    uri,
+                    ranges,
+                } => {
+                    self.handle_lsp_folding_ranges(request_id, uri, ranges);
+                }
+                AsyncMessage::LspSelectionRanges {
+                    request_id,
+                    uri,
+                    ranges,
+                } => {
+                    self.handle_lsp_selection_ranges(request_id, uri, ranges);
+                }
+                AsyncMessage::LspDocumentSymbols {
+                    request_id,
+                    uri,
+                    symbols,
+                } => {
+                    self.handle_lsp_document_symbols(request_id, uri, symbols);
+                }
+                AsyncMessage::LspFormatting {
+                    request_id,
+                    uri,
+                    edits,
+                } => {
+                    self.handle_lsp_formatting(request_id, uri, edits);
+                }
+                AsyncMessage::LspCallHierarchy { request_id, items } => {
+                    self.handle_call_hierarchy_prepared(request_id, items);
+                }
+                AsyncMessage::LspCallHierarchyCalls { request_id, calls } => {
+                    self.handle_call_hierarchy_calls(request_id, calls);
+                }
+                AsyncMessage::LspTypeHierarchy { request_id, items } => {
+                    self.handle_type_hierarchy_items(request_id, items);
+                }
+                AsyncMessage::LspWorkspaceSymbols {
+                    request_id,
+                    language,
+                    symbols,
+                } => {
+                    self.handle_lsp_workspace_symbols(request_id, language, symbols);
+                }
+                AsyncMessage::LspCodeLenses {
+                    request_id,
+                    uri,
+                    lenses,
+                } => {
+                    self.handle_lsp_code_lenses(request_id, uri, lenses);
+                }
+                AsyncMessage::LspCodeLensResolved { request_id, lens } => {
+                    self.handle_code_lens_resolved(request_id, lens);
+                }
+                AsyncMessage::LspExecuteCommand { request_id, result } => {
+                    self.handle_execute_command_response(request_id, result);
+                }
+                AsyncMessage::LspCodeLensRefresh { language } => {
+                    self.handle_code_lens_refresh(&language);
+                }
                 AsyncMessage::LspServerQuiescent { language } => {
                     self.handle_lsp_server_quiescent(language);
                 }
@@ -3282,6 +3506,51 @@ impl Editor {
                 AsyncMessage::FileOpenDirectoryLoaded(result) => {
                     self.handle_file_open_directory_loaded(result);
                 }
+                AsyncMessage::LineIndexProgress {
+                    buffer_id,
+                    task_id,
+                    first_chunk,
+                    counts,
+                    bytes_scanned,
+                } => {
+                    self.handle_line_index_progress(
+                        buffer_id,
+                        task_id,
+                        first_chunk,
+                        counts,
+                        bytes_scanned,
+                    );
+                }
+                AsyncMessage::SearchProgress {
+                    task_id,
+                    matches,
+                    match_count,
+                    bytes_searched,
+                } => {
+                    self.handle_search_progress(task_id, matches, match_count, bytes_searched);
+                }
+                AsyncMessage::SearchComplete { task_id } => {
+                    self.handle_search_complete(task_id);
+                }
+                AsyncMessage::SearchFailed { task_id, error } => {
+                    self.handle_search_failed(task_id, error);
+                }
+                AsyncMessage::ProjectSearchResults { task_id, files } => {
+                    self.handle_project_search_results(task_id, files);
+                }
+                AsyncMessage::ProjectSearchComplete { task_id, summary } => {
+                    self.handle_project_search_complete(task_id, summary);
+                }
+                AsyncMessage::ProjectSearchFailed { task_id, error } => {
+                    self.handle_project_search_failed(task_id, error);
+                }
+                AsyncMessage::LineIndexFailed {
+                    buffer_id,
+                    task_id,
+                    error,
+                } => {
+                    self.handle_line_index_failed(buffer_id, task_id, error);
+                }
                 AsyncMessage::TerminalOutput { terminal_id } => {
                     // Terminal output received - check if we should auto-jump back to terminal mode
                     tracing::trace!("Terminal output received for {:?}", terminal_id);
@@ -3376,6 +3645,9 @@ impl Editor {
         // Process pending LSP server restarts (with exponential backoff)
         self.process_pending_lsp_restarts();
 
+        // Send the query of the workspace symbol prompt once it settles
+        self.process_workspace_symbol_search();
+
         // Check and clear the plugin render request flag
         #[cfg(feature = "plugins")]
         let plugin_render = {
@@ -3392,12 +3664,18 @@ impl Editor {
             let _ = checker.poll_result();
         }
 
-        // Poll for file changes (auto-revert) and file tree changes
+        // Poll for file changes (auto-revert, follow mode) and file tree changes
         let file_changes = self.poll_file_changes();
+        let followed_changes = self.poll_followed_files();
         let tree_changes = self.poll_file_tree_changes();
 
         // Trigger render if any async messages, plugin commands were processed, or plugin requested render
-        needs_render || processed_any_commands || plugin_render || file_changes || tree_changes
+        needs_render
+            || processed_any_commands
+            || plugin_render
+            || file_changes
+            || followed_changes
+            || tree_changes
     }
 
     /// Update LSP status bar string from active progress operations
@@ -3489,7 +3767,7 @@ impl Editor {
 
                 // Skip diffing in large file mode - too expensive
                 // TODO: Enable when we have an efficient streaming diff algorithm
-                let is_large_file = state.buffer.line_count().is_none();
+                let is_large_file = state.buffer.is_large_file();
                 let diff = if is_large_file {
                     BufferSavedDiff {
                         equal: !state.buffer.is_modified(),
@@ -3808,6 +4086,24 @@ impl Editor {
             } => {
                 self.handle_clear_line_indicators(buffer_id, namespace);
             }
+            PluginCommand::SetScrollbarMark {
+                buffer_id,
+                start_line,
+                end_line,
+                namespace,
+                color,
+                priority,
+            } => {
+                self.handle_set_scrollbar_mark(
+                    buffer_id, start_line, end_line, namespace, color, priority,
+                );
+            }
+            PluginCommand::ClearScrollbarMarks {
+                buffer_id,
+                namespace,
+            } => {
+                self.handle_clear_scrollbar_marks(buffer_id, namespace);
+            }
 
             // ==================== Status/Prompt Commands ====================
             PluginCommand::SetStatus { message } => {
@@ -783,6 +783,15 @@ impl Editor {
                     }
                 }
 
+                // Check encoding indicator area
+                if let Some((enc_row, enc_start, enc_end)) =
+                    self.cached_layout.status_bar_encoding_area
+                {
+                    if row == enc_row && col >= enc_start && col < enc_end {
+                        return Some(HoverTarget::StatusBarEncodingIndicator);
+                    }
+                }
+
                 // Check LSP indicator area
                 if let Some((lsp_row, lsp_start, lsp_end)) = self.cached_layout.status_bar_lsp_area
                 {
@@ -1141,6 +1150,23 @@ impl Editor {
             }
         }
 
+        // Check if click is on the outline panel
+        if let Some(outline_area) = self.cached_layout.outline_area {
+            if col >= outline_area.x
+                && col < outline_area.x + outline_area.width
+                && row >= outline_area.y
+                && row < outline_area.y + outline_area.height
+            {
+                self.handle_outline_click(col, row, outline_area);
+                return Ok(());
+            }
+        }
+
+        // Check if click is on a breadcrumb
+        if self.handle_breadcrumb_click(col, row) {
+            return Ok(());
+        }
+
         // Check if click is on a scrollbar
         let scrollbar_hit = self.cached_layout.split_areas.iter().find_map(
             |(split_id, buffer_id, _content_rect, scrollbar_rect, thumb_start, thumb_end)| {
@@ -1169,6 +1195,16 @@ impl Editor {
                 if let Some(view_state) = self.split_view_states.get(&split_id) {
                     self.mouse_state.drag_start_top_byte = Some(view_state.viewport.top_byte);
                 }
+            } else if let Some(position) = self
+                .cached_layout
+                .scrollbar_mark_rows
+                .get(&split_id)
+                .and_then(|rows| rows.get(row.saturating_sub(scrollbar_rect.y) as usize))
+                .copied()
+                .flatten()
+            {
+                // Click on a mark - move the cursor there
+                self.move_cursor_and_reveal(split_id, position);
             } else {
                 // Click on track - jump to position
                 self.mouse_state.dragging_scrollbar = Some(split_id);
@@ -1189,6 +1225,15 @@ impl Editor {
                     }
                 }
 
+                // Check encoding indicator - click opens reopen-with-encoding selector
+                if let Some((enc_row, enc_start, enc_end)) =
+                    self.cached_layout.status_bar_encoding_area
+                {
+                    if row == enc_row && col >= enc_start && col < enc_end {
+                        return self.handle_action(Action::ReopenWithEncoding);
+                    }
+                }
+
                 // Check LSP indicator - click opens LSP status popup
                 if let Some((lsp_row, lsp_start, lsp_end)) = self.cached_layout.status_bar_lsp_area
                 {
@@ -4,13 +4,16 @@
 //! such as formatters, linters, and other tools.
 
 use std::io::Write;
+use std::ops::Range;
 use std::path::Path;
 use std::process::{Command, Stdio};
 use std::time::Duration;
 
+use super::lsp_formatting::FormattingKind;
 use super::Editor;
-use crate::config::{FormatterConfig, OnSaveAction};
-use crate::model::event::Event;
+use crate::config::{FinalNewlineOption, FormatterConfig, OnSaveAction};
+use crate::model::event::{CursorId, Event};
+use crate::model::save_options::{BackupPolicy, SaveOptions};
 use crate::services::lsp::manager::detect_language;
 use rust_i18n::t;
 
@@ -49,16 +52,18 @@ impl Editor {
         let mut ran_any_action = false;
 
         // Run formatter if format_on_save is enabled
-        if lang_config.format_on_save {
+        if lang_config.format_on_save && self.formats_with_lsp(&language) {
+            // The server's edits arrive later; the buffer is saved again once
+            // they are applied
+            if let Err(e) = self.request_lsp_formatting(FormattingKind::OnSave) {
+                tracing::debug!("Format on save through the language server failed: {}", e);
+            }
+        } else if lang_config.format_on_save {
             if let Some(ref formatter) = lang_config.formatter {
                 match self.run_formatter(formatter, &path) {
                     ActionResult::Success(output) => {
                         self.replace_buffer_with_output(&output)?;
-                        // Re-save after formatting
-                        if let Err(e) = self.active_state_mut().buffer.save() {
-                            return Err(format!("Failed to re-save after format: {}", e));
-                        }
-                        self.active_event_log_mut().mark_saved();
+                        self.resave_after_format()?;
                         ran_any_action = true;
                     }
                     ActionResult::CommandNotFound(cmd) => {
@@ -99,6 +104,111 @@ impl Editor {
         Ok(ran_any_action)
     }
 
+    /// Save the active buffer again after formatting it on save
+    pub(crate) fn resave_after_format(&mut self) -> Result<(), String> {
+        // The previous version was already backed up by the first save
+        let options = SaveOptions {
+            backup: BackupPolicy::None,
+            ..self.active_save_options()
+        };
+        if let Err(e) = self.active_state_mut().buffer.save_with_options(&options) {
+            return Err(format!("Failed to re-save after format: {}", e));
+        }
+        self.active_event_log_mut().mark_saved();
+        self.record_local_history(self.active_buffer());
+        Ok(())
+    }
+
+    /// Apply the built-in on-save transforms configured for `path` to the
+    /// active buffer before it is written.
+    ///
+    /// Each transform is one undoable batch and leaves the cursors where they are.
+    pub(crate) fn apply_save_transforms(&mut self, path: &Path) {
+        let config = self.buffer_config_for_path(path);
+        let line_ending = self.active_state().buffer.line_ending().as_str();
+
+        if config.normalize_line_endings {
+            self.apply_save_transform("Normalize line endings", |text| {
+                line_ending_edits(text, line_ending)
+            });
+        }
+        if config.trim_trailing_whitespace {
+            let changed = config.trim_modified_lines_only.then(|| {
+                let diff = self.active_state().buffer.diff_since_saved();
+                if diff.equal {
+                    Vec::new()
+                } else {
+                    diff.byte_ranges
+                }
+            });
+            self.apply_save_transform("Trim trailing whitespace", |text| {
+                trailing_whitespace_edits(text, changed.as_deref())
+            });
+        }
+        if config.trim_trailing_blank_lines {
+            self.apply_save_transform("Trim trailing blank lines", trailing_blank_line_edits);
+        }
+        if config.final_newline != FinalNewlineOption::Keep {
+            self.apply_save_transform("Final newline", |text| {
+                final_newline_edits(text, config.final_newline, line_ending)
+            });
+        }
+    }
+
+    /// Apply the edits a transform computes from the active buffer's text
+    fn apply_save_transform(
+        &mut self,
+        description: &str,
+        transform: impl FnOnce(&str) -> Vec<(Range<usize>, String)>,
+    ) {
+        // Large files that are not fully loaded are left alone
+        let Some(text) = self.active_state().buffer.to_string() else {
+            return;
+        };
+        let edits = transform(&text);
+        self.apply_edits_to_active_buffer(description, &text, edits);
+    }
+
+    /// Apply byte-range replacements of `text` (the active buffer's content)
+    /// as one undoable batch.
+    ///
+    /// Cursors and marks outside the replaced ranges keep pointing at the same text.
+    pub(crate) fn apply_edits_to_active_buffer(
+        &mut self,
+        description: &str,
+        text: &str,
+        edits: Vec<(Range<usize>, String)>,
+    ) {
+        if edits.is_empty() {
+            return;
+        }
+
+        // Apply later edits first so earlier positions stay valid
+        let mut events = Vec::new();
+        for (range, replacement) in edits.into_iter().rev() {
+            if !range.is_empty() {
+                events.push(Event::Delete {
+                    deleted_text: text[range.clone()].to_string(),
+                    range: range.clone(),
+                    cursor_id: CursorId::UNDO_SENTINEL,
+                });
+            }
+            if !replacement.is_empty() {
+                events.push(Event::Insert {
+                    position: range.start,
+                    text: replacement,
+                    cursor_id: CursorId::UNDO_SENTINEL,
+                });
+            }
+        }
+        let batch = Event::Batch {
+            events,
+            description: description.to_string(),
+        };
+        self.active_event_log_mut().append(batch.clone());
+        self.apply_event_to_active_buffer(&batch);
+    }
+
     /// Format the current buffer using the configured formatter.
     /// Returns Ok(()) if formatting succeeded, or Err with an error message.
     pub fn format_buffer(&mut self) -> Result<(), String> {
@@ -117,6 +227,10 @@ impl Editor {
             None => return Err("No language detected for this file".to_string()),
         };
 
+        if self.formats_with_lsp(&language) {
+            return self.request_lsp_formatting(FormattingKind::Document);
+        }
+
         // Get formatter for this language
         let formatter = self
             .config
@@ -452,6 +566,89 @@ impl Editor {
     }
 }
 
+/// Replace every line ending in `text` that differs from `line_ending`
+fn line_ending_edits(text: &str, line_ending: &str) -> Vec<(Range<usize>, String)> {
+    let bytes = text.as_bytes();
+    let mut edits = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let len = match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => 2,
+            b'\r' | b'\n' => 1,
+            _ => 0,
+        };
+        if len == 0 {
+            i += 1;
+            continue;
+        }
+        if &text[i..i + len] != line_ending {
+            edits.push((i..i + len, line_ending.to_string()));
+        }
+        i += len;
+    }
+    edits
+}
+
+/// Remove trailing spaces and tabs from each line of `text`, or only from the
+/// lines touching one of the `changed` byte ranges
+fn trailing_whitespace_edits(
+    text: &str,
+    changed: Option<&[Range<usize>]>,
+) -> Vec<(Range<usize>, String)> {
+    let mut edits = Vec::new();
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let trimmed = content.trim_end_matches([' ', '\t']);
+        let line_end = line_start + content.len();
+        let modified = changed.is_none_or(|ranges| {
+            ranges
+                .iter()
+                .any(|r| r.start <= line_end && r.end >= line_start)
+        });
+        if trimmed.len() < content.len() && modified {
+            edits.push((line_start + trimmed.len()..line_end, String::new()));
+        }
+        line_start += line.len();
+    }
+    edits
+}
+
+/// Remove the blank lines after the last line with content
+fn trailing_blank_line_edits(text: &str) -> Vec<(Range<usize>, String)> {
+    let content_end = text.trim_end_matches([' ', '\t', '\r', '\n']).len();
+    let rest = &text[content_end..];
+    // Keep the line ending of the last line with content
+    let Some(i) = rest.find(['\r', '\n']) else {
+        return Vec::new();
+    };
+    let keep = content_end + i + if rest[i..].starts_with("\r\n") { 2 } else { 1 };
+    if keep < text.len() {
+        vec![(keep..text.len(), String::new())]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Add or remove the line ending at the end of `text`
+fn final_newline_edits(
+    text: &str,
+    option: FinalNewlineOption,
+    line_ending: &str,
+) -> Vec<(Range<usize>, String)> {
+    let ends_with_newline = text.ends_with(['\n', '\r']);
+    match option {
+        FinalNewlineOption::Ensure if !text.is_empty() && !ends_with_newline => {
+            vec![(text.len()..text.len(), line_ending.to_string())]
+        }
+        FinalNewlineOption::Strip if ends_with_newline => {
+            let len = if text.ends_with("\r\n") { 2 } else { 1 };
+            vec![(text.len() - len..text.len(), String::new())]
+        }
+        _ => Vec::new(),
+    }
+}
+
 /// Check if a command exists in the system PATH.
 fn command_exists(command: &str) -> bool {
     // Use 'which' on Unix or 'where' on Windows to check if command exists
@@ -515,3 +712,75 @@ fn detect_shell() -> String {
     // Last resort
     "sh".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(text: &str, edits: Vec<(Range<usize>, String)>) -> String {
+        let mut result = text.to_string();
+        for (range, replacement) in edits.into_iter().rev() {
+            result.replace_range(range, &replacement);
+        }
+        result
+    }
+
+    #[test]
+    fn test_line_ending_edits() {
+        let text = "a\r\nb\nc\rd\r\n";
+        assert_eq!(apply(text, line_ending_edits(text, "\n")), "a\nb\nc\nd\n");
+        assert_eq!(
+            apply(text, line_ending_edits(text, "\r\n")),
+            "a\r\nb\r\nc\r\nd\r\n"
+        );
+        assert!(line_ending_edits("a\nb\n", "\n").is_empty());
+    }
+
+    #[test]
+    fn test_trailing_whitespace_edits() {
+        let text = "a  \r\nb\t\nc \nd";
+        assert_eq!(
+            apply(text, trailing_whitespace_edits(text, None)),
+            "a\r\nb\nc\nd"
+        );
+        // Only the line touching the changed range is trimmed
+        let changed = [6..7];
+        assert_eq!(
+            apply(text, trailing_whitespace_edits(text, Some(&changed))),
+            "a  \r\nb\nc \nd"
+        );
+        assert!(trailing_whitespace_edits(text, Some(&[])).is_empty());
+    }
+
+    #[test]
+    fn test_trailing_blank_line_edits() {
+        let text = "a\n\n  \n\n";
+        assert_eq!(apply(text, trailing_blank_line_edits(text)), "a\n");
+        let text = "a  \r\n\r\n";
+        assert_eq!(apply(text, trailing_blank_line_edits(text)), "a  \r\n");
+        assert!(trailing_blank_line_edits("a\n").is_empty());
+        assert!(trailing_blank_line_edits("a").is_empty());
+    }
+
+    #[test]
+    fn test_final_newline_edits() {
+        let ensure = |text: &str| {
+            apply(
+                text,
+                final_newline_edits(text, FinalNewlineOption::Ensure, "\r\n"),
+            )
+        };
+        let strip = |text: &str| {
+            apply(
+                text,
+                final_newline_edits(text, FinalNewlineOption::Strip, "\n"),
+            )
+        };
+        assert_eq!(ensure("a"), "a\r\n");
+        assert_eq!(ensure("a\n"), "a\n");
+        assert_eq!(ensure(""), "");
+        assert_eq!(strip("a\r\n"), "a");
+        assert_eq!(strip("a\n\n"), "a\n");
+        assert_eq!(strip("a"), "a");
+    }
+}
@@ -0,0 +1,322 @@
+//! Outline panel and breadcrumbs (see `view::outline` and
+//! `view::document_symbols`).
+
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use rust_i18n::t;
+
+use crate::input::keybindings::KeyContext;
+use crate::model::event::{BufferId, SplitId};
+use crate::view::document_symbols::{kind_label, siblings, symbol_path};
+use crate::view::outline::{OutlinePanel, OutlineRow};
+use crate::view::prompt::{Prompt, PromptType};
+use crate::view::ui::OutlineRenderer;
+
+use super::Editor;
+
+impl Editor {
+    pub fn outline_visible(&self) -> bool {
+        self.outline.is_some()
+    }
+
+    pub fn toggle_outline(&mut self) {
+        if self.outline.take().is_some() {
+            if self.key_context == KeyContext::Outline {
+                self.key_context = KeyContext::Normal;
+            }
+            self.set_status_message(t!("outline.closed").to_string());
+        } else {
+            self.outline = Some(OutlinePanel::new());
+            self.key_context = KeyContext::Outline;
+            self.set_status_message(t!("outline.opened").to_string());
+        }
+    }
+
+    pub fn focus_outline(&mut self) {
+        if self.outline.is_some() {
+            self.on_editor_focus_lost();
+            self.cancel_search_prompt_if_active();
+            self.key_context = KeyContext::Outline;
+            self.set_status_message(t!("outline.focused").to_string());
+        } else {
+            self.toggle_outline();
+        }
+    }
+
+    /// Rows of the outline panel for the active buffer, and the path to the
+    /// symbol at the cursor
+    pub(super) fn outline_rows(&mut self) -> (Vec<OutlineRow>, Vec<usize>) {
+        let buffer_id = self.active_buffer();
+        let (Some(panel), Some(state)) = (self.outline.as_ref(), self.buffers.get_mut(&buffer_id))
+        else {
+            return (Vec::new(), Vec::new());
+        };
+        let cursor = state.cursors.primary().position;
+        let symbols = state.symbols.symbols(
+            &state.buffer,
+            &mut state.syntax_tree,
+            state.highlighter.language(),
+        );
+        (panel.rows(symbols), symbol_path(symbols, cursor))
+    }
+
+    /// Handle a key while the outline panel has focus; false for keys that
+    /// go to the keybinding resolver
+    pub(super) fn handle_outline_key(&mut self, key_event: &KeyEvent) -> bool {
+        let buffer_id = self.active_buffer();
+        let (Some(panel), Some(state)) = (self.outline.as_mut(), self.buffers.get_mut(&buffer_id))
+        else {
+            return false;
+        };
+        let symbols = state.symbols.symbols(
+            &state.buffer,
+            &mut state.syntax_tree,
+            state.highlighter.language(),
+        );
+        let rows = panel.rows(symbols);
+        let last = rows.len().saturating_sub(1);
+        let selected = rows.get(panel.selected);
+
+        let mut jump = None;
+        match (key_event.code, key_event.modifiers) {
+            (KeyCode::Up, KeyModifiers::NONE) => panel.selected = panel.selected.saturating_sub(1),
+            (KeyCode::Down, KeyModifiers::NONE) => panel.selected = (panel.selected + 1).min(last),
+            (KeyCode::Home, KeyModifiers::NONE) => panel.selected = 0,
+            (KeyCode::End, KeyModifiers::NONE) => panel.selected = last,
+            (KeyCode::Left, KeyModifiers::NONE) => {
+                if let Some(row) = selected {
+                    if row.has_children && row.expanded && panel.filter.is_empty() {
+                        panel.set_expanded(symbols, row, false);
+                    } else if let Some(parent) = rows
+                        .iter()
+                        .position(|r| r.path == row.path[..row.path.len() - 1])
+                    {
+                        panel.selected = parent;
+                    }
+                }
+            }
+            (KeyCode::Right, KeyModifiers::NONE) => {
+                if let Some(row) = selected {
+                    if row.has_children && !row.expanded {
+                        panel.set_expanded(symbols, row, true);
+                    } else if row.has_children {
+                        panel.selected = (panel.selected + 1).min(last);
+                    }
+                }
+            }
+            (KeyCode::Enter, KeyModifiers::NONE) => {
+                let Some(row) = selected else {
+                    return true;
+                };
+                jump = Some(row.position);
+            }
+            (KeyCode::Backspace, KeyModifiers::NONE) => {
+                let mut filter = panel.filter.clone();
+                filter.pop();
+                panel.set_filter(symbols, filter);
+            }
+            (KeyCode::Esc, KeyModifiers::NONE) => {
+                if panel.filter.is_empty() {
+                    self.key_context = KeyContext::Normal;
+                } else {
+                    panel.set_filter(symbols, String::new());
+                }
+            }
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                let filter = format!("{}{}", panel.filter, c);
+                panel.set_filter(symbols, filter);
+            }
+            _ => return false,
+        }
+
+        if let Some(position) = jump {
+            self.move_cursor_and_reveal(self.split_manager.active_split(), position);
+            self.key_context = KeyContext::Normal;
+        }
+        true
+    }
+
+    /// Handle a click in the outline panel
+    pub(super) fn handle_outline_click(&mut self, col: u16, row: u16, area: Rect) {
+        self.key_context = KeyContext::Outline;
+        let buffer_id = self.active_buffer();
+        let (rows, _) = self.outline_rows();
+        let Some(panel) = self.outline.as_mut() else {
+            return;
+        };
+        // The panel has a 1-line border at top and bottom
+        if row <= area.y {
+            return;
+        }
+        let index = (row - area.y - 1) as usize + panel.scroll_offset;
+        let Some(clicked) = rows.get(index) else {
+            return;
+        };
+        panel.selected = index;
+
+        if clicked.has_children && col == area.x + OutlineRenderer::indicator_column(clicked) {
+            if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                let symbols = state.symbols.symbols(
+                    &state.buffer,
+                    &mut state.syntax_tree,
+                    state.highlighter.language(),
+                );
+                panel.set_expanded(symbols, clicked, !clicked.expanded);
+            }
+        } else {
+            self.move_cursor_and_reveal(self.split_manager.active_split(), clicked.position);
+            self.key_context = KeyContext::Normal;
+        }
+    }
+
+    /// Pick a symbol among the siblings of the one at the cursor
+    pub fn pick_sibling_symbol(&mut self) {
+        let buffer_id = self.active_buffer();
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let cursor = state.cursors.primary().position;
+        let symbols = state.symbols.symbols(
+            &state.buffer,
+            &mut state.syntax_tree,
+            state.highlighter.language(),
+        );
+        let path = symbol_path(symbols, cursor);
+        let depth = path.len().saturating_sub(1);
+        self.show_symbol_picker(buffer_id, &path[..depth], path.last().copied());
+    }
+
+    /// Open a picker for the children of the symbol at `parent_path`,
+    /// selecting the one at `current`
+    fn show_symbol_picker(
+        &mut self,
+        buffer_id: BufferId,
+        parent_path: &[usize],
+        current: Option<usize>,
+    ) {
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return;
+        };
+        let symbols = state.symbols.symbols(
+            &state.buffer,
+            &mut state.syntax_tree,
+            state.highlighter.language(),
+        );
+        let suggestions: Vec<_> = siblings(symbols, parent_path)
+            .iter()
+            .map(|symbol| crate::input::commands::Suggestion {
+                text: symbol.name.clone(),
+                description: Some(kind_label(symbol.kind).to_string()),
+                value: Some(symbol.position.to_string()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+        if suggestions.is_empty() {
+            self.set_status_message(t!("outline.empty").to_string());
+            return;
+        }
+        let mut prompt = Prompt::with_suggestions(
+            t!("outline.goto_symbol").to_string(),
+            PromptType::GotoSymbol,
+            suggestions,
+        );
+        if let Some(current) = current {
+            prompt.selected_suggestion = Some(current);
+        }
+        self.prompt = Some(prompt);
+    }
+
+    /// Move to the symbol picked in the "Go to Symbol" prompt
+    pub(crate) fn goto_symbol_from_prompt(&mut self, position: &str) {
+        if let Ok(position) = position.parse::<usize>() {
+            self.move_cursor_and_reveal(self.split_manager.active_split(), position);
+        }
+    }
+
+    /// Breadcrumbs of the visible splits: the file name, then the names of
+    /// the symbols containing the split's cursor
+    pub(super) fn breadcrumbs(&mut self, area: Rect) -> HashMap<SplitId, Vec<String>> {
+        let mut crumbs = HashMap::new();
+        if !self.config.editor.breadcrumbs {
+            return crumbs;
+        }
+        let active_split = self.split_manager.active_split();
+        for (split_id, buffer_id, _) in self.split_manager.get_visible_buffers(area) {
+            let Some(file_name) = self
+                .buffer_metadata
+                .get(&buffer_id)
+                .and_then(|metadata| metadata.file_path())
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+            else {
+                continue;
+            };
+            self.maybe_request_document_symbols(buffer_id);
+            let Some(state) = self.buffers.get_mut(&buffer_id) else {
+                continue;
+            };
+            if state.is_composite_buffer {
+                continue;
+            }
+            let cursor = if split_id == active_split {
+                state.cursors.primary().position
+            } else {
+                match self.split_view_states.get(&split_id) {
+                    Some(view_state) => view_state.cursors.primary().position,
+                    None => continue,
+                }
+            };
+            let symbols = state.symbols.symbols(
+                &state.buffer,
+                &mut state.syntax_tree,
+                state.highlighter.language(),
+            );
+            let mut names = vec![file_name];
+            let mut level = symbols;
+            for index in symbol_path(symbols, cursor) {
+                names.push(level[index].name.clone());
+                level = &level[index].children;
+            }
+            crumbs.insert(split_id, names);
+        }
+        crumbs
+    }
+
+    /// Handle a click on a breadcrumb, opening a picker for its siblings;
+    /// false if the click is not on one
+    pub(super) fn handle_breadcrumb_click(&mut self, col: u16, row: u16) -> bool {
+        let Some((split_id, crumb)) = self.cached_layout.breadcrumb_areas.iter().find_map(
+            |(split_id, crumb, crumb_row, start_col, end_col)| {
+                (row == *crumb_row && col >= *start_col && col < *end_col)
+                    .then_some((*split_id, *crumb))
+            },
+        ) else {
+            return false;
+        };
+        let Some(buffer_id) = self.split_manager.get_buffer_id(split_id) else {
+            return false;
+        };
+        self.focus_split(split_id, buffer_id);
+        self.key_context = KeyContext::Normal;
+
+        let Some(state) = self.buffers.get_mut(&buffer_id) else {
+            return true;
+        };
+        let cursor = state.cursors.primary().position;
+        let symbols = state.symbols.symbols(
+            &state.buffer,
+            &mut state.syntax_tree,
+            state.highlighter.language(),
+        );
+        let path = symbol_path(symbols, cursor);
+        // The first crumb is the file: it lists the top-level symbols
+        let parent = crumb.saturating_sub(1).min(path.len());
+        let current = path.get(parent).copied();
+        self.show_symbol_picker(buffer_id, &path[..parent], current);
+        true
+    }
+}
@@ -192,7 +192,14 @@ impl Editor {
     /// Handle ClearVirtualTexts command
     pub(super) fn handle_clear_virtual_texts(&mut self, buffer_id: BufferId) {
         if let Some(state) = self.buffers.get_mut(&buffer_id) {
-            state.virtual_texts.clear(&mut state.marker_list);
+            use crate::view::code_lens;
+            use crate::view::virtual_text::VirtualTextNamespace;
+            // Code lenses are the editor's own virtual lines, only re-added
+            // when the buffer changes
+            let code_lenses = VirtualTextNamespace::from_string(code_lens::NAMESPACE.to_string());
+            state
+                .virtual_texts
+                .clear_except_namespace(&mut state.marker_list, &code_lenses);
         }
     }
 
@@ -935,6 +942,46 @@ impl Editor {
         }
     }
 
+    /// Handle SetScrollbarMark command
+    pub(super) fn handle_set_scrollbar_mark(
+        &mut self,
+        buffer_id: BufferId,
+        start_line: usize,
+        end_line: usize,
+        namespace: String,
+        color: (u8, u8, u8),
+        priority: i32,
+    ) {
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            // Convert line numbers to byte offsets for marker-based tracking
+            let buffer_len = state.buffer.len();
+            let start = state
+                .buffer
+                .line_start_offset(start_line)
+                .unwrap_or(buffer_len);
+            let end = state
+                .buffer
+                .line_start_offset(end_line.max(start_line))
+                .unwrap_or(buffer_len);
+            state.scrollbar_marks.add(
+                &mut state.marker_list,
+                namespace,
+                start..end,
+                ratatui::style::Color::Rgb(color.0, color.1, color.2),
+                priority,
+            );
+        }
+    }
+
+    /// Handle ClearScrollbarMarks command
+    pub(super) fn handle_clear_scrollbar_marks(&mut self, buffer_id: BufferId, namespace: String) {
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state
+                .scrollbar_marks
+                .clear_namespace(&mut state.marker_list, &namespace);
+        }
+    }
+
     // ==================== Status/Prompt Commands ====================
 
     /// Handle SetStatus command
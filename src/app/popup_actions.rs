@@ -4,7 +4,7 @@
 
 use super::Editor;
 use crate::model::event::Event;
-use crate::primitives::snippet::{expand_snippet, is_snippet};
+use crate::primitives::snippet::is_snippet;
 use crate::primitives::word_navigation::find_completion_word_start;
 use rust_i18n::t;
 
@@ -69,6 +69,21 @@ impl Editor {
             return PopupConfirmResult::EarlyReturn;
         }
 
+        // If it's a snippet choice popup, replace the tabstop with the chosen option
+        let snippet_choice = self
+            .active_state()
+            .popups
+            .top()
+            .filter(|popup| popup.title.as_deref() == Some(&*t!("snippet.popup_choice")))
+            .map(|popup| popup.selected_item().and_then(|item| item.data.clone()));
+        if let Some(choice) = snippet_choice {
+            self.hide_popup();
+            if let Some(choice) = choice {
+                self.replace_selections(choice, "Snippet choice");
+            }
+            return PopupConfirmResult::EarlyReturn;
+        }
+
         // If it's a completion popup, insert the selected item
         let completion_text = if let Some(popup) = self.active_state().popups.top() {
             if let Some(title) = &popup.title {
@@ -84,26 +99,20 @@ impl Editor {
             None
         };
 
-        // Perform the completion if we have text
+        self.hide_popup();
+
+        // Perform the completion if we have text (after hiding the popup, as
+        // a snippet may open its own)
         if let Some(text) = completion_text {
             self.insert_completion_text(text);
         }
 
-        self.hide_popup();
         PopupConfirmResult::Done
     }
 
     /// Insert completion text, replacing the word prefix at cursor.
-    /// If the text contains LSP snippet syntax, it will be expanded.
+    /// Snippets start a snippet session on their tabstops.
     fn insert_completion_text(&mut self, text: String) {
-        // Check if this is a snippet and expand it
-        let (insert_text, cursor_offset) = if is_snippet(&text) {
-            let expanded = expand_snippet(&text);
-            (expanded.text, Some(expanded.cursor_offset))
-        } else {
-            (text, None)
-        };
-
         let (cursor_id, cursor_pos, word_start) = {
             let state = self.active_state();
             let cursor_id = state.cursors.primary_id();
@@ -112,6 +121,11 @@ impl Editor {
             (cursor_id, cursor_pos, word_start)
         };
 
+        if is_snippet(&text) || self.is_snippet_completion(&text) {
+            self.insert_snippet(&text, word_start..cursor_pos);
+            return;
+        }
+
         let deleted_text = if word_start < cursor_pos {
             self.active_state_mut()
                 .get_text_range(word_start, cursor_pos)
@@ -137,31 +151,12 @@ impl Editor {
 
         let insert_event = Event::Insert {
             position: insert_pos,
-            text: insert_text.clone(),
+            text,
             cursor_id,
         };
 
         self.active_event_log_mut().append(insert_event.clone());
         self.apply_event_to_active_buffer(&insert_event);
-
-        // If this was a snippet, position cursor at the snippet's $0 location
-        if let Some(offset) = cursor_offset {
-            let new_cursor_pos = insert_pos + offset;
-            // Get current cursor position after the insert
-            let current_pos = self.active_state().cursors.primary().position;
-            if current_pos != new_cursor_pos {
-                let move_event = Event::MoveCursor {
-                    cursor_id,
-                    old_position: current_pos,
-                    new_position: new_cursor_pos,
-                    old_anchor: None,
-                    new_anchor: None,
-                    old_sticky_column: 0,
-                    new_sticky_column: 0,
-                };
-                self.active_state_mut().apply(&move_event);
-            }
-        }
     }
 
     /// Handle PopupCancel action.
@@ -0,0 +1,894 @@
+//! Project search panel.
+//!
+//! Lists the matches of a project-wide search in a virtual buffer, grouped by
+//! file, as they stream in from a [`ProjectSearchTask`]. Enter opens the match
+//! under the cursor. Once a replacement is chosen, every match shows a preview
+//! of its changed line and can be toggled before the replacement is applied.
+//! Applying edits the files that are open in buffers (so each can also be undone
+//! there) and loads and saves the others the same way, keeping their encoding;
+//! Undo Project Replace reverts the whole operation.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use rust_i18n::t;
+
+use super::Editor;
+use crate::model::buffer::TextBuffer;
+use crate::model::event::{BufferId, Event, OverlayFace, SplitDirection, SplitId};
+use crate::model::save_options::SaveOptions;
+use crate::primitives::text_property::TextPropertyEntry;
+use crate::services::lsp::manager::detect_language;
+use crate::services::project_search::{
+    FileMatches, ProjectSearchQuery, ProjectSearchSummary, ProjectSearchTask,
+};
+use crate::view::overlay::OverlayNamespace;
+use crate::view::prompt::PromptType;
+use crate::view::split::SplitViewState;
+
+/// Display name of the project search panel buffer
+pub(crate) const PROJECT_SEARCH_BUFFER_NAME: &str = "*Project Search*";
+
+/// Buffer mode of the project search panel (registered in `ModeRegistry::new`)
+pub(crate) const PROJECT_SEARCH_MODE: &str = "project-search";
+
+/// Text property holding `[file]` or `[file, match]` indices on panel rows
+const MATCH_PROPERTY: &str = "project_match";
+
+/// Overlay namespace for the panel's match highlighting
+const OVERLAY_NAMESPACE: &str = "project-search";
+
+/// Background highlights of the panel content, by byte range
+type Highlights = Vec<(Range<usize>, (u8, u8, u8))>;
+
+/// State of the open project search panel
+pub(crate) struct ProjectSearchPanel {
+    /// The panel's virtual buffer
+    buffer_id: BufferId,
+
+    /// Split that matches are opened in
+    source_split: SplitId,
+
+    /// The search shown in the panel
+    query: ProjectSearchQuery,
+
+    /// The query's compiled regex, for replacement previews
+    regex: Regex,
+
+    /// Replacement text, once chosen
+    replacement: Option<String>,
+
+    /// Files with matches, in path order
+    files: Vec<FileMatches>,
+
+    /// Matches left out of the replace, as (file, match) indices
+    deselected: HashSet<(usize, usize)>,
+
+    /// The search, while it is running
+    task: Option<ProjectSearchTask>,
+
+    /// Totals, once the search has finished
+    summary: Option<ProjectSearchSummary>,
+}
+
+/// What a project-wide replace changed, so it can be undone as a whole
+#[derive(Default)]
+pub(crate) struct ProjectReplaceRecord {
+    /// Buffers edited, with the undo state the replace left each one in
+    buffers: Vec<(BufferId, usize)>,
+
+    /// Files written, with their content before and after the replace
+    files: Vec<(PathBuf, String, String)>,
+}
+
+impl Editor {
+    /// Prompt for the text to search the project for
+    pub(crate) fn start_project_search_prompt(&mut self, prompt_type: PromptType) {
+        let message = if prompt_type == PromptType::ProjectReplaceSearch {
+            t!("project_search.replace_search_prompt")
+        } else {
+            t!("project_search.prompt")
+        };
+        let initial_text = self
+            .project_search
+            .as_ref()
+            .map(|panel| panel.query.text.clone())
+            .unwrap_or_default();
+        self.start_prompt_with_initial_text(message.to_string(), prompt_type, initial_text);
+    }
+
+    /// Prompt for the replacement of the panel's matches, or for the search
+    /// first if the panel isn't focused
+    pub(crate) fn start_project_replace(&mut self) {
+        let Some(panel) = self
+            .project_search
+            .as_ref()
+            .filter(|panel| panel.buffer_id == self.active_buffer())
+        else {
+            self.start_project_search_prompt(PromptType::ProjectReplaceSearch);
+            return;
+        };
+        let message = t!("project_search.replace_prompt", search = &panel.query.text).to_string();
+        let initial_text = panel.replacement.clone().unwrap_or_default();
+        self.start_prompt_with_initial_text(message, PromptType::ProjectReplace, initial_text);
+    }
+
+    /// Prompt for the include/exclude globs of the panel's search
+    pub(crate) fn start_project_search_filter_prompt(&mut self) {
+        let Some(panel) = &self.project_search else {
+            return;
+        };
+        let initial_text = panel.query.filters();
+        self.start_prompt_with_initial_text(
+            t!("project_search.files_prompt").to_string(),
+            PromptType::ProjectSearchFiles,
+            initial_text,
+        );
+    }
+
+    /// Search the project for `text` with the current search options, keeping
+    /// the file globs of the previous search. Returns whether the search started.
+    pub(crate) fn start_project_search(&mut self, text: &str) -> bool {
+        if text.is_empty() {
+            self.set_status_message(t!("search.cancelled").to_string());
+            return false;
+        }
+        let mut query = self
+            .project_search
+            .as_ref()
+            .map(|panel| panel.query.clone())
+            .unwrap_or_default();
+        query.text = text.to_string();
+        query.use_regex = self.search_use_regex;
+        query.case_sensitive = self.search_case_sensitive;
+        query.whole_word = self.search_whole_word;
+        self.run_project_search(query, None, true)
+    }
+
+    /// Choose the replacement for the panel's matches and preview it
+    pub(crate) fn set_project_replacement(&mut self, replacement: String) {
+        let Some(panel) = &mut self.project_search else {
+            return;
+        };
+        panel.replacement = Some(replacement);
+        self.render_project_search_panel();
+        self.set_status_message(t!("project_search.preview_ready").to_string());
+    }
+
+    /// Change the file globs of the panel's search and run it again
+    pub(crate) fn set_project_search_filters(&mut self, filters: &str) {
+        let Some(panel) = &self.project_search else {
+            return;
+        };
+        let mut query = panel.query.clone();
+        let replacement = panel.replacement.clone();
+        query.set_filters(filters);
+        self.run_project_search(query, replacement, true);
+    }
+
+    /// Start a search and show its results in the panel, focusing it unless
+    /// this only refreshes the results
+    fn run_project_search(
+        &mut self,
+        query: ProjectSearchQuery,
+        replacement: Option<String>,
+        focus: bool,
+    ) -> bool {
+        let regex = match query.regex() {
+            Ok(regex) => regex,
+            Err(e) => {
+                self.set_status_message(
+                    t!("error.invalid_regex", error = e.to_string()).to_string(),
+                );
+                return false;
+            }
+        };
+
+        // Search open files through their buffers, so unsaved changes count
+        let open_buffers: HashMap<PathBuf, String> = self
+            .buffer_metadata
+            .iter()
+            .filter_map(|(id, metadata)| {
+                let path = metadata.file_path()?;
+                let state = self.buffers.get(id)?;
+                if state.buffer.is_large_file() || state.buffer.is_binary() {
+                    return None;
+                }
+                Some((path.clone(), state.buffer.to_string()?))
+            })
+            .collect();
+
+        let (Some(runtime), Some(bridge)) = (&self.tokio_runtime, &self.async_bridge) else {
+            return false;
+        };
+        let task = ProjectSearchTask::spawn(
+            runtime,
+            bridge.sender(),
+            self.working_dir.clone(),
+            query.clone(),
+            open_buffers,
+            self.config.editor.large_file_threshold_bytes,
+        );
+
+        let existing = self
+            .project_search
+            .take()
+            .filter(|panel| self.buffers.contains_key(&panel.buffer_id));
+        let (buffer_id, source_split) = match existing {
+            Some(panel) => (panel.buffer_id, panel.source_split),
+            None => {
+                let buffer_id = self.create_virtual_buffer(
+                    PROJECT_SEARCH_BUFFER_NAME.to_string(),
+                    PROJECT_SEARCH_MODE.to_string(),
+                    true,
+                );
+                if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                    state.margins.set_line_numbers(false);
+                    state.editing_disabled = true;
+                }
+                (buffer_id, self.split_manager.active_split())
+            }
+        };
+        self.project_search = Some(ProjectSearchPanel {
+            buffer_id,
+            source_split,
+            query,
+            regex,
+            replacement,
+            files: Vec::new(),
+            deselected: HashSet::new(),
+            task: Some(task),
+            summary: None,
+        });
+
+        if focus {
+            self.focus_project_search_panel(buffer_id);
+            self.set_cursor_in_buffer(buffer_id, 0);
+        }
+        self.render_project_search_panel();
+        true
+    }
+
+    /// Focus the panel, splitting it off next to the active split if it isn't visible
+    fn focus_project_search_panel(&mut self, buffer_id: BufferId) {
+        self.save_current_split_view_state();
+        if let Some(&split_id) = self.split_manager.splits_for_buffer(buffer_id).first() {
+            self.split_manager.set_active_split(split_id);
+        } else {
+            match self
+                .split_manager
+                .split_active(SplitDirection::Vertical, buffer_id, 0.5)
+            {
+                Ok(split_id) => {
+                    let mut view_state = SplitViewState::with_buffer(
+                        self.terminal_width,
+                        self.terminal_height,
+                        buffer_id,
+                    );
+                    view_state.viewport.line_wrap_enabled = false;
+                    self.split_view_states.insert(split_id, view_state);
+                    self.split_manager.set_active_split(split_id);
+                }
+                Err(e) => {
+                    self.set_status_message(t!("split.error", error = e).to_string());
+                }
+            }
+        }
+        self.restore_current_split_view_state();
+    }
+
+    /// Add a batch of files with matches to the panel
+    pub(super) fn handle_project_search_results(&mut self, task_id: u64, files: Vec<FileMatches>) {
+        let Some(panel) = self.project_search_for_task(task_id) else {
+            return;
+        };
+        panel.files.extend(files);
+        self.render_project_search_panel();
+    }
+
+    /// Show the totals of a finished project search
+    pub(super) fn handle_project_search_complete(
+        &mut self,
+        task_id: u64,
+        summary: ProjectSearchSummary,
+    ) {
+        let Some(panel) = self.project_search_for_task(task_id) else {
+            return;
+        };
+        panel.task = None;
+        panel.summary = Some(summary);
+        let matches: usize = panel.files.iter().map(|f| f.matches.len()).sum();
+        let files = panel.files.len();
+        let text = panel.query.text.clone();
+        self.render_project_search_panel();
+        if matches == 0 {
+            self.set_status_message(t!("search.no_occurrences", search = text).to_string());
+        } else {
+            self.set_status_message(
+                t!("project_search.summary", matches = matches, files = files).to_string(),
+            );
+        }
+    }
+
+    /// Report a project search that could not run
+    pub(super) fn handle_project_search_failed(&mut self, task_id: u64, error: String) {
+        let Some(panel) = self.project_search_for_task(task_id) else {
+            return;
+        };
+        panel.task = None;
+        tracing::warn!("Project search failed: {}", error);
+        self.render_project_search_panel();
+        self.set_status_message(t!("project_search.failed", error = error).to_string());
+    }
+
+    /// The panel, if `task_id` is its running search
+    fn project_search_for_task(&mut self, task_id: u64) -> Option<&mut ProjectSearchPanel> {
+        self.project_search
+            .as_mut()
+            .filter(|panel| panel.task.as_ref().is_some_and(|task| task.id == task_id))
+    }
+
+    /// Forget the project search panel if its buffer is being closed
+    pub(crate) fn on_project_search_buffer_closed(&mut self, buffer_id: BufferId) {
+        if self.project_search.as_ref().map(|p| p.buffer_id) == Some(buffer_id) {
+            self.project_search = None;
+        }
+    }
+
+    /// Open the match under the panel's cursor in the split the search started from
+    pub(crate) fn project_search_open_match(&mut self) {
+        let Some((file, index)) = self.project_search_selection() else {
+            return;
+        };
+        let Some(panel) = &self.project_search else {
+            return;
+        };
+        let file_matches = &panel.files[file];
+        let Some(m) = file_matches.matches.get(index.unwrap_or(0)) else {
+            return;
+        };
+        let path = file_matches.path.clone();
+        let (line, column) = (m.line + 1, m.range.start + 1);
+        let source_split = panel.source_split;
+
+        self.save_current_split_view_state();
+        if self.split_view_states.contains_key(&source_split) {
+            self.split_manager.set_active_split(source_split);
+        }
+        self.restore_current_split_view_state();
+        if let Err(e) = self.open_file(&path) {
+            self.set_status_message(t!("file.error_opening", error = e.to_string()).to_string());
+            return;
+        }
+        self.jump_to_line_column(Some(line), Some(column));
+    }
+
+    /// Include or leave out the match under the cursor (or all matches of the
+    /// file under the cursor) when replacing
+    pub(crate) fn project_search_toggle_match(&mut self) {
+        let Some((file, index)) = self.project_search_selection() else {
+            return;
+        };
+        let Some(panel) = &mut self.project_search else {
+            return;
+        };
+        if panel.replacement.is_none() {
+            self.set_status_message(t!("project_search.no_replacement").to_string());
+            return;
+        }
+        match index {
+            Some(index) => {
+                if !panel.deselected.remove(&(file, index)) {
+                    panel.deselected.insert((file, index));
+                }
+            }
+            None => {
+                let keys: Vec<_> = (0..panel.files[file].matches.len())
+                    .map(|index| (file, index))
+                    .collect();
+                if keys.iter().any(|key| !panel.deselected.contains(key)) {
+                    panel.deselected.extend(keys);
+                } else {
+                    for key in &keys {
+                        panel.deselected.remove(key);
+                    }
+                }
+            }
+        }
+        let total: usize = panel.files.iter().map(|f| f.matches.len()).sum();
+        let selected = total - panel.deselected.len();
+        self.render_project_search_panel();
+        self.set_status_message(
+            t!(
+                "project_search.selected",
+                selected = selected,
+                total = total
+            )
+            .to_string(),
+        );
+    }
+
+    /// Replace the selected matches: in buffers for open files, on disk otherwise
+    pub(crate) fn apply_project_replace(&mut self) {
+        let Some(panel) = &self.project_search else {
+            return;
+        };
+        let Some(replacement) = panel.replacement.clone() else {
+            self.set_status_message(t!("project_search.no_replacement").to_string());
+            return;
+        };
+        if panel.task.is_some() {
+            self.set_status_message(t!("project_search.still_searching").to_string());
+            return;
+        }
+
+        // Selected edits per file: range in the file, text expected there, new text
+        let mut plans = Vec::new();
+        for (file, file_matches) in panel.files.iter().enumerate() {
+            let edits: Vec<(Range<usize>, String, String)> = file_matches
+                .matches
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !panel.deselected.contains(&(file, *index)))
+                .map(|(_, m)| {
+                    let new_text = panel.query.replacement_for(
+                        &panel.regex,
+                        &m.line_text,
+                        m.range.clone(),
+                        &replacement,
+                    );
+                    (m.file_range(), m.matched_text().to_string(), new_text)
+                })
+                .collect();
+            if !edits.is_empty() {
+                plans.push((file_matches.path.clone(), edits));
+            }
+        }
+        if plans.is_empty() {
+            self.set_status_message(t!("project_search.nothing_selected").to_string());
+            return;
+        }
+        let description = format!(
+            "Replace '{}' with '{}' in project",
+            panel.query.text, replacement
+        );
+        let (query, panel_replacement) = (panel.query.clone(), panel.replacement.clone());
+
+        let mut record = ProjectReplaceRecord::default();
+        let mut replaced = 0;
+        let mut skipped = 0;
+        let mut errors = Vec::new();
+        for (path, edits) in plans {
+            let open_buffer = self
+                .buffer_metadata
+                .iter()
+                .find(|(_, metadata)| metadata.file_path() == Some(&path))
+                .map(|(id, _)| *id);
+            let result = match open_buffer {
+                Some(buffer_id) => self
+                    .replace_in_buffer(buffer_id, &edits, &description)
+                    .map(|(count, state)| {
+                        record.buffers.push((buffer_id, state));
+                        count
+                    })
+                    .map_err(|e| e.to_string()),
+                None => self
+                    .replace_in_file(&path, &edits)
+                    .map(|(count, old, new)| {
+                        record.files.push((path.clone(), old, new));
+                        count
+                    })
+                    .map_err(|e| e.to_string()),
+            };
+            match result {
+                Ok(count) => {
+                    replaced += count;
+                    skipped += edits.len() - count;
+                }
+                Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+
+        let files = record.buffers.len() + record.files.len();
+        if files > 0 {
+            self.last_project_replace = Some(record);
+        }
+        // Show what is left to replace
+        self.run_project_search(query, panel_replacement, false);
+
+        let mut message =
+            t!("project_search.replaced", count = replaced, files = files).to_string();
+        if skipped > 0 {
+            message.push_str(&t!("project_search.skipped_changed", count = skipped));
+        }
+        if !errors.is_empty() {
+            tracing::warn!("Project replace errors: {}", errors.join(", "));
+            message.push_str(&t!("project_search.write_errors", count = errors.len()));
+        }
+        self.set_status_message(message);
+    }
+
+    /// Apply a file's replacements to its open buffer as one undoable edit.
+    /// Edits whose text changed since the search are skipped. Returns the number
+    /// of replacements and the undo state after them.
+    fn replace_in_buffer(
+        &mut self,
+        buffer_id: BufferId,
+        edits: &[(Range<usize>, String, String)],
+        description: &str,
+    ) -> anyhow::Result<(usize, usize)> {
+        let state = self
+            .buffers
+            .get_mut(&buffer_id)
+            .ok_or_else(|| anyhow::anyhow!("buffer not found"))?;
+        let cursor_id = state.cursors.primary_id();
+        let mut events = Vec::new();
+        let mut count = 0;
+        for (range, old_text, new_text) in edits {
+            let current = state.buffer.get_text_range_mut(range.start, range.len())?;
+            if current != old_text.as_bytes() {
+                continue;
+            }
+            events.push(Event::Delete {
+                range: range.clone(),
+                deleted_text: old_text.clone(),
+                cursor_id,
+            });
+            events.push(Event::Insert {
+                position: range.start,
+                text: new_text.clone(),
+                cursor_id,
+            });
+            count += 1;
+        }
+        if count > 0 {
+            self.apply_events_to_buffer_as_bulk_edit(buffer_id, events, description.to_string())?;
+            self.invalidate_layouts_for_buffer(buffer_id);
+        }
+        let undo_state = self
+            .event_logs
+            .get(&buffer_id)
+            .map(|log| log.content_state())
+            .unwrap_or(0);
+        Ok((count, undo_state))
+    }
+
+    /// Apply a file's replacements on disk, loading and saving it like an open
+    /// buffer, so its encoding, the save strategy, backups and the local
+    /// history are honored.
+    /// Edits whose text changed since the search are skipped. Returns the
+    /// number of replacements and the content before and after.
+    fn replace_in_file(
+        &self,
+        path: &Path,
+        edits: &[(Range<usize>, String, String)],
+    ) -> anyhow::Result<(usize, String, String)> {
+        let mut buffer = self.load_file_buffer(path)?;
+        let old = buffer
+            .to_string()
+            .ok_or_else(|| anyhow::anyhow!("file is too large"))?;
+        let mut count = 0;
+        // Back to front, so earlier ranges stay valid
+        for (range, old_text, new_text) in edits.iter().rev() {
+            if old.get(range.clone()) == Some(old_text.as_str()) {
+                buffer.replace_range(range.clone(), new_text);
+                count += 1;
+            }
+        }
+        if count == 0 {
+            return Ok((0, old.clone(), old));
+        }
+        buffer.save_with_options(&self.file_save_options(path))?;
+        self.record_file_history(&buffer);
+        let new = buffer
+            .to_string()
+            .ok_or_else(|| anyhow::anyhow!("file is too large"))?;
+        Ok((count, old, new))
+    }
+
+    /// Write `old` back to a file replaced on disk, if it still holds `new`.
+    /// Returns whether the file was reverted.
+    fn revert_replaced_file(&self, path: &Path, old: &str, new: &str) -> anyhow::Result<bool> {
+        let mut buffer = self.load_file_buffer(path)?;
+        if buffer.to_string().as_deref() != Some(new) {
+            return Ok(false);
+        }
+        buffer.replace_content(old);
+        buffer.save_with_options(&self.file_save_options(path))?;
+        self.record_file_history(&buffer);
+        Ok(true)
+    }
+
+    /// Load a file that is not open in the editor, detecting its encoding
+    fn load_file_buffer(&self, path: &Path) -> anyhow::Result<TextBuffer> {
+        TextBuffer::load_from_file(path, self.config.editor.large_file_threshold_bytes as usize)
+    }
+
+    /// How a file that is not open in the editor is saved, by its language
+    fn file_save_options(&self, path: &Path) -> SaveOptions {
+        let language = detect_language(path, &self.config.languages);
+        self.save_options_for(language.as_deref().unwrap_or("text"))
+    }
+
+    /// Revert the last project-wide replace, leaving alone buffers and files
+    /// that changed since
+    pub(crate) fn undo_project_replace(&mut self) {
+        let Some(record) = self.last_project_replace.take() else {
+            self.set_status_message(t!("project_search.nothing_to_undo").to_string());
+            return;
+        };
+
+        let mut reverted = 0;
+        let mut skipped = 0;
+        for (buffer_id, undo_state) in record.buffers {
+            let at_replace = self
+                .event_logs
+                .get(&buffer_id)
+                .is_some_and(|log| log.content_state() == undo_state);
+            if !at_replace {
+                skipped += 1;
+                continue;
+            }
+            if buffer_id == self.active_buffer() {
+                self.handle_undo();
+            } else if let (Some(log), Some(state)) = (
+                self.event_logs.get_mut(&buffer_id),
+                self.buffers.get_mut(&buffer_id),
+            ) {
+                for event in log.undo() {
+                    state.apply(&event);
+                }
+                state.buffer.set_modified(!log.is_at_saved_position());
+                self.invalidate_layouts_for_buffer(buffer_id);
+            }
+            reverted += 1;
+        }
+        for (path, old, new) in record.files {
+            if self
+                .revert_replaced_file(&path, &old, &new)
+                .unwrap_or(false)
+            {
+                reverted += 1;
+            } else {
+                skipped += 1;
+            }
+        }
+
+        // Refresh the panel's results
+        if let Some(panel) = &self.project_search {
+            let (query, replacement) = (panel.query.clone(), panel.replacement.clone());
+            self.run_project_search(query, replacement, false);
+        }
+        let mut message = t!("project_search.undone", files = reverted).to_string();
+        if skipped > 0 {
+            message.push_str(&t!("project_search.undo_skipped", count = skipped));
+        }
+        self.set_status_message(message);
+    }
+
+    /// The (file, match) shown on the panel row under the cursor; the match is
+    /// None on a file's header row
+    fn project_search_selection(&self) -> Option<(usize, Option<usize>)> {
+        let panel = self.project_search.as_ref()?;
+        if self.active_buffer() != panel.buffer_id {
+            return None;
+        }
+        let state = self.buffers.get(&panel.buffer_id)?;
+        let indices = state
+            .text_properties
+            .get_at(state.cursors.primary().position)
+            .iter()
+            .find_map(|p| p.get_as::<Vec<usize>>(MATCH_PROPERTY))?;
+        let file = *indices.first()?;
+        (file < panel.files.len()).then(|| (file, indices.get(1).copied()))
+    }
+
+    /// Move a buffer's cursor, in the buffer and in every split showing it
+    pub(super) fn set_cursor_in_buffer(&mut self, buffer_id: BufferId, position: usize) {
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.cursors.primary_mut().position = position;
+            state.cursors.primary_mut().anchor = None;
+        }
+        for split_id in self.split_manager.splits_for_buffer(buffer_id) {
+            if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+                view_state.cursors.primary_mut().position = position;
+                view_state.cursors.primary_mut().anchor = None;
+            }
+        }
+    }
+
+    /// Rebuild the panel content from its state
+    fn render_project_search_panel(&mut self) {
+        let Some(panel) = &self.project_search else {
+            return;
+        };
+        let buffer_id = panel.buffer_id;
+        let (entries, highlights) = self.project_search_content(panel);
+        if let Err(e) = self.set_virtual_buffer_content(buffer_id, entries) {
+            tracing::error!("Failed to set project search content: {}", e);
+            return;
+        }
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            let namespace = OverlayNamespace::from_string(OVERLAY_NAMESPACE.to_string());
+            state.apply(&Event::ClearNamespace {
+                namespace: namespace.clone(),
+            });
+            for (range, color) in highlights {
+                state.apply(&Event::AddOverlay {
+                    namespace: Some(namespace.clone()),
+                    range,
+                    face: OverlayFace::Background { color },
+                    priority: 10,
+                    message: None,
+                    extend_to_line_end: false,
+                });
+            }
+        }
+    }
+
+    /// Build the panel content: a header, then the matches grouped by file
+    fn project_search_content(
+        &self,
+        panel: &ProjectSearchPanel,
+    ) -> (Vec<TextPropertyEntry>, Highlights) {
+        let mut entries = Vec::new();
+        let mut highlights = Vec::new();
+        let mut offset = 0;
+        let push =
+            |entries: &mut Vec<TextPropertyEntry>, offset: &mut usize, entry: TextPropertyEntry| {
+                *offset += entry.text.len();
+                entries.push(entry);
+            };
+
+        let mut title = t!("project_search.title", query = &panel.query.text).to_string();
+        for (enabled, label) in [
+            (panel.query.use_regex, t!("search.regex")),
+            (panel.query.case_sensitive, t!("search.case_sensitive")),
+            (panel.query.whole_word, t!("search.whole_word")),
+        ] {
+            if enabled {
+                title.push_str(&format!("  [{label}]"));
+            }
+        }
+        push(
+            &mut entries,
+            &mut offset,
+            TextPropertyEntry::text(format!("{title}\n")),
+        );
+        let filters = panel.query.filters();
+        if !filters.is_empty() {
+            push(
+                &mut entries,
+                &mut offset,
+                TextPropertyEntry::text(format!(
+                    "{}\n",
+                    t!("project_search.files", filters = filters)
+                )),
+            );
+        }
+        if let Some(replacement) = &panel.replacement {
+            push(
+                &mut entries,
+                &mut offset,
+                TextPropertyEntry::text(format!(
+                    "{}\n",
+                    t!("project_search.replace_with", replacement = replacement)
+                )),
+            );
+        }
+        let matches: usize = panel.files.iter().map(|f| f.matches.len()).sum();
+        let status = match panel.summary {
+            _ if panel.task.is_some() => t!("project_search.searching").to_string(),
+            Some(summary) => {
+                let mut status = t!(
+                    "project_search.totals",
+                    matches = matches,
+                    files = panel.files.len(),
+                    searched = summary.files_searched
+                )
+                .to_string();
+                if summary.truncated {
+                    status.push_str(&t!("project_search.truncated"));
+                }
+                status
+            }
+            None => String::new(),
+        };
+        push(
+            &mut entries,
+            &mut offset,
+            TextPropertyEntry::text(format!("{status}\n{}\n\n", t!("project_search.help"))),
+        );
+
+        let rgb = |color: ratatui::style::Color, fallback: (u8, u8, u8)| match color {
+            ratatui::style::Color::Rgb(r, g, b) => (r, g, b),
+            _ => fallback,
+        };
+        let match_color = rgb(self.theme.search_match_bg, (100, 90, 30));
+        let added_color = rgb(self.theme.diff_add_bg, (35, 60, 35));
+        let removed_color = rgb(self.theme.diff_remove_bg, (70, 35, 35));
+
+        // Result paths are canonical
+        let root = self
+            .working_dir
+            .canonicalize()
+            .unwrap_or_else(|_| self.working_dir.clone());
+        for (file, file_matches) in panel.files.iter().enumerate() {
+            let path = file_matches
+                .path
+                .strip_prefix(&root)
+                .unwrap_or(&file_matches.path);
+            push(
+                &mut entries,
+                &mut offset,
+                TextPropertyEntry::text(format!(
+                    "{} ({})\n",
+                    path.display(),
+                    file_matches.matches.len()
+                ))
+                .with_property(MATCH_PROPERTY, serde_json::json!([file])),
+            );
+            for (index, m) in file_matches.matches.iter().enumerate() {
+                let property = serde_json::json!([file, index]);
+                let prefix = match panel.replacement {
+                    Some(_) => {
+                        let checkbox = if panel.deselected.contains(&(file, index)) {
+                            "[ ]"
+                        } else {
+                            "[x]"
+                        };
+                        format!("  {checkbox} {:>5}: ", m.line + 1)
+                    }
+                    None => format!("  {:>5}: ", m.line + 1),
+                };
+                let line_start = offset + prefix.len();
+                let color = if panel.replacement.is_some() {
+                    removed_color
+                } else {
+                    match_color
+                };
+                highlights.push((line_start + m.range.start..line_start + m.range.end, color));
+                push(
+                    &mut entries,
+                    &mut offset,
+                    TextPropertyEntry::text(format!("{prefix}{}\n", m.line_text))
+                        .with_property(MATCH_PROPERTY, property.clone()),
+                );
+
+                // Preview of the line after replacing this match
+                if let Some(replacement) = &panel.replacement {
+                    let new_text = panel.query.replacement_for(
+                        &panel.regex,
+                        &m.line_text,
+                        m.range.clone(),
+                        replacement,
+                    );
+                    let indent = " ".repeat(prefix.len());
+                    let start = offset + indent.len() + m.range.start;
+                    highlights.push((start..start + new_text.len(), added_color));
+                    push(
+                        &mut entries,
+                        &mut offset,
+                        TextPropertyEntry::text(format!(
+                            "{indent}{}{new_text}{}\n",
+                            &m.line_text[..m.range.start],
+                            &m.line_text[m.range.end..]
+                        ))
+                        .with_property(MATCH_PROPERTY, property),
+                    );
+                }
+            }
+        }
+        if panel.task.is_none() && panel.files.is_empty() {
+            push(
+                &mut entries,
+                &mut offset,
+                TextPropertyEntry::text(format!("{}\n", t!("project_search.no_matches"))),
+            );
+        }
+
+        (entries, highlights)
+    }
+}
@@ -112,6 +112,20 @@ impl Editor {
                     self.perform_replace(&search, &input);
                 }
             }
+            PromptType::ProjectSearch => {
+                self.start_project_search(&input);
+            }
+            PromptType::ProjectReplaceSearch => {
+                if self.start_project_search(&input) {
+                    self.start_project_replace();
+                }
+            }
+            PromptType::ProjectReplace => {
+                self.set_project_replacement(input);
+            }
+            PromptType::ProjectSearchFiles => {
+                self.set_project_search_filters(&input);
+            }
             PromptType::Command => {
                 let commands = self.command_registry.read().unwrap().get_all();
                 if let Some(cmd) = commands.iter().find(|c| c.get_localized_name() == input) {
@@ -344,6 +358,24 @@ impl Editor {
                     self.switch_to_tab(BufferId(id));
                 }
             }
+            PromptType::FileHistory { buffer_id } => {
+                self.open_file_history_snapshot(buffer_id, input.trim());
+            }
+            PromptType::InsertSnippet => {
+                self.insert_snippet_from_prompt(input.trim());
+            }
+            PromptType::GotoSymbol => {
+                self.goto_symbol_from_prompt(input.trim());
+            }
+            PromptType::WorkspaceSymbol => {
+                self.goto_workspace_symbol_from_prompt(input.trim());
+            }
+            PromptType::GotoLocation => {
+                self.goto_location_from_prompt(input.trim());
+            }
+            PromptType::CodeLens => {
+                self.run_code_lens_from_prompt(input.trim());
+            }
             PromptType::QueryReplaceConfirm => {
                 // This is handled by InsertChar, not PromptConfirm
                 // But if somehow Enter is pressed, treat it as skip (n)
@@ -354,9 +386,23 @@ impl Editor {
             PromptType::SetTabSize => {
                 self.handle_set_tab_size(&input);
             }
+            PromptType::FoldLevel => {
+                self.handle_fold_level(&input);
+            }
             PromptType::SetLineEnding => {
                 self.handle_set_line_ending(&input);
             }
+            PromptType::ReopenWithEncoding => {
+                self.handle_reopen_with_encoding(&input);
+            }
+            PromptType::SaveWithEncoding => {
+                if self.handle_save_with_encoding(&input) {
+                    return PromptResult::ExecuteAction(Action::Save);
+                }
+            }
+            PromptType::HexSearch => {
+                self.handle_hex_search(&input);
+            }
             PromptType::ShellCommand { replace } => {
                 self.handle_shell_command(&input, replace);
             }
@@ -409,7 +455,13 @@ impl Editor {
             before_len
         );
 
-        match self.active_state_mut().buffer.save_to_file(&full_path) {
+        self.apply_save_transforms(&full_path);
+        let options = self.active_save_options();
+        match self
+            .active_state_mut()
+            .buffer
+            .save_to_file_with_options(&full_path, &options)
+        {
             Ok(()) => {
                 let after_save_idx = self.active_event_log().current_index();
                 let after_save_len = self.active_event_log().len();
@@ -445,6 +497,7 @@ impl Editor {
                     }
                 }
 
+                self.record_local_history(self.active_buffer());
                 self.notify_lsp_save();
 
                 self.emit_event(
@@ -566,6 +619,50 @@ impl Editor {
         }
     }
 
+    /// Parse the encoding from prompt input (e.g., "UTF-16 LE" from "UTF-16 LE (Unicode, little-endian)")
+    fn parse_encoding_input(input: &str) -> Option<crate::model::encoding::Encoding> {
+        let trimmed = input.trim();
+        let name = trimmed.split(" (").next().unwrap_or(trimmed);
+        crate::model::encoding::Encoding::from_label(name)
+    }
+
+    /// Handle ReopenWithEncoding prompt confirmation.
+    fn handle_reopen_with_encoding(&mut self, input: &str) {
+        let Some(encoding) = Self::parse_encoding_input(input) else {
+            self.set_status_message(t!("error.unknown_encoding", input = input).to_string());
+            return;
+        };
+
+        // Reopening discards the buffer content, so refuse if there are unsaved changes
+        if self.active_state().buffer.is_modified() {
+            self.set_status_message(t!("status.reopen_modified").to_string());
+            return;
+        }
+
+        if let Err(e) = self.reopen_with_encoding(encoding) {
+            self.set_status_message(t!("error.reopen_failed", error = e.to_string()).to_string());
+        }
+    }
+
+    /// Handle SaveWithEncoding prompt confirmation.
+    ///
+    /// Returns true if the encoding was set and the buffer should now be saved.
+    fn handle_save_with_encoding(&mut self, input: &str) -> bool {
+        match Self::parse_encoding_input(input) {
+            Some(encoding) => {
+                self.active_state_mut().buffer.set_encoding(encoding);
+                self.set_status_message(
+                    t!("settings.encoding_set", value = encoding.display_name()).to_string(),
+                );
+                true
+            }
+            None => {
+                self.set_status_message(t!("error.unknown_encoding", input = input).to_string());
+                false
+            }
+        }
+    }
+
     /// Handle register-based input (macros, bookmarks).
     fn handle_register_input<F>(&mut self, input: &str, action: F, register_type: &str)
     where
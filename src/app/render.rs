@@ -12,6 +12,10 @@ impl Editor {
         // calling sync_scroll_groups, so that the sync reads the correct position.
         // Otherwise, cursor movements like 'G' (go to end) won't sync properly because
         // viewport.top_byte hasn't been updated yet.
+        // Keep the undo tree panel in sync with its buffer's history and selection
+        self.sync_undo_tree_panel();
+        self.sync_hierarchy_panel();
+
         let active_split = self.split_manager.active_split();
         self.pre_sync_ensure_visible(active_split);
 
@@ -19,6 +23,9 @@ impl Editor {
         // This sets viewport positions based on the authoritative scroll_line in each group
         self.sync_scroll_groups();
 
+        // Highlight the background search matches that scrolled into view
+        self.sync_search_viewport_highlights();
+
         // NOTE: Viewport sync with cursor is handled by split_rendering.rs which knows the
         // correct content area dimensions. Don't sync here with incorrect EditorState viewport size.
 
@@ -35,11 +42,37 @@ impl Editor {
         }
         for buffer_id in buffers_to_request {
             self.maybe_request_semantic_tokens(buffer_id);
+            self.maybe_request_folding_ranges(buffer_id);
+            self.maybe_request_code_lenses(buffer_id);
+        }
+        let views: Vec<_> = self
+            .split_view_states
+            .iter()
+            .filter_map(|(split_id, view_state)| {
+                let buffer_id = self.split_manager.get_buffer_id(*split_id)?;
+                Some((
+                    buffer_id,
+                    view_state.viewport.top_byte,
+                    view_state.viewport.height as usize,
+                ))
+            })
+            .collect();
+        for (buffer_id, top_byte, height) in views {
+            self.maybe_resolve_code_lenses(buffer_id, top_byte, height);
         }
 
         for (split_id, view_state) in &self.split_view_states {
             if let Some(buffer_id) = self.split_manager.get_buffer_id(*split_id) {
                 if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                    // Unfold around cursors that moved into a collapsed fold
+                    // (search, go to line, jumps)
+                    if state.folds.has_collapsed() {
+                        let positions: Vec<usize> =
+                            state.cursors.iter().map(|(_, c)| c.position).collect();
+                        for position in positions {
+                            state.folds.expand_containing(&state.buffer, position);
+                        }
+                    }
                     let top_byte = view_state.viewport.top_byte;
                     let height = view_state.viewport.height;
                     if let Err(e) = state.prepare_for_render(top_byte, height) {
@@ -74,6 +107,8 @@ impl Editor {
                     | PromptType::Replace { .. }
                     | PromptType::QueryReplaceSearch
                     | PromptType::QueryReplace { .. }
+                    | PromptType::ProjectSearch
+                    | PromptType::ProjectReplaceSearch
             )
         });
 
@@ -182,6 +217,37 @@ impl Editor {
             editor_content_area = main_content_area;
         }
 
+        // Split the outline panel off the right side of the editor area
+        let editor_content_area = if self.outline.is_some() {
+            let horizontal_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(75), Constraint::Percentage(25)])
+                .split(editor_content_area);
+            self.cached_layout.outline_area = Some(horizontal_chunks[1]);
+
+            self.maybe_request_document_symbols(self.active_buffer());
+            let is_focused = self.key_context == KeyContext::Outline;
+            let (rows, cursor_path) = self.outline_rows();
+            if let Some(panel) = self.outline.as_mut() {
+                // While the panel is not focused its selection follows the cursor
+                if !is_focused {
+                    panel.follow_cursor(&rows, &cursor_path);
+                }
+                OutlineRenderer::render(
+                    panel,
+                    &rows,
+                    frame,
+                    horizontal_chunks[1],
+                    is_focused,
+                    &self.theme,
+                );
+            }
+            horizontal_chunks[0]
+        } else {
+            self.cached_layout.outline_area = None;
+            editor_content_area
+        };
+
         // Note: Tabs are now rendered within each split by SplitRenderer
 
         // Trigger lines_changed hooks for newly visible lines in all visible buffers
@@ -308,8 +374,8 @@ impl Editor {
         }
 
         // Render editor content (same for both layouts)
-        let lsp_waiting = self.pending_completion_request.is_some()
-            || self.pending_goto_definition_request.is_some();
+        let lsp_waiting =
+            self.pending_completion_request.is_some() || self.pending_goto_request.is_some();
 
         // Hide the hardware cursor when menu is open, file explorer is focused, terminal mode,
         // or settings UI is open
@@ -320,6 +386,7 @@ impl Editor {
         let settings_visible = self.settings_state.as_ref().map_or(false, |s| s.visible);
         let hide_cursor = self.menu_state.active_menu.is_some()
             || self.key_context == KeyContext::FileExplorer
+            || self.key_context == KeyContext::Outline
             || self.terminal_mode
             || settings_visible;
 
@@ -346,33 +413,69 @@ impl Editor {
 
         let is_maximized = self.split_manager.is_maximized();
 
-        let (split_areas, tab_areas, close_split_areas, maximize_split_areas, view_line_mappings) =
-            SplitRenderer::render_content(
-                frame,
-                editor_content_area,
-                &self.split_manager,
-                &mut self.buffers,
-                &self.buffer_metadata,
-                &mut self.event_logs,
-                &self.composite_buffers,
-                &mut self.composite_view_states,
-                &self.theme,
-                self.ansi_background.as_ref(),
-                self.background_fade,
-                lsp_waiting,
-                self.config.editor.large_file_threshold_bytes,
-                self.config.editor.line_wrap,
-                self.config.editor.estimated_line_length,
-                self.config.editor.highlight_context_bytes,
-                Some(&mut self.split_view_states),
-                hide_cursor,
-                hovered_tab,
-                hovered_close_split,
-                hovered_maximize_split,
-                is_maximized,
-                self.config.editor.relative_line_numbers,
-                self.tab_bar_visible,
-            );
+        let scrollbar_marks = self.buffer_scrollbar_marks();
+        let breadcrumbs = self.breadcrumbs(editor_content_area);
+        let (
+            split_areas,
+            tab_areas,
+            close_split_areas,
+            maximize_split_areas,
+            view_line_mappings,
+            scrollbar_mark_rows,
+        ) = SplitRenderer::render_content(
+            frame,
+            editor_content_area,
+            &self.split_manager,
+            &mut self.buffers,
+            &self.buffer_metadata,
+            &mut self.event_logs,
+            &self.composite_buffers,
+            &mut self.composite_view_states,
+            &self.theme,
+            self.ansi_background.as_ref(),
+            self.background_fade,
+            lsp_waiting,
+            self.config.editor.large_file_threshold_bytes,
+            self.config.editor.line_wrap,
+            self.config.editor.estimated_line_length,
+            self.config.editor.highlight_context_bytes,
+            Some(&mut self.split_view_states),
+            hide_cursor,
+            hovered_tab,
+            hovered_close_split,
+            hovered_maximize_split,
+            is_maximized,
+            self.config.editor.relative_line_numbers,
+            if self.config.editor.sticky_scroll {
+                self.config.editor.sticky_scroll_max_lines
+            } else {
+                0
+            },
+            &scrollbar_marks,
+            self.tab_bar_visible,
+            &breadcrumbs,
+        );
+
+        // The breadcrumb bar is the row above the content of its split
+        self.cached_layout.breadcrumb_areas = split_areas
+            .iter()
+            .filter_map(|(split_id, _, content_rect, _, _, _)| {
+                let crumbs = breadcrumbs.get(split_id)?;
+                let area = ratatui::layout::Rect::new(
+                    content_rect.x,
+                    content_rect.y.checked_sub(1)?,
+                    content_rect.width + 1,
+                    1,
+                );
+                Some(
+                    SplitRenderer::breadcrumb_columns(area, crumbs)
+                        .into_iter()
+                        .enumerate()
+                        .map(move |(crumb, (start, end))| (*split_id, crumb, area.y, start, end)),
+                )
+            })
+            .flatten()
+            .collect();
 
         // Detect viewport changes and fire hooks
         // Compare against previous frame's viewport state (stored in self.previous_viewports)
@@ -443,6 +546,7 @@ impl Editor {
         self.cached_layout.close_split_areas = close_split_areas;
         self.cached_layout.maximize_split_areas = maximize_split_areas;
         self.cached_layout.view_line_mappings = view_line_mappings;
+        self.cached_layout.scrollbar_mark_rows = scrollbar_mark_rows;
         self.cached_layout.separator_areas = self
             .split_manager
             .get_separators_with_ids(editor_content_area);
@@ -545,9 +649,11 @@ impl Editor {
                 Some(HoverTarget::StatusBarLineEndingIndicator) => {
                     StatusBarHover::LineEndingIndicator
                 }
+                Some(HoverTarget::StatusBarEncodingIndicator) => StatusBarHover::EncodingIndicator,
                 _ => StatusBarHover::None,
             };
 
+            let hex_view = self.is_hex_view_active();
             let status_bar_layout = StatusBarRenderer::render_status_bar(
                 frame,
                 main_chunks[status_bar_idx],
@@ -563,6 +669,7 @@ impl Editor {
                 warning_level,               // Pass warning level for colored indicator
                 general_warning_count,       // Pass general warning count for badge
                 status_bar_hover,            // Pass hover state for indicator styling
+                hex_view,
             );
 
             // Store status bar layout for click detection
@@ -573,6 +680,7 @@ impl Editor {
             self.cached_layout.status_bar_warning_area = status_bar_layout.warning_badge;
             self.cached_layout.status_bar_line_ending_area =
                 status_bar_layout.line_ending_indicator;
+            self.cached_layout.status_bar_encoding_area = status_bar_layout.encoding_indicator;
         }
 
         // Render search options bar when in search prompt
@@ -1874,7 +1982,8 @@ impl Editor {
     /// Convert an action into a list of events to apply to the active buffer
     /// Returns None for actions that don't generate events (like Quit)
     pub fn action_to_events(&mut self, action: Action) -> Option<Vec<Event>> {
-        let tab_size = self.config.editor.tab_size;
+        // Per-buffer tab size (from language settings and .editorconfig)
+        let tab_size = self.active_state().tab_size;
         let auto_indent = self.config.editor.auto_indent;
         let estimated_line_length = self.config.editor.estimated_line_length;
 
@@ -1901,8 +2010,9 @@ impl Editor {
     /// Clear all search highlights from the active buffer and reset search state
     pub(super) fn clear_search_highlights(&mut self) {
         self.clear_search_overlays();
-        // Also clear search state
+        // Also clear search state, stopping any background search
         self.search_state = None;
+        self.search_task = None;
     }
 
     /// Clear only the visual search overlays, preserving search state for F3/Shift+F3
@@ -2030,38 +2140,14 @@ impl Editor {
         }
 
         let search_range = self.pending_search_range.take();
-
-        // For large files with lazy loading, we need to load the entire buffer
-        // before searching. This ensures the search can access all content.
-        // (Issue #657: Search on large plain text files)
-        let buffer_content = {
-            let state = self.active_state_mut();
-            let total_bytes = state.buffer.len();
-
-            // Force-load the entire buffer if not already loaded
-            // get_text_range_mut() handles lazy loading and returns the content
-            match state.buffer.get_text_range_mut(0, total_bytes) {
-                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
-                Err(e) => {
-                    tracing::warn!("Failed to load buffer for search: {}", e);
-                    self.set_status_message(t!("error.buffer_not_loaded").to_string());
-                    return;
-                }
-            }
-        };
+        // A new search replaces any still running in the background
+        self.search_task = None;
 
         // Get search settings
         let case_sensitive = self.search_case_sensitive;
         let whole_word = self.search_whole_word;
         let use_regex = self.search_use_regex;
 
-        // Determine search boundaries
-        let (search_start, search_end) = if let Some(ref range) = search_range {
-            (range.start, range.end)
-        } else {
-            (0, buffer_content.len())
-        };
-
         // Build regex pattern
         let regex_pattern = if use_regex {
             if whole_word {
@@ -2093,6 +2179,37 @@ impl Editor {
             }
         };
 
+        // Large files are searched in the background instead of being loaded whole
+        if self.active_state().buffer.is_large_file() {
+            self.start_background_search(query, &regex_pattern, case_sensitive, search_range);
+            return;
+        }
+
+        // Lazily loaded buffers are loaded whole before searching, so the search
+        // can access all content. (Issue #657: Search on large plain text files)
+        let buffer_content = {
+            let state = self.active_state_mut();
+            let total_bytes = state.buffer.len();
+
+            // Force-load the entire buffer if not already loaded
+            // get_text_range_mut() handles lazy loading and returns the content
+            match state.buffer.get_text_range_mut(0, total_bytes) {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(e) => {
+                    tracing::warn!("Failed to load buffer for search: {}", e);
+                    self.set_status_message(t!("error.buffer_not_loaded").to_string());
+                    return;
+                }
+            }
+        };
+
+        // Determine search boundaries
+        let (search_start, search_end) = if let Some(ref range) = search_range {
+            (range.start, range.end)
+        } else {
+            (0, buffer_content.len())
+        };
+
         // Find all matches within the search range (store position and length for overlays)
         let search_slice = &buffer_content[search_start..search_end];
         let match_ranges: Vec<(usize, usize)> = regex
@@ -2102,12 +2219,10 @@ impl Editor {
 
         if match_ranges.is_empty() {
             self.search_state = None;
-            let msg = if search_range.is_some() {
-                format!("No matches found for '{}' in selection", query)
-            } else {
-                format!("No matches found for '{}'", query)
-            };
-            self.set_status_message(msg);
+            self.set_status_message(Self::search_not_found_message(
+                query,
+                search_range.is_some(),
+            ));
             return;
         }
 
@@ -2177,9 +2292,29 @@ impl Editor {
             current_match_index: Some(current_match_index),
             wrap_search: search_range.is_none(), // Only wrap if not searching in selection
             search_range,
+            viewport_matches: None,
         });
 
-        let msg = if self.search_state.as_ref().unwrap().search_range.is_some() {
+        let in_selection = self.search_state.as_ref().unwrap().search_range.is_some();
+        self.set_status_message(Self::search_found_message(num_matches, query, in_selection));
+    }
+
+    /// Status message reporting that a search found nothing
+    pub(super) fn search_not_found_message(query: &str, in_selection: bool) -> String {
+        if in_selection {
+            format!("No matches found for '{}' in selection", query)
+        } else {
+            format!("No matches found for '{}'", query)
+        }
+    }
+
+    /// Status message reporting the number of matches of a search
+    pub(super) fn search_found_message(
+        num_matches: usize,
+        query: &str,
+        in_selection: bool,
+    ) -> String {
+        if in_selection {
             format!(
                 "Found {} match{} for '{}' in selection",
                 num_matches,
@@ -2193,8 +2328,7 @@ impl Editor {
                 if num_matches == 1 { "" } else { "es" },
                 query
             )
-        };
-        self.set_status_message(msg);
+        }
     }
 
     /// Get current match positions from search overlays (which use markers that track edits)
@@ -2228,12 +2362,14 @@ impl Editor {
         if let Some(ref mut search_state) = self.search_state {
             // Use overlay positions if they exist and there's no search_range
             // (selection-based search uses cached matches to respect range)
-            let match_positions =
-                if !overlay_positions.is_empty() && search_state.search_range.is_none() {
-                    overlay_positions
-                } else {
-                    search_state.matches.clone()
-                };
+            let match_positions = if !overlay_positions.is_empty()
+                && search_state.search_range.is_none()
+                && search_state.viewport_matches.is_none()
+            {
+                overlay_positions
+            } else {
+                search_state.matches.clone()
+            };
 
             if match_positions.is_empty() {
                 return;
@@ -2294,12 +2430,14 @@ impl Editor {
             // Use overlay positions if:
             // 1. They exist (overlays were created)
             // 2. There's no search_range (selection-based search uses cached matches to respect range)
-            let match_positions =
-                if !overlay_positions.is_empty() && search_state.search_range.is_none() {
-                    overlay_positions
-                } else {
-                    search_state.matches.clone()
-                };
+            let match_positions = if !overlay_positions.is_empty()
+                && search_state.search_range.is_none()
+                && search_state.viewport_matches.is_none()
+            {
+                overlay_positions
+            } else {
+                search_state.matches.clone()
+            };
 
             if match_positions.is_empty() {
                 return;
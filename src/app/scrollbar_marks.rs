@@ -0,0 +1,80 @@
+//! Marks on the scrollbar track (see `view::scrollbar_marks`).
+
+use std::collections::HashMap;
+
+use crate::model::event::BufferId;
+use crate::services::lsp::diagnostics::lsp_diagnostic_namespace;
+use crate::view::scrollbar_marks::{priority, ScrollbarMark};
+
+use super::Editor;
+
+impl Editor {
+    /// Scrollbar marks of the buffers shown in splits
+    ///
+    /// Cursor marks depend on the split and are added while rendering it.
+    pub(super) fn buffer_scrollbar_marks(&self) -> HashMap<BufferId, Vec<ScrollbarMark>> {
+        let mut marks = HashMap::new();
+        for split_id in self.split_manager.root().leaf_split_ids() {
+            let Some(buffer_id) = self.split_manager.get_buffer_id(split_id) else {
+                continue;
+            };
+            marks
+                .entry(buffer_id)
+                .or_insert_with(|| self.scrollbar_marks_for_buffer(buffer_id));
+        }
+        marks
+    }
+
+    fn scrollbar_marks_for_buffer(&self, buffer_id: BufferId) -> Vec<ScrollbarMark> {
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return Vec::new();
+        };
+        let theme = &self.theme;
+        let mut marks = state.scrollbar_marks.marks(&state.marker_list);
+
+        // Unsaved changes
+        let diff = state.buffer.diff_since_saved();
+        if !diff.equal {
+            marks.extend(diff.byte_ranges.into_iter().map(|range| {
+                ScrollbarMark::new(range, theme.scrollbar_change_fg, priority::CHANGE)
+            }));
+        }
+
+        // Diagnostics (their overlay priority encodes the severity) and search matches
+        let diagnostic_ns = lsp_diagnostic_namespace();
+        for overlay in state.overlays.all() {
+            let Some(namespace) = overlay.namespace.as_ref() else {
+                continue;
+            };
+            let (color, priority) = if *namespace == diagnostic_ns {
+                match overlay.priority {
+                    p if p >= 100 => (theme.diagnostic_error_fg, priority::DIAGNOSTIC_ERROR),
+                    p if p >= 50 => (theme.diagnostic_warning_fg, priority::DIAGNOSTIC_WARNING),
+                    p if p >= 30 => (theme.diagnostic_info_fg, priority::DIAGNOSTIC_INFO),
+                    _ => (theme.diagnostic_hint_fg, priority::DIAGNOSTIC_HINT),
+                }
+            } else if *namespace == self.search_namespace {
+                (theme.search_match_bg, priority::SEARCH_MATCH)
+            } else {
+                continue;
+            };
+            let range = overlay.range(&state.marker_list);
+            marks.push(ScrollbarMark::at(range.start, color, priority));
+        }
+
+        marks.extend(
+            self.bookmarks
+                .values()
+                .filter(|bookmark| bookmark.buffer_id == buffer_id)
+                .map(|bookmark| {
+                    ScrollbarMark::at(
+                        bookmark.position.min(state.buffer.len()),
+                        theme.scrollbar_bookmark_fg,
+                        priority::BOOKMARK,
+                    )
+                }),
+        );
+
+        marks
+    }
+}
@@ -169,6 +169,7 @@ impl Editor {
             let active_buffer = active_buffers.get(split_id).copied();
             let serialized = serialize_split_view_state(
                 view_state,
+                &self.buffers,
                 &self.buffer_metadata,
                 &self.working_dir,
                 active_buffer,
@@ -214,6 +215,8 @@ impl Editor {
             line_numbers: Some(self.config.editor.line_numbers),
             relative_line_numbers: Some(self.config.editor.relative_line_numbers),
             line_wrap: Some(self.config.editor.line_wrap),
+            sticky_scroll: Some(self.config.editor.sticky_scroll),
+            breadcrumbs: Some(self.config.editor.breadcrumbs),
             syntax_highlighting: Some(self.config.editor.syntax_highlighting),
             enable_inlay_hints: Some(self.config.editor.enable_inlay_hints),
             mouse_enabled: Some(self.mouse_enabled),
@@ -360,6 +363,11 @@ impl Editor {
                 top_view_line_offset: view_state.viewport.top_view_line_offset,
                 left_column: view_state.viewport.left_column,
             },
+            folds: self
+                .buffers
+                .get(&buffer_id)
+                .map(|state| state.folds.collapsed_ranges(&state.buffer))
+                .unwrap_or_default(),
         };
 
         // Save to disk immediately
@@ -443,6 +451,12 @@ impl Editor {
         if let Some(line_wrap) = session.config_overrides.line_wrap {
             self.config.editor.line_wrap = line_wrap;
         }
+        if let Some(sticky_scroll) = session.config_overrides.sticky_scroll {
+            self.config.editor.sticky_scroll = sticky_scroll;
+        }
+        if let Some(breadcrumbs) = session.config_overrides.breadcrumbs {
+            self.config.editor.breadcrumbs = breadcrumbs;
+        }
         if let Some(syntax_highlighting) = session.config_overrides.syntax_highlighting {
             self.config.editor.syntax_highlighting = syntax_highlighting;
         }
@@ -987,6 +1001,7 @@ impl Editor {
                             file_state.cursor.anchor.map(|a| a.min(max_pos));
                         editor_state.cursors.primary_mut().sticky_column =
                             file_state.cursor.sticky_column;
+                        super::folding::restore_folds(editor_state, &file_state.folds);
                         // Note: viewport is now exclusively owned by SplitViewState (restored above)
                     }
                     break;
@@ -1101,6 +1116,7 @@ fn serialize_split_node(
 
 fn serialize_split_view_state(
     view_state: &crate::view::split::SplitViewState,
+    buffers: &HashMap<BufferId, EditorState>,
     buffer_metadata: &HashMap<BufferId, super::types::BufferMetadata>,
     working_dir: &Path,
     active_buffer: Option<BufferId>,
@@ -1178,6 +1194,10 @@ fn serialize_split_view_state(
                                 top_view_line_offset: view_state.viewport.top_view_line_offset,
                                 left_column: view_state.viewport.left_column,
                             },
+                            folds: buffers
+                                .get(&active_id)
+                                .map(|state| state.folds.collapsed_ranges(&state.buffer))
+                                .unwrap_or_default(),
                         },
                     );
                 }
@@ -27,7 +27,8 @@ impl Editor {
                     // Load layer sources to show where each setting value comes from
                     let resolver =
                         ConfigResolver::new(self.dir_context.clone(), self.working_dir.clone());
-                    if let Ok(sources) = resolver.get_layer_sources() {
+                    if let Ok(mut sources) = resolver.get_layer_sources() {
+                        self.add_editorconfig_sources(&mut sources);
                         state.set_layer_sources(sources);
                     }
                     state.show();
@@ -129,7 +130,8 @@ impl Editor {
             ConfigLayer::User => "User",
             ConfigLayer::Project => "Project",
             ConfigLayer::Session => "Session",
-            ConfigLayer::System => "System", // Should never happen
+            ConfigLayer::EditorConfig => "EditorConfig", // Should never happen
+            ConfigLayer::System => "System",             // Should never happen
         };
 
         match resolver.save_to_layer(&new_config, target_layer) {
@@ -168,7 +170,7 @@ impl Editor {
             ConfigLayer::User => resolver.user_config_path(),
             ConfigLayer::Project => resolver.project_config_write_path(),
             ConfigLayer::Session => resolver.session_config_path(),
-            ConfigLayer::System => {
+            ConfigLayer::System | ConfigLayer::EditorConfig => {
                 self.set_status_message(t!("settings.cannot_edit_system").to_string());
                 return Ok(());
             }
@@ -209,7 +211,7 @@ impl Editor {
 }
 "#
                 }
-                ConfigLayer::System => unreachable!(),
+                ConfigLayer::System | ConfigLayer::EditorConfig => unreachable!(),
             };
             std::fs::write(&path, template)?;
         }
@@ -222,6 +224,7 @@ impl Editor {
             ConfigLayer::User => "User",
             ConfigLayer::Project => "Project",
             ConfigLayer::Session => "Session",
+            ConfigLayer::EditorConfig => "EditorConfig",
             ConfigLayer::System => "System",
         };
         self.set_status_message(
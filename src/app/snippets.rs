@@ -0,0 +1,373 @@
+//! Snippet insertion and tabstop navigation.
+//!
+//! Inserting a snippet (from completion or the "Insert Snippet" command)
+//! expands its variables and starts a [`SnippetSession`] on the buffer. Tab
+//! and Shift+Tab then select the next and previous tabstop, with a cursor on
+//! every mirror of it; choice tabstops open a popup of their options. The
+//! session ends at `$0`, on Escape, or once the cursor leaves the snippet.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::model::event::{CursorId, Event};
+use crate::primitives::snippet::{expand_snippet_with, SnippetSession, Tabstop};
+use crate::primitives::word_navigation::{find_word_end, find_word_start};
+use crate::services::snippets::{date_variable, SnippetDefinition, SnippetLibrary};
+use crate::view::prompt::{Prompt, PromptType};
+
+impl Editor {
+    /// User snippets for the active buffer's language
+    fn active_snippets(&mut self) -> Vec<SnippetDefinition> {
+        let dir = self.dir_context.snippets_dir();
+        let language = self.active_state().language.clone();
+        self.snippet_library
+            .get_or_insert_with(|| SnippetLibrary::load(&dir))
+            .for_language(&language)
+            .cloned()
+            .collect()
+    }
+
+    /// Completion items for the user snippets of the active buffer's language
+    pub(crate) fn snippet_completion_items(&mut self) -> Vec<lsp_types::CompletionItem> {
+        self.active_snippets()
+            .into_iter()
+            .flat_map(|snippet| {
+                snippet
+                    .prefixes
+                    .iter()
+                    .map(|prefix| lsp_types::CompletionItem {
+                        label: prefix.clone(),
+                        kind: Some(lsp_types::CompletionItemKind::SNIPPET),
+                        detail: Some(
+                            snippet
+                                .description
+                                .clone()
+                                .unwrap_or_else(|| snippet.name.clone()),
+                        ),
+                        insert_text: Some(snippet.body.clone()),
+                        insert_text_format: Some(lsp_types::InsertTextFormat::SNIPPET),
+                        ..Default::default()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Whether `text` is the body of a snippet in the completion popup
+    pub(crate) fn is_snippet_completion(&self, text: &str) -> bool {
+        self.completion_items.as_ref().is_some_and(|items| {
+            items.iter().any(|item| {
+                item.insert_text_format == Some(lsp_types::InsertTextFormat::SNIPPET)
+                    && item.insert_text.as_deref() == Some(text)
+            })
+        })
+    }
+
+    /// List the snippets of the active buffer's language to insert one
+    pub fn show_insert_snippet(&mut self) {
+        let snippets = self.active_snippets();
+        if snippets.is_empty() {
+            let dir = self.dir_context.snippets_dir();
+            self.set_status_message(
+                t!("snippet.none", dir = dir.display().to_string()).to_string(),
+            );
+            return;
+        }
+        let suggestions = snippets
+            .iter()
+            .enumerate()
+            .map(|(i, snippet)| crate::input::commands::Suggestion {
+                text: snippet.name.clone(),
+                description: snippet
+                    .description
+                    .clone()
+                    .or_else(|| Some(snippet.prefixes.join(", "))),
+                value: Some(i.to_string()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+        self.prompt = Some(Prompt::with_suggestions(
+            t!("snippet.prompt").to_string(),
+            PromptType::InsertSnippet,
+            suggestions,
+        ));
+    }
+
+    /// Insert the snippet picked in the "Insert Snippet" prompt over the selection
+    pub(crate) fn insert_snippet_from_prompt(&mut self, index: &str) {
+        let Some(snippet) = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| self.active_snippets().into_iter().nth(i))
+        else {
+            return;
+        };
+        let cursor = self.active_state().cursors.primary();
+        let range = cursor
+            .selection_range()
+            .unwrap_or(cursor.position..cursor.position);
+        self.insert_snippet(&snippet.body, range);
+    }
+
+    /// Replace `range` with the expansion of `body` and start a snippet session
+    pub(crate) fn insert_snippet(&mut self, body: &str, range: Range<usize>) {
+        let variables = self.snippet_variables(body, &range);
+        let now = chrono::Local::now();
+        let indent = {
+            let state = self.active_state();
+            let (line, _) = state.buffer.position_to_line_col(range.start);
+            let line = state.buffer.get_line(line).unwrap_or_default();
+            line.iter()
+                .take_while(|b| **b == b' ' || **b == b'\t')
+                .map(|b| *b as char)
+                .collect::<String>()
+        };
+        let expansion = expand_snippet_with(body, &indent, |name| {
+            variables
+                .get(name)
+                .cloned()
+                .or_else(|| date_variable(name, &now))
+        });
+
+        let state = self.active_state_mut();
+        let cursor_id = state.cursors.primary_id();
+        let mut events = secondary_cursor_removals(&state.cursors);
+        if !range.is_empty() {
+            events.push(Event::Delete {
+                range: range.clone(),
+                deleted_text: state.get_text_range(range.start, range.end),
+                cursor_id,
+            });
+        }
+        events.push(Event::Insert {
+            position: range.start,
+            text: expansion.text.clone(),
+            cursor_id,
+        });
+        let batch = Event::Batch {
+            events,
+            description: "Insert snippet".to_string(),
+        };
+        self.active_event_log_mut().append(batch.clone());
+        self.apply_event_to_active_buffer(&batch);
+
+        let session = SnippetSession::new(&expansion, range.start);
+        match session {
+            Some(session) => {
+                self.active_state_mut().snippet_session = Some(session);
+                self.select_snippet_tabstop();
+            }
+            None => {
+                // Only a final cursor position: place the cursor there
+                let ranges: Vec<_> = expansion
+                    .tabstops
+                    .last()
+                    .iter()
+                    .flat_map(|t| t.ranges.iter())
+                    .map(|r| range.start + r.start..range.start + r.end)
+                    .collect();
+                self.select_ranges(&ranges);
+            }
+        }
+    }
+
+    /// Whether Tab and Shift+Tab should move between snippet tabstops
+    ///
+    /// Ends the session if the cursor has left the snippet.
+    pub(crate) fn snippet_session_active(&mut self) -> bool {
+        let state = self.active_state_mut();
+        let position = state.cursors.primary().position;
+        match &state.snippet_session {
+            Some(session) if session.contains(position) => true,
+            Some(_) => {
+                state.snippet_session = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Select the next tabstop of the snippet session; `$0` ends it
+    pub(crate) fn snippet_next_tabstop(&mut self) {
+        if let Some(session) = &mut self.active_state_mut().snippet_session {
+            session.next_tabstop();
+        }
+        self.select_snippet_tabstop();
+    }
+
+    /// Select the previous tabstop of the snippet session
+    pub(crate) fn snippet_prev_tabstop(&mut self) {
+        if let Some(session) = &mut self.active_state_mut().snippet_session {
+            session.prev_tabstop();
+        }
+        self.select_snippet_tabstop();
+    }
+
+    /// End the snippet session of the active buffer
+    pub(crate) fn end_snippet_session(&mut self) {
+        self.active_state_mut().snippet_session = None;
+    }
+
+    /// Select every occurrence of the current tabstop
+    fn select_snippet_tabstop(&mut self) {
+        let Some(session) = &self.active_state().snippet_session else {
+            return;
+        };
+        let Tabstop {
+            ranges, choices, ..
+        } = session.current().clone();
+        if session.at_final() {
+            self.end_snippet_session();
+        }
+        self.select_ranges(&ranges);
+        if choices.len() > 1 {
+            self.show_snippet_choices(choices);
+        }
+    }
+
+    /// Put the primary cursor on the first range and a cursor on each other one
+    fn select_ranges(&mut self, ranges: &[Range<usize>]) {
+        let Some(first) = ranges.first() else {
+            return;
+        };
+        let cursors = &self.active_state().cursors;
+        let primary_id = cursors.primary_id();
+        let primary = cursors.primary();
+        let mut events = secondary_cursor_removals(cursors);
+        events.push(Event::MoveCursor {
+            cursor_id: primary_id,
+            old_position: primary.position,
+            new_position: first.end,
+            old_anchor: primary.anchor,
+            new_anchor: (!first.is_empty()).then_some(first.start),
+            old_sticky_column: 0,
+            new_sticky_column: 0,
+        });
+        let next_id = cursors.iter().map(|(id, _)| id.0).max().unwrap_or(0) + 1;
+        for (i, range) in ranges[1..].iter().enumerate() {
+            events.push(Event::AddCursor {
+                cursor_id: CursorId(next_id + i),
+                position: range.end,
+                anchor: (!range.is_empty()).then_some(range.start),
+            });
+        }
+        let batch = Event::Batch {
+            events,
+            description: "Select snippet tabstop".to_string(),
+        };
+        self.active_event_log_mut().append(batch.clone());
+        self.apply_event_to_active_buffer(&batch);
+    }
+
+    /// Offer the options of a choice tabstop in a popup
+    fn show_snippet_choices(&mut self, choices: Vec<String>) {
+        use crate::model::event::{
+            PopupContentData, PopupData, PopupListItemData, PopupPositionData,
+        };
+        let popup = PopupData {
+            title: Some(t!("snippet.popup_choice").to_string()),
+            description: None,
+            transient: false,
+            content: PopupContentData::List {
+                items: choices
+                    .into_iter()
+                    .map(|choice| PopupListItemData {
+                        text: choice.clone(),
+                        detail: None,
+                        icon: None,
+                        data: Some(choice),
+                    })
+                    .collect(),
+                selected: 0,
+            },
+            position: PopupPositionData::BelowCursor,
+            width: 30,
+            max_height: 10,
+            bordered: true,
+        };
+        self.active_state_mut().apply(&Event::ShowPopup { popup });
+    }
+
+    /// Values of the snippet variables used in `body`
+    fn snippet_variables(
+        &mut self,
+        body: &str,
+        range: &Range<usize>,
+    ) -> HashMap<&'static str, String> {
+        let mut variables = HashMap::new();
+        let working_dir = self.working_dir.clone();
+        let state = self.active_state_mut();
+
+        let selected = state.get_text_range(range.start, range.end);
+        variables.insert("TM_SELECTED_TEXT", selected);
+        let (line, _) = state.buffer.position_to_line_col(range.start);
+        let line_text = state.buffer.get_line(line).unwrap_or_default();
+        let line_text = String::from_utf8_lossy(&line_text);
+        variables.insert(
+            "TM_CURRENT_LINE",
+            line_text.trim_end_matches(['\r', '\n']).to_string(),
+        );
+        let word =
+            find_word_start(&state.buffer, range.start)..find_word_end(&state.buffer, range.start);
+        variables.insert(
+            "TM_CURRENT_WORD",
+            state.get_text_range(word.start, word.end),
+        );
+        variables.insert("TM_LINE_INDEX", line.to_string());
+        variables.insert("TM_LINE_NUMBER", (line + 1).to_string());
+
+        if let Some(path) = state.buffer.file_path().map(PathBuf::from) {
+            let file_name = |p: &std::path::Path| {
+                p.file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            };
+            variables.insert("TM_FILENAME", file_name(&path));
+            variables.insert(
+                "TM_FILENAME_BASE",
+                path.file_stem()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            );
+            if let Some(parent) = path.parent() {
+                variables.insert("TM_DIRECTORY", parent.display().to_string());
+            }
+            variables.insert("TM_FILEPATH", path.display().to_string());
+            let relative = path.strip_prefix(&working_dir).unwrap_or(&path);
+            variables.insert("RELATIVE_FILEPATH", relative.display().to_string());
+        }
+        variables.insert("WORKSPACE_NAME", {
+            working_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        variables.insert("WORKSPACE_FOLDER", working_dir.display().to_string());
+
+        // Reading the system clipboard can be slow; only do it when needed
+        if body.contains("CLIPBOARD") {
+            variables.insert("CLIPBOARD", self.clipboard.paste().unwrap_or_default());
+        }
+        variables
+    }
+}
+
+/// Events removing every cursor but the primary one
+fn secondary_cursor_removals(cursors: &crate::model::cursor::Cursors) -> Vec<Event> {
+    let primary_id = cursors.primary_id();
+    cursors
+        .iter()
+        .filter(|(id, _)| *id != primary_id)
+        .map(|(cursor_id, cursor)| Event::RemoveCursor {
+            cursor_id,
+            position: cursor.position,
+            anchor: cursor.anchor,
+        })
+        .collect()
+}
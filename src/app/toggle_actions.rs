@@ -8,10 +8,9 @@
 
 use rust_i18n::t;
 
-use crate::config::Config;
+use crate::config::{BufferConfig, Config};
 use crate::config_io::{ConfigLayer, ConfigResolver};
 use crate::input::keybindings::KeybindingResolver;
-use crate::services::lsp::manager::detect_language;
 
 use super::Editor;
 
@@ -88,30 +87,20 @@ impl Editor {
             .get(&buffer_id)
             .and_then(|m| m.file_path().cloned());
 
-        // Determine settings from config (with language fallback)
-        let (tab_size, use_tabs, show_whitespace_tabs) = if let Some(path) = &file_path {
-            if let Some(language) = detect_language(path, &self.config.languages) {
-                if let Some(lang_config) = self.config.languages.get(&language) {
-                    (
-                        lang_config.tab_size.unwrap_or(self.config.editor.tab_size),
-                        lang_config.use_tabs,
-                        lang_config.show_whitespace_tabs,
-                    )
-                } else {
-                    (self.config.editor.tab_size, false, true)
-                }
-            } else {
-                (self.config.editor.tab_size, false, true)
-            }
-        } else {
-            (self.config.editor.tab_size, false, true)
+        // Determine settings from config (with language fallback and .editorconfig)
+        let buffer_config = match &file_path {
+            Some(path) => self.buffer_config_for_path(path),
+            None => BufferConfig::resolve(&self.config, None),
         };
 
         // Apply settings to buffer
         if let Some(state) = self.buffers.get_mut(&buffer_id) {
-            state.tab_size = tab_size;
-            state.use_tabs = use_tabs;
-            state.show_whitespace_tabs = show_whitespace_tabs;
+            state.tab_size = buffer_config.tab_size;
+            state.use_tabs = buffer_config.use_tabs;
+            state.show_whitespace_tabs = buffer_config.show_whitespace_tabs;
+            state.rulers = buffer_config.rulers;
+            state.indent_guides = buffer_config.indent_guides;
+            state.bracket_pair_colorization = buffer_config.bracket_pair_colorization;
         }
 
         self.set_status_message(t!("toggle.buffer_settings_reset").to_string());
@@ -176,7 +165,7 @@ impl Editor {
         } else {
             // Clear inlay hints from all buffers
             for state in self.buffers.values_mut() {
-                state.virtual_texts.clear(&mut state.marker_list);
+                state.virtual_texts.clear_inline(&mut state.marker_list);
             }
             self.set_status_message(t!("toggle.inlay_hints_disabled").to_string());
         }
@@ -35,6 +35,27 @@ pub(super) struct SearchState {
     pub wrap_search: bool,
     /// Optional search range (for search in selection)
     pub search_range: Option<Range<usize>>,
+    /// Matches of a background search in a large file, which are highlighted
+    /// as they scroll into view instead of all at once
+    pub viewport_matches: Option<ViewportMatches>,
+}
+
+/// Matches of a background search, highlighted one viewport at a time
+///
+/// Only the first `MAX_REPORTED_MATCHES` positions are stored, in
+/// `SearchState::matches`; later matches are only counted.
+#[derive(Debug, Clone)]
+pub(super) struct ViewportMatches {
+    /// Buffer searched
+    pub buffer_id: BufferId,
+    /// Buffer version the matches were found in
+    pub version: u64,
+    /// Lengths of the stored matches
+    pub lengths: Vec<usize>,
+    /// Number of matches found, including those not stored
+    pub total: usize,
+    /// Byte range whose matches are currently highlighted
+    pub highlighted: Option<Range<usize>>,
 }
 
 /// A bookmark in the editor (position in a specific buffer)
@@ -357,6 +378,8 @@ pub enum HoverTarget {
     StatusBarWarningBadge,
     /// Hovering over the status bar line ending indicator
     StatusBarLineEndingIndicator,
+    /// Hovering over the status bar encoding indicator
+    StatusBarEncodingIndicator,
     /// Hovering over the search options "Case Sensitive" checkbox
     SearchOptionCaseSensitive,
     /// Hovering over the search options "Whole Word" checkbox
@@ -593,6 +616,8 @@ impl ViewLineMapping {
 pub(crate) struct CachedLayout {
     /// File explorer area (if visible)
     pub file_explorer_area: Option<Rect>,
+    /// Outline panel area (if visible)
+    pub outline_area: Option<Rect>,
     /// Editor content area (excluding file explorer)
     pub editor_content_area: Option<Rect>,
     /// Individual split areas with their scrollbar areas and thumb positions
@@ -622,6 +647,11 @@ pub(crate) struct CachedLayout {
     /// Maps visual row index to character position mappings
     /// Used to translate screen coordinates to buffer byte positions
     pub view_line_mappings: HashMap<SplitId, Vec<ViewLineMapping>>,
+    /// Breadcrumb hit areas
+    /// (split_id, crumb_index, row, start_col, end_col)
+    pub breadcrumb_areas: Vec<(SplitId, usize, u16, u16, u16)>,
+    /// Position of the scrollbar mark on each track row, per split
+    pub scrollbar_mark_rows: HashMap<SplitId, Vec<Option<usize>>>,
     /// Settings modal layout for hit testing
     pub settings_layout: Option<crate::view::settings::SettingsLayout>,
     /// Status bar area (row, x, width)
@@ -632,6 +662,8 @@ pub(crate) struct CachedLayout {
     pub status_bar_warning_area: Option<(u16, u16, u16)>,
     /// Status bar line ending indicator area (row, start_col, end_col)
     pub status_bar_line_ending_area: Option<(u16, u16, u16)>,
+    /// Status bar encoding indicator area (row, start_col, end_col)
+    pub status_bar_encoding_area: Option<(u16, u16, u16)>,
     /// Search options layout for checkbox hit testing
     pub search_options_layout: Option<crate::view::ui::status_bar::SearchOptionsLayout>,
 }
@@ -1,7 +1,11 @@
-//! Undo and redo action handlers.
+//! Undo and redo action handlers (including chronological navigation of the undo
+//! tree), and persistence of undo history across sessions.
 
 use super::Editor;
+use crate::model::event::BufferId;
 use rust_i18n::t;
+use std::path::Path;
+use std::time::Duration;
 
 impl Editor {
     /// Handle Undo action - revert the last edit operation.
@@ -52,4 +56,115 @@ impl Editor {
         // Update modified status based on event log position
         self.update_modified_from_event_log();
     }
+
+    /// Handle UndoChronological action - go back to the state created before the
+    /// current one, even if it is on another branch of the undo tree.
+    pub fn handle_undo_chronological(&mut self) {
+        if self.is_editing_disabled() {
+            self.set_status_message(t!("buffer.editing_disabled").to_string());
+            return;
+        }
+
+        let events = self.active_event_log_mut().undo_chronological();
+        for event in &events {
+            self.apply_event_to_active_buffer(event);
+        }
+
+        self.update_modified_from_event_log();
+    }
+
+    /// Handle RedoChronological action - go forward to the state created after
+    /// the current one, even if it is on another branch of the undo tree.
+    pub fn handle_redo_chronological(&mut self) {
+        if self.is_editing_disabled() {
+            self.set_status_message(t!("buffer.editing_disabled").to_string());
+            return;
+        }
+
+        let events = self.active_event_log_mut().redo_chronological();
+        for event in &events {
+            self.apply_event_to_active_buffer(event);
+        }
+
+        self.update_modified_from_event_log();
+    }
+
+    /// Restore the persisted undo history of a freshly opened file buffer.
+    ///
+    /// Does nothing unless persistent undo is enabled. The history is only restored
+    /// if the file content still matches what it was when the history was stored.
+    pub(crate) fn restore_undo_history(&mut self, buffer_id: BufferId, path: &Path) {
+        if !self.config.editor.persistent_undo {
+            return;
+        }
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        if state.buffer.is_large_file() || state.buffer.is_binary() || !path.is_file() {
+            return;
+        }
+
+        match self.undo_history.load(path) {
+            Ok(Some(event_log)) => {
+                tracing::debug!(
+                    "Restored {} undo history entries for {:?}",
+                    event_log.len(),
+                    path
+                );
+                self.event_logs.insert(buffer_id, event_log);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to load undo history for {:?}: {}", path, e),
+        }
+    }
+
+    /// Persist the undo history of a file buffer so it survives closing the editor.
+    ///
+    /// Does nothing unless persistent undo is enabled. Buffers whose file changed on
+    /// disk since it was last loaded or saved are skipped, since their history no
+    /// longer describes the file content.
+    pub(crate) fn persist_undo_history(&mut self, buffer_id: BufferId) {
+        if !self.config.editor.persistent_undo {
+            return;
+        }
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        if state.buffer.is_large_file() || state.buffer.is_binary() {
+            return;
+        }
+        let Some(path) = state.buffer.file_path() else {
+            return;
+        };
+        let Some(event_log) = self.event_logs.get(&buffer_id) else {
+            return;
+        };
+
+        let disk_mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        if disk_mtime.is_none() || self.file_mod_times.get(path).copied() != disk_mtime {
+            return;
+        }
+
+        if let Err(e) = self.undo_history.save(path, event_log) {
+            tracing::warn!("Failed to persist undo history for {:?}: {}", path, e);
+            return;
+        }
+
+        let max_age =
+            Duration::from_secs(self.config.editor.persistent_undo_max_age_days as u64 * 86400);
+        if let Err(e) = self
+            .undo_history
+            .prune(max_age, self.config.editor.persistent_undo_max_bytes)
+        {
+            tracing::warn!("Failed to prune undo history store: {}", e);
+        }
+    }
+
+    /// Persist the undo history of all open file buffers (call on shutdown)
+    pub fn persist_all_undo_histories(&mut self) {
+        let buffer_ids: Vec<BufferId> = self.buffers.keys().copied().collect();
+        for buffer_id in buffer_ids {
+            self.persist_undo_history(buffer_id);
+        }
+    }
 }
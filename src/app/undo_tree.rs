@@ -0,0 +1,657 @@
+//! Undo tree panel.
+//!
+//! Shows every branch of a buffer's undo history in a virtual buffer split off
+//! next to it, newest state first, and previews the state under the cursor as a
+//! diff against the buffer's current content. Enter restores the selected state.
+
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rust_i18n::t;
+
+use super::Editor;
+use crate::model::buffer::TextBuffer;
+use crate::model::event::{BufferId, Event, OverlayFace, SplitDirection, UndoState};
+use crate::model::line_diff::{unified_diff, DiffLine};
+use crate::primitives::text_property::TextPropertyEntry;
+use crate::view::overlay::OverlayNamespace;
+use crate::view::split::SplitViewState;
+
+/// Display name of the undo tree panel buffer
+pub(crate) const UNDO_TREE_BUFFER_NAME: &str = "*Undo Tree*";
+
+/// Buffer mode of the undo tree panel (registered in `ModeRegistry::new`)
+pub(crate) const UNDO_TREE_MODE: &str = "undo-tree";
+
+/// Text property holding the undo state shown on a tree row
+const STATE_PROPERTY: &str = "undo_state";
+
+/// Overlay namespace for the diff preview highlighting
+const OVERLAY_NAMESPACE: &str = "undo-tree";
+
+/// Unchanged lines shown around each change in the preview
+const PREVIEW_CONTEXT_LINES: usize = 3;
+
+/// Longest inserted/deleted text shown in a tree row
+const MAX_DESCRIPTION_CHARS: usize = 24;
+
+/// Background highlights of the panel content, by byte range
+type Highlights = Vec<(Range<usize>, (u8, u8, u8))>;
+
+/// State of the open undo tree panel
+pub(crate) struct UndoTreePanel {
+    /// The panel's virtual buffer
+    buffer_id: BufferId,
+
+    /// Buffer whose history is shown
+    source: BufferId,
+
+    /// History and selection the panel was last rendered for
+    rendered: Option<RenderedView>,
+}
+
+/// What the panel content currently reflects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RenderedView {
+    newest_state: usize,
+    content_state: usize,
+    saved_state: Option<usize>,
+    selected: usize,
+}
+
+impl Editor {
+    /// Open the undo tree panel for the active buffer, or focus it if it's already open
+    pub fn show_undo_tree(&mut self) {
+        let source = self.active_buffer();
+        if self.undo_tree.as_ref().map(|p| p.buffer_id) == Some(source) {
+            return;
+        }
+        if !self.event_logs.contains_key(&source) {
+            return;
+        }
+
+        let existing = self
+            .undo_tree
+            .as_ref()
+            .map(|p| p.buffer_id)
+            .filter(|id| self.buffers.contains_key(id));
+        let buffer_id = match existing {
+            Some(buffer_id) => buffer_id,
+            None => {
+                let buffer_id = self.create_virtual_buffer(
+                    UNDO_TREE_BUFFER_NAME.to_string(),
+                    UNDO_TREE_MODE.to_string(),
+                    true,
+                );
+                if let Some(state) = self.buffers.get_mut(&buffer_id) {
+                    state.margins.set_line_numbers(false);
+                    state.editing_disabled = true;
+                }
+                buffer_id
+            }
+        };
+        self.undo_tree = Some(UndoTreePanel {
+            buffer_id,
+            source,
+            rendered: None,
+        });
+
+        // Focus the panel, splitting it off next to the source if it isn't visible
+        self.save_current_split_view_state();
+        if let Some(&split_id) = self.split_manager.splits_for_buffer(buffer_id).first() {
+            self.split_manager.set_active_split(split_id);
+        } else {
+            match self
+                .split_manager
+                .split_active(SplitDirection::Vertical, buffer_id, 0.6)
+            {
+                Ok(split_id) => {
+                    let mut view_state = SplitViewState::with_buffer(
+                        self.terminal_width,
+                        self.terminal_height,
+                        buffer_id,
+                    );
+                    view_state.viewport.line_wrap_enabled = false;
+                    self.split_view_states.insert(split_id, view_state);
+                    self.split_manager.set_active_split(split_id);
+                }
+                Err(e) => {
+                    self.set_status_message(t!("split.error", error = e).to_string());
+                    return;
+                }
+            }
+        }
+        self.restore_current_split_view_state();
+
+        self.sync_undo_tree_panel();
+    }
+
+    /// Restore the state selected in the undo tree panel in its source buffer
+    pub fn handle_undo_tree_restore(&mut self) {
+        let Some(panel) = &self.undo_tree else {
+            return;
+        };
+        let (buffer_id, source) = (panel.buffer_id, panel.source);
+        if self.active_buffer() != buffer_id {
+            return;
+        }
+        let Some(target) = self.undo_tree_selection(buffer_id) else {
+            return;
+        };
+        let source_split = self
+            .split_manager
+            .splits_for_buffer(source)
+            .first()
+            .copied();
+        let Some(source_split) = source_split.filter(|_| self.event_logs.contains_key(&source))
+        else {
+            self.set_status_message(t!("undo_tree.source_hidden").to_string());
+            return;
+        };
+
+        // Apply the jump in the source buffer's split, then come back to the panel
+        let panel_split = self.split_manager.active_split();
+        self.save_current_split_view_state();
+        self.split_manager.set_active_split(source_split);
+        self.restore_current_split_view_state();
+
+        let events = self.active_event_log_mut().jump_to(target);
+        for event in &events {
+            self.apply_event_to_active_buffer(event);
+        }
+        self.update_modified_from_event_log();
+
+        self.save_current_split_view_state();
+        self.split_manager.set_active_split(panel_split);
+        self.restore_current_split_view_state();
+
+        self.set_status_message(t!("undo_tree.restored", id = target).to_string());
+        self.sync_undo_tree_panel();
+    }
+
+    /// Bring the undo tree panel up to date with its source buffer's history
+    /// and the state selected in it. Cheap when nothing changed; called before
+    /// every render.
+    pub(crate) fn sync_undo_tree_panel(&mut self) {
+        let Some(panel) = &self.undo_tree else {
+            return;
+        };
+        let (buffer_id, source, rendered) = (panel.buffer_id, panel.source, panel.rendered);
+        if !self.buffers.contains_key(&buffer_id) {
+            self.undo_tree = None;
+            return;
+        }
+        // Keep showing the last history if the source buffer was closed
+        let Some(log) = self.event_logs.get(&source) else {
+            return;
+        };
+
+        let history = (log.newest_state(), log.content_state(), log.saved_state());
+        let history_changed = rendered
+            .map(|r| (r.newest_state, r.content_state, r.saved_state) != history)
+            .unwrap_or(true);
+        // After the history changed, select the current state again
+        let selected = if history_changed {
+            log.content_state()
+        } else {
+            match self.undo_tree_selection(buffer_id) {
+                Some(selected) if rendered.map(|r| r.selected) != Some(selected) => selected,
+                _ => return,
+            }
+        };
+
+        let (entries, highlights, selected_offset) = self.undo_tree_content(source, selected);
+        if let Err(e) = self.set_virtual_buffer_content(buffer_id, entries) {
+            tracing::error!("Failed to set undo tree content: {}", e);
+            return;
+        }
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            let namespace = OverlayNamespace::from_string(OVERLAY_NAMESPACE.to_string());
+            state.apply(&Event::ClearNamespace {
+                namespace: namespace.clone(),
+            });
+            for (range, color) in highlights {
+                state.apply(&Event::AddOverlay {
+                    namespace: Some(namespace.clone()),
+                    range,
+                    face: OverlayFace::Background { color },
+                    priority: 10,
+                    message: None,
+                    extend_to_line_end: true,
+                });
+            }
+        }
+        if history_changed {
+            self.set_undo_tree_cursor(buffer_id, selected_offset);
+        }
+
+        if let Some(panel) = &mut self.undo_tree {
+            panel.rendered = Some(RenderedView {
+                newest_state: history.0,
+                content_state: history.1,
+                saved_state: history.2,
+                selected,
+            });
+        }
+    }
+
+    /// Forget the undo tree panel if its buffer is being closed
+    pub(crate) fn on_undo_tree_buffer_closed(&mut self, buffer_id: BufferId) {
+        if self.undo_tree.as_ref().map(|p| p.buffer_id) == Some(buffer_id) {
+            self.undo_tree = None;
+        }
+    }
+
+    /// State shown on the panel row under the cursor
+    fn undo_tree_selection(&self, buffer_id: BufferId) -> Option<usize> {
+        let state = self.buffers.get(&buffer_id)?;
+        // The panel's cursor lives in its split's view state unless the panel is focused
+        let position = if self.active_buffer() == buffer_id {
+            state.cursors.primary().position
+        } else {
+            let split_id = *self.split_manager.splits_for_buffer(buffer_id).first()?;
+            self.split_view_states
+                .get(&split_id)?
+                .cursors
+                .primary()
+                .position
+        };
+        state
+            .text_properties
+            .get_at(position)
+            .iter()
+            .find_map(|p| p.get_as::<usize>(STATE_PROPERTY))
+    }
+
+    /// Move the panel's cursor, in the buffer and in every split showing it
+    fn set_undo_tree_cursor(&mut self, buffer_id: BufferId, position: usize) {
+        if let Some(state) = self.buffers.get_mut(&buffer_id) {
+            state.cursors.primary_mut().position = position;
+            state.cursors.primary_mut().anchor = None;
+        }
+        for split_id in self.split_manager.splits_for_buffer(buffer_id) {
+            if let Some(view_state) = self.split_view_states.get_mut(&split_id) {
+                view_state.cursors.primary_mut().position = position;
+                view_state.cursors.primary_mut().anchor = None;
+            }
+        }
+    }
+
+    /// Build the panel content: the tree followed by a diff preview of `selected`.
+    /// Returns the entries, background highlights and the byte offset of the
+    /// selected row.
+    fn undo_tree_content(
+        &self,
+        source: BufferId,
+        selected: usize,
+    ) -> (Vec<TextPropertyEntry>, Highlights, usize) {
+        let mut entries = Vec::new();
+        let mut highlights = Vec::new();
+        let mut offset = 0;
+        let mut selected_offset = 0;
+        let push =
+            |entries: &mut Vec<TextPropertyEntry>, offset: &mut usize, entry: TextPropertyEntry| {
+                *offset += entry.text.len();
+                entries.push(entry);
+                *offset
+            };
+
+        let (Some(log), Some(state)) = (self.event_logs.get(&source), self.buffers.get(&source))
+        else {
+            return (entries, highlights, selected_offset);
+        };
+        let name = self
+            .buffer_metadata
+            .get(&source)
+            .map(|m| m.display_name.clone())
+            .unwrap_or_default();
+
+        push(
+            &mut entries,
+            &mut offset,
+            TextPropertyEntry::text(format!("{}\n", t!("undo_tree.title", name = name))),
+        );
+        push(
+            &mut entries,
+            &mut offset,
+            TextPropertyEntry::text(format!("{}\n\n", t!("undo_tree.help"))),
+        );
+
+        // Tree rows, newest first
+        let states = log.states();
+        let current = log.content_state();
+        let saved = log.saved_state();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        for (graph, state_id) in layout_graph(&states, current) {
+            let Some(state_id) = state_id else {
+                push(
+                    &mut entries,
+                    &mut offset,
+                    TextPropertyEntry::text(format!("{graph}\n")),
+                );
+                continue;
+            };
+            let undo_state = states.iter().find(|s| s.id == state_id);
+            let entry = undo_state.and_then(|s| s.entry);
+            let mut line = format!("{graph}{state_id:>4}  ");
+            match entry {
+                Some(entry) => {
+                    line.push_str(&describe_event(&entry.event));
+                    line.push_str(&format!(
+                        "  {}",
+                        format_age(now.saturating_sub(entry.timestamp))
+                    ));
+                }
+                None => line.push_str(&t!("undo_tree.initial_state")),
+            }
+            if state_id == current {
+                line.push_str(&format!("  [{}]", t!("undo_tree.current")));
+            }
+            if Some(state_id) == saved {
+                line.push_str(&format!("  [{}]", t!("undo_tree.saved")));
+            }
+            if state_id == selected {
+                selected_offset = offset;
+            }
+            push(
+                &mut entries,
+                &mut offset,
+                TextPropertyEntry::text(format!("{line}\n"))
+                    .with_property(STATE_PROPERTY, serde_json::json!(state_id)),
+            );
+        }
+
+        // Diff preview of the selected state against the current content
+        push(&mut entries, &mut offset, TextPropertyEntry::text("\n"));
+        let preview = if state.buffer.is_large_file() {
+            None
+        } else {
+            state.buffer.to_string().and_then(|current_text| {
+                let mut target_text = current_text.clone();
+                apply_to_text(&mut target_text, &log.events_to(selected), &state.buffer)?;
+                Some(unified_diff(
+                    &current_text,
+                    &target_text,
+                    PREVIEW_CONTEXT_LINES,
+                ))
+            })
+        };
+        let Some(diff) = preview else {
+            push(
+                &mut entries,
+                &mut offset,
+                TextPropertyEntry::text(format!("{}\n", t!("undo_tree.preview_unavailable"))),
+            );
+            return (entries, highlights, selected_offset);
+        };
+        if diff.is_empty() {
+            push(
+                &mut entries,
+                &mut offset,
+                TextPropertyEntry::text(format!("{}\n", t!("undo_tree.no_changes"))),
+            );
+            return (entries, highlights, selected_offset);
+        }
+
+        push(
+            &mut entries,
+            &mut offset,
+            TextPropertyEntry::text(format!("{}\n", t!("undo_tree.diff_header", id = selected))),
+        );
+        let rgb = |color: ratatui::style::Color, fallback: (u8, u8, u8)| match color {
+            ratatui::style::Color::Rgb(r, g, b) => (r, g, b),
+            _ => fallback,
+        };
+        let added_color = rgb(self.theme.diff_add_bg, (35, 60, 35));
+        let removed_color = rgb(self.theme.diff_remove_bg, (70, 35, 35));
+        for line in diff {
+            let start = offset;
+            let (text, color) = match line {
+                DiffLine::Context(text) => (format!(" {text}\n"), None),
+                DiffLine::Removed(text) => (format!("-{text}\n"), Some(removed_color)),
+                DiffLine::Added(text) => (format!("+{text}\n"), Some(added_color)),
+                DiffLine::Skipped(count) => (
+                    format!("{}\n", t!("undo_tree.lines_skipped", count = count)),
+                    None,
+                ),
+            };
+            let end = push(&mut entries, &mut offset, TextPropertyEntry::text(text));
+            if let Some(color) = color {
+                highlights.push((start..end - 1, color));
+            }
+        }
+
+        (entries, highlights, selected_offset)
+    }
+}
+
+/// Lay out the undo tree as text rows, newest state first.
+/// Returns the graph drawing of each row and the state it shows (None for rows
+/// that only join branches).
+fn layout_graph(states: &[UndoState], current: usize) -> Vec<(String, Option<usize>)> {
+    // Each column is waiting for the state its branch continues from
+    let mut columns: Vec<Option<usize>> = Vec::new();
+    let mut rows = Vec::new();
+
+    for state in states.iter().rev() {
+        let waiting: Vec<usize> = columns
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == Some(state.id))
+            .map(|(i, _)| i)
+            .collect();
+        let column = match waiting.first() {
+            Some(&column) => column,
+            None => match columns.iter().position(Option::is_none) {
+                Some(column) => column,
+                None => {
+                    columns.push(None);
+                    columns.len() - 1
+                }
+            },
+        };
+
+        // Join the other branches that fork off this state into its column
+        if let Some(&last) = waiting.last().filter(|&&last| last != column) {
+            let mut line = String::new();
+            for (i, c) in columns.iter().enumerate() {
+                let ch = if i == column {
+                    '├'
+                } else if i == last {
+                    '┘'
+                } else if waiting.contains(&i) {
+                    '┴'
+                } else if i > column && i < last {
+                    if c.is_some() {
+                        '┼'
+                    } else {
+                        '─'
+                    }
+                } else if c.is_some() {
+                    '│'
+                } else {
+                    ' '
+                };
+                line.push(ch);
+                line.push(if i >= column && i < last { '─' } else { ' ' });
+            }
+            rows.push((line, None));
+            for &i in &waiting[1..] {
+                columns[i] = None;
+            }
+        }
+
+        let mut line = String::new();
+        for (i, c) in columns.iter().enumerate() {
+            let ch = if i == column {
+                if state.id == current {
+                    '●'
+                } else {
+                    '○'
+                }
+            } else if c.is_some() {
+                '│'
+            } else {
+                ' '
+            };
+            line.push(ch);
+            line.push(' ');
+        }
+        rows.push((line, Some(state.id)));
+
+        columns[column] = state.parent;
+        while columns.last() == Some(&None) {
+            columns.pop();
+        }
+    }
+
+    let width = rows
+        .iter()
+        .map(|(graph, _)| graph.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (graph, _) in &mut rows {
+        let padding = width - graph.chars().count();
+        graph.extend(std::iter::repeat_n(' ', padding));
+    }
+    rows
+}
+
+/// Short description of the write event that produced a state
+fn describe_event(event: &Event) -> String {
+    match event {
+        Event::Insert { text, .. } => t!("undo_tree.insert", text = quote(text)).to_string(),
+        Event::Delete { deleted_text, .. } => {
+            t!("undo_tree.delete", text = quote(deleted_text)).to_string()
+        }
+        Event::Batch { description, .. } | Event::BulkEdit { description, .. } => {
+            description.clone()
+        }
+        Event::AddCursor { .. } => t!("undo_tree.add_cursor").to_string(),
+        Event::RemoveCursor { .. } => t!("undo_tree.remove_cursor").to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Quote and escape text for a single row, truncating long text
+fn quote(text: &str) -> String {
+    if text.chars().count() > MAX_DESCRIPTION_CHARS {
+        let truncated: String = text.chars().take(MAX_DESCRIPTION_CHARS).collect();
+        format!("{truncated:?}…")
+    } else {
+        format!("{text:?}")
+    }
+}
+
+/// Compact age like "42s", "5m", "3h" or "2d"
+fn format_age(millis: u64) -> String {
+    let secs = millis / 1000;
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// Apply text edits to a copy of a buffer's content, for previews.
+/// Returns None if an event doesn't fit the text.
+fn apply_to_text(text: &mut String, events: &[Event], buffer: &TextBuffer) -> Option<()> {
+    for event in events {
+        match event {
+            Event::Insert {
+                position,
+                text: inserted,
+                ..
+            } => {
+                if !text.is_char_boundary(*position) {
+                    return None;
+                }
+                text.insert_str(*position, inserted);
+            }
+            Event::Delete { range, .. } => {
+                text.get(range.clone())?;
+                text.replace_range(range.clone(), "");
+            }
+            Event::Batch { events, .. } => apply_to_text(text, events, buffer)?,
+            Event::BulkEdit { new_tree, .. } => {
+                *text = buffer.piece_tree_text(new_tree.as_ref()?)?;
+            }
+            _ => {}
+        }
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(id: usize, parent: Option<usize>) -> UndoState<'static> {
+        UndoState {
+            id,
+            parent,
+            entry: None,
+        }
+    }
+
+    #[test]
+    fn test_layout_linear_history() {
+        let states = [state(0, None), state(1, Some(0)), state(2, Some(1))];
+        let rows = layout_graph(&states, 2);
+        assert_eq!(
+            rows,
+            vec![
+                ("● ".to_string(), Some(2)),
+                ("○ ".to_string(), Some(1)),
+                ("○ ".to_string(), Some(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_branches_join_at_fork() {
+        // 2 and 3 both continue from 1
+        let states = [
+            state(0, None),
+            state(1, Some(0)),
+            state(2, Some(1)),
+            state(3, Some(1)),
+        ];
+        let rows = layout_graph(&states, 2);
+        let lines: Vec<&str> = rows.iter().map(|(graph, _)| graph.as_str()).collect();
+        assert_eq!(lines, vec!["○   ", "│ ● ", "├─┘ ", "○   ", "○   "]);
+        assert_eq!(rows[2].1, None);
+        assert_eq!(rows[3].1, Some(1));
+    }
+
+    #[test]
+    fn test_apply_to_text() {
+        let buffer = TextBuffer::from_bytes(b"hello".to_vec());
+        let mut text = "hello".to_string();
+        let events = vec![
+            Event::Delete {
+                range: 0..1,
+                deleted_text: "h".to_string(),
+                cursor_id: crate::model::event::CursorId(0),
+            },
+            Event::Insert {
+                position: 0,
+                text: "j".to_string(),
+                cursor_id: crate::model::event::CursorId(0),
+            },
+        ];
+        assert_eq!(apply_to_text(&mut text, &events, &buffer), Some(()));
+        assert_eq!(text, "jello");
+
+        let out_of_range = [Event::Insert {
+            position: 42,
+            text: "x".to_string(),
+            cursor_id: crate::model::event::CursorId(0),
+        }];
+        assert_eq!(apply_to_text(&mut text, &out_of_range, &buffer), None);
+    }
+}
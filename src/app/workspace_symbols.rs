@@ -0,0 +1,408 @@
+//! "Go to Symbol in Workspace": a prompt that searches the symbols of all
+//! running language servers (`workspace/symbol`) as you type.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use lsp_types::{OneOf, Position, SymbolKind, WorkspaceSymbolResponse};
+use rust_i18n::t;
+
+use crate::input::commands::Suggestion;
+use crate::input::fuzzy::fuzzy_match;
+use crate::view::document_symbols::kind_icon;
+use crate::view::prompt::{Prompt, PromptType};
+
+use super::{uri_to_path, Editor};
+
+/// How long the query has to stay unchanged before it is sent
+const QUERY_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// A symbol found by a server
+#[derive(Debug, Clone, PartialEq)]
+struct WorkspaceSymbolItem {
+    name: String,
+    kind: SymbolKind,
+    container: Option<String>,
+    path: PathBuf,
+    /// Start of the symbol (LSP position, UTF-16 columns)
+    position: Position,
+}
+
+/// State of the open "Go to Symbol in Workspace" prompt
+#[derive(Debug)]
+pub(super) struct WorkspaceSymbolSearch {
+    /// When the query last changed; it is sent once it settles
+    changed_at: Option<Instant>,
+    /// Requests in flight: request id -> language of the server
+    pending: HashMap<u64, String>,
+    /// Latest results of each server, by language
+    results: HashMap<String, Vec<WorkspaceSymbolItem>>,
+    /// The merged results, in the order of the prompt's suggestions
+    shown: Vec<WorkspaceSymbolItem>,
+}
+
+impl Editor {
+    /// Open the "Go to Symbol in Workspace" prompt
+    pub fn show_workspace_symbol_picker(&mut self) {
+        if self.workspace_symbol_servers().is_empty() {
+            self.set_status_message(t!("workspace_symbols.unavailable").to_string());
+            return;
+        }
+        self.cancel_workspace_symbol_requests();
+        self.prompt = Some(Prompt::with_suggestions(
+            t!("workspace_symbols.prompt").to_string(),
+            PromptType::WorkspaceSymbol,
+            Vec::new(),
+        ));
+        self.workspace_symbol_search = Some(WorkspaceSymbolSearch {
+            changed_at: Some(self.time_source.now()),
+            pending: HashMap::new(),
+            results: HashMap::new(),
+            shown: Vec::new(),
+        });
+    }
+
+    /// Languages of the running servers that search workspace symbols
+    fn workspace_symbol_servers(&self) -> Vec<String> {
+        let Some(lsp) = self.lsp.as_ref() else {
+            return Vec::new();
+        };
+        let mut languages: Vec<String> = lsp
+            .running_servers()
+            .into_iter()
+            .filter(|language| lsp.workspace_symbol_supported(language))
+            .collect();
+        languages.sort();
+        languages
+    }
+
+    /// The prompt input changed: re-rank the current results and search
+    /// again once the query settles
+    pub(super) fn workspace_symbol_query_changed(&mut self) {
+        let now = self.time_source.now();
+        if let Some(search) = self.workspace_symbol_search.as_mut() {
+            search.changed_at = Some(now);
+        }
+        self.refresh_workspace_symbol_suggestions();
+    }
+
+    /// Send the query once it has settled, and forget the search when its
+    /// prompt is gone. Called from the async message loop.
+    pub(super) fn process_workspace_symbol_search(&mut self) {
+        let Some(search) = self.workspace_symbol_search.as_ref() else {
+            return;
+        };
+        let query = match &self.prompt {
+            Some(prompt) if prompt.prompt_type == PromptType::WorkspaceSymbol => {
+                prompt.input.clone()
+            }
+            _ => {
+                self.cancel_workspace_symbol_requests();
+                self.workspace_symbol_search = None;
+                return;
+            }
+        };
+        let Some(changed_at) = search.changed_at else {
+            return;
+        };
+        if self.time_source.now().duration_since(changed_at) < QUERY_DEBOUNCE {
+            return;
+        }
+
+        // Results of an older query are outdated now
+        self.cancel_workspace_symbol_requests();
+        let languages = self.workspace_symbol_servers();
+        let mut pending = HashMap::new();
+        if let Some(lsp) = self.lsp.as_mut() {
+            for language in languages {
+                let Some(handle) = lsp.get_handle_mut(&language) else {
+                    continue;
+                };
+                let request_id = self.next_lsp_request_id;
+                self.next_lsp_request_id += 1;
+                match handle.workspace_symbols(request_id, query.clone()) {
+                    Ok(()) => {
+                        pending.insert(request_id, language);
+                    }
+                    Err(e) => {
+                        tracing::debug!("Failed to request workspace symbols: {}", e);
+                    }
+                }
+            }
+        }
+        if let Some(search) = self.workspace_symbol_search.as_mut() {
+            search.changed_at = None;
+            search.pending = pending;
+        }
+    }
+
+    /// Cancel the requests of the search that are still in flight
+    fn cancel_workspace_symbol_requests(&mut self) {
+        let Some(search) = self.workspace_symbol_search.as_mut() else {
+            return;
+        };
+        let pending = std::mem::take(&mut search.pending);
+        let Some(lsp) = self.lsp.as_mut() else {
+            return;
+        };
+        for (request_id, language) in pending {
+            if let Some(handle) = lsp.get_handle_mut(&language) {
+                if let Err(e) = handle.cancel_request(request_id) {
+                    tracing::debug!("Failed to cancel workspace symbol request: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Handle LSP workspace symbol response
+    pub(super) fn handle_lsp_workspace_symbols(
+        &mut self,
+        request_id: u64,
+        language: String,
+        symbols: Option<WorkspaceSymbolResponse>,
+    ) {
+        let Some(search) = self.workspace_symbol_search.as_mut() else {
+            return;
+        };
+        if search.pending.remove(&request_id).is_none() {
+            tracing::debug!(
+                "Ignoring workspace symbol response {} of an outdated query",
+                request_id
+            );
+            return;
+        }
+        match symbols {
+            Some(symbols) => {
+                search
+                    .results
+                    .insert(language, workspace_symbol_items(symbols));
+            }
+            None => {
+                search.results.remove(&language);
+            }
+        }
+        self.refresh_workspace_symbol_suggestions();
+    }
+
+    /// Merge the results of all servers, rank them against the prompt input
+    /// and show them as suggestions
+    fn refresh_workspace_symbol_suggestions(&mut self) {
+        let (Some(search), Some(prompt)) =
+            (self.workspace_symbol_search.as_mut(), self.prompt.as_mut())
+        else {
+            return;
+        };
+        if prompt.prompt_type != PromptType::WorkspaceSymbol {
+            return;
+        }
+
+        // Servers running for several languages may report the same symbol
+        let mut seen = HashSet::new();
+        let mut languages: Vec<&String> = search.results.keys().collect();
+        languages.sort();
+        let mut ranked: Vec<(i32, &WorkspaceSymbolItem)> = languages
+            .into_iter()
+            .flat_map(|language| &search.results[language])
+            .filter(|item| {
+                seen.insert((
+                    item.name.as_str(),
+                    item.path.as_path(),
+                    item.position.line,
+                    item.position.character,
+                ))
+            })
+            .filter_map(|item| {
+                // Servers may match on the qualified name; keep those results
+                let by_name = fuzzy_match(&prompt.input, &item.name);
+                if by_name.matched {
+                    return Some((by_name.score, item));
+                }
+                let qualified = format!("{}.{}", item.container.as_deref()?, item.name);
+                let by_qualified = fuzzy_match(&prompt.input, &qualified);
+                by_qualified.matched.then_some((by_qualified.score, item))
+            })
+            .collect();
+        ranked.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.path.cmp(&b.path))
+                .then_with(|| a.position.line.cmp(&b.position.line))
+        });
+        search.shown = ranked.into_iter().map(|(_, item)| item.clone()).collect();
+
+        let working_dir = &self.working_dir;
+        prompt.suggestions = search
+            .shown
+            .iter()
+            .enumerate()
+            .map(|(index, item)| Suggestion {
+                text: format!("{} {}", kind_icon(item.kind), item.name),
+                description: Some(symbol_location(item, working_dir)),
+                value: Some(index.to_string()),
+                disabled: false,
+                keybinding: None,
+                source: None,
+            })
+            .collect();
+        prompt.selected_suggestion = if prompt.suggestions.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+    }
+
+    /// Open the symbol picked in the "Go to Symbol in Workspace" prompt
+    pub(crate) fn goto_workspace_symbol_from_prompt(&mut self, index: &str) {
+        let Some(search) = self.workspace_symbol_search.take() else {
+            return;
+        };
+        let Some(item) = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| search.shown.get(index))
+        else {
+            return;
+        };
+        let buffer_id = match self.open_file(&item.path) {
+            Ok(buffer_id) => buffer_id,
+            Err(e) => {
+                self.set_status_message(
+                    t!("file.error_opening", error = e.to_string()).to_string(),
+                );
+                return;
+            }
+        };
+        let Some(state) = self.buffers.get(&buffer_id) else {
+            return;
+        };
+        let position = state.buffer.lsp_position_to_byte(
+            item.position.line as usize,
+            item.position.character as usize,
+        );
+        self.move_cursor_and_reveal(self.split_manager.active_split(), position);
+    }
+}
+
+/// Flatten a `workspace/symbol` response, skipping symbols outside files
+fn workspace_symbol_items(response: WorkspaceSymbolResponse) -> Vec<WorkspaceSymbolItem> {
+    match response {
+        WorkspaceSymbolResponse::Flat(symbols) => symbols
+            .into_iter()
+            .filter_map(|symbol| {
+                Some(WorkspaceSymbolItem {
+                    path: uri_to_path(&symbol.location.uri).ok()?,
+                    position: symbol.location.range.start,
+                    name: symbol.name,
+                    kind: symbol.kind,
+                    container: symbol.container_name,
+                })
+            })
+            .collect(),
+        WorkspaceSymbolResponse::Nested(symbols) => symbols
+            .into_iter()
+            .filter_map(|symbol| {
+                let (uri, position) = match &symbol.location {
+                    OneOf::Left(location) => (&location.uri, location.range.start),
+                    // Symbols without a range land at the top of the file
+                    OneOf::Right(location) => (&location.uri, Position::default()),
+                };
+                Some(WorkspaceSymbolItem {
+                    path: uri_to_path(uri).ok()?,
+                    position,
+                    name: symbol.name,
+                    kind: symbol.kind,
+                    container: symbol.container_name,
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Container name and path of a symbol, relative to the working directory
+fn symbol_location(item: &WorkspaceSymbolItem, working_dir: &Path) -> String {
+    let path = item.path.strip_prefix(working_dir).unwrap_or(&item.path);
+    let location = format!("{}:{}", path.display(), item.position.line + 1);
+    match &item.container {
+        Some(container) if !container.is_empty() => format!("{}  {}", container, location),
+        _ => location,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::{Location, Range, SymbolInformation, Uri, WorkspaceLocation, WorkspaceSymbol};
+    use std::str::FromStr;
+
+    fn uri(path: &str) -> Uri {
+        Uri::from_str(&format!("file://{}", path)).unwrap()
+    }
+
+    #[allow(deprecated)]
+    fn information(name: &str, container: Option<&str>, line: u32) -> SymbolInformation {
+        SymbolInformation {
+            name: name.to_string(),
+            kind: SymbolKind::FUNCTION,
+            tags: None,
+            deprecated: None,
+            location: Location {
+                uri: uri("/project/src/lib.rs"),
+                range: Range::new(Position::new(line, 4), Position::new(line, 10)),
+            },
+            container_name: container.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_flat_response_items() {
+        let items = workspace_symbol_items(WorkspaceSymbolResponse::Flat(vec![information(
+            "parse",
+            Some("parser"),
+            3,
+        )]));
+        assert_eq!(
+            items,
+            vec![WorkspaceSymbolItem {
+                name: "parse".to_string(),
+                kind: SymbolKind::FUNCTION,
+                container: Some("parser".to_string()),
+                path: PathBuf::from("/project/src/lib.rs"),
+                position: Position::new(3, 4),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_nested_response_without_range() {
+        let items =
+            workspace_symbol_items(WorkspaceSymbolResponse::Nested(vec![WorkspaceSymbol {
+                name: "Config".to_string(),
+                kind: SymbolKind::STRUCT,
+                tags: None,
+                container_name: None,
+                location: OneOf::Right(WorkspaceLocation {
+                    uri: uri("/project/src/config.rs"),
+                }),
+                data: None,
+            }]));
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].position, Position::default());
+        assert_eq!(items[0].path, PathBuf::from("/project/src/config.rs"));
+    }
+
+    #[test]
+    fn test_symbol_location() {
+        let items = workspace_symbol_items(WorkspaceSymbolResponse::Flat(vec![
+            information("parse", Some("parser"), 3),
+            information("main", None, 0),
+        ]));
+        let working_dir = Path::new("/project");
+        assert_eq!(
+            symbol_location(&items[0], working_dir),
+            "parser  src/lib.rs:4"
+        );
+        assert_eq!(symbol_location(&items[1], working_dir), "src/lib.rs:1");
+    }
+}
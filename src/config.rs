@@ -289,6 +289,129 @@ impl JsonSchema for LineEndingOption {
     }
 }
 
+/// How files are replaced on disk when saving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveStrategyOption {
+    /// Write a temporary file and rename it over the original
+    #[default]
+    Atomic,
+    /// Overwrite the original file, keeping its inode and hard links
+    InPlace,
+    /// Write a temporary file, then copy it over the original, keeping its inode
+    Copy,
+}
+
+impl SaveStrategyOption {
+    /// Convert to the buffer's SaveStrategy type
+    pub fn to_save_strategy(&self) -> crate::model::save_options::SaveStrategy {
+        match self {
+            Self::Atomic => crate::model::save_options::SaveStrategy::Atomic,
+            Self::InPlace => crate::model::save_options::SaveStrategy::InPlace,
+            Self::Copy => crate::model::save_options::SaveStrategy::Copy,
+        }
+    }
+}
+
+impl JsonSchema for SaveStrategyOption {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("SaveStrategyOption")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "How files are replaced on disk when saving",
+            "type": "string",
+            "enum": ["atomic", "in_place", "copy"],
+            "default": "atomic"
+        })
+    }
+}
+
+/// Where to keep the previous version of a file when saving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupOption {
+    /// No backups
+    #[default]
+    None,
+    /// A single backup next to the file, named `file~`
+    Adjacent,
+    /// Numbered backups in the backup directory
+    Directory,
+}
+
+impl JsonSchema for BackupOption {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("BackupOption")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Where to keep the previous version of a file when saving",
+            "type": "string",
+            "enum": ["none", "adjacent", "directory"],
+            "default": "none"
+        })
+    }
+}
+
+/// What to do with the newline at the end of a file when saving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FinalNewlineOption {
+    /// Leave the end of the file as it is
+    #[default]
+    Keep,
+    /// Add a newline if the file does not end with one
+    Ensure,
+    /// Remove the newline at the end of the file
+    Strip,
+}
+
+impl JsonSchema for FinalNewlineOption {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("FinalNewlineOption")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "What to do with the newline at the end of a file when saving",
+            "type": "string",
+            "enum": ["keep", "ensure", "strip"],
+            "default": "keep"
+        })
+    }
+}
+
+/// What formats the files of a language
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatterSource {
+    /// The external formatter if one is configured, otherwise the language server
+    #[default]
+    Auto,
+    /// The language server (textDocument/formatting)
+    Lsp,
+    /// The external formatter command only
+    External,
+}
+
+impl JsonSchema for FormatterSource {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("FormatterSource")
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "What formats the files of a language",
+            "type": "string",
+            "enum": ["auto", "lsp", "external"],
+            "default": "auto"
+        })
+    }
+}
+
 impl PartialEq<KeybindingMapName> for str {
     fn eq(&self, other: &KeybindingMapName) -> bool {
         self == other.0
@@ -417,6 +540,30 @@ pub struct EditorConfig {
     #[serde(default = "default_true")]
     pub line_wrap: bool,
 
+    /// Pin the lines that open the scopes enclosing the top of the view
+    /// (functions, classes, blocks) to the top of each split
+    #[serde(default = "default_false")]
+    pub sticky_scroll: bool,
+
+    /// Maximum number of lines pinned by sticky scroll
+    #[serde(default = "default_sticky_scroll_max_lines")]
+    pub sticky_scroll_max_lines: usize,
+
+    /// Whether to draw vertical guides in the leading whitespace at each
+    /// indentation level
+    #[serde(default = "default_false")]
+    pub indent_guides: bool,
+
+    /// Whether to enable bracket pair colorization (brackets colored by their
+    /// nesting depth)
+    #[serde(default = "default_false")]
+    pub bracket_pair_colorization: bool,
+
+    /// Whether to show a breadcrumb bar above each split with the path to the
+    /// symbol at the cursor
+    #[serde(default = "default_false")]
+    pub breadcrumbs: bool,
+
     /// Maximum time in milliseconds for syntax highlighting per frame
     #[serde(default = "default_highlight_timeout")]
     pub highlight_timeout_ms: u64,
@@ -456,6 +603,41 @@ pub struct EditorConfig {
     #[serde(default = "default_auto_save_interval")]
     pub auto_save_interval_secs: u32,
 
+    /// Whether to keep undo history across editor sessions
+    /// When enabled, the undo history of saved files is stored in the data
+    /// directory and restored when the file is reopened with unchanged content.
+    #[serde(default = "default_false")]
+    pub persistent_undo: bool,
+
+    /// Maximum age in days of persisted undo histories.
+    /// Histories not written for longer than this are removed.
+    #[serde(default = "default_persistent_undo_max_age_days")]
+    pub persistent_undo_max_age_days: u32,
+
+    /// Maximum total size in bytes of persisted undo histories.
+    /// The oldest histories are removed when the limit is exceeded.
+    /// Default: 50MB
+    #[serde(default = "default_persistent_undo_max_bytes")]
+    pub persistent_undo_max_bytes: u64,
+
+    /// Whether to keep a local history of saved files
+    /// Every save stores a compressed snapshot of the file in the data
+    /// directory, which the "File History" command can compare and restore.
+    /// Default: true
+    #[serde(default = "default_true")]
+    pub local_history: bool,
+
+    /// Maximum number of snapshots kept per file in the local history.
+    /// Default: 50
+    #[serde(default = "default_local_history_max_entries")]
+    pub local_history_max_entries: usize,
+
+    /// Maximum age in days of local history snapshots.
+    /// Older snapshots are removed when the file is saved again.
+    /// Default: 30
+    #[serde(default = "default_local_history_max_age_days")]
+    pub local_history_max_age_days: u32,
+
     /// Number of bytes to look back/forward from the viewport for syntax highlighting context.
     /// Larger values improve accuracy for multi-line constructs (strings, comments, nested blocks)
     /// but may slow down highlighting for very large files.
@@ -502,6 +684,32 @@ pub struct EditorConfig {
     #[serde(default)]
     pub default_line_ending: LineEndingOption,
 
+    /// How files are replaced on disk when saving.
+    /// Options: "atomic" (write a temporary file and rename it over the original),
+    /// "in_place" (overwrite the file, keeping its inode and hard links),
+    /// "copy" (write a temporary file, then copy it over the original)
+    /// Can be overridden per language.
+    /// Default: "atomic"
+    #[serde(default)]
+    pub save_strategy: SaveStrategyOption,
+
+    /// Whether to keep the previous version of a file when saving.
+    /// Options: "none", "adjacent" (`file~` next to the file),
+    /// "directory" (numbered versions in `backup_directory`)
+    /// Default: "none"
+    #[serde(default)]
+    pub backup: BackupOption,
+
+    /// Directory for "directory" backups.
+    /// Defaults to the `backups` directory in the editor's data directory.
+    #[serde(default)]
+    pub backup_directory: Option<std::path::PathBuf>,
+
+    /// Number of versions of each file kept by "directory" backups.
+    /// Default: 5
+    #[serde(default = "default_backup_count")]
+    pub backup_count: usize,
+
     /// Cursor style for the terminal cursor.
     /// Options: blinking_block, steady_block, blinking_bar, steady_bar, blinking_underline, steady_underline
     /// Default: blinking_block
@@ -555,6 +763,10 @@ fn default_scroll_offset() -> usize {
     3
 }
 
+fn default_sticky_scroll_max_lines() -> usize {
+    5
+}
+
 fn default_highlight_timeout() -> u64 {
     5
 }
@@ -571,6 +783,22 @@ fn default_auto_save_interval() -> u32 {
     2 // Auto-save every 2 seconds for fast recovery
 }
 
+fn default_persistent_undo_max_age_days() -> u32 {
+    30
+}
+
+fn default_persistent_undo_max_bytes() -> u64 {
+    50 * 1024 * 1024
+}
+
+fn default_local_history_max_entries() -> usize {
+    50
+}
+
+fn default_local_history_max_age_days() -> u32 {
+    30
+}
+
 fn default_highlight_context_bytes() -> usize {
     10_000 // 10KB context for accurate syntax highlighting
 }
@@ -591,6 +819,10 @@ fn default_file_tree_poll_interval() -> u64 {
     3000 // 3 seconds between directory mtime checks
 }
 
+fn default_backup_count() -> usize {
+    5
+}
+
 impl Default for EditorConfig {
     fn default() -> Self {
         Self {
@@ -601,6 +833,11 @@ impl Default for EditorConfig {
             scroll_offset: default_scroll_offset(),
             syntax_highlighting: true,
             line_wrap: true,
+            sticky_scroll: false,
+            sticky_scroll_max_lines: default_sticky_scroll_max_lines(),
+            indent_guides: false,
+            bracket_pair_colorization: false,
+            breadcrumbs: false,
             highlight_timeout_ms: default_highlight_timeout(),
             snapshot_interval: default_snapshot_interval(),
             large_file_threshold_bytes: default_large_file_threshold(),
@@ -608,6 +845,12 @@ impl Default for EditorConfig {
             enable_inlay_hints: true,
             recovery_enabled: true,
             auto_save_interval_secs: default_auto_save_interval(),
+            persistent_undo: false,
+            persistent_undo_max_age_days: default_persistent_undo_max_age_days(),
+            persistent_undo_max_bytes: default_persistent_undo_max_bytes(),
+            local_history: true,
+            local_history_max_entries: default_local_history_max_entries(),
+            local_history_max_age_days: default_local_history_max_age_days(),
             highlight_context_bytes: default_highlight_context_bytes(),
             mouse_hover_enabled: true,
             mouse_hover_delay_ms: default_mouse_hover_delay(),
@@ -615,6 +858,10 @@ impl Default for EditorConfig {
             auto_revert_poll_interval_ms: default_auto_revert_poll_interval(),
             file_tree_poll_interval_ms: default_file_tree_poll_interval(),
             default_line_ending: LineEndingOption::default(),
+            save_strategy: SaveStrategyOption::default(),
+            backup: BackupOption::default(),
+            backup_directory: None,
+            backup_count: default_backup_count(),
             cursor_style: CursorStyle::default(),
             quick_suggestions: true,
             show_menu_bar: true,
@@ -877,11 +1124,57 @@ pub struct LanguageConfig {
     #[serde(default)]
     pub format_on_save: bool,
 
+    /// What formats files of this language: the external formatter above,
+    /// the language server, or "auto" (the formatter if one is configured,
+    /// otherwise the language server)
+    #[serde(default)]
+    pub formatter_source: FormatterSource,
+
+    /// Whether the language server reformats code as you type its trigger
+    /// characters (textDocument/onTypeFormatting)
+    #[serde(default)]
+    pub format_on_type: bool,
+
     /// Actions to run when a file of this language is saved (linters, etc.)
     /// Actions are run in order; if any fails (non-zero exit), subsequent actions don't run
     /// Note: Use `formatter` + `format_on_save` for formatting, not on_save
     #[serde(default)]
     pub on_save: Vec<OnSaveAction>,
+
+    /// How files of this language are replaced on disk when saving.
+    /// If not specified, falls back to the global editor.save_strategy setting.
+    #[serde(default)]
+    pub save_strategy: Option<SaveStrategyOption>,
+
+    /// Remove trailing spaces and tabs from lines when saving
+    #[serde(default)]
+    pub trim_trailing_whitespace: bool,
+
+    /// Only trim trailing whitespace on lines changed since the last save
+    #[serde(default)]
+    pub trim_modified_lines_only: bool,
+
+    /// Remove blank lines at the end of the file when saving
+    #[serde(default)]
+    pub trim_trailing_blank_lines: bool,
+
+    /// Add or remove the newline at the end of the file when saving
+    #[serde(default)]
+    pub final_newline: FinalNewlineOption,
+
+    /// Convert mixed line endings to the file's line ending when saving
+    #[serde(default)]
+    pub normalize_line_endings: bool,
+
+    /// Whether to draw indent guides for this language.
+    /// If not specified, falls back to the global editor.indent_guides setting.
+    #[serde(default)]
+    pub indent_guides: Option<bool>,
+
+    /// Whether to color brackets by nesting depth for this language.
+    /// If not specified, falls back to the global editor.bracket_pair_colorization setting.
+    #[serde(default)]
+    pub bracket_pair_colorization: Option<bool>,
 }
 
 /// Resolved editor configuration for a specific buffer.
@@ -890,6 +1183,7 @@ pub struct LanguageConfig {
 /// language-specific overrides on top of the global editor config.
 ///
 /// Use `BufferConfig::resolve()` to create one from a Config and optional language ID.
+/// `.editorconfig` properties are merged in by the editor when it opens a file.
 #[derive(Debug, Clone)]
 pub struct BufferConfig {
     /// Number of spaces per tab character
@@ -918,6 +1212,39 @@ pub struct BufferConfig {
 
     /// Path to custom TextMate grammar (if any)
     pub textmate_grammar: Option<std::path::PathBuf>,
+
+    /// How the file is replaced on disk when saving
+    pub save_strategy: SaveStrategyOption,
+
+    /// Line ending for a new file
+    pub line_ending: LineEndingOption,
+
+    /// Character encoding for a new file (an encoding label such as "utf-8")
+    pub charset: Option<String>,
+
+    /// Whether to remove trailing whitespace when saving
+    pub trim_trailing_whitespace: bool,
+
+    /// Whether trimming only touches lines changed since the last save
+    pub trim_modified_lines_only: bool,
+
+    /// Whether to remove blank lines at the end of the file when saving
+    pub trim_trailing_blank_lines: bool,
+
+    /// What to do with the final newline when saving
+    pub final_newline: FinalNewlineOption,
+
+    /// Whether to convert mixed line endings when saving
+    pub normalize_line_endings: bool,
+
+    /// Columns at which to draw vertical rulers
+    pub rulers: Vec<usize>,
+
+    /// Whether to draw indent guides
+    pub indent_guides: bool,
+
+    /// Whether to color brackets by nesting depth
+    pub bracket_pair_colorization: bool,
 }
 
 impl BufferConfig {
@@ -943,6 +1270,17 @@ impl BufferConfig {
             on_save: Vec::new(),
             highlighter: HighlighterPreference::Auto,
             textmate_grammar: None,
+            save_strategy: editor.save_strategy,
+            line_ending: editor.default_line_ending.clone(),
+            charset: None,
+            trim_trailing_whitespace: false,
+            trim_modified_lines_only: false,
+            trim_trailing_blank_lines: false,
+            final_newline: FinalNewlineOption::Keep,
+            normalize_line_endings: false,
+            rulers: Vec::new(),
+            indent_guides: editor.indent_guides,
+            bracket_pair_colorization: editor.bracket_pair_colorization,
         };
 
         // Apply language-specific overrides if available
@@ -976,6 +1314,26 @@ impl BufferConfig {
 
                 // TextMate grammar path: from language config
                 config.textmate_grammar = lang_config.textmate_grammar.clone();
+
+                // Save strategy: use language setting if specified, else global
+                if let Some(strategy) = lang_config.save_strategy {
+                    config.save_strategy = strategy;
+                }
+
+                // On-save transforms: from language config
+                config.trim_trailing_whitespace = lang_config.trim_trailing_whitespace;
+                config.trim_modified_lines_only = lang_config.trim_modified_lines_only;
+                config.trim_trailing_blank_lines = lang_config.trim_trailing_blank_lines;
+                config.final_newline = lang_config.final_newline;
+                config.normalize_line_endings = lang_config.normalize_line_endings;
+
+                // Indent guides and bracket colors: use language setting if specified, else global
+                if let Some(indent_guides) = lang_config.indent_guides {
+                    config.indent_guides = indent_guides;
+                }
+                if let Some(colorize) = lang_config.bracket_pair_colorization {
+                    config.bracket_pair_colorization = colorize;
+                }
             }
         }
 
@@ -1328,6 +1686,20 @@ impl MenuConfig {
                         when: Some(context_keys::FORMATTER_AVAILABLE.to_string()),
                         checkbox: None,
                     },
+                    MenuItem::Action {
+                        label: t!("menu.edit.format_selection").to_string(),
+                        action: "format_selection".to_string(),
+                        args: HashMap::new(),
+                        when: Some(context_keys::HAS_SELECTION.to_string()),
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.edit.insert_snippet").to_string(),
+                        action: "insert_snippet".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
                 ],
             },
             // View menu
@@ -1342,6 +1714,13 @@ impl MenuConfig {
                         when: None,
                         checkbox: Some(context_keys::FILE_EXPLORER.to_string()),
                     },
+                    MenuItem::Action {
+                        label: t!("menu.view.outline").to_string(),
+                        action: "toggle_outline".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: Some(context_keys::OUTLINE.to_string()),
+                    },
                     MenuItem::Separator { separator: true },
                     MenuItem::Action {
                         label: t!("menu.view.line_numbers").to_string(),
@@ -1357,6 +1736,20 @@ impl MenuConfig {
                         when: None,
                         checkbox: Some(context_keys::LINE_WRAP.to_string()),
                     },
+                    MenuItem::Action {
+                        label: t!("menu.view.sticky_scroll").to_string(),
+                        action: "toggle_sticky_scroll".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: Some(context_keys::STICKY_SCROLL.to_string()),
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.view.breadcrumbs").to_string(),
+                        action: "toggle_breadcrumbs".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: Some(context_keys::BREADCRUMBS.to_string()),
+                    },
                     MenuItem::Action {
                         label: t!("menu.view.mouse_support").to_string(),
                         action: "toggle_mouse_capture".to_string(),
@@ -1560,6 +1953,13 @@ impl MenuConfig {
                         when: None,
                         checkbox: None,
                     },
+                    MenuItem::Action {
+                        label: t!("menu.selection.shrink_selection").to_string(),
+                        action: "shrink_selection".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
                     MenuItem::Separator { separator: true },
                     MenuItem::Action {
                         label: t!("menu.selection.add_cursor_above").to_string(),
@@ -1610,6 +2010,27 @@ impl MenuConfig {
                         when: None,
                         checkbox: None,
                     },
+                    MenuItem::Action {
+                        label: t!("menu.go.goto_type_definition").to_string(),
+                        action: "lsp_goto_type_definition".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.go.goto_implementation").to_string(),
+                        action: "lsp_goto_implementation".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.go.goto_declaration").to_string(),
+                        action: "lsp_goto_declaration".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
                     MenuItem::Action {
                         label: t!("menu.go.find_references").to_string(),
                         action: "lsp_references".to_string(),
@@ -1617,6 +2038,27 @@ impl MenuConfig {
                         when: None,
                         checkbox: None,
                     },
+                    MenuItem::Action {
+                        label: t!("menu.go.workspace_symbol").to_string(),
+                        action: "lsp_workspace_symbol".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.go.call_hierarchy").to_string(),
+                        action: "call_hierarchy".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.go.type_hierarchy").to_string(),
+                        action: "type_hierarchy".to_string(),
+                        args: HashMap::new(),
+                        when: None,
+                        checkbox: None,
+                    },
                     MenuItem::Separator { separator: true },
                     MenuItem::Action {
                         label: t!("menu.go.next_buffer").to_string(),
@@ -1661,6 +2103,27 @@ impl MenuConfig {
                         when: Some(context_keys::LSP_AVAILABLE.to_string()),
                         checkbox: None,
                     },
+                    MenuItem::Action {
+                        label: t!("menu.lsp.goto_type_definition").to_string(),
+                        action: "lsp_goto_type_definition".to_string(),
+                        args: HashMap::new(),
+                        when: Some(context_keys::LSP_AVAILABLE.to_string()),
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.lsp.goto_implementation").to_string(),
+                        action: "lsp_goto_implementation".to_string(),
+                        args: HashMap::new(),
+                        when: Some(context_keys::LSP_AVAILABLE.to_string()),
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.lsp.goto_declaration").to_string(),
+                        action: "lsp_goto_declaration".to_string(),
+                        args: HashMap::new(),
+                        when: Some(context_keys::LSP_AVAILABLE.to_string()),
+                        checkbox: None,
+                    },
                     MenuItem::Action {
                         label: t!("menu.lsp.find_references").to_string(),
                         action: "lsp_references".to_string(),
@@ -1668,6 +2131,27 @@ impl MenuConfig {
                         when: Some(context_keys::LSP_AVAILABLE.to_string()),
                         checkbox: None,
                     },
+                    MenuItem::Action {
+                        label: t!("menu.lsp.code_lens").to_string(),
+                        action: "lsp_code_lens".to_string(),
+                        args: HashMap::new(),
+                        when: Some(context_keys::LSP_AVAILABLE.to_string()),
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.lsp.call_hierarchy").to_string(),
+                        action: "call_hierarchy".to_string(),
+                        args: HashMap::new(),
+                        when: Some(context_keys::LSP_AVAILABLE.to_string()),
+                        checkbox: None,
+                    },
+                    MenuItem::Action {
+                        label: t!("menu.lsp.type_hierarchy").to_string(),
+                        action: "type_hierarchy".to_string(),
+                        args: HashMap::new(),
+                        when: Some(context_keys::LSP_AVAILABLE.to_string()),
+                        checkbox: None,
+                    },
                     MenuItem::Action {
                         label: t!("menu.lsp.rename_symbol").to_string(),
                         action: "lsp_rename".to_string(),
@@ -1938,7 +2422,17 @@ impl Config {
                     timeout_ms: 10000,
                 }),
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -1962,7 +2456,17 @@ impl Config {
                     timeout_ms: 10000,
                 }),
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -1986,7 +2490,17 @@ impl Config {
                     timeout_ms: 10000,
                 }),
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2014,7 +2528,17 @@ impl Config {
                     timeout_ms: 10000,
                 }),
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2038,7 +2562,17 @@ impl Config {
                     timeout_ms: 10000,
                 }),
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2069,7 +2603,17 @@ impl Config {
                     timeout_ms: 10000,
                 }),
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2088,7 +2632,17 @@ impl Config {
                 tab_size: None,
                 formatter: None,
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2122,7 +2676,17 @@ impl Config {
                 tab_size: None,
                 formatter: None,
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2145,7 +2709,17 @@ impl Config {
                 tab_size: Some(8), // Makefiles traditionally use 8-space tabs
                 formatter: None,
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2164,7 +2738,17 @@ impl Config {
                 tab_size: None,
                 formatter: None,
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2188,7 +2772,17 @@ impl Config {
                     timeout_ms: 10000,
                 }),
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2207,7 +2801,17 @@ impl Config {
                 tab_size: None,
                 formatter: None,
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2231,7 +2835,17 @@ impl Config {
                     timeout_ms: 10000,
                 }),
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2250,7 +2864,17 @@ impl Config {
                 tab_size: None,
                 formatter: None,
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2275,7 +2899,17 @@ impl Config {
                     timeout_ms: 10000,
                 }),
                 format_on_save: false,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
@@ -2757,7 +3391,17 @@ mod tests {
                     timeout_ms: 10000,
                 }),
                 format_on_save: true,
+                formatter_source: FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
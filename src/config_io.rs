@@ -121,6 +121,8 @@ pub enum ConfigLayer {
     System,
     /// User-global settings (~/.config/fresh/config.json)
     User,
+    /// `.editorconfig` files, which apply per file (read-only)
+    EditorConfig,
     /// Project-local settings ($PROJECT_ROOT/.fresh/config.json)
     Project,
     /// Runtime/volatile session state (highest precedence)
@@ -133,8 +135,9 @@ impl ConfigLayer {
         match self {
             Self::System => 0,
             Self::User => 1,
-            Self::Project => 2,
-            Self::Session => 3,
+            Self::EditorConfig => 2,
+            Self::Project => 3,
+            Self::Session => 4,
         }
     }
 }
@@ -287,10 +290,11 @@ impl ConfigResolver {
 
     /// Save a config to a specific layer, writing only the delta from parent layers.
     pub fn save_to_layer(&self, config: &Config, layer: ConfigLayer) -> Result<(), ConfigError> {
-        if layer == ConfigLayer::System {
-            return Err(ConfigError::ValidationError(
-                "Cannot write to System layer".to_string(),
-            ));
+        if matches!(layer, ConfigLayer::System | ConfigLayer::EditorConfig) {
+            return Err(ConfigError::ValidationError(format!(
+                "Cannot write to {:?} layer",
+                layer
+            )));
         }
 
         // Calculate parent config (merge all layers below target)
@@ -312,7 +316,7 @@ impl ConfigResolver {
             ConfigLayer::User => self.user_config_path(),
             ConfigLayer::Project => self.project_config_write_path(),
             ConfigLayer::Session => self.session_config_path(),
-            ConfigLayer::System => unreachable!(),
+            ConfigLayer::System | ConfigLayer::EditorConfig => unreachable!(),
         };
 
         // Ensure parent directory exists
@@ -720,6 +724,21 @@ impl DirectoryContext {
         self.data_dir.join("recovery")
     }
 
+    /// Get the persisted undo history directory path
+    pub fn undo_dir(&self) -> std::path::PathBuf {
+        self.data_dir.join("undo")
+    }
+
+    /// Get the local history directory path
+    pub fn local_history_dir(&self) -> std::path::PathBuf {
+        self.data_dir.join("history")
+    }
+
+    /// Get the default directory for numbered backups of saved files
+    pub fn backups_dir(&self) -> std::path::PathBuf {
+        self.data_dir.join("backups")
+    }
+
     /// Get the sessions directory path
     pub fn sessions_dir(&self) -> std::path::PathBuf {
         self.data_dir.join("sessions")
@@ -775,6 +794,11 @@ impl DirectoryContext {
         self.config_dir.join("grammars")
     }
 
+    /// Get the user snippets directory path
+    pub fn snippets_dir(&self) -> std::path::PathBuf {
+        self.config_dir.join("snippets")
+    }
+
     /// Get the plugins directory path
     pub fn plugins_dir(&self) -> std::path::PathBuf {
         self.config_dir.join("plugins")
@@ -870,7 +894,8 @@ mod tests {
     #[test]
     fn layer_precedence_ordering() {
         assert!(ConfigLayer::Session.precedence() > ConfigLayer::Project.precedence());
-        assert!(ConfigLayer::Project.precedence() > ConfigLayer::User.precedence());
+        assert!(ConfigLayer::Project.precedence() > ConfigLayer::EditorConfig.precedence());
+        assert!(ConfigLayer::EditorConfig.precedence() > ConfigLayer::User.precedence());
         assert!(ConfigLayer::User.precedence() > ConfigLayer::System.precedence());
     }
 
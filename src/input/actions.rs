@@ -5,10 +5,12 @@ use crate::model::buffer::{Buffer, LineEnding};
 use crate::model::cursor::{Position2D, SelectionMode};
 use crate::model::event::{CursorId, Event};
 use crate::primitives::display_width::{byte_offset_at_visual_column, str_width};
+use crate::primitives::syntax_selection;
 use crate::primitives::word_navigation::{
     find_word_end, find_word_start, find_word_start_left, find_word_start_right,
 };
 use crate::state::EditorState;
+use crate::view::folding::FoldedRegion;
 use std::ops::Range;
 
 /// Direction for block selection movement
@@ -68,6 +70,32 @@ fn calculate_visual_column(
 /// Pattern for matching line ending characters (\r and \n)
 const LINE_ENDING_CHARS: &[char] = &['\r', '\n'];
 
+/// Resolve a line reached by vertical movement to the nearest visible line
+///
+/// Lines hidden by a collapsed fold are skipped: moving up lands on the fold
+/// header, moving down on the first line after the fold.
+fn visible_line(
+    buffer: &mut Buffer,
+    folded: &[FoldedRegion],
+    line: (usize, String),
+    down: bool,
+    estimated_line_length: usize,
+) -> Option<(usize, String)> {
+    let Some(region) = folded.iter().find(|r| r.hidden.contains(&line.0)) else {
+        return Some(line);
+    };
+    let target = if down {
+        region.hidden.end
+    } else {
+        region.header_start
+    };
+    // A fold reaching the end of the buffer has no line after it
+    if down && buffer.get_line_number(target) == region.end_line {
+        return None;
+    }
+    buffer.line_iterator(target, estimated_line_length).next()
+}
+
 /// Get the length of line content excluding line ending characters (\r and \n).
 /// Handles CRLF, LF, and CR line endings.
 fn content_len_without_line_ending(content: &str) -> usize {
@@ -667,6 +695,38 @@ fn max_cursor_position(buffer: &Buffer) -> usize {
     buffer.len()
 }
 
+/// Grow a selection to the end of the next word
+///
+/// Returns the new (anchor, position). Used to expand selections in buffers
+/// without a syntax tree.
+fn word_expansion(
+    buffer: &Buffer,
+    position: usize,
+    anchor: Option<usize>,
+) -> (Option<usize>, usize) {
+    if let Some(anchor) = anchor {
+        // Already have a selection - expand by one word to the right
+        // First move to the start of the next word, then to its end
+        let next_word_start = find_word_start_right(buffer, position);
+        return (Some(anchor), find_word_end(buffer, next_word_start));
+    }
+
+    // No selection - select from cursor to end of current word
+    let word_start = find_word_start(buffer, position);
+    let word_end = find_word_end(buffer, position);
+
+    // If cursor is on non-word char OR at the end of a word,
+    // select from current position to end of next word
+    if word_start == word_end || position == word_end {
+        // Find the next word (skip non-word characters to find it)
+        let next_start = find_word_start_right(buffer, position);
+        (Some(position), find_word_end(buffer, next_start))
+    } else {
+        // On a word char - select from cursor to end of current word
+        (Some(position), word_end)
+    }
+}
+
 /// Convert an action into a sequence of events that can be applied to the editor state
 ///
 /// # Parameters
@@ -1045,6 +1105,7 @@ pub fn action_to_events(
         }
 
         Action::MoveUp => {
+            let folded = state.folds.folded_regions(&state.buffer);
             for (cursor_id, cursor) in state.cursors.iter() {
                 // Calculate visual column first (iterator is dropped after this call)
                 let (current_visual_column, _) = calculate_visual_column(
@@ -1065,7 +1126,15 @@ pub fn action_to_events(
                     .buffer
                     .line_iterator(cursor.position, estimated_line_length);
 
-                if let Some((prev_line_start, prev_line_content)) = iter.prev() {
+                if let Some((prev_line_start, prev_line_content)) = iter.prev().and_then(|line| {
+                    visible_line(
+                        &mut state.buffer,
+                        &folded,
+                        line,
+                        false,
+                        estimated_line_length,
+                    )
+                }) {
                     // Calculate byte offset from visual column, ensuring valid character boundary
                     let prev_line_text = prev_line_content.trim_end_matches('\n');
                     let byte_offset =
@@ -1092,6 +1161,7 @@ pub fn action_to_events(
         }
 
         Action::MoveDown => {
+            let folded = state.folds.folded_regions(&state.buffer);
             for (cursor_id, cursor) in state.cursors.iter() {
                 // Calculate visual column first (iterator is dropped after this call)
                 let (current_visual_column, _) = calculate_visual_column(
@@ -1115,7 +1185,15 @@ pub fn action_to_events(
                 // Consume current line
                 iter.next();
 
-                if let Some((next_line_start, next_line_content)) = iter.next() {
+                if let Some((next_line_start, next_line_content)) = iter.next().and_then(|line| {
+                    visible_line(
+                        &mut state.buffer,
+                        &folded,
+                        line,
+                        true,
+                        estimated_line_length,
+                    )
+                }) {
                     // Calculate byte offset from visual column, ensuring valid character boundary
                     let next_line_text = next_line_content.trim_end_matches('\n');
                     let byte_offset =
@@ -1413,6 +1491,7 @@ pub fn action_to_events(
         }
 
         Action::SelectUp => {
+            let folded = state.folds.folded_regions(&state.buffer);
             for (cursor_id, cursor) in state.cursors.iter() {
                 let mut iter = state
                     .buffer
@@ -1428,7 +1507,15 @@ pub fn action_to_events(
                     current_column
                 };
 
-                if let Some((prev_line_start, prev_line_content)) = iter.prev() {
+                if let Some((prev_line_start, prev_line_content)) = iter.prev().and_then(|line| {
+                    visible_line(
+                        &mut state.buffer,
+                        &folded,
+                        line,
+                        false,
+                        estimated_line_length,
+                    )
+                }) {
                     let prev_line_len = prev_line_content.trim_end_matches('\n').len();
                     let new_pos = prev_line_start + goal_column.min(prev_line_len);
 
@@ -1446,6 +1533,7 @@ pub fn action_to_events(
         }
 
         Action::SelectDown => {
+            let folded = state.folds.folded_regions(&state.buffer);
             for (cursor_id, cursor) in state.cursors.iter() {
                 let mut iter = state
                     .buffer
@@ -1463,7 +1551,15 @@ pub fn action_to_events(
 
                 // Skip current line, then get next line
                 iter.next();
-                if let Some((next_line_start, next_line_content)) = iter.next() {
+                if let Some((next_line_start, next_line_content)) = iter.next().and_then(|line| {
+                    visible_line(
+                        &mut state.buffer,
+                        &folded,
+                        line,
+                        true,
+                        estimated_line_length,
+                    )
+                }) {
                     let next_line_len = next_line_content.trim_end_matches('\n').len();
                     let new_pos = next_line_start + goal_column.min(next_line_len);
 
@@ -2121,6 +2217,9 @@ pub fn action_to_events(
         | Action::CommandPalette
         | Action::ShowHelp
         | Action::ToggleLineWrap
+        | Action::ToggleStickyScroll
+        | Action::ToggleHexView
+        | Action::HexSearch
         | Action::ToggleComposeMode
         | Action::SetComposeWidth
         | Action::IncreaseSplitSize
@@ -2128,6 +2227,13 @@ pub fn action_to_events(
         | Action::ToggleMaximizeSplit
         | Action::Undo
         | Action::Redo
+        | Action::UndoChronological
+        | Action::RedoChronological
+        | Action::ShowUndoTree
+        | Action::UndoTreeRestore
+        | Action::FileHistory
+        | Action::FileHistoryRestore
+        | Action::FileHistoryRestoreHunk
         | Action::GoToMatchingBracket
         | Action::JumpToNextError
         | Action::JumpToPreviousError
@@ -2212,9 +2318,24 @@ pub fn action_to_events(
         | Action::FileExplorerRename
         | Action::FileExplorerToggleHidden
         | Action::FileExplorerToggleGitignored
+        | Action::ToggleOutline
+        | Action::FocusOutline
+        | Action::ToggleBreadcrumbs
+        | Action::PickSiblingSymbol
+        | Action::InsertSnippet
         | Action::LspCompletion
         | Action::LspGotoDefinition
+        | Action::LspGotoTypeDefinition
+        | Action::LspGotoImplementation
+        | Action::LspGotoDeclaration
         | Action::LspReferences
+        | Action::LspCodeLens
+        | Action::LspWorkspaceSymbol
+        | Action::ShowCallHierarchy
+        | Action::ShowTypeHierarchy
+        | Action::HierarchyOpen
+        | Action::HierarchyToggleNode
+        | Action::HierarchyToggleDirection
         | Action::LspRename
         | Action::LspHover
         | Action::LspSignatureHelp
@@ -2234,6 +2355,13 @@ pub fn action_to_events(
         | Action::FindSelectionPrevious
         | Action::Replace
         | Action::QueryReplace
+        | Action::ProjectSearch
+        | Action::ProjectReplace
+        | Action::ProjectSearchOpen
+        | Action::ProjectSearchToggle
+        | Action::ProjectSearchFilter
+        | Action::ProjectSearchApply
+        | Action::UndoProjectReplace
         | Action::MenuActivate
         | Action::MenuClose
         | Action::MenuLeft
@@ -2253,7 +2381,9 @@ pub fn action_to_events(
         | Action::SelectLocale
         | Action::Revert
         | Action::ToggleAutoRevert
+        | Action::ToggleFollow
         | Action::FormatBuffer
+        | Action::FormatSelection
         | Action::OpenTerminal
         | Action::CloseTerminal
         | Action::FocusTerminal
@@ -2271,7 +2401,16 @@ pub fn action_to_events(
         | Action::SettingsIncrement
         | Action::SettingsDecrement
         | Action::SetTabSize
+        | Action::Fold
+        | Action::Unfold
+        | Action::ToggleFold
+        | Action::FoldAll
+        | Action::UnfoldAll
+        | Action::FoldLevel
         | Action::SetLineEnding
+        | Action::ReopenWithEncoding
+        | Action::SaveWithEncoding
+        | Action::StopLineIndexing
         | Action::ToggleIndentationStyle
         | Action::ToggleTabIndicators
         | Action::ToggleDebugHighlights
@@ -2322,51 +2461,72 @@ pub fn action_to_events(
         }
 
         Action::ExpandSelection => {
-            // Expand selection for each cursor
+            // Walk up the language server's selection ranges or the syntax
+            // tree; buffers without either grow word by word
+            let version = state.buffer.version();
+            let tree = state
+                .syntax_tree
+                .tree(&state.buffer, state.highlighter.language())
+                .map(|(_, tree)| tree);
             for (cursor_id, cursor) in state.cursors.iter() {
-                if let Some(anchor) = cursor.anchor {
-                    // Already have a selection - expand by one word to the right
-                    // First move to the start of the next word, then to its end
-                    let next_word_start = find_word_start_right(&state.buffer, cursor.position);
-                    let new_end = find_word_end(&state.buffer, next_word_start);
-                    events.push(Event::MoveCursor {
-                        cursor_id,
-                        old_position: cursor.position,
-                        new_position: new_end,
-                        old_anchor: cursor.anchor,
-                        new_anchor: Some(anchor),
-                        old_sticky_column: cursor.sticky_column,
-                        new_sticky_column: 0, // Reset sticky column
+                let selection = cursor
+                    .selection_range()
+                    .unwrap_or(cursor.position..cursor.position);
+                let lsp_range = state
+                    .selection_expansion
+                    .lsp_chain(version, selection.clone())
+                    .and_then(|chain| {
+                        syntax_selection::smallest_enclosing(&chain.ranges, selection.clone())
                     });
-                } else {
-                    // No selection - select from cursor to end of current word
-                    let word_start = find_word_start(&state.buffer, cursor.position);
-                    let word_end = find_word_end(&state.buffer, cursor.position);
-
-                    // If cursor is on non-word char OR at the end of a word,
-                    // select from current position to end of next word
-                    let (final_start, final_end) =
-                        if word_start == word_end || cursor.position == word_end {
-                            // Find the next word (skip non-word characters to find it)
-                            let next_start = find_word_start_right(&state.buffer, cursor.position);
-                            let next_end = find_word_end(&state.buffer, next_start);
-                            // Select FROM cursor position TO the end of next word
-                            (cursor.position, next_end)
-                        } else {
-                            // On a word char - select from cursor to end of current word
-                            (cursor.position, word_end)
-                        };
+                let (new_anchor, new_position) = match lsp_range.or_else(|| {
+                    tree.as_ref()
+                        .and_then(|tree| syntax_selection::enclosing_range(tree, selection))
+                }) {
+                    Some(range) => (Some(range.start), range.end),
+                    None if tree.is_some() => continue,
+                    None => word_expansion(&state.buffer, cursor.position, cursor.anchor),
+                };
+                state.selection_expansion.record(
+                    version,
+                    cursor_id,
+                    (cursor.anchor, cursor.position),
+                    (new_anchor, new_position),
+                );
+                events.push(Event::MoveCursor {
+                    cursor_id,
+                    old_position: cursor.position,
+                    new_position,
+                    old_anchor: cursor.anchor,
+                    new_anchor,
+                    old_sticky_column: cursor.sticky_column,
+                    new_sticky_column: 0, // Reset sticky column
+                });
+            }
+        }
 
-                    events.push(Event::MoveCursor {
-                        cursor_id,
-                        old_position: cursor.position,
-                        new_position: final_end,
-                        old_anchor: cursor.anchor,
-                        new_anchor: Some(final_start),
-                        old_sticky_column: cursor.sticky_column,
-                        new_sticky_column: 0, // Reset sticky column
+        Action::ShrinkSelection => {
+            // Retrace the expansions of each cursor; with none left, select
+            // the word to the left like Select Word Left, whose key it shares
+            let version = state.buffer.version();
+            for (cursor_id, cursor) in state.cursors.iter() {
+                let (new_anchor, new_position) = state
+                    .selection_expansion
+                    .shrink(version, cursor_id, (cursor.anchor, cursor.position))
+                    .unwrap_or_else(|| {
+                        (
+                            Some(cursor.anchor.unwrap_or(cursor.position)),
+                            find_word_start_left(&state.buffer, cursor.position),
+                        )
                     });
-                }
+                events.push(Event::MoveCursor {
+                    cursor_id,
+                    old_position: cursor.position,
+                    new_position,
+                    old_anchor: cursor.anchor,
+                    new_anchor,
+                    old_sticky_column: cursor.sticky_column,
+                    new_sticky_column: 0, // Reset sticky column
+                });
             }
         }
     }
@@ -106,6 +106,80 @@ impl ModeRegistry {
 
         registry.register(special_mode);
 
+        // Built-in "undo-tree" mode for the undo tree panel
+        let undo_tree_mode = BufferMode::new("undo-tree")
+            .with_parent("special")
+            .with_read_only(true)
+            .with_binding(KeyCode::Enter, KeyModifiers::NONE, "undo_tree_restore")
+            .with_binding(KeyCode::Char('j'), KeyModifiers::NONE, "move_down")
+            .with_binding(KeyCode::Char('k'), KeyModifiers::NONE, "move_up");
+
+        registry.register(undo_tree_mode);
+
+        // Built-in "hierarchy" mode for the call and type hierarchy panel
+        let hierarchy_mode = BufferMode::new("hierarchy")
+            .with_parent("special")
+            .with_read_only(true)
+            .with_binding(KeyCode::Enter, KeyModifiers::NONE, "hierarchy_open")
+            .with_binding(KeyCode::Tab, KeyModifiers::NONE, "hierarchy_toggle_node")
+            .with_binding(
+                KeyCode::Char(' '),
+                KeyModifiers::NONE,
+                "hierarchy_toggle_node",
+            )
+            .with_binding(
+                KeyCode::Char('t'),
+                KeyModifiers::NONE,
+                "hierarchy_toggle_direction",
+            )
+            .with_binding(KeyCode::Char('j'), KeyModifiers::NONE, "move_down")
+            .with_binding(KeyCode::Char('k'), KeyModifiers::NONE, "move_up");
+
+        registry.register(hierarchy_mode);
+
+        // Built-in "file-history" mode for comparing a file with a saved snapshot
+        let file_history_mode = BufferMode::new("file-history")
+            .with_parent("special")
+            .with_read_only(true)
+            .with_binding(
+                KeyCode::Char('r'),
+                KeyModifiers::NONE,
+                "file_history_restore",
+            )
+            .with_binding(
+                KeyCode::Char('h'),
+                KeyModifiers::NONE,
+                "file_history_restore_hunk",
+            );
+
+        registry.register(file_history_mode);
+
+        // Built-in "project-search" mode for the project search results panel
+        let project_search_mode = BufferMode::new("project-search")
+            .with_parent("special")
+            .with_read_only(true)
+            .with_binding(KeyCode::Enter, KeyModifiers::NONE, "project_search_open")
+            .with_binding(
+                KeyCode::Char(' '),
+                KeyModifiers::NONE,
+                "project_search_toggle",
+            )
+            .with_binding(KeyCode::Char('r'), KeyModifiers::NONE, "project_replace")
+            .with_binding(
+                KeyCode::Char('a'),
+                KeyModifiers::NONE,
+                "project_search_apply",
+            )
+            .with_binding(
+                KeyCode::Char('f'),
+                KeyModifiers::NONE,
+                "project_search_filter",
+            )
+            .with_binding(KeyCode::Char('j'), KeyModifiers::NONE, "move_down")
+            .with_binding(KeyCode::Char('k'), KeyModifiers::NONE, "move_up");
+
+        registry.register(project_search_mode);
+
         registry
     }
 
@@ -229,6 +229,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.toggle_follow").to_string(),
+            description: t!("cmd.toggle_follow_desc").to_string(),
+            action: Action::ToggleFollow,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.format_buffer").to_string(),
             description: t!("cmd.format_buffer_desc").to_string(),
@@ -237,6 +245,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.format_selection").to_string(),
+            description: t!("cmd.format_selection_desc").to_string(),
+            action: Action::FormatSelection,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.quit").to_string(),
             description: t!("cmd.quit_desc").to_string(),
@@ -262,6 +278,38 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.undo_chronological").to_string(),
+            description: t!("cmd.undo_chronological_desc").to_string(),
+            action: Action::UndoChronological,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.redo_chronological").to_string(),
+            description: t!("cmd.redo_chronological_desc").to_string(),
+            action: Action::RedoChronological,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.show_undo_tree").to_string(),
+            description: t!("cmd.show_undo_tree_desc").to_string(),
+            action: Action::ShowUndoTree,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.file_history").to_string(),
+            description: t!("cmd.file_history_desc").to_string(),
+            action: Action::FileHistory,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.copy").to_string(),
             description: t!("cmd.copy_desc").to_string(),
@@ -407,6 +455,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.shrink_selection").to_string(),
+            description: t!("cmd.shrink_selection_desc").to_string(),
+            action: Action::ShrinkSelection,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // Multi-cursor
         Command {
             name: t!("cmd.add_cursor_above").to_string(),
@@ -572,6 +628,30 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.reopen_with_encoding").to_string(),
+            description: t!("cmd.reopen_with_encoding_desc").to_string(),
+            action: Action::ReopenWithEncoding,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.save_with_encoding").to_string(),
+            description: t!("cmd.save_with_encoding_desc").to_string(),
+            action: Action::SaveWithEncoding,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.stop_line_indexing").to_string(),
+            description: t!("cmd.stop_line_indexing_desc").to_string(),
+            action: Action::StopLineIndexing,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.toggle_indentation").to_string(),
             description: t!("cmd.toggle_indentation_desc").to_string(),
@@ -612,6 +692,54 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.fold").to_string(),
+            description: t!("cmd.fold_desc").to_string(),
+            action: Action::Fold,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.unfold").to_string(),
+            description: t!("cmd.unfold_desc").to_string(),
+            action: Action::Unfold,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.toggle_fold").to_string(),
+            description: t!("cmd.toggle_fold_desc").to_string(),
+            action: Action::ToggleFold,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.fold_all").to_string(),
+            description: t!("cmd.fold_all_desc").to_string(),
+            action: Action::FoldAll,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.unfold_all").to_string(),
+            description: t!("cmd.unfold_all_desc").to_string(),
+            action: Action::UnfoldAll,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.fold_level").to_string(),
+            description: t!("cmd.fold_level_desc").to_string(),
+            action: Action::FoldLevel,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.scroll_tabs_left").to_string(),
             description: t!("cmd.scroll_tabs_left_desc").to_string(),
@@ -685,7 +813,7 @@ pub fn get_all_commands() -> Vec<Command> {
             name: t!("cmd.focus_editor").to_string(),
             description: t!("cmd.focus_editor_desc").to_string(),
             action: Action::FocusEditor,
-            contexts: vec![KeyContext::FileExplorer],
+            contexts: vec![KeyContext::FileExplorer, KeyContext::Outline],
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
@@ -745,6 +873,43 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        // Outline and breadcrumbs
+        Command {
+            name: t!("cmd.toggle_outline").to_string(),
+            description: t!("cmd.toggle_outline_desc").to_string(),
+            action: Action::ToggleOutline,
+            contexts: vec![
+                KeyContext::Normal,
+                KeyContext::FileExplorer,
+                KeyContext::Outline,
+            ],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.focus_outline").to_string(),
+            description: t!("cmd.focus_outline_desc").to_string(),
+            action: Action::FocusOutline,
+            contexts: vec![KeyContext::Normal, KeyContext::FileExplorer],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.toggle_breadcrumbs").to_string(),
+            description: t!("cmd.toggle_breadcrumbs_desc").to_string(),
+            action: Action::ToggleBreadcrumbs,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.pick_sibling_symbol").to_string(),
+            description: t!("cmd.pick_sibling_symbol_desc").to_string(),
+            action: Action::PickSiblingSymbol,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // View
         Command {
             name: t!("cmd.toggle_line_wrap").to_string(),
@@ -754,6 +919,30 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.toggle_sticky_scroll").to_string(),
+            description: t!("cmd.toggle_sticky_scroll_desc").to_string(),
+            action: Action::ToggleStickyScroll,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.toggle_hex_view").to_string(),
+            description: t!("cmd.toggle_hex_view_desc").to_string(),
+            action: Action::ToggleHexView,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.hex_search").to_string(),
+            description: t!("cmd.hex_search_desc").to_string(),
+            action: Action::HexSearch,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // Note: Compose mode commands removed - markdown_compose plugin provides these
         Command {
             name: t!("cmd.set_background").to_string(),
@@ -838,6 +1027,30 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.project_search").to_string(),
+            description: t!("cmd.project_search_desc").to_string(),
+            action: Action::ProjectSearch,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.project_replace").to_string(),
+            description: t!("cmd.project_replace_desc").to_string(),
+            action: Action::ProjectReplace,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.undo_project_replace").to_string(),
+            description: t!("cmd.undo_project_replace_desc").to_string(),
+            action: Action::UndoProjectReplace,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         // Navigation
         Command {
             name: t!("cmd.goto_line").to_string(),
@@ -855,6 +1068,14 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.insert_snippet").to_string(),
+            description: t!("cmd.insert_snippet_desc").to_string(),
+            action: Action::InsertSnippet,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.show_completions").to_string(),
             description: t!("cmd.show_completions_desc").to_string(),
@@ -871,6 +1092,38 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.goto_type_definition").to_string(),
+            description: t!("cmd.goto_type_definition_desc").to_string(),
+            action: Action::LspGotoTypeDefinition,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.goto_implementation").to_string(),
+            description: t!("cmd.goto_implementation_desc").to_string(),
+            action: Action::LspGotoImplementation,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.goto_declaration").to_string(),
+            description: t!("cmd.goto_declaration_desc").to_string(),
+            action: Action::LspGotoDeclaration,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.code_lens").to_string(),
+            description: t!("cmd.code_lens_desc").to_string(),
+            action: Action::LspCodeLens,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.show_hover_info").to_string(),
             description: t!("cmd.show_hover_info_desc").to_string(),
@@ -887,6 +1140,30 @@ pub fn get_all_commands() -> Vec<Command> {
             custom_contexts: vec![],
             source: CommandSource::Builtin,
         },
+        Command {
+            name: t!("cmd.workspace_symbol").to_string(),
+            description: t!("cmd.workspace_symbol_desc").to_string(),
+            action: Action::LspWorkspaceSymbol,
+            contexts: vec![KeyContext::Normal, KeyContext::FileExplorer],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.call_hierarchy").to_string(),
+            description: t!("cmd.call_hierarchy_desc").to_string(),
+            action: Action::ShowCallHierarchy,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
+        Command {
+            name: t!("cmd.type_hierarchy").to_string(),
+            description: t!("cmd.type_hierarchy_desc").to_string(),
+            action: Action::ShowTypeHierarchy,
+            contexts: vec![KeyContext::Normal],
+            custom_contexts: vec![],
+            source: CommandSource::Builtin,
+        },
         Command {
             name: t!("cmd.show_signature_help").to_string(),
             description: t!("cmd.show_signature_help_desc").to_string(),
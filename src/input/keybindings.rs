@@ -160,6 +160,8 @@ pub enum KeyContext {
     Popup,
     /// File explorer has focus
     FileExplorer,
+    /// Outline panel has focus
+    Outline,
     /// Menu bar is active
     Menu,
     /// Terminal has focus
@@ -181,6 +183,7 @@ impl KeyContext {
             "prompt" => Self::Prompt,
             "popup" => Self::Popup,
             "fileExplorer" | "file_explorer" => Self::FileExplorer,
+            "outline" => Self::Outline,
             "normal" => Self::Normal,
             "menu" => Self::Menu,
             "terminal" => Self::Terminal,
@@ -197,6 +200,7 @@ impl KeyContext {
             Self::Prompt => "prompt",
             Self::Popup => "popup",
             Self::FileExplorer => "fileExplorer",
+            Self::Outline => "outline",
             Self::Menu => "menu",
             Self::Terminal => "terminal",
             Self::Settings => "settings",
@@ -243,6 +247,7 @@ pub enum Action {
     SelectWord,
     SelectLine,
     ExpandSelection,
+    ShrinkSelection,
 
     // Block/rectangular selection (column-wise)
     BlockSelectLeft,
@@ -296,7 +301,9 @@ pub enum Action {
     Quit,
     Revert,
     ToggleAutoRevert,
+    ToggleFollow,
     FormatBuffer,
+    FormatSelection,
 
     // Navigation
     GotoLine,
@@ -339,6 +346,13 @@ pub enum Action {
     // Undo/redo
     Undo,
     Redo,
+    UndoChronological,
+    RedoChronological,
+    ShowUndoTree,
+    UndoTreeRestore,
+    FileHistory,
+    FileHistoryRestore,
+    FileHistoryRestoreHunk,
 
     // View
     ScrollUp,
@@ -350,12 +364,21 @@ pub enum Action {
     ClearWarnings,
     CommandPalette,
     ToggleLineWrap,
+    ToggleStickyScroll,
+    ToggleHexView,
+    HexSearch,
     ToggleComposeMode,
     SetComposeWidth,
     SelectTheme,
     SelectKeybindingMap,
     SelectCursorStyle,
     SelectLocale,
+    Fold,
+    Unfold,
+    ToggleFold,
+    FoldAll,
+    UnfoldAll,
+    FoldLevel,
 
     // Buffer/tab navigation
     NextBuffer,
@@ -447,10 +470,29 @@ pub enum Action {
     FileExplorerToggleHidden,
     FileExplorerToggleGitignored,
 
+    // Outline panel and breadcrumbs
+    ToggleOutline,
+    FocusOutline,
+    ToggleBreadcrumbs,
+    PickSiblingSymbol,
+
+    // Snippets
+    InsertSnippet,
+
     // LSP operations
     LspCompletion,
     LspGotoDefinition,
+    LspGotoTypeDefinition,
+    LspGotoImplementation,
+    LspGotoDeclaration,
     LspReferences,
+    LspCodeLens,
+    LspWorkspaceSymbol,
+    ShowCallHierarchy,
+    ShowTypeHierarchy,
+    HierarchyOpen,
+    HierarchyToggleNode,
+    HierarchyToggleDirection,
     LspRename,
     LspHover,
     LspSignatureHelp,
@@ -470,6 +512,9 @@ pub enum Action {
     // Buffer settings (per-buffer overrides)
     SetTabSize,
     SetLineEnding,
+    ReopenWithEncoding,
+    SaveWithEncoding,
+    StopLineIndexing,
     ToggleIndentationStyle,
     ToggleTabIndicators,
     ResetBufferSettings,
@@ -486,6 +531,13 @@ pub enum Action {
     FindSelectionPrevious, // Quick find previous occurrence of selection (Ctrl+Shift+F3)
     Replace,
     QueryReplace, // Interactive replace (y/n/!/q for each match)
+    ProjectSearch,
+    ProjectReplace,
+    ProjectSearchOpen,
+    ProjectSearchToggle,
+    ProjectSearchFilter,
+    ProjectSearchApply,
+    UndoProjectReplace,
 
     // Menu navigation
     MenuActivate,     // Open menu bar (Alt or F10)
@@ -586,6 +638,7 @@ impl Action {
             "select_word" => Self::SelectWord,
             "select_line" => Self::SelectLine,
             "expand_selection" => Self::ExpandSelection,
+            "shrink_selection" => Self::ShrinkSelection,
 
             // Block/rectangular selection
             "block_select_left" => Self::BlockSelectLeft,
@@ -635,7 +688,9 @@ impl Action {
             "quit" => Self::Quit,
             "revert" => Self::Revert,
             "toggle_auto_revert" => Self::ToggleAutoRevert,
+            "toggle_follow" => Self::ToggleFollow,
             "format_buffer" => Self::FormatBuffer,
+            "format_selection" => Self::FormatSelection,
             "goto_line" => Self::GotoLine,
             "goto_matching_bracket" => Self::GoToMatchingBracket,
             "jump_to_next_error" => Self::JumpToNextError,
@@ -672,6 +727,13 @@ impl Action {
 
             "undo" => Self::Undo,
             "redo" => Self::Redo,
+            "undo_chronological" => Self::UndoChronological,
+            "redo_chronological" => Self::RedoChronological,
+            "show_undo_tree" => Self::ShowUndoTree,
+            "undo_tree_restore" => Self::UndoTreeRestore,
+            "file_history" => Self::FileHistory,
+            "file_history_restore" => Self::FileHistoryRestore,
+            "file_history_restore_hunk" => Self::FileHistoryRestoreHunk,
 
             "scroll_up" => Self::ScrollUp,
             "scroll_down" => Self::ScrollDown,
@@ -682,8 +744,17 @@ impl Action {
             "clear_warnings" => Self::ClearWarnings,
             "command_palette" => Self::CommandPalette,
             "toggle_line_wrap" => Self::ToggleLineWrap,
+            "toggle_sticky_scroll" => Self::ToggleStickyScroll,
+            "toggle_hex_view" => Self::ToggleHexView,
+            "hex_search" => Self::HexSearch,
             "toggle_compose_mode" => Self::ToggleComposeMode,
             "set_compose_width" => Self::SetComposeWidth,
+            "fold" => Self::Fold,
+            "unfold" => Self::Unfold,
+            "toggle_fold" => Self::ToggleFold,
+            "fold_all" => Self::FoldAll,
+            "unfold_all" => Self::UnfoldAll,
+            "fold_level" => Self::FoldLevel,
 
             "next_buffer" => Self::NextBuffer,
             "prev_buffer" => Self::PrevBuffer,
@@ -757,9 +828,26 @@ impl Action {
             "file_explorer_toggle_hidden" => Self::FileExplorerToggleHidden,
             "file_explorer_toggle_gitignored" => Self::FileExplorerToggleGitignored,
 
+            "toggle_outline" => Self::ToggleOutline,
+            "focus_outline" => Self::FocusOutline,
+            "toggle_breadcrumbs" => Self::ToggleBreadcrumbs,
+            "pick_sibling_symbol" => Self::PickSiblingSymbol,
+
+            "insert_snippet" => Self::InsertSnippet,
+
             "lsp_completion" => Self::LspCompletion,
             "lsp_goto_definition" => Self::LspGotoDefinition,
+            "lsp_goto_type_definition" => Self::LspGotoTypeDefinition,
+            "lsp_goto_implementation" => Self::LspGotoImplementation,
+            "lsp_goto_declaration" => Self::LspGotoDeclaration,
             "lsp_references" => Self::LspReferences,
+            "lsp_code_lens" => Self::LspCodeLens,
+            "lsp_workspace_symbol" => Self::LspWorkspaceSymbol,
+            "call_hierarchy" => Self::ShowCallHierarchy,
+            "type_hierarchy" => Self::ShowTypeHierarchy,
+            "hierarchy_open" => Self::HierarchyOpen,
+            "hierarchy_toggle_node" => Self::HierarchyToggleNode,
+            "hierarchy_toggle_direction" => Self::HierarchyToggleDirection,
             "lsp_rename" => Self::LspRename,
             "lsp_hover" => Self::LspHover,
             "lsp_signature_help" => Self::LspSignatureHelp,
@@ -781,6 +869,9 @@ impl Action {
             // Buffer settings
             "set_tab_size" => Self::SetTabSize,
             "set_line_ending" => Self::SetLineEnding,
+            "reopen_with_encoding" => Self::ReopenWithEncoding,
+            "save_with_encoding" => Self::SaveWithEncoding,
+            "stop_line_indexing" => Self::StopLineIndexing,
             "toggle_indentation_style" => Self::ToggleIndentationStyle,
             "toggle_tab_indicators" => Self::ToggleTabIndicators,
             "reset_buffer_settings" => Self::ResetBufferSettings,
@@ -795,6 +886,13 @@ impl Action {
             "find_selection_previous" => Self::FindSelectionPrevious,
             "replace" => Self::Replace,
             "query_replace" => Self::QueryReplace,
+            "project_search" => Self::ProjectSearch,
+            "project_replace" => Self::ProjectReplace,
+            "project_search_open" => Self::ProjectSearchOpen,
+            "project_search_toggle" => Self::ProjectSearchToggle,
+            "project_search_filter" => Self::ProjectSearchFilter,
+            "project_search_apply" => Self::ProjectSearchApply,
+            "undo_project_replace" => Self::UndoProjectReplace,
 
             "menu_activate" => Self::MenuActivate,
             "menu_close" => Self::MenuClose,
@@ -1086,6 +1184,8 @@ impl KeybindingResolver {
                 | Action::TerminalPaste
                 // File explorer
                 | Action::ToggleFileExplorer
+                // Outline
+                | Action::ToggleOutline
                 // Menu bar
                 | Action::ToggleMenuBar
         )
@@ -1574,6 +1674,7 @@ impl KeybindingResolver {
             Action::SelectWord => t!("action.select_word"),
             Action::SelectLine => t!("action.select_line"),
             Action::ExpandSelection => t!("action.expand_selection"),
+            Action::ShrinkSelection => t!("action.shrink_selection"),
             Action::BlockSelectLeft => t!("action.block_select_left"),
             Action::BlockSelectRight => t!("action.block_select_right"),
             Action::BlockSelectUp => t!("action.block_select_up"),
@@ -1612,7 +1713,9 @@ impl KeybindingResolver {
             Action::Quit => t!("action.quit"),
             Action::Revert => t!("action.revert"),
             Action::ToggleAutoRevert => t!("action.toggle_auto_revert"),
+            Action::ToggleFollow => t!("action.toggle_follow"),
             Action::FormatBuffer => t!("action.format_buffer"),
+            Action::FormatSelection => t!("action.format_selection"),
             Action::GotoLine => t!("action.goto_line"),
             Action::GoToMatchingBracket => t!("action.goto_matching_bracket"),
             Action::JumpToNextError => t!("action.jump_to_next_error"),
@@ -1641,6 +1744,13 @@ impl KeybindingResolver {
             Action::PromptJumpToBookmark => t!("action.prompt_jump_to_bookmark"),
             Action::Undo => t!("action.undo"),
             Action::Redo => t!("action.redo"),
+            Action::UndoChronological => t!("action.undo_chronological"),
+            Action::RedoChronological => t!("action.redo_chronological"),
+            Action::ShowUndoTree => t!("action.show_undo_tree"),
+            Action::UndoTreeRestore => t!("action.undo_tree_restore"),
+            Action::FileHistory => t!("action.file_history"),
+            Action::FileHistoryRestore => t!("action.file_history_restore"),
+            Action::FileHistoryRestoreHunk => t!("action.file_history_restore_hunk"),
             Action::ScrollUp => t!("action.scroll_up"),
             Action::ScrollDown => t!("action.scroll_down"),
             Action::ShowHelp => t!("action.show_help"),
@@ -1650,8 +1760,17 @@ impl KeybindingResolver {
             Action::ClearWarnings => t!("action.clear_warnings"),
             Action::CommandPalette => t!("action.command_palette"),
             Action::ToggleLineWrap => t!("action.toggle_line_wrap"),
+            Action::ToggleStickyScroll => t!("action.toggle_sticky_scroll"),
+            Action::ToggleHexView => t!("action.toggle_hex_view"),
+            Action::HexSearch => t!("action.hex_search"),
             Action::ToggleComposeMode => t!("action.toggle_compose_mode"),
             Action::SetComposeWidth => t!("action.set_compose_width"),
+            Action::Fold => t!("action.fold"),
+            Action::Unfold => t!("action.unfold"),
+            Action::ToggleFold => t!("action.toggle_fold"),
+            Action::FoldAll => t!("action.fold_all"),
+            Action::UnfoldAll => t!("action.unfold_all"),
+            Action::FoldLevel => t!("action.fold_level"),
             Action::NextBuffer => t!("action.next_buffer"),
             Action::PrevBuffer => t!("action.prev_buffer"),
             Action::NavigateBack => t!("action.navigate_back"),
@@ -1718,9 +1837,24 @@ impl KeybindingResolver {
             Action::FileExplorerRename => t!("action.file_explorer_rename"),
             Action::FileExplorerToggleHidden => t!("action.file_explorer_toggle_hidden"),
             Action::FileExplorerToggleGitignored => t!("action.file_explorer_toggle_gitignored"),
+            Action::ToggleOutline => t!("action.toggle_outline"),
+            Action::FocusOutline => t!("action.focus_outline"),
+            Action::ToggleBreadcrumbs => t!("action.toggle_breadcrumbs"),
+            Action::PickSiblingSymbol => t!("action.pick_sibling_symbol"),
+            Action::InsertSnippet => t!("action.insert_snippet"),
             Action::LspCompletion => t!("action.lsp_completion"),
             Action::LspGotoDefinition => t!("action.lsp_goto_definition"),
+            Action::LspGotoTypeDefinition => t!("action.lsp_goto_type_definition"),
+            Action::LspGotoImplementation => t!("action.lsp_goto_implementation"),
+            Action::LspGotoDeclaration => t!("action.lsp_goto_declaration"),
             Action::LspReferences => t!("action.lsp_references"),
+            Action::LspCodeLens => t!("action.lsp_code_lens"),
+            Action::LspWorkspaceSymbol => t!("action.lsp_workspace_symbol"),
+            Action::ShowCallHierarchy => t!("action.call_hierarchy"),
+            Action::ShowTypeHierarchy => t!("action.type_hierarchy"),
+            Action::HierarchyOpen => t!("action.hierarchy_open"),
+            Action::HierarchyToggleNode => t!("action.hierarchy_toggle_node"),
+            Action::HierarchyToggleDirection => t!("action.hierarchy_toggle_direction"),
             Action::LspRename => t!("action.lsp_rename"),
             Action::LspHover => t!("action.lsp_hover"),
             Action::LspSignatureHelp => t!("action.lsp_signature_help"),
@@ -1736,6 +1870,9 @@ impl KeybindingResolver {
             Action::SetBackgroundBlend => t!("action.set_background_blend"),
             Action::SetTabSize => t!("action.set_tab_size"),
             Action::SetLineEnding => t!("action.set_line_ending"),
+            Action::ReopenWithEncoding => t!("action.reopen_with_encoding"),
+            Action::SaveWithEncoding => t!("action.save_with_encoding"),
+            Action::StopLineIndexing => t!("action.stop_line_indexing"),
             Action::ToggleIndentationStyle => t!("action.toggle_indentation_style"),
             Action::ToggleTabIndicators => t!("action.toggle_tab_indicators"),
             Action::ResetBufferSettings => t!("action.reset_buffer_settings"),
@@ -1748,6 +1885,13 @@ impl KeybindingResolver {
             Action::FindSelectionPrevious => t!("action.find_selection_previous"),
             Action::Replace => t!("action.replace"),
             Action::QueryReplace => t!("action.query_replace"),
+            Action::ProjectSearch => t!("action.project_search"),
+            Action::ProjectReplace => t!("action.project_replace"),
+            Action::ProjectSearchOpen => t!("action.project_search_open"),
+            Action::ProjectSearchToggle => t!("action.project_search_toggle"),
+            Action::ProjectSearchFilter => t!("action.project_search_filter"),
+            Action::ProjectSearchApply => t!("action.project_search_apply"),
+            Action::UndoProjectReplace => t!("action.undo_project_replace"),
             Action::MenuActivate => t!("action.menu_activate"),
             Action::MenuClose => t!("action.menu_close"),
             Action::MenuLeft => t!("action.menu_left"),
@@ -598,6 +598,7 @@ fn run_editor_iteration(
     if let Err(e) = editor.end_recovery_session() {
         tracing::warn!("Failed to end recovery session: {}", e);
     }
+    editor.persist_all_undo_histories();
 
     let update_result = editor.get_update_result().cloned();
     let restart_dir = editor.take_restart_dir();
@@ -1,10 +1,13 @@
 /// Text buffer that uses PieceTree with integrated line tracking
 /// Architecture where the tree is the single source of truth for text and line information
+use crate::model::compression::{CompressWriter, Compression};
+use crate::model::encoding::{Encoding, EncodingWriter, ENCODING_SAMPLE_SIZE};
 use crate::model::piece_tree::{
-    BufferData, BufferLocation, Cursor, PieceInfo, PieceRangeIter, PieceTree, Position,
+    BufferData, BufferLocation, Cursor, LeafData, PieceInfo, PieceRangeIter, PieceTree, Position,
     StringBuffer, TreeStats,
 };
 use crate::model::piece_tree_diff::PieceTreeDiff;
+use crate::model::save_options::{self, SaveOptions, SaveStrategy};
 use crate::primitives::grapheme;
 use anyhow::{Context, Result};
 use regex::bytes::Regex;
@@ -46,6 +49,126 @@ impl std::fmt::Display for SudoSaveRequired {
 
 impl std::error::Error for SudoSaveRequired {}
 
+/// Temporary file holding the decoded (UTF-8) contents of a large file.
+///
+/// Large files that are not UTF-8 are transcoded into a spill file so that they
+/// can still be loaded lazily in chunks. The file is removed when dropped.
+#[derive(Debug)]
+struct SpillFile {
+    path: PathBuf,
+}
+
+impl SpillFile {
+    /// Create a new, empty spill file in the system temporary directory
+    fn create(source_path: &Path) -> io::Result<(Self, std::fs::File)> {
+        let file_name = source_path
+            .file_name()
+            .unwrap_or_else(|| std::ffi::OsStr::new("fresh-spill"));
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!(
+            "fresh-{}-{}-{}.spill",
+            std::process::id(),
+            timestamp,
+            file_name.to_string_lossy()
+        ));
+        let file = std::fs::File::create(&path)?;
+        Ok((Self { path }, file))
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Region of a file holding the content of a large file, scanned in the
+/// background to give it exact line numbers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndexSource {
+    /// File to scan (the file itself, or its decoded spill file)
+    pub path: PathBuf,
+    /// Offset of the content in the file (e.g. after a BOM)
+    pub offset: usize,
+    /// Length of the content in bytes
+    pub len: usize,
+}
+
+/// Content of a buffer at one point in time, independent of later edits.
+///
+/// Used by background tasks (e.g. searching a large file) that must not hold
+/// the buffer itself. Unloaded regions are read from their files on demand.
+#[derive(Debug, Clone)]
+pub struct BufferSnapshot {
+    tree: Arc<PieceTree>,
+    buffers: Vec<StringBuffer>,
+}
+
+impl BufferSnapshot {
+    /// Total content length in bytes
+    pub fn total_bytes(&self) -> usize {
+        self.tree.total_bytes()
+    }
+
+    /// Read `bytes` bytes starting at `offset`, clamped to the end of the content
+    pub fn read_range(&self, offset: usize, bytes: usize) -> io::Result<Vec<u8>> {
+        let end = (offset + bytes).min(self.total_bytes());
+        let mut result = Vec::with_capacity(end.saturating_sub(offset));
+        let mut source_file_cache: Option<(PathBuf, std::fs::File)> = None;
+
+        for piece_view in self.tree.iter_pieces_in_range(offset, end) {
+            let buffer = self
+                .buffers
+                .get(piece_view.location.buffer_id())
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Buffer {} not found", piece_view.location.buffer_id()),
+                    )
+                })?;
+
+            // Clip the piece to the requested range
+            let piece_end = piece_view.doc_offset + piece_view.bytes;
+            let read_start = offset.max(piece_view.doc_offset);
+            let read_end = end.min(piece_end);
+            if read_end <= read_start {
+                continue;
+            }
+            let start_in_buffer = piece_view.buffer_offset + (read_start - piece_view.doc_offset);
+            let len = read_end - read_start;
+
+            match &buffer.data {
+                BufferData::Loaded { data, .. } => {
+                    result.extend_from_slice(&data[start_in_buffer..start_in_buffer + len]);
+                }
+                BufferData::Unloaded {
+                    file_path,
+                    file_offset,
+                    ..
+                } => {
+                    let source_file = match &mut source_file_cache {
+                        Some((cached_path, file)) if cached_path == file_path => file,
+                        _ => {
+                            let file = std::fs::File::open(file_path)?;
+                            source_file_cache = Some((file_path.clone(), file));
+                            &mut source_file_cache.as_mut().unwrap().1
+                        }
+                    };
+                    source_file.seek(SeekFrom::Start((file_offset + start_in_buffer) as u64))?;
+                    let old_len = result.len();
+                    result.resize(old_len + len, 0);
+                    source_file.read_exact(&mut result[old_len..])?;
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
 // Large file support configuration
 /// Default threshold for considering a file "large" (100 MB)
 pub const DEFAULT_LARGE_FILE_THRESHOLD: usize = 100 * 1024 * 1024;
@@ -177,6 +300,34 @@ pub struct TextBuffer {
     /// changed the line ending format and needs conversion on save.
     original_line_ending: LineEnding,
 
+    /// Character encoding of the file on disk (the buffer itself is always UTF-8)
+    encoding: Encoding,
+
+    /// Encoding the file had when loaded or last saved. A differing `encoding`
+    /// means the user chose to save with a new encoding.
+    original_encoding: Encoding,
+
+    /// Compression format of the file on disk, if any. The buffer holds the
+    /// decompressed content, which is recompressed with the same codec on save.
+    compression: Option<Compression>,
+
+    /// Decoded or decompressed contents of a large file, referenced by unloaded
+    /// chunks. Held only so the file is removed when the buffer is dropped.
+    #[allow(dead_code)]
+    spill_file: Option<SpillFile>,
+
+    /// Content of a large file to scan for line feeds, until its lines are indexed
+    line_index_source: Option<LineIndexSource>,
+
+    /// Save the buffer's bytes exactly as they are, without encoding or
+    /// line ending conversion. Set while the buffer is edited as raw bytes
+    /// (e.g. in the hex view).
+    raw_bytes: bool,
+
+    /// Why the file could not be decompressed, when it was opened as it is on
+    /// disk instead
+    decompress_error: Option<String>,
+
     /// The file size on disk after the last save.
     /// Used for chunked recovery to know the original file size for reconstruction.
     /// Updated when loading from file or after saving.
@@ -204,6 +355,13 @@ impl TextBuffer {
             is_binary: false,
             line_ending,
             original_line_ending: line_ending,
+            encoding: Encoding::default(),
+            original_encoding: Encoding::default(),
+            compression: None,
+            spill_file: None,
+            line_index_source: None,
+            raw_bytes: false,
+            decompress_error: None,
             saved_file_size: None,
             version: 0,
         }
@@ -257,6 +415,13 @@ impl TextBuffer {
             recovery_pending: false,
             large_file: false,
             is_binary: false,
+            encoding: Encoding::default(),
+            original_encoding: Encoding::default(),
+            compression: None,
+            spill_file: None,
+            line_index_source: None,
+            raw_bytes: false,
+            decompress_error: None,
             saved_file_size: Some(bytes), // Treat initial content as "saved" state
             version: 0,
         }
@@ -284,15 +449,35 @@ impl TextBuffer {
             is_binary: false,
             line_ending,
             original_line_ending: line_ending,
+            encoding: Encoding::default(),
+            original_encoding: Encoding::default(),
+            compression: None,
+            spill_file: None,
+            line_index_source: None,
+            raw_bytes: false,
+            decompress_error: None,
             saved_file_size: None,
             version: 0,
         }
     }
 
     /// Load a text buffer from a file
+    ///
+    /// The character encoding is detected automatically (see [`Encoding::detect`]).
     pub fn load_from_file<P: AsRef<Path>>(
         path: P,
         large_file_threshold: usize,
+    ) -> anyhow::Result<Self> {
+        Self::load_from_file_with_encoding(path, large_file_threshold, None)
+    }
+
+    /// Load a text buffer from a file, decoding it with the given encoding.
+    ///
+    /// If `encoding` is None, the encoding is detected from the file content.
+    pub fn load_from_file_with_encoding<P: AsRef<Path>>(
+        path: P,
+        large_file_threshold: usize,
+        encoding: Option<Encoding>,
     ) -> anyhow::Result<Self> {
         let path = path.as_ref();
 
@@ -307,20 +492,91 @@ impl TextBuffer {
             DEFAULT_LARGE_FILE_THRESHOLD
         };
 
+        if let Some(compression) = Compression::detect_file(path)? {
+            match Self::load_compressed_file(path, file_size, threshold, encoding, compression) {
+                Ok(buffer) => return Ok(buffer),
+                // A truncated archive, or a text file that merely starts like
+                // one, is opened as it is
+                Err(e) => {
+                    tracing::warn!("Opening {} without decompressing: {:#}", path.display(), e);
+                    let mut buffer = if file_size >= threshold {
+                        Self::load_large_file(path, file_size, encoding)?
+                    } else {
+                        Self::load_small_file(path, encoding)?
+                    };
+                    buffer.decompress_error = Some(format!("{:#}", e));
+                    return Ok(buffer);
+                }
+            }
+        }
+
         // Choose loading strategy based on file size
         if file_size >= threshold {
-            Self::load_large_file(path, file_size)
+            Self::load_large_file(path, file_size, encoding)
         } else {
-            Self::load_small_file(path)
+            Self::load_small_file(path, encoding)
         }
     }
 
+    /// Load a compressed file by decompressing it into a spill file.
+    ///
+    /// The decompressed content is then loaded like any other file, so large
+    /// compressed files still use the lazy large-file path. The buffer keeps the
+    /// original path and recompresses with the same codec when saved.
+    fn load_compressed_file(
+        path: &Path,
+        file_size: usize,
+        threshold: usize,
+        encoding: Option<Encoding>,
+        compression: Compression,
+    ) -> anyhow::Result<Self> {
+        let (spill, out_file) = SpillFile::create(path)?;
+        let mut writer = io::BufWriter::new(out_file);
+        let decompressed_size = compression
+            .decompress(std::fs::File::open(path)?, &mut writer)
+            .with_context(|| format!("Failed to decompress {}", path.display()))?;
+        writer.flush()?;
+        drop(writer);
+        tracing::debug!(
+            "Buffer::load_compressed_file: decompressed {} bytes of {} to {} bytes",
+            file_size,
+            compression.display_name(),
+            decompressed_size
+        );
+
+        let mut buffer = if decompressed_size >= threshold {
+            let mut buffer = Self::load_large_file(&spill.path, decompressed_size, encoding)?;
+            // Unless it was transcoded into a spill file of its own, the content
+            // is read straight from the decompressed spill file
+            if buffer.spill_file.is_none() {
+                buffer.spill_file = Some(spill);
+            }
+            buffer
+        } else {
+            Self::load_small_file(&spill.path, encoding)?
+        };
+        buffer.file_path = Some(path.to_path_buf());
+        buffer.compression = Some(compression);
+        buffer.saved_file_size = Some(file_size);
+        Ok(buffer)
+    }
+
     /// Load a small file with full eager loading and line indexing
-    fn load_small_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+    fn load_small_file<P: AsRef<Path>>(
+        path: P,
+        encoding: Option<Encoding>,
+    ) -> anyhow::Result<Self> {
         let path = path.as_ref();
         let mut file = std::fs::File::open(path)?;
         let mut contents = Vec::new();
         file.read_to_end(&mut contents)?;
+        let file_size = contents.len();
+
+        // Decode to UTF-8 if the file uses another encoding
+        let encoding = encoding.unwrap_or_else(|| Encoding::detect(&contents, false));
+        if encoding != Encoding::Utf8 {
+            contents = encoding.decode(&contents);
+        }
 
         // Detect if this is a binary file
         let is_binary = Self::detect_binary(&contents);
@@ -336,20 +592,68 @@ impl TextBuffer {
         buffer.is_binary = is_binary;
         buffer.line_ending = line_ending;
         buffer.original_line_ending = line_ending;
+        buffer.encoding = encoding;
+        buffer.original_encoding = encoding;
+        buffer.saved_file_size = Some(file_size);
         Ok(buffer)
     }
 
     /// Load a large file with unloaded buffer (no line indexing, lazy loading)
-    fn load_large_file<P: AsRef<Path>>(path: P, file_size: usize) -> anyhow::Result<Self> {
-        use crate::model::piece_tree::{BufferData, BufferLocation};
+    ///
+    /// UTF-8 files are referenced in place (skipping any BOM). Files in other
+    /// encodings are first transcoded into a UTF-8 spill file, which is then
+    /// loaded lazily in the same way.
+    fn load_large_file<P: AsRef<Path>>(
+        path: P,
+        file_size: usize,
+        encoding: Option<Encoding>,
+    ) -> anyhow::Result<Self> {
+        use crate::model::piece_tree::BufferLocation;
 
         let path = path.as_ref();
 
-        // Read a sample of the file to detect if it's binary and line ending format
+        // Read a sample of the file to detect its encoding
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None => {
+                let mut file = std::fs::File::open(path)?;
+                let sample_size = file_size.min(ENCODING_SAMPLE_SIZE);
+                let mut sample = vec![0u8; sample_size];
+                file.read_exact(&mut sample)?;
+                Encoding::detect(&sample, sample_size < file_size)
+            }
+        };
+
+        // Locate the UTF-8 content: the file itself, or a transcoded spill file
+        let (content_path, content_offset, content_size, spill_file) = if encoding.is_utf8() {
+            // Skip the BOM only if the file has one: a file reopened as UTF-8 BOM
+            // may not start with it
+            let bom = encoding.bom();
+            let mut head = vec![0u8; bom.len().min(file_size)];
+            std::fs::File::open(path)?.read_exact(&mut head)?;
+            let bom_len = if head == bom { bom.len() } else { 0 };
+            (path.to_path_buf(), bom_len, file_size - bom_len, None)
+        } else {
+            let (spill, out_file) = SpillFile::create(path)?;
+            let mut reader = io::BufReader::new(std::fs::File::open(path)?);
+            let mut writer = io::BufWriter::new(out_file);
+            let decoded_size = encoding.transcode_to_utf8(&mut reader, &mut writer)?;
+            writer.flush()?;
+            tracing::debug!(
+                "Buffer::load_large_file: transcoded {} bytes of {} to {} bytes of UTF-8",
+                file_size,
+                encoding.display_name(),
+                decoded_size
+            );
+            (spill.path.clone(), 0, decoded_size, Some(spill))
+        };
+
+        // Read a sample of the content to detect if it's binary and line ending format
         // We read the first 8KB for both binary and line ending detection
         let (is_binary, line_ending) = {
-            let mut file = std::fs::File::open(path)?;
-            let sample_size = file_size.min(8 * 1024);
+            let mut file = std::fs::File::open(&content_path)?;
+            file.seek(SeekFrom::Start(content_offset as u64))?;
+            let sample_size = content_size.min(8 * 1024);
             let mut sample = vec![0u8; sample_size];
             file.read_exact(&mut sample)?;
             let is_binary = Self::detect_binary(&sample);
@@ -357,20 +661,19 @@ impl TextBuffer {
             (is_binary, line_ending)
         };
 
-        // Create an unloaded buffer that references the entire file
-        let buffer = StringBuffer {
-            id: 0,
-            data: BufferData::Unloaded {
-                file_path: path.to_path_buf(),
-                file_offset: 0,
-                bytes: file_size,
-            },
+        let line_index_source = LineIndexSource {
+            path: content_path.clone(),
+            offset: content_offset,
+            len: content_size,
         };
 
+        // Create an unloaded buffer that references the entire content
+        let buffer = StringBuffer::new_unloaded(0, content_path, content_offset, content_size);
+
         // Create piece tree with a single piece covering the whole file
         // No line feed count (None) since we're not computing line indexing
-        let piece_tree = if file_size > 0 {
-            PieceTree::new(BufferLocation::Stored(0), 0, file_size, None)
+        let piece_tree = if content_size > 0 {
+            PieceTree::new(BufferLocation::Stored(0), 0, content_size, None)
         } else {
             PieceTree::empty()
         };
@@ -394,6 +697,13 @@ impl TextBuffer {
             is_binary,
             line_ending,
             original_line_ending: line_ending,
+            encoding,
+            original_encoding: encoding,
+            compression: None,
+            spill_file,
+            line_index_source: Some(line_index_source),
+            raw_bytes: false,
+            decompress_error: None,
             saved_file_size: Some(file_size),
             version: 0,
         })
@@ -401,8 +711,13 @@ impl TextBuffer {
 
     /// Save the buffer to its associated file
     pub fn save(&mut self) -> anyhow::Result<()> {
+        self.save_with_options(&SaveOptions::default())
+    }
+
+    /// Save the buffer to its associated file with the given strategy and backups
+    pub fn save_with_options(&mut self, options: &SaveOptions) -> anyhow::Result<()> {
         if let Some(path) = &self.file_path {
-            self.save_to_file(path.clone())
+            self.save_to_file_with_options(path.clone(), options)
         } else {
             anyhow::bail!(io::Error::new(
                 io::ErrorKind::NotFound,
@@ -452,20 +767,214 @@ impl TextBuffer {
     ///
     /// If the line ending format has been changed (via set_line_ending), all content
     /// will be converted to the new format during save.
+    ///
+    /// Content is encoded with the buffer's encoding (see [`TextBuffer::encoding`]),
+    /// including its byte order mark if it has one, and recompressed if the file
+    /// was compressed (see [`TextBuffer::compression`]).
     pub fn save_to_file<P: AsRef<Path>>(&mut self, path: P) -> anyhow::Result<()> {
+        self.save_to_file_with_options(path, &SaveOptions::default())
+    }
+
+    /// Save the buffer to a specific file with the given strategy and backups
+    ///
+    /// See [`TextBuffer::save_to_file`]. The previous version of the file is
+    /// backed up first, then replaced as described by [`SaveStrategy`].
+    /// Permissions, ownership and extended attributes are kept where possible.
+    pub fn save_to_file_with_options<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        options: &SaveOptions,
+    ) -> anyhow::Result<()> {
         let dest_path = path.as_ref();
-        let total = self.total_bytes();
 
         // Get original file metadata (permissions, owner, etc.) before writing
         // so we can preserve it after creating/renaming the temp file
         let original_metadata = std::fs::metadata(dest_path).ok();
 
-        // Check if we need to convert line endings
-        let needs_conversion = self.line_ending != self.original_line_ending;
-        let target_ending = self.line_ending;
+        if original_metadata.is_some() {
+            options
+                .backup
+                .write_backup(dest_path)
+                .with_context(|| format!("Failed to back up {}", dest_path.display()))?;
+        }
+
+        // Rewriting the file in place would overwrite regions still streamed from it
+        let strategy = match options.strategy {
+            SaveStrategy::InPlace if self.streams_from(dest_path) => SaveStrategy::Copy,
+            strategy => strategy,
+        };
+
+        let written_in_place = strategy == SaveStrategy::InPlace
+            && original_metadata.is_some()
+            && self.write_in_place(dest_path)?;
+        if !written_in_place {
+            self.write_via_temp_file(dest_path, strategy, original_metadata)?;
+        }
+
+        // Update saved file size to match the file on disk
+        let new_size = std::fs::metadata(dest_path)?.len() as usize;
+        tracing::debug!(
+            "Buffer::save: updating saved_file_size from {:?} to {}",
+            self.saved_file_size,
+            new_size
+        );
+        self.saved_file_size = Some(new_size);
+
+        // Line feed counts scanned from the overwritten file would no longer match
+        if self
+            .line_index_source
+            .as_ref()
+            .is_some_and(|source| source.path == dest_path)
+        {
+            self.line_index_source = None;
+        }
+
+        self.file_path = Some(dest_path.to_path_buf());
+        self.mark_saved_snapshot();
+
+        // Update original_line_ending to match what we just saved
+        // This prevents repeated conversions on subsequent saves
+        self.original_line_ending = self.line_ending;
+        self.original_encoding = self.encoding;
 
+        Ok(())
+    }
+
+    /// Write the new content to a temporary file, then move it into place:
+    /// renamed over `dest_path` for atomic saves and new files, otherwise
+    /// copied over the existing file to keep its inode.
+    fn write_via_temp_file(
+        &self,
+        dest_path: &Path,
+        strategy: SaveStrategy,
+        original_metadata: Option<std::fs::Metadata>,
+    ) -> anyhow::Result<()> {
         // Stage A: Temporary File Creation
-        let (temp_path, mut out_file) = Self::create_temp_file(dest_path)?;
+        let (temp_path, out_file) = Self::create_temp_file(dest_path)?;
+
+        // Stage B: Write content, encoded in the buffer's encoding
+        let out_file = match self.write_encoded(out_file) {
+            Ok(file) => file,
+            Err(e) => {
+                let _ = std::fs::remove_file(&temp_path);
+                return Err(e.into());
+            }
+        };
+
+        // Ensure all data is written
+        out_file.sync_all()?;
+        drop(out_file);
+
+        if strategy != SaveStrategy::Atomic && original_metadata.is_some() {
+            return match Self::copy_over(&temp_path, dest_path) {
+                Ok(()) => {
+                    let _ = std::fs::remove_file(&temp_path);
+                    Ok(())
+                }
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                    Err(self.make_sudo_error(temp_path, dest_path, original_metadata))
+                }
+                Err(e) => {
+                    let _ = std::fs::remove_file(&temp_path);
+                    Err(e.into())
+                }
+            };
+        }
+
+        // Restore original file permissions/owner before renaming
+        if let Some(ref meta) = original_metadata {
+            // Best effort restore
+            let _ = Self::restore_file_metadata(&temp_path, meta);
+            let _ = save_options::copy_xattrs(dest_path, &temp_path);
+        }
+
+        // Stage C: Atomic Replacement or Sudo Fallback
+        if let Err(e) = std::fs::rename(&temp_path, dest_path) {
+            let is_permission_denied = e.kind() == io::ErrorKind::PermissionDenied;
+            let is_cross_device = cfg!(unix) && e.raw_os_error() == Some(18);
+
+            if is_cross_device {
+                #[cfg(unix)]
+                {
+                    match std::fs::copy(&temp_path, dest_path) {
+                        Ok(_) => {
+                            let _ = std::fs::remove_file(&temp_path);
+                        }
+                        Err(copy_err) if copy_err.kind() == io::ErrorKind::PermissionDenied => {
+                            return Err(self.make_sudo_error(
+                                temp_path,
+                                dest_path,
+                                original_metadata,
+                            ));
+                        }
+                        Err(copy_err) => return Err(copy_err.into()),
+                    }
+                }
+            } else if is_permission_denied {
+                return Err(self.make_sudo_error(temp_path, dest_path, original_metadata));
+            } else {
+                return Err(e.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Truncate `dest_path` and write the new content into it.
+    /// Returns false if the file can't be opened for writing.
+    fn write_in_place(&self, dest_path: &Path) -> anyhow::Result<bool> {
+        let out_file = match std::fs::OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(dest_path)
+        {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+        self.write_encoded(out_file)?.sync_all()?;
+        Ok(true)
+    }
+
+    /// Overwrite the content of `dest_path` with that of `source`, keeping its inode
+    fn copy_over(source: &Path, dest_path: &Path) -> io::Result<()> {
+        let mut source = std::fs::File::open(source)?;
+        let mut dest = std::fs::OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(dest_path)?;
+        io::copy(&mut source, &mut dest)?;
+        dest.sync_all()
+    }
+
+    /// Whether any content is streamed from `path` rather than held in memory
+    fn streams_from(&self, path: &Path) -> bool {
+        self.buffers.iter().any(|buffer| {
+            matches!(&buffer.data, BufferData::Unloaded { file_path, .. } if file_path == path)
+        })
+    }
+
+    /// Write the content to `out`, encoded in the buffer's encoding and
+    /// recompressed if needed, and return `out` when done
+    fn write_encoded(&self, out: std::fs::File) -> io::Result<std::fs::File> {
+        let encoding = if self.raw_bytes {
+            Encoding::Utf8
+        } else {
+            self.encoding
+        };
+        let out = CompressWriter::new(out, self.compression)?;
+        let mut writer = EncodingWriter::new(out, encoding);
+        self.write_content(&mut writer)?;
+        writer.finish()?.finish()
+    }
+
+    /// Write the buffer content to `out`, converting line endings if the
+    /// line ending format has been changed since the file was loaded.
+    fn write_content<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let total = self.total_bytes();
+
+        // Check if we need to convert line endings
+        let needs_conversion = !self.raw_bytes && self.line_ending != self.original_line_ending;
+        let target_ending = self.line_ending;
 
         if total > 0 {
             // Cache for open source files (for streaming unloaded regions)
@@ -490,10 +999,10 @@ impl TextBuffer {
                         if needs_conversion {
                             // Convert line endings before writing
                             let converted = Self::convert_line_endings_to(chunk, target_ending);
-                            out_file.write_all(&converted)?;
+                            out.write_all(&converted)?;
                         } else {
                             // Write directly without conversion
-                            out_file.write_all(chunk)?;
+                            out.write_all(chunk)?;
                         }
                     }
                     BufferData::Unloaded {
@@ -530,10 +1039,10 @@ impl TextBuffer {
                                     &chunk_buf[..to_read],
                                     target_ending,
                                 );
-                                out_file.write_all(&converted)?;
+                                out.write_all(&converted)?;
                             } else {
                                 // Write directly without conversion
-                                out_file.write_all(&chunk_buf[..to_read])?;
+                                out.write_all(&chunk_buf[..to_read])?;
                             }
                             remaining -= to_read;
                         }
@@ -542,61 +1051,6 @@ impl TextBuffer {
             }
         }
 
-        // Ensure all data is written
-        out_file.sync_all()?;
-        drop(out_file);
-
-        // Restore original file permissions/owner before renaming
-        if let Some(ref meta) = original_metadata {
-            // Best effort restore
-            let _ = Self::restore_file_metadata(&temp_path, meta);
-        }
-
-        // Stage C: Atomic Replacement or Sudo Fallback
-        if let Err(e) = std::fs::rename(&temp_path, dest_path) {
-            let is_permission_denied = e.kind() == io::ErrorKind::PermissionDenied;
-            let is_cross_device = cfg!(unix) && e.raw_os_error() == Some(18);
-
-            if is_cross_device {
-                #[cfg(unix)]
-                {
-                    match std::fs::copy(&temp_path, dest_path) {
-                        Ok(_) => {
-                            let _ = std::fs::remove_file(&temp_path);
-                        }
-                        Err(copy_err) if copy_err.kind() == io::ErrorKind::PermissionDenied => {
-                            return Err(self.make_sudo_error(
-                                temp_path,
-                                dest_path,
-                                original_metadata,
-                            ));
-                        }
-                        Err(copy_err) => return Err(copy_err.into()),
-                    }
-                }
-            } else if is_permission_denied {
-                return Err(self.make_sudo_error(temp_path, dest_path, original_metadata));
-            } else {
-                return Err(e.into());
-            }
-        }
-
-        // Update saved file size to match the file on disk
-        let new_size = std::fs::metadata(dest_path)?.len() as usize;
-        tracing::debug!(
-            "Buffer::save: updating saved_file_size from {:?} to {}",
-            self.saved_file_size,
-            new_size
-        );
-        self.saved_file_size = Some(new_size);
-
-        self.file_path = Some(dest_path.to_path_buf());
-        self.mark_saved_snapshot();
-
-        // Update original_line_ending to match what we just saved
-        // This prevents repeated conversions on subsequent saves
-        self.original_line_ending = self.line_ending;
-
         Ok(())
     }
 
@@ -609,6 +1063,7 @@ impl TextBuffer {
         self.file_path = Some(dest_path);
         self.mark_saved_snapshot();
         self.original_line_ending = self.line_ending;
+        self.original_encoding = self.encoding;
         Ok(())
     }
 
@@ -674,6 +1129,148 @@ impl TextBuffer {
         self.piece_tree.line_count()
     }
 
+    /// Content to scan for exact line numbers, while a large file's line
+    /// count is still unknown
+    pub fn line_index_source(&self) -> Option<&LineIndexSource> {
+        if self.line_count().is_some() {
+            return None;
+        }
+        self.line_index_source.as_ref()
+    }
+
+    /// Record the line feed counts of scanned chunks of a large file.
+    ///
+    /// `counts[i]` is the number of line feeds in chunk `first_chunk + i` of
+    /// `source`, where chunks are `LOAD_CHUNK_SIZE` bytes long. Pieces of the
+    /// scanned chunks are split at chunk boundaries and given their counts, so
+    /// that line numbers become exact once every chunk has been recorded. Loaded
+    /// pieces without a count are counted from memory. Counts for a source other
+    /// than the buffer's current one (e.g. after saving over it) are ignored.
+    pub fn apply_line_feed_counts(
+        &mut self,
+        source: &LineIndexSource,
+        first_chunk: usize,
+        counts: &[usize],
+    ) -> io::Result<()> {
+        if self.line_index_source.as_ref() != Some(source) {
+            return Ok(());
+        }
+        let scanned_chunks = first_chunk..first_chunk + counts.len();
+        let mut leaves = Vec::new();
+        let mut loaded_buffers = Vec::new();
+
+        for leaf in self.piece_tree.get_leaves() {
+            if leaf.line_feed_cnt.is_some() {
+                leaves.push(leaf);
+                continue;
+            }
+            let buffer_id = leaf.location.buffer_id();
+            let Some(buffer) = self.buffers.get(buffer_id) else {
+                leaves.push(leaf);
+                continue;
+            };
+            let file_offset = match &buffer.data {
+                BufferData::Loaded { data, .. } => {
+                    let end = (leaf.offset + leaf.bytes).min(data.len());
+                    let count = data[leaf.offset.min(end)..end]
+                        .iter()
+                        .filter(|&&b| b == b'\n')
+                        .count();
+                    leaves.push(LeafData::new(
+                        leaf.location,
+                        leaf.offset,
+                        leaf.bytes,
+                        Some(count),
+                    ));
+                    loaded_buffers.push(buffer_id);
+                    continue;
+                }
+                BufferData::Unloaded {
+                    file_path,
+                    file_offset,
+                    ..
+                } if *file_path == source.path && *file_offset + leaf.offset >= source.offset => {
+                    *file_offset
+                }
+                BufferData::Unloaded { .. } => {
+                    leaves.push(leaf);
+                    continue;
+                }
+            };
+
+            // Split the piece at chunk boundaries (in content coordinates)
+            let start = file_offset + leaf.offset - source.offset;
+            let end = start + leaf.bytes;
+            let mut unknown_start = None;
+            let mut pos = start;
+            while pos < end {
+                let chunk = pos / LOAD_CHUNK_SIZE;
+                let chunk_start = chunk * LOAD_CHUNK_SIZE;
+                let chunk_end = (chunk_start + LOAD_CHUNK_SIZE).min(source.len);
+                let part_end = chunk_end.min(end);
+
+                if scanned_chunks.contains(&chunk) {
+                    if let Some(unknown) = unknown_start.take() {
+                        leaves.push(LeafData::new(
+                            leaf.location,
+                            leaf.offset + unknown - start,
+                            pos - unknown,
+                            None,
+                        ));
+                    }
+                    let part = StringBuffer::new_unloaded(
+                        self.next_buffer_id,
+                        source.path.clone(),
+                        source.offset + pos,
+                        part_end - pos,
+                    );
+                    let count = if pos == chunk_start && part_end == chunk_end {
+                        counts[chunk - first_chunk]
+                    } else {
+                        part.count_line_feeds(0, part_end - pos).ok_or_else(|| {
+                            io::Error::other(format!("failed to read {}", source.path.display()))
+                        })?
+                    };
+                    let location = match leaf.location {
+                        BufferLocation::Stored(_) => BufferLocation::Stored(part.id),
+                        BufferLocation::Added(_) => BufferLocation::Added(part.id),
+                    };
+                    leaves.push(LeafData::new(location, 0, part_end - pos, Some(count)));
+                    self.buffers.push(part);
+                    self.next_buffer_id += 1;
+                } else if unknown_start.is_none() {
+                    unknown_start = Some(pos);
+                }
+                pos = part_end;
+            }
+            if let Some(unknown) = unknown_start {
+                leaves.push(LeafData::new(
+                    leaf.location,
+                    leaf.offset + unknown - start,
+                    end - unknown,
+                    None,
+                ));
+            }
+        }
+
+        for buffer_id in loaded_buffers {
+            if let Some(buffer) = self.buffers.get_mut(buffer_id) {
+                buffer.index_lines();
+            }
+        }
+
+        // The content is unchanged, so an unmodified buffer stays at its saved state
+        let at_saved_state = Arc::ptr_eq(&self.saved_root, &self.piece_tree.root());
+        self.piece_tree = PieceTree::from_leaves(&leaves);
+        if at_saved_state {
+            self.saved_root = self.piece_tree.root();
+        }
+        if self.piece_tree.line_count().is_some() {
+            self.line_index_source = None;
+        }
+        Ok(())
+    }
+
     /// Snapshot the current tree as the saved baseline
     pub fn mark_saved_snapshot(&mut self) {
         self.saved_root = self.piece_tree.root();
@@ -1100,6 +1697,31 @@ impl TextBuffer {
         Arc::new(self.piece_tree.clone())
     }
 
+    /// Take a read-only snapshot of the content that can be read off the main thread.
+    ///
+    /// Loaded data is copied; unloaded regions of large files stay file references.
+    pub fn content_snapshot(&self) -> BufferSnapshot {
+        BufferSnapshot {
+            tree: self.snapshot_piece_tree(),
+            buffers: self.buffers.clone(),
+        }
+    }
+
+    /// Get all text of a piece tree snapshot taken from this buffer
+    /// Returns None if any buffers it references are unloaded
+    pub fn piece_tree_text(&self, tree: &PieceTree) -> Option<String> {
+        let mut result = Vec::with_capacity(tree.total_bytes());
+        for piece_view in tree.iter_pieces_in_range(0, tree.total_bytes()) {
+            let data = self
+                .buffers
+                .get(piece_view.location.buffer_id())?
+                .get_data()?;
+            let start = piece_view.buffer_offset;
+            result.extend_from_slice(data.get(start..start + piece_view.bytes)?);
+        }
+        Some(String::from_utf8_lossy(&result).into_owned())
+    }
+
     /// Apply bulk edits efficiently in a single pass
     /// Returns the net change in bytes
     pub fn apply_bulk_edits(&mut self, edits: &[(usize, usize, &str)]) -> isize {
@@ -1292,11 +1914,15 @@ impl TextBuffer {
                         break;
                     } else {
                         // Piece is small enough, load the entire buffer
-                        self.buffers
+                        let buffer = self
+                            .buffers
                             .get_mut(buffer_id)
-                            .context("Buffer not found")?
-                            .load()
-                            .context("Failed to load buffer")?;
+                            .context("Buffer not found")?;
+                        buffer.load().context("Failed to load buffer")?;
+                        // Keep line starts for chunks whose lines have been indexed
+                        if piece_view.line_feed_cnt.is_some() {
+                            buffer.index_lines();
+                        }
                     }
                 }
 
@@ -1480,6 +2106,58 @@ impl TextBuffer {
         );
     }
 
+    /// Append a region that was added to the end of the buffer's file on disk,
+    /// as `tail -f` does.
+    ///
+    /// `file_offset..file_offset + bytes` of `source_path` is appended in chunks
+    /// that large files read lazily and other buffers load. Their line feeds are
+    /// counted, so line numbers stay exact. The appended content is on disk, so a
+    /// buffer at its saved state stays there.
+    pub fn append_from_file(
+        &mut self,
+        source_path: &Path,
+        file_offset: usize,
+        bytes: usize,
+    ) -> io::Result<()> {
+        let at_saved_state = Arc::ptr_eq(&self.saved_root, &self.piece_tree.root());
+        let mut appended = 0;
+        while appended < bytes {
+            let len = (bytes - appended).min(LOAD_CHUNK_SIZE);
+            let mut part = StringBuffer::new_unloaded(
+                self.next_buffer_id,
+                source_path.to_path_buf(),
+                file_offset + appended,
+                len,
+            );
+            if !self.large_file {
+                part.load()?;
+            }
+            let line_feed_cnt = part.count_line_feeds(0, len).ok_or_else(|| {
+                io::Error::other(format!("failed to read {}", source_path.display()))
+            })?;
+            let location = BufferLocation::Stored(part.id);
+            self.buffers.push(part);
+            self.next_buffer_id += 1;
+            self.piece_tree.insert(
+                self.total_bytes(),
+                location,
+                0,
+                len,
+                Some(line_feed_cnt),
+                &self.buffers,
+            );
+            appended += len;
+        }
+
+        if bytes > 0 {
+            self.bump_version();
+            if at_saved_state {
+                self.saved_root = self.piece_tree.root();
+            }
+        }
+        Ok(())
+    }
+
     /// Check if the buffer has been modified since last save
     pub fn is_modified(&self) -> bool {
         self.modified
@@ -1588,13 +2266,71 @@ impl TextBuffer {
         self.mark_content_modified();
     }
 
-    /// Set the default line ending format for a new/empty buffer
-    ///
-    /// Unlike `set_line_ending`, this does NOT mark the buffer as modified.
-    /// This should be used when initializing a new buffer with a configured default.
-    pub fn set_default_line_ending(&mut self, line_ending: LineEnding) {
-        self.line_ending = line_ending;
-        self.original_line_ending = line_ending;
+    /// Set the default line ending format for a new/empty buffer
+    ///
+    /// Unlike `set_line_ending`, this does NOT mark the buffer as modified.
+    /// This should be used when initializing a new buffer with a configured default.
+    pub fn set_default_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+        self.original_line_ending = line_ending;
+    }
+
+    /// Get the character encoding used when saving this buffer
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Get the encoding the file had when it was loaded or last saved
+    pub fn original_encoding(&self) -> Encoding {
+        self.original_encoding
+    }
+
+    /// Set the character encoding for a new/empty buffer
+    ///
+    /// Unlike `set_encoding`, this does NOT mark the buffer as modified.
+    pub fn set_default_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+        self.original_encoding = encoding;
+    }
+
+    /// Set the character encoding for this buffer
+    ///
+    /// This marks the buffer as modified since the on-disk representation changes.
+    /// On save, the buffer content will be encoded with the new encoding.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+        self.mark_content_modified();
+    }
+
+    /// Get the compression format the buffer is saved with, if any
+    pub fn compression(&self) -> Option<Compression> {
+        self.compression
+    }
+
+    /// Set the compression format used when saving this buffer
+    ///
+    /// Unlike `set_encoding`, this does NOT mark the buffer as modified. It is
+    /// used to give a new file the compression its name implies.
+    pub fn set_compression(&mut self, compression: Option<Compression>) {
+        self.compression = compression;
+    }
+
+    /// Take the reason the file was opened without decompressing it, if it
+    /// looked compressed but could not be decompressed
+    pub fn take_decompress_error(&mut self) -> Option<String> {
+        self.decompress_error.take()
+    }
+
+    /// Whether the buffer is saved as raw bytes (see [`TextBuffer::set_raw_bytes`])
+    pub fn is_raw_bytes(&self) -> bool {
+        self.raw_bytes
+    }
+
+    /// Save the buffer's bytes verbatim, skipping encoding and line ending
+    /// conversion. Only meaningful for buffers loaded as UTF-8, whose bytes
+    /// are those of the file.
+    pub fn set_raw_bytes(&mut self, raw_bytes: bool) {
+        self.raw_bytes = raw_bytes;
     }
 
     /// Detect if the given bytes contain binary content.
@@ -1918,6 +2654,51 @@ impl TextBuffer {
         None
     }
 
+    /// Find the next occurrence of a byte pattern at or after `start_pos`, with wrap-around
+    ///
+    /// Unlike [`TextBuffer::find_next`], this loads unloaded regions of large files
+    /// chunk by chunk as it goes, so it can search the whole of a lazily loaded file.
+    pub fn find_next_bytes(&mut self, pattern: &[u8], start_pos: usize) -> Result<Option<usize>> {
+        if pattern.is_empty() {
+            return Ok(None);
+        }
+
+        let buffer_len = self.len();
+        let start_pos = start_pos.min(buffer_len);
+
+        if let Some(offset) = self.find_bytes_in_range(pattern, start_pos, buffer_len)? {
+            return Ok(Some(offset));
+        }
+
+        // Wrap around, allowing a match that straddles start_pos
+        let wrap_end = (start_pos + pattern.len() - 1).min(buffer_len);
+        self.find_bytes_in_range(pattern, 0, wrap_end)
+    }
+
+    /// Find a byte pattern lying entirely within `start..end`, loading chunks as needed
+    fn find_bytes_in_range(
+        &mut self,
+        pattern: &[u8],
+        start: usize,
+        end: usize,
+    ) -> Result<Option<usize>> {
+        const CHUNK_SIZE: usize = 64 * 1024;
+
+        let mut chunk_start = start;
+        while chunk_start + pattern.len() <= end {
+            // Chunks overlap by one byte less than the pattern, so a match
+            // crossing a chunk boundary is found in the chunk where it starts
+            let chunk_end = (chunk_start + CHUNK_SIZE + pattern.len() - 1).min(end);
+            let chunk = self.get_text_range_mut(chunk_start, chunk_end - chunk_start)?;
+            if let Some(pos) = Self::find_in_bytes(&chunk, pattern) {
+                return Ok(Some(chunk_start + pos));
+            }
+            chunk_start += CHUNK_SIZE;
+        }
+
+        Ok(None)
+    }
+
     /// Find the next occurrence of a regex pattern, with wrap-around
     pub fn find_next_regex(&self, regex: &Regex, start_pos: usize) -> Option<usize> {
         let buffer_len = self.len();
@@ -2473,10 +3254,12 @@ impl TextBuffer {
     ///
     /// # Behavior by File Size:
     /// - **Small files (< 1MB)**: Returns exact line number from piece tree's `line_starts` metadata
-    /// - **Large files (≥ 1MB)**: Returns estimated line number using `byte_offset / 80`
+    /// - **Large files (≥ 1MB)**: Returns estimated line number using `byte_offset / 80`,
+    ///   until the background line indexer has recorded the line feed counts up to `byte_offset`
     ///
-    /// Large files don't maintain line metadata for performance reasons. The estimation
-    /// assumes ~80 bytes per line on average, which works reasonably well for most text files.
+    /// Large files don't maintain line metadata when loaded for performance reasons. The
+    /// estimation assumes ~80 bytes per line on average, which works reasonably well for
+    /// most text files.
     pub fn get_line_number(&self, byte_offset: usize) -> usize {
         self.offset_to_position(byte_offset)
             .map(|pos| pos.line)
@@ -3252,6 +4035,27 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_find_next_bytes_in_unloaded_large_file() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("big.bin");
+            // Place patterns across the 64KB search chunk boundary and near the start
+            let mut data = vec![0u8; 200 * 1024];
+            data[10..14].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+            data[65534..65538].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+            std::fs::write(&file_path, &data).unwrap();
+
+            let mut buffer = TextBuffer::load_from_file(&file_path, 1024).unwrap();
+            assert!(buffer.is_large_file());
+            let pattern = [0xDE, 0xAD, 0xBE, 0xEF];
+
+            assert_eq!(buffer.find_next_bytes(&pattern, 0).unwrap(), Some(10));
+            assert_eq!(buffer.find_next_bytes(&pattern, 11).unwrap(), Some(65534));
+            // Wraps around to the start
+            assert_eq!(buffer.find_next_bytes(&pattern, 65535).unwrap(), Some(10));
+            assert_eq!(buffer.find_next_bytes(&[0xAA], 0).unwrap(), None);
+        }
+
         #[test]
         fn test_large_file_threshold_boundary() {
             let temp_dir = TempDir::new().unwrap();
@@ -3649,6 +4453,126 @@ mod tests {
                 "Length should be original + edits"
             );
         }
+
+        /// Content of varying line lengths, so that estimated line numbers are wrong
+        fn varied_lines(size: usize) -> (Vec<u8>, Vec<usize>) {
+            let mut content = Vec::with_capacity(size);
+            let mut line_starts = vec![0];
+            let mut i = 0;
+            while content.len() < size {
+                content.extend(std::iter::repeat_n(b'x', i % 37));
+                content.push(b'\n');
+                line_starts.push(content.len());
+                i += 1;
+            }
+            (content, line_starts)
+        }
+
+        #[test]
+        fn test_apply_line_feed_counts_gives_exact_line_numbers() {
+            use crate::services::line_indexer::scan_line_feeds;
+            use std::sync::atomic::AtomicBool;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("big.log");
+            let (content, line_starts) = varied_lines(LOAD_CHUNK_SIZE * 5 / 2);
+            std::fs::write(&file_path, &content).unwrap();
+
+            let mut buffer = TextBuffer::load_from_file(&file_path, 1).unwrap();
+            assert_eq!(buffer.line_count(), None);
+            // Load a chunk straddling the scanned chunks before indexing
+            buffer
+                .get_text_range_mut(LOAD_CHUNK_SIZE * 3 / 2, 100)
+                .unwrap();
+
+            let source = buffer.line_index_source().unwrap().clone();
+            let mut batches = Vec::new();
+            scan_line_feeds(&source, &AtomicBool::new(false), |first, counts| {
+                batches.extend(counts.into_iter().enumerate().map(|(i, c)| (first + i, c)));
+                true
+            })
+            .unwrap();
+            assert_eq!(batches.len(), 3);
+
+            // Line numbers stay estimated until every chunk is counted
+            let (chunk, count) = batches[0];
+            buffer
+                .apply_line_feed_counts(&source, chunk, &[count])
+                .unwrap();
+            assert_eq!(buffer.line_count(), None);
+            for &(chunk, count) in &batches[1..] {
+                buffer
+                    .apply_line_feed_counts(&source, chunk, &[count])
+                    .unwrap();
+            }
+
+            assert_eq!(buffer.line_count(), Some(line_starts.len()));
+            assert!(buffer.line_index_source().is_none());
+            assert!(!buffer.is_modified());
+            for line in [0, 1, 1000, 40_000, 70_000, line_starts.len() - 2] {
+                let offset = line_starts[line];
+                assert_eq!(buffer.get_line_number(offset), line);
+                assert_eq!(buffer.line_col_to_position(line, 0), offset);
+            }
+
+            // Content is unchanged, and edits keep the line count exact
+            assert_eq!(
+                buffer.get_text_range_mut(0, content.len()).unwrap(),
+                content
+            );
+            buffer.insert_bytes(LOAD_CHUNK_SIZE + 10, b"new\n".to_vec());
+            assert_eq!(buffer.line_count(), Some(line_starts.len() + 1));
+        }
+
+        #[test]
+        fn test_line_feed_counts_ignored_after_saving_over_source() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("big.log");
+            let (content, _) = varied_lines(LOAD_CHUNK_SIZE + 100);
+            std::fs::write(&file_path, &content).unwrap();
+
+            let mut buffer = TextBuffer::load_from_file(&file_path, 1).unwrap();
+            let source = buffer.line_index_source().unwrap().clone();
+            buffer.insert_bytes(0, b"edit\n".to_vec());
+            buffer.save_to_file(&file_path).unwrap();
+
+            // The file was rewritten, so counts scanned from it before no longer apply
+            assert!(buffer.line_index_source().is_none());
+            buffer.apply_line_feed_counts(&source, 0, &[7, 8]).unwrap();
+            assert_eq!(buffer.line_count(), None);
+        }
+
+        #[test]
+        fn test_append_from_file_keeps_saved_state_and_line_count() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("app.log");
+            std::fs::write(&file_path, "one\ntwo\n").unwrap();
+            let mut small = TextBuffer::load_from_file(&file_path, 1024).unwrap();
+
+            let (content, _) = varied_lines(LOAD_CHUNK_SIZE * 3 / 2);
+            let big_path = temp_dir.path().join("big.log");
+            std::fs::write(&big_path, &content[..100]).unwrap();
+            let mut big = TextBuffer::load_from_file(&big_path, 1).unwrap();
+
+            let mut log = std::fs::OpenOptions::new()
+                .append(true)
+                .open(&file_path)
+                .unwrap();
+            log.write_all(b"three\nfour").unwrap();
+            small.append_from_file(&file_path, 8, 10).unwrap();
+            assert_eq!(small.to_string().unwrap(), "one\ntwo\nthree\nfour");
+            assert_eq!(small.line_count(), Some(4));
+            assert!(!small.is_modified());
+            assert!(small.diff_since_saved().equal);
+
+            // Large files append lazily, in chunks
+            std::fs::write(&big_path, &content).unwrap();
+            big.append_from_file(&big_path, 100, content.len() - 100)
+                .unwrap();
+            assert!(big.buffers.iter().all(|b| !b.is_loaded()));
+            assert_eq!(big.get_text_range_mut(0, content.len()).unwrap(), content);
+            assert!(!big.is_modified());
+        }
     }
 
     // ===== Offset to Position Tests =====
@@ -4209,6 +5133,432 @@ mod tests {
             Ok(())
         }
     }
+
+    mod encoding_conversion {
+        use super::*;
+
+        #[test]
+        fn test_load_latin1_file_round_trips() {
+            use tempfile::TempDir;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("latin1.conf");
+            let original =
+                b"# Konfiguration f\xFCr den Server\nname = M\xFCller\nstadt = K\xF6ln\n";
+            std::fs::write(&file_path, original).unwrap();
+
+            let mut buffer =
+                TextBuffer::load_from_file(&file_path, DEFAULT_LARGE_FILE_THRESHOLD).unwrap();
+            assert_eq!(buffer.encoding(), Encoding::Windows1252);
+            assert!(!buffer.is_binary());
+            assert_eq!(
+                buffer.to_string().unwrap(),
+                "# Konfiguration für den Server\nname = Müller\nstadt = Köln\n"
+            );
+
+            buffer.insert(0, "ß");
+            buffer.save_to_file(&file_path).unwrap();
+            let saved = std::fs::read(&file_path).unwrap();
+            assert_eq!(saved[0], 0xDF);
+            assert_eq!(&saved[1..], original);
+        }
+
+        #[test]
+        fn test_load_utf16_file_preserves_bom() {
+            use tempfile::TempDir;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("app.log");
+            let mut original = vec![0xFF, 0xFE];
+            original.extend(
+                "Line 1\r\nLine 2\r\n"
+                    .encode_utf16()
+                    .flat_map(|u| u.to_le_bytes()),
+            );
+            std::fs::write(&file_path, &original).unwrap();
+
+            let mut buffer =
+                TextBuffer::load_from_file(&file_path, DEFAULT_LARGE_FILE_THRESHOLD).unwrap();
+            assert_eq!(buffer.encoding(), Encoding::Utf16Le);
+            assert!(!buffer.is_binary());
+            assert_eq!(buffer.line_ending(), LineEnding::CRLF);
+            assert_eq!(buffer.to_string().unwrap(), "Line 1\r\nLine 2\r\n");
+
+            buffer.save_to_file(&file_path).unwrap();
+            assert_eq!(std::fs::read(&file_path).unwrap(), original);
+        }
+
+        #[test]
+        fn test_save_with_encoding_converts_file() {
+            use tempfile::TempDir;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("text.txt");
+            std::fs::write(&file_path, "Grüße\n").unwrap();
+
+            let mut buffer =
+                TextBuffer::load_from_file(&file_path, DEFAULT_LARGE_FILE_THRESHOLD).unwrap();
+            assert_eq!(buffer.encoding(), Encoding::Utf8);
+
+            buffer.set_encoding(Encoding::Utf8Bom);
+            assert!(buffer.is_modified());
+            buffer.save_to_file(&file_path).unwrap();
+            assert_eq!(
+                std::fs::read(&file_path).unwrap(),
+                b"\xEF\xBB\xBFGr\xC3\xBC\xC3\x9Fe\n"
+            );
+            assert_eq!(buffer.original_encoding(), Encoding::Utf8Bom);
+
+            // Reloading detects the BOM and hides it from the buffer content
+            let buffer =
+                TextBuffer::load_from_file(&file_path, DEFAULT_LARGE_FILE_THRESHOLD).unwrap();
+            assert_eq!(buffer.encoding(), Encoding::Utf8Bom);
+            assert_eq!(buffer.to_string().unwrap(), "Grüße\n");
+        }
+
+        #[test]
+        fn test_raw_bytes_save_skips_conversions() {
+            use tempfile::TempDir;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("data.bin");
+            let original = b"\x00\x01line\r\nmore\n\xff".to_vec();
+            std::fs::write(&file_path, &original).unwrap();
+
+            let mut buffer = TextBuffer::load_from_file_with_encoding(
+                &file_path,
+                DEFAULT_LARGE_FILE_THRESHOLD,
+                Some(Encoding::Utf8),
+            )
+            .unwrap();
+            buffer.set_raw_bytes(true);
+            buffer.set_line_ending(LineEnding::LF);
+            buffer.set_encoding(Encoding::Utf16Le);
+
+            buffer.save_to_file(&file_path).unwrap();
+            assert_eq!(std::fs::read(&file_path).unwrap(), original);
+        }
+
+        #[test]
+        fn test_save_unmappable_character_fails_without_touching_file() {
+            use tempfile::TempDir;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("latin1.txt");
+            std::fs::write(&file_path, b"caf\xE9\n").unwrap();
+
+            let mut buffer =
+                TextBuffer::load_from_file(&file_path, DEFAULT_LARGE_FILE_THRESHOLD).unwrap();
+            assert_eq!(buffer.encoding(), Encoding::Windows1252);
+            buffer.insert(0, "☃");
+
+            assert!(buffer.save_to_file(&file_path).is_err());
+            assert_eq!(std::fs::read(&file_path).unwrap(), b"caf\xE9\n");
+            // The temporary file is cleaned up
+            assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        }
+
+        #[test]
+        fn test_large_utf16_file_loads_lazily_via_spill_file() {
+            use tempfile::TempDir;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("big.log");
+            let text = "línea de registro\n".repeat(2000);
+            let mut original = vec![0xFE, 0xFF];
+            original.extend(text.encode_utf16().flat_map(|u| u.to_be_bytes()));
+            std::fs::write(&file_path, &original).unwrap();
+
+            // Threshold below the file size forces the large file path
+            let mut buffer = TextBuffer::load_from_file(&file_path, 1024).unwrap();
+            assert!(buffer.is_large_file());
+            assert_eq!(buffer.encoding(), Encoding::Utf16Be);
+            assert_eq!(buffer.total_bytes(), text.len());
+            assert_eq!(buffer.file_path(), Some(file_path.as_path()));
+            assert_eq!(
+                buffer.get_text_range_mut(0, 19).unwrap(),
+                "línea de registro\n".as_bytes()
+            );
+
+            buffer.insert(0, "¡");
+            buffer.save_to_file(&file_path).unwrap();
+            let saved = std::fs::read(&file_path).unwrap();
+            assert_eq!(&saved[..4], &[0xFE, 0xFF, 0x00, 0xA1]);
+            assert_eq!(&saved[4..], &original[2..]);
+        }
+
+        #[test]
+        fn test_large_file_reopened_as_utf8_bom_keeps_leading_bytes() {
+            use tempfile::TempDir;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("big.txt");
+            let original = "first line\n".repeat(200);
+            std::fs::write(&file_path, &original).unwrap();
+
+            let mut buffer =
+                TextBuffer::load_from_file_with_encoding(&file_path, 1024, Some(Encoding::Utf8Bom))
+                    .unwrap();
+            assert!(buffer.is_large_file());
+            assert_eq!(buffer.encoding(), Encoding::Utf8Bom);
+            assert_eq!(buffer.total_bytes(), original.len());
+            assert_eq!(
+                buffer.get_text_range_mut(0, 11).unwrap(),
+                "first line\n".as_bytes()
+            );
+
+            // Saving adds the BOM in front of the untouched content
+            buffer.save_to_file(&file_path).unwrap();
+            let saved = std::fs::read(&file_path).unwrap();
+            assert_eq!(&saved[..3], Encoding::Utf8Bom.bom());
+            assert_eq!(&saved[3..], original.as_bytes());
+        }
+
+        #[test]
+        fn test_load_utf16_file_without_bom_saves_without_bom() {
+            use tempfile::TempDir;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("app.log");
+            let original: Vec<u8> = "Line 1\r\nLine 2\r\n"
+                .encode_utf16()
+                .flat_map(|u| u.to_le_bytes())
+                .collect();
+            std::fs::write(&file_path, &original).unwrap();
+
+            let mut buffer =
+                TextBuffer::load_from_file(&file_path, DEFAULT_LARGE_FILE_THRESHOLD).unwrap();
+            assert_eq!(buffer.encoding(), Encoding::Utf16LeNoBom);
+            assert_eq!(buffer.to_string().unwrap(), "Line 1\r\nLine 2\r\n");
+
+            buffer.insert(0, "L");
+            buffer.delete(0..1);
+            buffer.save_to_file(&file_path).unwrap();
+            assert_eq!(std::fs::read(&file_path).unwrap(), original);
+        }
+
+        fn gzip(data: &[u8]) -> Vec<u8> {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data).unwrap();
+            encoder.finish().unwrap()
+        }
+
+        fn gunzip(data: &[u8]) -> Vec<u8> {
+            let mut decoded = Vec::new();
+            flate2::read::GzDecoder::new(data)
+                .read_to_end(&mut decoded)
+                .unwrap();
+            decoded
+        }
+
+        #[test]
+        fn test_gzip_file_is_decompressed_and_recompressed() {
+            use tempfile::TempDir;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("app.log.1.gz");
+            std::fs::write(&file_path, gzip(b"first\nsecond\n")).unwrap();
+
+            let mut buffer = TextBuffer::load_from_file(&file_path, 0).unwrap();
+            assert!(!buffer.is_large_file());
+            assert!(!buffer.is_binary());
+            assert_eq!(buffer.compression(), Some(Compression::Gzip));
+            assert_eq!(buffer.to_string().unwrap(), "first\nsecond\n");
+
+            buffer.insert(0, "zeroth\n");
+            buffer.save_to_file(&file_path).unwrap();
+            let saved = std::fs::read(&file_path).unwrap();
+            assert_eq!(&saved[..2], &[0x1F, 0x8B]);
+            assert_eq!(gunzip(&saved), b"zeroth\nfirst\nsecond\n");
+        }
+
+        #[test]
+        fn test_large_gzip_file_loads_lazily_via_spill_file() {
+            use tempfile::TempDir;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("big.log.gz");
+            let text = "2026-10-17 INFO request served\n".repeat(2000);
+            let compressed = gzip(text.as_bytes());
+            std::fs::write(&file_path, &compressed).unwrap();
+
+            // The compressed file is below the threshold, the content is not
+            assert!(compressed.len() < 4096);
+            let mut buffer = TextBuffer::load_from_file(&file_path, 4096).unwrap();
+            assert!(buffer.is_large_file());
+            assert_eq!(buffer.compression(), Some(Compression::Gzip));
+            assert_eq!(buffer.total_bytes(), text.len());
+            assert_eq!(buffer.file_path(), Some(file_path.as_path()));
+            assert_eq!(
+                buffer.get_text_range_mut(0, 31).unwrap(),
+                b"2026-10-17 INFO request served\n"
+            );
+
+            buffer.insert(0, "# rotated\n");
+            buffer.save_to_file(&file_path).unwrap();
+            let saved = gunzip(&std::fs::read(&file_path).unwrap());
+            assert_eq!(saved, format!("# rotated\n{}", text).into_bytes());
+        }
+    }
+
+    mod save_strategies {
+        use super::*;
+        use crate::model::save_options::BackupPolicy;
+        use tempfile::TempDir;
+
+        fn save_with(buffer: &mut TextBuffer, path: &Path, strategy: SaveStrategy) {
+            let options = SaveOptions {
+                strategy,
+                ..Default::default()
+            };
+            buffer.save_to_file_with_options(path, &options).unwrap();
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn test_in_place_and_copy_keep_inode_hard_links_and_permissions() {
+            use std::os::unix::fs::PermissionsExt;
+
+            for strategy in [SaveStrategy::InPlace, SaveStrategy::Copy] {
+                let temp_dir = TempDir::new().unwrap();
+                let file_path = temp_dir.path().join("app.conf");
+                let link_path = temp_dir.path().join("link.conf");
+                std::fs::write(&file_path, "port = 80\n").unwrap();
+                std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o640))
+                    .unwrap();
+                std::fs::hard_link(&file_path, &link_path).unwrap();
+                let inode = std::fs::metadata(&file_path).unwrap().ino();
+
+                let mut buffer =
+                    TextBuffer::load_from_file(&file_path, DEFAULT_LARGE_FILE_THRESHOLD).unwrap();
+                buffer.insert(0, "# edited\n");
+                save_with(&mut buffer, &file_path, strategy);
+
+                let metadata = std::fs::metadata(&file_path).unwrap();
+                assert_eq!(metadata.ino(), inode, "{:?}", strategy);
+                assert_eq!(metadata.mode() & 0o777, 0o640, "{:?}", strategy);
+                assert_eq!(
+                    std::fs::read_to_string(&link_path).unwrap(),
+                    "# edited\nport = 80\n"
+                );
+                assert!(!buffer.is_modified());
+            }
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn test_atomic_save_replaces_inode_and_keeps_permissions() {
+            use std::os::unix::fs::PermissionsExt;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("script.sh");
+            std::fs::write(&file_path, "echo hi\n").unwrap();
+            std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o750)).unwrap();
+            let inode = std::fs::metadata(&file_path).unwrap().ino();
+
+            let mut buffer =
+                TextBuffer::load_from_file(&file_path, DEFAULT_LARGE_FILE_THRESHOLD).unwrap();
+            buffer.insert(0, "#!/bin/sh\n");
+            save_with(&mut buffer, &file_path, SaveStrategy::Atomic);
+
+            let metadata = std::fs::metadata(&file_path).unwrap();
+            assert_ne!(metadata.ino(), inode);
+            assert_eq!(metadata.mode() & 0o777, 0o750);
+            assert_eq!(
+                std::fs::read_to_string(&file_path).unwrap(),
+                "#!/bin/sh\necho hi\n"
+            );
+        }
+
+        #[test]
+        fn test_in_place_save_of_streamed_large_file_keeps_content() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("big.log");
+            let text = "2026-10-17 INFO request served\n".repeat(2000);
+            std::fs::write(&file_path, &text).unwrap();
+
+            // The unedited content is streamed from the file being overwritten
+            let mut buffer = TextBuffer::load_from_file(&file_path, 4096).unwrap();
+            assert!(buffer.is_large_file());
+            buffer.insert(0, "# rotated\n");
+            save_with(&mut buffer, &file_path, SaveStrategy::InPlace);
+
+            assert_eq!(
+                std::fs::read_to_string(&file_path).unwrap(),
+                format!("# rotated\n{}", text)
+            );
+        }
+
+        #[test]
+        fn test_save_backs_up_previous_version() {
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("notes.txt");
+            std::fs::write(&file_path, "first\n").unwrap();
+
+            let mut buffer =
+                TextBuffer::load_from_file(&file_path, DEFAULT_LARGE_FILE_THRESHOLD).unwrap();
+            buffer.insert(0, "second\n");
+            let options = SaveOptions {
+                strategy: SaveStrategy::InPlace,
+                backup: BackupPolicy::Adjacent,
+            };
+            buffer.save_with_options(&options).unwrap();
+
+            assert_eq!(
+                std::fs::read_to_string(temp_dir.path().join("notes.txt~")).unwrap(),
+                "first\n"
+            );
+            assert_eq!(
+                std::fs::read_to_string(&file_path).unwrap(),
+                "second\nfirst\n"
+            );
+        }
+
+        #[test]
+        #[cfg(target_os = "linux")]
+        fn test_atomic_save_keeps_extended_attributes() {
+            use std::ffi::CString;
+            use std::os::unix::ffi::OsStrExt;
+
+            let temp_dir = TempDir::new().unwrap();
+            let file_path = temp_dir.path().join("tagged.txt");
+            std::fs::write(&file_path, "content\n").unwrap();
+            let c_path = CString::new(file_path.as_os_str().as_bytes()).unwrap();
+            let name = CString::new("user.fresh.test").unwrap();
+            let value = b"kept";
+            let set = unsafe {
+                libc::setxattr(
+                    c_path.as_ptr(),
+                    name.as_ptr(),
+                    value.as_ptr().cast(),
+                    value.len(),
+                    0,
+                )
+            };
+            if set != 0 {
+                // The file system doesn't support user attributes
+                return;
+            }
+
+            let mut buffer =
+                TextBuffer::load_from_file(&file_path, DEFAULT_LARGE_FILE_THRESHOLD).unwrap();
+            buffer.insert(0, "more ");
+            save_with(&mut buffer, &file_path, SaveStrategy::Atomic);
+
+            let mut read = [0u8; 16];
+            let len = unsafe {
+                libc::getxattr(
+                    c_path.as_ptr(),
+                    name.as_ptr(),
+                    read.as_mut_ptr().cast(),
+                    read.len(),
+                )
+            };
+            assert_eq!(len, value.len() as isize);
+            assert_eq!(&read[..value.len()], value);
+        }
+    }
 }
 
 #[cfg(test)]
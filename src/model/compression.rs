@@ -0,0 +1,266 @@
+//! Compressed file containers
+//!
+//! Files compressed with gzip, bzip2, xz or zstd are decompressed when loaded
+//! and recompressed with the same codec when saved, so that rotated logs such
+//! as `app.log.1.gz` can be read and edited like plain text.
+//!
+//! All codecs are streamed in-process, so no command line tools are needed.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::path::Path;
+
+/// Number of leading bytes needed to recognize every supported format
+pub const MAGIC_SIZE: usize = 6;
+
+/// Compression format of a file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip (`.gz`)
+    Gzip,
+    /// bzip2 (`.bz2`)
+    Bzip2,
+    /// xz (`.xz`)
+    Xz,
+    /// Zstandard (`.zst`)
+    Zstd,
+}
+
+impl Compression {
+    /// Recognize a format from the leading bytes of a file
+    pub fn from_magic(header: &[u8]) -> Option<Self> {
+        if header.starts_with(&[0x1F, 0x8B]) {
+            Some(Self::Gzip)
+        } else if header.starts_with(b"BZh") {
+            Some(Self::Bzip2)
+        } else if header.starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(Self::Xz)
+        } else if header.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Recognize a format from the extension of a file name
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "gz" => Some(Self::Gzip),
+            "bz2" => Some(Self::Bzip2),
+            "xz" => Some(Self::Xz),
+            "zst" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Detect the format of the file at `path`.
+    ///
+    /// The magic bytes decide for files with content, so a plain text file that
+    /// merely has a compressed extension still opens as text. Empty files fall
+    /// back to the extension, so that they are compressed once written.
+    pub fn detect_file(path: &Path) -> io::Result<Option<Self>> {
+        let mut header = Vec::with_capacity(MAGIC_SIZE);
+        File::open(path)?
+            .take(MAGIC_SIZE as u64)
+            .read_to_end(&mut header)?;
+        if header.is_empty() {
+            Ok(Self::from_extension(path))
+        } else {
+            Ok(Self::from_magic(&header))
+        }
+    }
+
+    /// Short name shown in the status bar
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Bzip2 => "bzip2",
+            Self::Xz => "xz",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Stream-decompress `input` into `writer`.
+    ///
+    /// Returns the number of decompressed bytes written.
+    pub fn decompress<W: Write>(&self, input: File, writer: &mut W) -> io::Result<usize> {
+        let input = BufReader::new(input);
+        // Rotated logs are often concatenated members, which are all decoded
+        let mut reader: Box<dyn Read> = match self {
+            Self::Gzip => Box::new(flate2::read::MultiGzDecoder::new(input)),
+            Self::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(input)),
+            Self::Xz => Box::new(liblzma::read::XzDecoder::new_multi_decoder(input)),
+            Self::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(input)?),
+        };
+        Ok(io::copy(&mut reader, writer)? as usize)
+    }
+}
+
+/// A writer that compresses its input into a file.
+///
+/// Without a compression format the input is written to the file unchanged.
+pub enum CompressWriter {
+    Plain(File),
+    Gzip(flate2::write::GzEncoder<File>),
+    Bzip2(bzip2::write::BzEncoder<File>),
+    Xz(liblzma::write::XzEncoder<File>),
+    Zstd(zstd::stream::write::Encoder<'static, File>),
+}
+
+impl CompressWriter {
+    pub fn new(file: File, compression: Option<Compression>) -> io::Result<Self> {
+        Ok(match compression {
+            None => Self::Plain(file),
+            Some(Compression::Gzip) => Self::Gzip(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::default(),
+            )),
+            Some(Compression::Bzip2) => Self::Bzip2(bzip2::write::BzEncoder::new(
+                file,
+                bzip2::Compression::default(),
+            )),
+            // Preset 6 is the default of the xz tool
+            Some(Compression::Xz) => Self::Xz(liblzma::write::XzEncoder::new(file, 6)),
+            Some(Compression::Zstd) => Self::Zstd(zstd::stream::write::Encoder::new(
+                file,
+                zstd::DEFAULT_COMPRESSION_LEVEL,
+            )?),
+        })
+    }
+
+    /// Finish compressing and return the underlying file
+    pub fn finish(self) -> io::Result<File> {
+        match self {
+            Self::Plain(file) => Ok(file),
+            Self::Gzip(encoder) => encoder.finish(),
+            Self::Bzip2(encoder) => encoder.finish(),
+            Self::Xz(encoder) => encoder.finish(),
+            Self::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl Write for CompressWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Bzip2(encoder) => encoder.write(buf),
+            Self::Xz(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(file) => file.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Bzip2(encoder) => encoder.flush(),
+            Self::Xz(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_from_magic_and_extension() {
+        assert_eq!(
+            Compression::from_magic(&[0x1F, 0x8B, 0x08]),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::from_magic(b"BZh91AY"),
+            Some(Compression::Bzip2)
+        );
+        assert_eq!(
+            Compression::from_magic(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]),
+            Some(Compression::Xz)
+        );
+        assert_eq!(
+            Compression::from_magic(&[0x28, 0xB5, 0x2F, 0xFD]),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::from_magic(b"plain text"), None);
+
+        assert_eq!(
+            Compression::from_extension(Path::new("app.log.1.GZ")),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::from_extension(Path::new("dump.zst")),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::from_extension(Path::new("app.log")), None);
+    }
+
+    #[test]
+    fn test_detect_file_prefers_magic_bytes() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Plain text with a compressed extension is not compressed
+        let mislabeled = temp_dir.path().join("notes.gz");
+        std::fs::write(&mislabeled, "just text\n").unwrap();
+        assert_eq!(Compression::detect_file(&mislabeled).unwrap(), None);
+
+        // Empty files use the extension
+        let empty = temp_dir.path().join("new.xz");
+        std::fs::write(&empty, "").unwrap();
+        assert_eq!(
+            Compression::detect_file(&empty).unwrap(),
+            Some(Compression::Xz)
+        );
+    }
+
+    #[test]
+    fn test_round_trip_all_codecs() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = "2026-10-17 12:00:00 INFO started\n".repeat(1000);
+
+        for compression in [
+            Compression::Gzip,
+            Compression::Bzip2,
+            Compression::Xz,
+            Compression::Zstd,
+        ] {
+            let path = temp_dir.path().join(compression.display_name());
+            let mut writer =
+                CompressWriter::new(File::create(&path).unwrap(), Some(compression)).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+            writer.finish().unwrap();
+
+            assert_eq!(Compression::detect_file(&path).unwrap(), Some(compression));
+            let mut decompressed = Vec::new();
+            let size = compression
+                .decompress(File::open(&path).unwrap(), &mut decompressed)
+                .unwrap();
+            assert_eq!(size, content.len());
+            assert_eq!(decompressed, content.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_decompress_concatenated_gzip_members() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("rotated.gz");
+        let mut data = Vec::new();
+        for part in ["first\n", "second\n"] {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(part.as_bytes()).unwrap();
+            data.extend(encoder.finish().unwrap());
+        }
+        std::fs::write(&path, data).unwrap();
+
+        let mut decompressed = Vec::new();
+        Compression::Gzip
+            .decompress(File::open(&path).unwrap(), &mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, b"first\nsecond\n");
+    }
+}
@@ -0,0 +1,670 @@
+//! Character encoding detection and transcoding
+//!
+//! Buffers always hold UTF-8 internally. Files in other encodings are decoded
+//! to UTF-8 when loaded and encoded back to their original encoding (including
+//! any byte order mark) when saved.
+
+use std::io::{self, Read, Write};
+
+/// UTF-8 byte order mark
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+/// UTF-16 little-endian byte order mark
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+/// UTF-16 big-endian byte order mark
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Size of the sample inspected when detecting the encoding of a file
+pub const ENCODING_SAMPLE_SIZE: usize = 64 * 1024;
+
+/// Character encoding of a file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// UTF-8 without a byte order mark
+    #[default]
+    Utf8,
+    /// UTF-8 with a byte order mark
+    Utf8Bom,
+    /// UTF-16 little-endian (written with a byte order mark)
+    Utf16Le,
+    /// UTF-16 big-endian (written with a byte order mark)
+    Utf16Be,
+    /// UTF-16 little-endian without a byte order mark
+    Utf16LeNoBom,
+    /// UTF-16 big-endian without a byte order mark
+    Utf16BeNoBom,
+    /// Windows-1252, the de facto superset of ISO-8859-1 (Latin-1)
+    Windows1252,
+    /// ISO-8859-2 (Central European)
+    Iso8859_2,
+    /// ISO-8859-15 (Latin-9)
+    Iso8859_15,
+    /// Windows-1250 (Central European)
+    Windows1250,
+    /// Windows-1251 (Cyrillic)
+    Windows1251,
+    /// KOI8-R (Cyrillic)
+    Koi8R,
+    /// Shift_JIS (Japanese)
+    ShiftJis,
+    /// EUC-JP (Japanese)
+    EucJp,
+    /// GBK (Simplified Chinese)
+    Gbk,
+    /// GB18030 (Simplified Chinese)
+    Gb18030,
+    /// Big5 (Traditional Chinese)
+    Big5,
+    /// EUC-KR (Korean)
+    EucKr,
+}
+
+impl Encoding {
+    /// All encodings that can be selected by the user, in display order
+    pub const ALL: &'static [Encoding] = &[
+        Encoding::Utf8,
+        Encoding::Utf8Bom,
+        Encoding::Utf16Le,
+        Encoding::Utf16Be,
+        Encoding::Utf16LeNoBom,
+        Encoding::Utf16BeNoBom,
+        Encoding::Windows1252,
+        Encoding::Iso8859_2,
+        Encoding::Iso8859_15,
+        Encoding::Windows1250,
+        Encoding::Windows1251,
+        Encoding::Koi8R,
+        Encoding::ShiftJis,
+        Encoding::EucJp,
+        Encoding::Gbk,
+        Encoding::Gb18030,
+        Encoding::Big5,
+        Encoding::EucKr,
+    ];
+
+    /// Get the display name for status bar and prompts
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf8Bom => "UTF-8 BOM",
+            Self::Utf16Le => "UTF-16 LE",
+            Self::Utf16Be => "UTF-16 BE",
+            Self::Utf16LeNoBom => "UTF-16 LE (no BOM)",
+            Self::Utf16BeNoBom => "UTF-16 BE (no BOM)",
+            Self::Windows1252 => "Latin-1",
+            Self::Iso8859_2 => "ISO-8859-2",
+            Self::Iso8859_15 => "ISO-8859-15",
+            Self::Windows1250 => "Windows-1250",
+            Self::Windows1251 => "Windows-1251",
+            Self::Koi8R => "KOI8-R",
+            Self::ShiftJis => "Shift_JIS",
+            Self::EucJp => "EUC-JP",
+            Self::Gbk => "GBK",
+            Self::Gb18030 => "GB18030",
+            Self::Big5 => "Big5",
+            Self::EucKr => "EUC-KR",
+        }
+    }
+
+    /// Get a short human-readable description of the encoding
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "Unicode",
+            Self::Utf8Bom => "Unicode with byte order mark",
+            Self::Utf16Le => "Unicode, little-endian",
+            Self::Utf16Be => "Unicode, big-endian",
+            Self::Utf16LeNoBom => "Unicode, little-endian without byte order mark",
+            Self::Utf16BeNoBom => "Unicode, big-endian without byte order mark",
+            Self::Windows1252 => "Western European (Windows-1252)",
+            Self::Iso8859_2 | Self::Windows1250 => "Central European",
+            Self::Iso8859_15 => "Western European (Latin-9)",
+            Self::Windows1251 | Self::Koi8R => "Cyrillic",
+            Self::ShiftJis | Self::EucJp => "Japanese",
+            Self::Gbk | Self::Gb18030 => "Simplified Chinese",
+            Self::Big5 => "Traditional Chinese",
+            Self::EucKr => "Korean",
+        }
+    }
+
+    /// Parse an encoding from its display name or a common label
+    /// (case-insensitive, e.g. "utf-8", "latin1", "sjis", "UTF-16 LE")
+    pub fn from_label(label: &str) -> Option<Self> {
+        let trimmed = label.trim();
+        if let Some(encoding) = Self::ALL
+            .iter()
+            .find(|e| e.display_name().eq_ignore_ascii_case(trimmed))
+        {
+            return Some(*encoding);
+        }
+
+        let normalized: String = trimmed
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        match normalized.as_str() {
+            "utf8" => Some(Self::Utf8),
+            "utf8bom" | "utf8withbom" | "utf8sig" => Some(Self::Utf8Bom),
+            "utf16" | "utf16le" => Some(Self::Utf16Le),
+            "utf16be" => Some(Self::Utf16Be),
+            "utf16lenobom" => Some(Self::Utf16LeNoBom),
+            "utf16benobom" => Some(Self::Utf16BeNoBom),
+            "latin1" | "iso88591" | "windows1252" | "cp1252" => Some(Self::Windows1252),
+            "latin2" | "iso88592" => Some(Self::Iso8859_2),
+            "latin9" | "iso885915" => Some(Self::Iso8859_15),
+            "windows1250" | "cp1250" => Some(Self::Windows1250),
+            "windows1251" | "cp1251" => Some(Self::Windows1251),
+            "koi8r" => Some(Self::Koi8R),
+            "shiftjis" | "sjis" | "cp932" => Some(Self::ShiftJis),
+            "eucjp" => Some(Self::EucJp),
+            "gbk" | "cp936" | "gb2312" => Some(Self::Gbk),
+            "gb18030" => Some(Self::Gb18030),
+            "big5" => Some(Self::Big5),
+            "euckr" | "cp949" => Some(Self::EucKr),
+            _ => None,
+        }
+    }
+
+    /// Byte order mark written at the start of the file for this encoding
+    pub fn bom(&self) -> &'static [u8] {
+        match self {
+            Self::Utf8Bom => UTF8_BOM,
+            Self::Utf16Le => UTF16LE_BOM,
+            Self::Utf16Be => UTF16BE_BOM,
+            _ => &[],
+        }
+    }
+
+    /// Is this a UTF-8 encoding (bytes can be used as-is apart from the BOM)?
+    pub fn is_utf8(&self) -> bool {
+        matches!(self, Self::Utf8 | Self::Utf8Bom)
+    }
+
+    /// Is this a UTF-16 encoding, with or without a BOM?
+    pub fn is_utf16(&self) -> bool {
+        matches!(
+            self,
+            Self::Utf16Le | Self::Utf16Be | Self::Utf16LeNoBom | Self::Utf16BeNoBom
+        )
+    }
+
+    /// The underlying encoding_rs codec
+    fn codec(&self) -> &'static encoding_rs::Encoding {
+        match self {
+            Self::Utf8 | Self::Utf8Bom => encoding_rs::UTF_8,
+            Self::Utf16Le | Self::Utf16LeNoBom => encoding_rs::UTF_16LE,
+            Self::Utf16Be | Self::Utf16BeNoBom => encoding_rs::UTF_16BE,
+            Self::Windows1252 => encoding_rs::WINDOWS_1252,
+            Self::Iso8859_2 => encoding_rs::ISO_8859_2,
+            Self::Iso8859_15 => encoding_rs::ISO_8859_15,
+            Self::Windows1250 => encoding_rs::WINDOWS_1250,
+            Self::Windows1251 => encoding_rs::WINDOWS_1251,
+            Self::Koi8R => encoding_rs::KOI8_R,
+            Self::ShiftJis => encoding_rs::SHIFT_JIS,
+            Self::EucJp => encoding_rs::EUC_JP,
+            Self::Gbk => encoding_rs::GBK,
+            Self::Gb18030 => encoding_rs::GB18030,
+            Self::Big5 => encoding_rs::BIG5,
+            Self::EucKr => encoding_rs::EUC_KR,
+        }
+    }
+
+    /// Map a guess from the statistical detector back to a supported encoding
+    fn from_codec(codec: &'static encoding_rs::Encoding) -> Self {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(|e| !matches!(e, Self::Utf8Bom | Self::Utf16LeNoBom | Self::Utf16BeNoBom))
+            .find(|e| e.codec() == codec)
+            // ISO-8859-1 and unsupported single-byte encodings fall back to Latin-1,
+            // which never fails to decode and round-trips every byte.
+            .unwrap_or(Self::Windows1252)
+    }
+
+    /// Detect the encoding of a file from a sample of its leading bytes.
+    ///
+    /// Detection order:
+    /// 1. Byte order mark (UTF-8, UTF-16 LE/BE)
+    /// 2. UTF-16 without BOM (one zero byte in every pair of mostly-ASCII text)
+    /// 3. Valid UTF-8, or binary content (binary files are kept as raw bytes)
+    /// 4. Statistical guess between legacy encodings
+    ///
+    /// `truncated` indicates that the sample is only a prefix of the file, so an
+    /// incomplete multi-byte sequence at the end of the sample is not an error.
+    pub fn detect(sample: &[u8], truncated: bool) -> Self {
+        if sample.starts_with(UTF8_BOM) {
+            return Self::Utf8Bom;
+        }
+        if sample.starts_with(UTF16LE_BOM) {
+            return Self::Utf16Le;
+        }
+        if sample.starts_with(UTF16BE_BOM) {
+            return Self::Utf16Be;
+        }
+
+        if let Some(encoding) = Self::detect_utf16_without_bom(sample) {
+            return encoding;
+        }
+
+        match std::str::from_utf8(sample) {
+            Ok(_) => return Self::Utf8,
+            // Sample cut in the middle of a multi-byte character
+            Err(e) if truncated && e.error_len().is_none() => return Self::Utf8,
+            Err(_) => {}
+        }
+
+        // Control bytes that never appear in text mean binary content: leave it undecoded
+        if sample
+            .iter()
+            .any(|&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0B | 0x0C | 0x1B))
+        {
+            return Self::Utf8;
+        }
+
+        let mut detector = chardetng::EncodingDetector::new();
+        detector.feed(sample, !truncated);
+        Self::from_codec(detector.guess(None, true))
+    }
+
+    /// Detect UTF-16 text without a BOM by looking for the characteristic
+    /// pattern of zero high bytes in ASCII-heavy text.
+    fn detect_utf16_without_bom(sample: &[u8]) -> Option<Self> {
+        let pairs = sample.len() / 2;
+        if pairs < 4 {
+            return None;
+        }
+        let (mut even_zeros, mut odd_zeros) = (0usize, 0usize);
+        for pair in sample.chunks_exact(2) {
+            if pair[0] == 0 {
+                even_zeros += 1;
+            }
+            if pair[1] == 0 {
+                odd_zeros += 1;
+            }
+        }
+        // Require most pairs to have a zero on one side and almost none on the other
+        let threshold = pairs * 2 / 5;
+        let noise = pairs / 20;
+        if odd_zeros > threshold && even_zeros <= noise {
+            Some(Self::Utf16LeNoBom)
+        } else if even_zeros > threshold && odd_zeros <= noise {
+            Some(Self::Utf16BeNoBom)
+        } else {
+            None
+        }
+    }
+
+    /// Decode the complete contents of a file into UTF-8 bytes.
+    ///
+    /// A leading BOM matching the encoding is stripped. Malformed sequences are
+    /// replaced with U+FFFD.
+    pub fn decode(&self, bytes: &[u8]) -> Vec<u8> {
+        let bytes = bytes.strip_prefix(self.bom()).unwrap_or(bytes);
+        match self {
+            Self::Utf8 | Self::Utf8Bom => bytes.to_vec(),
+            _ => {
+                let (text, _had_errors) = self.codec().decode_without_bom_handling(bytes);
+                text.into_owned().into_bytes()
+            }
+        }
+    }
+
+    /// Stream-decode `reader` into UTF-8 bytes written to `writer`.
+    ///
+    /// Used for large files, where the decoded content is spilled to disk
+    /// instead of being held in memory. Returns the number of bytes written.
+    pub fn transcode_to_utf8<R: Read, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+    ) -> io::Result<usize> {
+        const CHUNK_SIZE: usize = 64 * 1024;
+        let mut decoder = self.codec().new_decoder_with_bom_removal();
+        let mut input = vec![0u8; CHUNK_SIZE];
+        let mut output = vec![0u8; CHUNK_SIZE * 3 + 16];
+        let mut total = 0;
+
+        loop {
+            let read = reader.read(&mut input)?;
+            let last = read == 0;
+            let mut src = &input[..read];
+            loop {
+                let (result, consumed, written, _had_errors) =
+                    decoder.decode_to_utf8(src, &mut output, last);
+                writer.write_all(&output[..written])?;
+                total += written;
+                src = &src[consumed..];
+                if result == encoding_rs::CoderResult::InputEmpty {
+                    break;
+                }
+            }
+            if last {
+                return Ok(total);
+            }
+        }
+    }
+}
+
+/// A writer that encodes UTF-8 input into a target encoding.
+///
+/// Input may be split at arbitrary byte positions (e.g. piece or chunk
+/// boundaries); incomplete UTF-8 sequences are held back until the rest of the
+/// character arrives. The BOM is written before the first byte of output.
+pub struct EncodingWriter<W: Write> {
+    inner: W,
+    encoding: Encoding,
+    encoder: Option<encoding_rs::Encoder>,
+    /// Trailing bytes of an incomplete UTF-8 sequence from the previous write
+    pending: Vec<u8>,
+    bom_written: bool,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    pub fn new(inner: W, encoding: Encoding) -> Self {
+        let encoder = if encoding.is_utf8() || encoding.is_utf16() {
+            None
+        } else {
+            Some(encoding.codec().new_encoder())
+        };
+        Self {
+            inner,
+            encoding,
+            encoder,
+            pending: Vec::new(),
+            bom_written: false,
+        }
+    }
+
+    /// Flush any pending output and return the underlying writer.
+    ///
+    /// Fails if the input ended in the middle of a UTF-8 sequence that cannot
+    /// be represented in the target encoding.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_bom()?;
+        if !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            if self.encoding.is_utf8() {
+                self.inner.write_all(&pending)?;
+            } else {
+                // Incomplete trailing sequence: encode as replacement character
+                self.encode_str("\u{FFFD}")?;
+            }
+        }
+        self.encode_str_last()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    fn write_bom(&mut self) -> io::Result<()> {
+        if !self.bom_written {
+            self.bom_written = true;
+            self.inner.write_all(self.encoding.bom())?;
+        }
+        Ok(())
+    }
+
+    /// Encode a chunk of valid UTF-8 text
+    fn encode_str(&mut self, text: &str) -> io::Result<()> {
+        match self.encoding {
+            Encoding::Utf8 | Encoding::Utf8Bom => self.inner.write_all(text.as_bytes()),
+            Encoding::Utf16Le
+            | Encoding::Utf16Be
+            | Encoding::Utf16LeNoBom
+            | Encoding::Utf16BeNoBom => {
+                let little_endian =
+                    matches!(self.encoding, Encoding::Utf16Le | Encoding::Utf16LeNoBom);
+                let mut out = Vec::with_capacity(text.len() * 2);
+                for unit in text.encode_utf16() {
+                    if little_endian {
+                        out.extend_from_slice(&unit.to_le_bytes());
+                    } else {
+                        out.extend_from_slice(&unit.to_be_bytes());
+                    }
+                }
+                self.inner.write_all(&out)
+            }
+            _ => self.encode_legacy(text, false),
+        }
+    }
+
+    /// Signal end of input to stateful legacy encoders
+    fn encode_str_last(&mut self) -> io::Result<()> {
+        if self.encoder.is_some() {
+            self.encode_legacy("", true)?;
+        }
+        Ok(())
+    }
+
+    fn encode_legacy(&mut self, mut text: &str, last: bool) -> io::Result<()> {
+        let encoding = self.encoding;
+        let Some(encoder) = self.encoder.as_mut() else {
+            return Ok(());
+        };
+        let mut out = vec![0u8; 16 * 1024];
+        loop {
+            let (result, read, written) =
+                encoder.encode_from_utf8_without_replacement(text, &mut out, last);
+            self.inner.write_all(&out[..written])?;
+            text = &text[read..];
+            match result {
+                encoding_rs::EncoderResult::InputEmpty => return Ok(()),
+                encoding_rs::EncoderResult::OutputFull => continue,
+                encoding_rs::EncoderResult::Unmappable(ch) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "character '{}' (U+{:04X}) cannot be represented in {}",
+                            ch,
+                            ch as u32,
+                            encoding.display_name()
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for EncodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_bom()?;
+        if self.encoding.is_utf8() {
+            self.inner.write_all(buf)?;
+            return Ok(buf.len());
+        }
+
+        let mut data = std::mem::take(&mut self.pending);
+        data.extend_from_slice(buf);
+        let mut rest = data.as_slice();
+        while !rest.is_empty() {
+            match std::str::from_utf8(rest) {
+                Ok(text) => {
+                    self.encode_str(text)?;
+                    rest = &[];
+                }
+                Err(e) => {
+                    let valid = e.valid_up_to();
+                    // The prefix up to `valid` was just validated
+                    let text = std::str::from_utf8(&rest[..valid]).unwrap_or_default();
+                    self.encode_str(text)?;
+                    match e.error_len() {
+                        // Incomplete sequence at the end: wait for more input
+                        None => {
+                            self.pending = rest[valid..].to_vec();
+                            rest = &[];
+                        }
+                        // Invalid bytes cannot be encoded: substitute U+FFFD
+                        Some(len) => {
+                            self.encode_str("\u{FFFD}")?;
+                            rest = &rest[valid + len..];
+                        }
+                    }
+                }
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_all(encoding: Encoding, text: &str) -> io::Result<Vec<u8>> {
+        let mut writer = EncodingWriter::new(Vec::new(), encoding);
+        writer.write_all(text.as_bytes())?;
+        writer.finish()
+    }
+
+    #[test]
+    fn test_detect_bom() {
+        assert_eq!(
+            Encoding::detect(b"\xEF\xBB\xBFhello", false),
+            Encoding::Utf8Bom
+        );
+        assert_eq!(
+            Encoding::detect(b"\xFF\xFEh\0i\0", false),
+            Encoding::Utf16Le
+        );
+        assert_eq!(
+            Encoding::detect(b"\xFE\xFF\0h\0i", false),
+            Encoding::Utf16Be
+        );
+    }
+
+    #[test]
+    fn test_detect_utf16_without_bom() {
+        let le: Vec<u8> = "hello world\r\n"
+            .encode_utf16()
+            .flat_map(|u| u.to_le_bytes())
+            .collect();
+        let be: Vec<u8> = "hello world\r\n"
+            .encode_utf16()
+            .flat_map(|u| u.to_be_bytes())
+            .collect();
+        assert_eq!(Encoding::detect(&le, false), Encoding::Utf16LeNoBom);
+        assert_eq!(Encoding::detect(&be, false), Encoding::Utf16BeNoBom);
+    }
+
+    #[test]
+    fn test_utf16_without_bom_round_trips_without_bom() {
+        let text = "hello world\r\nGrüße\r\n";
+        let le: Vec<u8> = text.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        let be: Vec<u8> = text.encode_utf16().flat_map(|u| u.to_be_bytes()).collect();
+        for original in [le, be] {
+            let encoding = Encoding::detect(&original, false);
+            let decoded = encoding.decode(&original);
+            assert_eq!(decoded, text.as_bytes());
+            let encoded = encode_all(encoding, text).unwrap();
+            assert_eq!(encoded, original, "{:?}", encoding);
+        }
+    }
+
+    #[test]
+    fn test_detect_utf8_and_binary() {
+        assert_eq!(
+            Encoding::detect("héllo wörld".as_bytes(), false),
+            Encoding::Utf8
+        );
+        assert_eq!(Encoding::detect(b"", false), Encoding::Utf8);
+        // Truncated in the middle of "é"
+        assert_eq!(Encoding::detect(b"caf\xC3", true), Encoding::Utf8);
+        // Binary content is not transcoded
+        assert_eq!(
+            Encoding::detect(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\xff\xfe\x80", false),
+            Encoding::Utf8
+        );
+        assert_eq!(
+            Encoding::detect(b"\x1f\x8b\x08\x08\xa5\xc3", false),
+            Encoding::Utf8
+        );
+    }
+
+    #[test]
+    fn test_detect_legacy_encodings() {
+        let latin1 = b"# Konfiguration f\xFCr den Server\nname = M\xFCller\nstadt = K\xF6ln\n";
+        assert_eq!(Encoding::detect(latin1, false), Encoding::Windows1252);
+
+        let (sjis, _, _) =
+            encoding_rs::SHIFT_JIS.encode("日本語のテキストファイルです。これはテストです。");
+        assert_eq!(Encoding::detect(&sjis, false), Encoding::ShiftJis);
+
+        let (gbk, _, _) = encoding_rs::GBK.encode("这是一个简体中文的测试文件，用于检测编码。");
+        assert_eq!(Encoding::detect(&gbk, false), Encoding::Gbk);
+    }
+
+    #[test]
+    fn test_decode_strips_bom() {
+        assert_eq!(Encoding::Utf8Bom.decode(b"\xEF\xBB\xBFhi"), b"hi");
+        assert_eq!(Encoding::Utf16Le.decode(b"\xFF\xFEh\0i\0"), b"hi");
+        assert_eq!(Encoding::Utf16Be.decode(b"\0h\0i"), b"hi");
+        assert_eq!(
+            Encoding::Windows1252.decode(b"M\xFCller"),
+            "Müller".as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_all_encodings() {
+        for encoding in Encoding::ALL {
+            let text = match encoding {
+                Encoding::ShiftJis | Encoding::EucJp => "行1\n日本語\n",
+                Encoding::Gbk | Encoding::Gb18030 => "行1\n中文\n",
+                Encoding::Big5 => "第1\n中文\n",
+                Encoding::EucKr => "한국어\n",
+                Encoding::Windows1251 | Encoding::Koi8R => "Привет\n",
+                Encoding::Iso8859_2 | Encoding::Windows1250 => "Łódź\n",
+                _ => "Müller\n",
+            };
+            let encoded = encode_all(*encoding, text).unwrap();
+            assert!(encoded.starts_with(encoding.bom()), "{:?}", encoding);
+            assert_eq!(encoding.decode(&encoded), text.as_bytes(), "{:?}", encoding);
+        }
+    }
+
+    #[test]
+    fn test_writer_handles_split_sequences() {
+        let mut writer = EncodingWriter::new(Vec::new(), Encoding::Utf16Le);
+        let bytes = "aé".as_bytes();
+        // Split "é" (C3 A9) across two writes
+        writer.write_all(&bytes[..2]).unwrap();
+        writer.write_all(&bytes[2..]).unwrap();
+        let out = writer.finish().unwrap();
+        assert_eq!(out, b"\xFF\xFEa\0\xE9\0");
+    }
+
+    #[test]
+    fn test_writer_rejects_unmappable_characters() {
+        let err = encode_all(Encoding::Windows1252, "snowman ☃").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_transcode_to_utf8_streaming() {
+        let text = "Grüße\n".repeat(20_000);
+        let encoded = encode_all(Encoding::Utf16Be, &text).unwrap();
+        let mut out = Vec::new();
+        let written = Encoding::Utf16Be
+            .transcode_to_utf8(&mut encoded.as_slice(), &mut out)
+            .unwrap();
+        assert_eq!(written, text.len());
+        assert_eq!(out, text.as_bytes());
+    }
+
+    #[test]
+    fn test_from_label() {
+        assert_eq!(Encoding::from_label("utf-8"), Some(Encoding::Utf8));
+        assert_eq!(Encoding::from_label("UTF-8 BOM"), Some(Encoding::Utf8Bom));
+        assert_eq!(Encoding::from_label("latin1"), Some(Encoding::Windows1252));
+        assert_eq!(Encoding::from_label("Shift_JIS"), Some(Encoding::ShiftJis));
+        assert_eq!(Encoding::from_label("utf-16le"), Some(Encoding::Utf16Le));
+        assert_eq!(
+            Encoding::from_label("UTF-16 BE (no BOM)"),
+            Some(Encoding::Utf16BeNoBom)
+        );
+        assert_eq!(Encoding::from_label("klingon"), None);
+    }
+}
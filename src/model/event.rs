@@ -1,6 +1,7 @@
 use crate::model::piece_tree::PieceTree;
 use crate::view::overlay::{OverlayHandle, OverlayNamespace};
 use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
 use std::ops::Range;
 use std::sync::Arc;
 
@@ -556,13 +557,49 @@ pub struct Snapshot {
     pub cursor_positions: Vec<(CursorId, usize, Option<usize>)>,
 }
 
-/// The event log - append-only log of all events
+/// A node in the undo tree: one logged event and the state it leads to
+#[derive(Debug, Clone)]
+struct UndoNode {
+    /// The logged event
+    entry: LogEntry,
+
+    /// State this event was applied to
+    parent: usize,
+
+    /// Child state that redo moves to from the state this event leads to
+    redo_child: Option<usize>,
+}
+
+/// A buffer state in the undo tree, as listed by [`EventLog::states`]
+#[derive(Debug, Clone, Copy)]
+pub struct UndoState<'a> {
+    /// State id (0 is the initial state; ids grow in creation order)
+    pub id: usize,
+
+    /// Nearest earlier state this one branched off from (None for the initial state)
+    pub parent: Option<usize>,
+
+    /// The write event that produced this state (None for the initial state)
+    pub entry: Option<&'a LogEntry>,
+}
+
+/// The event log - a tree of all events
+///
+/// States are numbered in creation order: state 0 is the initial state and the
+/// event at `nodes[i]` leads from its parent state to state `i + 1`. Undoing and
+/// then making a new edit starts a new branch instead of discarding the undone
+/// events, so every state the buffer has been in stays reachable. Undo/redo move
+/// along the active branch; the chronological variants and `jump_to` move
+/// across branches.
 pub struct EventLog {
-    /// All logged events
-    entries: Vec<LogEntry>,
+    /// All logged events, in creation order
+    nodes: Vec<UndoNode>,
+
+    /// Current state in the tree (for undo/redo)
+    current: usize,
 
-    /// Current position in the log (for undo/redo)
-    current_index: usize,
+    /// Child state that redo moves to from the initial state
+    root_redo_child: Option<usize>,
 
     /// Periodic snapshots for fast seeking
     snapshots: Vec<Snapshot>,
@@ -573,50 +610,74 @@ pub struct EventLog {
     /// Optional file for streaming events to disk
     stream_file: Option<std::fs::File>,
 
-    /// Index at which the buffer was last saved (for tracking modified status)
-    /// When current_index equals saved_at_index, the buffer is not modified
-    saved_at_index: Option<usize>,
+    /// State in which the buffer was last saved (for tracking modified status)
+    /// When current equals saved_at, the buffer is not modified
+    saved_at: Option<usize>,
+
+    /// Cached states of the active branch, cleared when moving in the tree
+    active_branch: OnceCell<Vec<usize>>,
 }
 
 impl EventLog {
     /// Create a new empty event log
     pub fn new() -> Self {
         Self {
-            entries: Vec::new(),
-            current_index: 0,
+            nodes: Vec::new(),
+            current: 0,
+            root_redo_child: None,
             snapshots: Vec::new(),
             snapshot_interval: 100,
             stream_file: None,
-            saved_at_index: Some(0), // New buffer starts at "saved" state (index 0)
+            saved_at: Some(0), // New buffer starts at "saved" state (state 0)
+            active_branch: OnceCell::new(),
         }
     }
 
     /// Mark the current position as the saved point
     /// Call this when the buffer is saved to disk
     pub fn mark_saved(&mut self) {
-        self.saved_at_index = Some(self.current_index);
+        self.saved_at = Some(self.current);
+    }
+
+    /// Get the index at which the buffer was last saved, if known
+    /// Only available when the saved state lies on the active branch
+    pub fn saved_index(&self) -> Option<usize> {
+        let saved = self.saved_at?;
+        self.active_branch()
+            .iter()
+            .position(|&state| state == saved)
+    }
+
+    /// Move to `index` on the active branch and mark it as the saved point
+    /// Used when restoring a persisted history whose saved state matches the file on disk
+    pub fn restore_saved_position(&mut self, index: usize) {
+        let branch = self.active_branch();
+        let state = branch[index.min(branch.len() - 1)];
+        self.move_to(state);
+        self.saved_at = Some(self.current);
     }
 
     /// Check if the buffer is at the saved position (not modified)
     /// Returns true if we're at the saved position OR if all events between
-    /// saved_at_index and current_index are readonly (don't modify buffer content)
+    /// the saved state and the current state are readonly (don't modify buffer content)
     pub fn is_at_saved_position(&self) -> bool {
-        match self.saved_at_index {
+        match self.saved_at {
             None => false,
-            Some(saved_idx) if saved_idx == self.current_index => true,
-            Some(saved_idx) => {
-                // Check if all events between saved position and current position
-                // are readonly (don't modify buffer content)
-                let (start, end) = if saved_idx < self.current_index {
-                    (saved_idx, self.current_index)
-                } else {
-                    (self.current_index, saved_idx)
-                };
-
-                // All events in range [start, end) must be readonly
-                self.entries[start..end]
-                    .iter()
-                    .all(|entry| !entry.event.modifies_buffer())
+            Some(saved) if saved == self.current => true,
+            Some(saved) => {
+                // Check if all events on the path between the saved state and the
+                // current state are readonly (don't modify buffer content),
+                // stopping at the first one that is not
+                let (mut from, mut to) = (self.current, saved);
+                while from != to {
+                    let state = if from > to { &mut from } else { &mut to };
+                    let node = &self.nodes[*state - 1];
+                    if node.entry.event.modifies_buffer() {
+                        return false;
+                    }
+                    *state = node.parent;
+                }
+                true
             }
         }
     }
@@ -696,18 +757,15 @@ impl EventLog {
     }
 
     /// Append an event to the log
+    /// If we're not at the tip of the active branch, this starts a new branch;
+    /// the undone events stay reachable through the undo tree
     pub fn append(&mut self, event: Event) -> usize {
-        // If we're not at the end, truncate future events
-        if self.current_index < self.entries.len() {
-            self.entries.truncate(self.current_index);
-        }
-
         // Stream event to file if enabled
         if let Some(ref mut file) = self.stream_file {
             use std::io::Write;
 
             let stream_entry = serde_json::json!({
-                "index": self.entries.len(),
+                "index": self.nodes.len(),
                 "timestamp": chrono::Local::now().to_rfc3339(),
                 "event": event,
             });
@@ -721,37 +779,42 @@ impl EventLog {
             }
         }
 
-        let entry = LogEntry::new(event);
-        self.entries.push(entry);
-        self.current_index = self.entries.len();
+        self.nodes.push(UndoNode {
+            entry: LogEntry::new(event),
+            parent: self.current,
+            redo_child: None,
+        });
+        let state = self.nodes.len();
+        self.set_redo_child(self.current, state);
+        self.move_to(state);
 
         // Check if we should create a snapshot
-        if self.entries.len() % self.snapshot_interval == 0 {
+        if self.nodes.len().is_multiple_of(self.snapshot_interval) {
             // Snapshot creation will be implemented when we have Buffer
             // For now, just track that we'd create one here
         }
 
-        self.current_index - 1
+        self.nodes.len() - 1
     }
 
-    /// Get the current event index
+    /// Get the current event index (number of events from the initial state)
     pub fn current_index(&self) -> usize {
-        self.current_index
+        self.depth(self.current)
     }
 
-    /// Get the number of events in the log
+    /// Get the number of events on the active branch
     pub fn len(&self) -> usize {
-        self.entries.len()
+        self.active_branch().len() - 1
     }
 
     /// Can we undo?
     pub fn can_undo(&self) -> bool {
-        self.current_index > 0
+        self.current > 0
     }
 
     /// Can we redo?
     pub fn can_redo(&self) -> bool {
-        self.current_index < self.entries.len()
+        self.redo_child(self.current).is_some()
     }
 
     /// Move back through events (for undo)
@@ -763,19 +826,24 @@ impl EventLog {
 
         // Keep moving backward until we find a write action
         while self.can_undo() && !found_write_action {
-            self.current_index -= 1;
-            let event = &self.entries[self.current_index].event;
+            let state = self.current;
+            let node = &self.nodes[state - 1];
+            let parent = node.parent;
 
             // Check if this is a write action - we'll stop after processing it
-            if event.is_write_action() {
+            if node.entry.event.is_write_action() {
                 found_write_action = true;
             }
 
             // Try to get the inverse of this event
-            if let Some(inverse) = event.inverse() {
+            if let Some(inverse) = node.entry.event.inverse() {
                 inverse_events.push(inverse);
             }
             // If no inverse exists (like MoveCursor), we just skip it
+
+            // Redo should come back down the branch we just left
+            self.set_redo_child(parent, state);
+            self.move_to(parent);
         }
 
         inverse_events
@@ -789,8 +857,8 @@ impl EventLog {
         let mut found_write_action = false;
 
         // Keep moving forward to collect write action and subsequent readonly events
-        while self.can_redo() {
-            let event = self.entries[self.current_index].event.clone();
+        while let Some(child) = self.redo_child(self.current) {
+            let event = self.nodes[child - 1].entry.event.clone();
 
             // If we've already found a write action and this is another write action, stop
             if found_write_action && event.is_write_action() {
@@ -798,7 +866,7 @@ impl EventLog {
                 break;
             }
 
-            self.current_index += 1;
+            self.move_to(child);
 
             // Mark if we found a write action
             if event.is_write_action() {
@@ -811,20 +879,129 @@ impl EventLog {
         events
     }
 
-    /// Get all events from the log
-    pub fn entries(&self) -> &[LogEntry] {
-        &self.entries
+    /// Move to the state created just before the current one, regardless of branch
+    /// (like Vim's `g-`). Returns the events to apply, in order.
+    pub fn undo_chronological(&mut self) -> Vec<Event> {
+        if self.current == 0 {
+            return Vec::new();
+        }
+        let from = self.write_state_of(self.current);
+        let target = (1..from)
+            .rev()
+            .find(|&state| self.is_write_state(state))
+            .unwrap_or(0);
+        self.jump_to(target)
+    }
+
+    /// Move to the state created just after the current one, regardless of branch
+    /// (like Vim's `g+`). Returns the events to apply, in order.
+    pub fn redo_chronological(&mut self) -> Vec<Event> {
+        let from = self.write_state_of(self.current);
+        match (from + 1..=self.nodes.len()).find(|&state| self.is_write_state(state)) {
+            Some(target) => self.jump_to(target),
+            None => Vec::new(),
+        }
+    }
+
+    /// Move to any state in the undo tree. Returns the events to apply, in order:
+    /// the inverses of the events back to the common ancestor, then the events
+    /// down to the target. The target's branch becomes the active branch.
+    pub fn jump_to(&mut self, target: usize) -> Vec<Event> {
+        if target > self.nodes.len() {
+            return Vec::new();
+        }
+        let events = self.events_to(target);
+
+        let (_, down) = self.path(self.current, target);
+        for &state in down.iter().rev() {
+            self.set_redo_child(self.nodes[state - 1].parent, state);
+        }
+        self.move_to(target);
+
+        events
+    }
+
+    /// Events that would move the buffer from the current state to `target`,
+    /// without moving. Used to preview other states.
+    pub fn events_to(&self, target: usize) -> Vec<Event> {
+        if target > self.nodes.len() {
+            return Vec::new();
+        }
+        let (up, down) = self.path(self.current, target);
+        let undo_events = up
+            .iter()
+            .filter_map(|&state| self.nodes[state - 1].entry.event.inverse());
+        let redo_events = down
+            .iter()
+            .rev()
+            .map(|&state| self.nodes[state - 1].entry.event.clone());
+        undo_events.chain(redo_events).collect()
+    }
+
+    /// The current state id
+    pub fn current_state(&self) -> usize {
+        self.current
+    }
+
+    /// The most recently created state, as listed by [`Self::states`]
+    pub fn newest_state(&self) -> usize {
+        (1..=self.nodes.len())
+            .rev()
+            .find(|&state| self.is_write_state(state))
+            .unwrap_or(0)
+    }
+
+    /// The state the buffer content was last saved in, as listed by [`Self::states`]
+    pub fn saved_state(&self) -> Option<usize> {
+        self.saved_at.map(|state| self.write_state_of(state))
+    }
+
+    /// The state the buffer content is in, as listed by [`Self::states`]
+    /// Readonly events after the last write action are skipped.
+    pub fn content_state(&self) -> usize {
+        self.write_state_of(self.current)
+    }
+
+    /// All states produced by write actions, plus the initial state, in creation order
+    pub fn states(&self) -> Vec<UndoState<'_>> {
+        std::iter::once(UndoState {
+            id: 0,
+            parent: None,
+            entry: None,
+        })
+        .chain(
+            (1..=self.nodes.len())
+                .filter(|&state| self.is_write_state(state))
+                .map(|state| {
+                    let node = &self.nodes[state - 1];
+                    UndoState {
+                        id: state,
+                        parent: Some(self.write_state_of(node.parent)),
+                        entry: Some(&node.entry),
+                    }
+                }),
+        )
+        .collect()
+    }
+
+    /// Get all events on the active branch
+    pub fn entries(&self) -> Vec<&LogEntry> {
+        self.active_branch()
+            .iter()
+            .skip(1)
+            .map(|&state| &self.nodes[state - 1].entry)
+            .collect()
     }
 
-    /// Get events in a range
-    pub fn range(&self, range: Range<usize>) -> &[LogEntry] {
-        &self.entries[range]
+    /// Get events in a range of the active branch
+    pub fn range(&self, range: Range<usize>) -> Vec<&LogEntry> {
+        self.entries()[range].to_vec()
     }
 
     /// Get the most recent event
     pub fn last_event(&self) -> Option<&Event> {
-        if self.current_index > 0 {
-            Some(&self.entries[self.current_index - 1].event)
+        if self.current > 0 {
+            Some(&self.nodes[self.current - 1].entry.event)
         } else {
             None
         }
@@ -832,18 +1009,19 @@ impl EventLog {
 
     /// Clear all events (for testing or reset)
     pub fn clear(&mut self) {
-        self.entries.clear();
-        self.current_index = 0;
+        self.nodes.clear();
+        self.root_redo_child = None;
+        self.move_to(0);
         self.snapshots.clear();
     }
 
-    /// Save event log to JSON Lines format
+    /// Save the active branch of the event log to JSON Lines format
     pub fn save_to_file(&self, path: &std::path::Path) -> std::io::Result<()> {
         use std::io::Write;
         let file = std::fs::File::create(path)?;
         let mut writer = std::io::BufWriter::new(file);
 
-        for entry in &self.entries {
+        for entry in self.entries() {
             let json = serde_json::to_string(entry)?;
             writeln!(writer, "{json}")?;
         }
@@ -851,13 +1029,31 @@ impl EventLog {
         Ok(())
     }
 
+    /// Create an event log from existing entries, positioned after the last one
+    pub fn from_entries(entries: Vec<LogEntry>) -> Self {
+        let mut log = Self::new();
+        let count = entries.len();
+        log.nodes = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| UndoNode {
+                entry,
+                parent: i,
+                redo_child: (i + 2 <= count).then_some(i + 2),
+            })
+            .collect();
+        log.root_redo_child = (count > 0).then_some(1);
+        log.move_to(count);
+        log
+    }
+
     /// Load event log from JSON Lines format
     pub fn load_from_file(path: &std::path::Path) -> std::io::Result<Self> {
         use std::io::BufRead;
         let file = std::fs::File::open(path)?;
         let reader = std::io::BufReader::new(file);
 
-        let mut log = Self::new();
+        let mut entries = Vec::new();
 
         for line in reader.lines() {
             let line = line?;
@@ -865,18 +1061,104 @@ impl EventLog {
                 continue;
             }
             let entry: LogEntry = serde_json::from_str(&line)?;
-            log.entries.push(entry);
+            entries.push(entry);
         }
 
-        log.current_index = log.entries.len();
-
-        Ok(log)
+        Ok(Self::from_entries(entries))
     }
 
     /// Set snapshot interval
     pub fn set_snapshot_interval(&mut self, interval: usize) {
         self.snapshot_interval = interval;
     }
+
+    /// Make `state` the current state
+    fn move_to(&mut self, state: usize) {
+        self.current = state;
+        self.active_branch.take();
+    }
+
+    /// Child state that redo moves to from `state`
+    fn redo_child(&self, state: usize) -> Option<usize> {
+        if state == 0 {
+            self.root_redo_child
+        } else {
+            self.nodes[state - 1].redo_child
+        }
+    }
+
+    fn set_redo_child(&mut self, state: usize, child: usize) {
+        if state == 0 {
+            self.root_redo_child = Some(child);
+        } else {
+            self.nodes[state - 1].redo_child = Some(child);
+        }
+        self.active_branch.take();
+    }
+
+    /// Number of events between the initial state and `state`
+    fn depth(&self, mut state: usize) -> usize {
+        let mut depth = 0;
+        while state > 0 {
+            state = self.nodes[state - 1].parent;
+            depth += 1;
+        }
+        depth
+    }
+
+    /// Whether `state` was produced by a write action
+    fn is_write_state(&self, state: usize) -> bool {
+        state > 0 && self.nodes[state - 1].entry.event.is_write_action()
+    }
+
+    /// Nearest state at or above `state` that was produced by a write action
+    fn write_state_of(&self, mut state: usize) -> usize {
+        while state > 0 && !self.is_write_state(state) {
+            state = self.nodes[state - 1].parent;
+        }
+        state
+    }
+
+    /// States from the initial state through the current state to the end of
+    /// the redo chain
+    fn active_branch(&self) -> &[usize] {
+        self.active_branch.get_or_init(|| {
+            let mut branch = vec![self.current];
+            let mut state = self.current;
+            while state > 0 {
+                state = self.nodes[state - 1].parent;
+                branch.push(state);
+            }
+            branch.reverse();
+
+            let mut state = self.current;
+            while let Some(child) = self.redo_child(state) {
+                branch.push(child);
+                state = child;
+            }
+            branch
+        })
+    }
+
+    /// Path between two states through their common ancestor
+    /// Returns the states left going up from `from` (nearest first) and the
+    /// states entered going down to `to` (nearest to `to` first)
+    fn path(&self, mut from: usize, mut to: usize) -> (Vec<usize>, Vec<usize>) {
+        let mut up = Vec::new();
+        let mut down = Vec::new();
+        // Parents always have lower ids, so the higher of the two states cannot
+        // be the common ancestor and moves up first
+        while from != to {
+            if from > to {
+                up.push(from);
+                from = self.nodes[from - 1].parent;
+            } else {
+                down.push(to);
+                to = self.nodes[to - 1].parent;
+            }
+        }
+        (up, down)
+    }
 }
 
 impl Default for EventLog {
@@ -971,7 +1253,7 @@ mod tests {
                 assert_eq!(redo_count, events.len());
             }
 
-            /// Appending after undo should truncate redo history
+            /// Appending after undo should start a new branch with nothing to redo
             #[test]
             fn append_after_undo_truncates(
                 initial_events in prop::collection::vec(arb_event(), 2..10),
@@ -1069,7 +1351,7 @@ mod tests {
     }
 
     #[test]
-    fn test_truncate_on_new_event_after_undo() {
+    fn test_new_event_after_undo_starts_branch() {
         let mut log = EventLog::new();
 
         log.append(Event::Insert {
@@ -1087,7 +1369,7 @@ mod tests {
         log.undo();
         assert_eq!(log.entries().len(), 2);
 
-        // Adding new event should truncate the future
+        // Adding new event replaces the redo tail of the active branch
         log.append(Event::Insert {
             position: 1,
             text: "c".to_string(),
@@ -1096,5 +1378,163 @@ mod tests {
 
         assert_eq!(log.entries().len(), 2);
         assert_eq!(log.current_index(), 2);
+        assert!(!log.can_redo());
+
+        // ...but the undone branch is still in the tree
+        let states = log.states();
+        assert_eq!(states.len(), 4);
+        assert_eq!(states[2].parent, Some(1));
+        assert_eq!(states[3].parent, Some(1));
+    }
+
+    fn insert(position: usize, text: &str) -> Event {
+        Event::Insert {
+            position,
+            text: text.to_string(),
+            cursor_id: CursorId(0),
+        }
+    }
+
+    fn inserted_text(events: &[Event]) -> Vec<String> {
+        events
+            .iter()
+            .map(|e| match e {
+                Event::Insert { text, .. } => format!("+{text}"),
+                Event::Delete { deleted_text, .. } => format!("-{deleted_text}"),
+                other => format!("{other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chronological_undo_redo_crosses_branches() {
+        let mut log = EventLog::new();
+        log.append(insert(0, "a")); // state 1
+        log.append(insert(1, "b")); // state 2
+        log.undo();
+        log.append(insert(1, "c")); // state 3, sibling of 2
+
+        // Regular undo only walks the active branch
+        assert_eq!(log.current_state(), 3);
+
+        // Chronological undo visits state 2 even though it's on another branch
+        let events = log.undo_chronological();
+        assert_eq!(inserted_text(&events), vec!["-c", "+b"]);
+        assert_eq!(log.current_state(), 2);
+
+        let events = log.undo_chronological();
+        assert_eq!(inserted_text(&events), vec!["-b"]);
+        assert_eq!(log.current_state(), 1);
+
+        let events = log.undo_chronological();
+        assert_eq!(inserted_text(&events), vec!["-a"]);
+        assert_eq!(log.current_state(), 0);
+        assert!(log.undo_chronological().is_empty());
+
+        // And back again in creation order
+        log.redo_chronological();
+        assert_eq!(log.current_state(), 1);
+        log.redo_chronological();
+        assert_eq!(log.current_state(), 2);
+        let events = log.redo_chronological();
+        assert_eq!(inserted_text(&events), vec!["-b", "+c"]);
+        assert_eq!(log.current_state(), 3);
+        assert!(log.redo_chronological().is_empty());
+    }
+
+    #[test]
+    fn test_jump_to_switches_active_branch() {
+        let mut log = EventLog::new();
+        log.append(insert(0, "a")); // state 1
+        log.append(insert(1, "b")); // state 2
+        log.undo();
+        log.append(insert(1, "c")); // state 3
+
+        // Previewing doesn't move
+        assert_eq!(inserted_text(&log.events_to(2)), vec!["-c", "+b"]);
+        assert_eq!(log.current_state(), 3);
+
+        let events = log.jump_to(2);
+        assert_eq!(inserted_text(&events), vec!["-c", "+b"]);
+        assert_eq!(log.current_state(), 2);
+
+        // Undo and redo now follow the branch we jumped to
+        assert_eq!(inserted_text(&log.undo()), vec!["-b"]);
+        assert_eq!(inserted_text(&log.redo()), vec!["+b"]);
+
+        // Jumping to an unknown state does nothing
+        assert!(log.jump_to(42).is_empty());
+        assert_eq!(log.current_state(), 2);
+    }
+
+    #[test]
+    fn test_saved_position_across_branches() {
+        let mut log = EventLog::new();
+        log.append(insert(0, "a")); // state 1
+        log.append(insert(1, "b")); // state 2
+        log.mark_saved();
+        assert_eq!(log.saved_state(), Some(2));
+
+        log.undo();
+        log.append(insert(1, "c")); // state 3
+        assert!(!log.is_at_saved_position());
+        // The saved state is no longer on the active branch
+        assert_eq!(log.saved_index(), None);
+
+        log.jump_to(2);
+        assert!(log.is_at_saved_position());
+        assert_eq!(log.saved_index(), Some(2));
+
+        // Readonly events don't move away from the saved content
+        log.append(Event::MoveCursor {
+            cursor_id: CursorId(0),
+            old_position: 2,
+            new_position: 0,
+            old_anchor: None,
+            new_anchor: None,
+            old_sticky_column: 0,
+            new_sticky_column: 0,
+        });
+        assert!(log.is_at_saved_position());
+        assert_eq!(log.content_state(), 2);
+    }
+
+    #[test]
+    fn test_active_branch_follows_moves() {
+        let mut log = EventLog::new();
+        for (i, text) in ["a", "b", "c"].into_iter().enumerate() {
+            log.append(insert(i, text)); // states 1-3
+        }
+        assert_eq!(log.len(), 3);
+
+        log.undo();
+        log.undo();
+        // Undone events stay on the active branch until a new edit
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.saved_index(), Some(0));
+
+        log.append(insert(1, "x")); // state 4
+        log.append(insert(2, "y")); // state 5
+        assert_eq!(log.len(), 3);
+        let branch = |log: &EventLog| {
+            let events: Vec<Event> = log.entries().iter().map(|e| e.event.clone()).collect();
+            inserted_text(&events)
+        };
+        assert_eq!(branch(&log), vec!["+a", "+x", "+y"]);
+
+        // Across branches of different lengths
+        assert_eq!(
+            inserted_text(&log.events_to(3)),
+            vec!["-y", "-x", "+b", "+c"]
+        );
+        log.jump_to(3);
+        assert_eq!(log.len(), 3);
+        assert_eq!(branch(&log), vec!["+a", "+b", "+c"]);
+        log.undo();
+        assert_eq!(log.current_index(), 2);
+        assert_eq!(log.len(), 3);
+
+        log.clear();
+        assert_eq!(log.len(), 0);
     }
 }
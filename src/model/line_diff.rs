@@ -299,10 +299,304 @@ pub fn merge_ranges(ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
     merged
 }
 
+/// A line of a unified diff, as produced by [`unified_diff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Line present in both texts
+    Context(String),
+    /// Line only present in the old text
+    Removed(String),
+    /// Line only present in the new text
+    Added(String),
+    /// Number of unchanged lines left out between hunks
+    Skipped(usize),
+}
+
+/// Largest line-count product for which the LCS table is computed; bigger
+/// changed regions are shown as fully replaced.
+const MAX_UNIFIED_DIFF_CELLS: usize = 4_000_000;
+
+/// Walk both line lists, returning (old line, new line) pairs: both set for
+/// unchanged lines, one set for removed/added lines.
+///
+/// The common prefix and suffix are trimmed before running the LCS, so typical
+/// small edits to large files stay cheap.
+fn line_ops(old_lines: &[&[u8]], new_lines: &[&[u8]]) -> Vec<(Option<usize>, Option<usize>)> {
+    let prefix = old_lines
+        .iter()
+        .zip(new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old_lines[prefix..old_lines.len() - suffix];
+    let new_middle = &new_lines[prefix..new_lines.len() - suffix];
+
+    let lcs = if old_middle.len() * new_middle.len() <= MAX_UNIFIED_DIFF_CELLS {
+        longest_common_subsequence(old_middle, new_middle)
+    } else {
+        vec![]
+    };
+
+    let mut ops: Vec<(Option<usize>, Option<usize>)> =
+        (0..prefix).map(|i| (Some(i), Some(i))).collect();
+    let (mut old_idx, mut new_idx) = (0, 0);
+    for m in lcs.iter().chain(std::iter::once(&LineMatch {
+        saved_idx: old_middle.len(),
+        current_idx: new_middle.len(),
+    })) {
+        ops.extend((old_idx..m.saved_idx).map(|i| (Some(prefix + i), None)));
+        ops.extend((new_idx..m.current_idx).map(|j| (None, Some(prefix + j))));
+        if m.saved_idx < old_middle.len() {
+            ops.push((Some(prefix + m.saved_idx), Some(prefix + m.current_idx)));
+        }
+        old_idx = m.saved_idx + 1;
+        new_idx = m.current_idx + 1;
+    }
+    let old_suffix_start = old_lines.len() - suffix;
+    let new_suffix_start = new_lines.len() - suffix;
+    ops.extend((0..suffix).map(|i| (Some(old_suffix_start + i), Some(new_suffix_start + i))));
+    ops
+}
+
+/// Compare two texts line by line and return a unified diff from `old` to `new`,
+/// keeping `context` unchanged lines around each change.
+pub fn unified_diff(old: &str, new: &str, context: usize) -> Vec<DiffLine> {
+    if old == new {
+        return vec![];
+    }
+
+    let old_lines: Vec<&[u8]> = old.as_bytes().split(|&b| b == b'\n').collect();
+    let new_lines: Vec<&[u8]> = new.as_bytes().split(|&b| b == b'\n').collect();
+
+    let ops = line_ops(&old_lines, &new_lines);
+
+    // Keep unchanged lines only near a change
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| op.0.is_none() || op.1.is_none())
+        .map(|(i, _)| i)
+        .collect();
+    let near_change = |i: usize| {
+        let next = changed.partition_point(|&c| c < i);
+        let after = changed.get(next).is_some_and(|&c| c - i <= context);
+        let before = next > 0 && i - changed[next - 1] <= context;
+        after || before
+    };
+
+    let line = |lines: &[&[u8]], i: usize| String::from_utf8_lossy(lines[i]).into_owned();
+    let mut result = Vec::new();
+    let mut skipped = 0;
+    for (i, op) in ops.iter().enumerate() {
+        match *op {
+            (Some(_), Some(_)) if !near_change(i) => {
+                skipped += 1;
+                continue;
+            }
+            _ if skipped > 0 => {
+                result.push(DiffLine::Skipped(skipped));
+                skipped = 0;
+            }
+            _ => {}
+        }
+        result.push(match *op {
+            (Some(o), Some(_)) => DiffLine::Context(line(&old_lines, o)),
+            (Some(o), None) => DiffLine::Removed(line(&old_lines, o)),
+            (None, Some(n)) => DiffLine::Added(line(&new_lines, n)),
+            (None, None) => unreachable!(),
+        });
+    }
+    if skipped > 0 {
+        result.push(DiffLine::Skipped(skipped));
+    }
+    result
+}
+
+/// A changed region between two texts, as produced by [`line_hunks`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineHunk {
+    /// Lines of the old text replaced by the hunk (empty for pure insertions)
+    pub old: Range<usize>,
+    /// Lines of the new text replacing them (empty for pure deletions)
+    pub new: Range<usize>,
+}
+
+/// Compare two texts line by line and return the changed regions from `old`
+/// to `new`, in order.
+pub fn line_hunks(old: &str, new: &str) -> Vec<LineHunk> {
+    if old == new {
+        return vec![];
+    }
+
+    let old_lines: Vec<&[u8]> = old.as_bytes().split(|&b| b == b'\n').collect();
+    let new_lines: Vec<&[u8]> = new.as_bytes().split(|&b| b == b'\n').collect();
+
+    let mut hunks: Vec<LineHunk> = Vec::new();
+    let (mut old_pos, mut new_pos) = (0, 0);
+    let mut in_hunk = false;
+    for op in line_ops(&old_lines, &new_lines) {
+        match op {
+            (Some(o), Some(n)) => {
+                in_hunk = false;
+                old_pos = o + 1;
+                new_pos = n + 1;
+                continue;
+            }
+            _ if !in_hunk => {
+                hunks.push(LineHunk {
+                    old: old_pos..old_pos,
+                    new: new_pos..new_pos,
+                });
+                in_hunk = true;
+            }
+            _ => {}
+        }
+        let hunk = hunks.last_mut().expect("hunk was just pushed");
+        match op {
+            (Some(o), None) => {
+                hunk.old.end = o + 1;
+                old_pos = o + 1;
+            }
+            (None, Some(n)) => {
+                hunk.new.end = n + 1;
+                new_pos = n + 1;
+            }
+            _ => unreachable!(),
+        }
+    }
+    hunks
+}
+
+/// Smallest single replacement turning `old` into `new`: the byte range of
+/// `old` to replace and the text to put there
+pub fn minimal_edit<'a>(old: &str, new: &'a str) -> (Range<usize>, &'a str) {
+    let mut prefix = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let mut suffix = old[prefix..]
+        .bytes()
+        .rev()
+        .zip(new[prefix..].bytes().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+        suffix -= 1;
+    }
+    (prefix..old.len() - suffix, &new[prefix..new.len() - suffix])
+}
+
+/// Byte-range replacements turning `old` into `new`, in order: one for each
+/// changed region of [`line_hunks`], narrowed to the bytes that differ.
+///
+/// Text outside the replacements is left alone, so positions in it (cursors,
+/// marks) still point at the same text once the edits are applied.
+pub fn minimal_edits(old: &str, new: &str) -> Vec<(Range<usize>, String)> {
+    // Byte offset of every line, then one past the end for a virtual final newline
+    let line_starts = |text: &str| -> Vec<usize> {
+        std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .chain(std::iter::once(text.len() + 1))
+            .collect()
+    };
+    let old_starts = line_starts(old);
+    let new_starts = line_starts(new);
+
+    line_hunks(old, new)
+        .into_iter()
+        .map(|hunk| {
+            let mut old_range = old_starts[hunk.old.start]..old_starts[hunk.old.end];
+            let mut new_range = new_starts[hunk.new.start]..new_starts[hunk.new.end];
+            // A hunk at the end of the texts reaches the virtual final newline;
+            // when one side is empty, its newline goes before the other side's lines
+            if old_range.end > old.len() {
+                if old_range.is_empty() {
+                    old_range.start = old.len();
+                    new_range.start -= 1;
+                } else if new_range.is_empty() {
+                    new_range.start = new.len();
+                    old_range.start -= 1;
+                }
+                old_range.end = old.len();
+                new_range.end = new.len();
+            }
+            let (range, text) = minimal_edit(&old[old_range.clone()], &new[new_range]);
+            (
+                old_range.start + range.start..old_range.start + range.end,
+                text.to_string(),
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn apply(old: &str, new: &str) -> String {
+        let (range, text) = minimal_edit(old, new);
+        format!("{}{}{}", &old[..range.start], text, &old[range.end..])
+    }
+
+    #[test]
+    fn test_minimal_edit() {
+        assert_eq!(minimal_edit("abcdef", "abXdef"), (2..3, "X"));
+        assert_eq!(minimal_edit("aaa", "aaaa"), (3..3, "a"));
+        assert_eq!(minimal_edit("same", "same"), (4..4, ""));
+        // Never splits a multi-byte character
+        assert_eq!(apply("é", "è"), "è");
+        assert_eq!(apply("xéy", "xèy"), "xèy");
+    }
+
+    fn apply_all(old: &str, new: &str) -> String {
+        let mut text = old.to_string();
+        for (range, replacement) in minimal_edits(old, new).into_iter().rev() {
+            text.replace_range(range, &replacement);
+        }
+        text
+    }
+
+    #[test]
+    fn test_minimal_edits() {
+        let cases = [
+            ("a\nb\nc\n", "a\nB\nc\n"),
+            ("a", "a\nb"),
+            ("a\nb", "a"),
+            ("a\n", "a"),
+            ("a", "a\n"),
+            ("", "x\ny\n"),
+            ("x\ny\n", ""),
+            (
+                "fn main(){\nlet x=1;\n}\n",
+                "fn main() {\n    let x = 1;\n}\n",
+            ),
+            ("one\ntwo\nthree\nfour", "zero\none\nthree\nfour!"),
+        ];
+        for (old, new) in cases {
+            assert_eq!(apply_all(old, new), new, "{:?} -> {:?}", old, new);
+        }
+        assert!(minimal_edits("same\n", "same\n").is_empty());
+    }
+
+    #[test]
+    fn test_minimal_edits_leave_unchanged_text_alone() {
+        let old = "fn a(){}\n\nfn b() {}\n\nfn c(){}\n";
+        let new = "fn a() {}\n\nfn b() {}\n\nfn c() {}\n";
+        assert_eq!(
+            minimal_edits(old, new),
+            vec![(6..6, " ".to_string()), (27..27, " ".to_string())]
+        );
+    }
+
     #[test]
     fn test_identical_content() {
         let content = b"line 1\nline 2\nline 3\n";
@@ -593,4 +887,62 @@ mod proptests {
             }
         }
     }
+
+    #[test]
+    fn test_unified_diff_identical() {
+        assert!(unified_diff("a\nb\n", "a\nb\n", 3).is_empty());
+    }
+
+    #[test]
+    fn test_unified_diff_keeps_context_around_changes() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n";
+        assert_eq!(
+            unified_diff(old, new, 1),
+            vec![
+                DiffLine::Skipped(3),
+                DiffLine::Context("4".to_string()),
+                DiffLine::Removed("5".to_string()),
+                DiffLine::Added("five".to_string()),
+                DiffLine::Context("6".to_string()),
+                DiffLine::Skipped(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_insert_and_delete() {
+        let diff = unified_diff("a\nb\nc", "a\nc\nd", 0);
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Skipped(1),
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Skipped(1),
+                DiffLine::Added("d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_hunks() {
+        assert!(line_hunks("a\nb", "a\nb").is_empty());
+        assert_eq!(
+            line_hunks("a\nb\nc\nd\ne", "a\nB\nc\ne\nf"),
+            vec![
+                LineHunk {
+                    old: 1..2,
+                    new: 1..2
+                },
+                LineHunk {
+                    old: 3..4,
+                    new: 3..3
+                },
+                LineHunk {
+                    old: 5..5,
+                    new: 4..5
+                },
+            ]
+        );
+    }
 }
@@ -1,6 +1,71 @@
+use lru::LruCache;
+use std::borrow::Cow;
 use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Deref;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// Most line starts kept for unloaded buffers, over all of them (8 bytes each)
+const UNLOADED_LINE_STARTS_LIMIT: usize = 4 * 1024 * 1024;
+
+/// Line starts computed for unloaded buffers, which means reading the buffer
+/// from its file. The most recently used are kept, keyed by
+/// [`StringBuffer::cache_key`].
+static UNLOADED_LINE_STARTS: LazyLock<Mutex<LineStartsCache>> =
+    LazyLock::new(|| Mutex::new(LineStartsCache::new()));
+
+/// Source of [`StringBuffer::cache_key`]
+static NEXT_CACHE_KEY: AtomicU64 = AtomicU64::new(0);
+
+/// Least recently used line starts, bounded by their total count
+struct LineStartsCache {
+    entries: LruCache<u64, Arc<[usize]>>,
+    total: usize,
+}
+
+impl LineStartsCache {
+    fn new() -> Self {
+        Self {
+            entries: LruCache::unbounded(),
+            total: 0,
+        }
+    }
+
+    fn get(&mut self, key: u64) -> Option<Arc<[usize]>> {
+        self.entries.get(&key).cloned()
+    }
+
+    fn put(&mut self, key: u64, line_starts: Arc<[usize]>) {
+        self.total += line_starts.len();
+        if let Some(old) = self.entries.put(key, line_starts) {
+            self.total -= old.len();
+        }
+        while self.total > UNLOADED_LINE_STARTS_LIMIT && self.entries.len() > 1 {
+            match self.entries.pop_lru() {
+                Some((_, evicted)) => self.total -= evicted.len(),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Line start offsets of a buffer, borrowed from it or shared with the cache
+pub enum LineStarts<'a> {
+    Borrowed(&'a [usize]),
+    Shared(Arc<[usize]>),
+}
+
+impl Deref for LineStarts<'_> {
+    type Target = [usize];
+
+    fn deref(&self) -> &[usize] {
+        match self {
+            Self::Borrowed(line_starts) => line_starts,
+            Self::Shared(line_starts) => line_starts,
+        }
+    }
+}
 
 /// A position in the document (line and column)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -33,6 +98,8 @@ pub struct StringBuffer {
     pub id: usize,
     /// The buffer data - either loaded or unloaded
     pub data: BufferData,
+    /// Identifies the buffer's content in the unloaded line starts cache
+    cache_key: u64,
 }
 
 impl StringBuffer {
@@ -46,6 +113,7 @@ impl StringBuffer {
                 data,
                 line_starts: Some(line_starts),
             },
+            cache_key: NEXT_CACHE_KEY.fetch_add(1, Ordering::Relaxed),
         }
     }
 
@@ -59,6 +127,7 @@ impl StringBuffer {
         StringBuffer {
             id,
             data: BufferData::Loaded { data, line_starts },
+            cache_key: NEXT_CACHE_KEY.fetch_add(1, Ordering::Relaxed),
         }
     }
 
@@ -71,6 +140,7 @@ impl StringBuffer {
                 file_offset,
                 bytes,
             },
+            cache_key: NEXT_CACHE_KEY.fetch_add(1, Ordering::Relaxed),
         }
     }
 
@@ -126,6 +196,89 @@ impl StringBuffer {
         }
     }
 
+    /// Compute line starts for a buffer that was loaded without them
+    pub fn index_lines(&mut self) {
+        if let BufferData::Loaded {
+            data,
+            line_starts: line_starts @ None,
+        } = &mut self.data
+        {
+            *line_starts = Some(Self::compute_line_starts(data));
+        }
+    }
+
+    /// Read `bytes` bytes at `offset` of the buffer, from its file if it is not loaded
+    fn read_range(&self, offset: usize, bytes: usize) -> Option<Cow<'_, [u8]>> {
+        match &self.data {
+            BufferData::Loaded { data, .. } => data.get(offset..offset + bytes).map(Cow::Borrowed),
+            BufferData::Unloaded {
+                file_path,
+                file_offset,
+                bytes: buffer_bytes,
+            } => {
+                if offset + bytes > *buffer_bytes {
+                    return None;
+                }
+                let mut file = std::fs::File::open(file_path).ok()?;
+                file.seek(SeekFrom::Start((file_offset + offset) as u64))
+                    .ok()?;
+                let mut data = vec![0u8; bytes];
+                file.read_exact(&mut data).ok()?;
+                Some(Cow::Owned(data))
+            }
+        }
+    }
+
+    /// Get the byte at `offset`, reading it from the file if the buffer is not loaded
+    pub fn byte_at(&self, offset: usize) -> Option<u8> {
+        self.read_range(offset, 1).map(|data| data[0])
+    }
+
+    /// Get line starts, computing them if the buffer was loaded without them or
+    /// is not loaded at all.
+    ///
+    /// Only used for pieces with a known line count. For large files these are
+    /// at most a chunk in size. Those of unloaded buffers are cached, so the
+    /// chunk is not read from disk again on every lookup.
+    pub fn line_starts_or_compute(&self) -> Option<LineStarts<'_>> {
+        match &self.data {
+            BufferData::Loaded {
+                line_starts: Some(line_starts),
+                ..
+            } => Some(LineStarts::Borrowed(line_starts)),
+            BufferData::Loaded { data, .. } => {
+                Some(LineStarts::Shared(Self::compute_line_starts(data).into()))
+            }
+            BufferData::Unloaded { bytes, .. } => {
+                let cached = UNLOADED_LINE_STARTS
+                    .lock()
+                    .ok()
+                    .and_then(|mut cache| cache.get(self.cache_key));
+                if let Some(line_starts) = cached {
+                    return Some(LineStarts::Shared(line_starts));
+                }
+                let data = self.read_range(0, *bytes)?;
+                let line_starts: Arc<[usize]> = Self::compute_line_starts(&data).into();
+                if let Ok(mut cache) = UNLOADED_LINE_STARTS.lock() {
+                    cache.put(self.cache_key, line_starts.clone());
+                }
+                Some(LineStarts::Shared(line_starts))
+            }
+        }
+    }
+
+    /// Count the line feeds in a range of the buffer.
+    ///
+    /// Unloaded buffers are read from their file, but only for ranges of at most
+    /// a chunk, so that splitting an unindexed large file never scans it.
+    pub fn count_line_feeds(&self, offset: usize, bytes: usize) -> Option<usize> {
+        if !self.is_loaded() && bytes > crate::model::buffer::LOAD_CHUNK_SIZE {
+            return None;
+        }
+        let data = self.read_range(offset, bytes)?;
+        Some(data.iter().filter(|&&b| b == b'\n').count())
+    }
+
     /// Create a new unloaded buffer representing a chunk of this buffer
     /// This is used for splitting large unloaded buffers into smaller chunks
     ///
@@ -265,7 +418,8 @@ pub struct PieceInfo {
 #[derive(Debug, Clone)]
 struct OffsetFindResult {
     info: PieceInfo,
-    bytes_before: usize, // Total bytes in all pieces before this one
+    bytes_before: usize,          // Total bytes in all pieces before this one
+    line_feed_cnt: Option<usize>, // Line feeds in the piece (None if unknown)
 }
 
 /// A cursor position in the document
@@ -343,7 +497,7 @@ impl PieceTreeNode {
                 location,
                 offset: piece_offset,
                 bytes,
-                ..
+                line_feed_cnt,
             } => {
                 if offset < *bytes {
                     Some(OffsetFindResult {
@@ -354,6 +508,7 @@ impl PieceTreeNode {
                             offset_in_piece: Some(offset),
                         },
                         bytes_before: 0,
+                        line_feed_cnt: *line_feed_cnt,
                     })
                 } else {
                     None
@@ -552,11 +707,9 @@ impl PieceTreeNode {
                 // Check if the last byte of this piece is a newline.
                 if column == 0 && target_line == lines_in_piece && target_line > lines_before {
                     let buffer = buffers.get(location.buffer_id())?;
-                    let data = buffer.get_data()?;
-                    let last_byte_offset = offset + bytes - 1;
-                    let last_byte = data.get(last_byte_offset)?;
+                    let last_byte = buffer.byte_at(offset + bytes - 1)?;
 
-                    if *last_byte == b'\n' {
+                    if last_byte == b'\n' {
                         // Piece ends with newline, so the next line starts in the next piece
                         return None;
                     }
@@ -571,7 +724,7 @@ impl PieceTreeNode {
                 // Get the buffer for this piece
                 let buffer_id = location.buffer_id();
                 let buffer = buffers.get(buffer_id)?;
-                let line_starts = buffer.get_line_starts()?;
+                let line_starts = buffer.line_starts_or_compute()?;
 
                 // Find the line within the piece
                 let line_in_piece = target_line - lines_before;
@@ -643,6 +796,14 @@ impl PieceTree {
         }
     }
 
+    /// Create a piece table from leaves in document order
+    pub fn from_leaves(leaves: &[LeafData]) -> Self {
+        PieceTree {
+            root: Self::build_balanced(leaves),
+            total_bytes: leaves.iter().map(|leaf| leaf.bytes).sum(),
+        }
+    }
+
     /// Create an empty piece table
     pub fn empty() -> Self {
         PieceTree {
@@ -1144,8 +1305,9 @@ impl PieceTree {
                 let end = (offset + bytes).min(data.len());
                 Some(data[offset..end].iter().filter(|&&b| b == b'\n').count())
             } else {
-                // Buffer is unloaded - return None
-                None
+                // Buffer is unloaded - only small ranges (e.g. of indexed
+                // chunks) are read to keep their line count known
+                buffer.count_line_feeds(offset, bytes)
             }
         } else {
             // Buffer not available - return None
@@ -1610,6 +1772,7 @@ impl PieceTree {
         if let Some(result) = self.root.find_by_offset(offset) {
             let piece_info = result.info;
             let bytes_before = result.bytes_before;
+            let piece_lines_known = result.line_feed_cnt.is_some();
 
             // Count lines before this piece
             // If line count is unknown, return None - we can't reliably compute position
@@ -1624,8 +1787,14 @@ impl PieceTree {
             // Get the buffer for this piece
             let buffer_id = piece_info.location.buffer_id();
             if let Some(buffer) = buffers.get(buffer_id) {
-                // Check if we have line starts available
-                if let Some(line_starts) = buffer.get_line_starts() {
+                // Check if we have line starts available. Pieces with a known
+                // line count can compute them (e.g. indexed chunks of large files)
+                let line_starts = if piece_lines_known {
+                    buffer.line_starts_or_compute()
+                } else {
+                    buffer.get_line_starts().map(LineStarts::Borrowed)
+                };
+                if let Some(line_starts) = line_starts {
                     // Find position within the piece
                     let offset_in_piece = piece_info.offset_in_piece.unwrap_or(0);
                     let byte_offset_in_buffer = piece_info.offset + offset_in_piece;
@@ -2142,6 +2311,22 @@ mod tests {
         assert_eq!(tree.total_bytes(), 100);
     }
 
+    #[test]
+    fn test_unloaded_line_starts_are_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chunk.txt");
+        std::fs::write(&path, "ab\ncd\nef").unwrap();
+        let buffer = StringBuffer::new_unloaded(0, path.clone(), 0, 8);
+
+        let first = buffer.line_starts_or_compute().unwrap();
+        assert_eq!(&*first, &[0, 3, 6]);
+
+        // Served from the cache, not re-read from the file
+        std::fs::remove_file(&path).unwrap();
+        let second = buffer.line_starts_or_compute().unwrap();
+        assert_eq!(&*second, &[0, 3, 6]);
+    }
+
     #[test]
     fn test_insert_at_end() {
         let buffers = test_buffers();
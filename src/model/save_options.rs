@@ -0,0 +1,235 @@
+//! How a buffer is written to its file when saved
+//!
+//! [`SaveStrategy`] decides how the file on disk is replaced with the new
+//! content, and [`BackupPolicy`] whether the previous version is kept first.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How the file on disk is replaced when saving
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SaveStrategy {
+    /// Write a temporary file and rename it over the original.
+    ///
+    /// The file is never left half-written, but it gets a new inode, which
+    /// breaks hard links and tools that watch the inode.
+    #[default]
+    Atomic,
+
+    /// Truncate the original file and write the new content into it.
+    ///
+    /// Keeps the inode and hard links, but a failure while writing leaves the
+    /// file truncated.
+    InPlace,
+
+    /// Write a temporary file, then copy its content over the original.
+    ///
+    /// Keeps the inode and hard links like [`SaveStrategy::InPlace`], while the
+    /// new content is complete on disk before the original is touched.
+    Copy,
+}
+
+/// Whether and where to keep the previous version of a file when saving
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum BackupPolicy {
+    /// No backups
+    #[default]
+    None,
+
+    /// A single backup next to the file, named `file~`
+    Adjacent,
+
+    /// Numbered backups in a central directory, newest first
+    /// (`<encoded path>.~1~`, `.~2~`, ...), keeping at most `count`
+    Directory { dir: PathBuf, count: usize },
+}
+
+/// Options for [`crate::model::buffer::TextBuffer::save_to_file_with_options`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SaveOptions {
+    pub strategy: SaveStrategy,
+    pub backup: BackupPolicy,
+}
+
+impl BackupPolicy {
+    /// Copy the current content of `path` to its backup location.
+    ///
+    /// Does nothing if `path` doesn't exist or isn't a regular file.
+    pub fn write_backup(&self, path: &Path) -> io::Result<()> {
+        if !std::fs::metadata(path).is_ok_and(|meta| meta.is_file()) {
+            return Ok(());
+        }
+        match self {
+            BackupPolicy::None => Ok(()),
+            BackupPolicy::Adjacent => {
+                let mut name = path.as_os_str().to_os_string();
+                name.push("~");
+                std::fs::copy(path, PathBuf::from(name)).map(|_| ())
+            }
+            BackupPolicy::Directory { dir, count } => {
+                if *count == 0 {
+                    return Ok(());
+                }
+                std::fs::create_dir_all(dir)?;
+                let numbered = |n: usize| numbered_backup_path(dir, path, n);
+
+                // Shift older versions up by one, dropping the oldest
+                match std::fs::remove_file(numbered(*count)) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                    _ => {}
+                }
+                for n in (1..*count).rev() {
+                    if numbered(n).exists() {
+                        std::fs::rename(numbered(n), numbered(n + 1))?;
+                    }
+                }
+                std::fs::copy(path, numbered(1)).map(|_| ())
+            }
+        }
+    }
+}
+
+/// Path of backup number `n` (1 is the newest) of `path` in the backup directory `dir`.
+///
+/// The full path of the file is encoded into the name, so files with the same
+/// name in different directories don't share backups.
+pub fn numbered_backup_path(dir: &Path, path: &Path, n: usize) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut name = String::new();
+    for c in absolute.to_string_lossy().chars() {
+        match c {
+            '%' => name.push_str("%25"),
+            '/' => name.push_str("%2F"),
+            '\\' => name.push_str("%5C"),
+            ':' => name.push_str("%3A"),
+            c => name.push(c),
+        }
+    }
+    dir.join(format!("{}.~{}~", name, n))
+}
+
+/// Copy the extended attributes of `from` to `to` (best effort).
+///
+/// Only supported on Linux; elsewhere this does nothing.
+#[cfg(target_os = "linux")]
+pub(crate) fn copy_xattrs(from: &Path, to: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = |path: &Path| {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    };
+    let from = c_path(from)?;
+    let to = c_path(to)?;
+
+    // SAFETY: all pointers are valid NUL-terminated strings or buffers of the
+    // length passed alongside them
+    unsafe {
+        let size = libc::listxattr(from.as_ptr(), std::ptr::null_mut(), 0);
+        if size <= 0 {
+            return if size == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            };
+        }
+        let mut names = vec![0u8; size as usize];
+        let size = libc::listxattr(from.as_ptr(), names.as_mut_ptr().cast(), names.len());
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        names.truncate(size as usize);
+
+        for name in names.split(|&b| b == 0).filter(|name| !name.is_empty()) {
+            let Ok(name) = CString::new(name) else {
+                continue;
+            };
+            let len = libc::getxattr(from.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0);
+            if len < 0 {
+                continue;
+            }
+            let mut value = vec![0u8; len as usize];
+            let len = libc::getxattr(
+                from.as_ptr(),
+                name.as_ptr(),
+                value.as_mut_ptr().cast(),
+                value.len(),
+            );
+            if len < 0 {
+                continue;
+            }
+            // Attributes we may not set (e.g. other namespaces) are skipped
+            libc::setxattr(
+                to.as_ptr(),
+                name.as_ptr(),
+                value.as_ptr().cast(),
+                len as usize,
+                0,
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn copy_xattrs(_from: &Path, _to: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_adjacent_backup_keeps_previous_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+        std::fs::write(&path, "old").unwrap();
+
+        BackupPolicy::Adjacent.write_backup(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("notes.txt~")).unwrap(),
+            "old"
+        );
+    }
+
+    #[test]
+    fn test_directory_backups_rotate_and_keep_count() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+        let dir = temp_dir.path().join("backups");
+        let policy = BackupPolicy::Directory {
+            dir: dir.clone(),
+            count: 2,
+        };
+
+        for version in ["one", "two", "three"] {
+            std::fs::write(&path, version).unwrap();
+            policy.write_backup(&path).unwrap();
+        }
+
+        let read = |n| std::fs::read_to_string(numbered_backup_path(&dir, &path, n)).unwrap();
+        assert_eq!(read(1), "three");
+        assert_eq!(read(2), "two");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_backup_names_encode_the_full_path() {
+        let dir = Path::new("/backups");
+        let a = numbered_backup_path(dir, Path::new("/a/b%c.txt"), 1);
+        let b = numbered_backup_path(dir, Path::new("/a%2Fb%c.txt"), 1);
+        assert_ne!(a, b);
+        assert_eq!(a.parent(), Some(dir));
+    }
+
+    #[test]
+    fn test_missing_file_is_not_backed_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("missing.txt");
+        BackupPolicy::Adjacent.write_backup(&path).unwrap();
+        assert!(!temp_dir.path().join("missing.txt~").exists());
+    }
+}
@@ -4,8 +4,9 @@
 //! enabling a 4-level overlay architecture (System → User → Project → Session).
 
 use crate::config::{
-    CursorStyle, FileBrowserConfig, FileExplorerConfig, FormatterConfig, HighlighterPreference,
-    Keybinding, KeybindingMapName, KeymapConfig, LanguageConfig, LineEndingOption, OnSaveAction,
+    BackupOption, CursorStyle, FileBrowserConfig, FileExplorerConfig, FinalNewlineOption,
+    FormatterConfig, FormatterSource, HighlighterPreference, Keybinding, KeybindingMapName,
+    KeymapConfig, LanguageConfig, LineEndingOption, OnSaveAction, SaveStrategyOption,
     TerminalConfig, ThemeName, WarningsConfig,
 };
 use crate::types::LspServerConfig;
@@ -136,6 +137,11 @@ pub struct PartialEditorConfig {
     pub scroll_offset: Option<usize>,
     pub syntax_highlighting: Option<bool>,
     pub line_wrap: Option<bool>,
+    pub sticky_scroll: Option<bool>,
+    pub sticky_scroll_max_lines: Option<usize>,
+    pub indent_guides: Option<bool>,
+    pub bracket_pair_colorization: Option<bool>,
+    pub breadcrumbs: Option<bool>,
     pub highlight_timeout_ms: Option<u64>,
     pub snapshot_interval: Option<usize>,
     pub large_file_threshold_bytes: Option<u64>,
@@ -143,6 +149,12 @@ pub struct PartialEditorConfig {
     pub enable_inlay_hints: Option<bool>,
     pub recovery_enabled: Option<bool>,
     pub auto_save_interval_secs: Option<u32>,
+    pub persistent_undo: Option<bool>,
+    pub persistent_undo_max_age_days: Option<u32>,
+    pub persistent_undo_max_bytes: Option<u64>,
+    pub local_history: Option<bool>,
+    pub local_history_max_entries: Option<usize>,
+    pub local_history_max_age_days: Option<u32>,
     pub highlight_context_bytes: Option<usize>,
     pub mouse_hover_enabled: Option<bool>,
     pub mouse_hover_delay_ms: Option<u64>,
@@ -150,6 +162,10 @@ pub struct PartialEditorConfig {
     pub auto_revert_poll_interval_ms: Option<u64>,
     pub file_tree_poll_interval_ms: Option<u64>,
     pub default_line_ending: Option<LineEndingOption>,
+    pub save_strategy: Option<SaveStrategyOption>,
+    pub backup: Option<BackupOption>,
+    pub backup_directory: Option<std::path::PathBuf>,
+    pub backup_count: Option<usize>,
     pub cursor_style: Option<CursorStyle>,
     pub quick_suggestions: Option<bool>,
     pub show_menu_bar: Option<bool>,
@@ -167,6 +183,13 @@ impl Merge for PartialEditorConfig {
         self.syntax_highlighting
             .merge_from(&other.syntax_highlighting);
         self.line_wrap.merge_from(&other.line_wrap);
+        self.sticky_scroll.merge_from(&other.sticky_scroll);
+        self.sticky_scroll_max_lines
+            .merge_from(&other.sticky_scroll_max_lines);
+        self.indent_guides.merge_from(&other.indent_guides);
+        self.bracket_pair_colorization
+            .merge_from(&other.bracket_pair_colorization);
+        self.breadcrumbs.merge_from(&other.breadcrumbs);
         self.highlight_timeout_ms
             .merge_from(&other.highlight_timeout_ms);
         self.snapshot_interval.merge_from(&other.snapshot_interval);
@@ -179,6 +202,16 @@ impl Merge for PartialEditorConfig {
         self.recovery_enabled.merge_from(&other.recovery_enabled);
         self.auto_save_interval_secs
             .merge_from(&other.auto_save_interval_secs);
+        self.persistent_undo.merge_from(&other.persistent_undo);
+        self.persistent_undo_max_age_days
+            .merge_from(&other.persistent_undo_max_age_days);
+        self.persistent_undo_max_bytes
+            .merge_from(&other.persistent_undo_max_bytes);
+        self.local_history.merge_from(&other.local_history);
+        self.local_history_max_entries
+            .merge_from(&other.local_history_max_entries);
+        self.local_history_max_age_days
+            .merge_from(&other.local_history_max_age_days);
         self.highlight_context_bytes
             .merge_from(&other.highlight_context_bytes);
         self.mouse_hover_enabled
@@ -193,6 +226,10 @@ impl Merge for PartialEditorConfig {
             .merge_from(&other.file_tree_poll_interval_ms);
         self.default_line_ending
             .merge_from(&other.default_line_ending);
+        self.save_strategy.merge_from(&other.save_strategy);
+        self.backup.merge_from(&other.backup);
+        self.backup_directory.merge_from(&other.backup_directory);
+        self.backup_count.merge_from(&other.backup_count);
         self.cursor_style.merge_from(&other.cursor_style);
         self.quick_suggestions.merge_from(&other.quick_suggestions);
         self.show_menu_bar.merge_from(&other.show_menu_bar);
@@ -279,7 +316,17 @@ pub struct PartialLanguageConfig {
     pub tab_size: Option<usize>,
     pub formatter: Option<FormatterConfig>,
     pub format_on_save: Option<bool>,
+    pub formatter_source: Option<FormatterSource>,
+    pub format_on_type: Option<bool>,
     pub on_save: Option<Vec<OnSaveAction>>,
+    pub save_strategy: Option<SaveStrategyOption>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub trim_modified_lines_only: Option<bool>,
+    pub trim_trailing_blank_lines: Option<bool>,
+    pub final_newline: Option<FinalNewlineOption>,
+    pub normalize_line_endings: Option<bool>,
+    pub indent_guides: Option<bool>,
+    pub bracket_pair_colorization: Option<bool>,
 }
 
 impl Merge for PartialLanguageConfig {
@@ -297,7 +344,22 @@ impl Merge for PartialLanguageConfig {
         self.tab_size.merge_from(&other.tab_size);
         self.formatter.merge_from(&other.formatter);
         self.format_on_save.merge_from(&other.format_on_save);
+        self.formatter_source.merge_from(&other.formatter_source);
+        self.format_on_type.merge_from(&other.format_on_type);
         self.on_save.merge_from(&other.on_save);
+        self.save_strategy.merge_from(&other.save_strategy);
+        self.trim_trailing_whitespace
+            .merge_from(&other.trim_trailing_whitespace);
+        self.trim_modified_lines_only
+            .merge_from(&other.trim_modified_lines_only);
+        self.trim_trailing_blank_lines
+            .merge_from(&other.trim_trailing_blank_lines);
+        self.final_newline.merge_from(&other.final_newline);
+        self.normalize_line_endings
+            .merge_from(&other.normalize_line_endings);
+        self.indent_guides.merge_from(&other.indent_guides);
+        self.bracket_pair_colorization
+            .merge_from(&other.bracket_pair_colorization);
     }
 }
 
@@ -332,6 +394,11 @@ impl From<&crate::config::EditorConfig> for PartialEditorConfig {
             scroll_offset: Some(cfg.scroll_offset),
             syntax_highlighting: Some(cfg.syntax_highlighting),
             line_wrap: Some(cfg.line_wrap),
+            sticky_scroll: Some(cfg.sticky_scroll),
+            sticky_scroll_max_lines: Some(cfg.sticky_scroll_max_lines),
+            indent_guides: Some(cfg.indent_guides),
+            bracket_pair_colorization: Some(cfg.bracket_pair_colorization),
+            breadcrumbs: Some(cfg.breadcrumbs),
             highlight_timeout_ms: Some(cfg.highlight_timeout_ms),
             snapshot_interval: Some(cfg.snapshot_interval),
             large_file_threshold_bytes: Some(cfg.large_file_threshold_bytes),
@@ -339,6 +406,12 @@ impl From<&crate::config::EditorConfig> for PartialEditorConfig {
             enable_inlay_hints: Some(cfg.enable_inlay_hints),
             recovery_enabled: Some(cfg.recovery_enabled),
             auto_save_interval_secs: Some(cfg.auto_save_interval_secs),
+            persistent_undo: Some(cfg.persistent_undo),
+            persistent_undo_max_age_days: Some(cfg.persistent_undo_max_age_days),
+            persistent_undo_max_bytes: Some(cfg.persistent_undo_max_bytes),
+            local_history: Some(cfg.local_history),
+            local_history_max_entries: Some(cfg.local_history_max_entries),
+            local_history_max_age_days: Some(cfg.local_history_max_age_days),
             highlight_context_bytes: Some(cfg.highlight_context_bytes),
             mouse_hover_enabled: Some(cfg.mouse_hover_enabled),
             mouse_hover_delay_ms: Some(cfg.mouse_hover_delay_ms),
@@ -346,6 +419,10 @@ impl From<&crate::config::EditorConfig> for PartialEditorConfig {
             auto_revert_poll_interval_ms: Some(cfg.auto_revert_poll_interval_ms),
             file_tree_poll_interval_ms: Some(cfg.file_tree_poll_interval_ms),
             default_line_ending: Some(cfg.default_line_ending.clone()),
+            save_strategy: Some(cfg.save_strategy),
+            backup: Some(cfg.backup),
+            backup_directory: cfg.backup_directory.clone(),
+            backup_count: Some(cfg.backup_count),
             cursor_style: Some(cfg.cursor_style),
             quick_suggestions: Some(cfg.quick_suggestions),
             show_menu_bar: Some(cfg.show_menu_bar),
@@ -369,6 +446,15 @@ impl PartialEditorConfig {
                 .syntax_highlighting
                 .unwrap_or(defaults.syntax_highlighting),
             line_wrap: self.line_wrap.unwrap_or(defaults.line_wrap),
+            sticky_scroll: self.sticky_scroll.unwrap_or(defaults.sticky_scroll),
+            sticky_scroll_max_lines: self
+                .sticky_scroll_max_lines
+                .unwrap_or(defaults.sticky_scroll_max_lines),
+            indent_guides: self.indent_guides.unwrap_or(defaults.indent_guides),
+            bracket_pair_colorization: self
+                .bracket_pair_colorization
+                .unwrap_or(defaults.bracket_pair_colorization),
+            breadcrumbs: self.breadcrumbs.unwrap_or(defaults.breadcrumbs),
             highlight_timeout_ms: self
                 .highlight_timeout_ms
                 .unwrap_or(defaults.highlight_timeout_ms),
@@ -386,6 +472,20 @@ impl PartialEditorConfig {
             auto_save_interval_secs: self
                 .auto_save_interval_secs
                 .unwrap_or(defaults.auto_save_interval_secs),
+            persistent_undo: self.persistent_undo.unwrap_or(defaults.persistent_undo),
+            persistent_undo_max_age_days: self
+                .persistent_undo_max_age_days
+                .unwrap_or(defaults.persistent_undo_max_age_days),
+            persistent_undo_max_bytes: self
+                .persistent_undo_max_bytes
+                .unwrap_or(defaults.persistent_undo_max_bytes),
+            local_history: self.local_history.unwrap_or(defaults.local_history),
+            local_history_max_entries: self
+                .local_history_max_entries
+                .unwrap_or(defaults.local_history_max_entries),
+            local_history_max_age_days: self
+                .local_history_max_age_days
+                .unwrap_or(defaults.local_history_max_age_days),
             highlight_context_bytes: self
                 .highlight_context_bytes
                 .unwrap_or(defaults.highlight_context_bytes),
@@ -407,6 +507,12 @@ impl PartialEditorConfig {
             default_line_ending: self
                 .default_line_ending
                 .unwrap_or(defaults.default_line_ending.clone()),
+            save_strategy: self.save_strategy.unwrap_or(defaults.save_strategy),
+            backup: self.backup.unwrap_or(defaults.backup),
+            backup_directory: self
+                .backup_directory
+                .or_else(|| defaults.backup_directory.clone()),
+            backup_count: self.backup_count.unwrap_or(defaults.backup_count),
             cursor_style: self.cursor_style.unwrap_or(defaults.cursor_style),
             quick_suggestions: self.quick_suggestions.unwrap_or(defaults.quick_suggestions),
             show_menu_bar: self.show_menu_bar.unwrap_or(defaults.show_menu_bar),
@@ -508,7 +614,17 @@ impl From<&LanguageConfig> for PartialLanguageConfig {
             tab_size: cfg.tab_size,
             formatter: cfg.formatter.clone(),
             format_on_save: Some(cfg.format_on_save),
+            formatter_source: Some(cfg.formatter_source),
+            format_on_type: Some(cfg.format_on_type),
             on_save: Some(cfg.on_save.clone()),
+            save_strategy: cfg.save_strategy,
+            trim_trailing_whitespace: Some(cfg.trim_trailing_whitespace),
+            trim_modified_lines_only: Some(cfg.trim_modified_lines_only),
+            trim_trailing_blank_lines: Some(cfg.trim_trailing_blank_lines),
+            final_newline: Some(cfg.final_newline),
+            normalize_line_endings: Some(cfg.normalize_line_endings),
+            indent_guides: cfg.indent_guides,
+            bracket_pair_colorization: cfg.bracket_pair_colorization,
         }
     }
 }
@@ -536,7 +652,27 @@ impl PartialLanguageConfig {
             tab_size: self.tab_size.or(defaults.tab_size),
             formatter: self.formatter.or_else(|| defaults.formatter.clone()),
             format_on_save: self.format_on_save.unwrap_or(defaults.format_on_save),
+            formatter_source: self.formatter_source.unwrap_or(defaults.formatter_source),
+            format_on_type: self.format_on_type.unwrap_or(defaults.format_on_type),
             on_save: self.on_save.unwrap_or_else(|| defaults.on_save.clone()),
+            save_strategy: self.save_strategy.or(defaults.save_strategy),
+            trim_trailing_whitespace: self
+                .trim_trailing_whitespace
+                .unwrap_or(defaults.trim_trailing_whitespace),
+            trim_modified_lines_only: self
+                .trim_modified_lines_only
+                .unwrap_or(defaults.trim_modified_lines_only),
+            trim_trailing_blank_lines: self
+                .trim_trailing_blank_lines
+                .unwrap_or(defaults.trim_trailing_blank_lines),
+            final_newline: self.final_newline.unwrap_or(defaults.final_newline),
+            normalize_line_endings: self
+                .normalize_line_endings
+                .unwrap_or(defaults.normalize_line_endings),
+            indent_guides: self.indent_guides.or(defaults.indent_guides),
+            bracket_pair_colorization: self
+                .bracket_pair_colorization
+                .or(defaults.bracket_pair_colorization),
         }
     }
 }
@@ -671,7 +807,17 @@ impl Default for LanguageConfig {
             tab_size: None,
             formatter: None,
             format_on_save: false,
+            formatter_source: FormatterSource::Auto,
+            format_on_type: false,
             on_save: Vec::new(),
+            save_strategy: None,
+            trim_trailing_whitespace: false,
+            trim_modified_lines_only: false,
+            trim_trailing_blank_lines: false,
+            final_newline: FinalNewlineOption::Keep,
+            normalize_line_endings: false,
+            indent_guides: None,
+            bracket_pair_colorization: None,
         }
     }
 }
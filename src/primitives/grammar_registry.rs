@@ -566,7 +566,17 @@ mod tests {
                 tab_size: None,
                 formatter: None,
                 format_on_save: false,
+                formatter_source: crate::config::FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: crate::config::FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
 
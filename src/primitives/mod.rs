@@ -16,6 +16,8 @@ pub mod line_wrapping;
 pub mod path_utils;
 pub mod reference_highlighter;
 pub mod snippet;
+pub mod syntax_selection;
+pub mod syntax_tree;
 pub mod text_property;
 pub mod visual_layout;
 pub mod word_navigation;
@@ -1,12 +1,19 @@
 //! LSP snippet parser and expander.
 //!
-//! Parses LSP snippet syntax and expands it to plain text with cursor positioning.
+//! Parses snippet syntax (as used by LSP completions and VS Code snippet
+//! files) into text plus the tabstops a snippet session navigates.
 //! Supports:
 //! - `$0` - final cursor position
-//! - `$n` - tabstops (expanded as empty, cursor goes to $0 or end)
-//! - `${n:text}` - tabstops with default text (uses the default)
-//! - `${n|choice1,choice2|}` - choices (uses first choice)
+//! - `$n` / `${n}` - tabstops; repeated numbers mirror each other
+//! - `${n:text}` - tabstops with placeholder text, which may nest
+//! - `${n|choice1,choice2|}` - choices (the first one is inserted)
+//! - `$NAME` / `${NAME}` / `${NAME:default}` - variables
 //! - `\\$` - escaped dollar sign
+//!
+//! Transforms (`${1/regex/format/}`) are parsed but not applied.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
 
 /// Result of expanding a snippet
 #[derive(Debug, Clone, PartialEq)]
@@ -17,6 +24,28 @@ pub struct ExpandedSnippet {
     pub cursor_offset: usize,
 }
 
+/// A tabstop of an expanded snippet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tabstop {
+    /// Tabstop number (0 is the final cursor position)
+    pub index: u32,
+    /// Byte ranges of every occurrence
+    pub ranges: Vec<Range<usize>>,
+    /// Options of a choice placeholder (empty otherwise)
+    pub choices: Vec<String>,
+}
+
+/// A snippet expanded to text with its tabstops
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetExpansion {
+    /// The expanded text
+    pub text: String,
+    /// Tabstops in visiting order, ending with `$0`
+    ///
+    /// A snippet without `$0` gets one at the end of its text.
+    pub tabstops: Vec<Tabstop>,
+}
+
 /// Expand an LSP snippet to plain text
 ///
 /// # Examples
@@ -34,141 +63,394 @@ pub struct ExpandedSnippet {
 /// assert_eq!(result.cursor_offset, 8); // cursor at end (no $0)
 /// ```
 pub fn expand_snippet(snippet: &str) -> ExpandedSnippet {
-    let mut result = String::new();
-    let mut cursor_offset: Option<usize> = None;
-    let mut chars = snippet.chars().peekable();
+    let expansion = expand_snippet_with(snippet, "", |_| None);
+    let cursor_offset = expansion
+        .tabstops
+        .last()
+        .and_then(|t| t.ranges.first())
+        .map_or(expansion.text.len(), |r| r.start);
+    ExpandedSnippet {
+        text: expansion.text,
+        cursor_offset,
+    }
+}
 
-    while let Some(c) = chars.next() {
-        if c == '\\' {
-            // Escape sequence
-            if let Some(&next) = chars.peek() {
-                if next == '$' || next == '\\' || next == '}' {
-                    result.push(chars.next().unwrap());
-                    continue;
-                }
+/// Expand a snippet with its tabstops
+///
+/// Lines after the first are prefixed with `indent`. `variable` resolves
+/// variable names; unknown variables (None) insert their name, empty ones
+/// their default.
+pub fn expand_snippet_with(
+    snippet: &str,
+    indent: &str,
+    variable: impl Fn(&str) -> Option<String>,
+) -> SnippetExpansion {
+    let elements = Parser::new(snippet).parse_elements(&[]);
+    let mut definitions = HashMap::new();
+    collect_definitions(&elements, &mut definitions);
+
+    let mut renderer = Renderer {
+        text: String::new(),
+        indent,
+        variable: &variable,
+        definitions: &definitions,
+        ranges: BTreeMap::new(),
+        choices: HashMap::new(),
+    };
+    renderer.render(&elements, true);
+
+    let Renderer {
+        text,
+        ranges,
+        mut choices,
+        ..
+    } = renderer;
+    let mut tabstops: Vec<Tabstop> = ranges
+        .into_iter()
+        .map(|(index, ranges)| Tabstop {
+            index,
+            ranges,
+            choices: choices.remove(&index).unwrap_or_default(),
+        })
+        .collect();
+    // Visit $1, $2, ... and finish at $0
+    match tabstops.iter().position(|t| t.index == 0) {
+        Some(pos) => {
+            let last = tabstops.remove(pos);
+            tabstops.push(last);
+        }
+        None => tabstops.push(Tabstop {
+            index: 0,
+            ranges: vec![Range {
+                start: text.len(),
+                end: text.len(),
+            }],
+            choices: Vec::new(),
+        }),
+    }
+    SnippetExpansion { text, tabstops }
+}
+
+/// A parsed piece of a snippet
+#[derive(Debug, Clone, PartialEq)]
+enum Element {
+    Text(String),
+    Tabstop {
+        index: u32,
+        placeholder: Vec<Element>,
+        choices: Vec<String>,
+    },
+    Variable {
+        name: String,
+        default: Option<Vec<Element>>,
+    },
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(snippet: &str) -> Self {
+        Self {
+            chars: snippet.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parse elements until one of `stop` (not consumed) or the end
+    fn parse_elements(&mut self, stop: &[char]) -> Vec<Element> {
+        let mut elements = Vec::new();
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            if stop.contains(&c) {
+                break;
             }
-            result.push(c);
-        } else if c == '$' {
-            // Snippet placeholder
-            if let Some(&next) = chars.peek() {
-                if next == '{' {
-                    // ${...} syntax
-                    chars.next(); // consume '{'
-                    let (expanded, is_final) = parse_brace_placeholder(&mut chars);
-                    if is_final {
-                        cursor_offset = Some(result.len());
+            self.pos += 1;
+            match c {
+                '\\' => match self.peek() {
+                    Some(next) if matches!(next, '$' | '\\' | '}') || stop.contains(&next) => {
+                        text.push(next);
+                        self.pos += 1;
                     }
-                    result.push_str(&expanded);
-                } else if next.is_ascii_digit() {
-                    // $n syntax
-                    let mut num = String::new();
-                    while let Some(&d) = chars.peek() {
-                        if d.is_ascii_digit() {
-                            num.push(chars.next().unwrap());
-                        } else {
-                            break;
+                    _ => text.push(c),
+                },
+                '$' => {
+                    let start = self.pos;
+                    match self.parse_dollar() {
+                        Some(element) => {
+                            if !text.is_empty() {
+                                elements.push(Element::Text(std::mem::take(&mut text)));
+                            }
+                            elements.push(element);
+                        }
+                        None => {
+                            // Not a valid placeholder, keep the $
+                            self.pos = start;
+                            text.push(c);
                         }
                     }
-                    if num == "0" {
-                        cursor_offset = Some(result.len());
-                    }
-                    // Other tabstops expand to nothing
-                } else {
-                    // Not a valid placeholder, keep the $
-                    result.push(c);
                 }
-            } else {
-                result.push(c);
+                _ => text.push(c),
             }
-        } else {
-            result.push(c);
         }
+        if !text.is_empty() {
+            elements.push(Element::Text(text));
+        }
+        elements
     }
 
-    ExpandedSnippet {
-        cursor_offset: cursor_offset.unwrap_or(result.len()),
-        text: result,
+    /// Parse what follows a `$`
+    fn parse_dollar(&mut self) -> Option<Element> {
+        if let Some(index) = self.parse_number() {
+            return Some(Element::Tabstop {
+                index,
+                placeholder: Vec::new(),
+                choices: Vec::new(),
+            });
+        }
+        if let Some(name) = self.parse_name() {
+            return Some(Element::Variable {
+                name,
+                default: None,
+            });
+        }
+        if !self.eat('{') {
+            return None;
+        }
+        if let Some(index) = self.parse_number() {
+            let (placeholder, choices) = match self.peek()? {
+                '}' => (Vec::new(), Vec::new()),
+                ':' => {
+                    self.pos += 1;
+                    (self.parse_elements(&['}']), Vec::new())
+                }
+                '|' => {
+                    self.pos += 1;
+                    (Vec::new(), self.parse_choices()?)
+                }
+                '/' => {
+                    self.skip_transform()?;
+                    (Vec::new(), Vec::new())
+                }
+                _ => return None,
+            };
+            if !self.eat('}') {
+                return None;
+            }
+            return Some(Element::Tabstop {
+                index,
+                placeholder,
+                choices,
+            });
+        }
+        let name = self.parse_name()?;
+        let default = match self.peek()? {
+            '}' => None,
+            ':' => {
+                self.pos += 1;
+                Some(self.parse_elements(&['}']))
+            }
+            '/' => {
+                self.skip_transform()?;
+                None
+            }
+            _ => return None,
+        };
+        if !self.eat('}') {
+            return None;
+        }
+        Some(Element::Variable { name, default })
     }
-}
 
-/// Parse a ${...} placeholder
-/// Returns (expanded_text, is_final_cursor)
-fn parse_brace_placeholder(chars: &mut std::iter::Peekable<std::str::Chars>) -> (String, bool) {
-    let mut content = String::new();
-    let mut depth = 1;
+    fn parse_number(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().ok()
+    }
 
-    // Collect everything until matching '}'
-    while let Some(c) = chars.next() {
-        if c == '{' {
-            depth += 1;
-            content.push(c);
-        } else if c == '}' {
-            depth -= 1;
-            if depth == 0 {
-                break;
-            }
-            content.push(c);
-        } else if c == '\\' {
-            // Handle escapes inside braces
-            if let Some(&next) = chars.peek() {
-                if next == '$' || next == '\\' || next == '}' || next == '|' {
-                    content.push(chars.next().unwrap());
-                    continue;
+    fn parse_name(&mut self) -> Option<String> {
+        if !self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        {
+            return None;
+        }
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+        }
+        Some(self.chars[start..self.pos].iter().collect())
+    }
+
+    /// Parse `a,b,c|` after the opening `|`
+    fn parse_choices(&mut self) -> Option<Vec<String>> {
+        let mut choices = Vec::new();
+        let mut choice = String::new();
+        loop {
+            match self.peek()? {
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(next) if matches!(next, ',' | '|' | '\\' | '$' | '}') => {
+                            choice.push(next);
+                            self.pos += 1;
+                        }
+                        _ => choice.push('\\'),
+                    }
+                }
+                ',' => {
+                    self.pos += 1;
+                    choices.push(std::mem::take(&mut choice));
+                }
+                '|' => {
+                    self.pos += 1;
+                    choices.push(choice);
+                    return Some(choices);
+                }
+                c => {
+                    self.pos += 1;
+                    choice.push(c);
                 }
             }
-            content.push(c);
-        } else {
-            content.push(c);
         }
     }
 
-    // Parse the content: n or n:default or n|choices|
-    let (tabstop, default) = parse_placeholder_content(&content);
-
-    let is_final = tabstop == Some(0);
-    (default, is_final)
+    /// Skip `/regex/format/options` up to (not including) the closing brace
+    fn skip_transform(&mut self) -> Option<()> {
+        let mut slashes = 0;
+        while slashes < 3 {
+            match self.peek()? {
+                '\\' => self.pos += 2,
+                '/' => {
+                    slashes += 1;
+                    self.pos += 1;
+                }
+                _ => self.pos += 1,
+            }
+        }
+        while self.peek()? != '}' {
+            self.pos += 1;
+        }
+        Some(())
+    }
 }
 
-/// Parse placeholder content like "1", "1:default", or "1|a,b,c|"
-/// Returns (tabstop_number, default_text)
-fn parse_placeholder_content(content: &str) -> (Option<u32>, String) {
-    // Find the tabstop number
-    let mut chars = content.chars().peekable();
-    let mut num_str = String::new();
-
-    while let Some(&c) = chars.peek() {
-        if c.is_ascii_digit() {
-            num_str.push(chars.next().unwrap());
-        } else {
-            break;
+/// Record the first tabstop of each number that has placeholder text or
+/// choices, so mirrors without their own text can repeat it
+fn collect_definitions<'a>(elements: &'a [Element], definitions: &mut HashMap<u32, &'a Element>) {
+    for element in elements {
+        match element {
+            Element::Tabstop {
+                index,
+                placeholder,
+                choices,
+            } => {
+                if !placeholder.is_empty() || !choices.is_empty() {
+                    definitions.entry(*index).or_insert(element);
+                }
+                collect_definitions(placeholder, definitions);
+            }
+            Element::Variable {
+                default: Some(default),
+                ..
+            } => collect_definitions(default, definitions),
+            _ => {}
         }
     }
+}
 
-    let tabstop = num_str.parse::<u32>().ok();
+struct Renderer<'a> {
+    text: String,
+    indent: &'a str,
+    variable: &'a dyn Fn(&str) -> Option<String>,
+    definitions: &'a HashMap<u32, &'a Element>,
+    ranges: BTreeMap<u32, Vec<Range<usize>>>,
+    choices: HashMap<u32, Vec<String>>,
+}
 
-    // Check what follows
-    match chars.peek() {
-        Some(':') => {
-            // Default text: ${n:default}
-            chars.next(); // consume ':'
-            let default: String = chars.collect();
-            // Recursively expand nested snippets in default
-            let expanded = expand_snippet(&default);
-            (tabstop, expanded.text)
-        }
-        Some('|') => {
-            // Choices: ${n|choice1,choice2|}
-            chars.next(); // consume '|'
-            let choices: String = chars.collect();
-            // Remove trailing '|' if present
-            let choices = choices.trim_end_matches('|');
-            // Use first choice
-            let first_choice = choices.split(',').next().unwrap_or("");
-            (tabstop, first_choice.to_string())
+impl Renderer<'_> {
+    /// Append `elements`, recording tabstop ranges if `record` is set
+    fn render(&mut self, elements: &[Element], record: bool) {
+        for element in elements {
+            match element {
+                Element::Text(text) => self.push_text(text),
+                Element::Tabstop {
+                    index,
+                    placeholder,
+                    choices,
+                } => {
+                    let start = self.text.len();
+                    let (placeholder, choices, own) =
+                        if !placeholder.is_empty() || !choices.is_empty() {
+                            (placeholder, choices, true)
+                        } else {
+                            match self.definitions.get(index) {
+                                Some(Element::Tabstop {
+                                    placeholder,
+                                    choices,
+                                    ..
+                                }) => (placeholder, choices, false),
+                                _ => (placeholder, choices, true),
+                            }
+                        };
+                    if let Some(first) = choices.first() {
+                        self.push_text(first);
+                        if record {
+                            self.choices
+                                .entry(*index)
+                                .or_insert_with(|| choices.clone());
+                        }
+                    } else {
+                        // Tabstops nested in a mirror belong to the original
+                        self.render(placeholder, record && own);
+                    }
+                    if record {
+                        self.ranges
+                            .entry(*index)
+                            .or_default()
+                            .push(start..self.text.len());
+                    }
+                }
+                Element::Variable { name, default } => match (self.variable)(name) {
+                    Some(value) if !value.is_empty() => self.push_text(&value),
+                    resolved => match default {
+                        Some(default) => self.render(default, record),
+                        None if resolved.is_none() => self.push_text(name),
+                        None => {}
+                    },
+                },
+            }
         }
-        _ => {
-            // Just a tabstop number, no default
-            (tabstop, String::new())
+    }
+
+    fn push_text(&mut self, text: &str) {
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.text.push('\n');
+                self.text.push_str(self.indent);
+            }
+            self.text.push_str(line);
         }
     }
 }
@@ -194,6 +476,116 @@ pub fn is_snippet(text: &str) -> bool {
     false
 }
 
+/// Tabstop navigation of a snippet inserted into a buffer
+///
+/// Tabstop ranges are absolute byte offsets kept in step with edits, so
+/// typing into a placeholder grows its range.
+#[derive(Debug, Clone)]
+pub struct SnippetSession {
+    /// Tabstops in visiting order, ending with `$0`
+    tabstops: Vec<Tabstop>,
+    /// Index into `tabstops` of the active one
+    current: usize,
+    /// Byte range of the whole snippet
+    extent: Range<usize>,
+}
+
+impl SnippetSession {
+    /// Start a session for `expansion` inserted at `offset`
+    ///
+    /// Returns None if the snippet has no tabstops besides `$0`.
+    pub fn new(expansion: &SnippetExpansion, offset: usize) -> Option<Self> {
+        if expansion.tabstops.len() < 2 {
+            return None;
+        }
+        let tabstops = expansion
+            .tabstops
+            .iter()
+            .map(|t| Tabstop {
+                index: t.index,
+                ranges: t
+                    .ranges
+                    .iter()
+                    .map(|r| r.start + offset..r.end + offset)
+                    .collect(),
+                choices: t.choices.clone(),
+            })
+            .collect();
+        Some(Self {
+            tabstops,
+            current: 0,
+            extent: offset..offset + expansion.text.len(),
+        })
+    }
+
+    /// The active tabstop
+    pub fn current(&self) -> &Tabstop {
+        &self.tabstops[self.current]
+    }
+
+    /// Whether the active tabstop is `$0`, which ends the session
+    pub fn at_final(&self) -> bool {
+        self.current + 1 == self.tabstops.len()
+    }
+
+    /// Move to the next tabstop
+    pub fn next_tabstop(&mut self) -> &Tabstop {
+        self.current = (self.current + 1).min(self.tabstops.len() - 1);
+        self.current()
+    }
+
+    /// Move to the previous tabstop
+    pub fn prev_tabstop(&mut self) -> &Tabstop {
+        self.current = self.current.saturating_sub(1);
+        self.current()
+    }
+
+    /// Whether `position` lies within the snippet
+    pub fn contains(&self, position: usize) -> bool {
+        self.extent.start <= position && position <= self.extent.end
+    }
+
+    /// Adjust tabstops for text inserted at `position`
+    ///
+    /// Text inserted inside a range, at its end, or into an empty range grows it.
+    pub fn adjust_for_insert(&mut self, position: usize, length: usize) {
+        let extent = std::iter::once(&mut self.extent);
+        for range in self
+            .tabstops
+            .iter_mut()
+            .flat_map(|t| t.ranges.iter_mut())
+            .chain(extent)
+        {
+            if position < range.start || (position == range.start && range.start < range.end) {
+                range.start += length;
+                range.end += length;
+            } else if position <= range.end {
+                range.end += length;
+            }
+        }
+    }
+
+    /// Adjust tabstops for `length` bytes deleted at `position`
+    pub fn adjust_for_delete(&mut self, position: usize, length: usize) {
+        let shift = |offset: usize| {
+            if offset >= position + length {
+                offset - length
+            } else {
+                offset.min(position)
+            }
+        };
+        let extent = std::iter::once(&mut self.extent);
+        for range in self
+            .tabstops
+            .iter_mut()
+            .flat_map(|t| t.ranges.iter_mut())
+            .chain(extent)
+        {
+            *range = shift(range.start)..shift(range.end);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -277,4 +669,84 @@ mod tests {
         assert_eq!(result.text, "foo()");
         assert_eq!(result.cursor_offset, 5);
     }
+
+    fn ranges(expansion: &SnippetExpansion) -> Vec<(u32, Vec<&str>)> {
+        expansion
+            .tabstops
+            .iter()
+            .map(|t| {
+                let texts = t
+                    .ranges
+                    .iter()
+                    .map(|r| &expansion.text[r.clone()])
+                    .collect();
+                (t.index, texts)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tabstops_in_visiting_order() {
+        let expansion = expand_snippet_with("for ${1:i} in ${2:iter} {\n\t$0\n}", "    ", |_| None);
+        assert_eq!(expansion.text, "for i in iter {\n    \t\n    }");
+        assert_eq!(
+            ranges(&expansion),
+            [(1, vec!["i"]), (2, vec!["iter"]), (0, vec![""])]
+        );
+        assert_eq!(expansion.tabstops[2].ranges[0].start, 21);
+    }
+
+    #[test]
+    fn test_mirrors_repeat_placeholder() {
+        let expansion = expand_snippet_with("let $1 = ${1:x}; $1", "", |_| None);
+        assert_eq!(expansion.text, "let x = x; x");
+        assert_eq!(ranges(&expansion)[0], (1, vec!["x", "x", "x"]));
+    }
+
+    #[test]
+    fn test_choice_tabstop_keeps_options() {
+        let expansion = expand_snippet_with("${1|a,b\\,c|}", "", |_| None);
+        assert_eq!(expansion.text, "a");
+        assert_eq!(expansion.tabstops[0].choices, ["a", "b,c"]);
+    }
+
+    #[test]
+    fn test_variables() {
+        let variable = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "TM_SELECTED_TEXT" => Some(String::new()),
+            _ => None,
+        };
+        let expansion = expand_snippet_with(
+            "$TM_FILENAME ${TM_SELECTED_TEXT:none} ${UNKNOWN} ${2/a/b/}",
+            "",
+            variable,
+        );
+        assert_eq!(expansion.text, "main.rs none UNKNOWN ");
+    }
+
+    #[test]
+    fn test_session_tracks_edits() {
+        let expansion = expand_snippet_with("for ${1:i} in ${2:iter}", "", |_| None);
+        let mut session = SnippetSession::new(&expansion, 10).unwrap();
+        assert_eq!(session.current().ranges, [14..15]);
+
+        // Replace "i" with "idx"
+        session.adjust_for_delete(14, 1);
+        session.adjust_for_insert(14, 3);
+        assert_eq!(session.current().ranges, [14..17]);
+        assert_eq!(session.next_tabstop().ranges, [21..25]);
+        assert!(!session.at_final());
+        assert_eq!(session.next_tabstop().ranges, [25..25]);
+        assert!(session.at_final());
+        assert!(session.contains(10));
+        assert!(!session.contains(9));
+        assert!(!session.contains(26));
+    }
+
+    #[test]
+    fn test_no_session_without_tabstops() {
+        let expansion = expand_snippet_with("println!($0)", "", |_| None);
+        assert!(SnippetSession::new(&expansion, 0).is_none());
+    }
 }
@@ -0,0 +1,241 @@
+//! Syntax-aware selection ranges
+//!
+//! Expanding a selection walks up the tree-sitter syntax tree from the
+//! selected text: identifier, expression, statement, block, function. Each
+//! step selects the smallest named node that strictly contains the current
+//! selection. When the language server provides `textDocument/selectionRange`
+//! its ranges are used instead.
+//!
+//! Every expansion is recorded per cursor so shrinking retraces the same
+//! steps back to the original selection.
+
+use crate::model::event::CursorId;
+#[cfg(test)]
+use crate::primitives::highlighter::Language;
+use std::collections::HashMap;
+use std::ops::Range;
+use tree_sitter::Tree;
+
+/// A cursor's selection as (anchor, position)
+pub type Selection = (Option<usize>, usize);
+
+/// Parse `text` from scratch, for tests (the editor reuses the buffer's
+/// incrementally updated `SyntaxTree`)
+#[cfg(test)]
+pub fn parse(text: &str, language: &Language) -> Option<Tree> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&language.tree_sitter_language()).ok()?;
+    parser.parse(text, None)
+}
+
+/// The smallest named node that strictly contains `range`
+///
+/// Returns None when the selection already covers the whole tree.
+pub fn enclosing_range(tree: &Tree, range: Range<usize>) -> Option<Range<usize>> {
+    let mut node = tree
+        .root_node()
+        .named_descendant_for_byte_range(range.start, range.end)?;
+    loop {
+        let node_range = node.byte_range();
+        if node_range.start <= range.start && node_range.end >= range.end && node_range != range {
+            return Some(node_range);
+        }
+        node = node.parent()?;
+    }
+}
+
+/// Selection ranges the language server returned for one position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectionChain {
+    /// Byte offset the ranges were requested for
+    pub origin: usize,
+    /// Ranges around `origin`, innermost first
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// One expansion of a cursor's selection
+#[derive(Debug, Clone, Copy)]
+struct ExpansionStep {
+    before: Selection,
+    after: Selection,
+}
+
+/// Expand/shrink selection state of a buffer
+#[derive(Debug, Default)]
+pub struct SelectionExpansion {
+    /// Expansions per cursor, oldest first
+    history: HashMap<CursorId, Vec<ExpansionStep>>,
+    /// Buffer version the history was recorded at
+    history_version: u64,
+    /// Language server selection ranges and the buffer version they belong to
+    lsp_chains: Option<(u64, Vec<SelectionChain>)>,
+}
+
+impl SelectionExpansion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store selection ranges from the language server
+    ///
+    /// Chains for the same version are merged so cursors requested
+    /// separately keep their ranges.
+    pub fn set_lsp_chains(&mut self, version: u64, chains: Vec<SelectionChain>) {
+        match &mut self.lsp_chains {
+            Some((v, existing)) if *v == version => {
+                existing.retain(|c| !chains.iter().any(|n| n.origin == c.origin));
+                existing.extend(chains);
+            }
+            _ => self.lsp_chains = Some((version, chains)),
+        }
+    }
+
+    /// The language server chain that applies to `selection`
+    ///
+    /// A chain applies when it was requested from inside the selection.
+    pub fn lsp_chain(&self, version: u64, selection: Range<usize>) -> Option<&SelectionChain> {
+        let (v, chains) = self.lsp_chains.as_ref()?;
+        if *v != version {
+            return None;
+        }
+        chains
+            .iter()
+            .find(|c| selection.start <= c.origin && c.origin <= selection.end)
+    }
+
+    /// Record that `cursor_id` expanded from `before` to `after`
+    ///
+    /// The history restarts when the cursor was moved or the buffer edited
+    /// since the last expansion.
+    pub fn record(
+        &mut self,
+        version: u64,
+        cursor_id: CursorId,
+        before: Selection,
+        after: Selection,
+    ) {
+        if self.history_version != version {
+            self.history.clear();
+            self.history_version = version;
+        }
+        let steps = self.history.entry(cursor_id).or_default();
+        if steps.last().is_some_and(|s| s.after != before) {
+            steps.clear();
+        }
+        steps.push(ExpansionStep { before, after });
+    }
+
+    /// Undo the last expansion of `cursor_id` if `current` is its result
+    pub fn shrink(
+        &mut self,
+        version: u64,
+        cursor_id: CursorId,
+        current: Selection,
+    ) -> Option<Selection> {
+        if self.history_version != version {
+            self.history.clear();
+            return None;
+        }
+        let steps = self.history.get_mut(&cursor_id)?;
+        if steps.last()?.after != current {
+            steps.clear();
+            return None;
+        }
+        steps.pop().map(|s| s.before)
+    }
+}
+
+/// The smallest range in `ranges` that strictly contains `selection`
+pub fn smallest_enclosing(
+    ranges: &[Range<usize>],
+    selection: Range<usize>,
+) -> Option<Range<usize>> {
+    ranges
+        .iter()
+        .filter(|r| r.start <= selection.start && r.end >= selection.end && **r != selection)
+        .min_by_key(|r| r.end - r.start)
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expansions(text: &str, start: usize) -> Vec<&str> {
+        let tree = parse(text, &Language::Rust).unwrap();
+        let mut range = start..start;
+        let mut steps = Vec::new();
+        while let Some(next) = enclosing_range(&tree, range.clone()) {
+            steps.push(&text[next.clone()]);
+            range = next;
+        }
+        steps
+    }
+
+    #[test]
+    fn test_expansion_walks_up_the_tree() {
+        let text = "fn main() {\n    let x = a + b;\n}\n";
+        let steps = expansions(text, text.find("a +").unwrap());
+        assert_eq!(
+            &steps[..5],
+            [
+                "a",
+                "a + b",
+                "let x = a + b;",
+                "{\n    let x = a + b;\n}",
+                "fn main() {\n    let x = a + b;\n}",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expansion_from_selection_skips_equal_nodes() {
+        let text = "fn f() { g(1, 2); }";
+        let tree = parse(text, &Language::Rust).unwrap();
+        let args = text.find("(1").unwrap()..text.find(";").unwrap();
+        assert_eq!(&text[args.clone()], "(1, 2)");
+        let next = enclosing_range(&tree, args).unwrap();
+        assert_eq!(&text[next], "g(1, 2)");
+    }
+
+    #[test]
+    fn test_shrink_retraces_expansions() {
+        let mut expansion = SelectionExpansion::new();
+        let cursor = CursorId(0);
+        expansion.record(1, cursor, (None, 5), (Some(4), 6));
+        expansion.record(1, cursor, (Some(4), 6), (Some(0), 10));
+        assert_eq!(
+            expansion.shrink(1, cursor, (Some(0), 10)),
+            Some((Some(4), 6))
+        );
+        assert_eq!(expansion.shrink(1, cursor, (Some(4), 6)), Some((None, 5)));
+        assert_eq!(expansion.shrink(1, cursor, (None, 5)), None);
+    }
+
+    #[test]
+    fn test_history_restarts_after_cursor_moves_or_edits() {
+        let mut expansion = SelectionExpansion::new();
+        let cursor = CursorId(0);
+        expansion.record(1, cursor, (None, 5), (Some(4), 6));
+        assert_eq!(expansion.shrink(1, cursor, (None, 8)), None);
+        assert_eq!(expansion.shrink(1, cursor, (Some(4), 6)), None);
+
+        expansion.record(1, cursor, (None, 5), (Some(4), 6));
+        assert_eq!(expansion.shrink(2, cursor, (Some(4), 6)), None);
+    }
+
+    #[test]
+    fn test_lsp_chain_applies_to_selections_around_its_origin() {
+        let mut expansion = SelectionExpansion::new();
+        let chain = SelectionChain {
+            origin: 5,
+            ranges: vec![4..6, 0..10],
+        };
+        expansion.set_lsp_chains(1, vec![chain.clone()]);
+        assert_eq!(expansion.lsp_chain(1, 5..5), Some(&chain));
+        assert_eq!(expansion.lsp_chain(1, 4..6), Some(&chain));
+        assert_eq!(expansion.lsp_chain(1, 7..7), None);
+        assert_eq!(expansion.lsp_chain(2, 5..5), None);
+        assert_eq!(smallest_enclosing(&chain.ranges, 4..6), Some(0..10));
+    }
+}
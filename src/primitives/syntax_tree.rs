@@ -0,0 +1,147 @@
+//! Tree-sitter syntax tree of a buffer
+//!
+//! Folding, bracket pair colorization and the outline all read the same tree,
+//! kept here per buffer. When the buffer changes, the changed span is found by
+//! comparing the new text with the previous one; the old tree is edited to
+//! match and handed back to the parser, which reuses the unchanged subtrees
+//! instead of parsing the whole buffer again.
+
+use crate::model::buffer::Buffer;
+use crate::primitives::highlighter::Language;
+use std::mem::Discriminant;
+use tree_sitter::{InputEdit, Parser, Point, Tree};
+
+/// A parsed buffer version
+struct Parsed {
+    version: u64,
+    language: Discriminant<Language>,
+    text: String,
+    tree: Tree,
+}
+
+/// Syntax tree of one buffer, updated incrementally as the buffer changes
+#[derive(Default)]
+pub struct SyntaxTree {
+    /// Parser set to the language of the last parse
+    parser: Option<(Discriminant<Language>, Parser)>,
+    parsed: Option<Parsed>,
+}
+
+impl SyntaxTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Text and syntax tree of the buffer's current version
+    ///
+    /// None without a tree-sitter language, or when the buffer content is not
+    /// fully loaded.
+    pub fn tree(&mut self, buffer: &Buffer, language: Option<&Language>) -> Option<(&str, &Tree)> {
+        let language = language?;
+        let key = std::mem::discriminant(language);
+        let version = buffer.version();
+        let current = self
+            .parsed
+            .as_ref()
+            .is_some_and(|parsed| parsed.version == version && parsed.language == key);
+        if !current {
+            let text = buffer.to_string()?;
+            if self.parser.as_ref().map(|(k, _)| *k) != Some(key) {
+                let mut parser = Parser::new();
+                parser.set_language(&language.tree_sitter_language()).ok()?;
+                self.parser = Some((key, parser));
+            }
+            let old_tree = self
+                .parsed
+                .take()
+                .filter(|parsed| parsed.language == key)
+                .map(|mut parsed| {
+                    parsed.tree.edit(&input_edit(&parsed.text, &text));
+                    parsed.tree
+                });
+            let (_, parser) = self.parser.as_mut()?;
+            let tree = parser.parse(&text, old_tree.as_ref())?;
+            self.parsed = Some(Parsed {
+                version,
+                language: key,
+                text,
+                tree,
+            });
+        }
+        self.parsed
+            .as_ref()
+            .map(|parsed| (parsed.text.as_str(), &parsed.tree))
+    }
+}
+
+/// The edit turning `old` into `new`: the span between their common prefix
+/// and common suffix
+fn input_edit(old: &str, new: &str) -> InputEdit {
+    let (old_bytes, new_bytes) = (old.as_bytes(), new.as_bytes());
+    let prefix = old_bytes
+        .iter()
+        .zip(new_bytes)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old_bytes
+        .iter()
+        .rev()
+        .zip(new_bytes.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_end = old.len() - suffix;
+    let new_end = new.len() - suffix;
+    InputEdit {
+        start_byte: prefix,
+        old_end_byte: old_end,
+        new_end_byte: new_end,
+        start_position: point_at(old_bytes, prefix),
+        old_end_position: point_at(old_bytes, old_end),
+        new_end_position: point_at(new_bytes, new_end),
+    }
+}
+
+/// Row and byte column of `offset` in `text`
+fn point_at(text: &[u8], offset: usize) -> Point {
+    let before = &text[..offset];
+    let row = before.iter().filter(|&&b| b == b'\n').count();
+    let line_start = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |newline| newline + 1);
+    Point::new(row, offset - line_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_edit_covers_changed_span() {
+        let edit = input_edit("fn a() {\n    x\n}\n", "fn a() {\n    yz\n}\n");
+        assert_eq!(edit.start_byte, 13);
+        assert_eq!(edit.old_end_byte, 14);
+        assert_eq!(edit.new_end_byte, 15);
+        assert_eq!(edit.start_position, Point::new(1, 4));
+        assert_eq!(edit.new_end_position, Point::new(1, 6));
+    }
+
+    #[test]
+    fn test_reparse_matches_full_parse() {
+        let mut buffer = Buffer::from_str_test("fn main() {\n    let x = 1;\n}\n");
+        let mut syntax = SyntaxTree::new();
+        syntax.tree(&buffer, Some(&Language::Rust)).unwrap();
+
+        buffer.insert(26, "\n    let y = [2];");
+        let (text, tree) = syntax.tree(&buffer, Some(&Language::Rust)).unwrap();
+        let mut parser = Parser::new();
+        parser
+            .set_language(&Language::Rust.tree_sitter_language())
+            .unwrap();
+        let fresh = parser.parse(text, None).unwrap();
+        assert_eq!(tree.root_node().to_sexp(), fresh.root_node().to_sexp());
+        assert!(!tree.root_node().has_error());
+    }
+}
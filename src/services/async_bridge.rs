@@ -10,11 +10,14 @@
 //! - Computation should be sync (editing, rendering)
 //! - Main loop remains responsive and simple
 
+use crate::model::event::BufferId;
 use crate::services::terminal::TerminalId;
 use crate::view::file_tree::{FileTreeView, NodeId};
 use lsp_types::{
-    CodeActionOrCommand, CompletionItem, Diagnostic, InlayHint, Location, SemanticTokensLegend,
-    SemanticTokensResult, SignatureHelp,
+    CallHierarchyItem, CodeActionOrCommand, CodeLens, CompletionItem, Diagnostic,
+    DocumentSymbolResponse, FoldingRange, InlayHint, Location, SelectionRange,
+    SemanticTokensLegend, SemanticTokensResult, SignatureHelp, TextEdit, TypeHierarchyItem,
+    WorkspaceSymbolResponse,
 };
 use serde_json::Value;
 use std::sync::mpsc;
@@ -37,6 +40,28 @@ pub enum AsyncMessage {
         semantic_tokens_legend: Option<SemanticTokensLegend>,
         /// Whether the server supports full document semantic tokens
         semantic_tokens_full: bool,
+        /// Whether the server provides folding ranges
+        folding_range: bool,
+        /// Whether the server provides selection ranges
+        selection_range: bool,
+        /// Whether the server provides document symbols
+        document_symbol: bool,
+        /// Whether the server searches workspace symbols
+        workspace_symbol: bool,
+        /// Whether the server formats whole documents
+        formatting: bool,
+        /// Whether the server formats ranges of a document
+        range_formatting: bool,
+        /// Characters that trigger on-type formatting
+        on_type_formatting_triggers: Vec<String>,
+        /// Whether the server provides call hierarchies
+        call_hierarchy: bool,
+        /// Whether the server provides type hierarchies
+        type_hierarchy: bool,
+        /// Whether the server provides code lenses
+        code_lens: bool,
+        /// Whether the server resolves the commands of code lenses lazily
+        code_lens_resolve: bool,
     },
 
     /// LSP server crashed or failed
@@ -53,8 +78,8 @@ pub enum AsyncMessage {
         items: Vec<CompletionItem>,
     },
 
-    /// LSP go-to-definition response
-    LspGotoDefinition {
+    /// LSP go-to-definition, type definition, implementation or declaration response
+    LspGoto {
         request_id: u64,
         locations: Vec<Location>,
     },
@@ -122,6 +147,97 @@ pub enum AsyncMessage {
         result: Result<Option<SemanticTokensResult>, String>,
     },
 
+    /// LSP folding range response (textDocument/foldingRange)
+    LspFoldingRanges {
+        request_id: u64,
+        uri: String,
+        /// Folding ranges (empty on error)
+        ranges: Vec<FoldingRange>,
+    },
+
+    /// LSP selection range response (textDocument/selectionRange)
+    LspSelectionRanges {
+        request_id: u64,
+        uri: String,
+        /// One range hierarchy per requested position (empty on error)
+        ranges: Vec<SelectionRange>,
+    },
+
+    /// LSP document symbol response (textDocument/documentSymbol)
+    LspDocumentSymbols {
+        request_id: u64,
+        uri: String,
+        /// Symbols of the document (None on error)
+        symbols: Option<DocumentSymbolResponse>,
+    },
+
+    /// LSP formatting response (textDocument/formatting, rangeFormatting
+    /// or onTypeFormatting)
+    LspFormatting {
+        request_id: u64,
+        uri: String,
+        /// Edits to apply (None on error)
+        edits: Option<Vec<TextEdit>>,
+    },
+
+    /// LSP workspace symbol response (workspace/symbol)
+    LspWorkspaceSymbols {
+        request_id: u64,
+        /// Language of the server that answered
+        language: String,
+        /// Matching symbols (None on error)
+        symbols: Option<WorkspaceSymbolResponse>,
+    },
+
+    /// LSP prepare call hierarchy response (textDocument/prepareCallHierarchy)
+    LspCallHierarchy {
+        request_id: u64,
+        /// Items for the symbol at the position (None on error)
+        items: Option<Vec<CallHierarchyItem>>,
+    },
+
+    /// LSP call hierarchy calls response (callHierarchy/incomingCalls or
+    /// outgoingCalls)
+    LspCallHierarchyCalls {
+        request_id: u64,
+        /// The calling (incoming) or called (outgoing) items, with the ranges
+        /// of the calls in the caller (None on error)
+        calls: Option<Vec<(CallHierarchyItem, Vec<lsp_types::Range>)>>,
+    },
+
+    /// LSP type hierarchy response (textDocument/prepareTypeHierarchy,
+    /// typeHierarchy/supertypes or subtypes)
+    LspTypeHierarchy {
+        request_id: u64,
+        /// The prepared, super- or subtype items (None on error)
+        items: Option<Vec<TypeHierarchyItem>>,
+    },
+
+    /// LSP code lens response (textDocument/codeLens)
+    LspCodeLenses {
+        request_id: u64,
+        uri: String,
+        /// Lenses of the document (None on error)
+        lenses: Option<Vec<CodeLens>>,
+    },
+
+    /// LSP code lens resolve response (codeLens/resolve)
+    LspCodeLensResolved {
+        request_id: u64,
+        /// The lens with its command filled in (None on error)
+        lens: Option<CodeLens>,
+    },
+
+    /// LSP execute command response (workspace/executeCommand)
+    LspExecuteCommand {
+        request_id: u64,
+        result: Result<(), String>,
+    },
+
+    /// The server asked for the code lenses of all documents to be
+    /// requested again (workspace/codeLens/refresh)
+    LspCodeLensRefresh { language: String },
+
     /// LSP server status became quiescent (project fully loaded)
     /// This is a rust-analyzer specific notification (experimental/serverStatus)
     LspServerQuiescent { language: String },
@@ -216,6 +332,52 @@ pub enum AsyncMessage {
 
     /// Terminal process exited
     TerminalExited { terminal_id: TerminalId },
+
+    /// Line feed counts of a batch of scanned chunks of a large file
+    LineIndexProgress {
+        buffer_id: BufferId,
+        task_id: u64,
+        first_chunk: usize,
+        counts: Vec<usize>,
+        bytes_scanned: usize,
+    },
+
+    /// Matches found by a background search, with the bytes searched so far
+    SearchProgress {
+        task_id: u64,
+        matches: Vec<(usize, usize)>,
+        /// Matches found in this batch, including those past the reported limit
+        match_count: usize,
+        bytes_searched: usize,
+    },
+
+    /// A background search reached the end of its range
+    SearchComplete { task_id: u64 },
+
+    /// A background search could not read the buffer
+    SearchFailed { task_id: u64, error: String },
+
+    /// Files with matches found by a project search
+    ProjectSearchResults {
+        task_id: u64,
+        files: Vec<crate::services::project_search::FileMatches>,
+    },
+
+    /// A project search finished
+    ProjectSearchComplete {
+        task_id: u64,
+        summary: crate::services::project_search::ProjectSearchSummary,
+    },
+
+    /// A project search could not start (e.g. an invalid glob)
+    ProjectSearchFailed { task_id: u64, error: String },
+
+    /// Scanning a large file for line feeds failed
+    LineIndexFailed {
+        buffer_id: BufferId,
+        task_id: u64,
+        error: String,
+    },
 }
 
 /// LSP progress value types
@@ -342,6 +504,17 @@ mod tests {
                 completion_trigger_characters: vec![".".to_string()],
                 semantic_tokens_legend: None,
                 semantic_tokens_full: false,
+                folding_range: false,
+                selection_range: false,
+                document_symbol: false,
+                workspace_symbol: false,
+                formatting: false,
+                range_formatting: false,
+                on_type_formatting_triggers: vec![],
+                call_hierarchy: false,
+                type_hierarchy: false,
+                code_lens: false,
+                code_lens_resolve: false,
             })
             .unwrap();
 
@@ -374,6 +547,17 @@ mod tests {
                 completion_trigger_characters: vec![],
                 semantic_tokens_legend: None,
                 semantic_tokens_full: false,
+                folding_range: false,
+                selection_range: false,
+                document_symbol: false,
+                workspace_symbol: false,
+                formatting: false,
+                range_formatting: false,
+                on_type_formatting_triggers: vec![],
+                call_hierarchy: false,
+                type_hierarchy: false,
+                code_lens: false,
+                code_lens_resolve: false,
             })
             .unwrap();
         sender
@@ -382,6 +566,17 @@ mod tests {
                 completion_trigger_characters: vec![],
                 semantic_tokens_legend: None,
                 semantic_tokens_full: false,
+                folding_range: false,
+                selection_range: false,
+                document_symbol: false,
+                workspace_symbol: false,
+                formatting: false,
+                range_formatting: false,
+                on_type_formatting_triggers: vec![],
+                call_hierarchy: false,
+                type_hierarchy: false,
+                code_lens: false,
+                code_lens_resolve: false,
             })
             .unwrap();
 
@@ -412,6 +607,17 @@ mod tests {
                 completion_trigger_characters: vec![],
                 semantic_tokens_legend: None,
                 semantic_tokens_full: false,
+                folding_range: false,
+                selection_range: false,
+                document_symbol: false,
+                workspace_symbol: false,
+                formatting: false,
+                range_formatting: false,
+                on_type_formatting_triggers: vec![],
+                call_hierarchy: false,
+                type_hierarchy: false,
+                code_lens: false,
+                code_lens_resolve: false,
             })
             .unwrap();
         sender2
@@ -420,6 +626,17 @@ mod tests {
                 completion_trigger_characters: vec![],
                 semantic_tokens_legend: None,
                 semantic_tokens_full: false,
+                folding_range: false,
+                selection_range: false,
+                document_symbol: false,
+                workspace_symbol: false,
+                formatting: false,
+                range_formatting: false,
+                on_type_formatting_triggers: vec![],
+                call_hierarchy: false,
+                type_hierarchy: false,
+                code_lens: false,
+                code_lens_resolve: false,
             })
             .unwrap();
 
@@ -520,6 +737,17 @@ mod tests {
                 completion_trigger_characters: vec![],
                 semantic_tokens_legend: None,
                 semantic_tokens_full: false,
+                folding_range: false,
+                selection_range: false,
+                document_symbol: false,
+                workspace_symbol: false,
+                formatting: false,
+                range_formatting: false,
+                on_type_formatting_triggers: vec![],
+                call_hierarchy: false,
+                type_hierarchy: false,
+                code_lens: false,
+                code_lens_resolve: false,
             })
             .unwrap();
 
@@ -539,6 +767,17 @@ mod tests {
                 completion_trigger_characters: vec![],
                 semantic_tokens_legend: None,
                 semantic_tokens_full: false,
+                folding_range: false,
+                selection_range: false,
+                document_symbol: false,
+                workspace_symbol: false,
+                formatting: false,
+                range_formatting: false,
+                on_type_formatting_triggers: vec![],
+                call_hierarchy: false,
+                type_hierarchy: false,
+                code_lens: false,
+                code_lens_resolve: false,
             })
             .unwrap();
 
@@ -563,6 +802,17 @@ mod tests {
                 completion_trigger_characters: vec![],
                 semantic_tokens_legend: None,
                 semantic_tokens_full: false,
+                folding_range: false,
+                selection_range: false,
+                document_symbol: false,
+                workspace_symbol: false,
+                formatting: false,
+                range_formatting: false,
+                on_type_formatting_triggers: vec![],
+                call_hierarchy: false,
+                type_hierarchy: false,
+                code_lens: false,
+                code_lens_resolve: false,
             })
             .unwrap();
         sender
@@ -571,6 +821,17 @@ mod tests {
                 completion_trigger_characters: vec![],
                 semantic_tokens_legend: None,
                 semantic_tokens_full: false,
+                folding_range: false,
+                selection_range: false,
+                document_symbol: false,
+                workspace_symbol: false,
+                formatting: false,
+                range_formatting: false,
+                on_type_formatting_triggers: vec![],
+                call_hierarchy: false,
+                type_hierarchy: false,
+                code_lens: false,
+                code_lens_resolve: false,
             })
             .unwrap();
         sender
@@ -579,6 +840,17 @@ mod tests {
                 completion_trigger_characters: vec![],
                 semantic_tokens_legend: None,
                 semantic_tokens_full: false,
+                folding_range: false,
+                selection_range: false,
+                document_symbol: false,
+                workspace_symbol: false,
+                formatting: false,
+                range_formatting: false,
+                on_type_formatting_triggers: vec![],
+                call_hierarchy: false,
+                type_hierarchy: false,
+                code_lens: false,
+                code_lens_resolve: false,
             })
             .unwrap();
 
@@ -0,0 +1,281 @@
+//! Background search of large buffers
+//!
+//! Searching a multi-gigabyte file on the main thread freezes the UI, so
+//! searches in large files run on a blocking thread over a [`BufferSnapshot`].
+//! Matches are streamed back to the main loop as they are found, together with
+//! how much of the buffer has been searched. Only the first
+//! [`MAX_REPORTED_MATCHES`] positions are sent; later matches are only counted.
+
+use crate::model::buffer::BufferSnapshot;
+use crate::model::event::BufferId;
+use crate::services::async_bridge::AsyncMessage;
+use regex::bytes::Regex;
+use std::io;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+
+/// Bytes searched at a time
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Extra bytes read past each chunk, so matches crossing its end are found
+const OVERLAP: usize = 4096;
+
+/// Chunks searched without matches between progress messages
+const CHUNKS_PER_PROGRESS: usize = 16;
+
+/// Matches whose positions are reported; later ones are only counted
+pub const MAX_REPORTED_MATCHES: usize = 100_000;
+
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A search running in the background over one buffer.
+///
+/// Dropping the task cancels the search.
+#[derive(Debug)]
+pub struct SearchTask {
+    /// Identifies the messages of this task
+    pub id: u64,
+    /// Buffer being searched
+    pub buffer_id: BufferId,
+    /// Buffer version the snapshot was taken at
+    pub version: u64,
+    /// The search query, as typed
+    pub query: String,
+    /// Range being searched
+    pub range: Range<usize>,
+    /// Bytes of the range searched so far
+    pub bytes_searched: usize,
+    cancel: Arc<AtomicBool>,
+}
+
+impl SearchTask {
+    /// Start searching `range` of `snapshot` on the runtime's blocking thread pool
+    #[allow(clippy::too_many_arguments)]
+    pub fn spawn(
+        runtime: &tokio::runtime::Runtime,
+        sender: mpsc::Sender<AsyncMessage>,
+        buffer_id: BufferId,
+        version: u64,
+        query: String,
+        snapshot: BufferSnapshot,
+        regex: Regex,
+        range: Range<usize>,
+    ) -> Self {
+        let id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let task_range = range.clone();
+        let task_cancel = cancel.clone();
+        runtime.spawn_blocking(move || {
+            let result = search_snapshot(
+                &snapshot,
+                &regex,
+                task_range,
+                MAX_REPORTED_MATCHES,
+                &task_cancel,
+                |matches, match_count, bytes_searched| {
+                    sender
+                        .send(AsyncMessage::SearchProgress {
+                            task_id: id,
+                            matches,
+                            match_count,
+                            bytes_searched,
+                        })
+                        .is_ok()
+                },
+            );
+            let message = match result {
+                Ok(true) => AsyncMessage::SearchComplete { task_id: id },
+                Ok(false) => return,
+                Err(e) => AsyncMessage::SearchFailed {
+                    task_id: id,
+                    error: e.to_string(),
+                },
+            };
+            let _ = sender.send(message);
+        });
+
+        Self {
+            id,
+            buffer_id,
+            version,
+            query,
+            range,
+            bytes_searched: 0,
+            cancel,
+        }
+    }
+
+    /// Size of the searched range in bytes
+    pub fn total_bytes(&self) -> usize {
+        self.range.len()
+    }
+}
+
+impl Drop for SearchTask {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Find all matches of `regex` in `range` of `snapshot`, in order.
+///
+/// Matches are passed to `on_progress` as `(position, length)` pairs along with
+/// the number of matches in the batch and the number of bytes searched so far.
+/// Past the first `max_reported` matches, matches are only counted. Returns
+/// whether the search ran to the end, rather than being stopped by `cancel` or
+/// by `on_progress` returning false.
+pub fn search_snapshot(
+    snapshot: &BufferSnapshot,
+    regex: &Regex,
+    range: Range<usize>,
+    max_reported: usize,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(Vec<(usize, usize)>, usize, usize) -> bool,
+) -> io::Result<bool> {
+    let end = range.end.min(snapshot.total_bytes());
+    let mut chunk_start = range.start;
+    // End of the last match found, so overlapping chunks don't find it twice
+    let mut last_match_end = range.start;
+    let mut chunks_since_progress = 0;
+    let mut reported = 0;
+
+    while chunk_start < end {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        let chunk_end = (chunk_start + CHUNK_SIZE).min(end);
+        let read_end = (chunk_end + OVERLAP).min(end);
+        let data = snapshot.read_range(chunk_start, read_end - chunk_start)?;
+
+        // Count matches starting in this chunk; the overlap only completes them
+        let mut matches = Vec::new();
+        let mut match_count = 0;
+        for m in regex.find_iter(&data) {
+            let pos = chunk_start + m.start();
+            if pos < last_match_end || pos >= chunk_end {
+                continue;
+            }
+            last_match_end = pos + m.len();
+            match_count += 1;
+            if reported < max_reported {
+                reported += 1;
+                matches.push((pos, m.len()));
+            }
+        }
+
+        chunk_start = chunk_end;
+        chunks_since_progress += 1;
+        if match_count > 0 || chunks_since_progress == CHUNKS_PER_PROGRESS || chunk_start == end {
+            chunks_since_progress = 0;
+            if !on_progress(matches, match_count, chunk_start - range.start) {
+                return Ok(false);
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::buffer::TextBuffer;
+    use tempfile::TempDir;
+
+    fn search_all(buffer: &TextBuffer, pattern: &str) -> Vec<(usize, usize)> {
+        let regex = Regex::new(pattern).unwrap();
+        let mut found = Vec::new();
+        let completed = search_snapshot(
+            &buffer.content_snapshot(),
+            &regex,
+            0..buffer.len(),
+            MAX_REPORTED_MATCHES,
+            &AtomicBool::new(false),
+            |matches, _, _| {
+                found.extend(matches);
+                true
+            },
+        )
+        .unwrap();
+        assert!(completed);
+        found
+    }
+
+    #[test]
+    fn test_search_large_file_finds_matches_across_chunks() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("big.log");
+        let mut content = vec![b'.'; CHUNK_SIZE * 3];
+        // After the insert below, one match straddles the first chunk boundary
+        content[CHUNK_SIZE - 9..CHUNK_SIZE - 3].copy_from_slice(b"NEEDLE");
+        content[CHUNK_SIZE * 3 - 10..CHUNK_SIZE * 3 - 4].copy_from_slice(b"NEEDLE");
+        std::fs::write(&path, &content).unwrap();
+
+        let mut buffer = TextBuffer::load_from_file(&path, 1024).unwrap();
+        assert!(buffer.is_large_file());
+        // Edited content is searched too
+        buffer.insert_bytes(0, b"NEEDLE".to_vec());
+
+        assert_eq!(
+            search_all(&buffer, "NEEDLE"),
+            vec![(0, 6), (CHUNK_SIZE - 3, 6), (CHUNK_SIZE * 3 - 4, 6)]
+        );
+    }
+
+    #[test]
+    fn test_search_reports_progress_and_stops_when_cancelled() {
+        let buffer = TextBuffer::from_bytes(vec![b'a'; CHUNK_SIZE * 2 + 10]);
+        let regex = Regex::new("b").unwrap();
+
+        let mut progress = Vec::new();
+        search_snapshot(
+            &buffer.content_snapshot(),
+            &regex,
+            10..buffer.len(),
+            MAX_REPORTED_MATCHES,
+            &AtomicBool::new(false),
+            |_, _, searched| {
+                progress.push(searched);
+                true
+            },
+        )
+        .unwrap();
+        assert_eq!(progress, vec![CHUNK_SIZE * 2]);
+
+        let completed = search_snapshot(
+            &buffer.content_snapshot(),
+            &regex,
+            0..buffer.len(),
+            MAX_REPORTED_MATCHES,
+            &AtomicBool::new(true),
+            |_, _, _| true,
+        )
+        .unwrap();
+        assert!(!completed);
+    }
+
+    #[test]
+    fn test_search_counts_matches_past_reported_limit() {
+        let buffer = TextBuffer::from_bytes(b"ab".repeat(CHUNK_SIZE));
+        let regex = Regex::new("a").unwrap();
+
+        let (mut reported, mut counted) = (Vec::new(), 0);
+        search_snapshot(
+            &buffer.content_snapshot(),
+            &regex,
+            0..buffer.len(),
+            3,
+            &AtomicBool::new(false),
+            |matches, match_count, _| {
+                reported.extend(matches);
+                counted += match_count;
+                true
+            },
+        )
+        .unwrap();
+        assert_eq!(reported, vec![(0, 1), (2, 1), (4, 1)]);
+        assert_eq!(counted, CHUNK_SIZE);
+    }
+}
@@ -0,0 +1,502 @@
+//! EditorConfig support
+//!
+//! `.editorconfig` files are looked up from a file's directory towards the
+//! filesystem root, stopping after one that declares `root = true`. Sections
+//! whose glob matches the file apply in order, so closer files and later
+//! sections win. See <https://editorconfig.org> for the format.
+//!
+//! The supported properties are merged into a [`BufferConfig`] by
+//! [`EditorConfigProperties::apply_to`]. `end_of_line` and `charset` only
+//! apply to new files: existing files keep the format they were written in.
+
+use crate::config::{BufferConfig, FinalNewlineOption, LineEndingOption};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Name of EditorConfig files
+pub const FILE_NAME: &str = ".editorconfig";
+
+/// `indent_style` property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    Tab,
+    Space,
+}
+
+/// `indent_size` property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentSize {
+    /// Indent by one tab stop (`tab_width` columns)
+    Tab,
+    Columns(usize),
+}
+
+/// EditorConfig properties that apply to one file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditorConfigProperties {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_size: Option<IndentSize>,
+    pub tab_width: Option<usize>,
+    pub end_of_line: Option<LineEndingOption>,
+    pub charset: Option<String>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+    pub max_line_length: Option<usize>,
+}
+
+impl EditorConfigProperties {
+    /// Properties for the file at `path` from the `.editorconfig` files above it
+    pub fn for_file(path: &Path) -> Self {
+        let mut files = Vec::new();
+        let mut dir = path.parent();
+        while let Some(current) = dir {
+            if let Ok(content) = fs::read_to_string(current.join(FILE_NAME)) {
+                let file = EditorConfigFile::parse(&content);
+                let root = file.root;
+                files.push((current, file));
+                if root {
+                    break;
+                }
+            }
+            dir = current.parent();
+        }
+
+        // Outermost file first, so closer files override it
+        let mut raw = HashMap::new();
+        for (dir, file) in files.iter().rev() {
+            for section in &file.sections {
+                if section_matches(&section.glob, dir, path) {
+                    for (key, value) in &section.properties {
+                        raw.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        Self::from_raw(&raw)
+    }
+
+    /// Typed properties from raw `key = value` pairs ("unset" clears a key)
+    fn from_raw(raw: &HashMap<String, String>) -> Self {
+        let get = |key: &str| raw.get(key).map(String::as_str).filter(|v| *v != "unset");
+        let number = |key: &str| {
+            get(key)
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|n| *n > 0)
+        };
+        let boolean = |key: &str| match get(key) {
+            Some("true") => Some(true),
+            Some("false") => Some(false),
+            _ => None,
+        };
+        Self {
+            indent_style: match get("indent_style") {
+                Some("tab") => Some(IndentStyle::Tab),
+                Some("space") => Some(IndentStyle::Space),
+                _ => None,
+            },
+            indent_size: match get("indent_size") {
+                Some("tab") => Some(IndentSize::Tab),
+                _ => number("indent_size").map(IndentSize::Columns),
+            },
+            tab_width: number("tab_width"),
+            end_of_line: match get("end_of_line") {
+                Some("lf") => Some(LineEndingOption::Lf),
+                Some("crlf") => Some(LineEndingOption::Crlf),
+                Some("cr") => Some(LineEndingOption::Cr),
+                _ => None,
+            },
+            charset: get("charset").map(str::to_string),
+            trim_trailing_whitespace: boolean("trim_trailing_whitespace"),
+            insert_final_newline: boolean("insert_final_newline"),
+            max_line_length: number("max_line_length"),
+        }
+    }
+
+    /// Whether no supported property is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Columns of one indentation level
+    fn indent_columns(&self) -> Option<usize> {
+        match self.indent_size {
+            Some(IndentSize::Columns(n)) => Some(n),
+            Some(IndentSize::Tab) => self.tab_width,
+            None => None,
+        }
+    }
+
+    /// Merge the properties into `config`
+    ///
+    /// `pinned` is called with the name of each `BufferConfig` field about to
+    /// be set; returning true keeps the existing value (for settings made
+    /// explicitly in project configuration). Returns the fields that were set.
+    pub fn apply_to(
+        &self,
+        config: &mut BufferConfig,
+        pinned: impl Fn(&str) -> bool,
+    ) -> Vec<&'static str> {
+        let mut applied = Vec::new();
+        let mut allow = |field: &'static str| {
+            let allowed = !pinned(field);
+            if allowed {
+                applied.push(field);
+            }
+            allowed
+        };
+
+        if let Some(style) = self.indent_style {
+            if allow("use_tabs") {
+                config.use_tabs = style == IndentStyle::Tab;
+            }
+        }
+        // Fresh has a single width for tab stops and indentation levels: use
+        // the one that matters for the indentation style in effect
+        let tab_size = if config.use_tabs {
+            self.tab_width.or(self.indent_columns())
+        } else {
+            self.indent_columns().or(self.tab_width)
+        };
+        if let Some(tab_size) = tab_size {
+            if allow("tab_size") {
+                config.tab_size = tab_size;
+            }
+        }
+        if let Some(line_ending) = &self.end_of_line {
+            if allow("line_ending") {
+                config.line_ending = line_ending.clone();
+            }
+        }
+        if let Some(charset) = &self.charset {
+            if allow("charset") {
+                config.charset = Some(charset.clone());
+            }
+        }
+        if let Some(trim) = self.trim_trailing_whitespace {
+            if allow("trim_trailing_whitespace") {
+                config.trim_trailing_whitespace = trim;
+            }
+        }
+        if let Some(insert) = self.insert_final_newline {
+            if allow("final_newline") {
+                config.final_newline = if insert {
+                    FinalNewlineOption::Ensure
+                } else {
+                    FinalNewlineOption::Strip
+                };
+            }
+        }
+        if let Some(max_line_length) = self.max_line_length {
+            if allow("rulers") {
+                config.rulers = vec![max_line_length];
+            }
+        }
+        applied
+    }
+}
+
+/// A parsed `.editorconfig` file
+#[derive(Debug, Default)]
+struct EditorConfigFile {
+    /// Whether lookup stops at this file
+    root: bool,
+    sections: Vec<Section>,
+}
+
+/// A `[glob]` section and its properties, in file order
+#[derive(Debug)]
+struct Section {
+    glob: String,
+    properties: Vec<(String, String)>,
+}
+
+impl EditorConfigFile {
+    fn parse(content: &str) -> Self {
+        let mut file = Self::default();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(glob) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                file.sections.push(Section {
+                    glob: glob.to_string(),
+                    properties: Vec::new(),
+                });
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim().to_lowercase();
+            match file.sections.last_mut() {
+                Some(section) => section.properties.push((key, value)),
+                None if key == "root" => file.root = value == "true",
+                None => {}
+            }
+        }
+        file
+    }
+}
+
+/// Whether the section glob of the `.editorconfig` in `dir` matches `file`
+///
+/// Globs without a `/` match the file name in any subdirectory; others are
+/// relative to `dir`.
+fn section_matches(glob: &str, dir: &Path, file: &Path) -> bool {
+    let Ok(relative) = file.strip_prefix(dir) else {
+        return false;
+    };
+    let relative = relative.to_string_lossy().replace('\\', "/");
+
+    let (glob, prefix) = if glob.contains('/') {
+        (glob.strip_prefix('/').unwrap_or(glob), "")
+    } else {
+        (glob, "(?:.*/)?")
+    };
+    let chars: Vec<char> = glob.chars().collect();
+    let mut pattern = format!("^{}", prefix);
+    let mut ranges = Vec::new();
+    translate_glob(&chars, &mut pattern, &mut ranges);
+    pattern.push('$');
+
+    let Ok(regex) = Regex::new(&pattern) else {
+        return false;
+    };
+    let Some(captures) = regex.captures(&relative) else {
+        return false;
+    };
+    // Numeric ranges are the only capturing groups, in order
+    ranges.iter().enumerate().all(|(i, (min, max))| {
+        captures
+            .get(i + 1)
+            .and_then(|m| m.as_str().parse::<i64>().ok())
+            .is_some_and(|n| (*min..=*max).contains(&n))
+    })
+}
+
+/// Append the regex for an EditorConfig glob, recording `{n1..n2}` ranges
+fn translate_glob(chars: &[char], out: &mut String, ranges: &mut Vec<(i64, i64)>) {
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '\\' if i < chars.len() => {
+                out.push_str(&regex::escape(&chars[i].to_string()));
+                i += 1;
+            }
+            '*' if chars.get(i) == Some(&'*') => {
+                out.push_str(".*");
+                i += 1;
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => match closing(chars, i, '[', ']') {
+                Some(end) if !chars[i..end].contains(&'/') => {
+                    out.push('[');
+                    let mut j = i;
+                    if matches!(chars.get(j), Some('!' | '^')) {
+                        out.push('^');
+                        j += 1;
+                    }
+                    for &ch in &chars[j..end] {
+                        if matches!(ch, '\\' | '[' | ']' | '^' | '&' | '~') {
+                            out.push('\\');
+                        }
+                        out.push(ch);
+                    }
+                    out.push(']');
+                    i = end + 1;
+                }
+                _ => out.push_str("\\["),
+            },
+            '{' => match closing(chars, i, '{', '}') {
+                Some(end) => {
+                    translate_braces(&chars[i..end], out, ranges);
+                    i = end + 1;
+                }
+                None => out.push_str("\\{"),
+            },
+            _ => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+}
+
+/// Append the regex for the inside of `{...}`
+fn translate_braces(inner: &[char], out: &mut String, ranges: &mut Vec<(i64, i64)>) {
+    let text: String = inner.iter().collect();
+    if let Some((min, max)) = text
+        .split_once("..")
+        .and_then(|(a, b)| Some((a.parse::<i64>().ok()?, b.parse::<i64>().ok()?)))
+    {
+        out.push_str("([+-]?[0-9]+)");
+        ranges.push((min.min(max), min.max(max)));
+        return;
+    }
+
+    // Split on commas outside nested braces
+    let mut alternatives = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut i = 0;
+    while i < inner.len() {
+        match inner[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    alternatives.push(&inner[start..]);
+
+    if alternatives.len() == 1 {
+        // `{word}` without a comma matches literally
+        out.push_str("\\{");
+        translate_glob(inner, out, ranges);
+        out.push_str("\\}");
+        return;
+    }
+    out.push_str("(?:");
+    for (i, alternative) in alternatives.iter().enumerate() {
+        if i > 0 {
+            out.push('|');
+        }
+        translate_glob(alternative, out, ranges);
+    }
+    out.push(')');
+}
+
+/// Index of the bracket closing the one opened just before `start`
+fn closing(chars: &[char], start: usize, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            c if c == close && depth == 0 => return Some(i),
+            c if c == close => depth -= 1,
+            c if c == open && open != '[' => depth += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use tempfile::TempDir;
+
+    fn matches(glob: &str, file: &str) -> bool {
+        section_matches(
+            glob,
+            Path::new("/project"),
+            &Path::new("/project").join(file),
+        )
+    }
+
+    #[test]
+    fn test_glob_matching() {
+        assert!(matches("*", "main.rs"));
+        assert!(matches("*.rs", "src/deep/main.rs"));
+        assert!(!matches("*.rs", "main.rs.bak"));
+        assert!(matches("*.{js,ts}", "web/app.ts"));
+        assert!(!matches("*.{js,ts}", "web/app.tsx"));
+        assert!(matches("Makefile", "sub/Makefile"));
+        assert!(matches("/Makefile", "Makefile"));
+        assert!(!matches("/Makefile", "sub/Makefile"));
+        assert!(matches("src/*.rs", "src/main.rs"));
+        assert!(!matches("src/*.rs", "src/app/main.rs"));
+        assert!(matches("src/**.rs", "src/app/main.rs"));
+        assert!(matches("file?.txt", "file1.txt"));
+        assert!(matches("[abc].md", "b.md"));
+        assert!(!matches("[!abc].md", "b.md"));
+        assert!(matches("test{1..10}.py", "test7.py"));
+        assert!(!matches("test{1..10}.py", "test11.py"));
+        assert!(matches("{single}.txt", "{single}.txt"));
+        assert!(matches("{a,{b,c}}.txt", "c.txt"));
+    }
+
+    #[test]
+    fn test_lookup_merges_up_to_root() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("outer");
+        let project = root.join("project");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        // Ignored: above the root file
+        std::fs::write(dir.path().join(FILE_NAME), "[*]\nmax_line_length = 40\n").unwrap();
+        std::fs::write(
+            root.join(FILE_NAME),
+            "root = true\n\n[*]\nindent_style = space\nindent_size = 4\nend_of_line = lf\n\
+             trim_trailing_whitespace = true\n",
+        )
+        .unwrap();
+        std::fs::write(
+            project.join(FILE_NAME),
+            "# Go and Makefiles use tabs\n[{*.go,Makefile}]\nindent_style = tab\ntab_width = 8\n\
+             indent_size = tab\n\n[*.md]\ntrim_trailing_whitespace = unset\n",
+        )
+        .unwrap();
+
+        let rust = EditorConfigProperties::for_file(&project.join("src/main.rs"));
+        assert_eq!(rust.indent_style, Some(IndentStyle::Space));
+        assert_eq!(rust.indent_size, Some(IndentSize::Columns(4)));
+        assert_eq!(rust.end_of_line, Some(LineEndingOption::Lf));
+        assert_eq!(rust.trim_trailing_whitespace, Some(true));
+        assert_eq!(rust.max_line_length, None);
+
+        let go = EditorConfigProperties::for_file(&project.join("main.go"));
+        assert_eq!(go.indent_style, Some(IndentStyle::Tab));
+        assert_eq!(go.indent_size, Some(IndentSize::Tab));
+        assert_eq!(go.tab_width, Some(8));
+
+        let markdown = EditorConfigProperties::for_file(&project.join("README.md"));
+        assert_eq!(markdown.trim_trailing_whitespace, None);
+
+        let outside = EditorConfigProperties::for_file(&dir.path().join("other.rs"));
+        assert_eq!(outside.max_line_length, Some(40));
+    }
+
+    #[test]
+    fn test_apply_to_buffer_config() {
+        let mut raw = HashMap::new();
+        for (key, value) in [
+            ("indent_style", "tab"),
+            ("indent_size", "tab"),
+            ("tab_width", "8"),
+            ("end_of_line", "crlf"),
+            ("charset", "latin1"),
+            ("insert_final_newline", "true"),
+            ("max_line_length", "100"),
+        ] {
+            raw.insert(key.to_string(), value.to_string());
+        }
+        let properties = EditorConfigProperties::from_raw(&raw);
+
+        let mut config = BufferConfig::resolve(&Config::default(), None);
+        let applied = properties.apply_to(&mut config, |field| field == "tab_size");
+        assert!(config.use_tabs);
+        assert_eq!(config.tab_size, 4);
+        assert_eq!(config.line_ending, LineEndingOption::Crlf);
+        assert_eq!(config.charset.as_deref(), Some("latin1"));
+        assert_eq!(config.final_newline, FinalNewlineOption::Ensure);
+        assert!(!config.trim_trailing_whitespace);
+        assert_eq!(config.rulers, [100]);
+        assert!(!applied.contains(&"tab_size"));
+        assert!(applied.contains(&"use_tabs"));
+
+        properties.apply_to(&mut config, |_| false);
+        assert_eq!(config.tab_size, 8);
+    }
+}
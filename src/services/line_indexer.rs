@@ -0,0 +1,197 @@
+//! Background line indexing for large files
+//!
+//! Large files are loaded lazily without line metadata, so line numbers are
+//! estimated from byte offsets. The line indexer scans such a file once on a
+//! blocking thread, counting the line feeds of each `LOAD_CHUNK_SIZE` chunk, and
+//! sends the counts to the main loop in batches. The main loop records them in
+//! the buffer's piece tree (see `TextBuffer::apply_line_feed_counts`), which
+//! switches line numbers to exact values once the whole file has been scanned.
+
+use crate::model::buffer::{LineIndexSource, LOAD_CHUNK_SIZE};
+use crate::model::event::BufferId;
+use crate::services::async_bridge::AsyncMessage;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+
+/// Number of chunks scanned between progress messages
+const CHUNKS_PER_BATCH: usize = 16;
+
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A running line indexing task for one buffer.
+///
+/// Dropping the task cancels the scan, so replacing or closing the buffer's
+/// state stops it.
+#[derive(Debug)]
+pub struct LineIndexTask {
+    /// Identifies the messages of this task
+    pub id: u64,
+    /// Content being scanned
+    pub source: LineIndexSource,
+    /// Bytes of the content scanned so far
+    pub bytes_scanned: usize,
+    cancel: Arc<AtomicBool>,
+}
+
+impl LineIndexTask {
+    /// Start scanning `source` on the runtime's blocking thread pool
+    pub fn spawn(
+        runtime: &tokio::runtime::Runtime,
+        sender: mpsc::Sender<AsyncMessage>,
+        buffer_id: BufferId,
+        source: LineIndexSource,
+    ) -> Self {
+        let id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let task_source = source.clone();
+        let task_cancel = cancel.clone();
+        runtime.spawn_blocking(move || {
+            let result = scan_line_feeds(&task_source, &task_cancel, |first_chunk, counts| {
+                let bytes_scanned =
+                    ((first_chunk + counts.len()) * LOAD_CHUNK_SIZE).min(task_source.len);
+                sender
+                    .send(AsyncMessage::LineIndexProgress {
+                        buffer_id,
+                        task_id: id,
+                        first_chunk,
+                        counts,
+                        bytes_scanned,
+                    })
+                    .is_ok()
+            });
+            if let Err(e) = result {
+                let _ = sender.send(AsyncMessage::LineIndexFailed {
+                    buffer_id,
+                    task_id: id,
+                    error: e.to_string(),
+                });
+            }
+        });
+
+        Self {
+            id,
+            source,
+            bytes_scanned: 0,
+            cancel,
+        }
+    }
+
+    /// Percentage of the content scanned so far
+    pub fn percent(&self) -> usize {
+        if self.source.len == 0 {
+            100
+        } else {
+            (self.bytes_scanned as u128 * 100 / self.source.len as u128) as usize
+        }
+    }
+}
+
+impl Drop for LineIndexTask {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Count the line feeds of each chunk of `source`.
+///
+/// Counts are passed to `on_batch` with the index of their first chunk, a few
+/// chunks at a time. Scanning stops early when `cancel` is set or `on_batch`
+/// returns false.
+pub fn scan_line_feeds(
+    source: &LineIndexSource,
+    cancel: &AtomicBool,
+    mut on_batch: impl FnMut(usize, Vec<usize>) -> bool,
+) -> io::Result<()> {
+    let mut file = File::open(&source.path)?;
+    file.seek(SeekFrom::Start(source.offset as u64))?;
+    let mut reader = file.take(source.len as u64);
+
+    let mut chunk = vec![0u8; LOAD_CHUNK_SIZE];
+    let mut first_chunk = 0;
+    let mut counts = Vec::with_capacity(CHUNKS_PER_BATCH);
+    let mut remaining = source.len;
+
+    while remaining > 0 {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let chunk_len = remaining.min(LOAD_CHUNK_SIZE);
+        reader.read_exact(&mut chunk[..chunk_len])?;
+        counts.push(chunk[..chunk_len].iter().filter(|&&b| b == b'\n').count());
+        remaining -= chunk_len;
+
+        if counts.len() == CHUNKS_PER_BATCH || remaining == 0 {
+            let batch = std::mem::replace(&mut counts, Vec::with_capacity(CHUNKS_PER_BATCH));
+            let batch_len = batch.len();
+            if !on_batch(first_chunk, batch) {
+                return Ok(());
+            }
+            first_chunk += batch_len;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scan_counts_line_feeds_per_chunk() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("big.log");
+        // A header to skip, then just over two chunks of content
+        let mut content = b"HEADER".to_vec();
+        content.extend(vec![b'x'; LOAD_CHUNK_SIZE - 1]);
+        content.push(b'\n');
+        content.extend(b"a\nb\nc".repeat(LOAD_CHUNK_SIZE / 5));
+        content.extend(b"\n\n");
+        std::fs::write(&path, &content).unwrap();
+
+        let source = LineIndexSource {
+            path,
+            offset: 6,
+            len: content.len() - 6,
+        };
+        let mut batches = Vec::new();
+        scan_line_feeds(&source, &AtomicBool::new(false), |first, counts| {
+            batches.push((first, counts));
+            true
+        })
+        .unwrap();
+
+        assert_eq!(batches.len(), 1);
+        let (first, counts) = &batches[0];
+        assert_eq!(*first, 0);
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts[0], 1);
+        assert_eq!(
+            counts.iter().sum::<usize>(),
+            content.iter().filter(|&&b| b == b'\n').count()
+        );
+    }
+
+    #[test]
+    fn test_scan_stops_when_cancelled() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("big.log");
+        std::fs::write(&path, "line\n".repeat(LOAD_CHUNK_SIZE)).unwrap();
+
+        let source = LineIndexSource {
+            path,
+            offset: 0,
+            len: 5 * LOAD_CHUNK_SIZE,
+        };
+        let mut called = false;
+        scan_line_feeds(&source, &AtomicBool::new(true), |_, _| {
+            called = true;
+            true
+        })
+        .unwrap();
+        assert!(!called);
+    }
+}
@@ -0,0 +1,331 @@
+//! Local file history
+//!
+//! Keeps a snapshot of a file's content every time it is saved, independently
+//! of version control, so saved edits that were later overwritten (by a
+//! formatter, a script or another save) can be recovered.
+//!
+//! ## File Layout
+//!
+//! The store is per project; each file gets a directory named after the hash
+//! of its path:
+//!
+//! ```text
+//! ~/.local/share/fresh/history/{project_hash}/
+//! └── {path_hash}/
+//!     ├── index.json        # File path and snapshots, oldest first
+//!     └── {content_hash}.gz # Gzip-compressed snapshot content
+//! ```
+//!
+//! Snapshots are deduplicated by content: saving content identical to the
+//! newest snapshot adds nothing, and snapshots with the same content share one
+//! compressed file. Snapshots past the age limit, and the oldest ones past the
+//! count limit, are removed when a new one is recorded.
+
+use crate::services::recovery::path_hash;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A saved version of a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// When the file was saved (seconds since epoch)
+    pub saved_at: u64,
+    /// SHA-256 of the content
+    pub content_hash: String,
+    /// Size of the content in bytes
+    pub size: usize,
+}
+
+/// Snapshots of one file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HistoryIndex {
+    /// Path of the file
+    path: PathBuf,
+    /// Snapshots, oldest first
+    snapshots: Vec<Snapshot>,
+}
+
+/// Limits applied when recording snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionLimits {
+    /// Most snapshots kept per file
+    pub max_entries: usize,
+    /// Snapshots older than this are removed (the newest one is always kept)
+    pub max_age: Duration,
+}
+
+/// Storage for the local history of a project's files
+#[derive(Debug)]
+pub struct LocalHistoryStore {
+    /// Base directory of the project's history
+    dir: PathBuf,
+}
+
+impl LocalHistoryStore {
+    /// Name of each file's index
+    const INDEX_FILE: &'static str = "index.json";
+    /// File extension of snapshot content files
+    const CONTENT_EXT: &'static str = "gz";
+
+    /// Create a store for the project at `project_root` under `base_dir`
+    pub fn for_project(base_dir: &Path, project_root: &Path) -> Self {
+        Self::new(base_dir.join(path_hash(project_root)))
+    }
+
+    /// Create a store in the given directory
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Get the base directory
+    pub fn base_dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn file_dir(&self, path: &Path) -> PathBuf {
+        self.dir.join(path_hash(path))
+    }
+
+    fn content_path(&self, path: &Path, content_hash: &str) -> PathBuf {
+        self.file_dir(path)
+            .join(format!("{content_hash}.{}", Self::CONTENT_EXT))
+    }
+
+    /// Record `content` as the newest snapshot of `path`
+    ///
+    /// Returns `Ok(false)` if it matches the newest snapshot and nothing was
+    /// recorded.
+    pub fn record(
+        &self,
+        path: &Path,
+        content: &[u8],
+        saved_at: SystemTime,
+        limits: RetentionLimits,
+    ) -> io::Result<bool> {
+        let content_hash = hash_bytes(content);
+        let mut index = self.read_index(path)?.unwrap_or_else(|| HistoryIndex {
+            path: path.to_path_buf(),
+            snapshots: Vec::new(),
+        });
+        if index.snapshots.last().map(|s| &s.content_hash) == Some(&content_hash) {
+            return Ok(false);
+        }
+
+        let file_dir = self.file_dir(path);
+        fs::create_dir_all(&file_dir)?;
+        let content_path = self.content_path(path, &content_hash);
+        if !content_path.exists() {
+            let temp_path = content_path.with_extension("gz.tmp");
+            let mut encoder = GzEncoder::new(
+                fs::File::create(&temp_path)?,
+                flate2::Compression::default(),
+            );
+            encoder.write_all(content)?;
+            encoder.finish()?.sync_all()?;
+            fs::rename(&temp_path, &content_path)?;
+        }
+
+        index.snapshots.push(Snapshot {
+            saved_at: unix_secs(saved_at),
+            content_hash,
+            size: content.len(),
+        });
+        self.apply_limits(&mut index, saved_at, limits);
+        self.write_index(path, &index)?;
+        self.remove_unreferenced_content(path, &index)?;
+        Ok(true)
+    }
+
+    /// Snapshots of `path`, newest first
+    pub fn snapshots(&self, path: &Path) -> io::Result<Vec<Snapshot>> {
+        let mut snapshots = self
+            .read_index(path)?
+            .map(|index| index.snapshots)
+            .unwrap_or_default();
+        snapshots.reverse();
+        Ok(snapshots)
+    }
+
+    /// Read the content of a snapshot of `path`
+    pub fn read(&self, path: &Path, snapshot: &Snapshot) -> io::Result<Vec<u8>> {
+        let file = fs::File::open(self.content_path(path, &snapshot.content_hash))?;
+        let mut content = Vec::with_capacity(snapshot.size);
+        GzDecoder::new(file).read_to_end(&mut content)?;
+        Ok(content)
+    }
+
+    fn read_index(&self, path: &Path) -> io::Result<Option<HistoryIndex>> {
+        let index_path = self.file_dir(path).join(Self::INDEX_FILE);
+        let json = match fs::read_to_string(&index_path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        match serde_json::from_str::<HistoryIndex>(&json) {
+            // Two paths with the same hash would share a directory
+            Ok(index) if index.path == path => Ok(Some(index)),
+            Ok(_) => Ok(None),
+            Err(e) => {
+                tracing::debug!("Discarding unreadable local history for {:?}: {}", path, e);
+                Ok(None)
+            }
+        }
+    }
+
+    fn write_index(&self, path: &Path, index: &HistoryIndex) -> io::Result<()> {
+        let index_path = self.file_dir(path).join(Self::INDEX_FILE);
+        let json = serde_json::to_string_pretty(index).map_err(io::Error::other)?;
+        let temp_path = index_path.with_extension("json.tmp");
+        fs::write(&temp_path, json)?;
+        fs::rename(&temp_path, &index_path)
+    }
+
+    /// Drop snapshots past the age and count limits, keeping the newest
+    fn apply_limits(&self, index: &mut HistoryIndex, now: SystemTime, limits: RetentionLimits) {
+        let cutoff = unix_secs(now).saturating_sub(limits.max_age.as_secs());
+        let newest = index.snapshots.len().saturating_sub(1);
+        let mut i = 0;
+        index.snapshots.retain(|snapshot| {
+            let keep = i == newest || snapshot.saved_at >= cutoff;
+            i += 1;
+            keep
+        });
+        let excess = index
+            .snapshots
+            .len()
+            .saturating_sub(limits.max_entries.max(1));
+        index.snapshots.drain(..excess);
+    }
+
+    /// Remove content files no snapshot refers to anymore
+    fn remove_unreferenced_content(&self, path: &Path, index: &HistoryIndex) -> io::Result<()> {
+        for entry in fs::read_dir(self.file_dir(path))? {
+            let content_path = entry?.path();
+            let Some(hash) = content_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(&format!(".{}", Self::CONTENT_EXT)))
+            else {
+                continue;
+            };
+            if !index.snapshots.iter().any(|s| s.content_hash == hash) {
+                fs::remove_file(&content_path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compute the SHA-256 of `content`
+fn hash_bytes(content: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(content))
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const LIMITS: RetentionLimits = RetentionLimits {
+        max_entries: 10,
+        max_age: Duration::from_secs(30 * 24 * 60 * 60),
+    };
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+    }
+
+    fn create_test_store() -> (LocalHistoryStore, TempDir, PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let store =
+            LocalHistoryStore::for_project(&temp_dir.path().join("history"), temp_dir.path());
+        let file = temp_dir.path().join("main.rs");
+        (store, temp_dir, file)
+    }
+
+    fn contents(store: &LocalHistoryStore, file: &Path) -> Vec<String> {
+        store
+            .snapshots(file)
+            .unwrap()
+            .iter()
+            .map(|s| String::from_utf8(store.read(file, s).unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshots_round_trip_newest_first() {
+        let (store, _temp, file) = create_test_store();
+        assert!(store.record(&file, b"one", at(0), LIMITS).unwrap());
+        assert!(store.record(&file, b"two", at(10), LIMITS).unwrap());
+
+        assert_eq!(contents(&store, &file), vec!["two", "one"]);
+        assert_eq!(
+            store.snapshots(&file).unwrap()[0].saved_at,
+            unix_secs(at(10))
+        );
+    }
+
+    #[test]
+    fn test_identical_content_is_deduplicated() {
+        let (store, _temp, file) = create_test_store();
+        store.record(&file, b"one", at(0), LIMITS).unwrap();
+        assert!(!store.record(&file, b"one", at(1), LIMITS).unwrap());
+        store.record(&file, b"two", at(2), LIMITS).unwrap();
+        store.record(&file, b"one", at(3), LIMITS).unwrap();
+
+        assert_eq!(contents(&store, &file), vec!["one", "two", "one"]);
+        // Snapshots with the same content share one content file
+        let content_files = fs::read_dir(store.file_dir(&file))
+            .unwrap()
+            .filter(|e| e.as_ref().unwrap().path().extension() == Some("gz".as_ref()))
+            .count();
+        assert_eq!(content_files, 2);
+    }
+
+    #[test]
+    fn test_limits_drop_old_snapshots_and_their_content() {
+        let (store, _temp, file) = create_test_store();
+        let limits = RetentionLimits {
+            max_entries: 2,
+            max_age: Duration::from_secs(100),
+        };
+        store.record(&file, b"ancient", at(0), limits).unwrap();
+        store.record(&file, b"one", at(500), limits).unwrap();
+        assert_eq!(contents(&store, &file), vec!["one"]);
+
+        store.record(&file, b"two", at(510), limits).unwrap();
+        store.record(&file, b"three", at(520), limits).unwrap();
+        assert_eq!(contents(&store, &file), vec!["three", "two"]);
+        assert!(!store.content_path(&file, &hash_bytes(b"one")).exists());
+
+        // The newest snapshot is kept however old it is
+        store.record(&file, b"four", at(10_000), limits).unwrap();
+        assert_eq!(contents(&store, &file), vec!["four"]);
+    }
+
+    #[test]
+    fn test_files_and_projects_are_separate() {
+        let (store, temp, file) = create_test_store();
+        let other_file = temp.path().join("lib.rs");
+        store.record(&file, b"main", at(0), LIMITS).unwrap();
+        store.record(&other_file, b"lib", at(0), LIMITS).unwrap();
+        assert_eq!(contents(&store, &file), vec!["main"]);
+        assert_eq!(contents(&store, &other_file), vec!["lib"]);
+
+        let other_project =
+            LocalHistoryStore::for_project(&temp.path().join("history"), Path::new("/elsewhere"));
+        assert!(other_project.snapshots(&file).unwrap().is_empty());
+    }
+}
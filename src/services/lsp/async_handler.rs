@@ -21,14 +21,22 @@ use lsp_types::{
         PublishDiagnostics,
     },
     request::{Initialize, Request},
-    ClientCapabilities, DidChangeTextDocumentParams, DidOpenTextDocumentParams,
-    DidSaveTextDocumentParams, InitializeParams, InitializeResult, InitializedParams,
-    PublishDiagnosticsParams, SemanticTokenModifier, SemanticTokenType,
-    SemanticTokensClientCapabilities, SemanticTokensClientCapabilitiesRequests,
+    CallHierarchyClientCapabilities, CallHierarchyItem, CallHierarchyServerCapability,
+    ClientCapabilities, CodeLens, CodeLensClientCapabilities, CodeLensWorkspaceClientCapabilities,
+    DidChangeTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    DocumentFormattingClientCapabilities, DocumentOnTypeFormattingClientCapabilities,
+    DocumentRangeFormattingClientCapabilities, DocumentSymbolClientCapabilities,
+    DocumentSymbolResponse, FoldingRange, FoldingRangeClientCapabilities,
+    FoldingRangeProviderCapability, FormattingOptions, InitializeParams, InitializeResult,
+    InitializedParams, Position, PublishDiagnosticsParams, SelectionRange,
+    SelectionRangeClientCapabilities, SelectionRangeProviderCapability, SemanticTokenModifier,
+    SemanticTokenType, SemanticTokensClientCapabilities, SemanticTokensClientCapabilitiesRequests,
     SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensParams, SemanticTokensResult,
     SemanticTokensServerCapabilities, ServerCapabilities, TextDocumentContentChangeEvent,
-    TextDocumentIdentifier, TextDocumentItem, TokenFormat, Uri, VersionedTextDocumentIdentifier,
-    WindowClientCapabilities, WorkspaceFolder,
+    TextDocumentIdentifier, TextDocumentItem, TextEdit, TokenFormat,
+    TypeHierarchyClientCapabilities, TypeHierarchyItem, Uri, VersionedTextDocumentIdentifier,
+    WindowClientCapabilities, WorkspaceFolder, WorkspaceSymbolClientCapabilities,
+    WorkspaceSymbolResponse,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -199,8 +207,9 @@ impl LspClientState {
 /// Create common LSP client capabilities with workDoneProgress support
 fn create_client_capabilities() -> ClientCapabilities {
     use lsp_types::{
-        GeneralClientCapabilities, RenameClientCapabilities, TextDocumentClientCapabilities,
-        WorkspaceClientCapabilities, WorkspaceEditClientCapabilities,
+        ExecuteCommandClientCapabilities, GeneralClientCapabilities, RenameClientCapabilities,
+        TextDocumentClientCapabilities, WorkspaceClientCapabilities,
+        WorkspaceEditClientCapabilities,
     };
 
     ClientCapabilities {
@@ -214,6 +223,11 @@ fn create_client_capabilities() -> ClientCapabilities {
                 document_changes: Some(true),
                 ..Default::default()
             }),
+            symbol: Some(WorkspaceSymbolClientCapabilities::default()),
+            execute_command: Some(ExecuteCommandClientCapabilities::default()),
+            code_lens: Some(CodeLensWorkspaceClientCapabilities {
+                refresh_support: Some(true),
+            }),
             ..Default::default()
         }),
         text_document: Some(TextDocumentClientCapabilities {
@@ -223,6 +237,21 @@ fn create_client_capabilities() -> ClientCapabilities {
                 honors_change_annotations: Some(true),
                 ..Default::default()
             }),
+            folding_range: Some(FoldingRangeClientCapabilities {
+                line_folding_only: Some(true),
+                ..Default::default()
+            }),
+            selection_range: Some(SelectionRangeClientCapabilities::default()),
+            formatting: Some(DocumentFormattingClientCapabilities::default()),
+            range_formatting: Some(DocumentRangeFormattingClientCapabilities::default()),
+            on_type_formatting: Some(DocumentOnTypeFormattingClientCapabilities::default()),
+            call_hierarchy: Some(CallHierarchyClientCapabilities::default()),
+            type_hierarchy: Some(TypeHierarchyClientCapabilities::default()),
+            code_lens: Some(CodeLensClientCapabilities::default()),
+            document_symbol: Some(DocumentSymbolClientCapabilities {
+                hierarchical_document_symbol_support: Some(true),
+                ..Default::default()
+            }),
             semantic_tokens: Some(SemanticTokensClientCapabilities {
                 dynamic_registration: Some(true),
                 requests: SemanticTokensClientCapabilitiesRequests {
@@ -313,6 +342,27 @@ fn semantic_tokens_full_supported(full: &Option<SemanticTokensFullOptions>) -> b
     }
 }
 
+/// The "go to" requests that answer with the locations of the symbol at a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GotoKind {
+    Definition,
+    TypeDefinition,
+    Implementation,
+    Declaration,
+}
+
+impl GotoKind {
+    /// LSP method of the request
+    pub fn method(self) -> &'static str {
+        match self {
+            Self::Definition => "textDocument/definition",
+            Self::TypeDefinition => "textDocument/typeDefinition",
+            Self::Implementation => "textDocument/implementation",
+            Self::Declaration => "textDocument/declaration",
+        }
+    }
+}
+
 /// Commands sent from the main loop to the LSP task
 #[derive(Debug)]
 enum LspCommand {
@@ -347,9 +397,10 @@ enum LspCommand {
         character: u32,
     },
 
-    /// Request go-to-definition
-    GotoDefinition {
+    /// Request the definition, type definition, implementation or declaration
+    Goto {
         request_id: u64,
+        kind: GotoKind,
         uri: Uri,
         line: u32,
         character: u32,
@@ -421,6 +472,86 @@ enum LspCommand {
     /// Request semantic tokens for the entire document
     SemanticTokensFull { request_id: u64, uri: Uri },
 
+    /// Request folding ranges for a document
+    FoldingRange { request_id: u64, uri: Uri },
+
+    /// Request the symbols of a document
+    DocumentSymbol { request_id: u64, uri: Uri },
+
+    /// Search the symbols of the whole workspace
+    WorkspaceSymbol { request_id: u64, query: String },
+
+    /// Format a whole document
+    Formatting {
+        request_id: u64,
+        uri: Uri,
+        options: FormattingOptions,
+    },
+
+    /// Format a range of a document
+    RangeFormatting {
+        request_id: u64,
+        uri: Uri,
+        range: lsp_types::Range,
+        options: FormattingOptions,
+    },
+
+    /// Format a document after a trigger character was typed
+    OnTypeFormatting {
+        request_id: u64,
+        uri: Uri,
+        position: Position,
+        ch: String,
+        options: FormattingOptions,
+    },
+
+    /// Prepare the call hierarchy of the symbol at a position
+    PrepareCallHierarchy {
+        request_id: u64,
+        uri: Uri,
+        position: Position,
+    },
+
+    /// Request the incoming or outgoing calls of a call hierarchy item
+    CallHierarchyCalls {
+        request_id: u64,
+        item: CallHierarchyItem,
+        incoming: bool,
+    },
+
+    /// Prepare the type hierarchy of the symbol at a position
+    PrepareTypeHierarchy {
+        request_id: u64,
+        uri: Uri,
+        position: Position,
+    },
+
+    /// Request the supertypes or subtypes of a type hierarchy item
+    TypeHierarchyRelatives {
+        request_id: u64,
+        item: TypeHierarchyItem,
+        supertypes: bool,
+    },
+
+    /// Request selection ranges around positions in a document
+    SelectionRange {
+        request_id: u64,
+        uri: Uri,
+        positions: Vec<Position>,
+    },
+
+    /// Request the code lenses of a document
+    CodeLens { request_id: u64, uri: Uri },
+
+    /// Fill in the command of a code lens
+    CodeLensResolve { request_id: u64, lens: CodeLens },
+
+    /// Run a command on the server (workspace/executeCommand)
+    ExecuteCommand {
+        request_id: u64,
+        command: lsp_types::Command,
+    },
+
     /// Cancel a pending request
     CancelRequest {
         /// Editor's request ID to cancel
@@ -657,9 +788,11 @@ impl LspState {
             ..Default::default()
         };
 
-        let result: InitializeResult = self
+        let raw_result: Value = self
             .send_request_sequential(Initialize::METHOD, Some(params), pending)
             .await?;
+        let result: InitializeResult = serde_json::from_value(raw_result.clone())
+            .map_err(|e| format!("Failed to deserialize initialize response: {}", e))?;
 
         self.capabilities = Some(result.capabilities.clone());
 
@@ -679,6 +812,56 @@ impl LspState {
 
         let (semantic_tokens_legend, semantic_tokens_full) =
             extract_semantic_token_capability(&result.capabilities);
+        let folding_range = !matches!(
+            result.capabilities.folding_range_provider,
+            None | Some(FoldingRangeProviderCapability::Simple(false))
+        );
+        let selection_range = !matches!(
+            result.capabilities.selection_range_provider,
+            None | Some(SelectionRangeProviderCapability::Simple(false))
+        );
+        let document_symbol = !matches!(
+            result.capabilities.document_symbol_provider,
+            None | Some(lsp_types::OneOf::Left(false))
+        );
+        let workspace_symbol = !matches!(
+            result.capabilities.workspace_symbol_provider,
+            None | Some(lsp_types::OneOf::Left(false))
+        );
+        let formatting = !matches!(
+            result.capabilities.document_formatting_provider,
+            None | Some(lsp_types::OneOf::Left(false))
+        );
+        let range_formatting = !matches!(
+            result.capabilities.document_range_formatting_provider,
+            None | Some(lsp_types::OneOf::Left(false))
+        );
+        let on_type_formatting_triggers = result
+            .capabilities
+            .document_on_type_formatting_provider
+            .as_ref()
+            .map(|options| {
+                std::iter::once(options.first_trigger_character.clone())
+                    .chain(options.more_trigger_character.iter().flatten().cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let call_hierarchy = !matches!(
+            result.capabilities.call_hierarchy_provider,
+            None | Some(CallHierarchyServerCapability::Simple(false))
+        );
+        let code_lens = result.capabilities.code_lens_provider.is_some();
+        let code_lens_resolve = result
+            .capabilities
+            .code_lens_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false);
+        // lsp-types has no field for typeHierarchyProvider (LSP 3.17)
+        let type_hierarchy = !matches!(
+            raw_result.pointer("/capabilities/typeHierarchyProvider"),
+            None | Some(Value::Null) | Some(Value::Bool(false))
+        );
 
         // Notify main loop
         let _ = self.async_tx.send(AsyncMessage::LspInitialized {
@@ -686,6 +869,17 @@ impl LspState {
             completion_trigger_characters,
             semantic_tokens_legend,
             semantic_tokens_full,
+            folding_range,
+            selection_range,
+            document_symbol,
+            workspace_symbol,
+            formatting,
+            range_formatting,
+            on_type_formatting_triggers,
+            call_hierarchy,
+            type_hierarchy,
+            code_lens,
+            code_lens_resolve,
         });
 
         // Send running status
@@ -872,10 +1066,11 @@ impl LspState {
         }
     }
 
-    /// Handle go-to-definition request
-    async fn handle_goto_definition(
+    /// Handle go-to-definition, type definition, implementation or declaration request
+    async fn handle_goto(
         &mut self,
         request_id: u64,
+        kind: GotoKind,
         uri: Uri,
         line: u32,
         character: u32,
@@ -887,7 +1082,8 @@ impl LspState {
         };
 
         tracing::trace!(
-            "LSP: go-to-definition request at {}:{}:{}",
+            "LSP: {} request at {}:{}:{}",
+            kind.method(),
             uri.as_str(),
             line,
             character
@@ -904,11 +1100,11 @@ impl LspState {
 
         // Send request and get response
         match self
-            .send_request_sequential::<_, Value>("textDocument/definition", Some(params), pending)
+            .send_request_sequential::<_, Value>(kind.method(), Some(params), pending)
             .await
         {
             Ok(result) => {
-                // Parse the definition response (can be Location, Vec<Location>, or LocationLink)
+                // Parse the response (can be Location, Vec<Location>, or LocationLink)
                 let locations = if let Ok(loc) =
                     serde_json::from_value::<lsp_types::Location>(result.clone())
                 {
@@ -933,16 +1129,16 @@ impl LspState {
                 };
 
                 // Send to main loop
-                let _ = self.async_tx.send(AsyncMessage::LspGotoDefinition {
+                let _ = self.async_tx.send(AsyncMessage::LspGoto {
                     request_id,
                     locations,
                 });
                 Ok(())
             }
             Err(e) => {
-                tracing::error!("Go-to-definition request failed: {}", e);
+                tracing::error!("{} request failed: {}", kind.method(), e);
                 // Send empty locations on error
-                let _ = self.async_tx.send(AsyncMessage::LspGotoDefinition {
+                let _ = self.async_tx.send(AsyncMessage::LspGoto {
                     request_id,
                     locations: vec![],
                 });
@@ -1616,136 +1812,744 @@ impl LspState {
         }
     }
 
-    /// Handle a plugin-initiated request by forwarding it to the server
-    async fn handle_plugin_request(
+    async fn handle_folding_range(
         &mut self,
         request_id: u64,
-        method: String,
-        params: Option<Value>,
+        uri: Uri,
         pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
-    ) {
-        tracing::trace!(
-            "Plugin request {} => method={} params={:?}",
-            request_id,
-            method,
-            params
-        );
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::FoldingRangeRequest, FoldingRangeParams, PartialResultParams,
+            WorkDoneProgressParams,
+        };
+
+        tracing::trace!("LSP: foldingRange request for {}", uri.as_str());
+
+        let params = FoldingRangeParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
         let result = self
-            .send_request_sequential_tracked::<Value, Value>(
-                &method,
-                params,
+            .send_request_sequential_tracked::<_, Option<Vec<FoldingRange>>>(
+                FoldingRangeRequest::METHOD,
+                Some(params),
                 pending,
                 Some(request_id),
             )
             .await;
-
-        tracing::trace!(
-            "Plugin request {} completed with result {:?}",
-            request_id,
-            &result
-        );
-        let _ = self.async_tx.send(AsyncMessage::PluginLspResponse {
-            language: self.language.clone(),
+        let ranges = match &result {
+            Ok(ranges) => ranges.clone().unwrap_or_default(),
+            Err(e) => {
+                tracing::warn!("Folding range request failed: {}", e);
+                Vec::new()
+            }
+        };
+        let _ = self.async_tx.send(AsyncMessage::LspFoldingRanges {
             request_id,
-            result,
+            uri: uri.as_str().to_string(),
+            ranges,
         });
+        result.map(|_| ())
     }
 
-    /// Handle shutdown command
-    async fn handle_shutdown(&mut self) -> Result<(), String> {
-        tracing::info!("Shutting down async LSP server");
-
-        let notification = JsonRpcNotification {
-            jsonrpc: "2.0".to_string(),
-            method: "shutdown".to_string(),
-            params: None,
+    async fn handle_document_symbol(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::DocumentSymbolRequest, DocumentSymbolParams, PartialResultParams,
+            WorkDoneProgressParams,
         };
 
-        self.write_message(&notification).await?;
+        tracing::trace!("LSP: documentSymbol request for {}", uri.as_str());
 
-        let exit = JsonRpcNotification {
-            jsonrpc: "2.0".to_string(),
-            method: "exit".to_string(),
-            params: None,
+        let params = DocumentSymbolParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
         };
 
-        self.write_message(&exit).await
-    }
-
-    /// Send a cancel request notification to the server
-    async fn send_cancel_request(&mut self, lsp_id: i64) -> Result<(), String> {
-        tracing::trace!("Sending $/cancelRequest for LSP id {}", lsp_id);
-
-        let notification = JsonRpcNotification {
-            jsonrpc: "2.0".to_string(),
-            method: "$/cancelRequest".to_string(),
-            params: Some(serde_json::json!({ "id": lsp_id })),
+        let result = self
+            .send_request_sequential_tracked::<_, Option<DocumentSymbolResponse>>(
+                DocumentSymbolRequest::METHOD,
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await;
+        let symbols = match &result {
+            Ok(symbols) => Some(
+                symbols
+                    .clone()
+                    .unwrap_or(DocumentSymbolResponse::Nested(Vec::new())),
+            ),
+            Err(e) => {
+                tracing::warn!("Document symbol request failed: {}", e);
+                None
+            }
         };
-
-        self.write_message(&notification).await
+        let _ = self.async_tx.send(AsyncMessage::LspDocumentSymbols {
+            request_id,
+            uri: uri.as_str().to_string(),
+            symbols,
+        });
+        result.map(|_| ())
     }
 
-    /// Cancel a request by editor request_id
-    async fn handle_cancel_request(&mut self, request_id: u64) -> Result<(), String> {
-        if let Some(lsp_id) = self.active_requests.remove(&request_id) {
-            tracing::info!(
-                "Cancelling request: editor_id={}, lsp_id={}",
-                request_id,
-                lsp_id
-            );
-            self.send_cancel_request(lsp_id).await
-        } else {
-            tracing::trace!(
-                "Cancel request ignored: no active LSP request for editor_id={}",
-                request_id
-            );
-            Ok(())
-        }
-    }
-}
+    async fn handle_workspace_symbol(
+        &mut self,
+        request_id: u64,
+        query: String,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::WorkspaceSymbolRequest, PartialResultParams, WorkDoneProgressParams,
+            WorkspaceSymbolParams,
+        };
 
-/// Async LSP task that handles all I/O
-struct LspTask {
-    /// Process handle - kept alive for lifetime management (kill_on_drop)
-    _process: Child,
+        tracing::trace!("LSP: workspace/symbol request for {:?}", query);
 
-    /// Stdin for sending messages
-    stdin: ChildStdin,
+        let params = WorkspaceSymbolParams {
+            query,
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
 
-    /// Stdout for receiving messages
-    stdout: BufReader<ChildStdout>,
+        let result = self
+            .send_request_sequential_tracked::<_, Option<WorkspaceSymbolResponse>>(
+                WorkspaceSymbolRequest::METHOD,
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await;
+        let symbols = match &result {
+            Ok(symbols) => Some(
+                symbols
+                    .clone()
+                    .unwrap_or(WorkspaceSymbolResponse::Flat(Vec::new())),
+            ),
+            Err(e) => {
+                tracing::warn!("Workspace symbol request failed: {}", e);
+                None
+            }
+        };
+        let _ = self.async_tx.send(AsyncMessage::LspWorkspaceSymbols {
+            request_id,
+            language: self.language.clone(),
+            symbols,
+        });
+        result.map(|_| ())
+    }
 
-    /// Next request ID
-    next_id: i64,
+    async fn handle_formatting(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        options: FormattingOptions,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{request::Formatting, DocumentFormattingParams, WorkDoneProgressParams};
 
-    /// Pending requests waiting for response
-    pending: HashMap<i64, oneshot::Sender<Result<Value, String>>>,
+        tracing::trace!("LSP: formatting request for {}", uri.as_str());
 
-    /// Server capabilities
-    capabilities: Option<ServerCapabilities>,
+        let params = DocumentFormattingParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            options,
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+        self.send_formatting_request(request_id, uri, Formatting::METHOD, params, pending)
+            .await
+    }
 
-    /// Document versions
-    document_versions: HashMap<PathBuf, i64>,
+    async fn handle_range_formatting(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        range: lsp_types::Range,
+        options: FormattingOptions,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::RangeFormatting, DocumentRangeFormattingParams, WorkDoneProgressParams,
+        };
 
-    /// Track when didOpen was sent for each document to avoid race with didChange
-    /// The LSP server needs time to process didOpen before it can handle didChange
-    pending_opens: HashMap<PathBuf, Instant>,
+        tracing::trace!("LSP: rangeFormatting request for {}", uri.as_str());
 
-    /// Whether initialized
-    initialized: bool,
+        let params = DocumentRangeFormattingParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range,
+            options,
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+        self.send_formatting_request(request_id, uri, RangeFormatting::METHOD, params, pending)
+            .await
+    }
 
-    /// Sender for async messages to main loop
-    async_tx: std_mpsc::Sender<AsyncMessage>,
+    async fn handle_on_type_formatting(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        position: Position,
+        ch: String,
+        options: FormattingOptions,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::OnTypeFormatting, DocumentOnTypeFormattingParams, TextDocumentPositionParams,
+        };
 
-    /// Language ID (for error reporting)
-    language: String,
+        tracing::trace!("LSP: onTypeFormatting request for {}", uri.as_str());
 
-    /// Server command (for plugin identification)
-    server_command: String,
+        let params = DocumentOnTypeFormattingParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            ch,
+            options,
+        };
+        self.send_formatting_request(request_id, uri, OnTypeFormatting::METHOD, params, pending)
+            .await
+    }
 
-    /// Path to stderr log file
-    stderr_log_path: std::path::PathBuf,
-}
+    /// Send one of the formatting requests and report the edits it returns
+    async fn send_formatting_request<P: Serialize>(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        method: &str,
+        params: P,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        let result = self
+            .send_request_sequential_tracked::<_, Option<Vec<TextEdit>>>(
+                method,
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await;
+        let edits = match &result {
+            Ok(edits) => Some(edits.clone().unwrap_or_default()),
+            Err(e) => {
+                tracing::warn!("{} request failed: {}", method, e);
+                None
+            }
+        };
+        let _ = self.async_tx.send(AsyncMessage::LspFormatting {
+            request_id,
+            uri: uri.as_str().to_string(),
+            edits,
+        });
+        result.map(|_| ())
+    }
+
+    async fn handle_prepare_call_hierarchy(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        position: Position,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::CallHierarchyPrepare, CallHierarchyPrepareParams, TextDocumentPositionParams,
+            WorkDoneProgressParams,
+        };
+
+        tracing::trace!("LSP: prepareCallHierarchy request for {}", uri.as_str());
+
+        let params = CallHierarchyPrepareParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+        let result = self
+            .send_request_sequential_tracked::<_, Option<Vec<CallHierarchyItem>>>(
+                CallHierarchyPrepare::METHOD,
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await;
+        let items = match &result {
+            Ok(items) => Some(items.clone().unwrap_or_default()),
+            Err(e) => {
+                tracing::warn!("Prepare call hierarchy request failed: {}", e);
+                None
+            }
+        };
+        let _ = self
+            .async_tx
+            .send(AsyncMessage::LspCallHierarchy { request_id, items });
+        result.map(|_| ())
+    }
+
+    async fn handle_call_hierarchy_calls(
+        &mut self,
+        request_id: u64,
+        item: CallHierarchyItem,
+        incoming: bool,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::{CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls},
+            CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyOutgoingCall,
+            CallHierarchyOutgoingCallsParams, PartialResultParams, WorkDoneProgressParams,
+        };
+
+        tracing::trace!(
+            "LSP: {} calls request for {}",
+            if incoming { "incoming" } else { "outgoing" },
+            item.name
+        );
+
+        let result = if incoming {
+            let params = CallHierarchyIncomingCallsParams {
+                item,
+                work_done_progress_params: WorkDoneProgressParams::default(),
+                partial_result_params: PartialResultParams::default(),
+            };
+            self.send_request_sequential_tracked::<_, Option<Vec<CallHierarchyIncomingCall>>>(
+                CallHierarchyIncomingCalls::METHOD,
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await
+            .map(|calls| {
+                calls
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|call| (call.from, call.from_ranges))
+                    .collect::<Vec<_>>()
+            })
+        } else {
+            let params = CallHierarchyOutgoingCallsParams {
+                item,
+                work_done_progress_params: WorkDoneProgressParams::default(),
+                partial_result_params: PartialResultParams::default(),
+            };
+            self.send_request_sequential_tracked::<_, Option<Vec<CallHierarchyOutgoingCall>>>(
+                CallHierarchyOutgoingCalls::METHOD,
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await
+            .map(|calls| {
+                calls
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|call| (call.to, call.from_ranges))
+                    .collect::<Vec<_>>()
+            })
+        };
+        let calls = match &result {
+            Ok(calls) => Some(calls.clone()),
+            Err(e) => {
+                tracing::warn!("Call hierarchy calls request failed: {}", e);
+                None
+            }
+        };
+        let _ = self
+            .async_tx
+            .send(AsyncMessage::LspCallHierarchyCalls { request_id, calls });
+        result.map(|_| ())
+    }
+
+    async fn handle_prepare_type_hierarchy(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        position: Position,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::TypeHierarchyPrepare, TextDocumentPositionParams, TypeHierarchyPrepareParams,
+            WorkDoneProgressParams,
+        };
+
+        tracing::trace!("LSP: prepareTypeHierarchy request for {}", uri.as_str());
+
+        let params = TypeHierarchyPrepareParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+        self.send_type_hierarchy_request(request_id, TypeHierarchyPrepare::METHOD, params, pending)
+            .await
+    }
+
+    async fn handle_type_hierarchy_relatives(
+        &mut self,
+        request_id: u64,
+        item: TypeHierarchyItem,
+        supertypes: bool,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::{TypeHierarchySubtypes, TypeHierarchySupertypes},
+            PartialResultParams, TypeHierarchySubtypesParams, TypeHierarchySupertypesParams,
+            WorkDoneProgressParams,
+        };
+
+        tracing::trace!(
+            "LSP: {} request for {}",
+            if supertypes { "supertypes" } else { "subtypes" },
+            item.name
+        );
+
+        if supertypes {
+            let params = TypeHierarchySupertypesParams {
+                item,
+                work_done_progress_params: WorkDoneProgressParams::default(),
+                partial_result_params: PartialResultParams::default(),
+            };
+            self.send_type_hierarchy_request(
+                request_id,
+                TypeHierarchySupertypes::METHOD,
+                params,
+                pending,
+            )
+            .await
+        } else {
+            let params = TypeHierarchySubtypesParams {
+                item,
+                work_done_progress_params: WorkDoneProgressParams::default(),
+                partial_result_params: PartialResultParams::default(),
+            };
+            self.send_type_hierarchy_request(
+                request_id,
+                TypeHierarchySubtypes::METHOD,
+                params,
+                pending,
+            )
+            .await
+        }
+    }
+
+    /// Send one of the type hierarchy requests and report the items it returns
+    async fn send_type_hierarchy_request<P: Serialize>(
+        &mut self,
+        request_id: u64,
+        method: &str,
+        params: P,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        let result = self
+            .send_request_sequential_tracked::<_, Option<Vec<TypeHierarchyItem>>>(
+                method,
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await;
+        let items = match &result {
+            Ok(items) => Some(items.clone().unwrap_or_default()),
+            Err(e) => {
+                tracing::warn!("{} request failed: {}", method, e);
+                None
+            }
+        };
+        let _ = self
+            .async_tx
+            .send(AsyncMessage::LspTypeHierarchy { request_id, items });
+        result.map(|_| ())
+    }
+
+    async fn handle_selection_range(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        positions: Vec<Position>,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::SelectionRangeRequest, PartialResultParams, SelectionRangeParams,
+            WorkDoneProgressParams,
+        };
+
+        tracing::trace!("LSP: selectionRange request for {}", uri.as_str());
+
+        let params = SelectionRangeParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            positions,
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
+        let result = self
+            .send_request_sequential_tracked::<_, Option<Vec<SelectionRange>>>(
+                SelectionRangeRequest::METHOD,
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await;
+        let ranges = match &result {
+            Ok(ranges) => ranges.clone().unwrap_or_default(),
+            Err(e) => {
+                tracing::warn!("Selection range request failed: {}", e);
+                Vec::new()
+            }
+        };
+        let _ = self.async_tx.send(AsyncMessage::LspSelectionRanges {
+            request_id,
+            uri: uri.as_str().to_string(),
+            ranges,
+        });
+        result.map(|_| ())
+    }
+
+    async fn handle_code_lens(
+        &mut self,
+        request_id: u64,
+        uri: Uri,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{
+            request::CodeLensRequest, CodeLensParams, PartialResultParams, WorkDoneProgressParams,
+        };
+
+        tracing::trace!("LSP: codeLens request for {}", uri.as_str());
+
+        let params = CodeLensParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
+        let result = self
+            .send_request_sequential_tracked::<_, Option<Vec<CodeLens>>>(
+                CodeLensRequest::METHOD,
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await;
+        let lenses = match &result {
+            Ok(lenses) => Some(lenses.clone().unwrap_or_default()),
+            Err(e) => {
+                tracing::warn!("Code lens request failed: {}", e);
+                None
+            }
+        };
+        let _ = self.async_tx.send(AsyncMessage::LspCodeLenses {
+            request_id,
+            uri: uri.as_str().to_string(),
+            lenses,
+        });
+        result.map(|_| ())
+    }
+
+    async fn handle_code_lens_resolve(
+        &mut self,
+        request_id: u64,
+        lens: CodeLens,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::request::CodeLensResolve;
+
+        tracing::trace!(
+            "LSP: codeLens/resolve request at line {}",
+            lens.range.start.line
+        );
+
+        let result = self
+            .send_request_sequential_tracked::<_, CodeLens>(
+                CodeLensResolve::METHOD,
+                Some(lens),
+                pending,
+                Some(request_id),
+            )
+            .await;
+        let lens = match &result {
+            Ok(lens) => Some(lens.clone()),
+            Err(e) => {
+                tracing::warn!("Code lens resolve request failed: {}", e);
+                None
+            }
+        };
+        let _ = self
+            .async_tx
+            .send(AsyncMessage::LspCodeLensResolved { request_id, lens });
+        result.map(|_| ())
+    }
+
+    async fn handle_execute_command(
+        &mut self,
+        request_id: u64,
+        command: lsp_types::Command,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) -> Result<(), String> {
+        use lsp_types::{request::ExecuteCommand, ExecuteCommandParams, WorkDoneProgressParams};
+
+        tracing::trace!("LSP: executeCommand request for {}", command.command);
+
+        let params = ExecuteCommandParams {
+            command: command.command,
+            arguments: command.arguments.unwrap_or_default(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+
+        let result = self
+            .send_request_sequential_tracked::<_, Value>(
+                ExecuteCommand::METHOD,
+                Some(params),
+                pending,
+                Some(request_id),
+            )
+            .await
+            .map(|_| ());
+        if let Err(e) = &result {
+            tracing::warn!("Execute command request failed: {}", e);
+        }
+        let _ = self.async_tx.send(AsyncMessage::LspExecuteCommand {
+            request_id,
+            result: result.clone(),
+        });
+        result
+    }
+
+    /// Handle a plugin-initiated request by forwarding it to the server
+    async fn handle_plugin_request(
+        &mut self,
+        request_id: u64,
+        method: String,
+        params: Option<Value>,
+        pending: &Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>,
+    ) {
+        tracing::trace!(
+            "Plugin request {} => method={} params={:?}",
+            request_id,
+            method,
+            params
+        );
+        let result = self
+            .send_request_sequential_tracked::<Value, Value>(
+                &method,
+                params,
+                pending,
+                Some(request_id),
+            )
+            .await;
+
+        tracing::trace!(
+            "Plugin request {} completed with result {:?}",
+            request_id,
+            &result
+        );
+        let _ = self.async_tx.send(AsyncMessage::PluginLspResponse {
+            language: self.language.clone(),
+            request_id,
+            result,
+        });
+    }
+
+    /// Handle shutdown command
+    async fn handle_shutdown(&mut self) -> Result<(), String> {
+        tracing::info!("Shutting down async LSP server");
+
+        let notification = JsonRpcNotification {
+            jsonrpc: "2.0".to_string(),
+            method: "shutdown".to_string(),
+            params: None,
+        };
+
+        self.write_message(&notification).await?;
+
+        let exit = JsonRpcNotification {
+            jsonrpc: "2.0".to_string(),
+            method: "exit".to_string(),
+            params: None,
+        };
+
+        self.write_message(&exit).await
+    }
+
+    /// Send a cancel request notification to the server
+    async fn send_cancel_request(&mut self, lsp_id: i64) -> Result<(), String> {
+        tracing::trace!("Sending $/cancelRequest for LSP id {}", lsp_id);
+
+        let notification = JsonRpcNotification {
+            jsonrpc: "2.0".to_string(),
+            method: "$/cancelRequest".to_string(),
+            params: Some(serde_json::json!({ "id": lsp_id })),
+        };
+
+        self.write_message(&notification).await
+    }
+
+    /// Cancel a request by editor request_id
+    async fn handle_cancel_request(&mut self, request_id: u64) -> Result<(), String> {
+        if let Some(lsp_id) = self.active_requests.remove(&request_id) {
+            tracing::info!(
+                "Cancelling request: editor_id={}, lsp_id={}",
+                request_id,
+                lsp_id
+            );
+            self.send_cancel_request(lsp_id).await
+        } else {
+            tracing::trace!(
+                "Cancel request ignored: no active LSP request for editor_id={}",
+                request_id
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Async LSP task that handles all I/O
+struct LspTask {
+    /// Process handle - kept alive for lifetime management (kill_on_drop)
+    _process: Child,
+
+    /// Stdin for sending messages
+    stdin: ChildStdin,
+
+    /// Stdout for receiving messages
+    stdout: BufReader<ChildStdout>,
+
+    /// Next request ID
+    next_id: i64,
+
+    /// Pending requests waiting for response
+    pending: HashMap<i64, oneshot::Sender<Result<Value, String>>>,
+
+    /// Server capabilities
+    capabilities: Option<ServerCapabilities>,
+
+    /// Document versions
+    document_versions: HashMap<PathBuf, i64>,
+
+    /// Track when didOpen was sent for each document to avoid race with didChange
+    /// The LSP server needs time to process didOpen before it can handle didChange
+    pending_opens: HashMap<PathBuf, Instant>,
+
+    /// Whether initialized
+    initialized: bool,
+
+    /// Sender for async messages to main loop
+    async_tx: std_mpsc::Sender<AsyncMessage>,
+
+    /// Language ID (for error reporting)
+    language: String,
+
+    /// Server command (for plugin identification)
+    server_command: String,
+
+    /// Path to stderr log file
+    stderr_log_path: std::path::PathBuf,
+}
 
 impl LspTask {
     /// Create a new LSP task
@@ -1917,380 +2721,666 @@ impl LspTask {
                                 stderr_log_path: Some(stderr_log_path.clone()),
                             });
                         }
-                        break;
-                    }
-                }
-            }
-            tracing::info!("LSP stdout reader task exiting for {}", language);
-        });
-    }
-
-    /// Run the task (processes commands and reads from stdout)
-    async fn run(self, mut command_rx: mpsc::Receiver<LspCommand>) {
-        tracing::info!("LspTask::run() started for language: {}", self.language);
-
-        // Create state struct for command processing
-        let mut state = LspState {
-            stdin: self.stdin,
-            next_id: self.next_id,
-            capabilities: self.capabilities,
-            document_versions: self.document_versions,
-            pending_opens: self.pending_opens,
-            initialized: self.initialized,
-            async_tx: self.async_tx.clone(),
-            language: self.language.clone(),
-            active_requests: HashMap::new(),
-        };
-
-        let pending = Arc::new(Mutex::new(self.pending));
-        let async_tx = state.async_tx.clone();
-        let language_clone = state.language.clone();
-
-        // Create channel for server-to-client request responses
-        let (server_response_tx, mut server_response_rx) = mpsc::channel::<JsonRpcResponse>(100);
-
-        // Flag to indicate intentional shutdown (prevents spurious error messages)
-        let shutting_down = Arc::new(AtomicBool::new(false));
-
-        // Spawn stdout reader task
-        Self::spawn_stdout_reader(
-            self.stdout,
-            pending.clone(),
-            async_tx.clone(),
-            language_clone.clone(),
-            self.server_command.clone(),
-            server_response_tx,
-            self.stderr_log_path,
-            shutting_down.clone(),
-        );
-
-        // Sequential command processing loop with server response handling
-        let mut pending_commands = Vec::new();
-        loop {
-            tokio::select! {
-                // Handle server-to-client responses (high priority)
-                Some(response) = server_response_rx.recv() => {
-                    tracing::trace!("Sending response to server request id={}", response.id);
-                    if let Err(e) = state.write_message(&response).await {
-                        tracing::error!("Failed to send response to server: {}", e);
-                    }
-                }
-                // Handle commands from the editor
-                Some(cmd) = command_rx.recv() => {
-                    tracing::trace!("LspTask received command: {:?}", cmd);
-                    match cmd {
-                        LspCommand::Initialize { root_uri, initialization_options, response } => {
-                            // Send initializing status
-                            let _ = async_tx.send(AsyncMessage::LspStatusUpdate {
-                                language: language_clone.clone(),
-                                status: LspServerStatus::Initializing,
-                            });
-                            tracing::info!("Processing Initialize command");
-                            let result =
-                                state.handle_initialize_sequential(root_uri, initialization_options, &pending).await;
-                            let success = result.is_ok();
-                            let _ = response.send(result);
-
-                            // After successful initialization, replay pending commands
-                            if success {
-                                let queued = std::mem::take(&mut pending_commands);
-                                state.replay_pending_commands(queued, &pending).await;
+                        break;
+                    }
+                }
+            }
+            tracing::info!("LSP stdout reader task exiting for {}", language);
+        });
+    }
+
+    /// Run the task (processes commands and reads from stdout)
+    async fn run(self, mut command_rx: mpsc::Receiver<LspCommand>) {
+        tracing::info!("LspTask::run() started for language: {}", self.language);
+
+        // Create state struct for command processing
+        let mut state = LspState {
+            stdin: self.stdin,
+            next_id: self.next_id,
+            capabilities: self.capabilities,
+            document_versions: self.document_versions,
+            pending_opens: self.pending_opens,
+            initialized: self.initialized,
+            async_tx: self.async_tx.clone(),
+            language: self.language.clone(),
+            active_requests: HashMap::new(),
+        };
+
+        let pending = Arc::new(Mutex::new(self.pending));
+        let async_tx = state.async_tx.clone();
+        let language_clone = state.language.clone();
+
+        // Create channel for server-to-client request responses
+        let (server_response_tx, mut server_response_rx) = mpsc::channel::<JsonRpcResponse>(100);
+
+        // Flag to indicate intentional shutdown (prevents spurious error messages)
+        let shutting_down = Arc::new(AtomicBool::new(false));
+
+        // Spawn stdout reader task
+        Self::spawn_stdout_reader(
+            self.stdout,
+            pending.clone(),
+            async_tx.clone(),
+            language_clone.clone(),
+            self.server_command.clone(),
+            server_response_tx,
+            self.stderr_log_path,
+            shutting_down.clone(),
+        );
+
+        // Sequential command processing loop with server response handling
+        let mut pending_commands = Vec::new();
+        loop {
+            tokio::select! {
+                // Handle server-to-client responses (high priority)
+                Some(response) = server_response_rx.recv() => {
+                    tracing::trace!("Sending response to server request id={}", response.id);
+                    if let Err(e) = state.write_message(&response).await {
+                        tracing::error!("Failed to send response to server: {}", e);
+                    }
+                }
+                // Handle commands from the editor
+                Some(cmd) = command_rx.recv() => {
+                    tracing::trace!("LspTask received command: {:?}", cmd);
+                    match cmd {
+                        LspCommand::Initialize { root_uri, initialization_options, response } => {
+                            // Send initializing status
+                            let _ = async_tx.send(AsyncMessage::LspStatusUpdate {
+                                language: language_clone.clone(),
+                                status: LspServerStatus::Initializing,
+                            });
+                            tracing::info!("Processing Initialize command");
+                            let result =
+                                state.handle_initialize_sequential(root_uri, initialization_options, &pending).await;
+                            let success = result.is_ok();
+                            let _ = response.send(result);
+
+                            // After successful initialization, replay pending commands
+                            if success {
+                                let queued = std::mem::take(&mut pending_commands);
+                                state.replay_pending_commands(queued, &pending).await;
+                            }
+                        }
+                        LspCommand::DidOpen {
+                            uri,
+                            text,
+                            language_id,
+                        } => {
+                            if state.initialized {
+                                tracing::info!("Processing DidOpen for {}", uri.as_str());
+                                let _ = state
+                                    .handle_did_open_sequential(uri, text, language_id, &pending)
+                                    .await;
+                            } else {
+                                tracing::trace!(
+                                    "Queueing DidOpen for {} until initialization completes",
+                                    uri.as_str()
+                                );
+                                pending_commands.push(LspCommand::DidOpen {
+                                    uri,
+                                    text,
+                                    language_id,
+                                });
+                            }
+                        }
+                        LspCommand::DidChange {
+                            uri,
+                            content_changes,
+                        } => {
+                            if state.initialized {
+                                tracing::trace!("Processing DidChange for {}", uri.as_str());
+                                let _ = state
+                                    .handle_did_change_sequential(uri, content_changes, &pending)
+                                    .await;
+                            } else {
+                                tracing::trace!(
+                                    "Queueing DidChange for {} until initialization completes",
+                                    uri.as_str()
+                                );
+                                pending_commands.push(LspCommand::DidChange {
+                                    uri,
+                                    content_changes,
+                                });
+                            }
+                        }
+                        LspCommand::DidSave { uri, text } => {
+                            if state.initialized {
+                                tracing::info!("Processing DidSave for {}", uri.as_str());
+                                let _ = state.handle_did_save(uri, text).await;
+                            } else {
+                                tracing::trace!(
+                                    "Queueing DidSave for {} until initialization completes",
+                                    uri.as_str()
+                                );
+                                pending_commands.push(LspCommand::DidSave { uri, text });
+                            }
+                        }
+                        LspCommand::Completion {
+                            request_id,
+                            uri,
+                            line,
+                            character,
+                        } => {
+                            if state.initialized {
+                                tracing::info!(
+                                    "Processing Completion request for {}",
+                                    uri.as_str()
+                                );
+                                let _ = state
+                                    .handle_completion(request_id, uri, line, character, &pending)
+                                    .await;
+                            } else {
+                                tracing::trace!("LSP not initialized, sending empty completion");
+                                let _ = state.async_tx.send(AsyncMessage::LspCompletion {
+                                    request_id,
+                                    items: vec![],
+                                });
+                            }
+                        }
+                        LspCommand::Goto {
+                            request_id,
+                            kind,
+                            uri,
+                            line,
+                            character,
+                        } => {
+                            if state.initialized {
+                                tracing::info!(
+                                    "Processing {:?} request for {}",
+                                    kind,
+                                    uri.as_str()
+                                );
+                                let _ = state
+                                    .handle_goto(
+                                        request_id, kind, uri, line, character, &pending,
+                                    )
+                                    .await;
+                            } else {
+                                tracing::trace!("LSP not initialized, sending empty locations");
+                                let _ = state.async_tx.send(AsyncMessage::LspGoto {
+                                    request_id,
+                                    locations: vec![],
+                                });
+                            }
+                        }
+                        LspCommand::Rename {
+                            request_id,
+                            uri,
+                            line,
+                            character,
+                            new_name,
+                        } => {
+                            if state.initialized {
+                                tracing::info!("Processing Rename request for {}", uri.as_str());
+                                let _ = state
+                                    .handle_rename(
+                                        request_id, uri, line, character, new_name, &pending,
+                                    )
+                                    .await;
+                            } else {
+                                tracing::trace!("LSP not initialized, cannot rename");
+                                let _ = state.async_tx.send(AsyncMessage::LspRename {
+                                    request_id,
+                                    result: Err("LSP not initialized".to_string()),
+                                });
+                            }
+                        }
+                        LspCommand::Hover {
+                            request_id,
+                            uri,
+                            line,
+                            character,
+                        } => {
+                            if state.initialized {
+                                tracing::info!("Processing Hover request for {}", uri.as_str());
+                                let _ = state
+                                    .handle_hover(request_id, uri, line, character, &pending)
+                                    .await;
+                            } else {
+                                tracing::trace!("LSP not initialized, cannot get hover");
+                                let _ = state.async_tx.send(AsyncMessage::LspHover {
+                                    request_id,
+                                    contents: String::new(),
+                                    is_markdown: false,
+                                    range: None,
+                                });
+                            }
+                        }
+                        LspCommand::References {
+                            request_id,
+                            uri,
+                            line,
+                            character,
+                        } => {
+                            if state.initialized {
+                                tracing::info!("Processing References request for {}", uri.as_str());
+                                let _ = state
+                                    .handle_references(request_id, uri, line, character, &pending)
+                                    .await;
+                            } else {
+                                tracing::trace!("LSP not initialized, cannot get references");
+                                let _ = state.async_tx.send(AsyncMessage::LspReferences {
+                                    request_id,
+                                    locations: Vec::new(),
+                                });
+                            }
+                        }
+                        LspCommand::SignatureHelp {
+                            request_id,
+                            uri,
+                            line,
+                            character,
+                        } => {
+                            if state.initialized {
+                                tracing::info!("Processing SignatureHelp request for {}", uri.as_str());
+                                let _ = state
+                                    .handle_signature_help(request_id, uri, line, character, &pending)
+                                    .await;
+                            } else {
+                                tracing::trace!("LSP not initialized, cannot get signature help");
+                                let _ = state.async_tx.send(AsyncMessage::LspSignatureHelp {
+                                    request_id,
+                                    signature_help: None,
+                                });
                             }
                         }
-                        LspCommand::DidOpen {
+                        LspCommand::CodeActions {
+                            request_id,
                             uri,
-                            text,
-                            language_id,
+                            start_line,
+                            start_char,
+                            end_line,
+                            end_char,
+                            diagnostics,
                         } => {
                             if state.initialized {
-                                tracing::info!("Processing DidOpen for {}", uri.as_str());
+                                tracing::info!("Processing CodeActions request for {}", uri.as_str());
                                 let _ = state
-                                    .handle_did_open_sequential(uri, text, language_id, &pending)
+                                    .handle_code_actions(
+                                        request_id,
+                                        uri,
+                                        start_line,
+                                        start_char,
+                                        end_line,
+                                        end_char,
+                                        diagnostics,
+                                        &pending,
+                                    )
                                     .await;
                             } else {
-                                tracing::trace!(
-                                    "Queueing DidOpen for {} until initialization completes",
+                                tracing::trace!("LSP not initialized, cannot get code actions");
+                                let _ = state.async_tx.send(AsyncMessage::LspCodeActions {
+                                    request_id,
+                                    actions: Vec::new(),
+                                });
+                            }
+                        }
+                        LspCommand::DocumentDiagnostic {
+                            request_id,
+                            uri,
+                            previous_result_id,
+                        } => {
+                            if state.initialized {
+                                tracing::info!(
+                                    "Processing DocumentDiagnostic request for {}",
                                     uri.as_str()
                                 );
-                                pending_commands.push(LspCommand::DidOpen {
-                                    uri,
-                                    text,
-                                    language_id,
+                                let _ = state
+                                    .handle_document_diagnostic(
+                                        request_id,
+                                        uri,
+                                        previous_result_id,
+                                        &pending,
+                                    )
+                                    .await;
+                            } else {
+                                tracing::trace!(
+                                    "LSP not initialized, cannot get document diagnostics"
+                                );
+                                let _ = state.async_tx.send(AsyncMessage::LspPulledDiagnostics {
+                                    request_id,
+                                    uri: uri.as_str().to_string(),
+                                    result_id: None,
+                                    diagnostics: Vec::new(),
+                                    unchanged: false,
                                 });
                             }
                         }
-                        LspCommand::DidChange {
+                        LspCommand::InlayHints {
+                            request_id,
                             uri,
-                            content_changes,
+                            start_line,
+                            start_char,
+                            end_line,
+                            end_char,
                         } => {
                             if state.initialized {
-                                tracing::trace!("Processing DidChange for {}", uri.as_str());
+                                tracing::info!(
+                                    "Processing InlayHints request for {}",
+                                    uri.as_str()
+                                );
                                 let _ = state
-                                    .handle_did_change_sequential(uri, content_changes, &pending)
+                                    .handle_inlay_hints(
+                                        request_id,
+                                        uri,
+                                        start_line,
+                                        start_char,
+                                        end_line,
+                                        end_char,
+                                        &pending,
+                                    )
                                     .await;
                             } else {
                                 tracing::trace!(
-                                    "Queueing DidChange for {} until initialization completes",
-                                    uri.as_str()
+                                    "LSP not initialized, cannot get inlay hints"
                                 );
-                                pending_commands.push(LspCommand::DidChange {
-                                    uri,
-                                    content_changes,
+                                let _ = state.async_tx.send(AsyncMessage::LspInlayHints {
+                                    request_id,
+                                    uri: uri.as_str().to_string(),
+                                    hints: Vec::new(),
                                 });
                             }
                         }
-                        LspCommand::DidSave { uri, text } => {
+                        LspCommand::SemanticTokensFull { request_id, uri } => {
                             if state.initialized {
-                                tracing::info!("Processing DidSave for {}", uri.as_str());
-                                let _ = state.handle_did_save(uri, text).await;
+                                tracing::info!(
+                                    "Processing SemanticTokens request for {}",
+                                    uri.as_str()
+                                );
+                                let _ = state
+                                    .handle_semantic_tokens_full(request_id, uri, &pending)
+                                    .await;
                             } else {
                                 tracing::trace!(
-                                    "Queueing DidSave for {} until initialization completes",
+                                    "LSP not initialized, cannot get semantic tokens"
+                                );
+                                let _ = state.async_tx.send(AsyncMessage::LspSemanticTokens {
+                                    request_id,
+                                    uri: uri.as_str().to_string(),
+                                    result: Err("LSP not initialized".to_string()),
+                                });
+                            }
+                        }
+                        LspCommand::FoldingRange { request_id, uri } => {
+                            if state.initialized {
+                                tracing::info!(
+                                    "Processing FoldingRange request for {}",
                                     uri.as_str()
                                 );
-                                pending_commands.push(LspCommand::DidSave { uri, text });
+                                let _ = state
+                                    .handle_folding_range(request_id, uri, &pending)
+                                    .await;
+                            } else {
+                                tracing::trace!("LSP not initialized, cannot get folding ranges");
+                                let _ = state.async_tx.send(AsyncMessage::LspFoldingRanges {
+                                    request_id,
+                                    uri: uri.as_str().to_string(),
+                                    ranges: Vec::new(),
+                                });
                             }
                         }
-                        LspCommand::Completion {
+                        LspCommand::DocumentSymbol { request_id, uri } => {
+                            if state.initialized {
+                                tracing::info!(
+                                    "Processing DocumentSymbol request for {}",
+                                    uri.as_str()
+                                );
+                                let _ = state
+                                    .handle_document_symbol(request_id, uri, &pending)
+                                    .await;
+                            } else {
+                                tracing::trace!("LSP not initialized, cannot get document symbols");
+                                let _ = state.async_tx.send(AsyncMessage::LspDocumentSymbols {
+                                    request_id,
+                                    uri: uri.as_str().to_string(),
+                                    symbols: None,
+                                });
+                            }
+                        }
+                        LspCommand::WorkspaceSymbol { request_id, query } => {
+                            if state.initialized {
+                                tracing::info!("Processing WorkspaceSymbol request for {:?}", query);
+                                let _ = state
+                                    .handle_workspace_symbol(request_id, query, &pending)
+                                    .await;
+                            } else {
+                                tracing::trace!("LSP not initialized, cannot search workspace symbols");
+                                let _ = state.async_tx.send(AsyncMessage::LspWorkspaceSymbols {
+                                    request_id,
+                                    language: state.language.clone(),
+                                    symbols: None,
+                                });
+                            }
+                        }
+                        LspCommand::Formatting {
                             request_id,
-                            uri,
-                            line,
-                            character,
+                            uri,
+                            options,
                         } => {
                             if state.initialized {
-                                tracing::info!(
-                                    "Processing Completion request for {}",
-                                    uri.as_str()
-                                );
+                                tracing::info!("Processing Formatting request for {}", uri.as_str());
                                 let _ = state
-                                    .handle_completion(request_id, uri, line, character, &pending)
+                                    .handle_formatting(request_id, uri, options, &pending)
                                     .await;
                             } else {
-                                tracing::trace!("LSP not initialized, sending empty completion");
-                                let _ = state.async_tx.send(AsyncMessage::LspCompletion {
+                                tracing::trace!("LSP not initialized, cannot format");
+                                let _ = state.async_tx.send(AsyncMessage::LspFormatting {
                                     request_id,
-                                    items: vec![],
+                                    uri: uri.as_str().to_string(),
+                                    edits: None,
                                 });
                             }
                         }
-                        LspCommand::GotoDefinition {
+                        LspCommand::RangeFormatting {
                             request_id,
                             uri,
-                            line,
-                            character,
+                            range,
+                            options,
                         } => {
                             if state.initialized {
                                 tracing::info!(
-                                    "Processing GotoDefinition request for {}",
+                                    "Processing RangeFormatting request for {}",
                                     uri.as_str()
                                 );
                                 let _ = state
-                                    .handle_goto_definition(
-                                        request_id, uri, line, character, &pending,
+                                    .handle_range_formatting(
+                                        request_id, uri, range, options, &pending,
                                     )
                                     .await;
                             } else {
-                                tracing::trace!("LSP not initialized, sending empty locations");
-                                let _ = state.async_tx.send(AsyncMessage::LspGotoDefinition {
+                                tracing::trace!("LSP not initialized, cannot format range");
+                                let _ = state.async_tx.send(AsyncMessage::LspFormatting {
                                     request_id,
-                                    locations: vec![],
+                                    uri: uri.as_str().to_string(),
+                                    edits: None,
                                 });
                             }
                         }
-                        LspCommand::Rename {
+                        LspCommand::OnTypeFormatting {
                             request_id,
                             uri,
-                            line,
-                            character,
-                            new_name,
+                            position,
+                            ch,
+                            options,
                         } => {
                             if state.initialized {
-                                tracing::info!("Processing Rename request for {}", uri.as_str());
+                                tracing::info!(
+                                    "Processing OnTypeFormatting request for {}",
+                                    uri.as_str()
+                                );
                                 let _ = state
-                                    .handle_rename(
-                                        request_id, uri, line, character, new_name, &pending,
+                                    .handle_on_type_formatting(
+                                        request_id, uri, position, ch, options, &pending,
                                     )
                                     .await;
                             } else {
-                                tracing::trace!("LSP not initialized, cannot rename");
-                                let _ = state.async_tx.send(AsyncMessage::LspRename {
+                                tracing::trace!("LSP not initialized, cannot format on type");
+                                let _ = state.async_tx.send(AsyncMessage::LspFormatting {
                                     request_id,
-                                    result: Err("LSP not initialized".to_string()),
+                                    uri: uri.as_str().to_string(),
+                                    edits: None,
                                 });
                             }
                         }
-                        LspCommand::Hover {
+                        LspCommand::PrepareCallHierarchy {
                             request_id,
                             uri,
-                            line,
-                            character,
+                            position,
                         } => {
                             if state.initialized {
-                                tracing::info!("Processing Hover request for {}", uri.as_str());
+                                tracing::info!(
+                                    "Processing PrepareCallHierarchy request for {}",
+                                    uri.as_str()
+                                );
                                 let _ = state
-                                    .handle_hover(request_id, uri, line, character, &pending)
+                                    .handle_prepare_call_hierarchy(
+                                        request_id, uri, position, &pending,
+                                    )
                                     .await;
                             } else {
-                                tracing::trace!("LSP not initialized, cannot get hover");
-                                let _ = state.async_tx.send(AsyncMessage::LspHover {
+                                tracing::trace!("LSP not initialized, cannot prepare call hierarchy");
+                                let _ = state.async_tx.send(AsyncMessage::LspCallHierarchy {
                                     request_id,
-                                    contents: String::new(),
-                                    is_markdown: false,
-                                    range: None,
+                                    items: None,
                                 });
                             }
                         }
-                        LspCommand::References {
+                        LspCommand::CallHierarchyCalls {
                             request_id,
-                            uri,
-                            line,
-                            character,
+                            item,
+                            incoming,
                         } => {
                             if state.initialized {
-                                tracing::info!("Processing References request for {}", uri.as_str());
+                                tracing::info!("Processing CallHierarchyCalls request for {}", item.name);
                                 let _ = state
-                                    .handle_references(request_id, uri, line, character, &pending)
+                                    .handle_call_hierarchy_calls(
+                                        request_id, item, incoming, &pending,
+                                    )
                                     .await;
                             } else {
-                                tracing::trace!("LSP not initialized, cannot get references");
-                                let _ = state.async_tx.send(AsyncMessage::LspReferences {
+                                tracing::trace!("LSP not initialized, cannot get calls");
+                                let _ = state.async_tx.send(AsyncMessage::LspCallHierarchyCalls {
                                     request_id,
-                                    locations: Vec::new(),
+                                    calls: None,
                                 });
                             }
                         }
-                        LspCommand::SignatureHelp {
+                        LspCommand::PrepareTypeHierarchy {
                             request_id,
                             uri,
-                            line,
-                            character,
+                            position,
                         } => {
                             if state.initialized {
-                                tracing::info!("Processing SignatureHelp request for {}", uri.as_str());
+                                tracing::info!(
+                                    "Processing PrepareTypeHierarchy request for {}",
+                                    uri.as_str()
+                                );
                                 let _ = state
-                                    .handle_signature_help(request_id, uri, line, character, &pending)
+                                    .handle_prepare_type_hierarchy(
+                                        request_id, uri, position, &pending,
+                                    )
                                     .await;
                             } else {
-                                tracing::trace!("LSP not initialized, cannot get signature help");
-                                let _ = state.async_tx.send(AsyncMessage::LspSignatureHelp {
+                                tracing::trace!("LSP not initialized, cannot prepare type hierarchy");
+                                let _ = state.async_tx.send(AsyncMessage::LspTypeHierarchy {
                                     request_id,
-                                    signature_help: None,
+                                    items: None,
                                 });
                             }
                         }
-                        LspCommand::CodeActions {
+                        LspCommand::TypeHierarchyRelatives {
                             request_id,
-                            uri,
-                            start_line,
-                            start_char,
-                            end_line,
-                            end_char,
-                            diagnostics,
+                            item,
+                            supertypes,
                         } => {
                             if state.initialized {
-                                tracing::info!("Processing CodeActions request for {}", uri.as_str());
+                                tracing::info!(
+                                    "Processing TypeHierarchyRelatives request for {}",
+                                    item.name
+                                );
                                 let _ = state
-                                    .handle_code_actions(
-                                        request_id,
-                                        uri,
-                                        start_line,
-                                        start_char,
-                                        end_line,
-                                        end_char,
-                                        diagnostics,
-                                        &pending,
+                                    .handle_type_hierarchy_relatives(
+                                        request_id, item, supertypes, &pending,
                                     )
                                     .await;
                             } else {
-                                tracing::trace!("LSP not initialized, cannot get code actions");
-                                let _ = state.async_tx.send(AsyncMessage::LspCodeActions {
+                                tracing::trace!("LSP not initialized, cannot get related types");
+                                let _ = state.async_tx.send(AsyncMessage::LspTypeHierarchy {
                                     request_id,
-                                    actions: Vec::new(),
+                                    items: None,
                                 });
                             }
                         }
-                        LspCommand::DocumentDiagnostic {
+                        LspCommand::SelectionRange {
                             request_id,
                             uri,
-                            previous_result_id,
+                            positions,
                         } => {
                             if state.initialized {
                                 tracing::info!(
-                                    "Processing DocumentDiagnostic request for {}",
+                                    "Processing SelectionRange request for {}",
                                     uri.as_str()
                                 );
                                 let _ = state
-                                    .handle_document_diagnostic(
-                                        request_id,
-                                        uri,
-                                        previous_result_id,
-                                        &pending,
-                                    )
+                                    .handle_selection_range(request_id, uri, positions, &pending)
                                     .await;
                             } else {
                                 tracing::trace!(
-                                    "LSP not initialized, cannot get document diagnostics"
+                                    "LSP not initialized, cannot get selection ranges"
                                 );
-                                let _ = state.async_tx.send(AsyncMessage::LspPulledDiagnostics {
+                                let _ = state.async_tx.send(AsyncMessage::LspSelectionRanges {
                                     request_id,
                                     uri: uri.as_str().to_string(),
-                                    result_id: None,
-                                    diagnostics: Vec::new(),
-                                    unchanged: false,
+                                    ranges: Vec::new(),
                                 });
                             }
                         }
-                        LspCommand::InlayHints {
-                            request_id,
-                            uri,
-                            start_line,
-                            start_char,
-                            end_line,
-                            end_char,
-                        } => {
+                        LspCommand::CodeLens { request_id, uri } => {
+                            if state.initialized {
+                                tracing::info!("Processing CodeLens request for {}", uri.as_str());
+                                let _ = state.handle_code_lens(request_id, uri, &pending).await;
+                            } else {
+                                tracing::trace!("LSP not initialized, cannot get code lenses");
+                                let _ = state.async_tx.send(AsyncMessage::LspCodeLenses {
+                                    request_id,
+                                    uri: uri.as_str().to_string(),
+                                    lenses: None,
+                                });
+                            }
+                        }
+                        LspCommand::CodeLensResolve { request_id, lens } => {
                             if state.initialized {
                                 tracing::info!(
-                                    "Processing InlayHints request for {}",
-                                    uri.as_str()
+                                    "Processing CodeLensResolve request at line {}",
+                                    lens.range.start.line
                                 );
                                 let _ = state
-                                    .handle_inlay_hints(
-                                        request_id,
-                                        uri,
-                                        start_line,
-                                        start_char,
-                                        end_line,
-                                        end_char,
-                                        &pending,
-                                    )
+                                    .handle_code_lens_resolve(request_id, lens, &pending)
                                     .await;
                             } else {
-                                tracing::trace!(
-                                    "LSP not initialized, cannot get inlay hints"
-                                );
-                                let _ = state.async_tx.send(AsyncMessage::LspInlayHints {
+                                tracing::trace!("LSP not initialized, cannot resolve code lens");
+                                let _ = state.async_tx.send(AsyncMessage::LspCodeLensResolved {
                                     request_id,
-                                    uri: uri.as_str().to_string(),
-                                    hints: Vec::new(),
+                                    lens: None,
                                 });
                             }
                         }
-                        LspCommand::SemanticTokensFull { request_id, uri } => {
+                        LspCommand::ExecuteCommand {
+                            request_id,
+                            command,
+                        } => {
                             if state.initialized {
                                 tracing::info!(
-                                    "Processing SemanticTokens request for {}",
-                                    uri.as_str()
+                                    "Processing ExecuteCommand request for {}",
+                                    command.command
                                 );
                                 let _ = state
-                                    .handle_semantic_tokens_full(request_id, uri, &pending)
+                                    .handle_execute_command(request_id, command, &pending)
                                     .await;
                             } else {
-                                tracing::trace!(
-                                    "LSP not initialized, cannot get semantic tokens"
-                                );
-                                let _ = state.async_tx.send(AsyncMessage::LspSemanticTokens {
+                                tracing::trace!("LSP not initialized, cannot execute command");
+                                let _ = state.async_tx.send(AsyncMessage::LspExecuteCommand {
                                     request_id,
-                                    uri: uri.as_str().to_string(),
                                     result: Err("LSP not initialized".to_string()),
                                 });
                             }
@@ -2503,6 +3593,22 @@ async fn handle_message_dispatch(
                         error: None,
                     }
                 }
+                "workspace/codeLens/refresh" => {
+                    // Server wants the code lenses of every document requested again
+                    tracing::trace!(
+                        "Acknowledging workspace/codeLens/refresh (id={})",
+                        request.id
+                    );
+                    let _ = async_tx.send(AsyncMessage::LspCodeLensRefresh {
+                        language: language.to_string(),
+                    });
+                    JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: request.id,
+                        result: Some(Value::Null),
+                        error: None,
+                    }
+                }
                 "client/registerCapability" => {
                     // Server wants to register a capability dynamically - acknowledge
                     tracing::trace!(
@@ -2999,22 +4105,25 @@ impl LspHandle {
             .map_err(|_| "Failed to send completion command".to_string())
     }
 
-    /// Request go-to-definition
-    pub fn goto_definition(
+    /// Request the definition, type definition, implementation or declaration
+    /// of the symbol at a position
+    pub fn goto(
         &self,
         request_id: u64,
+        kind: GotoKind,
         uri: Uri,
         line: u32,
         character: u32,
     ) -> Result<(), String> {
         self.command_tx
-            .try_send(LspCommand::GotoDefinition {
+            .try_send(LspCommand::Goto {
                 request_id,
+                kind,
                 uri,
                 line,
                 character,
             })
-            .map_err(|_| "Failed to send goto_definition command".to_string())
+            .map_err(|_| "Failed to send goto command".to_string())
     }
 
     /// Request rename
@@ -3165,6 +4274,189 @@ impl LspHandle {
             .map_err(|_| "Failed to send semantic_tokens command".to_string())
     }
 
+    /// Request folding ranges for a document
+    pub fn folding_ranges(&self, request_id: u64, uri: Uri) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::FoldingRange { request_id, uri })
+            .map_err(|_| "Failed to send folding_range command".to_string())
+    }
+
+    /// Request the symbols of a document
+    pub fn document_symbols(&self, request_id: u64, uri: Uri) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::DocumentSymbol { request_id, uri })
+            .map_err(|_| "Failed to send document_symbol command".to_string())
+    }
+
+    /// Search the symbols of the whole workspace
+    pub fn workspace_symbols(&self, request_id: u64, query: String) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::WorkspaceSymbol { request_id, query })
+            .map_err(|_| "Failed to send workspace_symbol command".to_string())
+    }
+
+    /// Format a whole document
+    pub fn formatting(
+        &self,
+        request_id: u64,
+        uri: Uri,
+        options: FormattingOptions,
+    ) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::Formatting {
+                request_id,
+                uri,
+                options,
+            })
+            .map_err(|_| "Failed to send formatting command".to_string())
+    }
+
+    /// Format a range of a document
+    pub fn range_formatting(
+        &self,
+        request_id: u64,
+        uri: Uri,
+        range: lsp_types::Range,
+        options: FormattingOptions,
+    ) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::RangeFormatting {
+                request_id,
+                uri,
+                range,
+                options,
+            })
+            .map_err(|_| "Failed to send range_formatting command".to_string())
+    }
+
+    /// Format a document after `ch` was typed at `position`
+    pub fn on_type_formatting(
+        &self,
+        request_id: u64,
+        uri: Uri,
+        position: Position,
+        ch: String,
+        options: FormattingOptions,
+    ) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::OnTypeFormatting {
+                request_id,
+                uri,
+                position,
+                ch,
+                options,
+            })
+            .map_err(|_| "Failed to send on_type_formatting command".to_string())
+    }
+
+    /// Prepare the call hierarchy of the symbol at a position
+    pub fn prepare_call_hierarchy(
+        &self,
+        request_id: u64,
+        uri: Uri,
+        position: Position,
+    ) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::PrepareCallHierarchy {
+                request_id,
+                uri,
+                position,
+            })
+            .map_err(|_| "Failed to send prepare_call_hierarchy command".to_string())
+    }
+
+    /// Request the incoming or outgoing calls of a call hierarchy item
+    pub fn call_hierarchy_calls(
+        &self,
+        request_id: u64,
+        item: CallHierarchyItem,
+        incoming: bool,
+    ) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::CallHierarchyCalls {
+                request_id,
+                item,
+                incoming,
+            })
+            .map_err(|_| "Failed to send call_hierarchy_calls command".to_string())
+    }
+
+    /// Prepare the type hierarchy of the symbol at a position
+    pub fn prepare_type_hierarchy(
+        &self,
+        request_id: u64,
+        uri: Uri,
+        position: Position,
+    ) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::PrepareTypeHierarchy {
+                request_id,
+                uri,
+                position,
+            })
+            .map_err(|_| "Failed to send prepare_type_hierarchy command".to_string())
+    }
+
+    /// Request the supertypes or subtypes of a type hierarchy item
+    pub fn type_hierarchy_relatives(
+        &self,
+        request_id: u64,
+        item: TypeHierarchyItem,
+        supertypes: bool,
+    ) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::TypeHierarchyRelatives {
+                request_id,
+                item,
+                supertypes,
+            })
+            .map_err(|_| "Failed to send type_hierarchy_relatives command".to_string())
+    }
+
+    /// Request selection ranges around positions in a document
+    pub fn selection_ranges(
+        &self,
+        request_id: u64,
+        uri: Uri,
+        positions: Vec<Position>,
+    ) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::SelectionRange {
+                request_id,
+                uri,
+                positions,
+            })
+            .map_err(|_| "Failed to send selection_range command".to_string())
+    }
+
+    /// Request the code lenses of a document
+    pub fn code_lenses(&self, request_id: u64, uri: Uri) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::CodeLens { request_id, uri })
+            .map_err(|_| "Failed to send code_lens command".to_string())
+    }
+
+    /// Fill in the command of a code lens
+    pub fn resolve_code_lens(&self, request_id: u64, lens: CodeLens) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::CodeLensResolve { request_id, lens })
+            .map_err(|_| "Failed to send code_lens_resolve command".to_string())
+    }
+
+    /// Run a command on the server
+    pub fn execute_command(
+        &self,
+        request_id: u64,
+        command: lsp_types::Command,
+    ) -> Result<(), String> {
+        self.command_tx
+            .try_send(LspCommand::ExecuteCommand {
+                request_id,
+                command,
+            })
+            .map_err(|_| "Failed to send execute_command command".to_string())
+    }
+
     /// Cancel a pending request by its editor request_id
     ///
     /// This sends a $/cancelRequest notification to the LSP server.
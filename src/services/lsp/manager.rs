@@ -72,6 +72,39 @@ pub struct LspManager {
 
     /// Whether a language supports full document semantic tokens
     semantic_tokens_full_support: HashMap<String, bool>,
+
+    /// Whether a language's server provides folding ranges
+    folding_range_support: HashMap<String, bool>,
+
+    /// Whether a language's server provides selection ranges
+    selection_range_support: HashMap<String, bool>,
+
+    /// Whether a language's server provides document symbols
+    document_symbol_support: HashMap<String, bool>,
+
+    /// Whether a language's server searches workspace symbols
+    workspace_symbol_support: HashMap<String, bool>,
+
+    /// Whether a language's server formats whole documents
+    formatting_support: HashMap<String, bool>,
+
+    /// Whether a language's server formats ranges of a document
+    range_formatting_support: HashMap<String, bool>,
+
+    /// On-type formatting trigger characters per language
+    on_type_formatting_triggers: HashMap<String, Vec<String>>,
+
+    /// Whether a language's server provides call hierarchies
+    call_hierarchy_support: HashMap<String, bool>,
+
+    /// Whether a language's server provides type hierarchies
+    type_hierarchy_support: HashMap<String, bool>,
+
+    /// Whether a language's server provides code lenses
+    code_lens_support: HashMap<String, bool>,
+
+    /// Whether a language's server resolves code lenses lazily
+    code_lens_resolve_support: HashMap<String, bool>,
 }
 
 impl LspManager {
@@ -91,6 +124,17 @@ impl LspManager {
             completion_trigger_characters: HashMap::new(),
             semantic_token_legends: HashMap::new(),
             semantic_tokens_full_support: HashMap::new(),
+            folding_range_support: HashMap::new(),
+            selection_range_support: HashMap::new(),
+            document_symbol_support: HashMap::new(),
+            workspace_symbol_support: HashMap::new(),
+            formatting_support: HashMap::new(),
+            range_formatting_support: HashMap::new(),
+            on_type_formatting_triggers: HashMap::new(),
+            call_hierarchy_support: HashMap::new(),
+            type_hierarchy_support: HashMap::new(),
+            code_lens_support: HashMap::new(),
+            code_lens_resolve_support: HashMap::new(),
         }
     }
 
@@ -156,6 +200,136 @@ impl LspManager {
             .unwrap_or(&false)
     }
 
+    /// Store whether the server for a language provides folding ranges
+    pub fn set_folding_range_support(&mut self, language: &str, supported: bool) {
+        self.folding_range_support
+            .insert(language.to_string(), supported);
+    }
+
+    /// Check if the language's server provides folding ranges
+    pub fn folding_range_supported(&self, language: &str) -> bool {
+        *self.folding_range_support.get(language).unwrap_or(&false)
+    }
+
+    /// Store whether the server for a language provides selection ranges
+    pub fn set_selection_range_support(&mut self, language: &str, supported: bool) {
+        self.selection_range_support
+            .insert(language.to_string(), supported);
+    }
+
+    /// Check if the language's server provides selection ranges
+    pub fn selection_range_supported(&self, language: &str) -> bool {
+        *self.selection_range_support.get(language).unwrap_or(&false)
+    }
+
+    /// Store whether the server for a language provides document symbols
+    pub fn set_document_symbol_support(&mut self, language: &str, supported: bool) {
+        self.document_symbol_support
+            .insert(language.to_string(), supported);
+    }
+
+    /// Check if the language's server provides document symbols
+    pub fn document_symbol_supported(&self, language: &str) -> bool {
+        *self.document_symbol_support.get(language).unwrap_or(&false)
+    }
+
+    /// Store whether the server for a language searches workspace symbols
+    pub fn set_workspace_symbol_support(&mut self, language: &str, supported: bool) {
+        self.workspace_symbol_support
+            .insert(language.to_string(), supported);
+    }
+
+    /// Check if the language's server searches workspace symbols
+    pub fn workspace_symbol_supported(&self, language: &str) -> bool {
+        *self
+            .workspace_symbol_support
+            .get(language)
+            .unwrap_or(&false)
+    }
+
+    /// Store the formatting capabilities of the server for a language
+    pub fn set_formatting_support(
+        &mut self,
+        language: &str,
+        formatting: bool,
+        range_formatting: bool,
+        on_type_triggers: Vec<String>,
+    ) {
+        self.formatting_support
+            .insert(language.to_string(), formatting);
+        self.range_formatting_support
+            .insert(language.to_string(), range_formatting);
+        self.on_type_formatting_triggers
+            .insert(language.to_string(), on_type_triggers);
+    }
+
+    /// Check if the language's server formats whole documents
+    pub fn formatting_supported(&self, language: &str) -> bool {
+        *self.formatting_support.get(language).unwrap_or(&false)
+    }
+
+    /// Check if the language's server formats ranges of a document
+    pub fn range_formatting_supported(&self, language: &str) -> bool {
+        *self
+            .range_formatting_support
+            .get(language)
+            .unwrap_or(&false)
+    }
+
+    /// Check if a character triggers on-type formatting for the language's server
+    pub fn is_on_type_formatting_trigger(&self, ch: char, language: &str) -> bool {
+        let ch_str = ch.to_string();
+        self.on_type_formatting_triggers
+            .get(language)
+            .map(|chars| chars.contains(&ch_str))
+            .unwrap_or(false)
+    }
+
+    /// Store whether the server for a language provides call and type hierarchies
+    pub fn set_hierarchy_support(
+        &mut self,
+        language: &str,
+        call_hierarchy: bool,
+        type_hierarchy: bool,
+    ) {
+        self.call_hierarchy_support
+            .insert(language.to_string(), call_hierarchy);
+        self.type_hierarchy_support
+            .insert(language.to_string(), type_hierarchy);
+    }
+
+    /// Check if the language's server provides call hierarchies
+    pub fn call_hierarchy_supported(&self, language: &str) -> bool {
+        *self.call_hierarchy_support.get(language).unwrap_or(&false)
+    }
+
+    /// Check if the language's server provides type hierarchies
+    pub fn type_hierarchy_supported(&self, language: &str) -> bool {
+        *self.type_hierarchy_support.get(language).unwrap_or(&false)
+    }
+
+    /// Store whether the server for a language provides code lenses and
+    /// resolves them lazily
+    pub fn set_code_lens_support(&mut self, language: &str, code_lens: bool, resolve: bool) {
+        self.code_lens_support
+            .insert(language.to_string(), code_lens);
+        self.code_lens_resolve_support
+            .insert(language.to_string(), resolve);
+    }
+
+    /// Check if the language's server provides code lenses
+    pub fn code_lens_supported(&self, language: &str) -> bool {
+        *self.code_lens_support.get(language).unwrap_or(&false)
+    }
+
+    /// Check if the language's server resolves code lenses lazily
+    pub fn code_lens_resolve_supported(&self, language: &str) -> bool {
+        *self
+            .code_lens_resolve_support
+            .get(language)
+            .unwrap_or(&false)
+    }
+
     /// Check if a character is a completion trigger for any running language server
     pub fn is_completion_trigger_char(&self, ch: char, language: &str) -> bool {
         let ch_str = ch.to_string();
@@ -737,7 +911,17 @@ mod tests {
                 tab_size: None,
                 formatter: None,
                 format_on_save: false,
+                formatter_source: crate::config::FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: crate::config::FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
         languages.insert(
@@ -755,7 +939,17 @@ mod tests {
                 tab_size: None,
                 formatter: None,
                 format_on_save: false,
+                formatter_source: crate::config::FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: crate::config::FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
         languages.insert(
@@ -773,7 +967,17 @@ mod tests {
                 tab_size: None,
                 formatter: None,
                 format_on_save: false,
+                formatter_source: crate::config::FormatterSource::Auto,
+                format_on_type: false,
                 on_save: vec![],
+                save_strategy: None,
+                trim_trailing_whitespace: false,
+                trim_modified_lines_only: false,
+                trim_trailing_blank_lines: false,
+                final_newline: crate::config::FinalNewlineOption::Keep,
+                normalize_line_endings: false,
+                indent_guides: None,
+                bracket_pair_colorization: None,
             },
         );
         languages
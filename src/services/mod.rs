@@ -4,19 +4,26 @@
 //! I/O, and async operations.
 
 pub mod async_bridge;
+pub mod buffer_search;
 pub mod clipboard;
+pub mod editorconfig;
 pub mod fs;
 #[cfg(target_os = "linux")]
 pub mod gpm;
+pub mod line_indexer;
+pub mod local_history;
 pub mod log_dirs;
 pub mod lsp;
 pub mod plugins;
 pub mod process_limits;
+pub mod project_search;
 pub mod recovery;
 pub mod release_checker;
 pub mod signal_handler;
+pub mod snippets;
 pub mod styled_html;
 pub mod terminal;
 pub mod time_source;
 pub mod tracing_setup;
+pub mod undo_history;
 pub mod warning_log;
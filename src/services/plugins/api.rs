@@ -500,6 +500,29 @@ pub enum PluginCommand {
         namespace: String,
     },
 
+    /// Mark a range of lines on the scrollbar track
+    /// Used for git changes, breakpoints, lint results, etc.
+    SetScrollbarMark {
+        buffer_id: BufferId,
+        /// First line of the range (0-indexed)
+        start_line: usize,
+        /// Last line of the range (0-indexed, inclusive)
+        end_line: usize,
+        /// Namespace for grouping (e.g., "git-gutter")
+        namespace: String,
+        /// Color as RGB tuple
+        color: (u8, u8, u8),
+        /// Priority when several marks share a track row (higher wins)
+        priority: i32,
+    },
+
+    /// Clear all scrollbar marks for a specific namespace
+    ClearScrollbarMarks {
+        buffer_id: BufferId,
+        /// Namespace to clear (e.g., "git-gutter")
+        namespace: String,
+    },
+
     /// Open a file at a specific line and column
     /// Line and column are 1-indexed to match git grep output
     OpenFileAtLocation {
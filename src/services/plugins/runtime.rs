@@ -960,6 +960,69 @@ fn op_fresh_clear_line_indicators(
     false
 }
 
+/// Mark a range of lines on the scrollbar track
+/// @param buffer_id - The buffer ID
+/// @param start_line - First line of the range (0-indexed)
+/// @param end_line - Last line of the range (0-indexed, inclusive)
+/// @param namespace - Namespace for grouping (e.g., "git-gutter")
+/// @param r - Red color component (0-255)
+/// @param g - Green color component (0-255)
+/// @param b - Blue color component (0-255)
+/// @param priority - Priority when several marks share a track row (higher wins)
+/// @returns true if the mark was set
+#[op2(fast)]
+#[allow(clippy::too_many_arguments)]
+fn op_fresh_set_scrollbar_mark(
+    state: &mut OpState,
+    buffer_id: u32,
+    start_line: u32,
+    end_line: u32,
+    #[string] namespace: String,
+    r: u8,
+    g: u8,
+    b: u8,
+    priority: i32,
+) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::SetScrollbarMark {
+                buffer_id: BufferId(buffer_id as usize),
+                start_line: start_line as usize,
+                end_line: end_line as usize,
+                namespace,
+                color: (r, g, b),
+                priority,
+            });
+        return result.is_ok();
+    }
+    false
+}
+
+/// Clear all scrollbar marks for a specific namespace
+/// @param buffer_id - The buffer ID
+/// @param namespace - Namespace to clear (e.g., "git-gutter")
+/// @returns true if marks were cleared
+#[op2(fast)]
+fn op_fresh_clear_scrollbar_marks(
+    state: &mut OpState,
+    buffer_id: u32,
+    #[string] namespace: String,
+) -> bool {
+    if let Some(runtime_state) = state.try_borrow::<Rc<RefCell<TsRuntimeState>>>() {
+        let runtime_state = runtime_state.borrow();
+        let result = runtime_state
+            .command_sender
+            .send(PluginCommand::ClearScrollbarMarks {
+                buffer_id: BufferId(buffer_id as usize),
+                namespace,
+            });
+        return result.is_ok();
+    }
+    false
+}
+
 /// Submit a transformed view stream for a viewport
 /// @param buffer_id - Buffer to apply the transform to
 /// @param start - Viewport start byte
@@ -3814,6 +3877,8 @@ extension!(
         op_fresh_refresh_lines,
         op_fresh_set_line_indicator,
         op_fresh_clear_line_indicators,
+        op_fresh_set_scrollbar_mark,
+        op_fresh_clear_scrollbar_marks,
         op_fresh_insert_at_cursor,
         op_fresh_register_command,
         op_fresh_unregister_command,
@@ -4120,6 +4185,14 @@ impl TypeScriptRuntime {
                         return core.ops.op_fresh_clear_line_indicators(bufferId, namespace);
                     },
 
+                    // Scrollbar marks
+                    setScrollbarMark(bufferId, startLine, endLine, namespace, r, g, b, priority) {
+                        return core.ops.op_fresh_set_scrollbar_mark(bufferId, startLine, endLine, namespace, r, g, b, priority);
+                    },
+                    clearScrollbarMarks(bufferId, namespace) {
+                        return core.ops.op_fresh_clear_scrollbar_marks(bufferId, namespace);
+                    },
+
                     insertAtCursor(text) {
                         return core.ops.op_fresh_insert_at_cursor(text);
                     },
@@ -0,0 +1,441 @@
+//! Project-wide search
+//!
+//! Walks a directory with the `ignore` crate, so `.gitignore` rules and hidden
+//! files are respected, narrowed down by include/exclude globs. Each text file
+//! is searched line by line. Files that are open in the editor are searched
+//! through their buffer content instead of the disk, so unsaved changes are
+//! found. The search runs on a blocking thread and streams results back to the
+//! main loop in batches.
+
+use crate::model::encoding::Encoding;
+use crate::services::async_bridge::AsyncMessage;
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+/// Matches collected before the search stops
+pub const MAX_MATCHES: usize = 10_000;
+
+/// Bytes checked for NUL when deciding whether a file is binary
+const BINARY_CHECK_BYTES: usize = 8192;
+
+/// Minimum time between two result batches
+const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+
+/// What to search for, and where
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectSearchQuery {
+    /// The text or regex to find
+    pub text: String,
+    /// Treat `text` as a regex rather than a literal
+    pub use_regex: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// Only search files matching one of these globs (all files when empty)
+    pub include: Vec<String>,
+    /// Skip files matching any of these globs
+    pub exclude: Vec<String>,
+}
+
+impl ProjectSearchQuery {
+    /// Build the regex matching this query
+    pub fn regex(&self) -> Result<Regex, regex::Error> {
+        let pattern = if self.use_regex {
+            self.text.clone()
+        } else {
+            regex::escape(&self.text)
+        };
+        let pattern = if self.whole_word {
+            format!(r"\b{}\b", pattern)
+        } else {
+            pattern
+        };
+        regex::RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+    }
+
+    /// Set the globs from space-separated patterns, `!` marking exclusions
+    /// (e.g. `src/**/*.rs !*_test.rs`)
+    pub fn set_filters(&mut self, filters: &str) {
+        self.include.clear();
+        self.exclude.clear();
+        for glob in filters.split_whitespace() {
+            match glob.strip_prefix('!') {
+                Some(excluded) if !excluded.is_empty() => self.exclude.push(excluded.to_string()),
+                Some(_) => {}
+                None => self.include.push(glob.to_string()),
+            }
+        }
+    }
+
+    /// The globs in the form accepted by [`Self::set_filters`]
+    pub fn filters(&self) -> String {
+        self.include
+            .iter()
+            .cloned()
+            .chain(self.exclude.iter().map(|glob| format!("!{glob}")))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The text a match of this query is replaced with
+    pub fn replacement_for(
+        &self,
+        regex: &Regex,
+        line: &str,
+        range: Range<usize>,
+        replacement: &str,
+    ) -> String {
+        if self.use_regex {
+            // Expand $1, ${name}... against the match in the context of its line
+            if let Some(caps) = regex.captures_at(line, range.start) {
+                if caps.get(0).map(|m| m.range()) == Some(range) {
+                    let mut expanded = String::new();
+                    caps.expand(replacement, &mut expanded);
+                    return expanded;
+                }
+            }
+        }
+        replacement.to_string()
+    }
+}
+
+/// One match of a project search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    /// Line of the match (0-indexed)
+    pub line: usize,
+    /// Byte offset of the line in the file
+    pub line_start: usize,
+    /// Byte range of the match within the line
+    pub range: Range<usize>,
+    /// The line's text, without its line ending
+    pub line_text: String,
+}
+
+impl LineMatch {
+    /// Byte range of the match in the file
+    pub fn file_range(&self) -> Range<usize> {
+        self.line_start + self.range.start..self.line_start + self.range.end
+    }
+
+    /// The matched text
+    pub fn matched_text(&self) -> &str {
+        &self.line_text[self.range.clone()]
+    }
+}
+
+/// The matches of a project search in one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMatches {
+    pub path: PathBuf,
+    pub matches: Vec<LineMatch>,
+}
+
+/// Totals of a finished project search
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProjectSearchSummary {
+    /// Text files searched
+    pub files_searched: usize,
+    /// Whether the search stopped at [`MAX_MATCHES`]
+    pub truncated: bool,
+}
+
+/// A project search running in the background.
+///
+/// Dropping the task cancels the search.
+#[derive(Debug)]
+pub struct ProjectSearchTask {
+    /// Identifies the messages of this task
+    pub id: u64,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ProjectSearchTask {
+    /// Start searching `root` on the runtime's blocking thread pool.
+    ///
+    /// `open_buffers` holds the content of files open in the editor, by
+    /// canonical path.
+    pub fn spawn(
+        runtime: &tokio::runtime::Runtime,
+        sender: mpsc::Sender<AsyncMessage>,
+        root: PathBuf,
+        query: ProjectSearchQuery,
+        open_buffers: HashMap<PathBuf, String>,
+        max_file_size: u64,
+    ) -> Self {
+        let id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let task_cancel = cancel.clone();
+        runtime.spawn_blocking(move || {
+            let result = search_project(
+                &root,
+                &query,
+                &open_buffers,
+                max_file_size,
+                &task_cancel,
+                |files| {
+                    sender
+                        .send(AsyncMessage::ProjectSearchResults { task_id: id, files })
+                        .is_ok()
+                },
+            );
+            if task_cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            let message = match result {
+                Ok(summary) => AsyncMessage::ProjectSearchComplete {
+                    task_id: id,
+                    summary,
+                },
+                Err(error) => AsyncMessage::ProjectSearchFailed { task_id: id, error },
+            };
+            let _ = sender.send(message);
+        });
+
+        Self { id, cancel }
+    }
+}
+
+impl Drop for ProjectSearchTask {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Search the files under `root` for `query`, in path order.
+///
+/// Results are passed to `on_batch` a few files at a time; searching stops early
+/// when `cancel` is set or `on_batch` returns false. Binary files, files that
+/// aren't UTF-8 and files larger than `max_file_size` are skipped.
+pub fn search_project(
+    root: &Path,
+    query: &ProjectSearchQuery,
+    open_buffers: &HashMap<PathBuf, String>,
+    max_file_size: u64,
+    cancel: &AtomicBool,
+    mut on_batch: impl FnMut(Vec<FileMatches>) -> bool,
+) -> Result<ProjectSearchSummary, String> {
+    let regex = query.regex().map_err(|e| e.to_string())?;
+
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in &query.include {
+        overrides.add(glob).map_err(|e| e.to_string())?;
+    }
+    for glob in &query.exclude {
+        overrides
+            .add(&format!("!{glob}"))
+            .map_err(|e| e.to_string())?;
+    }
+    let overrides = overrides.build().map_err(|e| e.to_string())?;
+
+    let walker = WalkBuilder::new(root)
+        .overrides(overrides)
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build();
+
+    let mut summary = ProjectSearchSummary::default();
+    let mut total_matches = 0;
+    let mut batch = Vec::new();
+    let mut last_batch = Instant::now();
+
+    for entry in walker {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(summary);
+        }
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                tracing::debug!("Project search skipping entry: {}", e);
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+
+        let path = entry.path();
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let disk_content;
+        let content = match open_buffers.get(&canonical) {
+            Some(content) => content.as_str(),
+            None => {
+                if entry
+                    .metadata()
+                    .map_or(true, |metadata| metadata.len() > max_file_size)
+                {
+                    continue;
+                }
+                let Ok(bytes) = std::fs::read(path) else {
+                    continue;
+                };
+                // Decoded like the editor loads it, so offsets match the buffer
+                let encoding = Encoding::detect(&bytes, false);
+                let head = &bytes[..bytes.len().min(BINARY_CHECK_BYTES)];
+                if head.contains(&0) && !encoding.is_utf16() {
+                    continue;
+                }
+                let Ok(text) = String::from_utf8(encoding.decode(&bytes)) else {
+                    continue;
+                };
+                disk_content = text;
+                &disk_content
+            }
+        };
+        summary.files_searched += 1;
+
+        let matches = search_text(content, &regex, MAX_MATCHES - total_matches);
+        if !matches.is_empty() {
+            total_matches += matches.len();
+            batch.push(FileMatches {
+                path: canonical,
+                matches,
+            });
+        }
+
+        summary.truncated = total_matches >= MAX_MATCHES;
+        if summary.truncated || last_batch.elapsed() >= BATCH_INTERVAL {
+            last_batch = Instant::now();
+            if !batch.is_empty() && !on_batch(std::mem::take(&mut batch)) {
+                return Ok(summary);
+            }
+        }
+        if summary.truncated {
+            return Ok(summary);
+        }
+    }
+
+    if !batch.is_empty() {
+        on_batch(batch);
+    }
+    Ok(summary)
+}
+
+/// Find up to `limit` matches of `regex` in `content`, line by line
+pub fn search_text(content: &str, regex: &Regex, limit: usize) -> Vec<LineMatch> {
+    let mut matches = Vec::new();
+    let mut line_start = 0;
+    for (line, raw_line) in content.split_inclusive('\n').enumerate() {
+        let line_text = raw_line.trim_end_matches(['\n', '\r']);
+        for m in regex.find_iter(line_text) {
+            if matches.len() == limit {
+                return matches;
+            }
+            // Empty matches can't be highlighted or replaced meaningfully
+            if m.is_empty() {
+                continue;
+            }
+            matches.push(LineMatch {
+                line,
+                line_start,
+                range: m.range(),
+                line_text: line_text.to_string(),
+            });
+        }
+        line_start += raw_line.len();
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn query(text: &str) -> ProjectSearchQuery {
+        ProjectSearchQuery {
+            text: text.to_string(),
+            case_sensitive: true,
+            ..Default::default()
+        }
+    }
+
+    fn run(
+        root: &Path,
+        query: &ProjectSearchQuery,
+        open_buffers: &HashMap<PathBuf, String>,
+    ) -> Vec<FileMatches> {
+        let mut files = Vec::new();
+        search_project(
+            root,
+            query,
+            open_buffers,
+            u64::MAX,
+            &AtomicBool::new(false),
+            |batch| {
+                files.extend(batch);
+                true
+            },
+        )
+        .unwrap();
+        files
+    }
+
+    #[test]
+    fn test_search_text_reports_lines_and_offsets() {
+        let regex = query("foo").regex().unwrap();
+        let matches = search_text("a foo\r\nfoo foo\n", &regex, usize::MAX);
+        let found: Vec<_> = matches
+            .iter()
+            .map(|m| (m.line, m.file_range(), m.line_text.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (0, 2..5, "a foo"),
+                (1, 7..10, "foo foo"),
+                (1, 11..14, "foo foo"),
+            ]
+        );
+        assert_eq!(search_text("foo foo", &regex, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_search_project_respects_globs_and_open_buffers() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn needle() {}\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("notes.md"), "needle\n").unwrap();
+        std::fs::write(root.join("data.bin"), b"needle\0").unwrap();
+
+        let files = run(&root, &query("needle"), &HashMap::new());
+        let paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(paths, vec![root.join("notes.md"), root.join("src/lib.rs")]);
+
+        // Unsaved buffer content is searched instead of the disk
+        let mut open_buffers = HashMap::new();
+        open_buffers.insert(root.join("src/main.rs"), "// needle\n".to_string());
+        let mut rust_only = query("needle");
+        rust_only.set_filters("*.rs !lib.rs");
+        assert_eq!(rust_only.filters(), "*.rs !lib.rs");
+        let files = run(&root, &rust_only, &open_buffers);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, root.join("src/main.rs"));
+        assert_eq!(files[0].matches[0].range, 3..9);
+    }
+
+    #[test]
+    fn test_replacement_expands_captures_in_regex_mode() {
+        let mut q = query(r"(\w+)_id");
+        q.use_regex = true;
+        let regex = q.regex().unwrap();
+        let line = "let user_id = 1;";
+        assert_eq!(q.replacement_for(&regex, line, 4..11, "${1}Id"), "userId");
+
+        let literal = query("$x");
+        let regex = literal.regex().unwrap();
+        assert_eq!(literal.replacement_for(&regex, "a $x", 2..4, "$1"), "$1");
+    }
+}
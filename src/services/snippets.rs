@@ -0,0 +1,320 @@
+//! User snippet files
+//!
+//! Snippets use the VS Code snippet file format, so existing collections
+//! can be copied over unchanged:
+//!
+//! ```text
+//! ~/.config/fresh/snippets/
+//! ├── rust.json             # Snippets for one language (named by language id)
+//! └── common.code-snippets  # Snippets for all languages, or those in "scope"
+//! ```
+//!
+//! Each file is a JSON object (comments and trailing commas allowed) mapping
+//! a snippet name to its definition:
+//!
+//! ```json
+//! {
+//!   "For loop": {
+//!     "prefix": ["for", "fori"],
+//!     "body": ["for ${1:i} in ${2:iter} {", "\t$0", "}"],
+//!     "description": "A for loop"
+//!   }
+//! }
+//! ```
+//!
+//! Bodies use the snippet syntax of [`crate::primitives::snippet`].
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Extension of snippet files that may apply to several languages
+const GLOBAL_EXTENSION: &str = "code-snippets";
+
+/// A snippet loaded from a snippet file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetDefinition {
+    /// Name of the snippet (its key in the file)
+    pub name: String,
+    /// Words that trigger the snippet in completion
+    pub prefixes: Vec<String>,
+    /// Snippet body
+    pub body: String,
+    /// Description shown next to the snippet
+    pub description: Option<String>,
+    /// Languages the snippet applies to (empty for all)
+    pub scope: Vec<String>,
+}
+
+impl SnippetDefinition {
+    /// Whether the snippet applies to `language`
+    pub fn applies_to(&self, language: &str) -> bool {
+        self.scope.is_empty() || self.scope.iter().any(|s| s == language)
+    }
+}
+
+/// A string or a list of strings
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StringOrList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StringOrList {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::One(s) => vec![s],
+            Self::Many(v) => v,
+        }
+    }
+}
+
+/// A snippet as written in a snippet file
+#[derive(Debug, Deserialize)]
+struct RawSnippet {
+    prefix: Option<StringOrList>,
+    body: StringOrList,
+    description: Option<String>,
+    scope: Option<String>,
+}
+
+/// All user snippets
+#[derive(Debug, Clone, Default)]
+pub struct SnippetLibrary {
+    snippets: Vec<SnippetDefinition>,
+}
+
+impl SnippetLibrary {
+    /// Load every snippet file in `dir`
+    ///
+    /// A missing directory gives an empty library; files that fail to parse
+    /// are skipped with a warning.
+    pub fn load(dir: &Path) -> Self {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Self::default();
+        };
+        let mut paths: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+        paths.sort();
+
+        let mut snippets = Vec::new();
+        for path in paths {
+            let (Some(stem), Some(extension)) = (
+                path.file_stem().and_then(|s| s.to_str()),
+                path.extension().and_then(|s| s.to_str()),
+            ) else {
+                continue;
+            };
+            let language = match extension {
+                "json" => Some(stem),
+                GLOBAL_EXTENSION => None,
+                _ => continue,
+            };
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| parse_snippet_file(&content, language));
+            match parsed {
+                Ok(file_snippets) => snippets.extend(file_snippets),
+                Err(e) => tracing::warn!("Failed to load snippets from {:?}: {}", path, e),
+            }
+        }
+        Self { snippets }
+    }
+
+    /// Snippets that apply to `language`
+    pub fn for_language<'a>(
+        &'a self,
+        language: &'a str,
+    ) -> impl Iterator<Item = &'a SnippetDefinition> + 'a {
+        self.snippets.iter().filter(move |s| s.applies_to(language))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snippets.is_empty()
+    }
+}
+
+/// Parse a snippet file; `language` scopes every snippet in a language file
+fn parse_snippet_file(
+    content: &str,
+    language: Option<&str>,
+) -> Result<Vec<SnippetDefinition>, String> {
+    let raw: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&strip_jsonc(content)).map_err(|e| e.to_string())?;
+    let mut snippets = Vec::new();
+    for (name, value) in raw {
+        let Ok(raw) = serde_json::from_value::<RawSnippet>(value) else {
+            tracing::warn!("Skipping malformed snippet {:?}", name);
+            continue;
+        };
+        let scope = match language {
+            Some(language) => vec![language.to_string()],
+            None => raw
+                .scope
+                .iter()
+                .flat_map(|s| s.split(','))
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+        };
+        snippets.push(SnippetDefinition {
+            prefixes: raw.prefix.map(StringOrList::into_vec).unwrap_or_default(),
+            body: raw.body.into_vec().join("\n"),
+            description: raw.description,
+            scope,
+            name,
+        });
+    }
+    Ok(snippets)
+}
+
+/// Remove comments and trailing commas so JSONC parses as JSON
+fn strip_jsonc(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (']' | '}', _) => {
+                // Drop a comma before the closing bracket
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Value of a date/time snippet variable (`CURRENT_YEAR`, ...)
+pub fn date_variable(name: &str, now: &DateTime<Local>) -> Option<String> {
+    let value = match name {
+        "CURRENT_YEAR" => now.year().to_string(),
+        "CURRENT_YEAR_SHORT" => format!("{:02}", now.year() % 100),
+        "CURRENT_MONTH" => format!("{:02}", now.month()),
+        "CURRENT_MONTH_NAME" => now.format("%B").to_string(),
+        "CURRENT_MONTH_NAME_SHORT" => now.format("%b").to_string(),
+        "CURRENT_DATE" => format!("{:02}", now.day()),
+        "CURRENT_DAY_NAME" => now.format("%A").to_string(),
+        "CURRENT_DAY_NAME_SHORT" => now.format("%a").to_string(),
+        "CURRENT_HOUR" => format!("{:02}", now.hour()),
+        "CURRENT_MINUTE" => format!("{:02}", now.minute()),
+        "CURRENT_SECOND" => format!("{:02}", now.second()),
+        "CURRENT_SECONDS_UNIX" => now.timestamp().to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_language_and_global_files() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("rust.json"),
+            r#"{
+                // Loops
+                "For loop": {
+                    "prefix": ["for", "fori"],
+                    "body": ["for ${1:i} in ${2:iter} {", "\t$0", "}"],
+                    "description": "A for loop", /* trailing comma */
+                },
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("common.code-snippets"),
+            r##"{
+                "Todo": { "prefix": "todo", "body": "// TODO: $0" },
+                "Shebang": { "prefix": "sh", "body": "#!/bin/sh", "scope": "shell, python" }
+            }"##,
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.txt"), "not snippets").unwrap();
+
+        let library = SnippetLibrary::load(dir.path());
+        let rust: Vec<_> = library.for_language("rust").map(|s| &s.name).collect();
+        assert_eq!(rust, ["Todo", "For loop"]);
+        let python: Vec<_> = library.for_language("python").map(|s| &s.name).collect();
+        assert_eq!(python, ["Todo", "Shebang"]);
+
+        let for_loop = library.for_language("rust").last().unwrap();
+        assert_eq!(for_loop.prefixes, ["for", "fori"]);
+        assert_eq!(for_loop.body, "for ${1:i} in ${2:iter} {\n\t$0\n}");
+        assert_eq!(for_loop.description.as_deref(), Some("A for loop"));
+    }
+
+    #[test]
+    fn test_invalid_files_are_skipped() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("go.json"), "{ not json").unwrap();
+        fs::write(
+            dir.path().join("c.json"),
+            r#"{ "Bad": { "prefix": "bad" }, "Main": { "prefix": "main", "body": "int main() {}" } }"#,
+        )
+        .unwrap();
+        let library = SnippetLibrary::load(dir.path());
+        let names: Vec<_> = library.for_language("c").map(|s| &s.name).collect();
+        assert_eq!(names, ["Main"]);
+        assert!(SnippetLibrary::load(&dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_strip_jsonc_keeps_strings() {
+        let stripped = strip_jsonc(r#"{"a": "// not a comment, }", "b": [1, 2,],}"#);
+        assert_eq!(stripped, r#"{"a": "// not a comment, }", "b": [1, 2]}"#);
+    }
+
+    #[test]
+    fn test_date_variables() {
+        let now = Local.with_ymd_and_hms(2024, 3, 7, 9, 5, 2).unwrap();
+        assert_eq!(date_variable("CURRENT_YEAR", &now).unwrap(), "2024");
+        assert_eq!(date_variable("CURRENT_YEAR_SHORT", &now).unwrap(), "24");
+        assert_eq!(date_variable("CURRENT_MONTH", &now).unwrap(), "03");
+        assert_eq!(date_variable("CURRENT_MONTH_NAME", &now).unwrap(), "March");
+        assert_eq!(date_variable("CURRENT_DATE", &now).unwrap(), "07");
+        assert_eq!(
+            date_variable("CURRENT_DAY_NAME_SHORT", &now).unwrap(),
+            "Thu"
+        );
+        assert_eq!(date_variable("CURRENT_SECOND", &now).unwrap(), "02");
+        assert_eq!(date_variable("TM_FILENAME", &now), None);
+    }
+}
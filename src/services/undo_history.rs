@@ -0,0 +1,486 @@
+//! Persistent undo history
+//!
+//! Keeps the undo history of saved files across editor sessions so that
+//! reopening a file lets the user undo past the point where it was opened.
+//!
+//! ## How it works
+//!
+//! 1. **Persist**: When a file is saved or its buffer is closed, the buffer
+//!    edits of its event log are written next to the recovery files, together
+//!    with a hash of the file content on disk.
+//! 2. **Restore**: When the file is opened again, the stored hash is compared
+//!    against the file on disk. If it matches, the history is restored with the
+//!    position set to the saved state; otherwise the entry is stale and removed.
+//! 3. **Limits**: Entries older than the configured age are removed, and the
+//!    oldest entries are evicted while the store exceeds its size limit.
+//!
+//! ## File Layout
+//!
+//! ```text
+//! ~/.local/share/fresh/undo/
+//! ├── {path_hash}.meta.json   # File path, content hash, history position
+//! └── {path_hash}.jsonl       # Event log entries (JSON Lines)
+//! ```
+//!
+//! Only text edits are persisted. Cursor, view and UI events are dropped, and
+//! history is cut at `BulkEdit` events because their tree snapshots are not
+//! serializable.
+
+use crate::model::event::{Event, EventLog};
+use crate::services::recovery::path_hash;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Metadata stored alongside a persisted undo history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoHistoryMetadata {
+    /// Path of the file this history belongs to
+    pub path: PathBuf,
+    /// SHA-256 of the file content the history's saved position corresponds to
+    pub content_hash: String,
+    /// Index of the saved position within the persisted entries
+    pub position: usize,
+    /// Number of persisted entries
+    pub entry_count: usize,
+    /// When this history was written (seconds since epoch)
+    pub updated_at: u64,
+}
+
+/// Storage for persisted undo histories
+#[derive(Debug)]
+pub struct UndoHistoryStore {
+    /// Base directory for undo history files
+    dir: PathBuf,
+}
+
+impl UndoHistoryStore {
+    /// File extension for metadata files
+    const META_EXT: &'static str = "meta.json";
+    /// File extension for event log files
+    const LOG_EXT: &'static str = "jsonl";
+
+    /// Create an undo history store in the given directory
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Get the base directory
+    pub fn base_dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Get paths for the metadata and log files of a file
+    fn entry_paths(&self, path: &Path) -> (PathBuf, PathBuf) {
+        let id = path_hash(path);
+        (
+            self.dir.join(format!("{id}.{}", Self::META_EXT)),
+            self.dir.join(format!("{id}.{}", Self::LOG_EXT)),
+        )
+    }
+
+    /// Persist the undo history of `path`
+    ///
+    /// `log` must describe a buffer whose saved position matches the current
+    /// content of `path` on disk. Returns `Ok(false)` if there is nothing worth
+    /// persisting, in which case any previous entry for the file is removed.
+    pub fn save(&self, path: &Path, log: &EventLog) -> io::Result<bool> {
+        let Some((history, position)) = persistable_history(log) else {
+            self.remove(path)?;
+            return Ok(false);
+        };
+
+        fs::create_dir_all(&self.dir)?;
+        let content_hash = hash_file(path)?;
+        let (meta_path, log_path) = self.entry_paths(path);
+
+        let temp_log_path = log_path.with_extension("jsonl.tmp");
+        history.save_to_file(&temp_log_path)?;
+        fs::rename(&temp_log_path, &log_path)?;
+
+        let meta = UndoHistoryMetadata {
+            path: path.to_path_buf(),
+            content_hash,
+            position,
+            entry_count: history.len(),
+            updated_at: unix_secs(SystemTime::now()),
+        };
+        let json = serde_json::to_string_pretty(&meta).map_err(io::Error::other)?;
+        let temp_meta_path = meta_path.with_extension("json.tmp");
+        fs::write(&temp_meta_path, json)?;
+        fs::rename(&temp_meta_path, &meta_path)?;
+
+        Ok(true)
+    }
+
+    /// Load the persisted undo history of `path`
+    ///
+    /// Returns `Ok(None)` if there is no history for the file. A history whose
+    /// content hash no longer matches the file on disk is removed.
+    pub fn load(&self, path: &Path) -> io::Result<Option<EventLog>> {
+        let (meta_path, log_path) = self.entry_paths(path);
+        if !meta_path.exists() {
+            return Ok(None);
+        }
+
+        let meta: UndoHistoryMetadata = match fs::read_to_string(&meta_path)
+            .and_then(|s| serde_json::from_str(&s).map_err(io::Error::from))
+        {
+            Ok(meta) => meta,
+            Err(e) => {
+                tracing::debug!("Discarding unreadable undo history for {:?}: {}", path, e);
+                self.remove(path)?;
+                return Ok(None);
+            }
+        };
+
+        if meta.path != path || meta.content_hash != hash_file(path)? {
+            tracing::debug!("Discarding stale undo history for {:?}", path);
+            self.remove(path)?;
+            return Ok(None);
+        }
+
+        let mut log = match EventLog::load_from_file(&log_path) {
+            Ok(log) if log.len() == meta.entry_count && meta.position <= log.len() => log,
+            _ => {
+                tracing::debug!("Discarding corrupt undo history for {:?}", path);
+                self.remove(path)?;
+                return Ok(None);
+            }
+        };
+        log.restore_saved_position(meta.position);
+
+        Ok(Some(log))
+    }
+
+    /// Remove the persisted undo history of `path`
+    pub fn remove(&self, path: &Path) -> io::Result<()> {
+        let (meta_path, log_path) = self.entry_paths(path);
+        for p in [meta_path, log_path] {
+            if p.exists() {
+                fs::remove_file(p)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Enforce the age and size limits of the store
+    ///
+    /// Entries last written more than `max_age` ago are removed first. Then the
+    /// oldest entries are removed until the store is at most `max_bytes` in
+    /// size. Returns the number of entries removed.
+    pub fn prune(&self, max_age: Duration, max_bytes: u64) -> io::Result<usize> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+
+        let now = SystemTime::now();
+        let mut entries: Vec<(PathBuf, PathBuf, u64, SystemTime)> = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let meta_path = entry?.path();
+            let Some(id) = meta_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(&format!(".{}", Self::META_EXT)))
+            else {
+                continue;
+            };
+            let log_path = self.dir.join(format!("{id}.{}", Self::LOG_EXT));
+            let size = [&meta_path, &log_path]
+                .iter()
+                .filter_map(|p| fs::metadata(p).ok())
+                .map(|m| m.len())
+                .sum();
+            let modified = fs::metadata(&meta_path)
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((meta_path, log_path, size, modified));
+        }
+
+        // Newest first, so eviction pops the oldest entries off the end
+        entries.sort_by(|a, b| b.3.cmp(&a.3));
+
+        let mut removed = 0;
+        let mut total: u64 = entries.iter().map(|e| e.2).sum();
+        while let Some((meta_path, log_path, size, modified)) = entries.pop() {
+            let expired = now.duration_since(modified).unwrap_or_default() > max_age;
+            if !expired && total <= max_bytes {
+                break;
+            }
+            let _ = fs::remove_file(&meta_path);
+            let _ = fs::remove_file(&log_path);
+            total -= size;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Extract the part of `log` that can be persisted
+///
+/// Returns the history containing only text edits, along with the index of
+/// the saved position within it. History is cut at the nearest `BulkEdit` on
+/// either side of the saved position. Returns `None` if the log has no saved
+/// position or there are no edits to keep.
+fn persistable_history(log: &EventLog) -> Option<(EventLog, usize)> {
+    let saved = log.saved_index()?;
+    let entries = log.entries();
+
+    let mut before: Vec<_> = entries[..saved]
+        .iter()
+        .rev()
+        .take_while(|e| !contains_bulk_edit(&e.event))
+        .filter_map(|e| {
+            let mut entry = (*e).clone();
+            entry.event = text_edits_only(&e.event)?;
+            Some(entry)
+        })
+        .collect();
+    before.reverse();
+
+    let after = entries[saved..]
+        .iter()
+        .take_while(|e| !contains_bulk_edit(&e.event))
+        .filter_map(|e| {
+            let mut entry = (*e).clone();
+            entry.event = text_edits_only(&e.event)?;
+            Some(entry)
+        });
+
+    let position = before.len();
+    let kept: Vec<_> = before.into_iter().chain(after).collect();
+    if kept.is_empty() {
+        return None;
+    }
+
+    let mut history = EventLog::from_entries(kept);
+    history.restore_saved_position(position);
+    Some((history, position))
+}
+
+/// Check whether an event is or contains a `BulkEdit`
+fn contains_bulk_edit(event: &Event) -> bool {
+    match event {
+        Event::BulkEdit { .. } => true,
+        Event::Batch { events, .. } => events.iter().any(contains_bulk_edit),
+        _ => false,
+    }
+}
+
+/// Strip an event down to its text edits, or `None` if it has none
+fn text_edits_only(event: &Event) -> Option<Event> {
+    match event {
+        Event::Insert { .. } | Event::Delete { .. } => Some(event.clone()),
+        Event::Batch {
+            events,
+            description,
+        } => {
+            let events: Vec<Event> = events.iter().filter_map(text_edits_only).collect();
+            (!events.is_empty()).then(|| Event::Batch {
+                events,
+                description: description.clone(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Compute the SHA-256 of a file's content
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    use sha2::{Digest, Sha256};
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::event::CursorId;
+    use tempfile::TempDir;
+
+    fn insert(position: usize, text: &str) -> Event {
+        Event::Insert {
+            position,
+            text: text.to_string(),
+            cursor_id: CursorId(0),
+        }
+    }
+
+    fn move_cursor() -> Event {
+        Event::MoveCursor {
+            cursor_id: CursorId(0),
+            old_position: 0,
+            new_position: 1,
+            old_anchor: None,
+            new_anchor: None,
+            old_sticky_column: 0,
+            new_sticky_column: 0,
+        }
+    }
+
+    fn create_test_store() -> (UndoHistoryStore, TempDir, PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let store = UndoHistoryStore::new(temp_dir.path().join("undo"));
+        let file = temp_dir.path().join("file.txt");
+        (store, temp_dir, file)
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let (store, _temp, file) = create_test_store();
+        fs::write(&file, "ab").unwrap();
+
+        let mut log = EventLog::new();
+        log.append(insert(0, "a"));
+        log.append(move_cursor());
+        log.append(insert(1, "b"));
+        log.mark_saved();
+
+        assert!(store.save(&file, &log).unwrap());
+
+        let mut restored = store.load(&file).unwrap().expect("history restored");
+        // The cursor movement is not persisted
+        assert_eq!(restored.len(), 2);
+        assert!(restored.is_at_saved_position());
+        assert!(restored.can_undo());
+        assert!(!restored.can_redo());
+
+        let undo = restored.undo();
+        assert!(matches!(&undo[0], Event::Delete { range, .. } if *range == (1..2)));
+        assert!(!restored.is_at_saved_position());
+    }
+
+    #[test]
+    fn test_unsaved_edits_become_redo() {
+        let (store, _temp, file) = create_test_store();
+        fs::write(&file, "a").unwrap();
+
+        let mut log = EventLog::new();
+        log.append(insert(0, "a"));
+        log.mark_saved();
+        log.append(insert(1, "b"));
+
+        assert!(store.save(&file, &log).unwrap());
+
+        let mut restored = store.load(&file).unwrap().unwrap();
+        assert!(restored.is_at_saved_position());
+        assert!(restored.can_redo());
+        let redo = restored.redo();
+        assert!(matches!(&redo[0], Event::Insert { text, .. } if text == "b"));
+    }
+
+    #[test]
+    fn test_stale_history_discarded() {
+        let (store, _temp, file) = create_test_store();
+        fs::write(&file, "a").unwrap();
+
+        let mut log = EventLog::new();
+        log.append(insert(0, "a"));
+        log.mark_saved();
+        store.save(&file, &log).unwrap();
+
+        fs::write(&file, "changed elsewhere").unwrap();
+        assert!(store.load(&file).unwrap().is_none());
+
+        // The stale entry is gone even after restoring the content
+        fs::write(&file, "a").unwrap();
+        assert!(store.load(&file).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bulk_edit_cuts_history() {
+        let (store, _temp, file) = create_test_store();
+        fs::write(&file, "xab").unwrap();
+
+        let mut log = EventLog::new();
+        log.append(insert(0, "x"));
+        log.append(Event::BulkEdit {
+            old_tree: None,
+            new_tree: None,
+            old_cursors: vec![],
+            new_cursors: vec![],
+            description: "indent".to_string(),
+        });
+        log.append(insert(1, "a"));
+        log.append(insert(2, "b"));
+        log.mark_saved();
+
+        store.save(&file, &log).unwrap();
+        let restored = store.load(&file).unwrap().unwrap();
+        assert_eq!(restored.len(), 2);
+    }
+
+    #[test]
+    fn test_nothing_to_persist_removes_entry() {
+        let (store, _temp, file) = create_test_store();
+        fs::write(&file, "a").unwrap();
+
+        let mut log = EventLog::new();
+        log.append(insert(0, "a"));
+        log.mark_saved();
+        store.save(&file, &log).unwrap();
+
+        assert!(!store.save(&file, &EventLog::new()).unwrap());
+        assert!(store.load(&file).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_prune_by_size() {
+        let (store, temp, _file) = create_test_store();
+
+        let mut files = Vec::new();
+        for i in 0..3 {
+            let file = temp.path().join(format!("file{i}.txt"));
+            fs::write(&file, "a").unwrap();
+            let mut log = EventLog::new();
+            log.append(insert(0, "a"));
+            log.mark_saved();
+            store.save(&file, &log).unwrap();
+            let (meta_path, _) = store.entry_paths(&file);
+            let mtime = SystemTime::now() - Duration::from_secs(100 - i * 10);
+            fs::File::options()
+                .write(true)
+                .open(&meta_path)
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+            files.push(file);
+        }
+
+        // No limits exceeded
+        assert_eq!(store.prune(Duration::from_secs(3600), u64::MAX).unwrap(), 0);
+
+        // Expired entries go first
+        assert_eq!(store.prune(Duration::from_secs(95), u64::MAX).unwrap(), 1);
+        assert!(store.load(&files[0]).unwrap().is_none());
+
+        // Size limit evicts the oldest remaining entry
+        let (meta_path, log_path) = store.entry_paths(&files[2]);
+        let newest_size =
+            fs::metadata(meta_path).unwrap().len() + fs::metadata(log_path).unwrap().len();
+        assert_eq!(
+            store.prune(Duration::from_secs(3600), newest_size).unwrap(),
+            1
+        );
+        assert!(store.load(&files[1]).unwrap().is_none());
+        assert!(store.load(&files[2]).unwrap().is_some());
+    }
+}
@@ -30,6 +30,7 @@ use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::input::input_history::get_data_dir;
+use crate::view::folding::FoldRange;
 
 /// Current session file format version
 pub const SESSION_VERSION: u32 = 1;
@@ -163,6 +164,10 @@ pub struct SerializedFileState {
 
     /// Scroll position (byte offset)
     pub scroll: SerializedScroll,
+
+    /// Collapsed folds (line ranges)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub folds: Vec<FoldRange>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -206,6 +211,10 @@ pub struct SessionConfigOverrides {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub line_wrap: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sticky_scroll: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breadcrumbs: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub syntax_highlighting: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub enable_inlay_hints: Option<bool>,
@@ -873,6 +882,7 @@ mod tests {
                 top_view_line_offset: 2,
                 left_column: 10,
             },
+            folds: vec![FoldRange::new(3, 8)],
         };
 
         let json = serde_json::to_string(&file_state).unwrap();
@@ -884,6 +894,7 @@ mod tests {
         assert_eq!(restored.additional_cursors.len(), 1);
         assert_eq!(restored.scroll.top_byte, 500);
         assert_eq!(restored.scroll.left_column, 10);
+        assert_eq!(restored.folds, vec![FoldRange::new(3, 8)]);
     }
 
     #[test]
@@ -13,11 +13,20 @@ use crate::primitives::highlight_engine::HighlightEngine;
 use crate::primitives::highlighter::Language;
 use crate::primitives::indent::IndentCalculator;
 use crate::primitives::reference_highlighter::ReferenceHighlighter;
+use crate::primitives::snippet::SnippetSession;
+use crate::primitives::syntax_selection::SelectionExpansion;
+use crate::primitives::syntax_tree::SyntaxTree;
 use crate::primitives::text_property::TextPropertyManager;
+use crate::services::line_indexer::LineIndexTask;
+use crate::view::bracket_pairs::BracketPairs;
+use crate::view::code_lens::CodeLenses;
+use crate::view::document_symbols::DocumentSymbols;
+use crate::view::folding::FoldManager;
 use crate::view::margin::{MarginAnnotation, MarginContent, MarginManager, MarginPosition};
 use crate::view::overlay::{Overlay, OverlayFace, OverlayManager, UnderlineStyle};
 use crate::view::popup::{Popup, PopupContent, PopupListItem, PopupManager, PopupPosition};
 use crate::view::reference_highlight_overlay::ReferenceHighlightOverlay;
+use crate::view::scrollbar_marks::PluginScrollbarMarks;
 use crate::view::virtual_text::VirtualTextManager;
 use anyhow::Result;
 use ratatui::style::{Color, Style};
@@ -60,12 +69,36 @@ pub struct EditorState {
     /// Virtual text manager for inline hints (type hints, parameter hints, etc.)
     pub virtual_texts: VirtualTextManager,
 
+    /// Marks plugins added to the scrollbar track
+    pub scrollbar_marks: PluginScrollbarMarks,
+
     /// Popups for floating windows (completion, documentation, etc.)
     pub popups: PopupManager,
 
     /// Margins for line numbers, annotations, gutter symbols, etc.)
     pub margins: MarginManager,
 
+    /// Fold ranges and collapsed folds
+    pub folds: FoldManager,
+
+    /// Bracket nesting depths for bracket pair colorization
+    pub bracket_pairs: BracketPairs,
+
+    /// Symbols for the outline panel and breadcrumbs
+    pub symbols: DocumentSymbols,
+
+    /// Syntax tree shared by folding, bracket pairs and symbols
+    pub syntax_tree: SyntaxTree,
+
+    /// Code lenses from the language server
+    pub code_lenses: CodeLenses,
+
+    /// Expand/shrink selection history and language server selection ranges
+    pub selection_expansion: SelectionExpansion,
+
+    /// Tabstops of the snippet being filled in, if any
+    pub snippet_session: Option<SnippetSession>,
+
     /// Cached line number for primary cursor (0-indexed)
     /// Maintained incrementally to avoid O(n) scanning on every render
     pub primary_cursor_line_number: LineNumber,
@@ -103,6 +136,16 @@ pub struct EditorState {
     /// Used for visual display of tab characters and indent calculations.
     pub tab_size: usize,
 
+    /// Columns at which vertical rulers are drawn.
+    /// Set from `max_line_length` in `.editorconfig`; empty by default.
+    pub rulers: Vec<usize>,
+
+    /// Whether indent guides are drawn. Set based on language config.
+    pub indent_guides: bool,
+
+    /// Whether brackets are colored by nesting depth. Set based on language config.
+    pub bracket_pair_colorization: bool,
+
     /// Semantic highlighter for word occurrence highlighting
     pub reference_highlighter: ReferenceHighlighter,
 
@@ -133,6 +176,9 @@ pub struct EditorState {
 
     /// The detected language for this buffer (e.g., "rust", "python", "text")
     pub language: String,
+
+    /// Background scan giving a large file exact line numbers (cancelled when dropped)
+    pub line_indexing: Option<LineIndexTask>,
 }
 
 impl EditorState {
@@ -149,8 +195,16 @@ impl EditorState {
             overlays: OverlayManager::new(),
             marker_list: MarkerList::new(),
             virtual_texts: VirtualTextManager::new(),
+            scrollbar_marks: PluginScrollbarMarks::new(),
             popups: PopupManager::new(),
             margins: MarginManager::new(),
+            folds: FoldManager::new(),
+            bracket_pairs: BracketPairs::new(),
+            symbols: DocumentSymbols::new(),
+            syntax_tree: SyntaxTree::new(),
+            code_lenses: CodeLenses::new(),
+            selection_expansion: SelectionExpansion::new(),
+            snippet_session: None,
             primary_cursor_line_number: LineNumber::Absolute(0), // Start at line 0
             mode: "insert".to_string(),
             text_properties: TextPropertyManager::new(),
@@ -160,6 +214,9 @@ impl EditorState {
             show_whitespace_tabs: true,
             use_tabs: false,
             tab_size: 4, // Default tab size
+            rulers: Vec::new(),
+            indent_guides: false,
+            bracket_pair_colorization: false,
             reference_highlighter: ReferenceHighlighter::new(),
             view_mode: ViewMode::Source,
             debug_highlight_mode: false,
@@ -170,6 +227,7 @@ impl EditorState {
             reference_highlight_overlay: ReferenceHighlightOverlay::new(),
             semantic_tokens: None,
             language: "text".to_string(), // Default to plain text
+            line_indexing: None,
         }
     }
 
@@ -253,8 +311,16 @@ impl EditorState {
             overlays: OverlayManager::new(),
             marker_list,
             virtual_texts: VirtualTextManager::new(),
+            scrollbar_marks: PluginScrollbarMarks::new(),
             popups: PopupManager::new(),
             margins: MarginManager::new(),
+            folds: FoldManager::new(),
+            bracket_pairs: BracketPairs::new(),
+            symbols: DocumentSymbols::new(),
+            syntax_tree: SyntaxTree::new(),
+            code_lenses: CodeLenses::new(),
+            selection_expansion: SelectionExpansion::new(),
+            snippet_session: None,
             primary_cursor_line_number: LineNumber::Absolute(0), // Start at line 0
             mode: "insert".to_string(),
             text_properties: TextPropertyManager::new(),
@@ -264,6 +330,9 @@ impl EditorState {
             show_whitespace_tabs: true,
             use_tabs: false,
             tab_size: 4, // Default tab size
+            rulers: Vec::new(),
+            indent_guides: false,
+            bracket_pair_colorization: false,
             reference_highlighter,
             view_mode: ViewMode::Source,
             debug_highlight_mode: false,
@@ -274,6 +343,7 @@ impl EditorState {
             reference_highlight_overlay: ReferenceHighlightOverlay::new(),
             semantic_tokens: None,
             language: language_name,
+            line_indexing: None,
         })
     }
 
@@ -292,7 +362,30 @@ impl EditorState {
         registry: &GrammarRegistry,
         languages: &std::collections::HashMap<String, crate::config::LanguageConfig>,
     ) -> anyhow::Result<Self> {
-        let buffer = Buffer::load_from_file(path, large_file_threshold)?;
+        Self::from_file_with_encoding(
+            path,
+            _width,
+            _height,
+            large_file_threshold,
+            registry,
+            languages,
+            None,
+        )
+    }
+
+    /// Create an editor state from a file, decoding it with the given encoding.
+    ///
+    /// If `encoding` is None, the encoding is detected from the file content.
+    pub fn from_file_with_encoding(
+        path: &std::path::Path,
+        _width: u16,
+        _height: u16,
+        large_file_threshold: usize,
+        registry: &GrammarRegistry,
+        languages: &std::collections::HashMap<String, crate::config::LanguageConfig>,
+        encoding: Option<crate::model::encoding::Encoding>,
+    ) -> anyhow::Result<Self> {
+        let buffer = Buffer::load_from_file_with_encoding(path, large_file_threshold, encoding)?;
 
         // Create highlighter using HighlightEngine with language config
         let highlighter = HighlightEngine::for_file_with_languages(path, registry, languages);
@@ -330,8 +423,16 @@ impl EditorState {
             overlays: OverlayManager::new(),
             marker_list,
             virtual_texts: VirtualTextManager::new(),
+            scrollbar_marks: PluginScrollbarMarks::new(),
             popups: PopupManager::new(),
             margins: MarginManager::new(),
+            folds: FoldManager::new(),
+            bracket_pairs: BracketPairs::new(),
+            symbols: DocumentSymbols::new(),
+            syntax_tree: SyntaxTree::new(),
+            code_lenses: CodeLenses::new(),
+            selection_expansion: SelectionExpansion::new(),
+            snippet_session: None,
             primary_cursor_line_number: LineNumber::Absolute(0), // Start at line 0
             mode: "insert".to_string(),
             text_properties: TextPropertyManager::new(),
@@ -341,6 +442,9 @@ impl EditorState {
             show_whitespace_tabs: true,
             use_tabs: false,
             tab_size: 4, // Default tab size
+            rulers: Vec::new(),
+            indent_guides: false,
+            bracket_pair_colorization: false,
             reference_highlighter,
             view_mode: ViewMode::Source,
             debug_highlight_mode: false,
@@ -351,6 +455,7 @@ impl EditorState {
             reference_highlight_overlay: ReferenceHighlightOverlay::new(),
             semantic_tokens: None,
             language: language_name,
+            line_indexing: None,
         })
     }
 
@@ -366,6 +471,10 @@ impl EditorState {
         // CRITICAL: Adjust markers BEFORE modifying buffer
         self.marker_list.adjust_for_insert(position, text.len());
         self.margins.adjust_for_insert(position, text.len());
+        self.folds.adjust_for_insert(position, text.len());
+        if let Some(session) = &mut self.snippet_session {
+            session.adjust_for_insert(position, text.len());
+        }
 
         // Insert text into buffer
         self.buffer.insert(position, text);
@@ -414,6 +523,10 @@ impl EditorState {
         // CRITICAL: Adjust markers BEFORE modifying buffer
         self.marker_list.adjust_for_delete(range.start, len);
         self.margins.adjust_for_delete(range.start, len);
+        self.folds.adjust_for_delete(range.start, len);
+        if let Some(session) = &mut self.snippet_session {
+            session.adjust_for_delete(range.start, len);
+        }
 
         // Delete from buffer
         self.buffer.delete(range.clone());
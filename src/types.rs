@@ -11,8 +11,11 @@ use serde::{Deserialize, Serialize};
 pub mod context_keys {
     pub const LINE_NUMBERS: &str = "line_numbers";
     pub const LINE_WRAP: &str = "line_wrap";
+    pub const STICKY_SCROLL: &str = "sticky_scroll";
+    pub const BREADCRUMBS: &str = "breadcrumbs";
     pub const COMPOSE_MODE: &str = "compose_mode";
     pub const FILE_EXPLORER: &str = "file_explorer";
+    pub const OUTLINE: &str = "outline";
     pub const MENU_BAR: &str = "menu_bar";
     pub const FILE_EXPLORER_FOCUSED: &str = "file_explorer_focused";
     pub const MOUSE_CAPTURE: &str = "mouse_capture";
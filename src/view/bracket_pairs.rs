@@ -0,0 +1,147 @@
+//! Bracket pair colorization
+//!
+//! Brackets are colored by their nesting depth. The brackets come from the
+//! tree-sitter syntax tree, so brackets inside strings and comments (which are
+//! part of a single string or comment node) are not counted. Buffers without
+//! a tree-sitter language are not colorized, and neither are brackets without
+//! a matching partner.
+
+use crate::model::buffer::Buffer;
+use crate::primitives::highlighter::Language;
+use crate::primitives::syntax_tree::SyntaxTree;
+use tree_sitter::Tree;
+
+/// A matched bracket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bracket {
+    /// Byte offset of the bracket
+    pub position: usize,
+    /// Number of bracket pairs enclosing this one (0 for the outermost)
+    pub depth: usize,
+}
+
+/// Matched brackets of one buffer, cached until the buffer changes
+#[derive(Debug, Default)]
+pub struct BracketPairs {
+    cached: Option<(u64, Vec<Bracket>)>,
+}
+
+impl BracketPairs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matched brackets of the buffer, sorted by position
+    pub fn brackets(
+        &mut self,
+        buffer: &Buffer,
+        syntax: &mut SyntaxTree,
+        language: Option<&Language>,
+    ) -> &[Bracket] {
+        let version = buffer.version();
+        if self.cached.as_ref().map(|(v, _)| *v) != Some(version) {
+            let brackets = syntax
+                .tree(buffer, language)
+                .map(|(_, tree)| bracket_depths(tree))
+                .unwrap_or_default();
+            self.cached = Some((version, brackets));
+        }
+        self.cached
+            .as_ref()
+            .map(|(_, brackets)| brackets.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+fn closing_bracket(open: &str) -> Option<&'static str> {
+    match open {
+        "(" => Some(")"),
+        "[" => Some("]"),
+        "{" => Some("}"),
+        _ => None,
+    }
+}
+
+/// Matched brackets of `text` with their nesting depth, sorted by position
+///
+/// Only bracket tokens of the syntax tree are considered. A closing bracket
+/// that does not match the innermost open one is ignored.
+pub fn bracket_depths(tree: &Tree) -> Vec<Bracket> {
+    // Open brackets waiting for their partner: (closing bracket, position)
+    let mut open: Vec<(&'static str, usize)> = Vec::new();
+    let mut brackets = Vec::new();
+    let mut cursor = tree.walk();
+    let mut visited_children = false;
+    loop {
+        let node = cursor.node();
+        if !visited_children && node.child_count() == 0 && !node.is_named() && !node.is_missing() {
+            let kind = node.kind();
+            if let Some(close) = closing_bracket(kind) {
+                open.push((close, node.start_byte()));
+            } else if matches!(kind, ")" | "]" | "}")
+                && open.last().is_some_and(|(close, _)| *close == kind)
+            {
+                let (_, start) = open.pop().expect("checked above");
+                let depth = open.len();
+                brackets.push(Bracket {
+                    position: start,
+                    depth,
+                });
+                brackets.push(Bracket {
+                    position: node.start_byte(),
+                    depth,
+                });
+            }
+        }
+        if !visited_children && cursor.goto_first_child() {
+            continue;
+        }
+        if cursor.goto_next_sibling() {
+            visited_children = false;
+        } else if cursor.goto_parent() {
+            visited_children = true;
+        } else {
+            break;
+        }
+    }
+    brackets.sort_by_key(|bracket| bracket.position);
+    brackets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::syntax_selection::parse;
+
+    fn depths(text: &str) -> Vec<(char, usize)> {
+        bracket_depths(&parse(text, &Language::Rust).unwrap())
+            .into_iter()
+            .map(|b| (text[b.position..].chars().next().unwrap(), b.depth))
+            .collect()
+    }
+
+    #[test]
+    fn test_nesting_depths() {
+        assert_eq!(
+            depths("fn f(a: [u8; 2]) { g(a) }"),
+            vec![
+                ('(', 0),
+                ('[', 1),
+                (']', 1),
+                (')', 0),
+                ('{', 0),
+                ('(', 1),
+                (')', 1),
+                ('}', 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_brackets_in_strings_and_comments_are_skipped() {
+        assert_eq!(
+            depths("fn f() { let s = \"(]\"; // {\n}"),
+            vec![('(', 0), (')', 0), ('{', 0), ('}', 0)]
+        );
+    }
+}
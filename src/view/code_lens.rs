@@ -0,0 +1,211 @@
+//! Code lenses
+//!
+//! Actionable annotations such as "Run test | Debug | 3 references" that a
+//! language server attaches to lines of a document (`textDocument/codeLens`).
+//! Servers may answer with bare ranges and fill in the commands later, one
+//! lens at a time (`codeLens/resolve`). The lenses of a line are rendered
+//! together as a virtual line above it, and unresolved lenses keep that line
+//! blank so the text below does not jump once they are resolved.
+
+use lsp_types::{CodeLens, Command};
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Virtual text namespace of the rendered lens lines
+pub const NAMESPACE: &str = "lsp-code-lens";
+
+/// Separator between the lenses of a line
+pub const SEPARATOR: &str = " | ";
+
+/// The lenses of one line, as rendered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LensLine {
+    /// 0-indexed line the lenses belong to
+    pub line: usize,
+    /// Titles of the resolved lenses, joined by the separator
+    pub text: String,
+    /// Character columns of each title in `text`, with the index of its lens
+    pub segments: Vec<(Range<usize>, usize)>,
+}
+
+impl LensLine {
+    /// Index of the lens whose title covers a character column of the text
+    pub fn lens_at(&self, column: usize) -> Option<usize> {
+        self.segments
+            .iter()
+            .find(|(columns, _)| columns.contains(&column))
+            .map(|(_, index)| *index)
+    }
+}
+
+/// Code lenses of a buffer
+#[derive(Debug, Default)]
+pub struct CodeLenses {
+    /// Buffer version the lenses were computed for
+    version: Option<u64>,
+    /// Lenses in document order
+    lenses: Vec<CodeLens>,
+    /// Lenses that were sent to be resolved; those the server failed to
+    /// resolve stay here so they are not requested on every frame
+    resolving: HashSet<usize>,
+}
+
+impl CodeLenses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer version of the lenses, `None` before the first answer or after
+    /// the server asked for a refresh
+    pub fn version(&self) -> Option<u64> {
+        self.version
+    }
+
+    /// Replace the lenses with those computed for `version`
+    pub fn set(&mut self, version: u64, mut lenses: Vec<CodeLens>) {
+        lenses.sort_by_key(|lens| (lens.range.start.line, lens.range.start.character));
+        self.version = Some(version);
+        self.lenses = lenses;
+        self.resolving.clear();
+    }
+
+    /// Keep the lenses on screen but request them again
+    pub fn invalidate(&mut self) {
+        self.version = None;
+    }
+
+    pub fn lenses(&self) -> &[CodeLens] {
+        &self.lenses
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lenses.is_empty()
+    }
+
+    /// Command of a lens, if it is resolved
+    pub fn command(&self, index: usize) -> Option<&Command> {
+        self.lenses.get(index)?.command.as_ref()
+    }
+
+    /// Indices of the lenses on `lines` that still need resolving, marking
+    /// them as in flight
+    pub fn take_unresolved(&mut self, lines: Range<usize>) -> Vec<usize> {
+        let indices: Vec<usize> = self
+            .lenses
+            .iter()
+            .enumerate()
+            .filter(|(index, lens)| {
+                lens.command.is_none()
+                    && lines.contains(&(lens.range.start.line as usize))
+                    && !self.resolving.contains(index)
+            })
+            .map(|(index, _)| index)
+            .collect();
+        self.resolving.extend(indices.iter().copied());
+        indices
+    }
+
+    /// Store a resolved lens, if the lenses are still those of `version`.
+    /// Returns whether anything changed.
+    pub fn resolve(&mut self, version: u64, index: usize, lens: Option<CodeLens>) -> bool {
+        if self.version != Some(version) || !self.resolving.contains(&index) {
+            return false;
+        }
+        match (lens, self.lenses.get_mut(index)) {
+            (Some(lens), Some(current)) if lens.command.is_some() => {
+                current.command = lens.command;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The lenses grouped by line, in document order
+    pub fn lines(&self) -> Vec<LensLine> {
+        let mut lines: Vec<LensLine> = Vec::new();
+        for (index, lens) in self.lenses.iter().enumerate() {
+            let line = lens.range.start.line as usize;
+            if lines.last().map(|last| last.line) != Some(line) {
+                lines.push(LensLine {
+                    line,
+                    text: String::new(),
+                    segments: Vec::new(),
+                });
+            }
+            let Some(command) = &lens.command else {
+                continue;
+            };
+            let current = lines.last_mut().unwrap();
+            if !current.segments.is_empty() {
+                current.text.push_str(SEPARATOR);
+            }
+            let start = current.text.chars().count();
+            current.text.push_str(&command.title);
+            let end = current.text.chars().count();
+            current.segments.push((start..end, index));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::{Position, Range as LspRange};
+
+    fn lens(line: u32, character: u32, title: Option<&str>) -> CodeLens {
+        CodeLens {
+            range: LspRange::new(
+                Position::new(line, character),
+                Position::new(line, character + 1),
+            ),
+            command: title.map(|title| Command::new(title.to_string(), "cmd".to_string(), None)),
+            data: None,
+        }
+    }
+
+    #[test]
+    fn test_lines_group_lenses_in_document_order() {
+        let mut lenses = CodeLenses::new();
+        lenses.set(
+            1,
+            vec![
+                lens(4, 0, Some("3 references")),
+                lens(0, 4, Some("Debug")),
+                lens(0, 0, Some("Run test")),
+                lens(2, 0, None),
+            ],
+        );
+
+        let lines = lenses.lines();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].line, 0);
+        assert_eq!(lines[0].text, "Run test | Debug");
+        assert_eq!(lines[0].lens_at(0), Some(0));
+        assert_eq!(lines[0].lens_at(8), None);
+        assert_eq!(lines[0].lens_at(12), Some(1));
+        assert_eq!(lines[1].text, "");
+        assert_eq!(lines[2].text, "3 references");
+        assert_eq!(lenses.command(3).unwrap().title, "3 references");
+        assert!(lenses.command(2).is_none());
+    }
+
+    #[test]
+    fn test_resolve_only_applies_to_current_version() {
+        let mut lenses = CodeLenses::new();
+        lenses.set(1, vec![lens(0, 0, None), lens(9, 0, None)]);
+
+        assert_eq!(lenses.take_unresolved(0..5), vec![0]);
+        // Already in flight
+        assert!(lenses.take_unresolved(0..5).is_empty());
+        // Not resolved by the server: not requested again
+        assert!(!lenses.resolve(1, 0, None));
+        assert!(lenses.take_unresolved(0..5).is_empty());
+
+        assert!(!lenses.resolve(2, 0, Some(lens(0, 0, Some("Run")))));
+        lenses.set(2, vec![lens(0, 0, None)]);
+        assert_eq!(lenses.take_unresolved(0..5), vec![0]);
+        assert!(lenses.resolve(2, 0, Some(lens(0, 0, Some("Run")))));
+        assert_eq!(lenses.lines()[0].text, "Run");
+    }
+}
@@ -0,0 +1,447 @@
+//! Document symbols
+//!
+//! The functions, classes, modules and other definitions of a buffer as a
+//! tree, shown by the outline panel and the breadcrumb bar. They come from
+//! the language server (`textDocument/documentSymbol`) once it has answered
+//! for the buffer, and from the tree-sitter tags query of the buffer's
+//! language otherwise. Symbols nest by the byte ranges they span.
+
+use crate::model::buffer::Buffer;
+use crate::primitives::highlighter::Language;
+use crate::primitives::syntax_tree::SyntaxTree;
+use lsp_types::{DocumentSymbolResponse, Position, SymbolKind};
+use std::borrow::Cow;
+use std::mem::Discriminant;
+use std::ops::Range;
+use tree_sitter::{Query, QueryCursor, StreamingIterator, Tree};
+
+/// A symbol of a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Bytes spanned by the symbol, e.g. a function including its body
+    pub range: Range<usize>,
+    /// Byte offset of the symbol's name, where jumps land
+    pub position: usize,
+    pub children: Vec<OutlineSymbol>,
+}
+
+impl OutlineSymbol {
+    fn contains(&self, position: usize) -> bool {
+        self.range.start <= position && position <= self.range.end
+    }
+}
+
+/// Short label for a symbol kind
+pub fn kind_label(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::FILE => "file",
+        SymbolKind::MODULE | SymbolKind::NAMESPACE | SymbolKind::PACKAGE => "mod",
+        SymbolKind::CLASS => "class",
+        SymbolKind::METHOD => "method",
+        SymbolKind::PROPERTY | SymbolKind::FIELD => "field",
+        SymbolKind::CONSTRUCTOR => "ctor",
+        SymbolKind::ENUM => "enum",
+        SymbolKind::INTERFACE => "iface",
+        SymbolKind::FUNCTION => "fn",
+        SymbolKind::VARIABLE => "var",
+        SymbolKind::CONSTANT => "const",
+        SymbolKind::ENUM_MEMBER => "variant",
+        SymbolKind::STRUCT => "struct",
+        SymbolKind::EVENT => "event",
+        SymbolKind::OPERATOR => "op",
+        SymbolKind::TYPE_PARAMETER => "type",
+        _ => "sym",
+    }
+}
+
+/// One-character icon for a symbol kind, like those of the completion popup
+pub fn kind_icon(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::FUNCTION | SymbolKind::METHOD | SymbolKind::CONSTRUCTOR => "λ",
+        SymbolKind::STRUCT | SymbolKind::CLASS => "S",
+        SymbolKind::ENUM => "E",
+        SymbolKind::ENUM_MEMBER => "e",
+        SymbolKind::INTERFACE => "I",
+        SymbolKind::MODULE | SymbolKind::NAMESPACE | SymbolKind::PACKAGE => "M",
+        SymbolKind::CONSTANT => "c",
+        SymbolKind::VARIABLE | SymbolKind::FIELD | SymbolKind::PROPERTY => "v",
+        SymbolKind::TYPE_PARAMETER => "T",
+        _ => "•",
+    }
+}
+
+/// Indices of the symbols enclosing `position`, outermost first
+pub fn symbol_path(symbols: &[OutlineSymbol], position: usize) -> Vec<usize> {
+    let mut path = Vec::new();
+    let mut level = symbols;
+    while let Some(index) = level.iter().position(|symbol| symbol.contains(position)) {
+        path.push(index);
+        level = &level[index].children;
+    }
+    path
+}
+
+/// The symbol at an index path, as returned by `symbol_path`
+pub fn symbol_at<'a>(symbols: &'a [OutlineSymbol], path: &[usize]) -> Option<&'a OutlineSymbol> {
+    let (last, parents) = path.split_last()?;
+    siblings(symbols, parents).get(*last)
+}
+
+/// The children of the symbol at `parent_path`, or the top-level symbols for
+/// an empty path
+pub fn siblings<'a>(symbols: &'a [OutlineSymbol], parent_path: &[usize]) -> &'a [OutlineSymbol] {
+    let mut level = symbols;
+    for index in parent_path {
+        match level.get(*index) {
+            Some(symbol) => level = &symbol.children,
+            None => return &[],
+        }
+    }
+    level
+}
+
+/// Nest symbols by their ranges: a symbol becomes a child of the innermost
+/// symbol spanning it
+pub fn nest_symbols(mut flat: Vec<OutlineSymbol>) -> Vec<OutlineSymbol> {
+    fn attach(symbol: OutlineSymbol, open: &mut [OutlineSymbol], roots: &mut Vec<OutlineSymbol>) {
+        match open.last_mut() {
+            Some(parent) => parent.children.push(symbol),
+            None => roots.push(symbol),
+        }
+    }
+
+    flat.sort_by(|a, b| {
+        a.range
+            .start
+            .cmp(&b.range.start)
+            .then(b.range.end.cmp(&a.range.end))
+    });
+    let mut roots = Vec::new();
+    // Symbols whose range may still contain the next one, outermost first
+    let mut open: Vec<OutlineSymbol> = Vec::new();
+    for symbol in flat {
+        while let Some(top) = open.last() {
+            if symbol.range.start >= top.range.start && symbol.range.end <= top.range.end {
+                break;
+            }
+            let done = open.pop().expect("checked above");
+            attach(done, &mut open, &mut roots);
+        }
+        open.push(symbol);
+    }
+    while let Some(done) = open.pop() {
+        attach(done, &mut open, &mut roots);
+    }
+    roots
+}
+
+/// Convert a `textDocument/documentSymbol` response, mapping LSP positions to
+/// byte offsets with `to_byte`
+pub fn symbols_from_lsp(
+    response: DocumentSymbolResponse,
+    to_byte: impl Fn(Position) -> usize,
+) -> Vec<OutlineSymbol> {
+    fn convert(
+        symbol: lsp_types::DocumentSymbol,
+        to_byte: &dyn Fn(Position) -> usize,
+    ) -> OutlineSymbol {
+        let mut children: Vec<OutlineSymbol> = symbol
+            .children
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| convert(child, to_byte))
+            .collect();
+        children.sort_by_key(|child| child.range.start);
+        OutlineSymbol {
+            name: symbol.name,
+            kind: symbol.kind,
+            range: to_byte(symbol.range.start)..to_byte(symbol.range.end),
+            position: to_byte(symbol.selection_range.start),
+            children,
+        }
+    }
+
+    match response {
+        DocumentSymbolResponse::Nested(symbols) => {
+            let mut symbols: Vec<OutlineSymbol> = symbols
+                .into_iter()
+                .map(|symbol| convert(symbol, &to_byte))
+                .collect();
+            symbols.sort_by_key(|symbol| symbol.range.start);
+            symbols
+        }
+        // Flat symbol information carries no hierarchy: nest by range
+        DocumentSymbolResponse::Flat(symbols) => nest_symbols(
+            symbols
+                .into_iter()
+                .map(|symbol| {
+                    let range =
+                        to_byte(symbol.location.range.start)..to_byte(symbol.location.range.end);
+                    OutlineSymbol {
+                        name: symbol.name,
+                        kind: symbol.kind,
+                        position: range.start,
+                        range,
+                        children: Vec::new(),
+                    }
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Source of the tags query of a language, None if it has none
+fn tags_query_source(language: &Language) -> Option<Cow<'static, str>> {
+    Some(Cow::Borrowed(match language {
+        Language::Rust => tree_sitter_rust::TAGS_QUERY,
+        Language::Python => tree_sitter_python::TAGS_QUERY,
+        Language::JavaScript => tree_sitter_javascript::TAGS_QUERY,
+        // The TypeScript query only covers what TypeScript adds to JavaScript
+        Language::TypeScript => {
+            return Some(Cow::Owned(format!(
+                "{}\n{}",
+                tree_sitter_javascript::TAGS_QUERY,
+                tree_sitter_typescript::TAGS_QUERY
+            )))
+        }
+        Language::C => tree_sitter_c::TAGS_QUERY,
+        Language::Cpp => tree_sitter_cpp::TAGS_QUERY,
+        Language::Go => tree_sitter_go::TAGS_QUERY,
+        Language::Java => tree_sitter_java::TAGS_QUERY,
+        Language::Php => tree_sitter_php::TAGS_QUERY,
+        Language::Ruby => tree_sitter_ruby::TAGS_QUERY,
+        Language::Lua => tree_sitter_lua::TAGS_QUERY,
+        // The C# and Pascal grammars do not ship their tags queries
+        Language::CSharp
+        | Language::Pascal
+        | Language::HTML
+        | Language::CSS
+        | Language::Json
+        | Language::Bash => return None,
+    }))
+}
+
+/// Compile the tags query of a language
+pub fn tags_query(language: &Language) -> Option<Query> {
+    let source = tags_query_source(language)?;
+    match Query::new(&language.tree_sitter_language(), &source) {
+        Ok(query) => Some(query),
+        Err(e) => {
+            tracing::debug!("Tags query failed for {:?}: {}", language, e);
+            None
+        }
+    }
+}
+
+/// Symbol kind of a tags query capture such as `definition.function`
+fn tag_kind(capture: &str) -> Option<SymbolKind> {
+    Some(match capture.strip_prefix("definition.")? {
+        "function" => SymbolKind::FUNCTION,
+        "method" => SymbolKind::METHOD,
+        "class" => SymbolKind::CLASS,
+        "interface" => SymbolKind::INTERFACE,
+        "module" => SymbolKind::MODULE,
+        "macro" => SymbolKind::FUNCTION,
+        "constant" => SymbolKind::CONSTANT,
+        "field" => SymbolKind::FIELD,
+        "type" => SymbolKind::STRUCT,
+        _ => SymbolKind::VARIABLE,
+    })
+}
+
+/// Symbols of `text` found by a tags query (see `tags_query`)
+pub fn tag_symbols(text: &str, tree: &Tree, query: &Query) -> Vec<OutlineSymbol> {
+    let capture_names = query.capture_names();
+    let mut symbols: Vec<OutlineSymbol> = Vec::new();
+    let mut query_cursor = QueryCursor::new();
+    let mut matches = query_cursor.matches(query, tree.root_node(), text.as_bytes());
+    while let Some(query_match) = matches.next() {
+        let mut name = None;
+        let mut definition = None;
+        for capture in query_match.captures {
+            let capture_name = capture_names[capture.index as usize];
+            if capture_name == "name" {
+                name = Some(capture.node);
+            } else if let Some(kind) = tag_kind(capture_name) {
+                definition = Some((capture.node, kind));
+            }
+        }
+        let (Some(name), Some((node, kind))) = (name, definition) else {
+            continue;
+        };
+        let range = node.start_byte()..node.end_byte();
+        // Several patterns can match one definition (a method is also a
+        // function): the first one wins
+        if symbols.iter().any(|symbol| symbol.range == range) {
+            continue;
+        }
+        symbols.push(OutlineSymbol {
+            name: text[name.start_byte()..name.end_byte()].to_string(),
+            kind,
+            range,
+            position: name.start_byte(),
+            children: Vec::new(),
+        });
+    }
+    nest_symbols(symbols)
+}
+
+/// Symbols of one buffer, cached until the buffer changes
+#[derive(Debug, Default)]
+pub struct DocumentSymbols {
+    /// Symbols from the language server
+    lsp: Option<Vec<OutlineSymbol>>,
+    /// Buffer version of the last language server request
+    lsp_version: Option<u64>,
+    /// Symbols from the tags query, for a buffer version
+    tags: Option<(u64, Vec<OutlineSymbol>)>,
+    /// Compiled tags query of the buffer's language
+    query: Option<(Discriminant<Language>, Option<Query>)>,
+}
+
+impl DocumentSymbols {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer version the language server was last asked about
+    pub fn lsp_version(&self) -> Option<u64> {
+        self.lsp_version
+    }
+
+    /// Record the language server's answer for a buffer version; None when the
+    /// request failed, which keeps the symbols there are
+    pub fn set_lsp_symbols(&mut self, version: u64, symbols: Option<Vec<OutlineSymbol>>) {
+        self.lsp_version = Some(version);
+        if let Some(symbols) = symbols {
+            self.lsp = Some(symbols);
+        }
+    }
+
+    /// Symbols of the buffer
+    ///
+    /// Symbols from the language server are kept while the buffer is edited
+    /// until the server answers for the new version.
+    pub fn symbols(
+        &mut self,
+        buffer: &Buffer,
+        syntax: &mut SyntaxTree,
+        language: Option<&Language>,
+    ) -> &[OutlineSymbol] {
+        if let Some(symbols) = &self.lsp {
+            return symbols;
+        }
+        let version = buffer.version();
+        if self.tags.as_ref().map(|(v, _)| *v) != Some(version) {
+            let symbols = language
+                .and_then(|language| {
+                    let key = std::mem::discriminant(language);
+                    if self.query.as_ref().map(|(k, _)| *k) != Some(key) {
+                        self.query = Some((key, tags_query(language)));
+                    }
+                    let query = self.query.as_ref()?.1.as_ref()?;
+                    let (text, tree) = syntax.tree(buffer, Some(language))?;
+                    Some(tag_symbols(text, tree, query))
+                })
+                .unwrap_or_default();
+            self.tags = Some((version, symbols));
+        }
+        self.tags
+            .as_ref()
+            .map(|(_, symbols)| symbols.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::syntax_selection::parse;
+
+    fn names(symbols: &[OutlineSymbol]) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|symbol| {
+                if symbol.children.is_empty() {
+                    symbol.name.clone()
+                } else {
+                    format!("{}({})", symbol.name, names(&symbol.children).join(" "))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tags_queries_compile() {
+        for language in [
+            Language::Rust,
+            Language::Python,
+            Language::JavaScript,
+            Language::TypeScript,
+            Language::C,
+            Language::Cpp,
+            Language::Go,
+            Language::Java,
+            Language::Php,
+            Language::Ruby,
+            Language::Lua,
+        ] {
+            assert!(tags_query(&language).is_some(), "{language:?}");
+        }
+    }
+
+    #[test]
+    fn test_tag_symbols_nest() {
+        let text =
+            "mod a {\n    fn f() {}\n    trait T { fn g() {} }\n}\nstruct S;\nfn main() {}\n";
+        let query = tags_query(&Language::Rust).unwrap();
+        let symbols = tag_symbols(text, &parse(text, &Language::Rust).unwrap(), &query);
+        assert_eq!(names(&symbols), vec!["a(f T(g))", "S", "main"]);
+        assert_eq!(symbols[2].kind, SymbolKind::FUNCTION);
+        assert_eq!(symbols[2].position, text.find("main").unwrap());
+
+        let python = "class C:\n    def m(self):\n        pass\n\ndef f():\n    pass\n";
+        let query = tags_query(&Language::Python).unwrap();
+        let symbols = tag_symbols(python, &parse(python, &Language::Python).unwrap(), &query);
+        assert_eq!(names(&symbols), vec!["C(m)", "f"]);
+    }
+
+    #[test]
+    fn test_symbol_path() {
+        let text = "mod a {\n    fn f() {}\n    fn g() {}\n}\nfn main() {}\n";
+        let query = tags_query(&Language::Rust).unwrap();
+        let symbols = tag_symbols(text, &parse(text, &Language::Rust).unwrap(), &query);
+        let path = symbol_path(&symbols, text.find("g()").unwrap() + 4);
+        assert_eq!(path, vec![0, 1]);
+        assert_eq!(symbol_at(&symbols, &path).unwrap().name, "g");
+        assert_eq!(names(siblings(&symbols, &path[..1])), vec!["f", "g"]);
+        assert_eq!(symbol_path(&symbols, text.len()), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_symbols_from_flat_lsp_response() {
+        #[allow(deprecated)]
+        let info = |name: &str, kind, start: u32, end: u32| lsp_types::SymbolInformation {
+            name: name.to_string(),
+            kind,
+            tags: None,
+            deprecated: None,
+            location: lsp_types::Location {
+                uri: "file:///a.rs".parse().unwrap(),
+                range: lsp_types::Range::new(Position::new(start, 0), Position::new(end, 0)),
+            },
+            container_name: None,
+        };
+        let response = DocumentSymbolResponse::Flat(vec![
+            info("main", SymbolKind::FUNCTION, 5, 6),
+            info("Point", SymbolKind::STRUCT, 0, 4),
+            info("x", SymbolKind::FIELD, 1, 2),
+        ]);
+        // Ten bytes per line
+        let symbols = symbols_from_lsp(response, |position| position.line as usize * 10);
+        assert_eq!(names(&symbols), vec!["Point(x)", "main"]);
+        assert_eq!(symbols[0].range, 0..40);
+    }
+}
@@ -0,0 +1,570 @@
+//! Code folding
+//!
+//! Fold ranges come from the language server (`textDocument/foldingRange`)
+//! when it has answered for the current buffer version, otherwise from the
+//! tree-sitter syntax tree, and otherwise from indentation.
+//!
+//! A collapsed fold keeps its header line visible and hides the lines after
+//! it up to the end of the range. Collapsed folds are anchored by markers, so
+//! they follow edits made above or inside them; line numbers are only derived
+//! from the markers when they are needed.
+//!
+//! Folding is unavailable for buffers without exact line numbers (large files
+//! that have not been indexed).
+
+use crate::model::buffer::Buffer;
+use crate::model::marker::{MarkerId, MarkerList};
+use crate::primitives::highlighter::Language;
+use crate::primitives::syntax_tree::SyntaxTree;
+use crate::view::viewport::Viewport;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use tree_sitter::Tree;
+
+/// A foldable range of lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FoldRange {
+    /// Line that stays visible when the range is folded (0-indexed)
+    pub start_line: usize,
+    /// Last line hidden when the range is folded (0-indexed)
+    pub end_line: usize,
+}
+
+impl FoldRange {
+    pub fn new(start_line: usize, end_line: usize) -> Self {
+        Self {
+            start_line,
+            end_line,
+        }
+    }
+
+    /// Whether `line` is the header or one of the hidden lines
+    pub fn contains_line(&self, line: usize) -> bool {
+        line >= self.start_line && line <= self.end_line
+    }
+}
+
+/// A collapsed fold resolved against the current buffer content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldedRegion {
+    /// Line that stays visible
+    pub header_line: usize,
+    /// Last hidden line
+    pub end_line: usize,
+    /// Byte offset where the header line starts
+    pub header_start: usize,
+    /// Bytes hidden by the fold: from the start of the line after the header
+    /// to the start of the line after `end_line` (or the end of the buffer)
+    pub hidden: Range<usize>,
+}
+
+/// A collapsed fold, anchored at the start of its header and last lines
+#[derive(Debug, Clone, Copy)]
+struct CollapsedFold {
+    start: MarkerId,
+    end: MarkerId,
+}
+
+/// Fold ranges and collapsed folds of one buffer
+#[derive(Debug, Default)]
+pub struct FoldManager {
+    /// Markers anchoring the collapsed folds
+    markers: MarkerList,
+    /// Collapsed folds, in the order they were collapsed
+    collapsed: Vec<CollapsedFold>,
+    /// Ranges from the language server and the buffer version they describe
+    lsp_ranges: Option<(u64, Vec<FoldRange>)>,
+    /// Ranges computed for a buffer version
+    cached_ranges: Option<(u64, Vec<FoldRange>)>,
+}
+
+impl FoldManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adjust the collapsed folds after an insertion
+    pub fn adjust_for_insert(&mut self, position: usize, length: usize) {
+        self.markers.adjust_for_insert(position, length);
+    }
+
+    /// Adjust the collapsed folds after a deletion
+    pub fn adjust_for_delete(&mut self, position: usize, length: usize) {
+        self.markers.adjust_for_delete(position, length);
+    }
+
+    /// Whether any fold is collapsed
+    pub fn has_collapsed(&self) -> bool {
+        !self.collapsed.is_empty()
+    }
+
+    /// Store the ranges reported by the language server for a buffer version
+    pub fn set_lsp_ranges(&mut self, version: u64, ranges: Vec<FoldRange>) {
+        self.lsp_ranges = Some((version, normalize_ranges(ranges)));
+        self.cached_ranges = None;
+    }
+
+    /// Buffer version the language server ranges describe
+    pub fn lsp_ranges_version(&self) -> Option<u64> {
+        self.lsp_ranges.as_ref().map(|(version, _)| *version)
+    }
+
+    /// Foldable ranges of the buffer, sorted by start line
+    ///
+    /// Computed ranges are cached until the buffer changes.
+    pub fn ranges(
+        &mut self,
+        buffer: &Buffer,
+        syntax: &mut SyntaxTree,
+        language: Option<&Language>,
+        tab_size: usize,
+    ) -> &[FoldRange] {
+        let version = buffer.version();
+        // A server with nothing to fold leaves folding to the fallbacks
+        let use_lsp = self
+            .lsp_ranges
+            .as_ref()
+            .is_some_and(|(v, ranges)| *v == version && !ranges.is_empty());
+        if !use_lsp {
+            return self.scope_ranges(buffer, syntax, language, tab_size);
+        }
+        self.lsp_ranges
+            .as_ref()
+            .map(|(_, ranges)| ranges.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Ranges of the syntax tree nodes (or indented blocks) of the buffer,
+    /// ignoring the language server, sorted by start line
+    ///
+    /// Cached until the buffer changes.
+    pub fn scope_ranges(
+        &mut self,
+        buffer: &Buffer,
+        syntax: &mut SyntaxTree,
+        language: Option<&Language>,
+        tab_size: usize,
+    ) -> &[FoldRange] {
+        let version = buffer.version();
+        if self.cached_ranges.as_ref().map(|(v, _)| *v) != Some(version) {
+            let ranges = compute_ranges(buffer, syntax, language, tab_size);
+            self.cached_ranges = Some((version, ranges));
+        }
+        self.cached_ranges
+            .as_ref()
+            .map(|(_, ranges)| ranges.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Collapse a range
+    ///
+    /// Returns false if the range is not valid for the buffer or already collapsed.
+    pub fn collapse(&mut self, buffer: &Buffer, range: FoldRange) -> bool {
+        if range.end_line <= range.start_line || buffer.line_count().is_none() {
+            return false;
+        }
+        let (Some(start), Some(end)) = (
+            buffer.line_start_offset(range.start_line),
+            buffer.line_start_offset(range.end_line),
+        ) else {
+            return false;
+        };
+        if self.is_collapsed(buffer, range.start_line) {
+            return false;
+        }
+        self.collapsed.push(CollapsedFold {
+            start: self.markers.create(start, true),
+            end: self.markers.create(end, true),
+        });
+        true
+    }
+
+    /// Expand the fold whose header is `line`
+    pub fn expand_at_line(&mut self, buffer: &Buffer, line: usize) -> bool {
+        let before = self.collapsed.len();
+        self.retain_collapsed(buffer, |range| range.is_some_and(|r| r.start_line != line));
+        self.collapsed.len() != before
+    }
+
+    /// Expand every fold hiding the byte at `position`
+    pub fn expand_containing(&mut self, buffer: &Buffer, position: usize) -> bool {
+        let before = self.collapsed.len();
+        let line = buffer.get_line_number(position);
+        self.retain_collapsed(buffer, |range| {
+            range.is_some_and(|r| line <= r.start_line || line > r.end_line)
+        });
+        self.collapsed.len() != before
+    }
+
+    /// Expand all folds
+    pub fn expand_all(&mut self) -> bool {
+        let had_folds = !self.collapsed.is_empty();
+        for fold in self.collapsed.drain(..) {
+            self.markers.delete(fold.start);
+            self.markers.delete(fold.end);
+        }
+        had_folds
+    }
+
+    /// Whether a fold with its header on `line` is collapsed
+    pub fn is_collapsed(&self, buffer: &Buffer, line: usize) -> bool {
+        self.collapsed
+            .iter()
+            .filter_map(|fold| self.resolve(buffer, fold))
+            .any(|range| range.start_line == line)
+    }
+
+    /// Line ranges of the collapsed folds, sorted by start line
+    pub fn collapsed_ranges(&self, buffer: &Buffer) -> Vec<FoldRange> {
+        let mut ranges: Vec<FoldRange> = self
+            .collapsed
+            .iter()
+            .filter_map(|fold| self.resolve(buffer, fold))
+            .collect();
+        ranges.sort();
+        ranges.dedup();
+        ranges
+    }
+
+    /// The regions hidden by collapsed folds, sorted and without nesting
+    ///
+    /// A fold inside another collapsed fold is covered by the outer one.
+    pub fn folded_regions(&self, buffer: &Buffer) -> Vec<FoldedRegion> {
+        if self.collapsed.is_empty() || buffer.line_count().is_none() {
+            return Vec::new();
+        }
+        let mut regions: Vec<FoldedRegion> = Vec::new();
+        for range in self.collapsed_ranges(buffer) {
+            if let Some(last) = regions.last_mut() {
+                if range.start_line <= last.end_line {
+                    // Folds sharing the header line hide the lines of the larger one
+                    if range.start_line == last.header_line && range.end_line > last.end_line {
+                        last.end_line = range.end_line;
+                        last.hidden.end = hidden_end(buffer, range.end_line);
+                    }
+                    continue;
+                }
+            }
+            let (Some(header_start), Some(hidden_start)) = (
+                buffer.line_start_offset(range.start_line),
+                buffer.line_start_offset(range.start_line + 1),
+            ) else {
+                continue;
+            };
+            regions.push(FoldedRegion {
+                header_line: range.start_line,
+                end_line: range.end_line,
+                header_start,
+                hidden: hidden_start..hidden_end(buffer, range.end_line),
+            });
+        }
+        regions
+    }
+
+    /// The collapsed region hiding the byte at `position`, if any
+    pub fn region_containing(&self, buffer: &Buffer, position: usize) -> Option<FoldedRegion> {
+        if self.collapsed.is_empty() {
+            return None;
+        }
+        self.folded_regions(buffer)
+            .into_iter()
+            .find(|region| region.hidden.contains(&position))
+    }
+
+    /// Resolve a collapsed fold to lines, or None if edits removed it
+    fn resolve(&self, buffer: &Buffer, fold: &CollapsedFold) -> Option<FoldRange> {
+        let start = self.markers.get_position(fold.start)?;
+        let end = self.markers.get_position(fold.end)?;
+        let range = FoldRange::new(buffer.get_line_number(start), buffer.get_line_number(end));
+        (range.end_line > range.start_line).then_some(range)
+    }
+
+    /// Keep the collapsed folds whose resolved range passes `keep`
+    fn retain_collapsed(&mut self, buffer: &Buffer, keep: impl Fn(Option<FoldRange>) -> bool) {
+        let mut kept = Vec::with_capacity(self.collapsed.len());
+        for fold in std::mem::take(&mut self.collapsed) {
+            if keep(self.resolve(buffer, &fold)) {
+                kept.push(fold);
+            } else {
+                self.markers.delete(fold.start);
+                self.markers.delete(fold.end);
+            }
+        }
+        self.collapsed = kept;
+    }
+}
+
+/// Scroll a viewport by `lines` visible lines, stepping over collapsed folds
+pub fn scroll_viewport(
+    viewport: &mut Viewport,
+    buffer: &mut Buffer,
+    regions: &[FoldedRegion],
+    lines: isize,
+) {
+    if regions.is_empty() {
+        if lines < 0 {
+            viewport.scroll_up(buffer, lines.unsigned_abs());
+        } else {
+            viewport.scroll_down(buffer, lines as usize);
+        }
+        return;
+    }
+    for _ in 0..lines.unsigned_abs() {
+        if lines < 0 {
+            viewport.scroll_up(buffer, 1);
+        } else {
+            viewport.scroll_down(buffer, 1);
+        }
+        if let Some(region) = regions
+            .iter()
+            .find(|r| r.hidden.contains(&viewport.top_byte))
+        {
+            viewport.top_byte = if lines < 0 {
+                region.header_start
+            } else {
+                region.hidden.end.min(buffer.len())
+            };
+        }
+    }
+}
+
+/// Start of the line after `line`, or the end of the buffer
+fn hidden_end(buffer: &Buffer, line: usize) -> usize {
+    buffer
+        .line_start_offset(line + 1)
+        .unwrap_or_else(|| buffer.len())
+}
+
+/// Compute fold ranges from the syntax tree, or from indentation when the
+/// buffer has no tree-sitter language
+fn compute_ranges(
+    buffer: &Buffer,
+    syntax: &mut SyntaxTree,
+    language: Option<&Language>,
+    tab_size: usize,
+) -> Vec<FoldRange> {
+    if buffer.line_count().is_none() {
+        return Vec::new();
+    }
+    if let Some((text, tree)) = syntax.tree(buffer, language) {
+        return syntax_ranges(text, tree);
+    }
+    match buffer.to_string() {
+        Some(text) => indentation_ranges(&text, tab_size),
+        None => Vec::new(),
+    }
+}
+
+/// Fold ranges of the multi-line named nodes of the syntax tree
+///
+/// A node whose last line starts with a closing delimiter keeps that line
+/// visible, so a folded block reads `fn main() { ⋯` followed by `}`.
+pub fn syntax_ranges(text: &str, tree: &Tree) -> Vec<FoldRange> {
+    let lines: Vec<&str> = text.lines().collect();
+
+    let mut ranges = Vec::new();
+    let mut cursor = tree.walk();
+    let mut visited_children = false;
+    loop {
+        let node = cursor.node();
+        if !visited_children && node.is_named() && node.parent().is_some() {
+            let start_line = node.start_position().row;
+            let mut end_line = node.end_position().row;
+            if node.end_position().column == 0 {
+                end_line = end_line.saturating_sub(1);
+            }
+            let closes = lines.get(end_line).is_some_and(|line| {
+                let line = line.trim_start();
+                line.starts_with(['}', ']', ')'])
+                    || line.starts_with("end")
+                    || line.starts_with("</")
+            });
+            if closes {
+                end_line = end_line.saturating_sub(1);
+            }
+            if end_line > start_line {
+                ranges.push(FoldRange::new(start_line, end_line));
+            }
+        }
+        if !visited_children && cursor.goto_first_child() {
+            continue;
+        }
+        if cursor.goto_next_sibling() {
+            visited_children = false;
+        } else if cursor.goto_parent() {
+            visited_children = true;
+        } else {
+            break;
+        }
+    }
+    normalize_ranges(ranges)
+}
+
+/// Fold ranges from indentation: a line starts a range covering the lines
+/// after it that are indented deeper (blank lines in between included)
+pub fn indentation_ranges(text: &str, tab_size: usize) -> Vec<FoldRange> {
+    let indents: Vec<Option<usize>> = text
+        .lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                return None;
+            }
+            let mut width = 0;
+            for ch in line.chars() {
+                match ch {
+                    ' ' => width += 1,
+                    '\t' => width += tab_size.max(1) - width % tab_size.max(1),
+                    _ => break,
+                }
+            }
+            Some(width)
+        })
+        .collect();
+
+    let mut ranges = Vec::new();
+    for (start, indent) in indents.iter().enumerate() {
+        let Some(indent) = *indent else {
+            continue;
+        };
+        let mut end = start;
+        for (line, other) in indents.iter().enumerate().skip(start + 1) {
+            match other {
+                None => continue,
+                Some(other) if *other > indent => end = line,
+                Some(_) => break,
+            }
+        }
+        if end > start {
+            ranges.push(FoldRange::new(start, end));
+        }
+    }
+    ranges
+}
+
+/// Sort ranges by start line, keeping the largest range for each start line
+pub fn normalize_ranges(mut ranges: Vec<FoldRange>) -> Vec<FoldRange> {
+    ranges.retain(|r| r.end_line > r.start_line);
+    ranges.sort_by(|a, b| {
+        a.start_line
+            .cmp(&b.start_line)
+            .then(b.end_line.cmp(&a.end_line))
+    });
+    ranges.dedup_by_key(|r| r.start_line);
+    ranges
+}
+
+/// The smallest range containing `line`, skipping ranges rejected by `skip`
+pub fn innermost_range(
+    ranges: &[FoldRange],
+    line: usize,
+    skip: impl Fn(&FoldRange) -> bool,
+) -> Option<FoldRange> {
+    ranges
+        .iter()
+        .filter(|r| r.contains_line(line) && !skip(r))
+        .min_by_key(|r| r.end_line - r.start_line)
+        .copied()
+}
+
+/// Nesting level of each range (1 for ranges not inside another range)
+///
+/// `ranges` must be normalized.
+pub fn nesting_levels(ranges: &[FoldRange]) -> Vec<usize> {
+    let mut open: Vec<FoldRange> = Vec::new();
+    ranges
+        .iter()
+        .map(|range| {
+            while open
+                .last()
+                .is_some_and(|outer| outer.end_line < range.start_line)
+            {
+                open.pop();
+            }
+            // Partially overlapping ranges are treated as siblings
+            while open
+                .last()
+                .is_some_and(|outer| outer.end_line < range.end_line)
+            {
+                open.pop();
+            }
+            open.push(*range);
+            open.len()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::syntax_selection::parse;
+
+    fn lines(ranges: &[FoldRange]) -> Vec<(usize, usize)> {
+        ranges.iter().map(|r| (r.start_line, r.end_line)).collect()
+    }
+
+    #[test]
+    fn test_indentation_ranges() {
+        let text = "a:\n  b\n\n  c:\n    d\ne\n";
+        assert_eq!(lines(&indentation_ranges(text, 4)), vec![(0, 4), (3, 4)]);
+    }
+
+    #[test]
+    fn test_syntax_ranges_keep_closing_line_visible() {
+        let text = "fn main() {\n    let x = [\n        1,\n    ];\n}\n";
+        let tree = parse(text, &Language::Rust).unwrap();
+        let ranges = syntax_ranges(text, &tree);
+        assert_eq!(lines(&ranges), vec![(0, 3), (1, 2)]);
+    }
+
+    #[test]
+    fn test_nesting_levels() {
+        let ranges = normalize_ranges(vec![
+            FoldRange::new(0, 10),
+            FoldRange::new(1, 4),
+            FoldRange::new(2, 3),
+            FoldRange::new(5, 9),
+            FoldRange::new(12, 14),
+        ]);
+        assert_eq!(nesting_levels(&ranges), vec![1, 2, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_collapsed_fold_follows_edits() {
+        let mut buffer = Buffer::from_str_test("a\nb\n  c\n  d\ne\n");
+        let mut folds = FoldManager::new();
+        assert!(folds.collapse(&buffer, FoldRange::new(1, 3)));
+        assert!(!folds.collapse(&buffer, FoldRange::new(1, 3)));
+
+        let regions = folds.folded_regions(&buffer);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].hidden, 4..12);
+        assert_eq!(
+            folds.region_containing(&buffer, 5).map(|r| r.header_line),
+            Some(1)
+        );
+        assert_eq!(folds.region_containing(&buffer, 2), None);
+
+        // Lines inserted above move the fold down
+        folds.adjust_for_insert(0, 2);
+        buffer.insert(0, "x\n");
+        assert_eq!(folds.collapsed_ranges(&buffer), vec![FoldRange::new(2, 4)]);
+
+        assert!(folds.expand_containing(&buffer, 8));
+        assert!(!folds.has_collapsed());
+    }
+
+    #[test]
+    fn test_nested_folds_merge_into_outer_region() {
+        let buffer = Buffer::from_str_test("a\n b\n  c\n  d\n e\nf\n");
+        let mut folds = FoldManager::new();
+        folds.collapse(&buffer, FoldRange::new(1, 3));
+        folds.collapse(&buffer, FoldRange::new(0, 4));
+        let regions = folds.folded_regions(&buffer);
+        assert_eq!(regions.len(), 1);
+        assert_eq!((regions[0].header_line, regions[0].end_line), (0, 4));
+
+        // Expanding the outer fold leaves the inner one collapsed
+        assert!(folds.expand_at_line(&buffer, 0));
+        assert_eq!(folds.collapsed_ranges(&buffer), vec![FoldRange::new(1, 3)]);
+    }
+}
@@ -0,0 +1,206 @@
+//! View state for the hex editor
+//!
+//! A split in hex view shows its buffer as rows of [`BYTES_PER_ROW`] bytes:
+//! an offset column, the bytes in hex and an ASCII gutter. The primary cursor
+//! of the buffer stays authoritative; its position is the offset of the byte
+//! under the cursor, and this state adds which nibble (or the ASCII gutter)
+//! has focus.
+//!
+//! ```text
+//! 00000000  7F 45 4C 46 02 01 01 00  00 00 00 00 00 00 00 00  .ELF............
+//! ```
+
+use crate::model::event::BufferId;
+
+/// Number of bytes shown on each row
+pub const BYTES_PER_ROW: usize = 16;
+
+/// Minimum number of hex digits in the offset column
+const MIN_OFFSET_DIGITS: usize = 8;
+
+/// Per-split hex view state
+#[derive(Debug, Clone)]
+pub struct HexViewState {
+    /// The buffer shown as hex. Switching the split to another buffer
+    /// shows that buffer as text again.
+    pub buffer_id: BufferId,
+
+    /// First visible row
+    pub top_row: usize,
+
+    /// Whether the cursor is on the low (second) nibble of its byte
+    pub low_nibble: bool,
+
+    /// Whether typing goes to the ASCII gutter instead of the hex columns
+    pub ascii_focus: bool,
+
+    /// Last pattern searched for, repeated by Find Next
+    pub last_pattern: Option<Vec<u8>>,
+
+    /// Cursor offset the view last scrolled to. The view only follows the
+    /// cursor when it moves, so it can be scrolled away with the mouse wheel.
+    followed_cursor: Option<usize>,
+}
+
+impl HexViewState {
+    /// Create a hex view of the given buffer
+    pub fn new(buffer_id: BufferId) -> Self {
+        Self {
+            buffer_id,
+            top_row: 0,
+            low_nibble: false,
+            ascii_focus: false,
+            last_pattern: None,
+            followed_cursor: None,
+        }
+    }
+
+    /// Scroll to keep `cursor` visible if it moved since the last call
+    pub fn follow_cursor(&mut self, cursor: usize, visible_rows: usize) {
+        if self.followed_cursor != Some(cursor) {
+            self.followed_cursor = Some(cursor);
+            self.scroll_to(cursor, visible_rows);
+        }
+    }
+
+    /// Scroll by `delta` rows, keeping the top row at or before `max_row`
+    pub fn scroll(&mut self, delta: isize, max_row: usize) {
+        self.top_row = self.top_row.saturating_add_signed(delta).min(max_row);
+    }
+
+    /// Scroll the minimum amount needed for the row containing `offset` to be visible
+    pub fn scroll_to(&mut self, offset: usize, visible_rows: usize) {
+        let row = offset / BYTES_PER_ROW;
+        let visible_rows = visible_rows.max(1);
+        if row < self.top_row {
+            self.top_row = row;
+        } else if row >= self.top_row + visible_rows {
+            self.top_row = row + 1 - visible_rows;
+        }
+    }
+}
+
+/// Number of rows needed to show `len` bytes, plus the position just past
+/// the end where bytes can be appended
+pub fn row_count(len: usize) -> usize {
+    len / BYTES_PER_ROW + 1
+}
+
+/// Number of hex digits in the offset column for a buffer of `len` bytes
+pub fn offset_digits(len: usize) -> usize {
+    let digits = (usize::BITS - len.leading_zeros()).div_ceil(4) as usize;
+    digits.max(MIN_OFFSET_DIGITS)
+}
+
+/// Column of the first hex digit of byte `index` within a row, relative to
+/// the start of the hex columns. Bytes are grouped in two halves of eight.
+pub fn hex_column(index: usize) -> usize {
+    index * 3 + index / (BYTES_PER_ROW / 2)
+}
+
+/// Width of the hex columns, including the gap before the ASCII gutter
+pub fn hex_columns_width() -> usize {
+    hex_column(BYTES_PER_ROW)
+}
+
+/// Character shown for a byte in the ASCII gutter
+pub fn ascii_char(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        byte as char
+    } else {
+        '.'
+    }
+}
+
+/// Parse a hex search pattern such as `DE AD BE EF`, `deadbeef` or `0xDEAD`.
+///
+/// Whitespace, `:` and `,` separate bytes; a `0x` prefix is allowed on each
+/// group. Returns None for empty input, non-hex characters or an odd number
+/// of digits in a group.
+pub fn parse_hex_pattern(input: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for group in input.split(|c: char| c.is_whitespace() || c == ':' || c == ',') {
+        let digits = group
+            .strip_prefix("0x")
+            .or_else(|| group.strip_prefix("0X"))
+            .unwrap_or(group);
+        if digits.len() % 2 != 0 {
+            return None;
+        }
+        for pair in digits.as_bytes().chunks(2) {
+            let pair = std::str::from_utf8(pair).ok()?;
+            bytes.push(u8::from_str_radix(pair, 16).ok()?);
+        }
+    }
+    (!bytes.is_empty()).then_some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_pattern() {
+        let expected = Some(vec![0xDE, 0xAD, 0xBE, 0xEF]);
+        assert_eq!(parse_hex_pattern("DE AD BE EF"), expected);
+        assert_eq!(parse_hex_pattern("deadbeef"), expected);
+        assert_eq!(parse_hex_pattern("0xDEAD 0xbeef"), expected);
+        assert_eq!(parse_hex_pattern("de:ad:be:ef"), expected);
+        assert_eq!(parse_hex_pattern("  00  "), Some(vec![0x00]));
+
+        assert_eq!(parse_hex_pattern(""), None);
+        assert_eq!(parse_hex_pattern("ABC"), None);
+        assert_eq!(parse_hex_pattern("GG"), None);
+        assert_eq!(parse_hex_pattern("é1"), None);
+    }
+
+    #[test]
+    fn test_row_layout() {
+        assert_eq!(hex_column(0), 0);
+        assert_eq!(hex_column(7), 21);
+        // Second group of eight starts after an extra space
+        assert_eq!(hex_column(8), 25);
+        assert_eq!(hex_column(15), 46);
+        assert_eq!(hex_columns_width(), 50);
+
+        assert_eq!(offset_digits(0), 8);
+        assert_eq!(offset_digits(0xFFFF_FFFF), 8);
+        assert_eq!(offset_digits(0x1_0000_0000), 9);
+
+        assert_eq!(row_count(0), 1);
+        assert_eq!(row_count(15), 1);
+        assert_eq!(row_count(16), 2);
+    }
+
+    #[test]
+    fn test_scroll_to() {
+        let mut view = HexViewState::new(BufferId(1));
+        view.scroll_to(16 * 30, 10);
+        assert_eq!(view.top_row, 21);
+        view.scroll_to(16 * 25 + 3, 10);
+        assert_eq!(view.top_row, 21);
+        view.scroll_to(5, 10);
+        assert_eq!(view.top_row, 0);
+    }
+
+    #[test]
+    fn test_follow_cursor_only_when_it_moves() {
+        let mut view = HexViewState::new(BufferId(1));
+        view.follow_cursor(0, 10);
+        view.scroll(20, 100);
+        assert_eq!(view.top_row, 20);
+
+        // Same cursor: the scrolled view stays put
+        view.follow_cursor(0, 10);
+        assert_eq!(view.top_row, 20);
+
+        // Moved cursor: the view brings it back into sight
+        view.follow_cursor(16, 10);
+        assert_eq!(view.top_row, 1);
+
+        view.scroll(-5, 100);
+        assert_eq!(view.top_row, 0);
+        view.scroll(500, 100);
+        assert_eq!(view.top_row, 100);
+    }
+}
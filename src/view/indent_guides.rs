@@ -0,0 +1,130 @@
+//! Indent guides
+//!
+//! A vertical guide is drawn in the leading whitespace of a line at every
+//! indentation level below the line's own. Blank lines take the indentation
+//! of the lines around them so guides run through them, and the guide of the
+//! block containing the cursor is highlighted.
+
+use std::ops::Range;
+
+/// Symbol drawn for a guide
+pub const GUIDE_SYMBOL: &str = "│";
+
+/// Visual width of the leading whitespace of `line`, or None for a blank line
+pub fn leading_indent(line: &str, tab_size: usize) -> Option<usize> {
+    let tab_size = tab_size.max(1);
+    let mut width = 0;
+    for ch in line.chars() {
+        match ch {
+            ' ' => width += 1,
+            '\t' => width += tab_size - width % tab_size,
+            '\r' | '\n' => return None,
+            _ => return Some(width),
+        }
+    }
+    None
+}
+
+/// Indentation used for the guides of consecutive lines
+///
+/// A blank line takes the smaller indentation of the nearest non-blank lines
+/// before and after it; `before` and `after` are the indentation of the
+/// nearest non-blank lines outside `indents`.
+pub fn guide_indents(
+    indents: &[Option<usize>],
+    before: Option<usize>,
+    after: Option<usize>,
+) -> Vec<usize> {
+    let mut previous = before;
+    let mut result = Vec::with_capacity(indents.len());
+    for (i, indent) in indents.iter().enumerate() {
+        let indent = match indent {
+            Some(indent) => *indent,
+            None => {
+                let next = indents[i + 1..].iter().flatten().next().copied().or(after);
+                previous.unwrap_or(0).min(next.unwrap_or(0))
+            }
+        };
+        if indents[i].is_some() {
+            previous = Some(indent);
+        }
+        result.push(indent);
+    }
+    result
+}
+
+/// Columns of the guides of a line with indentation `indent`
+pub fn guide_columns(indent: usize, indent_size: usize) -> impl Iterator<Item = usize> {
+    (0..indent).step_by(indent_size.max(1))
+}
+
+/// The highlighted guide: its column and the lines (indices into `indents`)
+/// it is drawn on
+///
+/// On a line opening a block (the next line is indented deeper) this is the
+/// guide of that block, otherwise the guide of the block containing the line.
+pub fn active_guide(
+    indents: &[usize],
+    cursor: usize,
+    indent_size: usize,
+) -> Option<(usize, Range<usize>)> {
+    let indent_size = indent_size.max(1);
+    let cursor_indent = *indents.get(cursor)?;
+    let (column, mut start) = if indents
+        .get(cursor + 1)
+        .is_some_and(|next| *next > cursor_indent)
+    {
+        (cursor_indent / indent_size * indent_size, cursor + 1)
+    } else if cursor_indent == 0 {
+        return None;
+    } else {
+        ((cursor_indent - 1) / indent_size * indent_size, cursor)
+    };
+    while start > 0 && indents[start - 1] > column {
+        start -= 1;
+    }
+    let mut end = start;
+    while end < indents.len() && indents[end] > column {
+        end += 1;
+    }
+    Some((column, start..end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leading_indent() {
+        assert_eq!(leading_indent("    x", 4), Some(4));
+        assert_eq!(leading_indent("\t  x", 4), Some(6));
+        assert_eq!(leading_indent("  \t x", 4), Some(5));
+        assert_eq!(leading_indent("   \n", 4), None);
+        assert_eq!(leading_indent("", 4), None);
+    }
+
+    #[test]
+    fn test_blank_lines_take_surrounding_indent() {
+        let indents = [Some(4), None, Some(8), None, Some(0)];
+        assert_eq!(guide_indents(&indents, None, None), vec![4, 4, 8, 0, 0]);
+        assert_eq!(guide_indents(&[None, Some(4)], Some(8), None), vec![4, 4]);
+    }
+
+    #[test]
+    fn test_active_guide() {
+        // fn f() {
+        //     if x {
+        //         y
+        //     }
+        // }
+        let indents = [0, 4, 8, 4, 0];
+        // On a line opening a block: that block's guide
+        assert_eq!(active_guide(&indents, 0, 4), Some((0, 1..4)));
+        assert_eq!(active_guide(&indents, 1, 4), Some((4, 2..3)));
+        // Inside a block: the enclosing guide
+        assert_eq!(active_guide(&indents, 2, 4), Some((4, 2..3)));
+        assert_eq!(active_guide(&indents, 3, 4), Some((0, 1..4)));
+        assert_eq!(active_guide(&indents, 4, 4), None);
+        assert_eq!(guide_columns(9, 4).collect::<Vec<_>>(), vec![0, 4, 8]);
+    }
+}
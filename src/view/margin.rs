@@ -189,6 +189,33 @@ impl MarginConfig {
             0
         }
     }
+
+    /// Column of the fold marker within the margin, if fold markers can be shown
+    ///
+    /// Markers replace the space before the separator line, so the margin keeps its width.
+    pub fn fold_marker_column(&self) -> Option<usize> {
+        (self.enabled && self.show_separator && self.separator.starts_with(' '))
+            .then_some(1 + self.width)
+    }
+}
+
+/// Fold marker shown on a fold's header line, in the separator's leading space
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldMarker {
+    /// The fold is collapsed; its lines are hidden
+    Collapsed,
+    /// The line starts a range that can be folded
+    Expanded,
+}
+
+impl FoldMarker {
+    /// The symbol to display
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Collapsed => "▸",
+            Self::Expanded => "▾",
+        }
+    }
 }
 
 /// A margin annotation for a specific line
@@ -2,15 +2,22 @@
 //!
 //! This module contains all presentation and rendering components.
 
+pub mod bracket_pairs;
 pub mod calibration_wizard;
+pub mod code_lens;
 pub mod color_support;
 pub mod composite_view;
 pub mod controls;
 pub mod dimming;
+pub mod document_symbols;
 pub mod file_browser_input;
 pub mod file_tree;
+pub mod folding;
+pub mod hex_view;
+pub mod indent_guides;
 pub mod margin;
 pub mod markdown;
+pub mod outline;
 pub mod overlay;
 pub mod popup;
 pub mod popup_input;
@@ -19,8 +26,10 @@ pub mod prompt_input;
 pub mod query_replace_input;
 pub mod reference_highlight_overlay;
 pub mod scroll_sync;
+pub mod scrollbar_marks;
 pub mod settings;
 pub mod split;
+pub mod sticky_scroll;
 pub mod stream;
 pub mod theme;
 pub mod ui;
@@ -0,0 +1,231 @@
+//! Outline panel
+//!
+//! A side panel listing the symbols of the active buffer as a collapsible
+//! tree (see `view::document_symbols`). While the panel is not focused its
+//! selection follows the cursor; while it is, typing filters the symbols
+//! fuzzily and Enter jumps to the selected one.
+
+use crate::input::fuzzy::fuzzy_match;
+use crate::view::document_symbols::OutlineSymbol;
+use lsp_types::SymbolKind;
+use std::collections::HashSet;
+
+/// A row of the outline panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineRow {
+    pub depth: usize,
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Byte offset to jump to
+    pub position: usize,
+    /// Indices of the symbol and its parents, outermost first
+    pub path: Vec<usize>,
+    pub has_children: bool,
+    pub expanded: bool,
+}
+
+/// State of the outline panel
+#[derive(Debug, Default)]
+pub struct OutlinePanel {
+    /// Fuzzy filter typed while the panel is focused
+    pub filter: String,
+    /// Collapsed symbols, by the names of the symbol and its parents
+    collapsed: HashSet<Vec<String>>,
+    /// Selected row
+    pub selected: usize,
+    /// First visible row
+    pub scroll_offset: usize,
+}
+
+impl OutlinePanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rows for `symbols`
+    ///
+    /// Without a filter, the children of collapsed symbols are hidden. With
+    /// one, every symbol matching it is shown along with its parents.
+    pub fn rows(&self, symbols: &[OutlineSymbol]) -> Vec<OutlineRow> {
+        let mut rows = Vec::new();
+        let mut names = Vec::new();
+        let mut path = Vec::new();
+        self.push_rows(symbols, &mut names, &mut path, &mut rows);
+        rows
+    }
+
+    fn push_rows(
+        &self,
+        symbols: &[OutlineSymbol],
+        names: &mut Vec<String>,
+        path: &mut Vec<usize>,
+        rows: &mut Vec<OutlineRow>,
+    ) {
+        for (index, symbol) in symbols.iter().enumerate() {
+            if !self.filter.is_empty() && !self.matches(symbol) {
+                continue;
+            }
+            names.push(symbol.name.clone());
+            path.push(index);
+            let expanded = !self.filter.is_empty() || !self.collapsed.contains(names);
+            rows.push(OutlineRow {
+                depth: path.len() - 1,
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+                position: symbol.position,
+                path: path.clone(),
+                has_children: !symbol.children.is_empty(),
+                expanded,
+            });
+            if expanded {
+                self.push_rows(&symbol.children, names, path, rows);
+            }
+            names.pop();
+            path.pop();
+        }
+    }
+
+    /// Whether the symbol or one of its descendants matches the filter
+    fn matches(&self, symbol: &OutlineSymbol) -> bool {
+        fuzzy_match(&self.filter, &symbol.name).matched
+            || symbol.children.iter().any(|child| self.matches(child))
+    }
+
+    fn names(symbols: &[OutlineSymbol], path: &[usize]) -> Vec<String> {
+        let mut names = Vec::with_capacity(path.len());
+        let mut level = symbols;
+        for index in path {
+            let Some(symbol) = level.get(*index) else {
+                break;
+            };
+            names.push(symbol.name.clone());
+            level = &symbol.children;
+        }
+        names
+    }
+
+    /// Collapse or expand the symbol of a row
+    pub fn set_expanded(&mut self, symbols: &[OutlineSymbol], row: &OutlineRow, expanded: bool) {
+        let names = Self::names(symbols, &row.path);
+        if expanded {
+            self.collapsed.remove(&names);
+        } else {
+            self.collapsed.insert(names);
+        }
+    }
+
+    /// Select the row of the innermost symbol on `cursor_path` that has one
+    pub fn follow_cursor(&mut self, rows: &[OutlineRow], cursor_path: &[usize]) {
+        if let Some(index) = (1..=cursor_path.len())
+            .rev()
+            .find_map(|len| rows.iter().position(|row| row.path == cursor_path[..len]))
+        {
+            self.selected = index;
+        }
+    }
+
+    /// Set the filter, selecting the first row matching it
+    pub fn set_filter(&mut self, symbols: &[OutlineSymbol], filter: String) {
+        self.filter = filter;
+        let rows = self.rows(symbols);
+        self.selected = rows
+            .iter()
+            .position(|row| fuzzy_match(&self.filter, &row.name).matched)
+            .unwrap_or(0);
+        self.scroll_offset = 0;
+    }
+
+    /// Keep the selection inside `row_count` rows and scroll it into a view
+    /// of `height` rows
+    pub fn clamp(&mut self, row_count: usize, height: usize) {
+        self.selected = self.selected.min(row_count.saturating_sub(1));
+        if self.selected < self.scroll_offset {
+            self.scroll_offset = self.selected;
+        } else if height > 0 && self.selected >= self.scroll_offset + height {
+            self.scroll_offset = self.selected + 1 - height;
+        }
+        self.scroll_offset = self
+            .scroll_offset
+            .min(row_count.saturating_sub(height.max(1)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(
+        name: &str,
+        range: std::ops::Range<usize>,
+        children: Vec<OutlineSymbol>,
+    ) -> OutlineSymbol {
+        OutlineSymbol {
+            name: name.to_string(),
+            kind: SymbolKind::FUNCTION,
+            position: range.start,
+            range,
+            children,
+        }
+    }
+
+    fn symbols() -> Vec<OutlineSymbol> {
+        vec![
+            symbol(
+                "Parser",
+                0..50,
+                vec![
+                    symbol("parse_expr", 10..20, vec![]),
+                    symbol("peek", 30..40, vec![]),
+                ],
+            ),
+            symbol("main", 60..70, vec![]),
+        ]
+    }
+
+    fn names(rows: &[OutlineRow]) -> Vec<&str> {
+        rows.iter().map(|row| row.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_collapse_hides_children() {
+        let symbols = symbols();
+        let mut panel = OutlinePanel::new();
+        let rows = panel.rows(&symbols);
+        assert_eq!(names(&rows), vec!["Parser", "parse_expr", "peek", "main"]);
+        assert_eq!(rows[1].depth, 1);
+
+        panel.set_expanded(&symbols, &rows[0], false);
+        let rows = panel.rows(&symbols);
+        assert_eq!(names(&rows), vec!["Parser", "main"]);
+        assert!(!rows[0].expanded);
+    }
+
+    #[test]
+    fn test_filter_keeps_parents() {
+        let symbols = symbols();
+        let mut panel = OutlinePanel::new();
+        panel.set_filter(&symbols, "pe".to_string());
+        let rows = panel.rows(&symbols);
+        assert_eq!(names(&rows), vec!["Parser", "parse_expr", "peek"]);
+        // The parent matches too, so it is selected first
+        assert_eq!(panel.selected, 0);
+
+        panel.set_filter(&symbols, "pk".to_string());
+        assert_eq!(names(&panel.rows(&symbols)), vec!["Parser", "peek"]);
+        assert_eq!(panel.selected, 1);
+    }
+
+    #[test]
+    fn test_follow_cursor_selects_innermost_visible_row() {
+        let symbols = symbols();
+        let mut panel = OutlinePanel::new();
+        let rows = panel.rows(&symbols);
+        panel.follow_cursor(&rows, &[0, 1]);
+        assert_eq!(panel.selected, 2);
+
+        panel.set_expanded(&symbols, &rows[0], false);
+        let rows = panel.rows(&symbols);
+        panel.follow_cursor(&rows, &[0, 1]);
+        assert_eq!(panel.selected, 0);
+    }
+}
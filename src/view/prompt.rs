@@ -27,6 +27,14 @@ pub enum PromptType {
     QueryReplace { search: String },
     /// Query replace confirmation prompt (y/n/!/q for each match)
     QueryReplaceConfirm,
+    /// Search for text in all project files
+    ProjectSearch,
+    /// Search project files (for replace - will prompt for replacement after)
+    ProjectReplaceSearch,
+    /// Replacement text for the project search matches
+    ProjectReplace,
+    /// Include/exclude globs for the project search
+    ProjectSearchFiles,
     /// Execute a command by name (M-x)
     Command,
     /// Go to a specific line number
@@ -58,8 +66,16 @@ pub enum PromptType {
     SetComposeWidth,
     /// Set tab size for current buffer
     SetTabSize,
+    /// Fold all regions at a nesting level
+    FoldLevel,
     /// Set line ending format for current buffer
     SetLineEnding,
+    /// Reload the current buffer from disk with a chosen encoding (select from list)
+    ReopenWithEncoding,
+    /// Save the current buffer with a chosen encoding (select from list)
+    SaveWithEncoding,
+    /// Search for a hex byte pattern (e.g. "DE AD BE EF")
+    HexSearch,
     /// Stop a running LSP server (select from list)
     StopLspServer,
     /// Select a theme (select from list)
@@ -106,6 +122,21 @@ pub enum PromptType {
     },
     /// Switch to a tab by name (from the current split's open buffers)
     SwitchToTab,
+    /// Pick a local history snapshot of a buffer's file to compare against
+    FileHistory {
+        buffer_id: crate::model::event::BufferId,
+    },
+    /// Pick a user snippet to insert
+    InsertSnippet,
+    /// Pick a symbol of the active buffer to go to
+    GotoSymbol,
+    /// Search the symbols of the workspace through the language servers
+    WorkspaceSymbol,
+    /// Pick one of the locations a definition, type definition, implementation
+    /// or declaration request answered with
+    GotoLocation,
+    /// Pick a code lens of the current buffer to run its command
+    CodeLens,
     /// Run shell command on buffer/selection
     /// If replace is true, replace the input with the output
     /// If replace is false, output goes to a new buffer
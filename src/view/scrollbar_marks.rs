@@ -0,0 +1,208 @@
+//! Scrollbar marks
+//!
+//! The scrollbar track doubles as an overview ruler: diagnostics, search
+//! matches, cursors, bookmarks, changes and plugin-supplied marks are drawn on
+//! the track rows covering their position in the buffer, and clicking one
+//! jumps to it.
+
+use std::ops::Range;
+
+use ratatui::style::Color;
+
+use crate::model::marker::{MarkerId, MarkerList};
+
+/// Symbol drawn on a track row holding a mark
+pub const MARK_SYMBOL: &str = "━";
+
+/// Priorities of the built-in marks; when several marks share a track row the
+/// highest one is drawn
+pub mod priority {
+    pub const CHANGE: i32 = 10;
+    pub const SEARCH_MATCH: i32 = 20;
+    pub const BOOKMARK: i32 = 30;
+    pub const DIAGNOSTIC_HINT: i32 = 40;
+    pub const DIAGNOSTIC_INFO: i32 = 50;
+    pub const DIAGNOSTIC_WARNING: i32 = 60;
+    pub const DIAGNOSTIC_ERROR: i32 = 70;
+    pub const CURSOR: i32 = 80;
+}
+
+/// A byte range of the buffer to show on the scrollbar track
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollbarMark {
+    /// Bytes covered by the mark (empty for a single position)
+    pub range: Range<usize>,
+    pub color: Color,
+    pub priority: i32,
+}
+
+impl ScrollbarMark {
+    pub fn new(range: Range<usize>, color: Color, priority: i32) -> Self {
+        Self {
+            range,
+            color,
+            priority,
+        }
+    }
+
+    /// A mark at a single position
+    pub fn at(position: usize, color: Color, priority: i32) -> Self {
+        Self::new(position..position, color, priority)
+    }
+}
+
+/// The mark drawn on each of `height` track rows, if any
+///
+/// `row_of` maps a byte position to its track row. A mark covers every row
+/// between its start and end; the highest priority mark wins a row.
+pub fn track_rows(
+    marks: &[ScrollbarMark],
+    height: usize,
+    row_of: impl Fn(usize) -> usize,
+) -> Vec<Option<&ScrollbarMark>> {
+    let mut rows: Vec<Option<&ScrollbarMark>> = vec![None; height];
+    if height == 0 {
+        return rows;
+    }
+    for mark in marks {
+        let first = row_of(mark.range.start).min(height - 1);
+        let last = row_of(mark.range.end.max(mark.range.start))
+            .min(height - 1)
+            .max(first);
+        for row in &mut rows[first..=last] {
+            if row.is_none_or(|current| mark.priority > current.priority) {
+                *row = Some(mark);
+            }
+        }
+    }
+    rows
+}
+
+/// A mark added by a plugin, anchored to the buffer with markers
+#[derive(Debug, Clone)]
+struct PluginMark {
+    namespace: String,
+    start: MarkerId,
+    end: MarkerId,
+    color: Color,
+    priority: i32,
+}
+
+/// Scrollbar marks supplied by plugins, grouped by namespace
+#[derive(Debug, Clone, Default)]
+pub struct PluginScrollbarMarks {
+    marks: Vec<PluginMark>,
+}
+
+impl PluginScrollbarMarks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a mark over `range`; it moves with edits to the buffer
+    pub fn add(
+        &mut self,
+        marker_list: &mut MarkerList,
+        namespace: String,
+        range: Range<usize>,
+        color: Color,
+        priority: i32,
+    ) {
+        let start = marker_list.create(range.start, true);
+        let end = marker_list.create(range.end.max(range.start), false);
+        self.marks.push(PluginMark {
+            namespace,
+            start,
+            end,
+            color,
+            priority,
+        });
+    }
+
+    /// Remove all marks in a namespace
+    pub fn clear_namespace(&mut self, marker_list: &mut MarkerList, namespace: &str) {
+        self.marks.retain(|mark| {
+            if mark.namespace == namespace {
+                marker_list.delete(mark.start);
+                marker_list.delete(mark.end);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Current marks, with positions resolved from their markers
+    pub fn marks(&self, marker_list: &MarkerList) -> Vec<ScrollbarMark> {
+        self.marks
+            .iter()
+            .filter_map(|mark| {
+                let start = marker_list.get_position(mark.start)?;
+                let end = marker_list.get_position(mark.end).unwrap_or(start);
+                Some(ScrollbarMark::new(start..end, mark.color, mark.priority))
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_rows_priority_and_ranges() {
+        let marks = vec![
+            ScrollbarMark::new(0..30, Color::Green, priority::CHANGE),
+            ScrollbarMark::at(15, Color::Red, priority::DIAGNOSTIC_ERROR),
+            ScrollbarMark::at(95, Color::Yellow, priority::SEARCH_MATCH),
+        ];
+        // 100 bytes over 10 rows
+        let rows = track_rows(&marks, 10, |position| position / 10);
+        let colors: Vec<Option<Color>> = rows.iter().map(|row| row.map(|m| m.color)).collect();
+        assert_eq!(
+            colors,
+            vec![
+                Some(Color::Green),
+                Some(Color::Red),
+                Some(Color::Green),
+                Some(Color::Green),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(Color::Yellow),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_track_rows_clamps_to_track() {
+        let marks = vec![ScrollbarMark::at(500, Color::Red, priority::CURSOR)];
+        let rows = track_rows(&marks, 4, |position| position / 10);
+        assert!(rows[3].is_some());
+        assert!(track_rows(&marks, 0, |position| position).is_empty());
+    }
+
+    #[test]
+    fn test_plugin_marks_follow_edits_and_clear_by_namespace() {
+        let mut marker_list = MarkerList::new();
+        let mut marks = PluginScrollbarMarks::new();
+        marks.add(&mut marker_list, "a".into(), 10..20, Color::Blue, 5);
+        marks.add(&mut marker_list, "b".into(), 30..30, Color::Cyan, 5);
+
+        marker_list.adjust_for_insert(0, 5);
+        let resolved = marks.marks(&marker_list);
+        assert_eq!(resolved[0].range, 15..25);
+        assert_eq!(resolved[1].range, 35..35);
+
+        marks.clear_namespace(&mut marker_list, "a");
+        let resolved = marks.marks(&marker_list);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].color, Color::Cyan);
+    }
+}
@@ -575,6 +575,7 @@ fn render_setting_item_pure(
     let layer_label = match layer_source {
         crate::config_io::ConfigLayer::System => None, // Don't show for defaults
         crate::config_io::ConfigLayer::User => Some("user"),
+        crate::config_io::ConfigLayer::EditorConfig => Some("editorconfig"),
         crate::config_io::ConfigLayer::Project => Some("project"),
         crate::config_io::ConfigLayer::Session => Some("session"),
     };
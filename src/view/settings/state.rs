@@ -385,8 +385,8 @@ impl SettingsState {
 
     /// Set the target layer for saving changes.
     pub fn set_target_layer(&mut self, layer: ConfigLayer) {
-        if layer != ConfigLayer::System {
-            // Cannot target System layer (read-only)
+        if !matches!(layer, ConfigLayer::System | ConfigLayer::EditorConfig) {
+            // Cannot target read-only layers
             self.target_layer = layer;
             // Clear pending changes when switching layers
             self.pending_changes.clear();
@@ -396,7 +396,8 @@ impl SettingsState {
     /// Cycle through writable layers: User -> Project -> Session -> User
     pub fn cycle_target_layer(&mut self) {
         self.target_layer = match self.target_layer {
-            ConfigLayer::System => ConfigLayer::User, // Should never be System, but handle it
+            // Should never be a read-only layer, but handle it
+            ConfigLayer::System | ConfigLayer::EditorConfig => ConfigLayer::User,
             ConfigLayer::User => ConfigLayer::Project,
             ConfigLayer::Project => ConfigLayer::Session,
             ConfigLayer::Session => ConfigLayer::User,
@@ -409,6 +410,7 @@ impl SettingsState {
     pub fn target_layer_name(&self) -> &'static str {
         match self.target_layer {
             ConfigLayer::System => "System (read-only)",
+            ConfigLayer::EditorConfig => "EditorConfig (read-only)",
             ConfigLayer::User => "User",
             ConfigLayer::Project => "Project",
             ConfigLayer::Session => "Session",
@@ -434,6 +436,7 @@ impl SettingsState {
         match layer {
             ConfigLayer::System => "default",
             ConfigLayer::User => "user",
+            ConfigLayer::EditorConfig => "editorconfig",
             ConfigLayer::Project => "project",
             ConfigLayer::Session => "session",
         }
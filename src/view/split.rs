@@ -25,6 +25,7 @@
 /// ```
 use crate::model::cursor::Cursors;
 use crate::model::event::{BufferId, SplitDirection, SplitId};
+use crate::view::hex_view::HexViewState;
 use crate::view::ui::view_pipeline::Layout;
 use crate::view::viewport::Viewport;
 use crate::{services::plugins::api::ViewTransformPayload, state::ViewMode};
@@ -115,6 +116,10 @@ pub struct SplitViewState {
     /// the composite layout. This makes the source buffer the "active buffer"
     /// so normal keybindings work directly.
     pub composite_view: Option<BufferId>,
+
+    /// When set, this split shows its buffer as a hex dump for byte-level editing
+    /// (see [`crate::view::hex_view`]). Only applies while the split shows that buffer.
+    pub hex_view: Option<HexViewState>,
 }
 
 impl SplitViewState {
@@ -135,6 +140,7 @@ impl SplitViewState {
             previous_buffer: None,
             sync_group: None,
             composite_view: None,
+            hex_view: None,
         }
     }
 
@@ -155,6 +161,7 @@ impl SplitViewState {
             previous_buffer: None,
             sync_group: None,
             composite_view: None,
+            hex_view: None,
         }
     }
 
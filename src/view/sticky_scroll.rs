@@ -0,0 +1,64 @@
+//! Sticky scroll
+//!
+//! While the top of a split is inside a function, class or nested block, the
+//! lines opening those scopes are drawn over the first rows of the split.
+//! Scopes are the ranges folding computes from the syntax tree, or from
+//! indentation for languages without a grammar (see `view::folding`).
+//!
+//! The pinned rows cover buffer lines rather than shrinking the view, so the
+//! viewport scrolls exactly as it does without them.
+
+use crate::view::folding::FoldRange;
+
+/// Header lines to pin over a view whose first line is `top_line`, outermost
+/// scope first
+///
+/// A scope stays pinned while the line shown below its row is still inside it.
+/// `ranges` must be normalized (see `folding::normalize_ranges`).
+pub fn sticky_lines(ranges: &[FoldRange], top_line: usize, max_lines: usize) -> Vec<usize> {
+    let mut lines: Vec<usize> = Vec::new();
+    while lines.len() < max_lines {
+        // First line left uncovered by the pinned rows
+        let visible = top_line + lines.len();
+        let next = ranges.iter().find(|range| {
+            lines.last().is_none_or(|last| range.start_line > *last)
+                && range.start_line < visible
+                && range.end_line > visible
+        });
+        match next {
+            Some(range) => lines.push(range.start_line),
+            None => break,
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::folding::normalize_ranges;
+
+    #[test]
+    fn test_sticky_lines_nested_scopes() {
+        // impl at 0..20, fn at 2..10, block at 4..8
+        let ranges = normalize_ranges(vec![
+            FoldRange::new(0, 20),
+            FoldRange::new(2, 10),
+            FoldRange::new(4, 8),
+        ]);
+        assert_eq!(sticky_lines(&ranges, 0, 5), Vec::<usize>::new());
+        assert_eq!(sticky_lines(&ranges, 1, 5), vec![0]);
+        assert_eq!(sticky_lines(&ranges, 5, 5), vec![0, 2, 4]);
+        assert_eq!(sticky_lines(&ranges, 5, 2), vec![0, 2]);
+        // The block ends before the line below its row
+        assert_eq!(sticky_lines(&ranges, 6, 5), vec![0, 2]);
+        assert_eq!(sticky_lines(&ranges, 12, 5), vec![0]);
+    }
+
+    #[test]
+    fn test_sticky_lines_scope_starting_under_pinned_rows() {
+        // The fn header at line 1 is covered by the row pinning line 0
+        let ranges = normalize_ranges(vec![FoldRange::new(0, 20), FoldRange::new(1, 10)]);
+        assert_eq!(sticky_lines(&ranges, 1, 5), vec![0, 1]);
+    }
+}
@@ -202,6 +202,30 @@ pub struct EditorColors {
     /// Line number gutter background
     #[serde(default = "default_line_number_bg")]
     pub line_number_bg: ColorDef,
+    /// Background of ruler columns
+    #[serde(default = "default_ruler_bg")]
+    pub ruler_bg: ColorDef,
+    /// Background of the scope headers pinned by sticky scroll
+    #[serde(default = "default_sticky_scroll_bg")]
+    pub sticky_scroll_bg: ColorDef,
+    /// Indent guides in leading whitespace
+    #[serde(default = "default_indent_guide_fg")]
+    pub indent_guide_fg: ColorDef,
+    /// Indent guide of the scope containing the cursor
+    #[serde(default = "default_indent_guide_active_fg")]
+    pub indent_guide_active_fg: ColorDef,
+    /// Brackets at nesting depths 1, 4, 7, ...
+    #[serde(default = "default_bracket_pair_1_fg")]
+    pub bracket_pair_1_fg: ColorDef,
+    /// Brackets at nesting depths 2, 5, 8, ...
+    #[serde(default = "default_bracket_pair_2_fg")]
+    pub bracket_pair_2_fg: ColorDef,
+    /// Brackets at nesting depths 3, 6, 9, ...
+    #[serde(default = "default_bracket_pair_3_fg")]
+    pub bracket_pair_3_fg: ColorDef,
+    /// Placeholder shown after the header line of a collapsed fold
+    #[serde(default = "default_fold_placeholder_fg")]
+    pub fold_placeholder_fg: ColorDef,
     /// Diff added line background
     #[serde(default = "default_diff_add_bg")]
     pub diff_add_bg: ColorDef,
@@ -238,6 +262,30 @@ fn default_line_number_fg() -> ColorDef {
 fn default_line_number_bg() -> ColorDef {
     ColorDef::Rgb(30, 30, 30)
 }
+fn default_ruler_bg() -> ColorDef {
+    ColorDef::Rgb(50, 50, 50)
+}
+fn default_sticky_scroll_bg() -> ColorDef {
+    ColorDef::Rgb(37, 37, 42)
+}
+fn default_indent_guide_fg() -> ColorDef {
+    ColorDef::Rgb(60, 60, 60)
+}
+fn default_indent_guide_active_fg() -> ColorDef {
+    ColorDef::Rgb(120, 120, 120)
+}
+fn default_bracket_pair_1_fg() -> ColorDef {
+    ColorDef::Rgb(255, 215, 0) // Gold
+}
+fn default_bracket_pair_2_fg() -> ColorDef {
+    ColorDef::Rgb(218, 112, 214) // Orchid
+}
+fn default_bracket_pair_3_fg() -> ColorDef {
+    ColorDef::Rgb(23, 159, 255) // Blue
+}
+fn default_fold_placeholder_fg() -> ColorDef {
+    ColorDef::Rgb(128, 128, 128)
+}
 fn default_diff_add_bg() -> ColorDef {
     ColorDef::Rgb(35, 60, 35) // Dark green
 }
@@ -389,6 +437,12 @@ pub struct UiColors {
     /// Scrollbar thumb hover color
     #[serde(default = "default_scrollbar_thumb_hover_fg")]
     pub scrollbar_thumb_hover_fg: ColorDef,
+    /// Scrollbar marks for unsaved changes
+    #[serde(default = "default_scrollbar_change_fg")]
+    pub scrollbar_change_fg: ColorDef,
+    /// Scrollbar marks for bookmarks
+    #[serde(default = "default_scrollbar_bookmark_fg")]
+    pub scrollbar_bookmark_fg: ColorDef,
     /// Compose mode margin background
     #[serde(default = "default_compose_margin_bg")]
     pub compose_margin_bg: ColorDef,
@@ -587,6 +641,12 @@ fn default_scrollbar_track_hover_fg() -> ColorDef {
 fn default_scrollbar_thumb_hover_fg() -> ColorDef {
     ColorDef::Named("White".to_string())
 }
+fn default_scrollbar_change_fg() -> ColorDef {
+    ColorDef::Rgb(230, 160, 60)
+}
+fn default_scrollbar_bookmark_fg() -> ColorDef {
+    ColorDef::Rgb(190, 130, 230)
+}
 fn default_compose_margin_bg() -> ColorDef {
     ColorDef::Rgb(18, 18, 18) // Darker than editor_bg for "desk" effect
 }
@@ -774,6 +834,14 @@ pub struct Theme {
     pub current_line_bg: Color,
     pub line_number_fg: Color,
     pub line_number_bg: Color,
+    pub ruler_bg: Color,
+    pub sticky_scroll_bg: Color,
+    pub indent_guide_fg: Color,
+    pub indent_guide_active_fg: Color,
+    pub bracket_pair_1_fg: Color,
+    pub bracket_pair_2_fg: Color,
+    pub bracket_pair_3_fg: Color,
+    pub fold_placeholder_fg: Color,
 
     // Diff highlighting colors
     pub diff_add_bg: Color,
@@ -843,6 +911,8 @@ pub struct Theme {
     pub scrollbar_thumb_fg: Color,
     pub scrollbar_track_hover_fg: Color,
     pub scrollbar_thumb_hover_fg: Color,
+    pub scrollbar_change_fg: Color,
+    pub scrollbar_bookmark_fg: Color,
 
     // Compose mode colors
     pub compose_margin_bg: Color,
@@ -905,6 +975,14 @@ impl From<ThemeFile> for Theme {
             current_line_bg: file.editor.current_line_bg.into(),
             line_number_fg: file.editor.line_number_fg.into(),
             line_number_bg: file.editor.line_number_bg.into(),
+            ruler_bg: file.editor.ruler_bg.into(),
+            sticky_scroll_bg: file.editor.sticky_scroll_bg.into(),
+            indent_guide_fg: file.editor.indent_guide_fg.into(),
+            indent_guide_active_fg: file.editor.indent_guide_active_fg.into(),
+            bracket_pair_1_fg: file.editor.bracket_pair_1_fg.into(),
+            bracket_pair_2_fg: file.editor.bracket_pair_2_fg.into(),
+            bracket_pair_3_fg: file.editor.bracket_pair_3_fg.into(),
+            fold_placeholder_fg: file.editor.fold_placeholder_fg.into(),
             diff_add_bg: file.editor.diff_add_bg.clone().into(),
             diff_remove_bg: file.editor.diff_remove_bg.clone().into(),
             diff_modify_bg: file.editor.diff_modify_bg.into(),
@@ -957,6 +1035,8 @@ impl From<ThemeFile> for Theme {
             scrollbar_thumb_fg: file.ui.scrollbar_thumb_fg.into(),
             scrollbar_track_hover_fg: file.ui.scrollbar_track_hover_fg.into(),
             scrollbar_thumb_hover_fg: file.ui.scrollbar_thumb_hover_fg.into(),
+            scrollbar_change_fg: file.ui.scrollbar_change_fg.into(),
+            scrollbar_bookmark_fg: file.ui.scrollbar_bookmark_fg.into(),
             compose_margin_bg: file.ui.compose_margin_bg.into(),
             semantic_highlight_bg: file.ui.semantic_highlight_bg.into(),
             terminal_bg: file.ui.terminal_bg.into(),
@@ -1006,6 +1086,14 @@ impl From<Theme> for ThemeFile {
                 current_line_bg: theme.current_line_bg.into(),
                 line_number_fg: theme.line_number_fg.into(),
                 line_number_bg: theme.line_number_bg.into(),
+                ruler_bg: theme.ruler_bg.into(),
+                sticky_scroll_bg: theme.sticky_scroll_bg.into(),
+                indent_guide_fg: theme.indent_guide_fg.into(),
+                indent_guide_active_fg: theme.indent_guide_active_fg.into(),
+                bracket_pair_1_fg: theme.bracket_pair_1_fg.into(),
+                bracket_pair_2_fg: theme.bracket_pair_2_fg.into(),
+                bracket_pair_3_fg: theme.bracket_pair_3_fg.into(),
+                fold_placeholder_fg: theme.fold_placeholder_fg.into(),
                 diff_add_bg: theme.diff_add_bg.into(),
                 diff_remove_bg: theme.diff_remove_bg.into(),
                 diff_modify_bg: theme.diff_modify_bg.into(),
@@ -1057,6 +1145,8 @@ impl From<Theme> for ThemeFile {
                 scrollbar_thumb_fg: theme.scrollbar_thumb_fg.into(),
                 scrollbar_track_hover_fg: theme.scrollbar_track_hover_fg.into(),
                 scrollbar_thumb_hover_fg: theme.scrollbar_thumb_hover_fg.into(),
+                scrollbar_change_fg: theme.scrollbar_change_fg.into(),
+                scrollbar_bookmark_fg: theme.scrollbar_bookmark_fg.into(),
                 compose_margin_bg: theme.compose_margin_bg.into(),
                 semantic_highlight_bg: theme.semantic_highlight_bg.into(),
                 terminal_bg: theme.terminal_bg.into(),
@@ -0,0 +1,125 @@
+//! Hex view rendering
+//!
+//! Renders a buffer as a hex dump for splits in hex view. Only the bytes of
+//! the visible rows are read, so large files are paged in chunk by chunk as
+//! the view scrolls.
+
+use crate::state::EditorState;
+use crate::view::hex_view::{self, HexViewState, BYTES_PER_ROW};
+use crate::view::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Clear, Paragraph};
+use ratatui::Frame;
+
+/// Renders the hex view of a buffer
+pub struct HexViewRenderer;
+
+impl HexViewRenderer {
+    /// Render `state` as hex into `area`, scrolling `view` to follow `cursor` when it moves.
+    ///
+    /// `cursor` is the byte offset of the split's primary cursor. The terminal
+    /// cursor is placed on the focused nibble (or ASCII character) when `show_cursor`.
+    pub fn render(
+        frame: &mut Frame,
+        area: Rect,
+        state: &mut EditorState,
+        view: &mut HexViewState,
+        cursor: usize,
+        theme: &Theme,
+        show_cursor: bool,
+    ) {
+        frame.render_widget(Clear, area);
+
+        let visible_rows = area.height as usize;
+        let len = state.buffer.len();
+        let cursor = cursor.min(len);
+        view.top_row = view.top_row.min(hex_view::row_count(len) - 1);
+        view.follow_cursor(cursor, visible_rows);
+
+        let start = view.top_row * BYTES_PER_ROW;
+        let bytes = match state
+            .buffer
+            .get_text_range_mut(start, visible_rows * BYTES_PER_ROW)
+        {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                tracing::warn!("Failed to read bytes for hex view: {}", e);
+                Vec::new()
+            }
+        };
+
+        let digits = hex_view::offset_digits(len);
+        let hex_start = digits + 2;
+        let ascii_start = hex_start + hex_view::hex_columns_width();
+
+        let text_style = Style::default().fg(theme.editor_fg).bg(theme.editor_bg);
+        let dim_style = Style::default()
+            .fg(theme.line_number_fg)
+            .bg(theme.editor_bg);
+        let cursor_style = Style::default().fg(theme.editor_fg).bg(theme.selection_bg);
+
+        let mut lines = Vec::with_capacity(visible_rows);
+        for (row, chunk) in (view.top_row..hex_view::row_count(len))
+            .take(visible_rows)
+            .zip(
+                bytes
+                    .chunks(BYTES_PER_ROW)
+                    .chain(std::iter::repeat(&[][..])),
+            )
+        {
+            let row_start = row * BYTES_PER_ROW;
+            let cursor_index = (cursor / BYTES_PER_ROW == row).then_some(cursor % BYTES_PER_ROW);
+
+            let mut spans = vec![Span::styled(
+                format!("{:0digits$X}  ", row_start),
+                dim_style,
+            )];
+
+            for index in 0..BYTES_PER_ROW {
+                let style = if cursor_index == Some(index) {
+                    cursor_style
+                } else {
+                    text_style
+                };
+                let text = chunk
+                    .get(index)
+                    .map(|byte| format!("{:02X}", byte))
+                    .unwrap_or_else(|| "  ".to_string());
+                spans.push(Span::styled(text, style));
+                let gap = hex_view::hex_column(index + 1) - hex_view::hex_column(index) - 2;
+                spans.push(Span::styled(" ".repeat(gap), text_style));
+            }
+
+            for (index, &byte) in chunk.iter().enumerate() {
+                let style = if cursor_index == Some(index) {
+                    cursor_style
+                } else if hex_view::ascii_char(byte) == '.' && byte != b'.' {
+                    dim_style
+                } else {
+                    text_style
+                };
+                spans.push(Span::styled(hex_view::ascii_char(byte).to_string(), style));
+            }
+
+            lines.push(Line::from(spans));
+        }
+
+        frame.render_widget(Paragraph::new(lines).style(text_style), area);
+
+        let cursor_row = cursor / BYTES_PER_ROW;
+        if show_cursor && cursor_row >= view.top_row {
+            let row = cursor_row - view.top_row;
+            let index = cursor % BYTES_PER_ROW;
+            let column = if view.ascii_focus {
+                ascii_start + index
+            } else {
+                hex_start + hex_view::hex_column(index) + usize::from(view.low_nibble)
+            };
+            if column < area.width as usize && row < visible_rows {
+                frame.set_cursor_position((area.x + column as u16, area.y + row as u16));
+            }
+        }
+    }
+}
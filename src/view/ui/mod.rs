@@ -11,11 +11,14 @@
 //! - `scrollbar` - Reusable scrollbar widget
 //! - `scroll_panel` - Reusable scrollable panel for variable-height items
 //! - `file_browser` - File open dialog popup
+//! - `hex_view` - Hex dump rendering for splits in hex view
 
 pub mod file_browser;
 pub mod file_explorer;
+pub mod hex_view;
 pub mod menu;
 pub mod menu_input;
+pub mod outline;
 pub mod scroll_panel;
 pub mod scrollbar;
 pub mod split_rendering;
@@ -28,8 +31,10 @@ pub mod view_pipeline;
 // Re-export main types for convenience
 pub use file_browser::{FileBrowserLayout, FileBrowserRenderer};
 pub use file_explorer::FileExplorerRenderer;
+pub use hex_view::HexViewRenderer;
 pub use menu::{context_keys, MenuContext, MenuRenderer, MenuState};
 pub use menu_input::MenuInputHandler;
+pub use outline::OutlineRenderer;
 pub use scroll_panel::{
     FocusRegion, RenderInfo, ScrollItem, ScrollState, ScrollablePanel, ScrollablePanelLayout,
 };
@@ -0,0 +1,159 @@
+use crate::primitives::display_width::str_width;
+use crate::view::document_symbols::kind_label;
+use crate::view::outline::{OutlinePanel, OutlineRow};
+use crate::view::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use rust_i18n::t;
+
+pub struct OutlineRenderer;
+
+impl OutlineRenderer {
+    /// Render the outline panel in the given frame area
+    pub fn render(
+        panel: &mut OutlinePanel,
+        rows: &[OutlineRow],
+        frame: &mut Frame,
+        area: Rect,
+        is_focused: bool,
+        theme: &Theme,
+    ) {
+        // Account for borders (top + bottom = 2)
+        let viewport_height = area.height.saturating_sub(2) as usize;
+        panel.clamp(rows.len(), viewport_height);
+        let scroll_offset = panel.scroll_offset;
+        let visible_end = (scroll_offset + viewport_height).min(rows.len());
+
+        // Available width for content (subtract borders and cursor indicator)
+        let content_width = area.width.saturating_sub(3) as usize;
+
+        let items: Vec<ListItem> = rows[scroll_offset..visible_end]
+            .iter()
+            .map(|row| Self::render_row(row, theme, content_width))
+            .collect();
+
+        let title = if panel.filter.is_empty() {
+            format!(" {} ", t!("outline.title"))
+        } else {
+            format!(" {}: {} ", t!("outline.title"), panel.filter)
+        };
+
+        // Same styling as the file explorer
+        let (title_style, border_style) = if is_focused {
+            (
+                Style::default()
+                    .fg(theme.editor_bg)
+                    .bg(theme.editor_fg)
+                    .add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.cursor),
+            )
+        } else {
+            (
+                Style::default().fg(theme.line_number_fg),
+                Style::default().fg(theme.split_separator_fg),
+            )
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_style(title_style)
+            .border_style(border_style)
+            .style(Style::default().bg(theme.editor_bg));
+
+        if rows.is_empty() {
+            let message = if panel.filter.is_empty() {
+                t!("outline.empty")
+            } else {
+                t!("outline.no_matches")
+            };
+            let paragraph = Paragraph::new(message.to_string())
+                .style(Style::default().fg(theme.line_number_fg))
+                .block(block);
+            frame.render_widget(paragraph, area);
+            return;
+        }
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(if is_focused {
+                Style::default().bg(theme.selection_bg).fg(theme.editor_fg)
+            } else {
+                Style::default().bg(theme.current_line_bg)
+            });
+
+        // Only the visible rows are passed, so the selection is relative to the viewport
+        let mut list_state = ListState::default();
+        if panel.selected >= scroll_offset && panel.selected < visible_end {
+            list_state.select(Some(panel.selected - scroll_offset));
+        }
+        frame.render_stateful_widget(list, area, &mut list_state);
+
+        // When focused, show a cursor indicator at the selected row
+        if is_focused && panel.selected >= scroll_offset && panel.selected < visible_end {
+            let cursor_x = area.x + 1;
+            let cursor_y = area.y + 1 + (panel.selected - scroll_offset) as u16;
+            let cursor_indicator = Paragraph::new("▌").style(Style::default().fg(theme.cursor));
+            frame.render_widget(cursor_indicator, Rect::new(cursor_x, cursor_y, 1, 1));
+            frame.set_cursor_position((cursor_x, cursor_y));
+        }
+    }
+
+    /// Column of the expand/collapse indicator of a row, relative to the panel
+    pub fn indicator_column(row: &OutlineRow) -> u16 {
+        // Left border and cursor indicator, then two columns per level
+        2 + (row.depth * 2) as u16
+    }
+
+    /// Render a single symbol as a ListItem
+    fn render_row(row: &OutlineRow, theme: &Theme, content_width: usize) -> ListItem<'static> {
+        let mut spans = Vec::new();
+        spans.push(Span::raw(" "));
+        if row.depth > 0 {
+            spans.push(Span::raw("  ".repeat(row.depth)));
+        }
+
+        let indicator = if !row.has_children {
+            "  "
+        } else if row.expanded {
+            "▼ "
+        } else {
+            "> "
+        };
+        spans.push(Span::styled(
+            indicator,
+            Style::default().fg(theme.line_number_fg),
+        ));
+
+        let label = kind_label(row.kind);
+        spans.push(Span::styled(
+            format!("{} ", label),
+            Style::default().fg(theme.line_number_fg),
+        ));
+
+        let used = 1 + row.depth * 2 + 2 + str_width(label) + 1;
+        let name: String = if used + str_width(&row.name) > content_width {
+            let mut name = String::new();
+            let mut width = used;
+            for c in row.name.chars() {
+                let w = str_width(c.encode_utf8(&mut [0; 4]));
+                if width + w + 1 > content_width {
+                    name.push('…');
+                    break;
+                }
+                width += w;
+                name.push(c);
+            }
+            name
+        } else {
+            row.name.clone()
+        };
+        spans.push(Span::styled(name, Style::default().fg(theme.editor_fg)));
+
+        ListItem::new(Line::from(spans))
+    }
+}
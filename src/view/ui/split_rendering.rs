@@ -9,10 +9,16 @@ use crate::model::cursor::SelectionMode;
 use crate::model::event::{BufferId, EventLog, SplitDirection};
 use crate::primitives::ansi::AnsiParser;
 use crate::primitives::ansi_background::AnsiBackground;
-use crate::primitives::display_width::char_width;
+use crate::primitives::display_width::{char_width, str_width};
 use crate::services::plugins::api::ViewTransformPayload;
 use crate::state::{EditorState, ViewMode};
+use crate::view::folding::FoldedRegion;
+use crate::view::indent_guides;
+use crate::view::margin::FoldMarker;
+use crate::view::scrollbar_marks::{self, priority as scrollbar_priority, ScrollbarMark};
 use crate::view::split::SplitManager;
+use crate::view::sticky_scroll;
+use crate::view::ui::hex_view::HexViewRenderer;
 use crate::view::ui::tabs::TabsRenderer;
 use crate::view::ui::view_pipeline::{
     should_show_line_number, LineStart, ViewLine, ViewLineIterator,
@@ -33,6 +39,20 @@ use std::ops::Range;
 /// memory usage reasonable (~80KB per ViewLine instead of hundreds of MB).
 const MAX_SAFE_LINE_WIDTH: usize = 10_000;
 
+/// Text shown after the header line of a collapsed fold
+const FOLD_PLACEHOLDER: &str = " ⋯";
+
+/// Largest buffer for which fold ranges are computed while rendering, to show the
+/// fold marker on the cursor line and the sticky scroll headers (fold commands
+/// compute them for any size)
+const FOLD_RANGES_MAX_BYTES: usize = 512 * 1024;
+
+/// Largest buffer whose brackets are colored by nesting depth
+const BRACKET_PAIRS_MAX_BYTES: usize = 512 * 1024;
+
+/// Separator between the crumbs of a breadcrumb bar
+const BREADCRUMB_SEPARATOR: &str = " › ";
+
 /// Compute character-level diff between two strings, returning ranges of changed characters.
 /// Returns a tuple of (old_changed_ranges, new_changed_ranges) where each range indicates
 /// character indices that differ between the strings.
@@ -326,6 +346,12 @@ struct DecorationContext {
     diagnostic_lines: HashSet<usize>,
     /// Line indicators indexed by line number (highest priority indicator per line)
     line_indicators: BTreeMap<usize, crate::view::margin::LineIndicator>,
+    /// Collapsed folds by header line (header line -> last hidden line)
+    folded_lines: BTreeMap<usize, usize>,
+    /// Cursor line, when it starts a foldable range
+    foldable_cursor_line: Option<usize>,
+    /// Colors of the matched brackets in the viewport by byte position
+    bracket_colors: HashMap<usize, Color>,
 }
 
 struct LineRenderOutput {
@@ -344,6 +370,7 @@ struct LastLineEnd {
 
 struct SplitLayout {
     tabs_rect: Rect,
+    breadcrumbs_rect: Rect,
     content_rect: Rect,
     scrollbar_rect: Rect,
 }
@@ -387,6 +414,7 @@ struct CharStyleContext<'a> {
     theme: &'a crate::view::theme::Theme,
     highlight_spans: &'a [crate::primitives::highlighter::HighlightSpan],
     semantic_token_spans: &'a [crate::primitives::highlighter::HighlightSpan],
+    bracket_colors: &'a HashMap<usize, Color>,
     viewport_overlays: &'a [(crate::view::overlay::Overlay, Range<usize>)],
     primary_cursor_position: usize,
     is_active: bool,
@@ -412,6 +440,8 @@ struct LeftMarginContext<'a> {
     cursor_line: usize,
     /// Whether to show relative line numbers
     relative_line_numbers: bool,
+    /// Fold marker for this line
+    fold_marker: Option<FoldMarker>,
 }
 
 /// Render the left margin (indicators + line numbers + separator) to line_spans
@@ -509,19 +539,37 @@ fn render_left_margin(
         push_span_with_map(line_spans, line_view_map, rendered_text, margin_style, None);
     }
 
-    // Render separator
+    // Render separator, with the fold marker in its leading space
     if ctx.state.margins.left_config.show_separator {
         let separator_style = Style::default().fg(ctx.theme.line_number_fg);
-        push_span_with_map(
-            line_spans,
-            line_view_map,
-            ctx.state.margins.left_config.separator.clone(),
-            separator_style,
-            None,
-        );
+        let separator = &ctx.state.margins.left_config.separator;
+        let fold_marker = ctx
+            .fold_marker
+            .filter(|_| ctx.state.margins.left_config.fold_marker_column().is_some());
+        let separator = match fold_marker {
+            Some(marker) => {
+                push_span_with_map(
+                    line_spans,
+                    line_view_map,
+                    marker.symbol().to_string(),
+                    separator_style,
+                    None,
+                );
+                separator[1..].to_string()
+            }
+            None => separator.clone(),
+        };
+        push_span_with_map(line_spans, line_view_map, separator, separator_style, None);
     }
 }
 
+/// The source line shown after `line`, skipping the lines of a collapsed fold
+fn next_source_line(folded_lines: &BTreeMap<usize, usize>, line: usize) -> usize {
+    folded_lines
+        .get(&line)
+        .map_or(line + 1, |end_line| end_line + 1)
+}
+
 /// Compute the style for a character by layering: token -> ANSI -> syntax -> semantic -> overlays -> selection -> cursor
 fn compute_char_style(ctx: &CharStyleContext) -> CharStyleOutput {
     use crate::view::overlay::OverlayFace;
@@ -600,7 +648,8 @@ fn compute_char_style(ctx: &CharStyleContext) -> CharStyleOutput {
     // Note: Reference highlighting (word under cursor) is now handled via overlays
     // in the "Apply overlay styles" section below
 
-    // Apply LSP semantic token foreground color when no custom token style is set.
+    // Apply LSP semantic token and bracket pair foreground colors when no custom
+    // token style is set.
     if ctx.token_style.is_none() {
         if let Some(bp) = ctx.byte_pos {
             if let Some(token_span) = ctx
@@ -610,6 +659,9 @@ fn compute_char_style(ctx: &CharStyleContext) -> CharStyleOutput {
             {
                 style = style.fg(token_span.color);
             }
+            if let Some(color) = ctx.bracket_colors.get(&bp) {
+                style = style.fg(*color);
+            }
         }
     }
 
@@ -715,7 +767,10 @@ impl SplitRenderer {
         hovered_maximize_split: Option<crate::model::event::SplitId>,
         is_maximized: bool,
         relative_line_numbers: bool,
+        sticky_scroll_lines: usize,
+        scrollbar_marks: &HashMap<BufferId, Vec<ScrollbarMark>>,
         tab_bar_visible: bool,
+        breadcrumbs: &HashMap<crate::model::event::SplitId, Vec<String>>,
     ) -> (
         Vec<(
             crate::model::event::SplitId,
@@ -729,6 +784,7 @@ impl SplitRenderer {
         Vec<(crate::model::event::SplitId, u16, u16, u16)>, // close split button areas
         Vec<(crate::model::event::SplitId, u16, u16, u16)>, // maximize split button areas
         HashMap<crate::model::event::SplitId, Vec<ViewLineMapping>>, // view line mappings for mouse clicks
+        HashMap<crate::model::event::SplitId, Vec<Option<usize>>>, // scrollbar mark position per track row
     ) {
         let _span = tracing::trace_span!("render_content").entered();
 
@@ -744,12 +800,19 @@ impl SplitRenderer {
         let mut maximize_split_areas = Vec::new();
         let mut view_line_mappings: HashMap<crate::model::event::SplitId, Vec<ViewLineMapping>> =
             HashMap::new();
+        let mut scrollbar_mark_rows: HashMap<crate::model::event::SplitId, Vec<Option<usize>>> =
+            HashMap::new();
 
         // Render each split
         for (split_id, buffer_id, split_area) in visible_buffers {
             let is_active = split_id == active_split_id;
 
-            let layout = Self::split_layout(split_area, tab_bar_visible);
+            let split_breadcrumbs = breadcrumbs.get(&split_id);
+            let layout =
+                Self::split_layout(split_area, tab_bar_visible, split_breadcrumbs.is_some());
+            if let Some(crumbs) = split_breadcrumbs {
+                Self::render_breadcrumbs(frame, layout.breadcrumbs_rect, crumbs, theme, is_active);
+            }
             let (split_buffers, tab_scroll_offset) =
                 Self::split_buffers_for_tabs(split_view_states.as_deref(), split_id, buffer_id);
 
@@ -897,6 +960,50 @@ impl SplitRenderer {
                     continue;
                 }
 
+                // Check if this split shows the buffer in hex view
+                if let Some(split_vs) = split_view_states
+                    .as_deref_mut()
+                    .and_then(|svs| svs.get_mut(&split_id))
+                {
+                    if let Some(hex_view) = split_vs
+                        .hex_view
+                        .as_mut()
+                        .filter(|hex| hex.buffer_id == buffer_id)
+                    {
+                        split_vs
+                            .viewport
+                            .resize(layout.content_rect.width, layout.content_rect.height);
+
+                        // The active split's cursors live in the buffer state
+                        let cursor = if is_active {
+                            state.cursors.primary().position
+                        } else {
+                            split_vs.cursors.primary().position
+                        };
+                        HexViewRenderer::render(
+                            frame,
+                            layout.content_rect,
+                            state,
+                            hex_view,
+                            cursor,
+                            theme,
+                            is_active && state.show_cursors && !hide_cursor,
+                        );
+
+                        Self::render_composite_scrollbar(
+                            frame,
+                            layout.scrollbar_rect,
+                            crate::view::hex_view::row_count(state.buffer.len()),
+                            hex_view.top_row,
+                            layout.content_rect.height as usize,
+                            is_active,
+                        );
+
+                        view_line_mappings.insert(split_id, Vec::new());
+                        continue;
+                    }
+                }
+
                 // Get viewport from SplitViewState (authoritative source)
                 // We need to get it mutably for sync operations
                 // Use as_deref() to get Option<&HashMap> for read-only operations
@@ -949,6 +1056,7 @@ impl SplitRenderer {
                     buffer_id,
                     hide_cursor,
                     relative_line_numbers,
+                    sticky_scroll_lines,
                 );
 
                 // Store view line mappings for mouse click handling
@@ -977,6 +1085,28 @@ impl SplitRenderer {
                     top_line,
                 );
 
+                // Mark diagnostics, search matches, cursors etc. on the track
+                let marks = scrollbar_marks
+                    .get(&buffer_id)
+                    .map_or(&[][..], Vec::as_slice);
+                let cursor_marks: Vec<ScrollbarMark> = state
+                    .cursors
+                    .iter()
+                    .map(|(_, cursor)| {
+                        ScrollbarMark::at(cursor.position, theme.cursor, scrollbar_priority::CURSOR)
+                    })
+                    .collect();
+                let mark_rows = Self::render_scrollbar_marks(
+                    frame,
+                    state,
+                    layout.scrollbar_rect,
+                    marks,
+                    &cursor_marks,
+                    large_file_threshold_bytes,
+                    total_lines,
+                );
+                scrollbar_mark_rows.insert(split_id, mark_rows);
+
                 // Restore the original cursors after rendering content and scrollbar
                 Self::restore_split_state(state, saved_cursors);
 
@@ -1019,6 +1149,7 @@ impl SplitRenderer {
             close_split_areas,
             maximize_split_areas,
             view_line_mappings,
+            scrollbar_mark_rows,
         )
     }
 
@@ -1209,6 +1340,8 @@ impl SplitRenderer {
                         false,        // line_wrap_enabled
                         content_width,
                         gutter_width,
+                        &[], // Panes are aligned line by line, so folds don't apply
+                        theme.fold_placeholder_fg,
                     );
 
                     // Build source_line -> ViewLine index mapping
@@ -1683,31 +1816,90 @@ impl SplitRenderer {
         }
     }
 
-    fn split_layout(split_area: Rect, tab_bar_visible: bool) -> SplitLayout {
+    fn split_layout(
+        split_area: Rect,
+        tab_bar_visible: bool,
+        breadcrumbs_visible: bool,
+    ) -> SplitLayout {
         let tabs_height = if tab_bar_visible { 1u16 } else { 0u16 };
+        let breadcrumbs_height = if breadcrumbs_visible { 1u16 } else { 0u16 };
+        let header_height = (tabs_height + breadcrumbs_height).min(split_area.height);
         let scrollbar_width = 1u16;
 
         let tabs_rect = Rect::new(split_area.x, split_area.y, split_area.width, tabs_height);
-        let content_rect = Rect::new(
+        let breadcrumbs_rect = Rect::new(
             split_area.x,
             split_area.y + tabs_height,
+            split_area.width,
+            header_height.saturating_sub(tabs_height),
+        );
+        let content_rect = Rect::new(
+            split_area.x,
+            split_area.y + header_height,
             split_area.width.saturating_sub(scrollbar_width),
-            split_area.height.saturating_sub(tabs_height),
+            split_area.height.saturating_sub(header_height),
         );
         let scrollbar_rect = Rect::new(
             split_area.x + split_area.width.saturating_sub(scrollbar_width),
-            split_area.y + tabs_height,
+            split_area.y + header_height,
             scrollbar_width,
-            split_area.height.saturating_sub(tabs_height),
+            split_area.height.saturating_sub(header_height),
         );
 
         SplitLayout {
             tabs_rect,
+            breadcrumbs_rect,
             content_rect,
             scrollbar_rect,
         }
     }
 
+    /// Columns of each crumb of a breadcrumb bar, as (start, end) ranges,
+    /// clipped to the bar
+    pub fn breadcrumb_columns(area: Rect, crumbs: &[String]) -> Vec<(u16, u16)> {
+        let right = area.x + area.width;
+        let mut x = area.x + 1;
+        let mut columns = Vec::with_capacity(crumbs.len());
+        for crumb in crumbs {
+            if x >= right {
+                break;
+            }
+            let end = (x + str_width(crumb) as u16).min(right);
+            columns.push((x, end));
+            x = end + str_width(BREADCRUMB_SEPARATOR) as u16;
+        }
+        columns
+    }
+
+    /// Render the breadcrumb bar of a split
+    fn render_breadcrumbs(
+        frame: &mut Frame,
+        area: Rect,
+        crumbs: &[String],
+        theme: &crate::view::theme::Theme,
+        is_active: bool,
+    ) {
+        if area.height == 0 {
+            return;
+        }
+        let separator_style = Style::default().fg(theme.line_number_fg);
+        let mut spans = vec![Span::raw(" ")];
+        for (i, crumb) in crumbs.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled(BREADCRUMB_SEPARATOR, separator_style));
+            }
+            let style = if is_active && i == crumbs.len() - 1 {
+                Style::default().fg(theme.editor_fg)
+            } else {
+                separator_style
+            };
+            spans.push(Span::styled(crumb.clone(), style));
+        }
+        let paragraph =
+            Paragraph::new(Line::from(spans)).style(Style::default().bg(theme.editor_bg));
+        frame.render_widget(paragraph, area);
+    }
+
     fn split_buffers_for_tabs(
         split_view_states: Option<
             &HashMap<crate::model::event::SplitId, crate::view::split::SplitViewState>,
@@ -1944,6 +2136,55 @@ impl SplitRenderer {
         (thumb_start, thumb_end)
     }
 
+    /// Draw marks and cursors over the scrollbar track of a split
+    /// Returns the position of the mark to jump to on each track row, for
+    /// mouse clicks. Only single-position marks are jumped to: cursors, and
+    /// spans that can cover the whole track, leave clicks to the track.
+    fn render_scrollbar_marks(
+        frame: &mut Frame,
+        state: &EditorState,
+        scrollbar_rect: Rect,
+        marks: &[ScrollbarMark],
+        cursor_marks: &[ScrollbarMark],
+        large_file_threshold_bytes: u64,
+        total_lines: usize,
+    ) -> Vec<Option<usize>> {
+        let height = scrollbar_rect.height as usize;
+        let buffer_len = state.buffer.len();
+        let large_file = buffer_len > large_file_threshold_bytes as usize;
+        let row_of = |position| {
+            if large_file {
+                // No exact line count: place marks by byte offset, like the thumb
+                position * height / buffer_len.max(1)
+            } else {
+                state.buffer.get_line_number(position) * height / total_lines.max(1)
+            }
+        };
+        let all_marks: Vec<ScrollbarMark> = marks.iter().chain(cursor_marks).cloned().collect();
+        let rows = scrollbar_marks::track_rows(&all_marks, height, row_of);
+
+        let buf = frame.buffer_mut();
+        for (row, mark) in rows.iter().enumerate() {
+            let Some(mark) = mark else {
+                continue;
+            };
+            if let Some(cell) = buf.cell_mut((scrollbar_rect.x, scrollbar_rect.y + row as u16)) {
+                cell.set_symbol(scrollbar_marks::MARK_SYMBOL);
+                cell.set_fg(mark.color);
+            }
+        }
+
+        let point_marks: Vec<ScrollbarMark> = marks
+            .iter()
+            .filter(|mark| mark.range.is_empty())
+            .cloned()
+            .collect();
+        scrollbar_marks::track_rows(&point_marks, height, row_of)
+            .into_iter()
+            .map(|mark| mark.map(|mark| mark.range.start))
+            .collect()
+    }
+
     fn build_view_data(
         state: &mut EditorState,
         viewport: &crate::view::viewport::Viewport,
@@ -1953,6 +2194,8 @@ impl SplitRenderer {
         line_wrap_enabled: bool,
         content_width: usize,
         gutter_width: usize,
+        folded_regions: &[FoldedRegion],
+        fold_placeholder_fg: Color,
     ) -> ViewData {
         // Check if buffer is binary before building tokens
         let is_binary = state.buffer.is_binary();
@@ -1966,6 +2209,8 @@ impl SplitRenderer {
             visible_count,
             is_binary,
             line_ending,
+            folded_regions,
+            fold_placeholder_fg,
         );
 
         // Use plugin transform if available, otherwise use base tokens
@@ -2106,6 +2351,7 @@ impl SplitRenderer {
         result
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn build_base_tokens(
         buffer: &mut Buffer,
         top_byte: usize,
@@ -2113,9 +2359,24 @@ impl SplitRenderer {
         visible_count: usize,
         is_binary: bool,
         line_ending: crate::model::buffer::LineEnding,
+        folded_regions: &[FoldedRegion],
+        fold_placeholder_fg: Color,
     ) -> Vec<crate::services::plugins::api::ViewTokenWire> {
         use crate::model::buffer::LineEnding;
-        use crate::services::plugins::api::{ViewTokenWire, ViewTokenWireKind};
+        use crate::services::plugins::api::{ViewTokenStyle, ViewTokenWire, ViewTokenWireKind};
+
+        // Shown at the end of a collapsed fold's header line
+        let fold_placeholder_fg = crate::view::theme::color_to_rgb(fold_placeholder_fg);
+        let fold_placeholder = || ViewTokenWire {
+            source_offset: None,
+            kind: ViewTokenWireKind::Text(FOLD_PLACEHOLDER.to_string()),
+            style: Some(ViewTokenStyle {
+                fg: fold_placeholder_fg,
+                bg: None,
+                bold: false,
+                italic: false,
+            }),
+        };
 
         let mut tokens = Vec::new();
 
@@ -2136,6 +2397,10 @@ impl SplitRenderer {
 
         while lines_seen < max_lines {
             if let Some((line_start, line_content)) = iter.next() {
+                let folded = folded_regions
+                    .binary_search_by_key(&line_start, |region| region.header_start)
+                    .ok()
+                    .map(|idx| &folded_regions[idx]);
                 let mut byte_offset = 0usize;
                 let content_bytes = line_content.as_bytes();
                 let mut skip_next_lf = false; // Track if we should skip \n after \r in CRLF
@@ -2169,6 +2434,9 @@ impl SplitRenderer {
                             let is_crlf_file = line_ending == LineEnding::CRLF;
                             let next_byte = content_bytes.get(byte_offset + 1);
                             if is_crlf_file && next_byte == Some(&b'\n') {
+                                if folded.is_some() {
+                                    tokens.push(fold_placeholder());
+                                }
                                 // CRLF: emit Newline token at \r position for cursor visibility
                                 tokens.push(ViewTokenWire {
                                     source_offset,
@@ -2194,6 +2462,9 @@ impl SplitRenderer {
                             continue;
                         }
                         '\n' => {
+                            if folded.is_some() {
+                                tokens.push(fold_placeholder());
+                            }
                             tokens.push(ViewTokenWire {
                                 source_offset,
                                 kind: ViewTokenWireKind::Newline,
@@ -2246,6 +2517,10 @@ impl SplitRenderer {
                     byte_offset += ch_len;
                 }
                 lines_seen += 1;
+                // Continue after the lines hidden by a collapsed fold
+                if let Some(region) = folded {
+                    iter = buffer.line_iterator(region.hidden.end, estimated_line_length);
+                }
             } else {
                 break;
             }
@@ -2442,6 +2717,8 @@ impl SplitRenderer {
             visible_count,
             is_binary,
             line_ending,
+            &[],
+            Color::Reset, // No folds, so no placeholder to color
         )
     }
 
@@ -2887,6 +3164,57 @@ impl SplitRenderer {
             |byte_offset| state.buffer.get_line_number(byte_offset),
         );
 
+        let folded_lines = state
+            .folds
+            .folded_regions(&state.buffer)
+            .into_iter()
+            .map(|region| (region.header_line, region.end_line))
+            .collect();
+
+        // Offer folding on the cursor line when it starts a foldable range
+        let foldable_cursor_line = if state.margins.left_config.fold_marker_column().is_some()
+            && state.buffer.len() <= FOLD_RANGES_MAX_BYTES
+        {
+            let cursor_line = state.buffer.get_line_number(primary_cursor_position);
+            let language = state.highlighter.language().copied();
+            state
+                .folds
+                .ranges(
+                    &state.buffer,
+                    &mut state.syntax_tree,
+                    language.as_ref(),
+                    state.tab_size,
+                )
+                .binary_search_by_key(&cursor_line, |range| range.start_line)
+                .is_ok()
+                .then_some(cursor_line)
+        } else {
+            None
+        };
+
+        let bracket_colors =
+            if state.bracket_pair_colorization && state.buffer.len() <= BRACKET_PAIRS_MAX_BYTES {
+                let colors = [
+                    theme.bracket_pair_1_fg,
+                    theme.bracket_pair_2_fg,
+                    theme.bracket_pair_3_fg,
+                ];
+                let language = state.highlighter.language().copied();
+                let brackets = state.bracket_pairs.brackets(
+                    &state.buffer,
+                    &mut state.syntax_tree,
+                    language.as_ref(),
+                );
+                let first = brackets.partition_point(|bracket| bracket.position < viewport_start);
+                brackets[first..]
+                    .iter()
+                    .take_while(|bracket| bracket.position < viewport_end)
+                    .map(|bracket| (bracket.position, colors[bracket.depth % colors.len()]))
+                    .collect()
+            } else {
+                HashMap::new()
+            };
+
         DecorationContext {
             highlight_spans,
             semantic_token_spans,
@@ -2894,6 +3222,9 @@ impl SplitRenderer {
             virtual_text_lookup,
             diagnostic_lines,
             line_indicators,
+            folded_lines,
+            foldable_cursor_line,
+            bracket_colors,
         }
     }
 
@@ -2946,6 +3277,7 @@ impl SplitRenderer {
         viewport_start: usize,
         estimated_line_length: usize,
         visible_count: usize,
+        folded_regions: &[FoldedRegion],
     ) -> usize {
         let mut iter_temp = state
             .buffer
@@ -2954,6 +3286,13 @@ impl SplitRenderer {
         for _ in 0..visible_count {
             if let Some((line_start, line_content)) = iter_temp.next() {
                 viewport_end = line_start + line_content.len();
+                if let Ok(idx) =
+                    folded_regions.binary_search_by_key(&line_start, |region| region.header_start)
+                {
+                    iter_temp = state
+                        .buffer
+                        .line_iterator(folded_regions[idx].hidden.end, estimated_line_length);
+                }
             } else {
                 break;
             }
@@ -2991,10 +3330,12 @@ impl SplitRenderer {
 
         let highlight_spans = &decorations.highlight_spans;
         let semantic_token_spans = &decorations.semantic_token_spans;
+        let bracket_colors = &decorations.bracket_colors;
         let viewport_overlays = &decorations.viewport_overlays;
         let virtual_text_lookup = &decorations.virtual_text_lookup;
         let diagnostic_lines = &decorations.diagnostic_lines;
         let line_indicators = &decorations.line_indicators;
+        let folded_lines = &decorations.folded_lines;
 
         let mut lines = Vec::new();
         let mut view_line_mappings = Vec::new();
@@ -3068,7 +3409,7 @@ impl SplitRenderer {
             // 2. The CURRENT line is also a source line
             // This ensures virtual/injected lines don't cause line numbers to skip
             if show_line_number && prev_was_source_line {
-                current_source_line_num += 1;
+                current_source_line_num = next_source_line(folded_lines, current_source_line_num);
             }
             // Only update the flag when we see a source line - virtual lines
             // between source lines shouldn't reset the tracking
@@ -3094,6 +3435,16 @@ impl SplitRenderer {
             // This is critical for proper rendering of combining characters (Thai, etc.)
             let mut span_acc = SpanAccumulator::new();
 
+            let fold_marker = if is_continuation {
+                None
+            } else if folded_lines.contains_key(&current_source_line_num) {
+                Some(FoldMarker::Collapsed)
+            } else if decorations.foldable_cursor_line == Some(current_source_line_num) {
+                Some(FoldMarker::Expanded)
+            } else {
+                None
+            };
+
             // Render left margin (indicators + line numbers + separator)
             render_left_margin(
                 &LeftMarginContext {
@@ -3106,6 +3457,7 @@ impl SplitRenderer {
                     line_indicators,
                     cursor_line,
                     relative_line_numbers,
+                    fold_marker,
                 },
                 &mut line_spans,
                 &mut line_view_map,
@@ -3274,6 +3626,7 @@ impl SplitRenderer {
                         theme,
                         highlight_spans,
                         semantic_token_spans,
+                        bracket_colors,
                         viewport_overlays,
                         primary_cursor_position,
                         is_active,
@@ -3835,6 +4188,7 @@ impl SplitRenderer {
         _buffer_id: BufferId,
         hide_cursor: bool,
         relative_line_numbers: bool,
+        sticky_scroll_lines: usize,
     ) -> Vec<ViewLineMapping> {
         let _span = tracing::trace_span!("render_buffer_in_split").entered();
 
@@ -3857,6 +4211,23 @@ impl SplitRenderer {
 
         // Clone view_transform so we can reuse it if scrolling triggers a rebuild
         let view_transform_for_rebuild = view_transform.clone();
+        // Plugin view transforms replace the buffer text, so headers taken from
+        // buffer lines would not match what is shown
+        let sticky_scroll_lines = if view_transform.is_some() {
+            0
+        } else {
+            sticky_scroll_lines.min(visible_count / 2)
+        };
+
+        // Keep the top of the view out of the lines hidden by collapsed folds
+        let folded_regions = state.folds.folded_regions(&state.buffer);
+        if let Some(region) = folded_regions
+            .iter()
+            .find(|region| region.hidden.contains(&viewport.top_byte))
+        {
+            viewport.top_byte = region.header_start;
+            viewport.top_view_line_offset = 0;
+        }
 
         let view_data = Self::build_view_data(
             state,
@@ -3867,6 +4238,8 @@ impl SplitRenderer {
             line_wrap,
             render_area.width as usize,
             gutter_width,
+            &folded_regions,
+            theme.fold_placeholder_fg,
         );
 
         // Ensure cursor is visible using Layout-aware check (handles virtual lines)
@@ -3886,6 +4259,8 @@ impl SplitRenderer {
                 line_wrap,
                 render_area.width as usize,
                 gutter_width,
+                &folded_regions,
+                theme.fold_placeholder_fg,
             )
         } else {
             view_data
@@ -3927,11 +4302,20 @@ impl SplitRenderer {
             viewport_start,
             estimated_line_length,
             visible_count,
+            &folded_regions,
         );
 
+        // Scope headers pinned over the top rows, outermost first. They are
+        // decorated along with the viewport so they share its highlight cache.
+        let mut sticky_lines = Self::sticky_scroll_lines(state, viewport, sticky_scroll_lines);
+        let decoration_start = sticky_lines
+            .first()
+            .and_then(|&line| state.buffer.line_start_offset(line))
+            .map_or(viewport_start, |start| start.min(viewport_start));
+
         let decorations = Self::decoration_context(
             state,
-            viewport_start,
+            decoration_start,
             viewport_end,
             selection.primary_cursor_position,
             theme,
@@ -3960,12 +4344,12 @@ impl SplitRenderer {
                 // Count how many source lines were in the skipped portion
                 // A view line is a "source line" if it shows a line number (not a continuation)
                 let skipped_lines = &view_data.lines[..calculated_offset];
-                let skipped_source_lines = skipped_lines
+                let adjusted_line_num = skipped_lines
                     .iter()
                     .filter(|vl| should_show_line_number(vl))
-                    .count();
-
-                let adjusted_line_num = starting_line_num + skipped_source_lines;
+                    .fold(starting_line_num, |line, _| {
+                        next_source_line(&decorations.folded_lines, line)
+                    });
 
                 // Recalculate view_anchor on the sliced array
                 let adjusted_anchor = Self::calculate_view_anchor(sliced, viewport.top_byte);
@@ -4040,6 +4424,62 @@ impl SplitRenderer {
             }
         }
 
+        // Render rulers by tinting the background of their column
+        let content_x = render_area.x as usize + gutter_width;
+        let area_right = (render_area.x + render_area.width) as usize;
+        for &column in &state.rulers {
+            let Some(ruler_x) = column
+                .checked_sub(viewport.left_column)
+                .map(|offset| content_x + offset)
+                .filter(|x| *x < area_right)
+            else {
+                continue;
+            };
+            let buf = frame.buffer_mut();
+            for y in render_area.y..render_area.y + render_area.height {
+                if let Some(cell) = buf.cell_mut((ruler_x as u16, y)) {
+                    // Leave selections and other highlights visible
+                    if cell.bg == theme.editor_bg {
+                        cell.set_bg(theme.ruler_bg);
+                    }
+                }
+            }
+        }
+
+        // Render indent guides in the blank cells of the leading whitespace
+        if state.indent_guides {
+            let guides = Self::indent_guide_columns(
+                state,
+                &render_output.view_line_mappings,
+                line_wrap,
+                selection.primary_cursor_position,
+                estimated_line_length,
+            );
+            let buf = frame.buffer_mut();
+            for (row, columns) in guides.iter().enumerate().take(render_area.height as usize) {
+                let y = render_area.y + row as u16;
+                for &(column, is_active) in columns {
+                    let Some(guide_x) = column
+                        .checked_sub(viewport.left_column)
+                        .map(|offset| content_x + offset)
+                        .filter(|x| *x < area_right)
+                    else {
+                        continue;
+                    };
+                    if let Some(cell) = buf.cell_mut((guide_x as u16, y)) {
+                        if cell.symbol() == " " {
+                            cell.set_symbol(indent_guides::GUIDE_SYMBOL);
+                            cell.set_fg(if is_active {
+                                theme.indent_guide_active_fg
+                            } else {
+                                theme.indent_guide_fg
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
         let buffer_ends_with_newline = if state.buffer.len() > 0 {
             let last_char = state.get_text_range(state.buffer.len() - 1, state.buffer.len());
             last_char == "\n"
@@ -4057,6 +4497,78 @@ impl SplitRenderer {
             gutter_width,
         );
 
+        // Never cover the row of a visible cursor
+        if let Some((_, cursor_row)) = render_output.cursor {
+            sticky_lines.truncate(cursor_row as usize);
+        }
+        let mut view_line_mappings = render_output.view_line_mappings;
+        for (row, &line) in sticky_lines.iter().enumerate() {
+            let Some(line_start) = state.buffer.line_start_offset(line) else {
+                break;
+            };
+            let mut header_viewport = viewport.clone();
+            header_viewport.top_byte = line_start;
+            header_viewport.top_view_line_offset = 0;
+            let header_data = Self::build_view_data(
+                state,
+                &header_viewport,
+                None,
+                estimated_line_length,
+                1,
+                line_wrap,
+                render_area.width as usize,
+                gutter_width,
+                &folded_regions,
+                theme.fold_placeholder_fg,
+            );
+            let row_area = Rect::new(
+                render_area.x,
+                render_area.y + row as u16,
+                render_area.width,
+                1,
+            );
+            let header_output = Self::render_view_lines(LineRenderInput {
+                state,
+                theme,
+                view_lines: &header_data.lines,
+                view_anchor: Self::calculate_view_anchor(&header_data.lines, line_start),
+                render_area: row_area,
+                gutter_width,
+                selection: &selection,
+                decorations: &decorations,
+                starting_line_num: line,
+                visible_line_count: 1,
+                lsp_waiting,
+                is_active,
+                line_wrap,
+                estimated_lines,
+                left_column: viewport.left_column,
+                relative_line_numbers,
+            });
+
+            frame.render_widget(Clear, row_area);
+            frame.render_widget(
+                Paragraph::new(header_output.lines).style(Style::default().bg(theme.editor_bg)),
+                row_area,
+            );
+            let buf = frame.buffer_mut();
+            for x in row_area.x..row_area.x + row_area.width {
+                if let Some(cell) = buf.cell_mut((x, row_area.y)) {
+                    if cell.bg == theme.editor_bg {
+                        cell.set_bg(theme.sticky_scroll_bg);
+                    }
+                }
+            }
+
+            // Clicking a header moves the cursor to its line
+            if let (Some(slot), Some(mapping)) = (
+                view_line_mappings.get_mut(row),
+                header_output.view_line_mappings.into_iter().next(),
+            ) {
+                *slot = mapping;
+            }
+        }
+
         if is_active && state.show_cursors && !hide_cursor {
             if let Some((cursor_screen_x, cursor_screen_y)) = cursor {
                 // cursor_screen_x already includes gutter width from line_view_map
@@ -4082,7 +4594,131 @@ impl SplitRenderer {
 
         // Extract view line mappings for mouse click handling
         // This maps screen coordinates to buffer byte positions
-        render_output.view_line_mappings
+        view_line_mappings
+    }
+
+    /// Indent guides of each rendered row: their columns and whether each is
+    /// the guide of the block containing the cursor
+    ///
+    /// Wrapped continuation rows and rows without source text get no guides.
+    fn indent_guide_columns(
+        state: &mut EditorState,
+        view_line_mappings: &[ViewLineMapping],
+        line_wrap: bool,
+        primary_cursor_position: usize,
+        estimated_line_length: usize,
+    ) -> Vec<Vec<(usize, bool)>> {
+        // How far to look past the view for the indentation of blank lines at its edges
+        const BLANK_SCAN_LINES: usize = 100;
+        let tab_size = state.tab_size;
+
+        // Start and indentation of the lines shown, and the line each row starts
+        let mut lines: Vec<(usize, Option<usize>)> = Vec::new();
+        let mut row_lines = Vec::with_capacity(view_line_mappings.len());
+        for mapping in view_line_mappings {
+            // Blank lines render no characters but end on their newline
+            let line = mapping
+                .char_source_bytes
+                .iter()
+                .flatten()
+                .next()
+                .copied()
+                .or((mapping.line_end_byte > 0).then_some(mapping.line_end_byte))
+                .and_then(|first_byte| {
+                    let (line_start, text) = state
+                        .buffer
+                        .line_iterator(first_byte, estimated_line_length)
+                        .next()?;
+                    Some((first_byte, line_start, text))
+                });
+            let Some((first_byte, line_start, text)) = line else {
+                row_lines.push(None);
+                continue;
+            };
+            if lines.last().is_some_and(|(start, _)| *start == line_start) {
+                row_lines.push(None);
+                continue;
+            }
+            lines.push((line_start, indent_guides::leading_indent(&text, tab_size)));
+            let is_continuation = line_wrap && first_byte > line_start;
+            row_lines.push((!is_continuation).then_some(lines.len() - 1));
+        }
+        let (Some(&(first_start, first_indent)), Some(&(last_start, last_indent))) =
+            (lines.first(), lines.last())
+        else {
+            return Vec::new();
+        };
+
+        let before = if first_indent.is_none() {
+            let mut iter = state
+                .buffer
+                .line_iterator(first_start, estimated_line_length);
+            (0..BLANK_SCAN_LINES)
+                .map_while(|_| iter.prev())
+                .find_map(|(_, text)| indent_guides::leading_indent(&text, tab_size))
+        } else {
+            None
+        };
+        let after = if last_indent.is_none() {
+            let mut iter = state
+                .buffer
+                .line_iterator(last_start, estimated_line_length);
+            iter.next();
+            (0..BLANK_SCAN_LINES)
+                .map_while(|_| iter.next())
+                .find_map(|(_, text)| indent_guides::leading_indent(&text, tab_size))
+        } else {
+            None
+        };
+        let raw_indents: Vec<Option<usize>> = lines.iter().map(|(_, indent)| *indent).collect();
+        let indents = indent_guides::guide_indents(&raw_indents, before, after);
+
+        let cursor_line_start = state
+            .buffer
+            .line_iterator(primary_cursor_position, estimated_line_length)
+            .next()
+            .map(|(line_start, _)| line_start);
+        let active = lines
+            .iter()
+            .position(|(line_start, _)| Some(*line_start) == cursor_line_start)
+            .and_then(|cursor| indent_guides::active_guide(&indents, cursor, tab_size));
+
+        row_lines
+            .into_iter()
+            .map(|line| {
+                let Some(line) = line else {
+                    return Vec::new();
+                };
+                indent_guides::guide_columns(indents[line], tab_size)
+                    .map(|column| {
+                        let is_active = active.as_ref().is_some_and(|(active_column, lines)| {
+                            *active_column == column && lines.contains(&line)
+                        });
+                        (column, is_active)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Lines sticky scroll pins over the top of the view (at most `max_lines`)
+    fn sticky_scroll_lines(
+        state: &mut EditorState,
+        viewport: &crate::view::viewport::Viewport,
+        max_lines: usize,
+    ) -> Vec<usize> {
+        if max_lines == 0 || state.buffer.len() > FOLD_RANGES_MAX_BYTES {
+            return Vec::new();
+        }
+        let top_line = state.buffer.get_line_number(viewport.top_byte);
+        let language = state.highlighter.language().copied();
+        let ranges = state.folds.scope_ranges(
+            &state.buffer,
+            &mut state.syntax_tree,
+            language.as_ref(),
+            state.tab_size,
+        );
+        sticky_scroll::sticky_lines(ranges, top_line, max_lines)
     }
 
     /// Apply styles from original line_spans to a wrapped segment
@@ -4205,6 +4841,7 @@ mod tests {
         let render_area = Rect::new(0, 0, 20, 4);
         let visible_count = viewport.visible_line_count();
         let gutter_width = state.margins.left_total_width();
+        let theme = Theme::from_name(theme::THEME_DARK).unwrap();
 
         let view_data = SplitRenderer::build_view_data(
             &mut state,
@@ -4215,6 +4852,8 @@ mod tests {
             false, // line wrap disabled for tests
             render_area.width as usize,
             gutter_width,
+            &[],
+            theme.fold_placeholder_fg,
         );
         let view_anchor = SplitRenderer::calculate_view_anchor(&view_data.lines, 0);
 
@@ -4232,8 +4871,8 @@ mod tests {
             viewport_start,
             content.len().max(1),
             visible_count,
+            &[],
         );
-        let theme = Theme::from_name(theme::THEME_DARK).unwrap();
         let decorations = SplitRenderer::decoration_context(
             &mut state,
             viewport_start,
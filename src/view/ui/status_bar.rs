@@ -3,6 +3,7 @@
 use std::path::Path;
 
 use crate::app::WarningLevel;
+use crate::model::encoding::Encoding;
 use crate::primitives::display_width::{char_width, str_width};
 use crate::state::EditorState;
 use crate::view::prompt::Prompt;
@@ -22,6 +23,8 @@ pub struct StatusBarLayout {
     pub warning_badge: Option<(u16, u16, u16)>,
     /// Line ending indicator area (row, start_col, end_col)
     pub line_ending_indicator: Option<(u16, u16, u16)>,
+    /// Encoding indicator area (row, start_col, end_col)
+    pub encoding_indicator: Option<(u16, u16, u16)>,
 }
 
 /// Status bar hover state for styling clickable indicators
@@ -35,6 +38,8 @@ pub enum StatusBarHover {
     WarningBadge,
     /// Mouse is over the line ending indicator
     LineEndingIndicator,
+    /// Mouse is over the encoding indicator
+    EncodingIndicator,
 }
 
 /// Which search option checkbox is being hovered
@@ -251,6 +256,7 @@ impl StatusBarRenderer {
     ///
     /// # Returns
     /// Layout information with positions of clickable indicators
+    #[allow(clippy::too_many_arguments)]
     pub fn render_status_bar(
         frame: &mut Frame,
         area: Rect,
@@ -266,6 +272,7 @@ impl StatusBarRenderer {
         warning_level: WarningLevel,
         general_warning_count: usize,
         hover: StatusBarHover,
+        hex_view: bool,
     ) -> StatusBarLayout {
         Self::render_status(
             frame,
@@ -282,6 +289,7 @@ impl StatusBarRenderer {
             warning_level,
             general_warning_count,
             hover,
+            hex_view,
         )
     }
 
@@ -448,6 +456,7 @@ impl StatusBarRenderer {
     }
 
     /// Render the normal status bar
+    #[allow(clippy::too_many_arguments)]
     fn render_status(
         frame: &mut Frame,
         area: Rect,
@@ -463,6 +472,7 @@ impl StatusBarRenderer {
         warning_level: WarningLevel,
         general_warning_count: usize,
         hover: StatusBarHover,
+        hex_view: bool,
     ) -> StatusBarLayout {
         // Initialize layout tracking
         let mut layout = StatusBarLayout::default();
@@ -574,16 +584,55 @@ impl StatusBarRenderer {
 
         // Build left status (file info, position, diagnostics, messages)
         // Line and column are 0-indexed internally, but displayed as 1-indexed (standard editor convention)
-        let base_status = format!(
-            "{filename}{modified} | Ln {}, Col {}{diagnostics_summary}{cursor_count_indicator}",
-            line + 1,
-            col + 1
-        );
+        // In hex view the position is the byte offset instead
+        let base_status = if hex_view {
+            format!(
+                "{filename}{modified} | Hex 0x{:X}{diagnostics_summary}{cursor_count_indicator}",
+                cursor.position
+            )
+        } else {
+            format!(
+                "{filename}{modified} | Ln {}, Col {}{diagnostics_summary}{cursor_count_indicator}",
+                line + 1,
+                col + 1
+            )
+        };
 
         let left_status = format!("{base_status}{chord_display}{message_suffix}");
 
         // Build right-side indicators (these stay fixed on the right)
-        // Order: [Line ending] [LSP indicator] [warning badge] [update] [Palette]
+        // Order: [Line indexing] [Compression] [Encoding] [Line ending] [LSP indicator] [warning badge] [update] [Palette]
+
+        // Line indexing progress, shown while a large file is scanned for exact line numbers
+        let indexing_text = state
+            .line_indexing
+            .as_ref()
+            .map(|task| {
+                format!(
+                    " {} ",
+                    t!("status.indexing_lines", percent = task.percent())
+                )
+            })
+            .unwrap_or_default();
+        let indexing_width = str_width(&indexing_text);
+
+        // Compression indicator, shown only for compressed files
+        let compression_text = state
+            .buffer
+            .compression()
+            .map(|compression| format!(" {} ", compression.display_name()))
+            .unwrap_or_default();
+        let compression_width = str_width(&compression_text);
+
+        // Encoding indicator (clickable to reopen with another encoding)
+        // Plain UTF-8 is the default and not worth the space, so only other encodings are shown
+        let encoding = state.buffer.encoding();
+        let encoding_text = if encoding == Encoding::Utf8 {
+            String::new()
+        } else {
+            format!(" {} ", encoding.display_name())
+        };
+        let encoding_width = str_width(&encoding_text);
 
         // Line ending indicator (clickable to change format)
         let line_ending_text = format!(" {} ", state.buffer.line_ending().display_name());
@@ -622,10 +671,13 @@ impl StatusBarRenderer {
         let padded_cmd_palette = format!(" {} ", cmd_palette_indicator);
 
         // Calculate available width and right side width
-        // Right side: [Line ending] [LSP indicator] [warning badge] [update] [Palette]
+        // Right side: [Line indexing] [Compression] [Encoding] [Line ending] [LSP indicator] [warning badge] [update] [Palette]
         let available_width = area.width as usize;
         let cmd_palette_width = str_width(&padded_cmd_palette);
-        let right_side_width = line_ending_width
+        let right_side_width = indexing_width
+            + compression_width
+            + encoding_width
+            + line_ending_width
             + lsp_indicator_width
             + warning_badge_width
             + update_width
@@ -703,6 +755,47 @@ impl StatusBarRenderer {
                 current_col = area.x + (available_width - right_side_width) as u16;
             }
 
+            // Add line indexing progress
+            if indexing_width > 0 {
+                spans.push(Span::styled(
+                    indexing_text.clone(),
+                    Style::default()
+                        .fg(theme.status_bar_fg)
+                        .bg(theme.status_bar_bg),
+                ));
+                current_col += indexing_width as u16;
+            }
+
+            // Add compression indicator
+            if compression_width > 0 {
+                spans.push(Span::styled(
+                    compression_text.clone(),
+                    Style::default()
+                        .fg(theme.status_bar_fg)
+                        .bg(theme.status_bar_bg),
+                ));
+                current_col += compression_width as u16;
+            }
+
+            // Add encoding indicator (clickable to reopen with another encoding)
+            if encoding_width > 0 {
+                let is_hovering = hover == StatusBarHover::EncodingIndicator;
+                // Record position for click detection
+                layout.encoding_indicator =
+                    Some((area.y, current_col, current_col + encoding_width as u16));
+                let (fg, bg) = if is_hovering {
+                    (theme.menu_hover_fg, theme.menu_hover_bg)
+                } else {
+                    (theme.status_bar_fg, theme.status_bar_bg)
+                };
+                let mut style = Style::default().fg(fg).bg(bg);
+                if is_hovering {
+                    style = style.add_modifier(Modifier::UNDERLINED);
+                }
+                spans.push(Span::styled(encoding_text.clone(), style));
+                current_col += encoding_width as u16;
+            }
+
             // Add line ending indicator (clickable to change format)
             {
                 let is_hovering = hover == StatusBarHover::LineEndingIndicator;
@@ -308,6 +308,18 @@ impl VirtualTextManager {
         self.texts.clear();
     }
 
+    /// Clear the inline virtual texts (BeforeChar/AfterChar), keeping virtual lines
+    pub fn clear_inline(&mut self, marker_list: &mut MarkerList) {
+        self.texts.retain(|_, vtext| {
+            if vtext.position.is_inline() {
+                marker_list.delete(vtext.marker_id);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
     /// Get the number of virtual text entries
     pub fn len(&self) -> usize {
         self.texts.len()
@@ -408,6 +420,22 @@ impl VirtualTextManager {
         }
     }
 
+    /// Clear all virtual texts except those in a namespace
+    pub fn clear_except_namespace(
+        &mut self,
+        marker_list: &mut MarkerList,
+        namespace: &VirtualTextNamespace,
+    ) {
+        self.texts.retain(|_, vtext| {
+            if vtext.namespace.as_ref() == Some(namespace) {
+                true
+            } else {
+                marker_list.delete(vtext.marker_id);
+                false
+            }
+        });
+    }
+
     /// Query only virtual LINES (LineAbove/LineBelow) in a byte range
     ///
     /// Used by the render pipeline to inject header/footer lines.
@@ -604,6 +632,48 @@ mod tests {
         assert_eq!(marker_list.marker_count(), 0);
     }
 
+    #[test]
+    fn test_clear_except_namespace() {
+        let mut marker_list = MarkerList::new();
+        let mut manager = VirtualTextManager::new();
+        let kept = VirtualTextNamespace::from_string("lens".to_string());
+
+        manager.add(
+            &mut marker_list,
+            10,
+            ": i32".to_string(),
+            hint_style(),
+            VirtualTextPosition::AfterChar,
+            0,
+        );
+        manager.add_line(
+            &mut marker_list,
+            0,
+            "blame".to_string(),
+            hint_style(),
+            VirtualTextPosition::LineAbove,
+            VirtualTextNamespace::from_string("blame".to_string()),
+            0,
+        );
+        manager.add_line(
+            &mut marker_list,
+            0,
+            "2 references".to_string(),
+            hint_style(),
+            VirtualTextPosition::LineAbove,
+            kept.clone(),
+            0,
+        );
+
+        manager.clear_except_namespace(&mut marker_list, &kept);
+
+        assert_eq!(manager.len(), 1);
+        assert_eq!(marker_list.marker_count(), 1);
+        let lines = manager.query_lines_in_range(&marker_list, 0, 20);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].1.text, "2 references");
+    }
+
     #[test]
     fn test_query_range() {
         let mut marker_list = MarkerList::new();
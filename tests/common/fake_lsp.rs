@@ -954,6 +954,722 @@ done
         std::env::temp_dir().join("fake_lsp_server_inlay_hints.sh")
     }
 
+    /// Spawn a fake LSP server that answers workspace/symbol requests
+    ///
+    /// The script reports a single symbol described by its arguments: name,
+    /// kind, container name and absolute file path (the symbol is on line 1,
+    /// column 4 of the file). Like most servers, it finds nothing for an
+    /// empty query.
+    pub fn spawn_with_workspace_symbols() -> anyhow::Result<Self> {
+        let (stop_tx, stop_rx) = mpsc::channel();
+
+        let script = r#"#!/bin/bash
+
+symbol_name="$1"
+symbol_kind="$2"
+symbol_container="$3"
+symbol_path="$4"
+
+# Function to read a message
+read_message() {
+    # Read headers
+    local content_length=0
+    while IFS=: read -r key value; do
+        key=$(echo "$key" | tr -d '\r\n')
+        value=$(echo "$value" | tr -d '\r\n ')
+        if [ "$key" = "Content-Length" ]; then
+            content_length=$value
+        fi
+        # Empty line marks end of headers
+        if [ -z "$key" ]; then
+            break
+        fi
+    done
+
+    # Read content
+    if [ $content_length -gt 0 ]; then
+        dd bs=1 count=$content_length 2>/dev/null
+    fi
+}
+
+# Function to send a message
+send_message() {
+    local message="$1"
+    local length=${#message}
+    echo -en "Content-Length: $length\r\n\r\n$message"
+}
+
+# Main loop
+while true; do
+    # Read incoming message
+    msg=$(read_message)
+
+    if [ -z "$msg" ]; then
+        break
+    fi
+
+    # Extract method from JSON
+    method=$(echo "$msg" | grep -o '"method":"[^"]*"' | cut -d'"' -f4)
+    msg_id=$(echo "$msg" | grep -o '"id":[0-9]*' | cut -d':' -f2)
+
+    case "$method" in
+        "initialize")
+            # Send initialize response with workspaceSymbolProvider capability
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":{"capabilities":{"textDocumentSync":1,"workspaceSymbolProvider":true}}}'
+            ;;
+        "initialized")
+            # No response needed for notification
+            ;;
+        "textDocument/didOpen"|"textDocument/didChange"|"textDocument/didSave")
+            # No response for notifications
+            ;;
+        "textDocument/inlayHint")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            ;;
+        "workspace/symbol")
+            query=$(echo "$msg" | grep -o '"query":"[^"]*"' | cut -d'"' -f4)
+            if [ -z "$query" ]; then
+                send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            else
+                send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[{"name":"'$symbol_name'","kind":'$symbol_kind',"containerName":"'$symbol_container'","location":{"uri":"file://'$symbol_path'","range":{"start":{"line":1,"character":4},"end":{"line":1,"character":10}}}}]}'
+            fi
+            ;;
+        "shutdown")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":null}'
+            break
+            ;;
+    esac
+done
+"#;
+
+        // Write script to a temporary file
+        let script_path = Self::workspace_symbols_script_path();
+        std::fs::write(&script_path, script)?;
+
+        // Make it executable
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&script_path)?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&script_path, perms)?;
+        }
+
+        let handle = Some(thread::spawn(move || {
+            // Wait for stop signal
+            let _ = stop_rx.recv();
+        }));
+
+        Ok(Self { handle, stop_tx })
+    }
+
+    /// Get the path to the workspace symbols fake LSP server script
+    pub fn workspace_symbols_script_path() -> std::path::PathBuf {
+        std::env::temp_dir().join("fake_lsp_server_workspace_symbols.sh")
+    }
+
+    /// Spawn a fake LSP server that formats documents
+    ///
+    /// Every formatting request indents one line by four spaces: line 1 for
+    /// `textDocument/formatting`, the first line of the range for
+    /// `textDocument/rangeFormatting`, and the cursor's line for
+    /// `textDocument/onTypeFormatting`, which is triggered by `;`.
+    pub fn spawn_with_formatting() -> anyhow::Result<Self> {
+        let (stop_tx, stop_rx) = mpsc::channel();
+
+        let script = r#"#!/bin/bash
+
+# Function to read a message
+read_message() {
+    # Read headers
+    local content_length=0
+    while IFS=: read -r key value; do
+        key=$(echo "$key" | tr -d '\r\n')
+        value=$(echo "$value" | tr -d '\r\n ')
+        if [ "$key" = "Content-Length" ]; then
+            content_length=$value
+        fi
+        # Empty line marks end of headers
+        if [ -z "$key" ]; then
+            break
+        fi
+    done
+
+    # Read content
+    if [ $content_length -gt 0 ]; then
+        dd bs=1 count=$content_length 2>/dev/null
+    fi
+}
+
+# Function to send a message
+send_message() {
+    local message="$1"
+    local length=${#message}
+    echo -en "Content-Length: $length\r\n\r\n$message"
+}
+
+# Reply with an edit indenting the given line
+send_indent() {
+    send_message '{"jsonrpc":"2.0","id":'$1',"result":[{"range":{"start":{"line":'$2',"character":0},"end":{"line":'$2',"character":0}},"newText":"    "}]}'
+}
+
+# Main loop
+while true; do
+    # Read incoming message
+    msg=$(read_message)
+
+    if [ -z "$msg" ]; then
+        break
+    fi
+
+    # Extract method from JSON
+    method=$(echo "$msg" | grep -o '"method":"[^"]*"' | cut -d'"' -f4)
+    msg_id=$(echo "$msg" | grep -o '"id":[0-9]*' | cut -d':' -f2)
+
+    case "$method" in
+        "initialize")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":{"capabilities":{"textDocumentSync":1,"documentFormattingProvider":true,"documentRangeFormattingProvider":true,"documentOnTypeFormattingProvider":{"firstTriggerCharacter":";"}}}}'
+            ;;
+        "initialized")
+            # No response needed for notification
+            ;;
+        "textDocument/didOpen"|"textDocument/didChange"|"textDocument/didSave")
+            # No response for notifications
+            ;;
+        "textDocument/inlayHint")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            ;;
+        "textDocument/formatting")
+            send_indent $msg_id 1
+            ;;
+        "textDocument/rangeFormatting")
+            line=$(echo "$msg" | grep -o '"start":{"line":[0-9]*' | cut -d':' -f3)
+            send_indent $msg_id $line
+            ;;
+        "textDocument/onTypeFormatting")
+            line=$(echo "$msg" | grep -o '"position":{"line":[0-9]*' | cut -d':' -f3)
+            send_indent $msg_id $line
+            ;;
+        "shutdown")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":null}'
+            break
+            ;;
+    esac
+done
+"#;
+
+        // Write script to a temporary file
+        let script_path = Self::formatting_script_path();
+        std::fs::write(&script_path, script)?;
+
+        // Make it executable
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&script_path)?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&script_path, perms)?;
+        }
+
+        let handle = Some(thread::spawn(move || {
+            // Wait for stop signal
+            let _ = stop_rx.recv();
+        }));
+
+        Ok(Self { handle, stop_tx })
+    }
+
+    /// Get the path to the formatting fake LSP server script
+    pub fn formatting_script_path() -> std::path::PathBuf {
+        std::env::temp_dir().join("fake_lsp_server_formatting.sh")
+    }
+
+    /// Spawn a fake LSP server with call and type hierarchies
+    ///
+    /// Every item is in the file the request was made for. Call hierarchy of
+    /// `helper` (line 4): `main` (line 0) calls it on line 1, and it calls
+    /// `leaf` (line 8) on line 5. Type hierarchy of `Shape` (line 10): its only
+    /// subtype is `Circle` (line 12).
+    pub fn spawn_with_hierarchy() -> anyhow::Result<Self> {
+        let (stop_tx, stop_rx) = mpsc::channel();
+
+        let script = r#"#!/bin/bash
+
+# Function to read a message
+read_message() {
+    # Read headers
+    local content_length=0
+    while IFS=: read -r key value; do
+        key=$(echo "$key" | tr -d '\r\n')
+        value=$(echo "$value" | tr -d '\r\n ')
+        if [ "$key" = "Content-Length" ]; then
+            content_length=$value
+        fi
+        # Empty line marks end of headers
+        if [ -z "$key" ]; then
+            break
+        fi
+    done
+
+    # Read content
+    if [ $content_length -gt 0 ]; then
+        dd bs=1 count=$content_length 2>/dev/null
+    fi
+}
+
+# Function to send a message
+send_message() {
+    local message="$1"
+    local length=${#message}
+    echo -en "Content-Length: $length\r\n\r\n$message"
+}
+
+# A hierarchy item: name, kind, line of its name, uri
+item() {
+    echo '{"name":"'$1'","kind":'$2',"uri":"'$4'","range":{"start":{"line":'$3',"character":0},"end":{"line":'$3',"character":20}},"selectionRange":{"start":{"line":'$3',"character":3},"end":{"line":'$3',"character":9}}}'
+}
+
+# A range on a line
+range() {
+    echo '{"start":{"line":'$1',"character":4},"end":{"line":'$1',"character":10}}'
+}
+
+# Main loop
+while true; do
+    # Read incoming message
+    msg=$(read_message)
+
+    if [ -z "$msg" ]; then
+        break
+    fi
+
+    # Extract method from JSON
+    method=$(echo "$msg" | grep -o '"method":"[^"]*"' | cut -d'"' -f4)
+    msg_id=$(echo "$msg" | grep -o '"id":[0-9]*' | cut -d':' -f2)
+    uri=$(echo "$msg" | grep -o '"uri":"[^"]*"' | head -1 | cut -d'"' -f4)
+    name=$(echo "$msg" | grep -o '"name":"[^"]*"' | head -1 | cut -d'"' -f4)
+
+    case "$method" in
+        "initialize")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":{"capabilities":{"textDocumentSync":1,"callHierarchyProvider":true,"typeHierarchyProvider":true}}}'
+            ;;
+        "initialized")
+            # No response needed for notification
+            ;;
+        "textDocument/didOpen"|"textDocument/didChange"|"textDocument/didSave")
+            # No response for notifications
+            ;;
+        "textDocument/inlayHint")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            ;;
+        "textDocument/prepareCallHierarchy")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":['"$(item helper 12 4 $uri)"']}'
+            ;;
+        "callHierarchy/incomingCalls")
+            if [ "$name" = "helper" ]; then
+                send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[{"from":'"$(item main 12 0 $uri)"',"fromRanges":['"$(range 1)"']}]}'
+            else
+                send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            fi
+            ;;
+        "callHierarchy/outgoingCalls")
+            if [ "$name" = "helper" ]; then
+                send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[{"to":'"$(item leaf 12 8 $uri)"',"fromRanges":['"$(range 5)"']}]}'
+            else
+                send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            fi
+            ;;
+        "textDocument/prepareTypeHierarchy")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":['"$(item Shape 11 10 $uri)"']}'
+            ;;
+        "typeHierarchy/subtypes")
+            if [ "$name" = "Shape" ]; then
+                send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":['"$(item Circle 23 12 $uri)"']}'
+            else
+                send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            fi
+            ;;
+        "typeHierarchy/supertypes")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            ;;
+        "shutdown")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":null}'
+            break
+            ;;
+    esac
+done
+"#;
+
+        // Write script to a temporary file
+        let script_path = Self::hierarchy_script_path();
+        std::fs::write(&script_path, script)?;
+
+        // Make it executable
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&script_path)?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&script_path, perms)?;
+        }
+
+        let handle = Some(thread::spawn(move || {
+            // Wait for stop signal
+            let _ = stop_rx.recv();
+        }));
+
+        Ok(Self { handle, stop_tx })
+    }
+
+    /// Get the path to the hierarchy fake LSP server script
+    pub fn hierarchy_script_path() -> std::path::PathBuf {
+        std::env::temp_dir().join("fake_lsp_server_hierarchy.sh")
+    }
+
+    /// Spawn a fake LSP server answering "go to" requests
+    ///
+    /// Every location is in the file the request was made for: the definition
+    /// is on line 0, the type definition on line 1, the implementations on
+    /// lines 2 and 4, and there is no declaration.
+    pub fn spawn_with_goto() -> anyhow::Result<Self> {
+        let (stop_tx, stop_rx) = mpsc::channel();
+
+        let script = r#"#!/bin/bash
+
+# Function to read a message
+read_message() {
+    # Read headers
+    local content_length=0
+    while IFS=: read -r key value; do
+        key=$(echo "$key" | tr -d '\r\n')
+        value=$(echo "$value" | tr -d '\r\n ')
+        if [ "$key" = "Content-Length" ]; then
+            content_length=$value
+        fi
+        # Empty line marks end of headers
+        if [ -z "$key" ]; then
+            break
+        fi
+    done
+
+    # Read content
+    if [ $content_length -gt 0 ]; then
+        dd bs=1 count=$content_length 2>/dev/null
+    fi
+}
+
+# Function to send a message
+send_message() {
+    local message="$1"
+    local length=${#message}
+    echo -en "Content-Length: $length\r\n\r\n$message"
+}
+
+# A location: uri, line, character
+location() {
+    echo '{"uri":"'$1'","range":{"start":{"line":'$2',"character":'$3'},"end":{"line":'$2',"character":'$(($3 + 5))'}}}'
+}
+
+# Main loop
+while true; do
+    # Read incoming message
+    msg=$(read_message)
+
+    if [ -z "$msg" ]; then
+        break
+    fi
+
+    # Extract method from JSON
+    method=$(echo "$msg" | grep -o '"method":"[^"]*"' | cut -d'"' -f4)
+    msg_id=$(echo "$msg" | grep -o '"id":[0-9]*' | cut -d':' -f2)
+    uri=$(echo "$msg" | grep -o '"uri":"[^"]*"' | head -1 | cut -d'"' -f4)
+
+    case "$method" in
+        "initialize")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":{"capabilities":{"textDocumentSync":1,"definitionProvider":true,"typeDefinitionProvider":true,"implementationProvider":true,"declarationProvider":true}}}'
+            ;;
+        "initialized")
+            # No response needed for notification
+            ;;
+        "textDocument/didOpen"|"textDocument/didChange"|"textDocument/didSave")
+            # No response for notifications
+            ;;
+        "textDocument/inlayHint")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            ;;
+        "textDocument/definition")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":'"$(location $uri 0 7)"'}'
+            ;;
+        "textDocument/typeDefinition")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":['"$(location $uri 1 6)"']}'
+            ;;
+        "textDocument/implementation")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":['"$(location $uri 2 0)"','"$(location $uri 4 0)"']}'
+            ;;
+        "textDocument/declaration")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":null}'
+            ;;
+        "shutdown")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":null}'
+            break
+            ;;
+    esac
+done
+"#;
+
+        // Write script to a temporary file
+        let script_path = Self::goto_script_path();
+        std::fs::write(&script_path, script)?;
+
+        // Make it executable
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&script_path)?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&script_path, perms)?;
+        }
+
+        let handle = Some(thread::spawn(move || {
+            // Wait for stop signal
+            let _ = stop_rx.recv();
+        }));
+
+        Ok(Self { handle, stop_tx })
+    }
+
+    /// Get the path to the "go to" fake LSP server script
+    pub fn goto_script_path() -> std::path::PathBuf {
+        std::env::temp_dir().join("fake_lsp_server_goto.sh")
+    }
+
+    /// Spawn a fake LSP server that offers selection ranges but never
+    /// answers a request for them
+    pub fn spawn_with_silent_selection_range() -> anyhow::Result<Self> {
+        let (stop_tx, stop_rx) = mpsc::channel();
+
+        let script = r#"#!/bin/bash
+
+# Function to read a message
+read_message() {
+    # Read headers
+    local content_length=0
+    while IFS=: read -r key value; do
+        key=$(echo "$key" | tr -d '\r\n')
+        value=$(echo "$value" | tr -d '\r\n ')
+        if [ "$key" = "Content-Length" ]; then
+            content_length=$value
+        fi
+        # Empty line marks end of headers
+        if [ -z "$key" ]; then
+            break
+        fi
+    done
+
+    # Read content
+    if [ $content_length -gt 0 ]; then
+        dd bs=1 count=$content_length 2>/dev/null
+    fi
+}
+
+# Function to send a message
+send_message() {
+    local message="$1"
+    local length=${#message}
+    echo -en "Content-Length: $length\r\n\r\n$message"
+}
+
+# Main loop
+while true; do
+    # Read incoming message
+    msg=$(read_message)
+
+    if [ -z "$msg" ]; then
+        break
+    fi
+
+    # Extract method from JSON
+    method=$(echo "$msg" | grep -o '"method":"[^"]*"' | cut -d'"' -f4)
+    msg_id=$(echo "$msg" | grep -o '"id":[0-9]*' | cut -d':' -f2)
+
+    case "$method" in
+        "initialize")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":{"capabilities":{"textDocumentSync":1,"selectionRangeProvider":true}}}'
+            ;;
+        "initialized")
+            # No response needed for notification
+            ;;
+        "textDocument/didOpen"|"textDocument/didChange"|"textDocument/didSave")
+            # No response for notifications
+            ;;
+        "textDocument/inlayHint")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            ;;
+        "textDocument/selectionRange")
+            # Never answered
+            ;;
+        "shutdown")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":null}'
+            break
+            ;;
+    esac
+done
+"#;
+
+        // Write script to a temporary file
+        let script_path = Self::silent_selection_range_script_path();
+        std::fs::write(&script_path, script)?;
+
+        // Make it executable
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&script_path)?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&script_path, perms)?;
+        }
+
+        let handle = Some(thread::spawn(move || {
+            // Wait for stop signal
+            let _ = stop_rx.recv();
+        }));
+
+        Ok(Self { handle, stop_tx })
+    }
+
+    /// Get the path to the silent selection range fake LSP server script
+    pub fn silent_selection_range_script_path() -> std::path::PathBuf {
+        std::env::temp_dir().join("fake_lsp_server_silent_selection_range.sh")
+    }
+
+    /// Spawn a fake LSP server providing code lenses
+    ///
+    /// Line 0 gets a "Run (runs: N)" lens counting how often its `fake.run`
+    /// command was executed, and a lens resolved later into "Debug", whose
+    /// `fake.debug` command fails. Line 3 gets a "2 references" lens showing
+    /// references. Running `fake.run` makes the server ask for a refresh.
+    pub fn spawn_with_code_lens() -> anyhow::Result<Self> {
+        let (stop_tx, stop_rx) = mpsc::channel();
+
+        let script = r#"#!/bin/bash
+
+# Function to read a message
+read_message() {
+    # Read headers
+    local content_length=0
+    while IFS=: read -r key value; do
+        key=$(echo "$key" | tr -d '\r\n')
+        value=$(echo "$value" | tr -d '\r\n ')
+        if [ "$key" = "Content-Length" ]; then
+            content_length=$value
+        fi
+        # Empty line marks end of headers
+        if [ -z "$key" ]; then
+            break
+        fi
+    done
+
+    # Read content
+    if [ $content_length -gt 0 ]; then
+        dd bs=1 count=$content_length 2>/dev/null
+    fi
+}
+
+# Function to send a message
+send_message() {
+    local message="$1"
+    local length=${#message}
+    echo -en "Content-Length: $length\r\n\r\n$message"
+}
+
+# A range on a line: line, character
+range() {
+    echo '{"start":{"line":'$1',"character":'$2'},"end":{"line":'$1',"character":'$(($2 + 4))'}}'
+}
+
+runs=0
+
+# Main loop
+while true; do
+    # Read incoming message
+    msg=$(read_message)
+
+    if [ -z "$msg" ]; then
+        break
+    fi
+
+    # Extract method from JSON
+    method=$(echo "$msg" | grep -o '"method":"[^"]*"' | cut -d'"' -f4)
+    msg_id=$(echo "$msg" | grep -o '"id":[0-9]*' | head -1 | cut -d':' -f2)
+
+    case "$method" in
+        "initialize")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":{"capabilities":{"textDocumentSync":1,"codeLensProvider":{"resolveProvider":true},"executeCommandProvider":{"commands":["fake.run","fake.debug"]}}}}'
+            ;;
+        "initialized")
+            # No response needed for notification
+            ;;
+        "textDocument/didOpen"|"textDocument/didChange"|"textDocument/didSave")
+            # No response for notifications
+            ;;
+        "textDocument/inlayHint")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":[]}'
+            ;;
+        "textDocument/codeLens")
+            uri=$(echo "$msg" | grep -o '"uri":"[^"]*"' | head -1 | cut -d'"' -f4)
+            run='{"range":'"$(range 0 3)"',"command":{"title":"Run (runs: '$runs')","command":"fake.run"}}'
+            debug='{"range":'"$(range 0 3)"',"data":1}'
+            locations='[{"uri":"'$uri'","range":'"$(range 1 4)"'},{"uri":"'$uri'","range":'"$(range 4 4)"'}]'
+            references='{"range":'"$(range 3 3)"',"command":{"title":"2 references","command":"editor.action.showReferences","arguments":["'$uri'",{"line":3,"character":3},'"$locations"']}}'
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":['"$run"','"$debug"','"$references"']}'
+            ;;
+        "codeLens/resolve")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":{"range":'"$(range 0 3)"',"command":{"title":"Debug","command":"fake.debug"}}}'
+            ;;
+        "workspace/executeCommand")
+            command=$(echo "$msg" | grep -o '"command":"[^"]*"' | cut -d'"' -f4)
+            if [ "$command" = "fake.run" ]; then
+                runs=$((runs + 1))
+                send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":null}'
+                send_message '{"jsonrpc":"2.0","id":1000,"method":"workspace/codeLens/refresh"}'
+            else
+                send_message '{"jsonrpc":"2.0","id":'$msg_id',"error":{"code":-32603,"message":"no debugger"}}'
+            fi
+            ;;
+        "shutdown")
+            send_message '{"jsonrpc":"2.0","id":'$msg_id',"result":null}'
+            break
+            ;;
+    esac
+done
+"#;
+
+        // Write script to a temporary file
+        let script_path = Self::code_lens_script_path();
+        std::fs::write(&script_path, script)?;
+
+        // Make it executable
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&script_path)?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&script_path, perms)?;
+        }
+
+        let handle = Some(thread::spawn(move || {
+            // Wait for stop signal
+            let _ = stop_rx.recv();
+        }));
+
+        Ok(Self { handle, stop_tx })
+    }
+
+    /// Get the path to the code lens fake LSP server script
+    pub fn code_lens_script_path() -> std::path::PathBuf {
+        std::env::temp_dir().join("fake_lsp_server_code_lens.sh")
+    }
+
     /// Spawn a fake LSP server that logs all received methods to a file
     ///
     /// This variant logs each method name to a log file, which can be used
@@ -0,0 +1,87 @@
+// End-to-end tests for bracket pair colorization
+
+use crate::common::harness::EditorTestHarness;
+use fresh::config::Config;
+use ratatui::style::Color;
+
+const SOURCE: &str = "fn main() { call(\"(\", [1, 2]); }\n";
+
+fn harness_with(config: Config, file_name: &str) -> EditorTestHarness {
+    let mut harness = EditorTestHarness::with_temp_project_and_config(80, 24, config).unwrap();
+    let path = harness.project_dir().unwrap().join(file_name);
+    std::fs::write(&path, SOURCE).unwrap();
+    harness.open_file(&path).unwrap();
+    harness.render().unwrap();
+    harness
+}
+
+fn config_with_colors() -> Config {
+    let mut config = Config::default();
+    config.editor.bracket_pair_colorization = true;
+    config
+}
+
+/// Foreground of each character of the source line, by its byte offset
+fn colors(harness: &EditorTestHarness) -> Vec<Option<Color>> {
+    let (first_row, _) = harness.content_area_rows();
+    let row = first_row as u16;
+    let text = harness.get_row_text(row);
+    let content_x = text[..text.find("fn main").unwrap()].chars().count() as u16;
+    (0..SOURCE.len() as u16 - 1)
+        .map(|column| {
+            harness
+                .get_cell_style(content_x + column, row)
+                .and_then(|style| style.fg)
+        })
+        .collect()
+}
+
+#[test]
+fn test_brackets_colored_by_depth() {
+    let harness = harness_with(config_with_colors(), "main.rs");
+    let theme = harness.editor().theme().clone();
+    let colors = colors(&harness);
+    let at = |needle: &str, nth: usize| {
+        let position = SOURCE.match_indices(needle).nth(nth).unwrap().0;
+        colors[position]
+    };
+
+    // `main()` and `{ ... }` are at depth 0
+    assert_eq!(at("(", 0), Some(theme.bracket_pair_1_fg));
+    assert_eq!(at("{", 0), Some(theme.bracket_pair_1_fg));
+    assert_eq!(at("}", 0), Some(theme.bracket_pair_1_fg));
+    // `call(...)` at depth 1, `[1, 2]` at depth 2
+    assert_eq!(at("(", 1), Some(theme.bracket_pair_2_fg));
+    assert_eq!(at(")", 1), Some(theme.bracket_pair_2_fg));
+    assert_eq!(at("[", 0), Some(theme.bracket_pair_3_fg));
+    assert_eq!(at("]", 0), Some(theme.bracket_pair_3_fg));
+
+    // The bracket inside the string literal is not colorized
+    let in_string = at("(", 2);
+    assert_ne!(in_string, Some(theme.bracket_pair_1_fg));
+    assert_ne!(in_string, Some(theme.bracket_pair_2_fg));
+    assert_ne!(in_string, Some(theme.bracket_pair_3_fg));
+}
+
+#[test]
+fn test_bracket_colorization_off_by_default() {
+    let harness = harness_with(Config::default(), "main.rs");
+    let theme = harness.editor().theme().clone();
+    let position = SOURCE.find('[').unwrap();
+    assert_ne!(colors(&harness)[position], Some(theme.bracket_pair_3_fg));
+}
+
+/// Languages can turn colorization on regardless of the global setting
+#[test]
+fn test_bracket_colorization_per_language() {
+    let mut config = Config::default();
+    config
+        .languages
+        .get_mut("rust")
+        .unwrap()
+        .bracket_pair_colorization = Some(true);
+    let harness = harness_with(config, "main.rs");
+    let theme = harness.editor().theme().clone();
+    let position = SOURCE.find('[').unwrap();
+    assert_eq!(colors(&harness)[position], Some(theme.bracket_pair_3_fg));
+}
@@ -0,0 +1,140 @@
+// End-to-end tests for code lenses
+
+use crate::common::fake_lsp::FakeLspServer;
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use fresh::services::lsp::LspServerConfig;
+use fresh::services::process_limits::ProcessLimits;
+use std::path::Path;
+
+/// Laid out to match the lenses of the fake code lens server
+const SOURCE: &str = "fn main() {\n    helper();\n}\nfn helper() {}\nfn other() { helper(); }\n";
+
+/// A Rust file served by the fake code lens server, with its lenses shown
+fn harness_with_server(temp_dir: &Path) -> EditorTestHarness {
+    let path = temp_dir.join("main.rs");
+    std::fs::write(&path, SOURCE).unwrap();
+
+    let mut config = Config::default();
+    config.lsp.insert(
+        "rust".to_string(),
+        LspServerConfig {
+            command: FakeLspServer::code_lens_script_path()
+                .to_string_lossy()
+                .to_string(),
+            args: vec![],
+            enabled: true,
+            auto_start: true,
+            process_limits: ProcessLimits::default(),
+            initialization_options: None,
+        },
+    );
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 24, config, temp_dir.to_path_buf())
+            .unwrap();
+    harness.open_file(&path).unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Run (runs: 0) | Debug"))
+        .unwrap();
+    harness
+}
+
+/// Screen position of the first occurrence of a text
+fn screen_position(harness: &EditorTestHarness, text: &str) -> (u16, u16) {
+    let screen = harness.screen_to_string();
+    screen
+        .lines()
+        .enumerate()
+        .find_map(|(row, line)| {
+            let byte = line.find(text)?;
+            Some((line[..byte].chars().count() as u16, row as u16))
+        })
+        .unwrap_or_else(|| panic!("'{}' not on screen:\n{}", text, screen))
+}
+
+/// Lenses are drawn on their own line above the code they belong to, once
+/// resolved
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_code_lenses_render_above_their_line() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_code_lens()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path());
+    harness.wait_until(|h| h.screen_to_string().contains("2 references"))?;
+
+    let (_, run_row) = screen_position(&harness, "Run (runs: 0)");
+    let (_, main_row) = screen_position(&harness, "fn main()");
+    assert_eq!(run_row + 1, main_row);
+    let (_, references_row) = screen_position(&harness, "2 references");
+    let (_, helper_row) = screen_position(&harness, "fn helper()");
+    assert_eq!(references_row + 1, helper_row);
+
+    // Virtual lines are not part of the buffer
+    assert_eq!(harness.get_buffer_content().unwrap(), SOURCE);
+    Ok(())
+}
+
+/// Running a lens from the picker executes its command on the server, which
+/// then asks for the lenses to be refreshed
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_code_lens_picker_executes_command() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_code_lens()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path());
+
+    harness.send_key(KeyCode::Char('l'), KeyModifiers::ALT | KeyModifiers::SHIFT)?;
+    harness.render()?;
+    harness.assert_screen_contains("Code lens:");
+
+    harness.send_key(KeyCode::Enter, KeyModifiers::NONE)?;
+    harness.wait_until(|h| h.screen_to_string().contains("Run (runs: 1)"))?;
+    Ok(())
+}
+
+/// A failing command is reported
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_code_lens_command_failure_is_reported() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_code_lens()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path());
+
+    let (col, row) = screen_position(&harness, "Debug");
+    harness.mouse_click(col + 1, row)?;
+    harness.wait_until(|h| h.screen_to_string().contains("Code lens 'Debug' failed"))?;
+    harness.assert_screen_contains("no debugger");
+    Ok(())
+}
+
+/// Clicking a lens runs its command instead of moving the cursor; the editor
+/// shows the references of the well-known "show references" command itself
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_code_lens_click_shows_references() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_code_lens()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path());
+    harness.wait_until(|h| h.screen_to_string().contains("2 references"))?;
+
+    let cursor = harness.cursor_position();
+    let (col, row) = screen_position(&harness, "2 references");
+    harness.mouse_click(col + 2, row)?;
+    harness.assert_screen_contains("Found 2 reference(s) for 'helper'");
+    assert_eq!(harness.cursor_position(), cursor);
+    Ok(())
+}
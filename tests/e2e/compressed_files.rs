@@ -0,0 +1,151 @@
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::io::{Read, Write};
+use tempfile::TempDir;
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn gunzip(data: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::new();
+    flate2::read::GzDecoder::new(data)
+        .read_to_end(&mut decoded)
+        .unwrap();
+    decoded
+}
+
+/// A rotated gzip log opens as text and is recompressed when saved
+#[test]
+fn test_gzip_log_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("app.log.1.gz");
+    std::fs::write(
+        &file_path,
+        gzip(b"INFO service started\nWARN disk at 91%\n"),
+    )
+    .unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("INFO service started");
+    harness.assert_screen_contains("WARN disk at 91%");
+    harness.assert_screen_contains(" gzip ");
+
+    harness.type_text("# checked\n").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    let saved = std::fs::read(&file_path).unwrap();
+    assert_eq!(&saved[..2], &[0x1F, 0x8B]);
+    assert_eq!(
+        gunzip(&saved),
+        b"# checked\nINFO service started\nWARN disk at 91%\n"
+    );
+}
+
+/// Compression is recognized by magic bytes even without a telling extension
+#[test]
+fn test_compressed_file_detected_by_magic_bytes() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("app.log.1");
+    std::fs::write(&file_path, gzip(b"rotated without suffix\n")).unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("rotated without suffix");
+    harness.assert_screen_contains(" gzip ");
+}
+
+/// A new file with a compressed extension is written compressed
+#[test]
+fn test_new_gzip_file_is_compressed() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("notes.txt.gz");
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.type_text("fresh notes\n").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    assert_eq!(
+        gunzip(&std::fs::read(&file_path).unwrap()),
+        b"fresh notes\n"
+    );
+}
+
+/// bzip2 files are handled without the bzip2 command line tool
+#[test]
+fn test_bzip2_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("dump.sql.bz2");
+    let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+    encoder.write_all(b"INSERT INTO t VALUES (1);\n").unwrap();
+    std::fs::write(&file_path, encoder.finish().unwrap()).unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("INSERT INTO t VALUES (1);");
+    harness.assert_screen_contains(" bzip2 ");
+
+    harness.type_text("BEGIN;\n").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    let mut decoded = Vec::new();
+    bzip2::read::BzDecoder::new(std::fs::read(&file_path).unwrap().as_slice())
+        .read_to_end(&mut decoded)
+        .unwrap();
+    assert_eq!(decoded, b"BEGIN;\nINSERT INTO t VALUES (1);\n");
+}
+
+/// A truncated archive still opens, undecompressed, with a warning
+#[test]
+fn test_truncated_gzip_opens_as_is() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("partial.log.gz");
+    let data = gzip(&b"a line that repeats\n".repeat(200));
+    std::fs::write(&file_path, &data[..data.len() / 2]).unwrap();
+
+    let mut harness = EditorTestHarness::new(120, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+
+    let status = harness.editor().get_status_message().cloned().unwrap();
+    assert!(status.starts_with("Could not decompress"), "{status}");
+    assert_eq!(harness.editor().active_state().buffer.len(), data.len() / 2);
+    assert_eq!(harness.editor().active_state().buffer.compression(), None);
+}
+
+/// A text file that happens to start with a compression magic is text
+#[test]
+fn test_text_with_compression_magic_opens_as_text() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    std::fs::write(&file_path, "BZh is how bzip2 files start\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(120, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("BZh is how bzip2 files start");
+
+    harness.type_text("> ").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&file_path).unwrap(),
+        "> BZh is how bzip2 files start\n"
+    );
+}
@@ -0,0 +1,154 @@
+// End-to-end tests for .editorconfig support
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use fresh::config_io::DirectoryContext;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// Harness for a project containing `.editorconfig` and the given files
+fn harness_in_project(
+    temp_dir: &TempDir,
+    editorconfig: &str,
+    files: &[(&str, &str)],
+    config: Config,
+) -> (EditorTestHarness, PathBuf) {
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir_all(&project_dir).unwrap();
+    std::fs::write(project_dir.join(".editorconfig"), editorconfig).unwrap();
+    for (name, content) in files {
+        let path = project_dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    let dir_context = DirectoryContext::for_testing(&temp_dir.path().join("context"));
+    let harness = EditorTestHarness::with_shared_dir_context(
+        120,
+        24,
+        config,
+        project_dir.clone(),
+        dir_context,
+    )
+    .unwrap();
+    (harness, project_dir)
+}
+
+fn key(harness: &mut EditorTestHarness, code: KeyCode, modifiers: KeyModifiers) {
+    harness.send_key(code, modifiers).unwrap();
+    harness.render().unwrap();
+}
+
+/// Indentation follows the sections matching each file
+#[test]
+fn test_editorconfig_indentation() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut harness, project_dir) = harness_in_project(
+        &temp_dir,
+        "root = true\n\n[*]\nindent_style = space\nindent_size = 2\n\n[*.txt]\nindent_style = tab\n",
+        &[("src/main.rs", ""), ("notes.txt", "")],
+        Config::default(),
+    );
+
+    harness.open_file(&project_dir.join("src/main.rs")).unwrap();
+    key(&mut harness, KeyCode::Tab, KeyModifiers::NONE);
+    harness.assert_buffer_content("  ");
+
+    harness.open_file(&project_dir.join("notes.txt")).unwrap();
+    key(&mut harness, KeyCode::Tab, KeyModifiers::NONE);
+    harness.assert_buffer_content("\t");
+}
+
+/// Settings in the project config file take precedence over .editorconfig
+#[test]
+fn test_project_config_overrides_editorconfig() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = Config::default();
+    config.editor.tab_size = 8;
+    let (mut harness, project_dir) = harness_in_project(
+        &temp_dir,
+        "[*]\nindent_size = 2\n",
+        &[
+            ("notes.txt", ""),
+            (".fresh/config.json", r#"{ "editor": { "tab_size": 8 } }"#),
+        ],
+        config,
+    );
+
+    harness.open_file(&project_dir.join("notes.txt")).unwrap();
+    key(&mut harness, KeyCode::Tab, KeyModifiers::NONE);
+    harness.assert_buffer_content("        ");
+}
+
+/// Trailing whitespace is trimmed and a final newline added on save, each undoably
+#[test]
+fn test_editorconfig_whitespace_on_save() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut harness, project_dir) = harness_in_project(
+        &temp_dir,
+        "[*.txt]\ntrim_trailing_whitespace = true\ninsert_final_newline = true\n",
+        &[("notes.txt", "")],
+        Config::default(),
+    );
+    let path = project_dir.join("notes.txt");
+    harness.open_file(&path).unwrap();
+
+    harness.type_text("hello  ").unwrap();
+    key(&mut harness, KeyCode::Enter, KeyModifiers::NONE);
+    harness.type_text("world").unwrap();
+    key(&mut harness, KeyCode::Up, KeyModifiers::NONE);
+    assert_eq!(harness.cursor_position(), "hello".len());
+
+    key(&mut harness, KeyCode::Char('s'), KeyModifiers::CONTROL);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello\nworld\n");
+    harness.assert_buffer_content("hello\nworld\n");
+    assert_eq!(harness.cursor_position(), "hello".len());
+
+    key(&mut harness, KeyCode::Char('z'), KeyModifiers::CONTROL);
+    harness.assert_buffer_content("hello\nworld");
+    key(&mut harness, KeyCode::Char('z'), KeyModifiers::CONTROL);
+    harness.assert_buffer_content("hello  \nworld");
+}
+
+/// `max_line_length` draws a ruler after that many columns
+#[test]
+fn test_editorconfig_ruler() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut harness, project_dir) = harness_in_project(
+        &temp_dir,
+        "[*]\nmax_line_length = 10\n",
+        &[("notes.txt", "0123456789abcdef\n")],
+        Config::default(),
+    );
+    harness.open_file(&project_dir.join("notes.txt")).unwrap();
+    harness.render().unwrap();
+
+    let (row, line) = (0..24)
+        .map(|row| (row, harness.get_screen_row(row)))
+        .find(|(_, line)| line.contains("0123456789abcdef"))
+        .unwrap();
+    let column = line[..line.find("0123").unwrap()].chars().count() as u16;
+    let ruler_bg = harness.editor().theme().ruler_bg;
+    let bg = |x: u16| harness.get_cell_style(x, row as u16).unwrap().bg;
+    assert_eq!(bg(column + 10), Some(ruler_bg));
+    assert_ne!(bg(column + 9), Some(ruler_bg));
+}
+
+/// The settings UI shows that a value comes from .editorconfig
+#[test]
+fn test_settings_show_editorconfig_source() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut harness, project_dir) = harness_in_project(
+        &temp_dir,
+        "[*.rs]\nindent_size = 2\n",
+        &[("main.rs", "")],
+        Config::default(),
+    );
+    harness.open_file(&project_dir.join("main.rs")).unwrap();
+
+    key(&mut harness, KeyCode::Char(','), KeyModifiers::CONTROL);
+    key(&mut harness, KeyCode::Char('/'), KeyModifiers::NONE);
+    harness.type_text("tab size").unwrap();
+    key(&mut harness, KeyCode::Enter, KeyModifiers::NONE);
+    harness.assert_screen_contains("(editorconfig)");
+}
@@ -0,0 +1,82 @@
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use tempfile::TempDir;
+
+/// A Latin-1 file is decoded for display and keeps its encoding when saved
+#[test]
+fn test_latin1_file_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("latin1.txt");
+
+    // "Grüße aus Köln" in Windows-1252 / Latin-1
+    std::fs::write(&file_path, b"Gr\xfc\xdfe aus K\xf6ln\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+
+    harness.assert_screen_contains("Grüße aus Köln");
+    harness.assert_screen_contains("Latin-1");
+
+    harness.type_text("Ä").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    let saved = std::fs::read(&file_path).unwrap();
+    assert_eq!(saved, b"\xc4Gr\xfc\xdfe aus K\xf6ln\n");
+}
+
+/// The status bar shows the encoding of non-UTF-8 files only
+#[test]
+fn test_encoding_indicator() {
+    let temp_dir = TempDir::new().unwrap();
+    let utf8_path = temp_dir.path().join("utf8.txt");
+    std::fs::write(&utf8_path, "plain text\n").unwrap();
+    let utf16_path = temp_dir.path().join("utf16.txt");
+    let mut utf16 = vec![0xFF, 0xFE];
+    utf16.extend("wide text\n".encode_utf16().flat_map(|u| u.to_le_bytes()));
+    std::fs::write(&utf16_path, utf16).unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&utf8_path).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_not_contains("UTF-8");
+
+    harness.open_file(&utf16_path).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("wide text");
+    harness.assert_screen_contains("UTF-16 LE");
+}
+
+/// Reopening a file with an explicit encoding re-decodes its content
+#[test]
+fn test_reopen_with_encoding() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("cyrillic.txt");
+
+    // "Привет" in Windows-1251, which is too short to be detected reliably
+    std::fs::write(&file_path, b"\xcf\xf0\xe8\xe2\xe5\xf2\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Reopen with Encoding").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Reopen with encoding:");
+
+    harness.type_text("Windows-1251").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    assert_eq!(harness.get_buffer_content().unwrap(), "Привет\n");
+    harness.assert_screen_contains("Windows-1251");
+}
@@ -0,0 +1,112 @@
+// End-to-end tests for the local file history
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use tempfile::TempDir;
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// Open the newest snapshot of the active file, run `key` in the comparison
+/// view and close it again
+fn restore_from_newest_snapshot(harness: &mut EditorTestHarness, key: char) {
+    run_command(harness, "File History");
+    harness.assert_screen_contains("File history:");
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Current");
+
+    harness
+        .send_key(KeyCode::Char(key), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// Saves are recorded, and a snapshot can be restored hunk by hunk or as a whole
+#[test]
+fn test_restore_hunk_and_whole_snapshot() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    std::fs::write(&file_path, "one\ntwo\nthree\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.type_text("1 ").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&file_path).unwrap(),
+        "1 one\ntwo\nthree\n"
+    );
+
+    // Change the first and the last line without saving
+    harness.type_text("X").unwrap();
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("tail").unwrap();
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "1 Xone\ntwo\nthree\ntail"
+    );
+
+    // The cursor starts on the first hunk
+    restore_from_newest_snapshot(&mut harness, 'h');
+    assert_eq!(
+        harness.get_buffer_content().unwrap(),
+        "1 one\ntwo\nthree\ntail"
+    );
+
+    restore_from_newest_snapshot(&mut harness, 'r');
+    assert_eq!(harness.get_buffer_content().unwrap(), "1 one\ntwo\nthree\n");
+}
+
+/// Files that were never saved have no history yet
+#[test]
+fn test_file_without_snapshots() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("untouched.txt");
+    std::fs::write(&file_path, "content\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    run_command(&mut harness, "File History");
+    harness.assert_screen_contains("No saved snapshots");
+}
+
+/// Saving a new buffer with "Save As" records its first snapshot
+#[test]
+fn test_save_as_records_snapshot() {
+    let mut harness = EditorTestHarness::with_temp_project(100, 24).unwrap();
+    let file_path = harness.project_dir().unwrap().join("draft.txt");
+    harness.new_buffer().unwrap();
+    harness.type_text("first draft\n").unwrap();
+
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.wait_for_screen_contains("Save as:").unwrap();
+    harness.type_text("draft.txt").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_until(|_| file_path.exists()).unwrap();
+
+    run_command(&mut harness, "File History");
+    harness.assert_screen_contains("File history:");
+}
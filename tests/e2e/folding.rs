@@ -0,0 +1,130 @@
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+const SOURCE: &str = "fn main() {
+    let a = 1;
+    let b = 2;
+    println!(\"{}\", a + b);
+}
+
+fn helper() {
+    let marker = 42;
+}
+";
+
+fn open_source(harness: &mut EditorTestHarness) {
+    let path = harness.project_dir().unwrap().join("main.rs");
+    std::fs::write(&path, SOURCE).unwrap();
+    harness.open_file(&path).unwrap();
+    harness.render().unwrap();
+}
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// Folding a function hides its body behind a placeholder and keeps the
+/// closing brace visible; unfolding restores it
+#[test]
+fn test_fold_and_unfold_function() {
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    open_source(&mut harness);
+
+    run_command(&mut harness, "Toggle Fold");
+    harness.assert_screen_contains("fn main() { ⋯");
+    harness.assert_screen_not_contains("let b = 2;");
+    harness.assert_screen_contains("let marker = 42;");
+    // Line numbers skip the hidden lines
+    let screen = harness.screen_to_string();
+    let closing_row = screen
+        .lines()
+        .find(|row| row.trim_end().ends_with('}'))
+        .unwrap();
+    assert!(closing_row.contains('5'), "row: {closing_row:?}");
+    assert!(!harness.editor().active_state().buffer.is_modified());
+
+    run_command(&mut harness, "Toggle Fold");
+    harness.assert_screen_contains("let b = 2;");
+    harness.assert_screen_not_contains("⋯");
+}
+
+/// The fold placeholder is drawn in the theme's `fold_placeholder_fg`
+#[test]
+fn test_fold_placeholder_uses_theme_color() {
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    open_source(&mut harness);
+    run_command(&mut harness, "Toggle Fold");
+
+    let (x, y) = (0..24)
+        .flat_map(|y| (0..80).map(move |x| (x, y)))
+        .find(|&(x, y)| harness.get_cell(x, y).as_deref() == Some("⋯"))
+        .expect("placeholder on screen");
+    let expected = harness.editor().theme().fold_placeholder_fg;
+    assert_eq!(harness.get_cell_style(x, y).unwrap().fg, Some(expected));
+}
+
+/// Moving down from a fold header lands after the fold
+#[test]
+fn test_cursor_moves_over_fold() {
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    open_source(&mut harness);
+
+    run_command(&mut harness, "Toggle Fold");
+    harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.cursor_position(), SOURCE.find("}\n").unwrap());
+    harness.assert_screen_contains("⋯");
+
+    harness.send_key(KeyCode::Up, KeyModifiers::NONE).unwrap();
+    assert_eq!(harness.cursor_position(), 0);
+}
+
+/// A search match inside a fold unfolds it
+#[test]
+fn test_search_unfolds_match() {
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    open_source(&mut harness);
+
+    run_command(&mut harness, "Fold All");
+    harness.assert_screen_not_contains("let b = 2;");
+    harness.assert_screen_not_contains("let marker = 42;");
+
+    harness
+        .send_key(KeyCode::Char('f'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("println").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.process_async_and_render().unwrap();
+    harness.assert_screen_contains("println!");
+    // The other fold stays collapsed
+    harness.assert_screen_not_contains("let marker = 42;");
+
+    run_command(&mut harness, "Unfold All");
+    harness.assert_screen_contains("let marker = 42;");
+}
+
+/// Folds follow edits made above them
+#[test]
+fn test_fold_follows_edits_above() {
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    open_source(&mut harness);
+
+    run_command(&mut harness, "Toggle Fold");
+    harness.send_key(KeyCode::Home, KeyModifiers::NONE).unwrap();
+    harness.type_text("// header\n").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("// header");
+    harness.assert_screen_contains("fn main() { ⋯");
+    harness.assert_screen_not_contains("let b = 2;");
+}
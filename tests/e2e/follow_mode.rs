@@ -0,0 +1,96 @@
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::io::Write;
+use std::path::Path;
+
+fn log_lines(range: std::ops::RangeInclusive<usize>) -> String {
+    range.map(|i| format!("line {:03}\n", i)).collect()
+}
+
+fn append(path: &Path, content: &str) {
+    let mut file = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+    file.write_all(content.as_bytes()).unwrap();
+    file.sync_all().unwrap();
+}
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// Appended lines extend the buffer and keep the view at the end, until the
+/// cursor moves away from the end
+#[test]
+fn test_follow_appends_and_stays_pinned_to_end() {
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    let path = harness.project_dir().unwrap().join("app.log");
+    std::fs::write(&path, log_lines(1..=30)).unwrap();
+    harness.open_file(&path).unwrap();
+
+    run_command(&mut harness, "Toggle Follow Mode");
+    harness.assert_screen_contains("Following app.log");
+    assert_eq!(harness.cursor_position(), log_lines(1..=30).len());
+
+    append(&path, &log_lines(31..=80));
+    harness
+        .wait_until(|h| h.get_buffer_content().unwrap() == log_lines(1..=80))
+        .unwrap();
+    harness.assert_screen_contains("line 080");
+    harness.assert_screen_not_contains("line 030");
+    assert_eq!(harness.cursor_position(), log_lines(1..=80).len());
+    assert!(!harness.editor().active_state().buffer.is_modified());
+
+    // Away from the end, appends don't move the view
+    harness
+        .send_key(KeyCode::Home, KeyModifiers::CONTROL)
+        .unwrap();
+    append(&path, &log_lines(81..=90));
+    harness
+        .wait_until(|h| h.get_buffer_content().unwrap() == log_lines(1..=90))
+        .unwrap();
+    harness.assert_screen_contains("line 001");
+    assert_eq!(harness.cursor_position(), 0);
+
+    run_command(&mut harness, "Toggle Follow Mode");
+    harness.assert_screen_contains("Follow mode off");
+}
+
+/// A truncated or rotated file is reopened
+#[test]
+fn test_follow_reopens_truncated_and_rotated_files() {
+    let mut harness = EditorTestHarness::with_temp_project(80, 24).unwrap();
+    let path = harness.project_dir().unwrap().join("app.log");
+    std::fs::write(&path, log_lines(1..=20)).unwrap();
+    harness.open_file(&path).unwrap();
+    run_command(&mut harness, "Toggle Follow Mode");
+
+    std::fs::write(&path, "restarted\n").unwrap();
+    harness
+        .wait_until(|h| h.get_buffer_content().unwrap() == "restarted\n")
+        .unwrap();
+    harness.assert_screen_contains("Log truncated or replaced");
+
+    // Rotation: the file is moved away and a new one takes its place
+    std::fs::rename(&path, path.with_extension("log.1")).unwrap();
+    std::fs::write(&path, log_lines(1..=40)).unwrap();
+    harness
+        .wait_until(|h| h.get_buffer_content().unwrap() == log_lines(1..=40))
+        .unwrap();
+    harness.assert_screen_contains("line 040");
+
+    append(&path, "after rotation\n");
+    harness
+        .wait_until(|h| {
+            h.get_buffer_content()
+                .unwrap()
+                .ends_with("line 040\nafter rotation\n")
+        })
+        .unwrap();
+}
@@ -0,0 +1,247 @@
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use tempfile::TempDir;
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// The hex view shows offsets, hex bytes and an ASCII gutter
+#[test]
+fn test_hex_view_renders_rows() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("header.bin");
+    let mut data = b"\x7FELF\x02\x01\x01\x00".to_vec();
+    data.extend_from_slice(&[0u8; 8]);
+    data.extend_from_slice(b"Hi!\r\n");
+    std::fs::write(&file_path, &data).unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    run_command(&mut harness, "Toggle Hex View");
+
+    harness.assert_screen_contains(
+        "00000000  7F 45 4C 46 02 01 01 00  00 00 00 00 00 00 00 00  .ELF............",
+    );
+    harness.assert_screen_contains("00000010  48 69 21 0D 0A");
+    harness.assert_screen_contains("Hi!..");
+    harness.assert_screen_contains("Hex 0x0");
+
+    // Toggling again returns to the text view
+    run_command(&mut harness, "Toggle Hex View");
+    harness.assert_screen_not_contains("00000000  7F 45");
+}
+
+/// Typing hex digits overwrites nibbles in place, and saving writes the raw bytes
+#[test]
+fn test_hex_view_overwrite_and_save() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    std::fs::write(&file_path, b"\x00\x01\x02\x03line\r\nend\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    run_command(&mut harness, "Toggle Hex View");
+
+    // Overwrite the first byte, then the low nibble of the third
+    harness.type_text("ab").unwrap();
+    harness
+        .send_key(KeyCode::Right, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Right, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Right, KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("F").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("00000000  AB 01 0F 03");
+    harness.assert_screen_contains("Hex 0x3");
+
+    // Tab switches to the ASCII gutter, where characters overwrite whole bytes
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE).unwrap();
+    harness.type_text("Z").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("00000000  AB 01 0F 5A");
+
+    // Deleting would change the size of the file and is refused
+    harness
+        .send_key(KeyCode::Backspace, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("AB 01 0F 5A 6C");
+
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(
+        std::fs::read(&file_path).unwrap(),
+        b"\xAB\x01\x0F\x5Aline\r\nend\n"
+    );
+
+    // A byte overwrite is a single undo step
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("00000000  AB 01 0F 03");
+}
+
+/// Text transformations would resize the bytes they change and are refused
+#[test]
+fn test_hex_view_refuses_text_transformations() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("data.bin");
+    let mut data = b"\x00\x01".to_vec();
+    // "ﬀ" is three bytes, its uppercase "FF" two
+    data.extend_from_slice("ﬀ lower".as_bytes());
+    std::fs::write(&file_path, &data).unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    run_command(&mut harness, "Toggle Hex View");
+
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::CONTROL)
+        .unwrap();
+    run_command(&mut harness, "Transform to Uppercase");
+    harness.assert_screen_contains("Hex view overwrites bytes in place");
+    assert_eq!(harness.editor().active_state().buffer.len(), data.len());
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(std::fs::read(&file_path).unwrap(), data);
+}
+
+/// Hex search finds byte patterns and Find Next repeats it
+#[test]
+fn test_hex_view_search() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("search.bin");
+    let mut data = vec![0u8; 64];
+    data[20..24].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    data[40..44].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+    std::fs::write(&file_path, &data).unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    run_command(&mut harness, "Toggle Hex View");
+
+    run_command(&mut harness, "Search Hex Bytes");
+    harness.type_text("de ad be ef").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.cursor_position(), 20);
+    harness.assert_screen_contains("Found at offset 0x14");
+
+    harness.send_key(KeyCode::F(3), KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.cursor_position(), 40);
+
+    // Wraps around to the first match
+    harness.send_key(KeyCode::F(3), KeyModifiers::NONE).unwrap();
+    assert_eq!(harness.cursor_position(), 20);
+}
+
+/// Large files are paged in as the hex view scrolls
+#[test]
+fn test_hex_view_pages_through_large_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("big.bin");
+    let data: Vec<u8> = (0..3 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&file_path, &data).unwrap();
+
+    let mut harness = EditorTestHarness::with_config(
+        100,
+        24,
+        fresh::config::Config {
+            editor: fresh::config::EditorConfig {
+                large_file_threshold_bytes: 1024,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    harness.open_file(&file_path).unwrap();
+    run_command(&mut harness, "Toggle Hex View");
+
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.cursor_position(), data.len());
+    // The last row of the file is shown
+    let last_row = 0x2FFFF0usize;
+    let expected = format!(
+        "002FFFF0  {:02X} {:02X}",
+        data[last_row],
+        data[last_row + 1]
+    );
+    harness.assert_screen_contains(&expected);
+
+    harness
+        .send_key(KeyCode::Home, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("00000000  00 01 02 03");
+}
+
+/// A file decoded from another encoding is shown with its bytes on disk
+#[test]
+fn test_hex_view_shows_undecoded_bytes() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("latin1.txt");
+    std::fs::write(&file_path, b"Gr\xfc\xdfe aus K\xf6ln\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Latin-1");
+
+    run_command(&mut harness, "Toggle Hex View");
+    harness.assert_screen_contains("00000000  47 72 FC DF 65");
+    harness.assert_screen_contains("Gr..e aus K.ln.");
+    harness.assert_screen_not_contains("Latin-1");
+}
+
+/// Only viewing a file in hex view leaves line ending conversion on save intact
+#[test]
+fn test_hex_view_toggled_off_keeps_line_ending_conversion() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    std::fs::write(&file_path, "one\ntwo\n").unwrap();
+
+    let mut harness = EditorTestHarness::new(100, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    run_command(&mut harness, "Toggle Hex View");
+    harness.assert_screen_contains("00000000  6F 6E 65 0A");
+    run_command(&mut harness, "Toggle Hex View");
+
+    run_command(&mut harness, "Set Line Ending");
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.wait_for_prompt_closed().unwrap();
+    harness.assert_screen_contains("CRLF");
+
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness
+        .wait_until(|h| !h.editor().active_state().buffer.is_modified())
+        .unwrap();
+    assert_eq!(std::fs::read(&file_path).unwrap(), b"one\r\ntwo\r\n");
+}
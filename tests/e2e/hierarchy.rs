@@ -0,0 +1,121 @@
+// End-to-end tests for the call and type hierarchy panel
+
+use crate::common::fake_lsp::FakeLspServer;
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use fresh::services::lsp::LspServerConfig;
+use fresh::services::process_limits::ProcessLimits;
+use std::path::Path;
+
+/// Laid out to match the items of the fake hierarchy server
+const SOURCE: &str = "fn main() {\n    helper();\n}\n\nfn helper() {\n    leaf();\n}\n\nfn leaf() {}\n\ntrait Shape {}\n\nstruct Circle;\n";
+
+/// A Rust file served by the fake hierarchy server
+fn harness_with_server(temp_dir: &Path) -> EditorTestHarness {
+    let path = temp_dir.join("main.rs");
+    std::fs::write(&path, SOURCE).unwrap();
+
+    let mut config = Config::default();
+    config.lsp.insert(
+        "rust".to_string(),
+        LspServerConfig {
+            command: FakeLspServer::hierarchy_script_path()
+                .to_string_lossy()
+                .to_string(),
+            args: vec![],
+            enabled: true,
+            auto_start: true,
+            process_limits: ProcessLimits::default(),
+            initialization_options: None,
+        },
+    );
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 30, config, temp_dir.to_path_buf())
+            .unwrap();
+    harness.open_file(&path).unwrap();
+    harness
+        .wait_until(|h| h.editor().is_lsp_server_ready("rust"))
+        .unwrap();
+    harness
+}
+
+/// The call hierarchy opens with the callers of the function, previews the
+/// call site, and switches to the callees with `t`
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_call_hierarchy_callers_and_callees() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_hierarchy()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path());
+
+    harness.send_key_repeat(KeyCode::Down, KeyModifiers::NONE, 4)?;
+    harness.send_key_repeat(KeyCode::Right, KeyModifiers::NONE, 4)?;
+    harness.send_key(KeyCode::Char('h'), KeyModifiers::ALT | KeyModifiers::SHIFT)?;
+    harness.wait_until(|h| h.screen_to_string().contains("main.rs:2"))?;
+    harness.assert_screen_contains("Calls to helper");
+    harness.assert_screen_contains("▾");
+
+    // Moving onto the caller previews the call site
+    harness.send_key(KeyCode::Char('j'), KeyModifiers::NONE)?;
+    harness.render()?;
+    harness.assert_screen_contains("▶    2 │     helper();");
+
+    harness.send_key(KeyCode::Char('t'), KeyModifiers::NONE)?;
+    harness.wait_until(|h| h.screen_to_string().contains("main.rs:9"))?;
+    harness.assert_screen_contains("Calls from helper");
+    Ok(())
+}
+
+/// Children are only requested when a node is expanded, and Enter opens the
+/// item in the split the hierarchy was opened from
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_call_hierarchy_expand_and_open() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_hierarchy()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path());
+
+    harness.editor_mut().show_call_hierarchy();
+    harness.wait_until(|h| h.screen_to_string().contains("main.rs:2"))?;
+    harness.assert_screen_contains("▸ λ main");
+
+    // `main` has no callers: expanding it leaves no marker
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE)?;
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE)?;
+    harness.wait_until(|h| !h.screen_to_string().contains("▸"))?;
+
+    harness.send_key(KeyCode::Enter, KeyModifiers::NONE)?;
+    harness.assert_buffer_content(SOURCE);
+    assert_eq!(harness.cursor_position(), SOURCE.find("helper();").unwrap());
+    Ok(())
+}
+
+/// The type hierarchy opens with the subtypes of the type
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_type_hierarchy_subtypes() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_hierarchy()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path());
+
+    harness.editor_mut().show_type_hierarchy();
+    harness.wait_until(|h| h.screen_to_string().contains("main.rs:13"))?;
+    harness.assert_screen_contains("Subtypes of Shape");
+    harness.assert_screen_contains("S Circle");
+
+    harness.send_key(KeyCode::Char('t'), KeyModifiers::NONE)?;
+    harness.wait_until(|h| h.screen_to_string().contains("Supertypes of Shape"))?;
+    assert!(!harness.screen_to_string().contains("S Circle"));
+    Ok(())
+}
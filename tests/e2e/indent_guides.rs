@@ -0,0 +1,139 @@
+// End-to-end tests for indent guides
+
+use crate::common::harness::EditorTestHarness;
+use fresh::config::Config;
+use fresh::view::indent_guides::GUIDE_SYMBOL;
+
+const SOURCE: &str = "fn main() {\n    if ready {\n        go();\n\n        stop();\n    }\n}\n";
+
+fn harness_with(config: Config, file_name: &str) -> EditorTestHarness {
+    let mut harness = EditorTestHarness::with_temp_project_and_config(80, 24, config).unwrap();
+    let path = harness.project_dir().unwrap().join(file_name);
+    std::fs::write(&path, SOURCE).unwrap();
+    harness.open_file(&path).unwrap();
+    harness.render().unwrap();
+    harness
+}
+
+fn config_with_guides() -> Config {
+    let mut config = Config::default();
+    config.editor.indent_guides = true;
+    config
+}
+
+/// Screen row showing `text`, and the screen column where buffer column 0 is drawn
+fn find_row(harness: &EditorTestHarness, text: &str) -> (u16, u16) {
+    let (first_row, last_row) = harness.content_area_rows();
+    let row_with = |text: &str| {
+        (first_row as u16..=last_row as u16)
+            .find(|&row| harness.get_row_text(row).contains(text))
+            .unwrap_or_else(|| panic!("row with {text:?}"))
+    };
+    // `fn main() {` is not indented
+    let main_text = harness.get_row_text(row_with("fn main() {"));
+    let content_x = main_text[..main_text.find("fn main").unwrap()]
+        .chars()
+        .count() as u16;
+    (row_with(text), content_x)
+}
+
+/// Buffer columns of the row showing `text` that hold a guide
+fn guide_columns(harness: &EditorTestHarness, text: &str) -> Vec<u16> {
+    let (row, content_x) = find_row(harness, text);
+    guide_columns_at(harness, row, content_x)
+}
+
+fn guide_columns_at(harness: &EditorTestHarness, row: u16, content_x: u16) -> Vec<u16> {
+    let width = harness.buffer().area.width;
+    (content_x..width)
+        .filter(|&x| harness.get_cell(x, row).as_deref() == Some(GUIDE_SYMBOL))
+        .map(|x| x - content_x)
+        .collect()
+}
+
+#[test]
+fn test_indent_guides_off_by_default() {
+    let harness = harness_with(Config::default(), "main.rs");
+    assert!(guide_columns(&harness, "go();").is_empty());
+}
+
+/// Guides are drawn at every indentation level below the line's own,
+/// including on blank lines inside a block
+#[test]
+fn test_indent_guides_at_each_level() {
+    let harness = harness_with(config_with_guides(), "main.rs");
+    assert_eq!(guide_columns(&harness, "if ready"), vec![0]);
+    assert_eq!(guide_columns(&harness, "go();"), vec![0, 4]);
+
+    // The blank line between `go();` and `stop();`
+    let (row, content_x) = find_row(&harness, "go();");
+    assert_eq!(guide_columns_at(&harness, row + 1, content_x), vec![0, 4]);
+    // The closing brace of the `if` block
+    let (row, content_x) = find_row(&harness, "stop();");
+    assert_eq!(guide_columns_at(&harness, row + 1, content_x), vec![0]);
+}
+
+/// The guide of the block containing the cursor is highlighted
+#[test]
+fn test_active_indent_guide() {
+    let mut harness = harness_with(config_with_guides(), "main.rs");
+    // Cursor on `go();`, inside the `if` block
+    harness
+        .editor_mut()
+        .active_state_mut()
+        .cursors
+        .primary_mut()
+        .position = SOURCE.find("go();").unwrap();
+    harness.render().unwrap();
+
+    let theme = harness.editor().theme().clone();
+    let (row, content_x) = find_row(&harness, "go();");
+    let fg = |column: u16| {
+        harness
+            .get_cell_style(content_x + column, row)
+            .and_then(|style| style.fg)
+    };
+    assert_eq!(fg(4), Some(theme.indent_guide_active_fg));
+    assert_eq!(fg(0), Some(theme.indent_guide_fg));
+}
+
+/// Languages can turn guides on or off regardless of the global setting
+#[test]
+fn test_indent_guides_per_language() {
+    let mut config = Config::default();
+    config.languages.get_mut("rust").unwrap().indent_guides = Some(true);
+    let harness = harness_with(config.clone(), "main.rs");
+    assert_eq!(guide_columns(&harness, "go();"), vec![0, 4]);
+
+    let harness = harness_with(config, "main.txt");
+    assert!(guide_columns(&harness, "go();").is_empty());
+
+    let mut config = config_with_guides();
+    config.languages.get_mut("rust").unwrap().indent_guides = Some(false);
+    let harness = harness_with(config, "main.rs");
+    assert!(guide_columns(&harness, "go();").is_empty());
+}
+
+/// Wrapped continuation rows get no guides
+#[test]
+fn test_indent_guides_skip_wrapped_rows() {
+    let mut harness =
+        EditorTestHarness::with_temp_project_and_config(40, 24, config_with_guides()).unwrap();
+    let path = harness.project_dir().unwrap().join("wrap.rs");
+    let long_call = format!("        call({});\n", "argument, ".repeat(8));
+    std::fs::write(
+        &path,
+        format!("fn main() {{\n    {{\n{long_call}    }}\n}}\n"),
+    )
+    .unwrap();
+    harness.open_file(&path).unwrap();
+    harness.render().unwrap();
+
+    let (row, content_x) = find_row(&harness, "call(");
+    assert_eq!(guide_columns_at(&harness, row, content_x), vec![0, 4]);
+    let continuation = guide_columns_at(&harness, row + 1, content_x);
+    assert!(
+        continuation.is_empty(),
+        "continuation guides: {continuation:?}"
+    );
+}
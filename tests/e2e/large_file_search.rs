@@ -0,0 +1,134 @@
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use tempfile::TempDir;
+
+fn large_file_harness() -> EditorTestHarness {
+    EditorTestHarness::with_config(
+        100,
+        24,
+        fresh::config::Config {
+            editor: fresh::config::EditorConfig {
+                large_file_threshold_bytes: 1024,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+/// A log with NEEDLE on lines 1000 and 2500 (8 bytes per line)
+fn open_log(harness: &mut EditorTestHarness, temp_dir: &TempDir) {
+    let file_path = temp_dir.path().join("big.log");
+    let content: String = (1..=3000)
+        .map(|i| match i {
+            1000 | 2500 => "NEEDLE!\n".to_string(),
+            _ => format!("L{:06}\n", i),
+        })
+        .collect();
+    std::fs::write(&file_path, content).unwrap();
+
+    harness.open_file(&file_path).unwrap();
+    // Let line indexing finish so its status message doesn't race the search
+    harness
+        .wait_until(|h| h.editor().active_state().line_indexing.is_none())
+        .unwrap();
+}
+
+fn search(harness: &mut EditorTestHarness, query: &str) {
+    harness
+        .send_key(KeyCode::Char('f'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(query).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+}
+
+/// Searching a large file runs in the background and reports the match count
+#[test]
+fn test_large_file_search_finds_all_matches() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = large_file_harness();
+    open_log(&mut harness, &temp_dir);
+
+    search(&mut harness, "NEEDLE");
+    harness
+        .wait_until(|h| {
+            h.screen_to_string()
+                .contains("Found 2 matches for 'NEEDLE'")
+        })
+        .unwrap();
+
+    assert_eq!(harness.cursor_position(), 999 * 8);
+    harness.assert_screen_contains("Ln 1000, Col 1");
+
+    // F3 moves on to the second match
+    harness.send_key(KeyCode::F(3), KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.cursor_position(), 2499 * 8);
+}
+
+/// Without matches after the cursor, the search wraps to the first match
+#[test]
+fn test_large_file_search_wraps_to_first_match() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = large_file_harness();
+    open_log(&mut harness, &temp_dir);
+
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    search(&mut harness, "NEEDLE");
+    harness
+        .wait_until(|h| {
+            h.screen_to_string()
+                .contains("Found 2 matches for 'NEEDLE'")
+        })
+        .unwrap();
+    assert_eq!(harness.cursor_position(), 999 * 8);
+
+    search(&mut harness, "HAYSTACK");
+    harness
+        .wait_until(|h| {
+            h.screen_to_string()
+                .contains("No matches found for 'HAYSTACK'")
+        })
+        .unwrap();
+    assert_eq!(harness.cursor_position(), 999 * 8);
+}
+
+/// Only the matches in view are highlighted, following the viewport as it scrolls
+#[test]
+fn test_large_file_search_highlights_only_visible_matches() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = large_file_harness();
+    open_log(&mut harness, &temp_dir);
+
+    search(&mut harness, "L0");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("Found 2998 matches for 'L0'"))
+        .unwrap();
+    harness.render().unwrap();
+
+    let highlighted = |harness: &EditorTestHarness| -> Vec<usize> {
+        let state = harness.editor().active_state();
+        state
+            .overlays
+            .all()
+            .iter()
+            .filter_map(|overlay| state.marker_list.get_position(overlay.start_marker))
+            .collect()
+    };
+    let near_top = highlighted(&harness);
+    assert!(!near_top.is_empty() && near_top.len() < 30, "{near_top:?}");
+    assert!(near_top.iter().all(|&pos| pos < 40 * 8));
+
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+    let near_end = highlighted(&harness);
+    assert!(!near_end.is_empty() && near_end.len() < 30, "{near_end:?}");
+    assert!(near_end.iter().all(|&pos| pos > 2950 * 8));
+}
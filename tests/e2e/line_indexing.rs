@@ -0,0 +1,78 @@
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use tempfile::TempDir;
+
+fn large_file_harness() -> EditorTestHarness {
+    EditorTestHarness::with_config(
+        100,
+        24,
+        fresh::config::Config {
+            editor: fresh::config::EditorConfig {
+                large_file_threshold_bytes: 1024,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+/// Short lines make estimated line numbers (80 bytes per line) far off, so
+/// Go To Line only lands on the right line once the file is indexed
+#[test]
+fn test_goto_line_is_exact_after_indexing() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("big.log");
+    let content: String = (1..=3000).map(|i| format!("L{:06}\n", i)).collect();
+    std::fs::write(&file_path, content).unwrap();
+
+    let mut harness = large_file_harness();
+    harness.open_file(&file_path).unwrap();
+    harness
+        .wait_until(|h| h.editor().active_state().buffer.line_count().is_some())
+        .unwrap();
+    assert!(harness.editor().active_state().line_indexing.is_none());
+    harness.assert_screen_contains("Indexed 3001 lines");
+
+    harness
+        .send_key(KeyCode::Char('g'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("2000").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    let position = harness.cursor_position();
+    assert_eq!(position, 1999 * 8);
+    harness.assert_screen_contains("Ln 2000, Col 1");
+    harness.assert_screen_contains("L002000");
+}
+
+/// The stop command reports when no scan is running
+#[test]
+fn test_stop_line_indexing_when_done() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("big.log");
+    std::fs::write(&file_path, "line\n".repeat(1000)).unwrap();
+
+    let mut harness = large_file_harness();
+    harness.open_file(&file_path).unwrap();
+    harness
+        .wait_until(|h| h.editor().active_state().line_indexing.is_none())
+        .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Stop Line Indexing").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("No line indexing in progress");
+    assert_eq!(
+        harness.editor().active_state().buffer.line_count(),
+        Some(1001)
+    );
+}
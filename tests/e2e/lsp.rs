@@ -4897,7 +4897,22 @@ fn test_completion_snippet_with_default() -> anyhow::Result<()> {
         "Snippet should expand with default text"
     );
 
-    // Verify cursor is at $0 position (after the 4 spaces on line 2)
+    // The first tabstop's default text is selected
+    let selection = harness
+        .editor()
+        .active_state()
+        .cursors
+        .primary()
+        .selection_range();
+    assert_eq!(selection, Some(3..7), "Tabstop $1 should be selected");
+
+    // Tab moves through $2 to the $0 position (after the 4 spaces on line 2)
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE)?;
+    assert_eq!(
+        harness.editor().active_state().cursors.primary().position,
+        8
+    );
+    harness.send_key(KeyCode::Tab, KeyModifiers::NONE)?;
     let cursor_pos = harness.editor().active_state().cursors.primary().position;
     assert_eq!(cursor_pos, 16, "Cursor should be at $0 position");
 
@@ -0,0 +1,149 @@
+// End-to-end tests for formatting through the language server
+
+use crate::common::fake_lsp::FakeLspServer;
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::{Config, FormatterSource};
+use fresh::services::lsp::LspServerConfig;
+use fresh::services::process_limits::ProcessLimits;
+use std::path::Path;
+
+const SOURCE: &str = "fn main() {\nlet x = 1;\nlet y = 2;\n}\n";
+
+/// A Rust file formatted by the fake formatting server
+fn harness_with_server(
+    temp_dir: &Path,
+    source: &str,
+    configure: impl FnOnce(&mut Config),
+) -> EditorTestHarness {
+    let path = temp_dir.join("main.rs");
+    std::fs::write(&path, source).unwrap();
+
+    let mut config = Config::default();
+    config.lsp.insert(
+        "rust".to_string(),
+        LspServerConfig {
+            command: FakeLspServer::formatting_script_path()
+                .to_string_lossy()
+                .to_string(),
+            args: vec![],
+            enabled: true,
+            auto_start: true,
+            process_limits: ProcessLimits::default(),
+            initialization_options: None,
+        },
+    );
+    let rust = config.languages.get_mut("rust").unwrap();
+    rust.formatter_source = FormatterSource::Lsp;
+    configure(&mut config);
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 24, config, temp_dir.to_path_buf())
+            .unwrap();
+    harness.open_file(&path).unwrap();
+    harness
+        .wait_until(|h| h.editor().is_lsp_server_ready("rust"))
+        .unwrap();
+    harness
+}
+
+/// "Format Buffer" applies the server's edits as one undo step, and the cursor
+/// stays on the same character
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_lsp_format_buffer() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_formatting()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path(), SOURCE, |_| {});
+
+    // Cursor on the "x" of the line the server indents
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE)?;
+    harness.send_key_repeat(KeyCode::Right, KeyModifiers::NONE, 4)?;
+    assert_eq!(harness.cursor_position(), SOURCE.find('x').unwrap());
+
+    harness.editor_mut().format_buffer().unwrap();
+    harness.wait_until(|h| h.get_buffer_content().unwrap().contains("    let x"))?;
+
+    let formatted = "fn main() {\n    let x = 1;\nlet y = 2;\n}\n";
+    harness.assert_buffer_content(formatted);
+    assert_eq!(harness.cursor_position(), formatted.find('x').unwrap());
+    harness.render()?;
+    harness.assert_screen_contains("Formatted with the language server");
+
+    harness.send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)?;
+    harness.assert_buffer_content(SOURCE);
+    Ok(())
+}
+
+/// "Format Selection" sends the selected range
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_lsp_format_selection() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_formatting()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path(), SOURCE, |_| {});
+
+    // Without a selection nothing is sent
+    assert!(harness.editor_mut().format_selection().is_err());
+
+    harness.send_key_repeat(KeyCode::Down, KeyModifiers::NONE, 2)?;
+    harness.send_key(KeyCode::End, KeyModifiers::SHIFT)?;
+    harness.editor_mut().format_selection().unwrap();
+    harness.wait_until(|h| h.get_buffer_content().unwrap().contains("    let y"))?;
+    harness.assert_buffer_content("fn main() {\nlet x = 1;\n    let y = 2;\n}\n");
+    Ok(())
+}
+
+/// Typing one of the server's trigger characters reformats the line when
+/// `format_on_type` is enabled
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_lsp_format_on_type() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_formatting()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path(), "fn main() {\nlet x = 1\n}\n", |c| {
+        c.languages.get_mut("rust").unwrap().format_on_type = true;
+    });
+
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE)?;
+    harness.send_key(KeyCode::End, KeyModifiers::NONE)?;
+    harness.type_text(";")?;
+    harness.wait_until(|h| h.get_buffer_content().unwrap().contains("    let x"))?;
+
+    let formatted = "fn main() {\n    let x = 1;\n}\n";
+    harness.assert_buffer_content(formatted);
+    assert_eq!(harness.cursor_position(), formatted.find(';').unwrap() + 1);
+    Ok(())
+}
+
+/// Format on save through the server saves the formatted buffer again
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_lsp_format_on_save() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_formatting()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path(), SOURCE, |c| {
+        c.languages.get_mut("rust").unwrap().format_on_save = true;
+    });
+
+    harness.send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)?;
+    let path = temp_dir.path().join("main.rs");
+    harness.wait_until(|_| {
+        std::fs::read_to_string(&path).is_ok_and(|content| content.contains("    let x"))
+    })?;
+    harness.wait_until(|h| !h.editor().active_state().buffer.is_modified())?;
+    harness.assert_buffer_content("fn main() {\n    let x = 1;\nlet y = 2;\n}\n");
+    Ok(())
+}
@@ -0,0 +1,124 @@
+// End-to-end tests for go to definition, type definition, implementation and
+// declaration
+
+use crate::common::fake_lsp::FakeLspServer;
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use fresh::services::lsp::LspServerConfig;
+use fresh::services::process_limits::ProcessLimits;
+use std::path::Path;
+
+/// Laid out to match the locations of the fake "go to" server
+const SOURCE: &str = "struct Point;\ntrait Shape {}\nimpl Shape for Point {}\nstruct Circle;\nimpl Shape for Circle {}\nfn main() {\n    let p = Point;\n}\n";
+
+/// A Rust file served by the fake "go to" server, with the cursor on `Point`
+/// in `main`
+fn harness_with_server(temp_dir: &Path) -> EditorTestHarness {
+    let path = temp_dir.join("main.rs");
+    std::fs::write(&path, SOURCE).unwrap();
+
+    let mut config = Config::default();
+    config.lsp.insert(
+        "rust".to_string(),
+        LspServerConfig {
+            command: FakeLspServer::goto_script_path()
+                .to_string_lossy()
+                .to_string(),
+            args: vec![],
+            enabled: true,
+            auto_start: true,
+            process_limits: ProcessLimits::default(),
+            initialization_options: None,
+        },
+    );
+
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 24, config, temp_dir.to_path_buf())
+            .unwrap();
+    harness.open_file(&path).unwrap();
+    harness
+        .wait_until(|h| h.editor().is_lsp_server_ready("rust"))
+        .unwrap();
+    harness
+        .send_key_repeat(KeyCode::Down, KeyModifiers::NONE, 6)
+        .unwrap();
+    harness
+        .send_key_repeat(KeyCode::Right, KeyModifiers::NONE, 12)
+        .unwrap();
+    assert_eq!(harness.cursor_position(), origin());
+    harness
+}
+
+/// Where the requests are made from
+fn origin() -> usize {
+    SOURCE.find("Point;\n}").unwrap()
+}
+
+/// A single location is jumped to, and navigating back returns to the origin
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_goto_type_definition_and_back() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_goto()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path());
+
+    let target = SOURCE.find("Shape").unwrap();
+    harness.send_key(KeyCode::F(12), KeyModifiers::CONTROL | KeyModifiers::SHIFT)?;
+    harness.wait_until(|h| h.cursor_position() == target)?;
+    harness.render()?;
+    harness.assert_screen_contains("Jumped to type definition");
+
+    harness.editor_mut().navigate_back();
+    assert_eq!(harness.cursor_position(), origin());
+    Ok(())
+}
+
+/// Several locations open a picker showing the code at each of them
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_goto_implementation_picker() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_goto()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path());
+
+    harness.send_key(KeyCode::F(12), KeyModifiers::CONTROL)?;
+    harness.wait_until(|h| h.screen_to_string().contains("Implementations (2)"))?;
+    harness.assert_screen_contains("impl Shape for Point {}");
+    harness.assert_screen_contains("main.rs:3");
+    harness.assert_screen_contains("main.rs:5");
+
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE)?;
+    harness.send_key(KeyCode::Enter, KeyModifiers::NONE)?;
+    assert_eq!(
+        harness.cursor_position(),
+        SOURCE.find("impl Shape for Circle").unwrap()
+    );
+
+    harness.editor_mut().navigate_back();
+    assert_eq!(harness.cursor_position(), origin());
+    Ok(())
+}
+
+/// An empty answer leaves the cursor and reports it
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_goto_declaration_not_found() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_goto()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_server(temp_dir.path());
+
+    harness.send_key(KeyCode::F(12), KeyModifiers::ALT)?;
+    harness.wait_until(|h| h.screen_to_string().contains("No declaration found"))?;
+    assert_eq!(harness.cursor_position(), origin());
+    Ok(())
+}
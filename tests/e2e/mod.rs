@@ -3,26 +3,41 @@ pub mod auto_indent;
 pub mod auto_revert;
 pub mod basic;
 pub mod binary_file;
+pub mod bracket_pairs;
 pub mod buffer_lifecycle;
 pub mod buffer_settings_commands;
 pub mod case_conversion;
+pub mod code_lens;
 pub mod command_palette;
+pub mod compressed_files;
 pub mod crash_repro;
 pub mod crlf_rendering;
 pub mod document_model;
+pub mod editorconfig;
 pub mod emacs_actions;
+pub mod encoding;
 pub mod explorer_menu;
 pub mod file_browser;
 pub mod file_explorer;
+pub mod file_history;
 pub mod file_permissions;
+pub mod folding;
+pub mod follow_mode;
+pub mod hex_view;
+pub mod hierarchy;
 pub mod indent_dedent;
+pub mod indent_guides;
 pub mod language_features_e2e;
 pub mod large_file_mode;
+pub mod large_file_search;
 pub mod lifecycle;
+pub mod line_indexing;
 pub mod line_wrapping;
 pub mod live_grep;
 pub mod locale;
 pub mod lsp;
+pub mod lsp_formatting;
+pub mod lsp_goto;
 pub mod lsp_order;
 pub mod margin;
 pub mod markdown_compose;
@@ -35,6 +50,7 @@ pub mod multibyte_characters;
 pub mod multicursor;
 pub mod on_save_actions;
 pub mod open_folder;
+pub mod outline;
 pub mod paste;
 #[cfg(feature = "plugins")]
 pub mod plugins;
@@ -42,12 +58,15 @@ pub mod position_history;
 pub mod position_history_bugs;
 pub mod position_history_debug;
 pub mod position_history_truncate_debug;
+pub mod project_search;
 pub mod prompt;
 pub mod prompt_editing;
 pub mod recovery;
 pub mod rendering;
 pub mod save_as_language_detection;
+pub mod save_strategy;
 pub mod scroll_clearing;
+pub mod scrollbar_marks;
 pub mod scrolling;
 pub mod search;
 pub mod selection;
@@ -57,10 +76,12 @@ pub mod settings_paste;
 pub mod shell_command;
 pub mod slow_filesystem;
 pub mod smart_editing;
+pub mod snippets;
 pub mod split_tabs;
 pub mod split_view;
 pub mod split_view_expectations;
 pub mod stdin_input;
+pub mod sticky_scroll;
 pub mod tab_config;
 pub mod tab_drag;
 pub mod tab_indent_selection;
@@ -81,3 +102,4 @@ pub mod vi_mode;
 pub mod virtual_lines;
 pub mod visual_regression;
 pub mod warning_indicators;
+pub mod workspace_symbols;
@@ -7,10 +7,13 @@
 //! - $FILE placeholder substitution
 //! - Timeout handling
 //! - Action failure handling
+//! - Built-in transforms (whitespace, blank lines, final newline, line endings)
 
 use crate::common::harness::EditorTestHarness;
 use crossterm::event::{KeyCode, KeyModifiers};
-use fresh::config::{Config, FormatterConfig, LanguageConfig, OnSaveAction};
+use fresh::config::{
+    Config, FinalNewlineOption, FormatterConfig, FormatterSource, LanguageConfig, OnSaveAction,
+};
 use tempfile::TempDir;
 
 /// Test format_on_save with formatter (replaces buffer content)
@@ -49,7 +52,17 @@ fn test_format_on_save() {
             tab_size: None,
             formatter: Some(formatter),
             format_on_save: true,
+            formatter_source: FormatterSource::Auto,
+            format_on_type: false,
             on_save: vec![],
+            save_strategy: None,
+            trim_trailing_whitespace: false,
+            trim_modified_lines_only: false,
+            trim_trailing_blank_lines: false,
+            final_newline: FinalNewlineOption::Keep,
+            normalize_line_endings: false,
+            indent_guides: None,
+            bracket_pair_colorization: None,
         },
     );
 
@@ -112,7 +125,17 @@ fn test_on_save_linter_style() {
             tab_size: None,
             formatter: None,
             format_on_save: false,
+            formatter_source: FormatterSource::Auto,
+            format_on_type: false,
             on_save: vec![action],
+            save_strategy: None,
+            trim_trailing_whitespace: false,
+            trim_modified_lines_only: false,
+            trim_trailing_blank_lines: false,
+            final_newline: FinalNewlineOption::Keep,
+            normalize_line_endings: false,
+            indent_guides: None,
+            bracket_pair_colorization: None,
         },
     );
 
@@ -175,7 +198,17 @@ fn test_on_save_action_failure() {
             tab_size: None,
             formatter: None,
             format_on_save: false,
+            formatter_source: FormatterSource::Auto,
+            format_on_type: false,
             on_save: vec![action],
+            save_strategy: None,
+            trim_trailing_whitespace: false,
+            trim_modified_lines_only: false,
+            trim_trailing_blank_lines: false,
+            final_newline: FinalNewlineOption::Keep,
+            normalize_line_endings: false,
+            indent_guides: None,
+            bracket_pair_colorization: None,
         },
     );
 
@@ -247,7 +280,17 @@ fn test_on_save_file_placeholder() {
             tab_size: None,
             formatter: None,
             format_on_save: false,
+            formatter_source: FormatterSource::Auto,
+            format_on_type: false,
             on_save: vec![action],
+            save_strategy: None,
+            trim_trailing_whitespace: false,
+            trim_modified_lines_only: false,
+            trim_trailing_blank_lines: false,
+            final_newline: FinalNewlineOption::Keep,
+            normalize_line_endings: false,
+            indent_guides: None,
+            bracket_pair_colorization: None,
         },
     );
 
@@ -313,7 +356,17 @@ fn test_formatter_stdin_mode() {
             tab_size: None,
             formatter: Some(formatter),
             format_on_save: true,
+            formatter_source: FormatterSource::Auto,
+            format_on_type: false,
             on_save: vec![],
+            save_strategy: None,
+            trim_trailing_whitespace: false,
+            trim_modified_lines_only: false,
+            trim_trailing_blank_lines: false,
+            final_newline: FinalNewlineOption::Keep,
+            normalize_line_endings: false,
+            indent_guides: None,
+            bracket_pair_colorization: None,
         },
     );
 
@@ -386,7 +439,17 @@ fn test_on_save_stops_on_failure() {
             tab_size: None,
             formatter: None,
             format_on_save: false,
+            formatter_source: FormatterSource::Auto,
+            format_on_type: false,
             on_save: vec![action1, action2],
+            save_strategy: None,
+            trim_trailing_whitespace: false,
+            trim_modified_lines_only: false,
+            trim_trailing_blank_lines: false,
+            final_newline: FinalNewlineOption::Keep,
+            normalize_line_endings: false,
+            indent_guides: None,
+            bracket_pair_colorization: None,
         },
     );
 
@@ -480,7 +543,17 @@ fn test_formatter_not_found_shows_message() {
             tab_size: None,
             formatter: Some(formatter),
             format_on_save: true,
+            formatter_source: FormatterSource::Auto,
+            format_on_type: false,
             on_save: vec![],
+            save_strategy: None,
+            trim_trailing_whitespace: false,
+            trim_modified_lines_only: false,
+            trim_trailing_blank_lines: false,
+            final_newline: FinalNewlineOption::Keep,
+            normalize_line_endings: false,
+            indent_guides: None,
+            bracket_pair_colorization: None,
         },
     );
 
@@ -506,3 +579,98 @@ fn test_formatter_not_found_shows_message() {
     // Should show a message about missing formatter
     harness.assert_screen_contains("Formatter");
 }
+
+/// Config applying the given built-in transforms to `.txt` files
+fn transforms_config(language: LanguageConfig) -> Config {
+    let mut config = Config::default();
+    config.languages.insert(
+        "plaintext".to_string(),
+        LanguageConfig {
+            extensions: vec!["txt".to_string()],
+            grammar: "plaintext".to_string(),
+            ..language
+        },
+    );
+    config
+}
+
+/// Built-in transforms run before writing, keep the cursor on its text,
+/// and are undoable
+#[test]
+fn test_builtin_save_transforms() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    std::fs::write(&file_path, "a  \nb\r\nc\nd\n\n  \n").unwrap();
+
+    let config = transforms_config(LanguageConfig {
+        trim_trailing_whitespace: true,
+        trim_trailing_blank_lines: true,
+        normalize_line_endings: true,
+        ..Default::default()
+    });
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    // Cursor at the start of "c"
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    assert_eq!(harness.cursor_position(), 7);
+
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "a\nb\nc\nd\n");
+    harness.assert_buffer_content("a\nb\nc\nd\n");
+    assert_eq!(harness.cursor_position(), 4);
+
+    // Each transform is undone on its own
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content("a\nb\nc\nd\n\n\n");
+}
+
+/// Trailing whitespace can be limited to lines changed since the last save
+#[test]
+fn test_trim_modified_lines_only() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    std::fs::write(&file_path, "old  \nnew\n").unwrap();
+
+    let config = transforms_config(LanguageConfig {
+        trim_trailing_whitespace: true,
+        trim_modified_lines_only: true,
+        ..Default::default()
+    });
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+    harness.type_text("  ").unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "old  \nnew\n");
+}
+
+/// The final newline can be stripped on save
+#[test]
+fn test_strip_final_newline() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    std::fs::write(&file_path, "text\n").unwrap();
+
+    let config = transforms_config(LanguageConfig {
+        final_newline: FinalNewlineOption::Strip,
+        ..Default::default()
+    });
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "text");
+}
@@ -0,0 +1,164 @@
+// End-to-end tests for the outline panel and breadcrumbs
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+
+const SOURCE: &str = "mod parser {
+    pub fn parse_expr() {}
+
+    pub fn peek() {}
+}
+
+fn main() {
+    let answer = 42;
+}
+";
+
+fn harness_with(breadcrumbs: bool) -> EditorTestHarness {
+    let mut config = Config::default();
+    config.editor.breadcrumbs = breadcrumbs;
+    let mut harness = EditorTestHarness::with_temp_project_and_config(100, 24, config).unwrap();
+    let path = harness.project_dir().unwrap().join("lib.rs");
+    std::fs::write(&path, SOURCE).unwrap();
+    harness.open_file(&path).unwrap();
+    harness.render().unwrap();
+    harness
+}
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+fn press(harness: &mut EditorTestHarness, code: KeyCode) {
+    harness.send_key(code, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+}
+
+/// Screen position of `text` in columns `from..`
+fn find_on_screen(harness: &EditorTestHarness, text: &str, from: usize) -> Option<(u16, u16)> {
+    harness
+        .screen_to_string()
+        .lines()
+        .enumerate()
+        .find_map(|(row, line)| {
+            let chars: Vec<char> = line.chars().collect();
+            let tail: String = chars.iter().skip(from).collect();
+            tail.find(text)
+                .map(|byte| (from + tail[..byte].chars().count(), row))
+        })
+        .map(|(col, row)| (col as u16, row as u16))
+}
+
+/// Whether the outline panel (the right quarter of the screen) shows `text`
+fn outline_contains(harness: &EditorTestHarness, text: &str) -> bool {
+    find_on_screen(harness, text, 75).is_some()
+}
+
+/// The panel lists the tree-sitter symbols as a tree, and its selection
+/// follows the cursor
+#[test]
+fn test_outline_lists_symbols_and_follows_cursor() {
+    let mut harness = harness_with(false);
+    run_command(&mut harness, "Toggle Outline");
+    harness.assert_screen_contains("Outline");
+    harness.assert_screen_contains("▼ mod parser");
+    assert!(outline_contains(&harness, "method parse_expr"));
+    assert!(outline_contains(&harness, "method peek"));
+    assert!(outline_contains(&harness, "fn main"));
+
+    // Back to the editor, then move into `main`
+    press(&mut harness, KeyCode::Esc);
+    for _ in 0..7 {
+        press(&mut harness, KeyCode::Down);
+    }
+    let (col, row) = find_on_screen(&harness, "main", 75).expect("main in the outline");
+    let current_line_bg = harness.editor().theme().current_line_bg;
+    let bg = harness.get_cell_style(col, row).unwrap().bg;
+    assert_eq!(bg, Some(current_line_bg));
+    let (col, row) = find_on_screen(&harness, "peek", 75).unwrap();
+    assert_ne!(
+        harness.get_cell_style(col, row).unwrap().bg,
+        Some(current_line_bg)
+    );
+
+    run_command(&mut harness, "Toggle Outline");
+    harness.assert_screen_not_contains("▼ mod parser");
+}
+
+/// Typing filters the symbols fuzzily and Enter jumps to the selected one
+#[test]
+fn test_outline_filter_and_jump() {
+    let mut harness = harness_with(false);
+    run_command(&mut harness, "Toggle Outline");
+    harness.type_text("pk").unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Outline: pk");
+    assert!(outline_contains(&harness, "peek"));
+    assert!(!outline_contains(&harness, "parse_expr"));
+    assert!(!outline_contains(&harness, "main"));
+
+    press(&mut harness, KeyCode::Enter);
+    assert_eq!(harness.cursor_position(), SOURCE.find("peek").unwrap());
+
+    // Focus is back in the editor
+    harness.type_text("x").unwrap();
+    assert!(harness.get_buffer_content().unwrap().contains("xpeek"));
+}
+
+/// Left collapses the selected symbol and Right expands it again
+#[test]
+fn test_outline_collapse_and_expand() {
+    let mut harness = harness_with(false);
+    run_command(&mut harness, "Toggle Outline");
+    press(&mut harness, KeyCode::Left);
+    harness.assert_screen_contains("> mod parser");
+    assert!(!outline_contains(&harness, "parse_expr"));
+    assert!(outline_contains(&harness, "main"));
+
+    press(&mut harness, KeyCode::Right);
+    harness.assert_screen_contains("▼ mod parser");
+    assert!(outline_contains(&harness, "parse_expr"));
+}
+
+/// The breadcrumb bar shows the path to the symbol at the cursor, and a
+/// crumb opens a picker for its siblings
+#[test]
+fn test_breadcrumbs_pick_sibling() {
+    let mut harness = harness_with(true);
+    for _ in 0..3 {
+        press(&mut harness, KeyCode::Down);
+    }
+    press(&mut harness, KeyCode::End);
+    harness.assert_screen_contains("lib.rs › parser › peek");
+
+    let (col, row) = find_on_screen(&harness, "peek", 0).unwrap();
+    harness.mouse_click(col, row).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Go to symbol:");
+
+    press(&mut harness, KeyCode::Up);
+    press(&mut harness, KeyCode::Enter);
+    assert_eq!(
+        harness.cursor_position(),
+        SOURCE.find("parse_expr").unwrap()
+    );
+    harness.assert_screen_contains("lib.rs › parser › parse_expr");
+}
+
+/// Without breadcrumbs the content starts right below the tabs
+#[test]
+fn test_breadcrumbs_off_by_default() {
+    let harness = harness_with(false);
+    harness.assert_screen_not_contains("lib.rs › ");
+    let (first_row, _) = harness.content_area_rows();
+    assert!(harness.get_screen_row(first_row).contains("mod parser {"));
+}
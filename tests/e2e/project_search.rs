@@ -0,0 +1,216 @@
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use tempfile::TempDir;
+
+/// A project with two files containing "foo", plus an ignored one
+fn project() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    std::fs::write(root.join("a.txt"), "foo one\nfoo two\n").unwrap();
+    std::fs::write(root.join("b.txt"), "foo three\n").unwrap();
+    std::fs::write(root.join("skip.log"), "foo ignored\n").unwrap();
+    std::fs::write(root.join(".ignore"), "*.log\n").unwrap();
+    temp_dir
+}
+
+fn search_project(harness: &mut EditorTestHarness, query: &str) {
+    harness
+        .send_key(
+            KeyCode::Char('f'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        )
+        .unwrap();
+    harness.type_text(query).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+}
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// Matches are listed by file, skipping ignored files, and Enter opens one
+#[test]
+fn test_project_search_lists_matches_by_file() {
+    let temp_dir = project();
+    let root = temp_dir.path().to_path_buf();
+    let mut harness = EditorTestHarness::with_working_dir(120, 30, root.clone()).unwrap();
+
+    search_project(&mut harness, "foo");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("3 matches in 2 files"))
+        .unwrap();
+    harness.assert_screen_contains("a.txt (2)");
+    harness.assert_screen_contains("b.txt (1)");
+    harness.assert_screen_contains("2: foo two");
+    assert!(!harness.screen_to_string().contains("skip.log"));
+
+    // Move to the second match of a.txt: title, status, help, blank, a.txt, 1
+    harness
+        .send_key_repeat(KeyCode::Down, KeyModifiers::NONE, 6)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+
+    let path = harness
+        .editor()
+        .active_state()
+        .buffer
+        .file_path()
+        .map(|p| p.to_path_buf());
+    assert_eq!(path, Some(root.join("a.txt").canonicalize().unwrap()));
+    assert_eq!(harness.cursor_position(), "foo one\n".len());
+}
+
+/// Replacing previews each match, skips deselected ones, edits open buffers
+/// (keeping their unsaved changes) and files on disk, and undoes as a whole
+#[test]
+fn test_project_replace_applies_to_buffers_and_files_and_undoes() {
+    let temp_dir = project();
+    let root = temp_dir.path().to_path_buf();
+    let mut harness = EditorTestHarness::with_working_dir(120, 30, root.clone()).unwrap();
+
+    // b.txt is open with an unsaved edit that adds a match
+    harness.open_file(&root.join("b.txt")).unwrap();
+    harness.type_text("foo ").unwrap();
+    harness.assert_buffer_content("foo foo three\n");
+
+    search_project(&mut harness, "foo");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("4 matches in 2 files"))
+        .unwrap();
+
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("bar").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("[x]     1: foo one");
+    harness.assert_screen_contains("bar one");
+
+    // Leave out the second match of a.txt: title, replacement, status, help,
+    // blank, a.txt, then the first match and its preview
+    harness
+        .send_key_repeat(KeyCode::Down, KeyModifiers::NONE, 8)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char(' '), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("[ ]     2: foo two");
+
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("1 matches in 1 files"))
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(root.join("a.txt")).unwrap(),
+        "bar one\nfoo two\n"
+    );
+    // The open file is edited in its buffer only
+    assert_eq!(
+        std::fs::read_to_string(root.join("b.txt")).unwrap(),
+        "foo three\n"
+    );
+    let b_path = root.join("b.txt");
+    harness.open_file(&b_path).unwrap();
+    harness.assert_buffer_content("bar bar three\n");
+
+    run_command(&mut harness, "Undo Project Replace");
+    assert_eq!(
+        std::fs::read_to_string(root.join("a.txt")).unwrap(),
+        "foo one\nfoo two\n"
+    );
+    harness.assert_buffer_content("foo foo three\n");
+}
+
+/// Files that are not open are saved in their own encoding, BOM included
+#[test]
+fn test_project_replace_keeps_file_encoding() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().to_path_buf();
+    let latin1 = b"Gr\xFC\xDFe aus K\xF6ln, foo f\xFCr alle\n";
+    std::fs::write(root.join("latin1.txt"), latin1).unwrap();
+    std::fs::write(root.join("bom.txt"), b"\xEF\xBB\xBFfoo \xC3\xA9t\xC3\xA9\n").unwrap();
+    let mut harness = EditorTestHarness::with_working_dir(120, 30, root.clone()).unwrap();
+
+    search_project(&mut harness, "foo");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("2 matches in 2 files"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("bär").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("No matches"))
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read(root.join("latin1.txt")).unwrap(),
+        b"Gr\xFC\xDFe aus K\xF6ln, b\xE4r f\xFCr alle\n"
+    );
+    assert_eq!(
+        std::fs::read(root.join("bom.txt")).unwrap(),
+        "\u{feff}bär été\n".as_bytes()
+    );
+
+    run_command(&mut harness, "Undo Project Replace");
+    assert_eq!(std::fs::read(root.join("latin1.txt")).unwrap(), latin1);
+}
+
+/// Files rewritten on disk by a project replace get a local history snapshot
+#[test]
+fn test_project_replace_records_file_history() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().to_path_buf();
+    let file_path = root.join("notes.txt");
+    std::fs::write(&file_path, "foo bar\n").unwrap();
+    let mut harness = EditorTestHarness::with_working_dir(120, 30, root).unwrap();
+
+    search_project(&mut harness, "foo");
+    harness
+        .wait_until(|h| h.screen_to_string().contains("1 matches in 1 files"))
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('r'), KeyModifiers::NONE)
+        .unwrap();
+    harness.type_text("baz").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .send_key(KeyCode::Char('a'), KeyModifiers::NONE)
+        .unwrap();
+    harness
+        .wait_until(|h| h.screen_to_string().contains("No matches"))
+        .unwrap();
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "baz bar\n");
+
+    harness.open_file(&file_path).unwrap();
+    run_command(&mut harness, "File History");
+    harness.assert_screen_contains("File history:");
+}
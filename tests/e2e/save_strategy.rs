@@ -0,0 +1,85 @@
+// End-to-end tests for save strategies and backup files
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::{BackupOption, Config, SaveStrategyOption};
+use std::path::Path;
+use tempfile::TempDir;
+
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+
+fn save_and_wait(harness: &mut EditorTestHarness, path: &Path, expected: &str) {
+    harness
+        .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+        .unwrap();
+    let path = path.to_path_buf();
+    let expected = expected.to_string();
+    harness
+        .wait_until(move |_| std::fs::read_to_string(&path).is_ok_and(|s| s == expected))
+        .unwrap();
+}
+
+/// An in-place save keeps the file's inode and hard links, and the previous
+/// version is kept next to it
+#[test]
+#[cfg(unix)]
+fn test_in_place_save_keeps_inode_and_writes_adjacent_backup() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("deploy.conf");
+    let link_path = temp_dir.path().join("linked.conf");
+    std::fs::write(&file_path, "replicas = 1\n").unwrap();
+    std::fs::hard_link(&file_path, &link_path).unwrap();
+    let inode = std::fs::metadata(&file_path).unwrap().ino();
+
+    let mut config = Config::default();
+    config.editor.save_strategy = SaveStrategyOption::InPlace;
+    config.editor.backup = BackupOption::Adjacent;
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.type_text("# v2\n").unwrap();
+    save_and_wait(&mut harness, &file_path, "# v2\nreplicas = 1\n");
+
+    assert_eq!(std::fs::metadata(&file_path).unwrap().ino(), inode);
+    assert_eq!(
+        std::fs::read_to_string(&link_path).unwrap(),
+        "# v2\nreplicas = 1\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("deploy.conf~")).unwrap(),
+        "replicas = 1\n"
+    );
+}
+
+/// A language's save strategy overrides the global one, and directory backups
+/// keep numbered versions
+#[test]
+#[cfg(unix)]
+fn test_language_save_strategy_and_numbered_backups() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("main.rs");
+    let backup_dir = temp_dir.path().join("backups");
+    std::fs::write(&file_path, "fn main() {}\n").unwrap();
+    let inode = std::fs::metadata(&file_path).unwrap().ino();
+
+    let mut config = Config::default();
+    config.editor.backup = BackupOption::Directory;
+    config.editor.backup_directory = Some(backup_dir.clone());
+    config.editor.backup_count = 2;
+    config.languages.get_mut("rust").unwrap().save_strategy = Some(SaveStrategyOption::Copy);
+    let mut harness = EditorTestHarness::with_config(80, 24, config).unwrap();
+    harness.open_file(&file_path).unwrap();
+
+    harness.type_text("//").unwrap();
+    save_and_wait(&mut harness, &file_path, "//fn main() {}\n");
+    harness.type_text("!").unwrap();
+    save_and_wait(&mut harness, &file_path, "//!fn main() {}\n");
+
+    assert_eq!(std::fs::metadata(&file_path).unwrap().ino(), inode);
+    let mut backups: Vec<String> = std::fs::read_dir(&backup_dir)
+        .unwrap()
+        .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    backups.sort();
+    assert_eq!(backups, vec!["//fn main() {}\n", "fn main() {}\n"]);
+}
@@ -0,0 +1,138 @@
+// End-to-end tests for marks on the scrollbar track
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use fresh::view::scrollbar_marks::MARK_SYMBOL;
+use ratatui::style::Color;
+
+/// Column of the scrollbar in an 80-wide terminal
+const SCROLLBAR_COLUMN: u16 = 79;
+
+fn content(lines: usize) -> String {
+    (0..lines).map(|i| format!("line {i}\n")).collect()
+}
+
+fn harness_with(config: Config, lines: usize) -> EditorTestHarness {
+    let mut harness = EditorTestHarness::with_temp_project_and_config(80, 24, config).unwrap();
+    let path = harness.project_dir().unwrap().join("long.txt");
+    std::fs::write(&path, content(lines)).unwrap();
+    harness.open_file(&path).unwrap();
+    harness.render().unwrap();
+    harness
+}
+
+/// Byte offset of the start of `line` in `content`
+fn line_start(line: usize) -> usize {
+    content(line).len()
+}
+
+/// Screen rows of the track holding a mark drawn in `color`
+fn mark_rows(harness: &EditorTestHarness, color: Color) -> Vec<u16> {
+    let (first_row, last_row) = harness.content_area_rows();
+    (first_row as u16..=last_row as u16)
+        .filter(|&row| {
+            harness.get_cell(SCROLLBAR_COLUMN, row).as_deref() == Some(MARK_SYMBOL)
+                && harness
+                    .get_cell_style(SCROLLBAR_COLUMN, row)
+                    .and_then(|style| style.fg)
+                    == Some(color)
+        })
+        .collect()
+}
+
+fn add_plugin_mark(harness: &mut EditorTestHarness, position: usize, color: Color) {
+    add_plugin_span(harness, position..position, color);
+}
+
+fn add_plugin_span(harness: &mut EditorTestHarness, range: std::ops::Range<usize>, color: Color) {
+    let state = harness.editor_mut().active_state_mut();
+    state
+        .scrollbar_marks
+        .add(&mut state.marker_list, "test".to_string(), range, color, 5);
+    harness.render().unwrap();
+}
+
+/// Clicking a plugin mark moves the cursor there and scrolls it into view
+#[test]
+fn test_click_plugin_mark_jumps_to_it() {
+    let mut harness = harness_with(Config::default(), 200);
+    add_plugin_mark(&mut harness, line_start(150), Color::Magenta);
+
+    let rows = mark_rows(&harness, Color::Magenta);
+    assert_eq!(rows.len(), 1, "mark rows: {rows:?}");
+
+    harness.mouse_click(SCROLLBAR_COLUMN, rows[0]).unwrap();
+    harness.render().unwrap();
+    assert_eq!(harness.cursor_position(), line_start(150));
+    let top_line = harness.top_line_number();
+    assert!(
+        top_line <= 150 && top_line > 100,
+        "top line after jump: {top_line}"
+    );
+}
+
+/// A span mark covering the track leaves clicks to the track, which scrolls
+/// there instead of jumping to the start of the span
+#[test]
+fn test_click_on_span_mark_scrolls_track() {
+    let mut harness = harness_with(Config::default(), 200);
+    add_plugin_span(&mut harness, 0..line_start(199), Color::Magenta);
+    let rows = mark_rows(&harness, Color::Magenta);
+    let (_, last_row) = harness.content_area_rows();
+    assert!(rows.contains(&(last_row as u16)), "mark rows: {rows:?}");
+
+    harness
+        .mouse_click(SCROLLBAR_COLUMN, last_row as u16)
+        .unwrap();
+    harness.render().unwrap();
+    assert!(harness.top_line_number() > 100);
+}
+
+/// Unsaved edits are marked on the track near the end of the file
+#[test]
+fn test_unsaved_change_is_marked() {
+    let mut harness = harness_with(Config::default(), 200);
+    let change_color = harness.editor().theme().scrollbar_change_fg;
+    assert!(mark_rows(&harness, change_color).is_empty());
+
+    harness
+        .send_key(KeyCode::End, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("edit").unwrap();
+    harness
+        .send_key(KeyCode::Home, KeyModifiers::CONTROL)
+        .unwrap();
+    harness.render().unwrap();
+
+    let rows = mark_rows(&harness, change_color);
+    let (first_row, last_row) = harness.content_area_rows();
+    let middle = (first_row + last_row) as u16 / 2;
+    assert!(!rows.is_empty(), "no change mark");
+    assert!(rows.iter().all(|&row| row > middle), "mark rows: {rows:?}");
+}
+
+/// The primary cursor is marked on the track
+#[test]
+fn test_cursor_is_marked() {
+    let harness = harness_with(Config::default(), 200);
+    let cursor_color = harness.editor().theme().cursor;
+    let (first_row, _) = harness.content_area_rows();
+    assert_eq!(mark_rows(&harness, cursor_color), vec![first_row as u16]);
+}
+
+/// Large files place marks by byte offset rather than by line
+#[test]
+fn test_large_file_marks_use_byte_offsets() {
+    let mut config = Config::default();
+    config.editor.large_file_threshold_bytes = 100;
+    let mut harness = harness_with(config, 400);
+    let len = content(400).len();
+    add_plugin_mark(&mut harness, len / 2, Color::Magenta);
+
+    let rows = mark_rows(&harness, Color::Magenta);
+    let (first_row, last_row) = harness.content_area_rows();
+    let middle = (first_row + last_row) as u16 / 2;
+    assert_eq!(rows.len(), 1, "mark rows: {rows:?}");
+    assert!(rows[0].abs_diff(middle) <= 1, "mark row: {}", rows[0]);
+}
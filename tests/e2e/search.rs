@@ -1787,11 +1787,15 @@ fn test_search_in_large_file() {
     harness.type_text("UNIQUE_SEARCH_TARGET").unwrap();
     harness.render().unwrap();
 
-    // Confirm search - with the fix, this should load the buffer and find the match
+    // Confirm search - large files are searched in the background
     harness
         .send_key(KeyCode::Enter, KeyModifiers::NONE)
         .unwrap();
-    harness.process_async_and_render().unwrap();
+    // 45000 lines * ~70 bytes = ~3.15MB. Search target is around byte 3MB.
+    let expected_min_pos = 45000 * 60; // Conservative estimate (~2.7MB)
+    harness
+        .wait_until(|h| h.cursor_position() > expected_min_pos)
+        .unwrap();
 
     // VERIFY THE FIX: Search should succeed without "Buffer not fully loaded" error
     let screen = harness.screen_to_string();
@@ -1803,8 +1807,6 @@ fn test_search_in_large_file() {
 
     // Verify cursor moved to the match (should be near the end, around byte 3MB)
     let cursor_pos = harness.cursor_position();
-    // 45000 lines * ~70 bytes = ~3.15MB. Search target is around byte 3MB.
-    let expected_min_pos = 45000 * 60; // Conservative estimate (~2.7MB)
     assert!(
         cursor_pos > expected_min_pos,
         "Cursor should have moved to the match position (at least byte {}), but is at {}",
@@ -1,5 +1,9 @@
+use crate::common::fake_lsp::FakeLspServer;
 use crate::common::harness::EditorTestHarness;
 use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use fresh::services::lsp::LspServerConfig;
+use fresh::services::process_limits::ProcessLimits;
 use tempfile::TempDir;
 
 /// Test that selections are visually visible on screen
@@ -1595,3 +1599,166 @@ fn test_select_at_file_boundaries() {
     // Just verify we can get selected text without panicking
     // The test validates that boundary operations don't crash
 }
+
+const RUST_SOURCE: &str = "fn main() {\n    let a = x + y;\n    let b = z + w;\n}\n";
+
+/// Open RUST_SOURCE with the cursor on `x`
+fn open_rust_source(temp_dir: &TempDir) -> EditorTestHarness {
+    let file_path = temp_dir.path().join("main.rs");
+    std::fs::write(&file_path, RUST_SOURCE).unwrap();
+
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+    for _ in 0.."x + y;".len() {
+        harness.send_key(KeyCode::Left, KeyModifiers::NONE).unwrap();
+    }
+    harness
+}
+
+/// Run Shrink Selection with its default key
+fn shrink_selection(harness: &mut EditorTestHarness) {
+    harness
+        .send_key(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::SHIFT)
+        .unwrap();
+}
+
+fn selected_texts(harness: &mut EditorTestHarness) -> Vec<String> {
+    let state = harness.editor_mut().active_state_mut();
+    let mut ranges: Vec<_> = state
+        .cursors
+        .iter()
+        .filter_map(|(_, cursor)| cursor.selection_range())
+        .collect();
+    ranges.sort_by_key(|r| r.start);
+    ranges
+        .into_iter()
+        .map(|r| state.get_text_range(r.start, r.end))
+        .collect()
+}
+
+/// Expand selection walks up the syntax tree when the buffer has a grammar
+#[test]
+fn test_expand_selection_follows_syntax_tree() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = open_rust_source(&temp_dir);
+
+    let mut steps = Vec::new();
+    for _ in 0..4 {
+        harness
+            .send_key(KeyCode::Right, KeyModifiers::CONTROL | KeyModifiers::SHIFT)
+            .unwrap();
+        steps.push(harness.get_selected_text());
+    }
+    assert_eq!(
+        steps,
+        [
+            "x",
+            "x + y",
+            "let a = x + y;",
+            "{\n    let a = x + y;\n    let b = z + w;\n}",
+        ]
+    );
+}
+
+/// Shrink selection retraces the expansions back to the cursor
+#[test]
+fn test_shrink_selection_retraces_expansions() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = open_rust_source(&temp_dir);
+    let start = harness.cursor_position();
+
+    for _ in 0..3 {
+        harness
+            .send_key(KeyCode::Right, KeyModifiers::CONTROL | KeyModifiers::SHIFT)
+            .unwrap();
+    }
+    assert_eq!(harness.get_selected_text(), "let a = x + y;");
+
+    shrink_selection(&mut harness);
+    assert_eq!(harness.get_selected_text(), "x + y");
+    shrink_selection(&mut harness);
+    assert_eq!(harness.get_selected_text(), "x");
+    shrink_selection(&mut harness);
+    harness.assert_no_selection();
+    assert_eq!(harness.cursor_position(), start);
+}
+
+/// Every cursor expands through its own syntax nodes
+#[test]
+fn test_expand_selection_multi_cursor() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = open_rust_source(&temp_dir);
+    harness
+        .send_key(KeyCode::Down, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        .unwrap();
+    assert_eq!(harness.editor().active_state().cursors.count(), 2);
+
+    for _ in 0..2 {
+        harness
+            .send_key(KeyCode::Right, KeyModifiers::CONTROL | KeyModifiers::SHIFT)
+            .unwrap();
+    }
+    assert_eq!(selected_texts(&mut harness), ["x + y", "z + w"]);
+}
+
+/// A server that never answers selection ranges only holds up expansion
+/// until the request times out; the syntax tree is used then
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_expand_selection_falls_back_when_server_is_silent() {
+    let _server = FakeLspServer::spawn_with_silent_selection_range().unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("main.rs");
+    std::fs::write(&file_path, RUST_SOURCE).unwrap();
+
+    let mut config = Config::default();
+    config.lsp.insert(
+        "rust".to_string(),
+        LspServerConfig {
+            command: FakeLspServer::silent_selection_range_script_path()
+                .to_string_lossy()
+                .to_string(),
+            args: vec![],
+            enabled: true,
+            auto_start: true,
+            process_limits: ProcessLimits::default(),
+            initialization_options: None,
+        },
+    );
+    let mut harness = EditorTestHarness::with_config_and_working_dir(
+        80,
+        24,
+        config,
+        temp_dir.path().to_path_buf(),
+    )
+    .unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness
+        .wait_until(|h| h.editor().is_lsp_server_ready("rust"))
+        .unwrap();
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+    for _ in 0.."x + y;".len() {
+        harness.send_key(KeyCode::Left, KeyModifiers::NONE).unwrap();
+    }
+
+    let expand = |harness: &mut EditorTestHarness| {
+        harness
+            .send_key(KeyCode::Right, KeyModifiers::CONTROL | KeyModifiers::SHIFT)
+            .unwrap();
+    };
+    expand(&mut harness);
+    expand(&mut harness);
+    assert_eq!(harness.get_selected_text(), "");
+
+    harness.advance_time(std::time::Duration::from_secs(3));
+    expand(&mut harness);
+    assert_eq!(harness.get_selected_text(), "x");
+    expand(&mut harness);
+    assert_eq!(harness.get_selected_text(), "x + y");
+}
@@ -1982,8 +1982,9 @@ fn test_entry_dialog_add_new_textlist_item() {
     harness.assert_screen_contains("Edit Value");
 
     // Navigate to Extensions section which has "[+] Add new"
-    // Fields in order: Key, Auto Indent, Comment Prefix, Extensions (3 downs)
-    for _ in 0..3 {
+    // Fields in order: Key, Auto Indent, Bracket Pair Colorization, Comment Prefix,
+    // Extensions (4 downs)
+    for _ in 0..4 {
         harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
     }
     harness.render().unwrap();
@@ -2059,8 +2060,9 @@ fn test_entry_dialog_delete_textlist_item() {
     harness.assert_screen_contains("Edit Value");
 
     // Navigate to Extensions section which has existing items
-    // Fields in order: Key, Auto Indent, Comment Prefix, Extensions (3 downs)
-    for _ in 0..3 {
+    // Fields in order: Key, Auto Indent, Bracket Pair Colorization, Comment Prefix,
+    // Extensions (4 downs)
+    for _ in 0..4 {
         harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
     }
     harness.render().unwrap();
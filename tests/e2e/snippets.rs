@@ -0,0 +1,183 @@
+// End-to-end tests for snippet sessions and user snippet files
+
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use fresh::config_io::DirectoryContext;
+use tempfile::TempDir;
+
+/// Harness editing `file_name` with the given snippet files installed
+fn harness_with_snippets(
+    temp_dir: &TempDir,
+    file_name: &str,
+    snippet_files: &[(&str, &str)],
+) -> EditorTestHarness {
+    let dir_context = DirectoryContext::for_testing(&temp_dir.path().join("context"));
+    let snippets_dir = dir_context.snippets_dir();
+    std::fs::create_dir_all(&snippets_dir).unwrap();
+    for (name, content) in snippet_files {
+        std::fs::write(snippets_dir.join(name), content).unwrap();
+    }
+
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir_all(&project_dir).unwrap();
+    let file_path = project_dir.join(file_name);
+    std::fs::write(&file_path, "").unwrap();
+
+    let mut config = Config::default();
+    config.editor.quick_suggestions = false;
+    let mut harness =
+        EditorTestHarness::with_shared_dir_context(100, 24, config, project_dir, dir_context)
+            .unwrap();
+    harness.open_file(&file_path).unwrap();
+    harness
+}
+
+fn key(harness: &mut EditorTestHarness, code: KeyCode, modifiers: KeyModifiers) {
+    harness.send_key(code, modifiers).unwrap();
+    harness.render().unwrap();
+}
+
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    key(harness, KeyCode::Char('p'), KeyModifiers::CONTROL);
+    harness.type_text(name).unwrap();
+    key(harness, KeyCode::Enter, KeyModifiers::NONE);
+}
+
+fn selected_texts(harness: &mut EditorTestHarness) -> Vec<String> {
+    let state = harness.editor_mut().active_state_mut();
+    let mut ranges: Vec<_> = state
+        .cursors
+        .iter()
+        .filter_map(|(_, cursor)| cursor.selection_range())
+        .collect();
+    ranges.sort_by_key(|r| r.start);
+    ranges
+        .into_iter()
+        .map(|r| state.get_text_range(r.start, r.end))
+        .collect()
+}
+
+/// A snippet picked from completion is filled in tabstop by tabstop
+#[test]
+fn test_completion_snippet_tabstops() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = harness_with_snippets(
+        &temp_dir,
+        "notes.txt",
+        &[(
+            "common.code-snippets",
+            r#"{ "For": { "prefix": "for", "body": ["for ${1:i} in ${2:iter} {", "\t$0", "}"] } }"#,
+        )],
+    );
+
+    harness.type_text("fo").unwrap();
+    key(&mut harness, KeyCode::Char(' '), KeyModifiers::CONTROL);
+    harness.assert_screen_contains("Completion");
+    key(&mut harness, KeyCode::Enter, KeyModifiers::NONE);
+    harness.assert_buffer_content("for i in iter {\n\t\n}");
+    assert_eq!(selected_texts(&mut harness), ["i"]);
+
+    harness.type_text("idx").unwrap();
+    key(&mut harness, KeyCode::Tab, KeyModifiers::NONE);
+    assert_eq!(selected_texts(&mut harness), ["iter"]);
+
+    // Shift+Tab goes back to the previous tabstop
+    key(&mut harness, KeyCode::BackTab, KeyModifiers::SHIFT);
+    assert_eq!(selected_texts(&mut harness), ["idx"]);
+    key(&mut harness, KeyCode::Tab, KeyModifiers::NONE);
+    harness.type_text("items").unwrap();
+
+    // The last Tab ends the session at $0
+    key(&mut harness, KeyCode::Tab, KeyModifiers::NONE);
+    harness.assert_buffer_content("for idx in items {\n\t\n}");
+    assert_eq!(harness.cursor_position(), "for idx in items {\n\t".len());
+    assert!(harness.editor().active_state().snippet_session.is_none());
+
+    // Tab indents again (with spaces, the default for text files)
+    key(&mut harness, KeyCode::Tab, KeyModifiers::NONE);
+    harness.assert_buffer_content("for idx in items {\n\t    \n}");
+}
+
+/// Mirrored tabstops are edited together with one cursor each
+#[test]
+fn test_mirrored_tabstops() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = harness_with_snippets(
+        &temp_dir,
+        "main.rs",
+        &[(
+            "rust.json",
+            r#"{ "Let": { "prefix": "letm", "body": "let ${1:x} = $1 + $1;$0" } }"#,
+        )],
+    );
+
+    run_command(&mut harness, "Insert Snippet");
+    harness.assert_screen_contains("Insert snippet:");
+    key(&mut harness, KeyCode::Enter, KeyModifiers::NONE);
+    harness.assert_buffer_content("let x = x + x;");
+    assert_eq!(harness.cursor_count(), 3);
+
+    harness.type_text("yz").unwrap();
+    harness.assert_buffer_content("let yz = yz + yz;");
+
+    key(&mut harness, KeyCode::Tab, KeyModifiers::NONE);
+    assert_eq!(harness.cursor_count(), 1);
+    assert_eq!(harness.cursor_position(), "let yz = yz + yz;".len());
+}
+
+/// Choice tabstops open a popup of their options
+#[test]
+fn test_choice_tabstop_popup() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = harness_with_snippets(
+        &temp_dir,
+        "notes.txt",
+        &[(
+            "common.code-snippets",
+            r#"{ "Visibility": { "prefix": "vis", "body": "${1|public,private|} fn $0" } }"#,
+        )],
+    );
+
+    run_command(&mut harness, "Insert Snippet");
+    key(&mut harness, KeyCode::Enter, KeyModifiers::NONE);
+    harness.assert_buffer_content("public fn ");
+    harness.assert_screen_contains("Choice");
+    harness.assert_screen_contains("private");
+
+    key(&mut harness, KeyCode::Down, KeyModifiers::NONE);
+    key(&mut harness, KeyCode::Enter, KeyModifiers::NONE);
+    harness.assert_buffer_content("private fn ");
+
+    key(&mut harness, KeyCode::Tab, KeyModifiers::NONE);
+    assert_eq!(harness.cursor_position(), "private fn ".len());
+}
+
+/// Variables are resolved, and snippets of other languages are not offered
+#[test]
+fn test_snippet_variables_and_language_scope() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut harness = harness_with_snippets(
+        &temp_dir,
+        "notes.txt",
+        &[
+            (
+                "common.code-snippets",
+                r#"{ "Header": { "prefix": "hdr", "body": "$TM_FILENAME_BASE: $CLIPBOARD ${UNSET:none}" } }"#,
+            ),
+            (
+                "python.json",
+                r#"{ "Main": { "prefix": "main", "body": "def main():" } }"#,
+            ),
+        ],
+    );
+    harness
+        .editor_mut()
+        .set_clipboard_for_test("pasted".to_string());
+
+    run_command(&mut harness, "Insert Snippet");
+    harness.assert_screen_contains("Header");
+    harness.assert_screen_not_contains("Main");
+    key(&mut harness, KeyCode::Enter, KeyModifiers::NONE);
+    harness.assert_buffer_content("notes: pasted none");
+}
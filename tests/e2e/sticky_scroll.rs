@@ -0,0 +1,106 @@
+// End-to-end tests for sticky scroll
+
+use crate::common::harness::EditorTestHarness;
+use fresh::config::Config;
+
+/// An impl with one long method: the method body spans lines 2..=31
+fn source() -> String {
+    let mut source = String::from("impl Widget {\n    fn render(&self) {\n");
+    for i in 0..30 {
+        source.push_str(&format!("        let value_{i} = {i};\n"));
+    }
+    source.push_str("    }\n}\n");
+    source
+}
+
+fn harness_with(sticky_scroll: bool, max_lines: usize) -> EditorTestHarness {
+    let mut config = Config::default();
+    config.editor.sticky_scroll = sticky_scroll;
+    config.editor.sticky_scroll_max_lines = max_lines;
+    let mut harness = EditorTestHarness::with_temp_project_and_config(80, 24, config).unwrap();
+    let path = harness.project_dir().unwrap().join("widget.rs");
+    std::fs::write(&path, source()).unwrap();
+    harness.open_file(&path).unwrap();
+    harness.render().unwrap();
+    harness
+}
+
+/// Scroll the view (not the cursor) down by `times` wheel steps
+fn scroll_down(harness: &mut EditorTestHarness, times: usize) {
+    let (first_row, _) = harness.content_area_rows();
+    for _ in 0..times {
+        harness.mouse_scroll_down(40, first_row as u16 + 5).unwrap();
+    }
+}
+
+/// Text of the first `count` content rows
+fn top_rows(harness: &EditorTestHarness, count: usize) -> Vec<String> {
+    let (first_row, _) = harness.content_area_rows();
+    (first_row..first_row + count)
+        .map(|row| harness.get_screen_row(row))
+        .collect()
+}
+
+/// The enclosing impl and fn headers are pinned over the top rows
+#[test]
+fn test_sticky_scroll_shows_enclosing_scopes() {
+    let mut harness = harness_with(true, 5);
+    scroll_down(&mut harness, 3);
+    let top_line = harness.top_line_number();
+    assert!(top_line > 2, "top line: {top_line}");
+
+    let rows = top_rows(&harness, 3);
+    assert!(rows[0].contains("impl Widget {"), "rows: {rows:?}");
+    assert!(rows[1].contains("fn render(&self) {"), "rows: {rows:?}");
+    // The third row shows buffer line `top_line + 2`, i.e. `value_{top_line}`
+    assert!(
+        rows[2].contains(&format!("value_{top_line} ")),
+        "rows: {rows:?}"
+    );
+
+    let (first_row, _) = harness.content_area_rows();
+    let sticky_bg = harness.editor().theme().sticky_scroll_bg;
+    let bg = harness.get_cell_style(40, first_row as u16).unwrap().bg;
+    assert_eq!(bg, Some(sticky_bg));
+}
+
+/// Nothing is pinned when sticky scroll is off or the top line is not inside a scope
+#[test]
+fn test_sticky_scroll_disabled_or_at_top() {
+    let harness = harness_with(true, 5);
+    let rows = top_rows(&harness, 2);
+    assert!(rows[0].contains("impl Widget {"), "rows: {rows:?}");
+    assert!(rows[1].contains("fn render(&self) {"), "rows: {rows:?}");
+    assert!(!harness.screen_to_string().contains("value_29"));
+
+    let mut harness = harness_with(false, 5);
+    scroll_down(&mut harness, 3);
+    harness.assert_screen_not_contains("impl Widget {");
+    harness.assert_screen_not_contains("fn render(&self) {");
+}
+
+/// The number of pinned lines is capped
+#[test]
+fn test_sticky_scroll_max_lines() {
+    let mut harness = harness_with(true, 1);
+    scroll_down(&mut harness, 3);
+    let rows = top_rows(&harness, 2);
+    assert!(rows[0].contains("impl Widget {"), "rows: {rows:?}");
+    assert!(rows[1].contains("let value_"), "rows: {rows:?}");
+    harness.assert_screen_not_contains("fn render(&self) {");
+}
+
+/// Clicking a pinned header moves the cursor to that line and scrolls to it
+#[test]
+fn test_sticky_scroll_click_jumps_to_header() {
+    let mut harness = harness_with(true, 5);
+    scroll_down(&mut harness, 3);
+    let (first_row, _) = harness.content_area_rows();
+    let row = harness.get_screen_row(first_row + 1);
+    let column = row[..row.find("fn render").unwrap()].chars().count() as u16;
+
+    harness.mouse_click(column, first_row as u16 + 1).unwrap();
+    let cursor = harness.cursor_position();
+    assert_eq!(cursor, source().find("fn render").unwrap());
+    assert!(harness.top_line_number() <= 1);
+}
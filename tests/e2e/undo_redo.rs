@@ -503,3 +503,165 @@ fn test_undo_to_empty_after_save_as() {
         final_content
     );
 }
+
+/// Test that undo history survives closing the editor when persistent undo is enabled,
+/// and is discarded once the file changes outside the editor
+#[test]
+fn test_persistent_undo_across_sessions() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir_all(&project_dir).unwrap();
+    let file_path = project_dir.join("notes.txt");
+    std::fs::write(&file_path, "start").unwrap();
+
+    let context_temp = TempDir::new().unwrap();
+    let dir_context = fresh::config_io::DirectoryContext::for_testing(context_temp.path());
+
+    let mut config = fresh::config::Config::default();
+    config.editor.persistent_undo = true;
+
+    // Session 1: edit and save, then quit
+    {
+        let mut harness = EditorTestHarness::with_shared_dir_context(
+            80,
+            24,
+            config.clone(),
+            project_dir.clone(),
+            dir_context.clone(),
+        )
+        .unwrap();
+        harness.open_file(&file_path).unwrap();
+        harness.send_key(KeyCode::End, KeyModifiers::NONE).unwrap();
+        harness.type_text(" more").unwrap();
+        harness
+            .send_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.editor_mut().persist_all_undo_histories();
+    }
+    assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "start more");
+
+    // Session 2: undo reaches back into the previous session
+    {
+        let mut harness = EditorTestHarness::with_shared_dir_context(
+            80,
+            24,
+            config.clone(),
+            project_dir.clone(),
+            dir_context.clone(),
+        )
+        .unwrap();
+        harness.open_file(&file_path).unwrap();
+        harness.assert_buffer_content("start more");
+
+        harness
+            .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.assert_buffer_content("start mor");
+        assert!(harness.editor().active_state().buffer.is_modified());
+
+        harness
+            .send_key(KeyCode::Char('y'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.assert_buffer_content("start more");
+        assert!(!harness.editor().active_state().buffer.is_modified());
+    }
+
+    // The file changes outside the editor: the stored history no longer applies
+    std::fs::write(&file_path, "rewritten").unwrap();
+    {
+        let mut harness =
+            EditorTestHarness::with_shared_dir_context(80, 24, config, project_dir, dir_context)
+                .unwrap();
+        harness.open_file(&file_path).unwrap();
+        harness
+            .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+            .unwrap();
+        harness.assert_buffer_content("rewritten");
+    }
+}
+
+/// Run a command from the command palette
+fn run_command(harness: &mut EditorTestHarness, name: &str) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text(name).unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// An edit after undo starts a new branch; chronological undo/redo can still reach
+/// the undone branch
+#[test]
+fn test_chronological_undo_reaches_undone_branch() {
+    let mut harness = EditorTestHarness::new(80, 24).unwrap();
+
+    harness.type_text("ab").unwrap();
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("c").unwrap();
+    harness.assert_buffer_content("ac");
+
+    // Regular redo has nothing left to redo on the new branch
+    harness
+        .send_key(KeyCode::Char('y'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.assert_buffer_content("ac");
+
+    // Going back in time visits the undone "b" first
+    run_command(&mut harness, "Undo Chronologically");
+    harness.assert_buffer_content("ab");
+    run_command(&mut harness, "Undo Chronologically");
+    harness.assert_buffer_content("a");
+
+    run_command(&mut harness, "Redo Chronologically");
+    harness.assert_buffer_content("ab");
+    run_command(&mut harness, "Redo Chronologically");
+    harness.assert_buffer_content("ac");
+}
+
+/// The undo tree panel shows every branch, previews the selected state as a
+/// diff and restores it on Enter
+#[test]
+fn test_undo_tree_panel_restores_branch() {
+    let mut harness = EditorTestHarness::new(120, 30).unwrap();
+
+    harness.type_text("ab").unwrap();
+    harness
+        .send_key(KeyCode::Char('z'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("c").unwrap();
+    harness.assert_buffer_content("ac");
+
+    run_command(&mut harness, "Show Undo Tree");
+    harness.assert_screen_contains("Undo tree of");
+    harness.assert_screen_contains("insert \"c\"");
+    harness.assert_screen_contains("insert \"b\"");
+    harness.assert_screen_contains("initial state");
+    harness.assert_screen_contains("[current]");
+    harness.assert_screen_contains("├─┘");
+    harness.assert_screen_contains("Same content as the current state");
+
+    // The newest state is selected; the next row is the undone branch
+    harness.send_key(KeyCode::Down, KeyModifiers::NONE).unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("-ac");
+    harness.assert_screen_contains("+ab");
+
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_screen_contains("Restored undo state");
+
+    harness
+        .send_key(KeyCode::Char('q'), KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+    harness.assert_buffer_content("ab");
+}
@@ -0,0 +1,138 @@
+// End-to-end tests for the "Go to Symbol in Workspace" picker
+
+use crate::common::fake_lsp::FakeLspServer;
+use crate::common::harness::EditorTestHarness;
+use crossterm::event::{KeyCode, KeyModifiers};
+use fresh::config::Config;
+use fresh::services::lsp::LspServerConfig;
+use fresh::services::process_limits::ProcessLimits;
+use std::path::Path;
+
+const PARSER: &str = "// Expression parser\npub fn parse_expr() {}\n";
+const SCRIPT: &str = "import sys\ndef parse_args():\n    pass\n";
+
+/// A fake server reporting one symbol of `path`
+fn server_config(name: &str, kind: u32, container: &str, path: &Path) -> LspServerConfig {
+    LspServerConfig {
+        command: FakeLspServer::workspace_symbols_script_path()
+            .to_string_lossy()
+            .to_string(),
+        args: vec![
+            name.to_string(),
+            kind.to_string(),
+            container.to_string(),
+            path.to_string_lossy().to_string(),
+        ],
+        enabled: true,
+        auto_start: true,
+        process_limits: ProcessLimits::default(),
+        initialization_options: None,
+    }
+}
+
+/// A project with a Rust and a Python file, each with its own running server
+fn harness_with_servers(temp_dir: &Path) -> EditorTestHarness {
+    std::fs::create_dir_all(temp_dir.join("src")).unwrap();
+    std::fs::create_dir_all(temp_dir.join("tools")).unwrap();
+    let parser = temp_dir.join("src/parser.rs");
+    let script = temp_dir.join("tools/cli.py");
+    std::fs::write(&parser, PARSER).unwrap();
+    std::fs::write(&script, SCRIPT).unwrap();
+
+    let mut config = Config::default();
+    config.lsp.insert(
+        "rust".to_string(),
+        server_config("parse_expr", 12, "parser", &parser),
+    );
+    config.lsp.insert(
+        "python".to_string(),
+        server_config("parse_args", 12, "cli", &script),
+    );
+    let mut harness =
+        EditorTestHarness::with_config_and_working_dir(120, 30, config, temp_dir.to_path_buf())
+            .unwrap();
+    harness.open_file(&script).unwrap();
+    harness.open_file(&parser).unwrap();
+    harness
+        .wait_until(|h| {
+            h.editor().is_lsp_server_ready("rust") && h.editor().is_lsp_server_ready("python")
+        })
+        .unwrap();
+    harness
+}
+
+fn open_picker(harness: &mut EditorTestHarness) {
+    harness
+        .send_key(KeyCode::Char('p'), KeyModifiers::CONTROL)
+        .unwrap();
+    harness.type_text("Go to Symbol in Workspace").unwrap();
+    harness
+        .send_key(KeyCode::Enter, KeyModifiers::NONE)
+        .unwrap();
+    harness.render().unwrap();
+}
+
+/// The query is sent to every server once typing pauses, and their results
+/// are merged and ranked with the kind icon, container and path
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_workspace_symbols_merge_servers() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_workspace_symbols()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_servers(temp_dir.path());
+
+    open_picker(&mut harness);
+    harness.assert_screen_contains("Workspace symbol:");
+
+    harness.type_text("parse")?;
+    harness.wait_until(|h| {
+        let screen = h.screen_to_string();
+        screen.contains("λ parse_expr") && screen.contains("λ parse_args")
+    })?;
+    harness.assert_screen_contains("parser  src/parser.rs:2");
+    harness.assert_screen_contains("cli  tools/cli.py:2");
+
+    // Narrowing the query filters the results right away
+    harness.type_text("_e")?;
+    harness.render()?;
+    harness.assert_screen_contains("λ parse_expr");
+    harness.assert_screen_not_contains("λ parse_args");
+    Ok(())
+}
+
+/// Confirming a symbol opens its file at the symbol
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "FakeLspServer uses a Bash script which is not available on Windows"
+)]
+fn test_workspace_symbols_open_location() -> anyhow::Result<()> {
+    let _server = FakeLspServer::spawn_with_workspace_symbols()?;
+    let temp_dir = tempfile::tempdir()?;
+    let mut harness = harness_with_servers(temp_dir.path());
+
+    open_picker(&mut harness);
+    harness.type_text("args")?;
+    harness.wait_until(|h| h.screen_to_string().contains("λ parse_args"))?;
+    harness.send_key(KeyCode::Enter, KeyModifiers::NONE)?;
+    harness.render()?;
+
+    assert_eq!(harness.get_buffer_content().unwrap(), SCRIPT);
+    assert_eq!(
+        harness.cursor_position(),
+        SCRIPT.find("def parse_args").unwrap() + 4
+    );
+    Ok(())
+}
+
+/// Without a server that searches workspace symbols the picker does not open
+#[test]
+fn test_workspace_symbols_without_server() {
+    let mut harness = EditorTestHarness::new(160, 24).unwrap();
+    open_picker(&mut harness);
+    harness.assert_screen_not_contains("Workspace symbol:");
+    harness.assert_screen_contains("No running language server can search workspace symbols");
+}